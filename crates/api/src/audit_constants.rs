@@ -128,6 +128,13 @@ pub mod auth_event {
 
     /// User account unlocked by administrator
     pub const ACCOUNT_UNLOCKED: &str = "account_unlocked";
+
+    // API Key Events
+    /// User created an API key for their organization
+    pub const API_KEY_CREATED: &str = "api_key_created";
+
+    /// User rotated an API key (old secret stays valid for the grace window)
+    pub const API_KEY_ROTATED: &str = "api_key_rotated";
 }
 
 /// Admin action types
@@ -2,7 +2,9 @@
 
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use sqlx::PgPool;
 use subtle::ConstantTimeEq;
+use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
 type HmacSha256 = Hmac<Sha256>;
@@ -10,10 +12,24 @@ type HmacSha256 = Hmac<Sha256>;
 const API_KEY_PREFIX: &str = "pmcp_";
 const API_KEY_VERSION: &str = "01";
 
+/// How long a rotated-out key keeps working by default
+const DEFAULT_ROTATION_GRACE: Duration = Duration::hours(24);
+
 /// API Key manager for generation and validation
 #[derive(Clone)]
 pub struct ApiKeyManager {
     hmac_secret: Vec<u8>,
+    rotation_grace: Duration,
+}
+
+/// Result of rotating an API key
+#[derive(Debug)]
+pub struct RotatedApiKey {
+    /// New full key, shown to the user once
+    pub full_key: String,
+    pub key_prefix: String,
+    /// Until when the previous secret is still accepted (`None` if revoked immediately)
+    pub old_key_valid_until: Option<OffsetDateTime>,
 }
 
 impl ApiKeyManager {
@@ -21,9 +37,63 @@ impl ApiKeyManager {
     pub fn new(secret: &str) -> Self {
         Self {
             hmac_secret: secret.as_bytes().to_vec(),
+            rotation_grace: DEFAULT_ROTATION_GRACE,
         }
     }
 
+    /// Set how long the old secret stays valid after a rotation (zero revokes it at once)
+    pub fn with_rotation_grace(mut self, grace: Duration) -> Self {
+        self.rotation_grace = grace;
+        self
+    }
+
+    /// Issue a new secret for an existing key
+    ///
+    /// The previous secret keeps authenticating until the grace window ends,
+    /// so integrations can swap keys without downtime.
+    /// Returns (new_full_key, old_key_valid_until) via [`RotatedApiKey`].
+    pub async fn rotate(&self, pool: &PgPool, key_id: Uuid) -> Result<RotatedApiKey, ApiKeyError> {
+        let (full_key, key_hash, key_prefix) = self.generate_key()?;
+        let old_key_valid_until = self.grace_ends_at(OffsetDateTime::now_utc());
+
+        // Only the current hash moves into the grace slot; a secret that was
+        // already in it from an earlier rotation is dropped.
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET key_hash = $2,
+                key_prefix = $3,
+                previous_key_hash = CASE WHEN $4::timestamptz IS NULL THEN NULL ELSE key_hash END,
+                previous_key_expires_at = $4,
+                request_count = 0,
+                encrypted_key = NULL,
+                key_nonce = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(key_id)
+        .bind(&key_hash)
+        .bind(&key_prefix)
+        .bind(old_key_valid_until)
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ApiKeyError::NotFound);
+        }
+
+        Ok(RotatedApiKey {
+            full_key,
+            key_prefix,
+            old_key_valid_until,
+        })
+    }
+
+    /// When a secret rotated out at `now` stops working
+    fn grace_ends_at(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        (self.rotation_grace > Duration::ZERO).then(|| now + self.rotation_grace)
+    }
+
     /// Generate a new API key
     /// Returns (full_key, key_hash, key_prefix)
     pub fn generate_key(&self) -> Result<(String, String, String), ApiKeyError> {
@@ -118,6 +188,10 @@ fn constant_time_compare(a: &str, b: &str) -> bool {
 pub enum ApiKeyError {
     #[error("HMAC initialization failed")]
     HmacInitFailed,
+    #[error("API key not found")]
+    NotFound,
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
 }
 
 #[cfg(test)]
@@ -160,4 +234,18 @@ mod tests {
             .validate_key(&modified_key)
             .expect("Validation failed"));
     }
+
+    #[test]
+    fn test_rotation_grace_window() {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        let manager = ApiKeyManager::new("test-secret-key-32-chars-minimum!");
+        assert_eq!(manager.grace_ends_at(now), Some(now + Duration::hours(24)));
+
+        let manager = manager.with_rotation_grace(Duration::hours(2));
+        assert_eq!(manager.grace_ends_at(now), Some(now + Duration::hours(2)));
+
+        let manager = manager.with_rotation_grace(Duration::ZERO);
+        assert_eq!(manager.grace_ends_at(now), None);
+    }
 }
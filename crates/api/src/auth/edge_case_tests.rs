@@ -332,7 +332,7 @@ mod jwt_tests {
         let org_id = Uuid::new_v4();
 
         let (refresh_token, _) = jwt
-            .generate_refresh_token(user_id, org_id, "owner", "test@example.com")
+            .generate_refresh_token(user_id, org_id, "owner", "test@example.com", Uuid::new_v4())
             .expect("Should generate token");
 
         // Using refresh token as access should fail
@@ -386,7 +386,7 @@ mod jwt_tests {
         let org_id = Uuid::new_v4();
 
         let (_, _, refresh_token, _) = jwt
-            .generate_token_pair(user_id, org_id, "owner", "test@example.com")
+            .generate_token_pair(user_id, org_id, "owner", "test@example.com", Uuid::new_v4())
             .expect("Should generate tokens");
 
         // Validate refresh token is valid (meaning exp is at least 30 days out)
@@ -414,7 +414,7 @@ mod jwt_tests {
         let org_id = Uuid::new_v4();
 
         let (_, access_jti, _, refresh_jti) = jwt
-            .generate_token_pair(user_id, org_id, "owner", "test@example.com")
+            .generate_token_pair(user_id, org_id, "owner", "test@example.com", Uuid::new_v4())
             .expect("Should generate tokens");
 
         assert_ne!(
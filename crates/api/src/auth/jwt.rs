@@ -24,6 +24,10 @@ pub struct Claims {
     pub token_type: TokenType,
     /// JWT ID (jti) for session tracking and revocation
     pub jti: String,
+    /// Refresh token family; every token rotated from the same login shares it
+    /// (absent on access tokens and on refresh tokens issued before rotation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_id: Option<Uuid>,
 }
 
 /// JWT claims structure for Supabase-issued tokens
@@ -112,6 +116,7 @@ impl JwtManager {
             exp: exp.unix_timestamp(),
             token_type: TokenType::Access,
             jti: jti.clone(),
+            family_id: None,
         };
 
         // SOC 2 CC6.1: Explicit algorithm prevents algorithm confusion attacks
@@ -122,12 +127,15 @@ impl JwtManager {
     }
 
     /// Generate a refresh token with unique JTI for session tracking
+    ///
+    /// Pass a new `family_id` on login and the presented token's family on refresh.
     pub fn generate_refresh_token(
         &self,
         user_id: Uuid,
         org_id: Uuid,
         role: &str,
         email: &str,
+        family_id: Uuid,
     ) -> Result<(String, String), JwtError> {
         let now = OffsetDateTime::now_utc();
         let exp = now + Duration::days(self.refresh_token_expiry_days);
@@ -142,6 +150,7 @@ impl JwtManager {
             exp: exp.unix_timestamp(),
             token_type: TokenType::Refresh,
             jti: jti.clone(),
+            family_id: Some(family_id),
         };
 
         // SOC 2 CC6.1: Explicit algorithm prevents algorithm confusion attacks
@@ -159,11 +168,12 @@ impl JwtManager {
         org_id: Uuid,
         role: &str,
         email: &str,
+        family_id: Uuid,
     ) -> Result<(String, String, String, String), JwtError> {
         let (access_token, access_jti) =
             self.generate_access_token(user_id, org_id, role, email)?;
         let (refresh_token, refresh_jti) =
-            self.generate_refresh_token(user_id, org_id, role, email)?;
+            self.generate_refresh_token(user_id, org_id, role, email, family_id)?;
         Ok((access_token, access_jti, refresh_token, refresh_jti))
     }
 
@@ -267,9 +277,10 @@ mod tests {
         let jwt = JwtManager::new("test-secret-key-at-least-32-chars!", 24);
         let user_id = Uuid::new_v4();
         let org_id = Uuid::new_v4();
+        let family_id = Uuid::new_v4();
 
         let (access_token, access_jti, refresh_token, refresh_jti) = jwt
-            .generate_token_pair(user_id, org_id, "owner", "test@example.com")
+            .generate_token_pair(user_id, org_id, "owner", "test@example.com", family_id)
             .expect("Failed to generate tokens");
 
         // Validate access token
@@ -281,6 +292,7 @@ mod tests {
         assert_eq!(access_claims.token_type, TokenType::Access);
         assert_eq!(access_claims.jti, access_jti);
         assert!(!access_claims.jti.is_empty());
+        assert_eq!(access_claims.family_id, None);
 
         // Validate refresh token
         let refresh_claims = jwt
@@ -290,6 +302,7 @@ mod tests {
         assert_eq!(refresh_claims.token_type, TokenType::Refresh);
         assert_eq!(refresh_claims.jti, refresh_jti);
         assert!(!refresh_claims.jti.is_empty());
+        assert_eq!(refresh_claims.family_id, Some(family_id));
 
         // Ensure JTIs are different
         assert_ne!(access_jti, refresh_jti);
@@ -309,4 +322,34 @@ mod tests {
         let result = jwt.validate_refresh_token(&access_token);
         assert!(matches!(result, Err(JwtError::WrongTokenType)));
     }
+
+    #[test]
+    fn test_refresh_token_without_family_still_validates() {
+        let secret = "test-secret-key-at-least-32-chars!";
+        let jwt = JwtManager::new(secret, 24);
+        let now = OffsetDateTime::now_utc();
+
+        // Refresh tokens issued before rotation carry no family_id
+        let legacy = serde_json::json!({
+            "sub": Uuid::new_v4(),
+            "org_id": Uuid::new_v4(),
+            "role": "owner",
+            "email": "test@example.com",
+            "iat": now.unix_timestamp(),
+            "exp": (now + Duration::days(1)).unix_timestamp(),
+            "token_type": "refresh",
+            "jti": Uuid::new_v4().to_string(),
+        });
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &legacy,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .expect("Failed to encode token");
+
+        let claims = jwt
+            .validate_refresh_token(&token)
+            .expect("Legacy refresh token rejected");
+        assert_eq!(claims.family_id, None);
+    }
 }
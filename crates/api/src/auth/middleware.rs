@@ -4,7 +4,7 @@ use axum::{
     extract::{Request, State},
     http::{
         header::{AUTHORIZATION, COOKIE},
-        Method, StatusCode,
+        HeaderMap, Method, StatusCode,
     },
     middleware::Next,
     response::{IntoResponse, Response},
@@ -52,7 +52,13 @@ pub(crate) struct CachedSupabaseAuth {
 /// Thread-safe token cache type (crate-internal, not part of public API)
 pub(crate) type TokenCache = Arc<RwLock<HashMap<String, CachedSupabaseAuth>>>;
 
-use super::{api_key::ApiKeyManager, jwt::JwtManager, password, sessions};
+use super::{
+    api_key::ApiKeyManager,
+    jwt::JwtManager,
+    password,
+    scopes::{self, Scope},
+    sessions,
+};
 
 /// Database row type for API key lookup
 #[derive(Debug, FromRow)]
@@ -62,6 +68,7 @@ struct ApiKeyRow {
     org_id: Uuid,
     scopes: Option<serde_json::Value>,
     subscription_tier: String,
+    is_expired: bool,
 }
 
 /// Database row type for organization membership lookup
@@ -95,7 +102,11 @@ impl AuthUser {
 pub enum AuthMethod {
     Jwt,
     SupabaseJwt,
-    ApiKey { key_id: Uuid },
+    /// `scopes` is None for keys with full access
+    ApiKey {
+        key_id: Uuid,
+        scopes: Option<Vec<Scope>>,
+    },
 }
 
 /// Response from Supabase /auth/v1/user endpoint
@@ -124,9 +135,8 @@ pub struct AuthState {
 /// SOC 2 CC6.1: Extract bearer token from HttpOnly cookies
 /// This enables secure cookie-based authentication that protects against XSS attacks.
 /// The cookie name matches what the frontend set-cookie API route uses.
-fn extract_token_from_cookie(request: &Request) -> Option<String> {
-    request
-        .headers()
+fn extract_token_from_cookie(headers: &HeaderMap) -> Option<String> {
+    headers
         .get(COOKIE)
         .and_then(|h| h.to_str().ok())
         .and_then(|cookies| {
@@ -143,20 +153,16 @@ fn extract_token_from_cookie(request: &Request) -> Option<String> {
 
 /// Extract bearer token from Authorization header or HttpOnly cookie
 /// Prefers Authorization header but falls back to cookie for SPA clients using HttpOnly cookies
-fn extract_bearer_token(request: &Request) -> Option<String> {
+pub(crate) fn extract_bearer_token(headers: &HeaderMap) -> Option<String> {
     // Try Authorization header first
-    if let Some(header) = request
-        .headers()
-        .get(AUTHORIZATION)
-        .and_then(|h| h.to_str().ok())
-    {
+    if let Some(header) = headers.get(AUTHORIZATION).and_then(|h| h.to_str().ok()) {
         if let Some(token) = header.strip_prefix("Bearer ") {
             return Some(token.to_string());
         }
     }
 
     // Fall back to HttpOnly cookie
-    extract_token_from_cookie(request)
+    extract_token_from_cookie(headers)
 }
 
 /// Extract API key from Authorization header (no cookie fallback for API keys)
@@ -170,6 +176,7 @@ fn extract_api_key(request: &Request) -> Option<String> {
 }
 
 /// Extract IP address from request headers (X-Forwarded-For, X-Real-IP, or CF-Connecting-IP)
+/// After `client_ip_middleware` only X-Real-IP is present, holding the resolved client IP
 fn extract_ip_address(request: &Request) -> Option<String> {
     // Try X-Forwarded-For first (may contain multiple IPs, take first)
     if let Some(xff) = request.headers().get("X-Forwarded-For") {
@@ -192,6 +199,21 @@ fn extract_ip_address(request: &Request) -> Option<String> {
     None
 }
 
+/// Reject scoped API keys on routes their scopes don't cover
+fn check_api_key_scope(auth_user: &AuthUser, request: &Request) -> Result<(), AuthError> {
+    if let AuthMethod::ApiKey {
+        scopes: Some(scopes),
+        ..
+    } = &auth_user.auth_method
+    {
+        let required = scopes::required_scope(request.method(), request.uri().path());
+        if !scopes::grants(scopes, required) {
+            return Err(AuthError::MissingScope(required));
+        }
+    }
+    Ok(())
+}
+
 /// Middleware that requires authentication
 pub async fn require_auth(
     State(auth_state): State<AuthState>,
@@ -209,11 +231,11 @@ pub async fn require_auth(
         .map(String::from);
 
     // SOC 2 CC6.1: Try Bearer token from header or HttpOnly cookie
-    let has_token = extract_bearer_token(&request).is_some();
+    let has_token = extract_bearer_token(request.headers()).is_some();
     let has_api_key = extract_api_key(&request).is_some();
     tracing::info!(path = %path, has_token = %has_token, has_api_key = %has_api_key, "require_auth: checking authentication");
 
-    let auth_result = if let Some(token) = extract_bearer_token(&request) {
+    let auth_result = if let Some(token) = extract_bearer_token(request.headers()) {
         authenticate_jwt(&auth_state, &token).await
     } else if let Some(key) = extract_api_key(&request) {
         authenticate_api_key(&auth_state, &key, ip_address, user_agent).await
@@ -232,6 +254,10 @@ pub async fn require_auth(
                 auth_method = ?auth_user.auth_method,
                 "require_auth: authentication successful"
             );
+            if let Err(err) = check_api_key_scope(&auth_user, &request) {
+                tracing::warn!(path = %path, error = %err, "require_auth: API key scope denied");
+                return err.into_response();
+            }
             request.extensions_mut().insert(auth_user);
             next.run(request).await
         }
@@ -257,7 +283,7 @@ pub async fn optional_auth(
         .map(String::from);
 
     // SOC 2 CC6.1: Try Bearer token from header or HttpOnly cookie
-    if let Some(token) = extract_bearer_token(&request) {
+    if let Some(token) = extract_bearer_token(request.headers()) {
         if let Ok(auth_user) = authenticate_jwt(&auth_state, &token).await {
             request.extensions_mut().insert(auth_user);
             return next.run(request).await;
@@ -291,7 +317,7 @@ pub async fn require_role(
         .map(String::from);
 
     // SOC 2 CC6.1: Try Bearer token from header or HttpOnly cookie
-    let auth_result = if let Some(token) = extract_bearer_token(&request) {
+    let auth_result = if let Some(token) = extract_bearer_token(request.headers()) {
         authenticate_jwt(&auth_state, &token).await
     } else if let Some(key) = extract_api_key(&request) {
         authenticate_api_key(&auth_state, &key, ip_address, user_agent).await
@@ -305,6 +331,9 @@ pub async fn require_role(
             if !required_roles.contains(&auth_user.role.as_str()) {
                 return AuthError::InsufficientPermissions.into_response();
             }
+            if let Err(err) = check_api_key_scope(&auth_user, &request) {
+                return err.into_response();
+            }
 
             let mut request = request;
             request.extensions_mut().insert(auth_user);
@@ -344,6 +373,23 @@ async fn authenticate_jwt(auth_state: &AuthState, token: &str) -> Result<AuthUse
                 .ok()
                 .flatten();
 
+        // Record activity for the active sessions list (fire and forget, at most once a minute)
+        if let Some(session_id) = session_id {
+            let pool = auth_state.pool.clone();
+            tokio::spawn(async move {
+                let _ = sqlx::query(
+                    r#"
+                    UPDATE user_sessions
+                    SET last_used_at = NOW()
+                    WHERE id = $1 AND last_used_at < NOW() - INTERVAL '1 minute'
+                    "#,
+                )
+                .bind(session_id)
+                .execute(&pool)
+                .await;
+            });
+        }
+
         // Verify the user still exists in the database (handles stale tokens after user ID changes)
         let user_exists: Option<(bool,)> = sqlx::query_as("SELECT TRUE FROM users WHERE id = $1")
             .bind(claims.sub)
@@ -576,6 +622,31 @@ async fn verify_supabase_token_via_api(
     result
 }
 
+/// Drop cached Supabase verifications for a user after their sessions are revoked
+///
+/// Otherwise a cached token keeps working for up to `TOKEN_CACHE_TTL`; once
+/// evicted, the next request is re-verified. PlexMCP-issued tokens need no
+/// eviction since their session is checked on every request. Pass the
+/// caller's `keep_token` when logging out only the *other* devices.
+pub(crate) async fn evict_cached_user(
+    token_cache: &TokenCache,
+    user_id: Uuid,
+    email: Option<&str>,
+    keep_token: Option<&str>,
+) {
+    let user_id = user_id.to_string();
+    token_cache.write().await.retain(|token, cached| {
+        if keep_token == Some(token.as_str()) {
+            return true;
+        }
+        let same_email = match (email, cached.user.email.as_deref()) {
+            (Some(email), Some(cached_email)) => email.eq_ignore_ascii_case(cached_email),
+            _ => false,
+        };
+        cached.user.id != user_id && !same_email
+    });
+}
+
 /// Actually make the API call to Supabase to verify a token
 async fn verify_supabase_token_api_call(
     auth_state: &AuthState,
@@ -856,11 +927,12 @@ async fn authenticate_api_key(
 
     let api_key: ApiKeyRow = match sqlx::query_as(
         r#"
-        SELECT ak.id, ak.org_id, ak.scopes, o.subscription_tier
+        SELECT ak.id, ak.org_id, ak.scopes, o.subscription_tier,
+               COALESCE(ak.expires_at <= NOW(), false) AS is_expired
         FROM api_keys ak
         JOIN organizations o ON o.id = ak.org_id
         WHERE ak.key_hash = $1
-          AND (ak.expires_at IS NULL OR ak.expires_at > NOW())
+           OR (ak.previous_key_hash = $1 AND ak.previous_key_expires_at > NOW())
         "#,
     )
     .bind(&key_hash)
@@ -869,11 +941,11 @@ async fn authenticate_api_key(
     {
         Ok(Some(key)) => key,
         Ok(None) => {
-            // Log key not found or expired (fire and forget)
+            // Log key not found (fire and forget)
             log_api_key_failure(
                 auth_state.pool.clone(),
                 key_prefix,
-                "key_not_found_or_expired".to_string(),
+                "key_not_found".to_string(),
                 ip_address,
                 user_agent,
             );
@@ -882,6 +954,23 @@ async fn authenticate_api_key(
         Err(_) => return Err(AuthError::DatabaseError),
     };
 
+    if api_key.is_expired {
+        log_api_key_failure(
+            auth_state.pool.clone(),
+            key_prefix,
+            "key_expired".to_string(),
+            ip_address,
+            user_agent,
+        );
+        return Err(AuthError::ApiKeyExpired);
+    }
+
+    let stored_scopes: Vec<String> = api_key
+        .scopes
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let scopes = scopes::effective_scopes(&stored_scopes);
+
     // Update last used timestamp (fire and forget)
     let pool = auth_state.pool.clone();
     let key_id = api_key.id;
@@ -899,7 +988,10 @@ async fn authenticate_api_key(
         org_id: Some(api_key.org_id),
         role: "api_key".to_string(),
         email: None,
-        auth_method: AuthMethod::ApiKey { key_id: api_key.id },
+        auth_method: AuthMethod::ApiKey {
+            key_id: api_key.id,
+            scopes,
+        },
         session_id: None, // API keys don't have sessions
     })
 }
@@ -950,8 +1042,12 @@ pub enum AuthError {
     InvalidToken,
     #[error("Invalid API key")]
     InvalidApiKey,
+    #[error("API key has expired")]
+    ApiKeyExpired,
     #[error("Insufficient permissions")]
     InsufficientPermissions,
+    #[error("API key lacks the '{0}' scope")]
+    MissingScope(Scope),
     #[error("No organization found")]
     NoOrganization,
     #[error("Database error")]
@@ -969,9 +1065,18 @@ impl IntoResponse for AuthError {
             }
             AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid or expired token"),
             AuthError::InvalidApiKey => (StatusCode::UNAUTHORIZED, "Invalid API key"),
+            AuthError::ApiKeyExpired => (StatusCode::UNAUTHORIZED, "API key has expired"),
             AuthError::InsufficientPermissions => {
                 (StatusCode::FORBIDDEN, "Insufficient permissions")
             }
+            AuthError::MissingScope(scope) => {
+                let body = Json(json!({
+                    "error": "API key lacks the required scope",
+                    "required_scope": scope,
+                    "code": StatusCode::FORBIDDEN.as_u16()
+                }));
+                return (StatusCode::FORBIDDEN, body).into_response();
+            }
             AuthError::NoOrganization => (
                 StatusCode::BAD_REQUEST,
                 "No organization found. Please create an organization first.",
@@ -1128,6 +1233,10 @@ pub async fn require_auth_with_billing(
         }
     }
 
+    if let Err(err) = check_api_key_scope(&auth_user, &request) {
+        return err.into_response();
+    }
+
     // Authentication and billing check passed, proceed
     request.extensions_mut().insert(auth_user);
     next.run(request).await
@@ -1303,7 +1412,54 @@ pub async fn require_full_access(
         }
     }
 
+    if let Err(err) = check_api_key_scope(&auth_user, &request) {
+        return err.into_response();
+    }
+
     // All checks passed, proceed
     request.extensions_mut().insert(auth_user);
     next.run(request).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(user_id: &str, email: &str) -> CachedSupabaseAuth {
+        CachedSupabaseAuth {
+            user: SupabaseUserResponse {
+                id: user_id.to_string(),
+                email: Some(email.to_string()),
+            },
+            cached_at: Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evict_cached_user_keeps_only_callers_token() {
+        let user_id = Uuid::new_v4();
+        let other_user = Uuid::new_v4().to_string();
+        let token_cache: TokenCache = Arc::new(RwLock::new(HashMap::from([
+            (
+                "laptop".to_string(),
+                cached(&user_id.to_string(), "a@example.com"),
+            ),
+            (
+                "phone".to_string(),
+                cached(&user_id.to_string(), "a@example.com"),
+            ),
+            ("tablet".to_string(), cached("supabase-id", "A@Example.com")),
+            ("other".to_string(), cached(&other_user, "b@example.com")),
+        ])));
+
+        evict_cached_user(&token_cache, user_id, Some("a@example.com"), Some("laptop")).await;
+
+        let mut remaining: Vec<_> = token_cache.read().await.keys().cloned().collect();
+        remaining.sort();
+        assert_eq!(remaining, ["laptop", "other"]);
+
+        evict_cached_user(&token_cache, user_id, Some("a@example.com"), None).await;
+        let remaining: Vec<_> = token_cache.read().await.keys().cloned().collect();
+        assert_eq!(remaining, ["other"]);
+    }
+}
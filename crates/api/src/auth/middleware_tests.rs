@@ -13,6 +13,7 @@ mod tests {
     use super::super::api_key::ApiKeyManager;
     use super::super::jwt::JwtManager;
     use super::super::middleware::*;
+    use super::super::scopes::Scope;
     use sqlx::PgPool;
     use std::sync::Arc;
     use uuid::Uuid;
@@ -209,13 +210,25 @@ mod tests {
 
         assert_eq!(AuthMethod::Jwt, AuthMethod::Jwt);
         assert_eq!(AuthMethod::SupabaseJwt, AuthMethod::SupabaseJwt);
-        assert_eq!(AuthMethod::ApiKey { key_id }, AuthMethod::ApiKey { key_id });
+        let api_key = AuthMethod::ApiKey {
+            key_id,
+            scopes: None,
+        };
+        assert_eq!(api_key, api_key.clone());
 
         assert_ne!(AuthMethod::Jwt, AuthMethod::SupabaseJwt);
         assert_ne!(
-            AuthMethod::ApiKey { key_id },
+            api_key,
+            AuthMethod::ApiKey {
+                key_id: Uuid::new_v4(),
+                scopes: None,
+            }
+        );
+        assert_ne!(
+            api_key,
             AuthMethod::ApiKey {
-                key_id: Uuid::new_v4()
+                key_id,
+                scopes: Some(vec![Scope::ReadBilling]),
             }
         );
     }
@@ -4,6 +4,31 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
+use sha1::{Digest, Sha1};
+
+/// Rules a new password has to meet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_special: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 12,
+            max_length: 128,
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_special: true,
+        }
+    }
+}
 
 /// Hash a password using Argon2id
 pub fn hash_password(password: &str) -> Result<String, PasswordError> {
@@ -44,15 +69,22 @@ pub fn verify_password(password: &str, hash: &str) -> Result<bool, PasswordError
         .is_ok())
 }
 
-/// Validate password strength
-pub fn validate_password_strength(password: &str) -> Result<(), PasswordValidationError> {
+/// Validate password strength against a policy
+pub fn validate_password_strength(
+    password: &str,
+    policy: &PasswordPolicy,
+) -> Result<(), PasswordValidationError> {
     // Length validation
-    if password.len() < 12 {
-        return Err(PasswordValidationError::TooShort);
+    if password.len() < policy.min_length {
+        return Err(PasswordValidationError::TooShort {
+            min_length: policy.min_length,
+        });
     }
 
-    if password.len() > 128 {
-        return Err(PasswordValidationError::TooLong);
+    if password.len() > policy.max_length {
+        return Err(PasswordValidationError::TooLong {
+            max_length: policy.max_length,
+        });
     }
 
     // Character type validation
@@ -63,19 +95,19 @@ pub fn validate_password_strength(password: &str) -> Result<(), PasswordValidati
         .chars()
         .any(|c| "!@#$%^&*()_+-=[]{}|;:,.<>?/~`".contains(c));
 
-    if !has_lowercase {
+    if policy.require_lowercase && !has_lowercase {
         return Err(PasswordValidationError::MissingLowercase);
     }
 
-    if !has_uppercase {
+    if policy.require_uppercase && !has_uppercase {
         return Err(PasswordValidationError::MissingUppercase);
     }
 
-    if !has_digit {
+    if policy.require_digit && !has_digit {
         return Err(PasswordValidationError::MissingDigit);
     }
 
-    if !has_special {
+    if policy.require_special && !has_special {
         return Err(PasswordValidationError::MissingSpecialChar);
     }
 
@@ -87,6 +119,52 @@ pub fn validate_password_strength(password: &str) -> Result<(), PasswordValidati
     Ok(())
 }
 
+/// Look a password up in a Pwned Passwords range API (k-anonymity)
+///
+/// Only the first 5 hex characters of the password's SHA-1 leave the server;
+/// the matching suffix is searched locally. `range_url` is the API base,
+/// e.g. `https://api.pwnedpasswords.com` or a self-hosted mirror.
+/// Returns how many times the password appears in known breaches (0 = not found).
+pub async fn breach_count(
+    client: &reqwest::Client,
+    range_url: &str,
+    password: &str,
+) -> Result<u64, PasswordError> {
+    let (prefix, suffix) = range_query(password);
+    let url = format!("{}/range/{}", range_url.trim_end_matches('/'), prefix);
+
+    let body = client
+        .get(&url)
+        // Padding hides how many suffixes share the prefix
+        .header("Add-Padding", "true")
+        .timeout(std::time::Duration::from_secs(5))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| PasswordError::BreachCheck(e.to_string()))?
+        .text()
+        .await
+        .map_err(|e| PasswordError::BreachCheck(e.to_string()))?;
+
+    Ok(count_in_range(&body, &suffix))
+}
+
+/// Split the password's uppercase SHA-1 hex into the 5-char prefix sent and the suffix kept
+fn range_query(password: &str) -> (String, String) {
+    let hash = hex::encode_upper(Sha1::digest(password.as_bytes()));
+    let (prefix, suffix) = hash.split_at(5);
+    (prefix.to_string(), suffix.to_string())
+}
+
+/// Find a suffix in a range response (`SUFFIX:COUNT` lines); padding entries have count 0
+fn count_in_range(body: &str, suffix: &str) -> u64 {
+    body.lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
 /// Check if password is in the common passwords list
 fn is_common_password(password: &str) -> bool {
     // Lowercase comparison for case-insensitive matching
@@ -306,14 +384,18 @@ pub enum PasswordError {
     Hashing(String),
     #[error("Invalid password hash: {0}")]
     InvalidHash(String),
+    #[error("Breached password check failed: {0}")]
+    BreachCheck(String),
 }
 
-#[derive(Debug, thiserror::Error)]
+/// Why a password was rejected; serialized with a `reason` tag for the frontend
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, serde::Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
 pub enum PasswordValidationError {
-    #[error("Password must be at least 12 characters")]
-    TooShort,
-    #[error("Password must be at most 128 characters")]
-    TooLong,
+    #[error("Password must be at least {min_length} characters")]
+    TooShort { min_length: usize },
+    #[error("Password must be at most {max_length} characters")]
+    TooLong { max_length: usize },
     #[error("Password must contain at least one lowercase letter")]
     MissingLowercase,
     #[error("Password must contain at least one uppercase letter")]
@@ -324,6 +406,8 @@ pub enum PasswordValidationError {
     MissingSpecialChar,
     #[error("This password is too common - please choose a unique password")]
     TooCommon,
+    #[error("This password has appeared in a data breach - please choose a different password")]
+    Breached { occurrences: u64 },
 }
 
 #[cfg(test)]
@@ -343,42 +427,42 @@ mod tests {
     fn test_password_validation() {
         // Too short
         assert!(matches!(
-            validate_password_strength("Short1!"),
-            Err(PasswordValidationError::TooShort)
+            validate_password_strength("Short1!", &PasswordPolicy::default()),
+            Err(PasswordValidationError::TooShort { .. })
         ));
 
         // No uppercase
         assert!(matches!(
-            validate_password_strength("lowercase123!"),
+            validate_password_strength("lowercase123!", &PasswordPolicy::default()),
             Err(PasswordValidationError::MissingUppercase)
         ));
 
         // No lowercase
         assert!(matches!(
-            validate_password_strength("UPPERCASE123!"),
+            validate_password_strength("UPPERCASE123!", &PasswordPolicy::default()),
             Err(PasswordValidationError::MissingLowercase)
         ));
 
         // No digits
         assert!(matches!(
-            validate_password_strength("NoDigitsHere!"),
+            validate_password_strength("NoDigitsHere!", &PasswordPolicy::default()),
             Err(PasswordValidationError::MissingDigit)
         ));
 
         // No special characters
         assert!(matches!(
-            validate_password_strength("ValidPass123"),
+            validate_password_strength("ValidPass123", &PasswordPolicy::default()),
             Err(PasswordValidationError::MissingSpecialChar)
         ));
 
         // Common password
         assert!(matches!(
-            validate_password_strength("Password123!"),
+            validate_password_strength("Password123!", &PasswordPolicy::default()),
             Err(PasswordValidationError::TooCommon)
         ));
 
         // Valid password
-        assert!(validate_password_strength("MySecureP@ss123").is_ok());
+        assert!(validate_password_strength("MySecureP@ss123", &PasswordPolicy::default()).is_ok());
     }
 
     #[test]
@@ -386,25 +470,27 @@ mod tests {
         // Common passwords from our blacklist (case-insensitive, exact match)
         // "password123!" is in the list (line 85)
         assert!(matches!(
-            validate_password_strength("Password123!"),
+            validate_password_strength("Password123!", &PasswordPolicy::default()),
             Err(PasswordValidationError::TooCommon)
         ));
 
         // "password@123" is in the list (line 105)
         assert!(matches!(
-            validate_password_strength("Password@123"),
+            validate_password_strength("Password@123", &PasswordPolicy::default()),
             Err(PasswordValidationError::TooCommon)
         ));
 
         // "password#123" is in the list (line 105)
         assert!(matches!(
-            validate_password_strength("Password#123"),
+            validate_password_strength("Password#123", &PasswordPolicy::default()),
             Err(PasswordValidationError::TooCommon)
         ));
 
         // Passwords not in exact match list should pass (if they meet other requirements)
-        assert!(validate_password_strength("MyUniqueP@ssw0rd123").is_ok());
-        assert!(validate_password_strength("Str0ngP@ssword!").is_ok());
+        assert!(
+            validate_password_strength("MyUniqueP@ssw0rd123", &PasswordPolicy::default()).is_ok()
+        );
+        assert!(validate_password_strength("Str0ngP@ssword!", &PasswordPolicy::default()).is_ok());
     }
 
     #[test]
@@ -445,18 +531,74 @@ mod tests {
     fn test_password_length_requirements() {
         // Less than 12 characters
         assert!(matches!(
-            validate_password_strength("Short1!aB"),
-            Err(PasswordValidationError::TooShort)
+            validate_password_strength("Short1!aB", &PasswordPolicy::default()),
+            Err(PasswordValidationError::TooShort { .. })
         ));
 
         // Exactly 12 characters (minimum)
-        assert!(validate_password_strength("ValidPass1!a").is_ok());
+        assert!(validate_password_strength("ValidPass1!a", &PasswordPolicy::default()).is_ok());
 
         // More than 128 characters
         let long_password = "A".repeat(129) + "1!";
         assert!(matches!(
-            validate_password_strength(&long_password),
-            Err(PasswordValidationError::TooLong)
+            validate_password_strength(&long_password, &PasswordPolicy::default()),
+            Err(PasswordValidationError::TooLong { .. })
         ));
     }
+
+    #[test]
+    fn test_custom_password_policy() {
+        let policy = PasswordPolicy {
+            min_length: 16,
+            max_length: 64,
+            require_lowercase: true,
+            require_uppercase: false,
+            require_digit: false,
+            require_special: false,
+        };
+
+        assert_eq!(
+            validate_password_strength("ValidPass1!a", &policy),
+            Err(PasswordValidationError::TooShort { min_length: 16 })
+        );
+        assert!(validate_password_strength("correct horse battery staple", &policy).is_ok());
+        assert_eq!(
+            validate_password_strength(&"a".repeat(65), &policy),
+            Err(PasswordValidationError::TooLong { max_length: 64 })
+        );
+    }
+
+    #[test]
+    fn test_validation_error_serializes_reason() {
+        let value = serde_json::to_value(PasswordValidationError::TooShort { min_length: 16 })
+            .expect("serialize");
+        assert_eq!(
+            value,
+            serde_json::json!({ "reason": "too_short", "min_length": 16 })
+        );
+
+        let value = serde_json::to_value(PasswordValidationError::MissingDigit).expect("serialize");
+        assert_eq!(value, serde_json::json!({ "reason": "missing_digit" }));
+    }
+
+    #[test]
+    fn test_breach_range_lookup() {
+        // SHA-1("password") = 5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8
+        let (prefix, suffix) = range_query("password");
+        assert_eq!(prefix, "5BAA6");
+        assert_eq!(suffix, "1E4C9B93F3F0682250B6CF8331B7EE68FD8");
+
+        let body = "003D68EB55068C33ACE09247EE4C639306B:3\r\n\
+                    1E4C9B93F3F0682250B6CF8331B7EE68FD8:9659365\r\n\
+                    01330C689E5D64F660D6947A93AD634EF8F:0\r\n";
+        assert_eq!(count_in_range(body, &suffix), 9659365);
+        assert_eq!(
+            count_in_range(body, "01330C689E5D64F660D6947A93AD634EF8F"),
+            0
+        );
+        assert_eq!(
+            count_in_range(body, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"),
+            0
+        );
+    }
 }
@@ -0,0 +1,180 @@
+//! API key scopes
+//!
+//! Keys created with one or more of these scopes can only reach the routes
+//! those scopes cover. Keys without any (including legacy `read`/`write` keys)
+//! keep full access, as before scopes existed.
+
+use std::fmt;
+use std::str::FromStr;
+
+use axum::http::Method;
+use serde::{Deserialize, Serialize};
+
+/// Scope strings accepted from older clients; they don't restrict the key
+const LEGACY_SCOPES: &[&str] = &["read", "write"];
+
+/// Permission granted to an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    ReadBilling,
+    WriteBilling,
+    ReadMcp,
+    WriteMcp,
+    /// Everything, including routes outside billing and MCP management
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::ReadBilling => "read_billing",
+            Scope::WriteBilling => "write_billing",
+            Scope::ReadMcp => "read_mcp",
+            Scope::WriteMcp => "write_mcp",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_billing" => Ok(Scope::ReadBilling),
+            "write_billing" => Ok(Scope::WriteBilling),
+            "read_mcp" => Ok(Scope::ReadMcp),
+            "write_mcp" => Ok(Scope::WriteMcp),
+            "admin" => Ok(Scope::Admin),
+            other => Err(format!("Unknown API key scope: {}", other)),
+        }
+    }
+}
+
+/// Check scopes sent when creating or updating a key
+pub fn validate_scopes(scopes: &[String]) -> Result<(), String> {
+    for scope in scopes {
+        if !LEGACY_SCOPES.contains(&scope.as_str()) {
+            scope.parse::<Scope>()?;
+        }
+    }
+    Ok(())
+}
+
+/// Scopes that restrict a key, or `None` if it has full access
+pub fn effective_scopes(stored: &[String]) -> Option<Vec<Scope>> {
+    let scopes: Vec<Scope> = stored.iter().filter_map(|s| s.parse().ok()).collect();
+    (!scopes.is_empty()).then_some(scopes)
+}
+
+/// Scope a scoped key needs for a request
+pub fn required_scope(method: &Method, path: &str) -> Scope {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path);
+    let read_only = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+
+    if path.starts_with("/billing") {
+        if read_only {
+            Scope::ReadBilling
+        } else {
+            Scope::WriteBilling
+        }
+    } else if path.starts_with("/mcps") {
+        if read_only {
+            Scope::ReadMcp
+        } else {
+            Scope::WriteMcp
+        }
+    } else {
+        Scope::Admin
+    }
+}
+
+/// Whether `scopes` cover `required` (write implies read, admin implies all)
+pub fn grants(scopes: &[Scope], required: Scope) -> bool {
+    scopes.iter().any(|&scope| {
+        scope == required
+            || scope == Scope::Admin
+            || matches!(
+                (scope, required),
+                (Scope::WriteBilling, Scope::ReadBilling) | (Scope::WriteMcp, Scope::ReadMcp)
+            )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_round_trip() {
+        for scope in [
+            Scope::ReadBilling,
+            Scope::WriteBilling,
+            Scope::ReadMcp,
+            Scope::WriteMcp,
+            Scope::Admin,
+        ] {
+            assert_eq!(scope.as_str().parse::<Scope>().unwrap(), scope);
+            assert_eq!(
+                serde_json::to_value(scope).unwrap(),
+                serde_json::Value::String(scope.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_and_effective_scopes() {
+        let legacy = vec!["read".to_string(), "write".to_string()];
+        assert!(validate_scopes(&legacy).is_ok());
+        assert_eq!(effective_scopes(&legacy), None);
+        assert_eq!(effective_scopes(&[]), None);
+
+        let read_only = vec!["read_billing".to_string(), "read_mcp".to_string()];
+        assert!(validate_scopes(&read_only).is_ok());
+        assert_eq!(
+            effective_scopes(&read_only),
+            Some(vec![Scope::ReadBilling, Scope::ReadMcp])
+        );
+
+        assert!(validate_scopes(&["billing:*".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_read_only_key_cannot_mutate_billing() {
+        let read_only = [Scope::ReadBilling, Scope::ReadMcp];
+
+        let get = required_scope(&Method::GET, "/api/v1/billing/invoices");
+        assert_eq!(get, Scope::ReadBilling);
+        assert!(grants(&read_only, get));
+
+        let post = required_scope(&Method::POST, "/api/v1/billing/portal");
+        assert_eq!(post, Scope::WriteBilling);
+        assert!(!grants(&read_only, post));
+
+        assert!(!grants(
+            &read_only,
+            required_scope(&Method::DELETE, "/api/v1/mcps/123")
+        ));
+        // Routes outside billing and MCP management need admin
+        assert!(!grants(
+            &read_only,
+            required_scope(&Method::GET, "/api/v1/api-keys")
+        ));
+    }
+
+    #[test]
+    fn test_write_and_admin_imply_read() {
+        assert!(grants(&[Scope::WriteBilling], Scope::ReadBilling));
+        assert!(grants(&[Scope::WriteMcp], Scope::ReadMcp));
+        assert!(!grants(&[Scope::WriteMcp], Scope::ReadBilling));
+        assert!(grants(&[Scope::Admin], Scope::WriteBilling));
+        assert!(grants(&[Scope::Admin], Scope::Admin));
+    }
+}
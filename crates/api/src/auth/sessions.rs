@@ -2,12 +2,23 @@
 //!
 //! Provides functions to create, validate, and revoke JWT sessions.
 //! Sessions are tracked in the `user_sessions` table with JTI (JWT ID) for revocation support.
+//!
+//! Refresh tokens rotate: each one can be exchanged once, and every token
+//! descended from the same login shares a family ID. Exchanged JTIs are kept
+//! in `refresh_token_uses`; if one is presented again the token has leaked,
+//! so the whole family is revoked and the user has to log in again.
+
+use std::sync::Arc;
 
+use maxminddb::Reader;
+use serde::Serialize;
 use sqlx::PgPool;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use super::{jwt::Claims, TokenError};
 use crate::error::ApiResult;
+use crate::routes::analytics_tracking::{lookup_location, parse_user_agent};
 
 /// SOC 2 CC6.1: Maximum concurrent sessions per user
 /// Prevents session accumulation and limits attack surface
@@ -17,18 +28,26 @@ const MAX_SESSIONS_PER_USER: i64 = 10;
 ///
 /// This should be called immediately after generating a JWT token pair.
 /// The JTI values are stored to allow revoking tokens before expiration.
+/// The client's approximate location is resolved from `ip_address` for the
+/// active sessions list.
 /// SOC 2 CC6.1: Enforces max session limit by revoking oldest sessions
 #[allow(clippy::too_many_arguments)]
 pub async fn save_session(
     pool: &PgPool,
+    geoip_reader: &Option<Arc<Reader<Vec<u8>>>>,
     user_id: Uuid,
     access_jti: &str,
     access_expires_at: OffsetDateTime,
     refresh_jti: &str,
     refresh_expires_at: OffsetDateTime,
+    family_id: Uuid,
     ip_address: Option<&str>,
     user_agent: Option<&str>,
 ) -> ApiResult<()> {
+    let location = ip_address
+        .and_then(|ip| lookup_location(geoip_reader, ip))
+        .unwrap_or_default();
+
     // Start a transaction to ensure both sessions are created atomically
     let mut tx = pool.begin().await?;
 
@@ -86,8 +105,11 @@ pub async fn save_session(
             expires_at,
             ip_address,
             user_agent,
-            token_type
-        ) VALUES ($1, $2, $3, $4, $5, 'refresh')
+            token_type,
+            family_id,
+            city,
+            country_code
+        ) VALUES ($1, $2, $3, $4, $5, 'refresh', $6, $7, $8)
         RETURNING id
         "#,
     )
@@ -96,6 +118,9 @@ pub async fn save_session(
     .bind(refresh_expires_at)
     .bind(ip_address)
     .bind(user_agent)
+    .bind(family_id)
+    .bind(&location.city)
+    .bind(&location.country_code)
     .fetch_one(&mut *tx)
     .await?;
 
@@ -109,8 +134,11 @@ pub async fn save_session(
             ip_address,
             user_agent,
             token_type,
-            parent_session_id
-        ) VALUES ($1, $2, $3, $4, $5, 'access', $6)
+            parent_session_id,
+            family_id,
+            city,
+            country_code
+        ) VALUES ($1, $2, $3, $4, $5, 'access', $6, $7, $8, $9)
         "#,
     )
     .bind(user_id)
@@ -119,6 +147,9 @@ pub async fn save_session(
     .bind(ip_address)
     .bind(user_agent)
     .bind(refresh_session_id)
+    .bind(family_id)
+    .bind(&location.city)
+    .bind(&location.country_code)
     .execute(&mut *tx)
     .await?;
 
@@ -172,24 +203,176 @@ pub async fn revoke_session(pool: &PgPool, jti: &str, reason: &str) -> ApiResult
     Ok(rows_affected > 0)
 }
 
-/// Revoke all sessions for a user
+/// Exchange a refresh token, marking it used
+///
+/// `claims` must come from a validated refresh token. Returns the family the
+/// replacement token belongs to. Refresh tokens issued before rotation have no
+/// family and start a new one here.
+/// SOC 2 CC6.1: A second exchange of the same token revokes its whole family
+pub async fn consume_refresh_token(pool: &PgPool, claims: &Claims) -> Result<Uuid, TokenError> {
+    if !is_session_valid(pool, &claims.jti, claims.sub)
+        .await
+        .map_err(|_| TokenError::DatabaseError)?
+    {
+        // Revoked (logout, rotation) or expired. A rotated token shows up
+        // here too, so check the use record before calling it merely invalid.
+        return match used_token_family(pool, &claims.jti).await? {
+            Some(family_id) => Err(reuse_detected(pool, claims, family_id).await),
+            None => Err(TokenError::InvalidToken),
+        };
+    }
+
+    let family_id = claims.family_id.unwrap_or_else(Uuid::new_v4);
+    let mut tx = pool.begin().await.map_err(|_| TokenError::DatabaseError)?;
+
+    // The primary key on jti makes this the single point that decides which
+    // of two concurrent exchanges wins
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO refresh_token_uses (jti, family_id, user_id, expires_at)
+        VALUES ($1, $2, $3, to_timestamp($4))
+        ON CONFLICT (jti) DO NOTHING
+        "#,
+    )
+    .bind(&claims.jti)
+    .bind(family_id)
+    .bind(claims.sub)
+    .bind(claims.exp as f64)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| TokenError::DatabaseError)?
+    .rows_affected();
+
+    if inserted == 0 {
+        drop(tx);
+        let family_id = used_token_family(pool, &claims.jti)
+            .await?
+            .unwrap_or(family_id);
+        return Err(reuse_detected(pool, claims, family_id).await);
+    }
+
+    // A pre-rotation token's sessions join the family it starts, so a later
+    // reuse revokes them along with everything issued from it
+    sqlx::query(
+        r#"
+        UPDATE user_sessions
+        SET family_id = $2
+        WHERE family_id IS NULL
+          AND (jti = $1 OR parent_session_id = (SELECT id FROM user_sessions WHERE jti = $1))
+        "#,
+    )
+    .bind(&claims.jti)
+    .bind(family_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| TokenError::DatabaseError)?;
+
+    sqlx::query(
+        r#"
+        UPDATE user_sessions
+        SET revoked_at = NOW(),
+            revocation_reason = 'rotated'
+        WHERE jti = $1
+          AND revoked_at IS NULL
+        "#,
+    )
+    .bind(&claims.jti)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| TokenError::DatabaseError)?;
+
+    tx.commit().await.map_err(|_| TokenError::DatabaseError)?;
+
+    Ok(family_id)
+}
+
+/// Family of a refresh token that has already been exchanged
+async fn used_token_family(pool: &PgPool, jti: &str) -> Result<Option<Uuid>, TokenError> {
+    sqlx::query_scalar("SELECT family_id FROM refresh_token_uses WHERE jti = $1")
+        .bind(jti)
+        .fetch_optional(pool)
+        .await
+        .map_err(|_| TokenError::DatabaseError)
+}
+
+/// Revoke the family of a reused refresh token
+async fn reuse_detected(pool: &PgPool, claims: &Claims, family_id: Uuid) -> TokenError {
+    tracing::warn!(
+        user_id = %claims.sub,
+        jti = %claims.jti,
+        family_id = %family_id,
+        "Refresh token reuse detected - revoking token family"
+    );
+
+    if let Err(e) = revoke_token_family(pool, family_id, "refresh_token_reuse").await {
+        tracing::error!(error = ?e, family_id = %family_id, "Failed to revoke token family");
+        return TokenError::DatabaseError;
+    }
+
+    TokenError::ReuseDetected
+}
+
+/// Revoke every session descended from the same login
+pub async fn revoke_token_family(pool: &PgPool, family_id: Uuid, reason: &str) -> ApiResult<u64> {
+    let rows_affected = sqlx::query(
+        r#"
+        UPDATE user_sessions
+        SET revoked_at = NOW(),
+            revocation_reason = $2
+        WHERE family_id = $1
+          AND revoked_at IS NULL
+        "#,
+    )
+    .bind(family_id)
+    .bind(reason)
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    Ok(rows_affected)
+}
+
+/// Revoke all sessions for a user (log out everywhere)
+///
+/// Covers access and refresh sessions, so no token of theirs can be refreshed
+/// afterwards. Pass the caller's `keep_session` (the access session ID from
+/// [`super::AuthUser::session_id`]) to log out all *other* devices: that
+/// session, its refresh session and the rest of its token family stay valid.
+/// Returns how many sessions were revoked.
 ///
 /// This should be called when:
-/// - User changes their password
+/// - User changes or resets their password
 /// - User is locked out or banned
 /// - Security incident requires force logout
-pub async fn revoke_all_sessions(pool: &PgPool, user_id: Uuid, reason: &str) -> ApiResult<u64> {
+pub async fn revoke_all_for_user(
+    pool: &PgPool,
+    user_id: Uuid,
+    reason: &str,
+    keep_session: Option<Uuid>,
+) -> ApiResult<u64> {
     let rows_affected = sqlx::query(
         r#"
+        WITH kept AS (
+            SELECT id, parent_session_id, family_id
+            FROM user_sessions
+            WHERE id = $3 AND user_id = $1
+        )
         UPDATE user_sessions
         SET revoked_at = NOW(),
             revocation_reason = $2
         WHERE user_id = $1
           AND revoked_at IS NULL
+          AND id NOT IN (
+              SELECT id FROM kept
+              UNION
+              SELECT parent_session_id FROM kept WHERE parent_session_id IS NOT NULL
+          )
+          AND (family_id IS NULL OR family_id IS DISTINCT FROM (SELECT family_id FROM kept))
         "#,
     )
     .bind(user_id)
     .bind(reason)
+    .bind(keep_session)
     .execute(pool)
     .await?
     .rows_affected();
@@ -197,7 +380,7 @@ pub async fn revoke_all_sessions(pool: &PgPool, user_id: Uuid, reason: &str) ->
     Ok(rows_affected)
 }
 
-/// List active sessions for a user
+/// A row of `user_sessions`
 #[derive(Debug, sqlx::FromRow, serde::Serialize)]
 pub struct UserSession {
     pub id: Uuid,
@@ -210,36 +393,122 @@ pub struct UserSession {
     pub token_type: String,
 }
 
-pub async fn list_sessions(pool: &PgPool, user_id: Uuid) -> ApiResult<Vec<UserSession>> {
-    let sessions = sqlx::query_as::<_, UserSession>(
+/// An active login as shown on the account security page
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    /// Refresh session ID (what `DELETE /auth/sessions/:id` takes)
+    pub id: Uuid,
+    /// When the user logged in on this device
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub last_seen_at: OffsetDateTime,
+    /// e.g. "Chrome on macOS"
+    pub device: String,
+    /// e.g. "Berlin, DE"; None when it couldn't be resolved
+    pub location: Option<String>,
+    pub ip_address: Option<String>,
+    /// Whether this is the session making the request
+    pub is_current: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ActiveSessionRow {
+    id: Uuid,
+    created_at: OffsetDateTime,
+    last_seen_at: OffsetDateTime,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    city: Option<String>,
+    country_code: Option<String>,
+    is_current: bool,
+}
+
+/// List a user's active logins, one per device
+///
+/// `current_session` is the caller's access session ID
+/// ([`super::AuthUser::session_id`]) and marks which entry is theirs.
+pub async fn list_active(
+    pool: &PgPool,
+    user_id: Uuid,
+    current_session: Option<Uuid>,
+) -> ApiResult<Vec<SessionInfo>> {
+    // One refresh session per device; access sessions hang off it. With
+    // rotation the refresh row is replaced on every refresh, so the login
+    // time is the oldest row in its family.
+    let rows = sqlx::query_as::<_, ActiveSessionRow>(
         r#"
         SELECT
-            id,
-            jti,
-            created_at,
-            expires_at,
-            last_used_at,
-            ip_address,
-            user_agent,
-            token_type
-        FROM user_sessions
-        WHERE user_id = $1
-          AND revoked_at IS NULL
-          AND expires_at > NOW()
-          AND token_type = 'refresh'  -- Only show refresh tokens (parent sessions)
-        ORDER BY created_at DESC
+            s.id,
+            COALESCE(
+                (SELECT MIN(f.created_at) FROM user_sessions f WHERE f.family_id = s.family_id),
+                s.created_at
+            ) AS created_at,
+            GREATEST(
+                s.last_used_at,
+                (SELECT MAX(c.last_used_at) FROM user_sessions c WHERE c.parent_session_id = s.id)
+            ) AS last_seen_at,
+            s.ip_address,
+            s.user_agent,
+            s.city,
+            s.country_code,
+            COALESCE(
+                s.id = (SELECT parent_session_id FROM user_sessions WHERE id = $2),
+                false
+            ) AS is_current
+        FROM user_sessions s
+        WHERE s.user_id = $1
+          AND s.revoked_at IS NULL
+          AND s.expires_at > NOW()
+          AND s.token_type = 'refresh'  -- Only show refresh tokens (parent sessions)
+        ORDER BY last_seen_at DESC
         "#,
     )
     .bind(user_id)
+    .bind(current_session)
     .fetch_all(pool)
     .await?;
 
-    Ok(sessions)
+    Ok(rows
+        .into_iter()
+        .map(|row| SessionInfo {
+            id: row.id,
+            created_at: row.created_at,
+            last_seen_at: row.last_seen_at,
+            device: device_summary(row.user_agent.as_deref()),
+            location: format_location(row.city.as_deref(), row.country_code.as_deref()),
+            ip_address: row.ip_address,
+            is_current: row.is_current,
+        })
+        .collect())
+}
+
+/// Short human-readable description of a user agent
+fn device_summary(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent.filter(|ua| !ua.is_empty()) else {
+        return "Unknown device".to_string();
+    };
+
+    match parse_user_agent(ua) {
+        (_, Some(browser), Some(os)) => format!("{} on {}", browser, os),
+        (_, Some(browser), None) => browser,
+        (_, None, Some(os)) => os,
+        (device_type, None, None) if device_type == "bot" => "Script or API client".to_string(),
+        _ => "Unknown device".to_string(),
+    }
+}
+
+/// "City, CC", whichever parts are known
+fn format_location(city: Option<&str>, country_code: Option<&str>) -> Option<String> {
+    match (city, country_code) {
+        (Some(city), Some(country)) => Some(format!("{}, {}", city, country)),
+        (Some(part), None) | (None, Some(part)) => Some(part.to_string()),
+        (None, None) => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    #[allow(unused_imports)]
     use super::*;
 
     #[test]
@@ -247,4 +516,228 @@ mod tests {
         // This test just ensures the module compiles
         // Actual integration tests require a test database
     }
+
+    #[test]
+    fn test_device_summary() {
+        let chrome_mac = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 \
+                          (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+        assert_eq!(device_summary(Some(chrome_mac)), "Chrome on macOS");
+        assert_eq!(device_summary(Some("curl/8.4.0")), "Unknown device");
+        assert_eq!(device_summary(Some("")), "Unknown device");
+        assert_eq!(device_summary(None), "Unknown device");
+    }
+
+    #[test]
+    fn test_format_location() {
+        assert_eq!(
+            format_location(Some("Berlin"), Some("DE")),
+            Some("Berlin, DE".to_string())
+        );
+        assert_eq!(format_location(None, Some("DE")), Some("DE".to_string()));
+        assert_eq!(format_location(None, None), None);
+    }
+
+    async fn test_user(pool: &PgPool) -> (Uuid, Uuid) {
+        let org_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO organizations (id, name, slug) VALUES ($1, 'Session Org', $2)")
+            .bind(org_id)
+            .bind(format!("session-org-{}", org_id))
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, org_id, email, password_hash, role) VALUES ($1, $2, $3, 'TEST_HASH', 'owner')",
+        )
+        .bind(user_id)
+        .bind(org_id)
+        .bind(format!("sessions-{}@example.com", user_id))
+        .execute(pool)
+        .await
+        .unwrap();
+        (org_id, user_id)
+    }
+
+    /// Save a session pair in `family_id` and return its (access JTI, refresh JTI)
+    async fn test_login(pool: &PgPool, user_id: Uuid, family_id: Uuid) -> (String, String) {
+        let access_jti = Uuid::new_v4().to_string();
+        let refresh_jti = Uuid::new_v4().to_string();
+        let expires_at = OffsetDateTime::now_utc() + time::Duration::days(1);
+        save_session(
+            pool,
+            &None,
+            user_id,
+            &access_jti,
+            expires_at,
+            &refresh_jti,
+            expires_at,
+            family_id,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        (access_jti, refresh_jti)
+    }
+
+    fn refresh_claims(user_id: Uuid, org_id: Uuid, jti: &str, family_id: Option<Uuid>) -> Claims {
+        let now = OffsetDateTime::now_utc();
+        Claims {
+            sub: user_id,
+            org_id,
+            role: "owner".to_string(),
+            email: format!("sessions-{}@example.com", user_id),
+            iat: now.unix_timestamp(),
+            exp: (now + time::Duration::days(1)).unix_timestamp(),
+            token_type: super::super::jwt::TokenType::Refresh,
+            jti: jti.to_string(),
+            family_id,
+        }
+    }
+
+    async fn session_id(pool: &PgPool, jti: &str) -> Uuid {
+        sqlx::query_scalar("SELECT id FROM user_sessions WHERE jti = $1")
+            .bind(jti)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn cleanup(pool: &PgPool, org_id: Uuid) {
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_revoke_all_for_user_keeps_only_the_callers_session() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let (org_id, user_id) = test_user(&pool).await;
+
+        let (laptop_access, laptop_refresh) = test_login(&pool, user_id, Uuid::new_v4()).await;
+        let (phone_access, phone_refresh) = test_login(&pool, user_id, Uuid::new_v4()).await;
+        // Refresh session from before token families existed
+        let legacy_refresh = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO user_sessions (user_id, jti, expires_at, token_type)
+             VALUES ($1, $2, NOW() + INTERVAL '1 day', 'refresh')",
+        )
+        .bind(user_id)
+        .bind(&legacy_refresh)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Log out the other devices: the laptop keeps its access and refresh session
+        let keep = session_id(&pool, &laptop_access).await;
+        let revoked = revoke_all_for_user(&pool, user_id, "user_logout_all", Some(keep))
+            .await
+            .unwrap();
+        assert_eq!(revoked, 3);
+        for jti in [&laptop_access, &laptop_refresh] {
+            assert!(is_session_valid(&pool, jti, user_id).await.unwrap());
+        }
+        for jti in [&phone_access, &phone_refresh, &legacy_refresh] {
+            assert!(!is_session_valid(&pool, jti, user_id).await.unwrap());
+        }
+
+        // Log out everywhere: nothing is kept
+        let revoked = revoke_all_for_user(&pool, user_id, "user_logout_all", None)
+            .await
+            .unwrap();
+        assert_eq!(revoked, 2);
+        for jti in [&laptop_access, &laptop_refresh] {
+            assert!(!is_session_valid(&pool, jti, user_id).await.unwrap());
+        }
+
+        cleanup(&pool, org_id).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_refresh_token_reuse_revokes_family() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let (org_id, user_id) = test_user(&pool).await;
+
+        let family_id = Uuid::new_v4();
+        let (access, refresh) = test_login(&pool, user_id, family_id).await;
+        let (other_access, other_refresh) = test_login(&pool, user_id, Uuid::new_v4()).await;
+        let claims = refresh_claims(user_id, org_id, &refresh, Some(family_id));
+
+        assert_eq!(
+            consume_refresh_token(&pool, &claims).await.unwrap(),
+            family_id
+        );
+        let (rotated_access, rotated_refresh) = test_login(&pool, user_id, family_id).await;
+        assert!(is_session_valid(&pool, &rotated_refresh, user_id)
+            .await
+            .unwrap());
+
+        // Presenting the exchanged token again means it leaked
+        assert!(matches!(
+            consume_refresh_token(&pool, &claims).await,
+            Err(TokenError::ReuseDetected)
+        ));
+        for jti in [&access, &refresh, &rotated_access, &rotated_refresh] {
+            assert!(!is_session_valid(&pool, jti, user_id).await.unwrap());
+        }
+        // Other logins are untouched
+        for jti in [&other_access, &other_refresh] {
+            assert!(is_session_valid(&pool, jti, user_id).await.unwrap());
+        }
+
+        cleanup(&pool, org_id).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_legacy_refresh_token_reuse_revokes_family() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let (org_id, user_id) = test_user(&pool).await;
+
+        // Session pair saved before token families existed
+        let refresh = Uuid::new_v4().to_string();
+        let access = Uuid::new_v4().to_string();
+        let refresh_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO user_sessions (user_id, jti, expires_at, token_type)
+             VALUES ($1, $2, NOW() + INTERVAL '1 day', 'refresh') RETURNING id",
+        )
+        .bind(user_id)
+        .bind(&refresh)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO user_sessions (user_id, jti, expires_at, token_type, parent_session_id)
+             VALUES ($1, $2, NOW() + INTERVAL '1 hour', 'access', $3)",
+        )
+        .bind(user_id)
+        .bind(&access)
+        .bind(refresh_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let claims = refresh_claims(user_id, org_id, &refresh, None);
+
+        // The first exchange starts a family
+        let family_id = consume_refresh_token(&pool, &claims).await.unwrap();
+        let (rotated_access, rotated_refresh) = test_login(&pool, user_id, family_id).await;
+        assert!(is_session_valid(&pool, &access, user_id).await.unwrap());
+
+        assert!(matches!(
+            consume_refresh_token(&pool, &claims).await,
+            Err(TokenError::ReuseDetected)
+        ));
+        for jti in [&access, &rotated_access, &rotated_refresh] {
+            assert!(!is_session_valid(&pool, jti, user_id).await.unwrap());
+        }
+
+        cleanup(&pool, org_id).await;
+    }
 }
@@ -223,6 +223,8 @@ pub enum TokenError {
     AlreadyUsed,
     #[error("Token has expired")]
     Expired,
+    #[error("Refresh token reuse detected")]
+    ReuseDetected,
     #[error("Database error")]
     DatabaseError,
 }
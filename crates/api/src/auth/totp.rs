@@ -195,9 +195,16 @@ pub fn get_otpauth_uri(secret: &str, email: &str) -> Result<String, TotpError> {
 // Backup Codes
 // =============================================================================
 
-/// Generate backup codes in xxxxx-xxxxx format (alphanumeric, ~51 bits entropy)
+/// Generate the standard set of [`BACKUP_CODE_COUNT`] backup codes
 pub fn generate_backup_codes() -> Vec<String> {
-    (0..BACKUP_CODE_COUNT)
+    generate_recovery_codes(BACKUP_CODE_COUNT)
+}
+
+/// Generate `n` one-time recovery codes in xxxxx-xxxxx format (alphanumeric, ~51 bits entropy)
+///
+/// Store only [`hash_backup_code`] hashes; each code is consumed on first use.
+pub fn generate_recovery_codes(n: usize) -> Vec<String> {
+    (0..n)
         .map(|_| {
             let code: String = (0..10)
                 .map(|_| {
@@ -437,6 +444,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generate_recovery_codes_count() {
+        assert!(generate_recovery_codes(0).is_empty());
+
+        let codes = generate_recovery_codes(3);
+        assert_eq!(codes.len(), 3);
+        assert!(codes
+            .iter()
+            .all(|c| c.len() == 11 && c.as_bytes()[5] == b'-'));
+    }
+
     #[test]
     fn test_backup_code_hash_verify() {
         let codes = generate_backup_codes();
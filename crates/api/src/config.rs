@@ -2,6 +2,8 @@
 
 use std::env;
 
+use crate::auth::PasswordPolicy;
+
 /// Application configuration loaded from environment variables
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -26,6 +28,14 @@ pub struct Config {
     pub supabase_service_role_key: String,
     pub jwt_expiry_hours: i64,
     pub api_key_hmac_secret: String,
+    /// Hours a rotated-out API key keeps working (0 = revoke immediately)
+    pub api_key_rotation_grace_hours: i64,
+    /// Password policy for new passwords
+    pub password_min_length: usize,
+    pub password_max_length: usize,
+    pub password_require_character_classes: bool,
+    /// Pwned Passwords range API base URL (public API or local mirror); empty disables the breach check
+    pub pwned_passwords_url: String,
     pub totp_encryption_key: String, // 32-byte hex key for 2FA secret encryption
 
     // Stripe
@@ -115,6 +125,23 @@ impl Config {
                 }
                 secret
             },
+            api_key_rotation_grace_hours: env::var("API_KEY_ROTATION_GRACE_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .unwrap_or(24),
+            password_min_length: env::var("PASSWORD_MIN_LENGTH")
+                .unwrap_or_else(|_| "12".to_string())
+                .parse()
+                .unwrap_or(12),
+            password_max_length: env::var("PASSWORD_MAX_LENGTH")
+                .unwrap_or_else(|_| "128".to_string())
+                .parse()
+                .unwrap_or(128),
+            password_require_character_classes: env::var("PASSWORD_REQUIRE_CHARACTER_CLASSES")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()
+                .unwrap_or(true),
+            pwned_passwords_url: env::var("PWNED_PASSWORDS_URL").unwrap_or_default(),
             // 2FA encryption key - generate with: openssl rand -hex 32
             totp_encryption_key: {
                 let key = env::var("TOTP_ENCRYPTION_KEY")
@@ -206,6 +233,19 @@ impl Config {
             maxmind_license_key: env::var("MAXMIND_LICENSE_KEY").unwrap_or_default(),
         })
     }
+
+    /// Password policy applied when a password is set or changed
+    pub fn password_policy(&self) -> PasswordPolicy {
+        let classes = self.password_require_character_classes;
+        PasswordPolicy {
+            min_length: self.password_min_length,
+            max_length: self.password_max_length,
+            require_lowercase: classes,
+            require_uppercase: classes,
+            require_digit: classes,
+            require_special: classes,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -2,6 +2,8 @@
 //!
 //! Sends transactional emails via Resend API for security-related events.
 
+use plexmcp_shared::Money;
+
 /// Email configuration
 #[derive(Debug, Clone)]
 pub struct EmailConfig {
@@ -661,10 +663,10 @@ impl SecurityEmailService {
         &self,
         to: &str,
         org_name: &str,
-        amount_owed_cents: i64,
+        amount_owed: &Money,
         reason: &str,
     ) {
-        let amount_owed = format!("${:.2}", amount_owed_cents as f64 / 100.0);
+        let amount_owed = amount_owed.to_string();
         let billing_link = format!("{}/settings/billing", self.config.dashboard_url);
 
         let html = format!(
@@ -1,12 +1,14 @@
 //! API error types and handling
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
 
+use crate::auth::PasswordValidationError;
+
 /// Application error type
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -19,6 +21,8 @@ pub enum ApiError {
     EmailAlreadyExists,
     #[error("Invalid or expired token")]
     InvalidToken,
+    #[error("Refresh token was already used; all sessions from this login were signed out")]
+    TokenReuseDetected,
     #[error("Authentication required")]
     Unauthorized,
     #[error("Insufficient permissions")]
@@ -29,6 +33,8 @@ pub enum ApiError {
     Validation(String),
     #[error("Invalid request: {0}")]
     BadRequest(String),
+    #[error("{0}")]
+    WeakPassword(#[from] PasswordValidationError),
 
     // Resource errors
     #[error("Resource not found")]
@@ -41,8 +47,12 @@ pub enum ApiError {
     // Rate limiting
     #[error("Too many requests")]
     RateLimited,
-    #[error("Too many requests: {0}")]
-    TooManyRequests(String),
+    #[error("Too many requests: {message}")]
+    TooManyRequests {
+        message: String,
+        /// Sent as `Retry-After` when known
+        retry_after_secs: Option<u64>,
+    },
 
     // Billing errors
     #[error("Subscription required")]
@@ -82,12 +92,20 @@ impl IntoResponse for ApiError {
                 (StatusCode::CONFLICT, "EMAIL_EXISTS", self.to_string())
             }
             ApiError::InvalidToken => (StatusCode::UNAUTHORIZED, "INVALID_TOKEN", self.to_string()),
+            ApiError::TokenReuseDetected => (
+                StatusCode::UNAUTHORIZED,
+                "TOKEN_REUSE_DETECTED",
+                self.to_string(),
+            ),
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", self.to_string()),
             ApiError::Forbidden => (StatusCode::FORBIDDEN, "FORBIDDEN", self.to_string()),
 
             // Validation
             ApiError::Validation(msg) => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR", msg.clone()),
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "BAD_REQUEST", msg.clone()),
+            ApiError::WeakPassword(_) => {
+                (StatusCode::BAD_REQUEST, "WEAK_PASSWORD", self.to_string())
+            }
 
             // Resources
             ApiError::NotFound => (StatusCode::NOT_FOUND, "NOT_FOUND", self.to_string()),
@@ -104,10 +122,10 @@ impl IntoResponse for ApiError {
                 "RATE_LIMITED",
                 self.to_string(),
             ),
-            ApiError::TooManyRequests(msg) => (
+            ApiError::TooManyRequests { message, .. } => (
                 StatusCode::TOO_MANY_REQUESTS,
                 "TOO_MANY_REQUESTS",
-                msg.clone(),
+                message.clone(),
             ),
 
             // Billing
@@ -148,14 +166,28 @@ impl IntoResponse for ApiError {
             ),
         };
 
-        let body = Json(json!({
-            "error": {
-                "code": code,
-                "message": message,
-            }
-        }));
+        let mut error = json!({
+            "code": code,
+            "message": message,
+        });
+        // Tell the frontend which rule failed (and its limit) for specific guidance
+        if let ApiError::WeakPassword(reason) = &self {
+            error["details"] = json!(reason);
+        }
 
-        (status, body).into_response()
+        let body = Json(json!({ "error": error }));
+
+        let mut response = (status, body).into_response();
+        if let ApiError::TooManyRequests {
+            retry_after_secs: Some(retry_after),
+            ..
+        } = &self
+        {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(*retry_after));
+        }
+        response
     }
 }
 
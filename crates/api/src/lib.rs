@@ -21,6 +21,7 @@ pub mod email;
 pub mod error;
 pub mod flyio;
 pub mod mcp;
+pub mod rate_limit;
 pub mod routes;
 pub mod routing;
 pub mod security;
@@ -26,6 +26,7 @@ mod email;
 mod error;
 mod flyio;
 mod mcp;
+mod rate_limit;
 mod routes;
 mod routing;
 mod security;
@@ -33,6 +34,7 @@ mod state;
 mod websocket;
 
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use axum::http::{header, Method};
 use axum::middleware;
@@ -42,7 +44,7 @@ use tower_http::{
     trace::TraceLayer,
 };
 
-use crate::security::security_headers_middleware;
+use crate::security::{client_ip_middleware, security_headers_middleware, TrustedProxyConfig};
 use time::OffsetDateTime;
 use tokio::time::{interval, Duration};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -183,6 +185,14 @@ async fn main() -> anyhow::Result<()> {
     // Create application state
     let state = AppState::new(pool.clone(), config.clone());
 
+    // Check the Stripe webhook setup before accepting traffic
+    #[cfg(feature = "billing")]
+    if let Some(billing) = &state.billing {
+        if let Err(e) = billing.webhooks.verify_configuration().await {
+            tracing::error!(error = %e, "Stripe webhook configuration is invalid");
+        }
+    }
+
     // Start background alert checker task for website analytics
     tokio::spawn(routes::analytics_tracking::alert_checker_task(pool.clone()));
     tracing::info!("Analytics alert checker task started");
@@ -264,9 +274,21 @@ async fn main() -> anyhow::Result<()> {
         .expose_headers([header::CONTENT_TYPE])
         .allow_credentials(true);
 
+    // Client IP headers are only trusted from configured proxies (e.g. Fly.io's edge)
+    let trusted_proxies = TrustedProxyConfig::from_env();
+    tracing::info!(
+        header = ?trusted_proxies.header,
+        trusted_proxies = trusted_proxies.trusted_proxies.len(),
+        "Client IP resolution configured"
+    );
+
     // Build the router
     // SOC 2 CC6.1: Security headers middleware adds X-Frame-Options, X-Content-Type-Options, etc.
     let app = create_router(state)
+        .layer(middleware::from_fn_with_state(
+            Arc::new(trusted_proxies),
+            client_ip_middleware,
+        ))
         .layer(middleware::from_fn(security_headers_middleware))
         .layer(cors)
         .layer(TraceLayer::new_for_http());
@@ -277,7 +299,11 @@ async fn main() -> anyhow::Result<()> {
 
     // Start the server
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
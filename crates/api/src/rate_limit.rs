@@ -0,0 +1,306 @@
+//! Rate limit response headers
+//!
+//! Every response from a rate limited endpoint reports the caller's quota:
+//! `X-RateLimit-Remaining` and `X-RateLimit-Reset` (unix seconds), plus
+//! `Retry-After` when the request was rejected. Rejections raised as
+//! `ApiError::TooManyRequests` get `Retry-After` from the error itself.
+//!
+//! [`enforce_route_profile`] applies a named [`RateLimiter`] profile to a
+//! whole group of routes. Handlers that check a stricter profile themselves
+//! keep their own headers on the response.
+
+use std::convert::Infallible;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use plexmcp_shared::{RateLimitResult2, RateLimiter};
+
+use crate::{auth::AuthUser, error::ApiError, routes::extract_client_ip};
+
+/// Requests left in the current window
+pub const RATE_LIMIT_REMAINING_HEADER: HeaderName =
+    HeaderName::from_static("x-ratelimit-remaining");
+
+/// Unix timestamp at which the window resets
+pub const RATE_LIMIT_RESET_HEADER: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+/// Set `Retry-After` (when rejected) and `X-RateLimit-*` from a check result
+pub fn set_rate_limit_headers(headers: &mut HeaderMap, result: &RateLimitResult2) {
+    if let Some(retry_after) = result.retry_after_secs {
+        headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    }
+    headers.insert(
+        RATE_LIMIT_REMAINING_HEADER,
+        HeaderValue::from(result.remaining),
+    );
+    headers.insert(
+        RATE_LIMIT_RESET_HEADER,
+        HeaderValue::from(result.reset_at.unix_timestamp()),
+    );
+}
+
+/// Rate limit headers for a handler's successful response
+///
+/// Holds `None` when no check result is available (the limiter failed
+/// open), in which case no headers are added.
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitHeaders(pub Option<RateLimitResult2>);
+
+impl IntoResponseParts for RateLimitHeaders {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Some(result) = &self.0 {
+            set_rate_limit_headers(res.headers_mut(), result);
+        }
+        Ok(res)
+    }
+}
+
+/// Route layer state: the limiter and the profile a route group uses
+///
+/// Unknown profile names get the limiter's default profile.
+#[derive(Clone)]
+pub struct RouteRateLimit {
+    limiter: RateLimiter,
+    profile: &'static str,
+}
+
+impl RouteRateLimit {
+    pub fn new(limiter: RateLimiter, profile: &'static str) -> Self {
+        Self { limiter, profile }
+    }
+}
+
+/// Counter key for a request: the authenticated user, else the client IP
+///
+/// Prefixed so a route group never shares a counter with a handler's own
+/// check against the same profile (e.g. login's per-IP auth check).
+fn route_key(request: &Request) -> Option<String> {
+    if let Some(user_id) = request
+        .extensions()
+        .get::<AuthUser>()
+        .and_then(|user| user.user_id)
+    {
+        return Some(format!("route:user:{}", user_id));
+    }
+    extract_client_ip(request.headers()).map(|ip| format!("route:ip:{}", ip))
+}
+
+/// Middleware that counts every request against the route group's profile
+///
+/// Layer it inside `require_auth` so requests are counted per user. Fails
+/// open when the limiter is unavailable.
+pub async fn enforce_route_profile(
+    State(limit): State<RouteRateLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = route_key(&request) else {
+        return next.run(request).await;
+    };
+
+    let result = match limit.limiter.check(limit.profile, &key).await {
+        Ok(result) => result,
+        Err(e) => {
+            tracing::error!(
+                error = ?e,
+                profile = limit.profile,
+                "Route rate limit check failed, allowing request"
+            );
+            return next.run(request).await;
+        }
+    };
+
+    if !result.allowed {
+        tracing::warn!(
+            profile = limit.profile,
+            key = %key,
+            retry_after = ?result.retry_after_secs,
+            "Route rate limit exceeded"
+        );
+        let retry_after = result.retry_after_secs.unwrap_or(60);
+        let mut response = ApiError::TooManyRequests {
+            message: format!(
+                "Too many requests. Please try again in {} seconds.",
+                retry_after
+            ),
+            retry_after_secs: Some(retry_after),
+        }
+        .into_response();
+        set_rate_limit_headers(response.headers_mut(), &result);
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    // A handler that checked its own (stricter) profile reports that quota instead
+    let headers = response.headers_mut();
+    headers
+        .entry(RATE_LIMIT_REMAINING_HEADER)
+        .or_insert_with(|| HeaderValue::from(result.remaining));
+    headers
+        .entry(RATE_LIMIT_RESET_HEADER)
+        .or_insert_with(|| HeaderValue::from(result.reset_at.unix_timestamp()));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Json, Router};
+    use plexmcp_shared::RateLimitConfig;
+    use time::OffsetDateTime;
+    use tower::ServiceExt;
+
+    fn allowed(remaining: u64) -> RateLimitResult2 {
+        RateLimitResult2 {
+            allowed: true,
+            remaining,
+            remaining_minute: remaining as u32,
+            remaining_hour: None,
+            remaining_monthly: None,
+            reset_at: OffsetDateTime::from_unix_timestamp(1_767_875_460).unwrap(),
+            retry_after_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_successful_response_carries_remaining_quota() {
+        let response = (
+            RateLimitHeaders(Some(allowed(7))),
+            Json(serde_json::json!({})),
+        )
+            .into_response();
+
+        let headers = response.headers();
+        assert_eq!(headers[RATE_LIMIT_REMAINING_HEADER], "7");
+        assert_eq!(headers[RATE_LIMIT_RESET_HEADER], "1767875460");
+        assert!(headers.get(header::RETRY_AFTER).is_none());
+    }
+
+    #[test]
+    fn test_rejected_result_sets_retry_after() {
+        let result = RateLimitResult2 {
+            allowed: false,
+            retry_after_secs: Some(42),
+            ..allowed(0)
+        };
+        let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+        set_rate_limit_headers(response.headers_mut(), &result);
+
+        let headers = response.headers();
+        assert_eq!(headers[header::RETRY_AFTER], "42");
+        assert_eq!(headers[RATE_LIMIT_REMAINING_HEADER], "0");
+        assert_eq!(headers[RATE_LIMIT_RESET_HEADER], "1767875460");
+    }
+
+    #[test]
+    fn test_too_many_requests_error_sets_retry_after() {
+        let response = crate::error::ApiError::TooManyRequests {
+            message: "Too many login attempts".to_string(),
+            retry_after_secs: Some(42),
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+    }
+
+    #[test]
+    fn test_no_result_adds_no_headers() {
+        let response = (RateLimitHeaders(None), StatusCode::OK).into_response();
+        assert!(response
+            .headers()
+            .get(RATE_LIMIT_REMAINING_HEADER)
+            .is_none());
+    }
+
+    fn limited_app(limiter: RateLimiter, profile: &'static str) -> Router {
+        Router::new()
+            .route("/dashboard", get(|| async { "ok" }))
+            .route(
+                "/own-limit",
+                get(|| async { (RateLimitHeaders(Some(allowed(1))), "ok") }),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                RouteRateLimit::new(limiter, profile),
+                enforce_route_profile,
+            ))
+    }
+
+    fn from_ip(uri: &str, ip: &str) -> axum::http::Request<Body> {
+        axum::http::Request::builder()
+            .uri(uri)
+            .header("x-real-ip", ip)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_route_profile_rejects_over_limit() {
+        let limiter = RateLimiter::new_in_memory().with_profile(
+            "dashboard",
+            RateLimitConfig {
+                requests_per_minute: 2,
+                ..Default::default()
+            },
+        );
+        let app = limited_app(limiter, "dashboard");
+
+        for remaining in ["1", "0"] {
+            let response = app
+                .clone()
+                .oneshot(from_ip("/dashboard", "1.2.3.4"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], remaining);
+        }
+
+        let response = app
+            .clone()
+            .oneshot(from_ip("/dashboard", "1.2.3.4"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().get(header::RETRY_AFTER).is_some());
+
+        // Another client has its own counter
+        let response = app.oneshot(from_ip("/dashboard", "5.6.7.8")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_route_profile_unknown_name_uses_default() {
+        let limiter = RateLimiter::new_in_memory().with_profile(
+            plexmcp_shared::rate_limit::DEFAULT_PROFILE,
+            RateLimitConfig {
+                requests_per_minute: 1,
+                ..Default::default()
+            },
+        );
+        let app = limited_app(limiter, "unnamed");
+
+        let response = app
+            .clone()
+            .oneshot(from_ip("/dashboard", "1.2.3.4"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(from_ip("/dashboard", "1.2.3.4")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_route_profile_keeps_handler_headers() {
+        let app = limited_app(RateLimiter::new_in_memory(), "dashboard");
+
+        let response = app.oneshot(from_ip("/own-limit", "1.2.3.4")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], "1");
+    }
+}
@@ -17,7 +17,7 @@
 //! Free tier users are capped at $15/mo in add-ons to encourage Pro upgrade at $29/mo.
 
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
@@ -466,11 +466,19 @@ pub async fn enable_addon(
     }))
 }
 
+/// Query params for disabling an add-on
+#[derive(Debug, Default, Deserialize)]
+pub struct DisableAddonQuery {
+    /// `immediate` (default, prorated credit) or `period_end` (no credit)
+    pub timing: Option<String>,
+}
+
 /// Disable an add-on for the organization
 pub async fn disable_addon(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
     Path(addon_type): Path<String>,
+    Query(query): Query<DisableAddonQuery>,
 ) -> Result<StatusCode, ApiError> {
     let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
 
@@ -483,6 +491,12 @@ pub async fn disable_addon(
     let addon = plexmcp_billing::AddonType::from_str(&addon_type)
         .ok_or_else(|| ApiError::BadRequest(format!("Invalid addon type: {}", addon_type)))?;
 
+    let timing = match query.timing.as_deref() {
+        None => plexmcp_billing::AddonRemovalTiming::default(),
+        Some(t) => plexmcp_billing::AddonRemovalTiming::from_str(t)
+            .ok_or_else(|| ApiError::BadRequest(format!("Invalid removal timing: {}", t)))?,
+    };
+
     // Create AddonService and disable
     let addon_service = plexmcp_billing::AddonService::new(
         billing.subscriptions.stripe().clone(),
@@ -490,7 +504,7 @@ pub async fn disable_addon(
     );
 
     addon_service
-        .disable_addon(org_id, addon)
+        .disable_addon(org_id, addon, timing)
         .await
         .map_err(|e| ApiError::Database(format!("Failed to disable addon: {}", e)))?;
 
@@ -16,7 +16,7 @@ use uuid::Uuid;
 
 use crate::{
     audit_constants::{admin_action, event_type, severity, target_type},
-    auth::AuthUser,
+    auth::{evict_cached_user, sessions, AuthUser},
     error::{ApiError, ApiResult},
     routes::extract_client_ip,
     state::AppState,
@@ -234,6 +234,9 @@ pub struct UpdateUserRequest {
     pub billing_interval: Option<String>,
     /// Custom price in cents for Enterprise tier
     pub custom_price_cents: Option<i64>,
+    /// ISO 4217 currency of the custom Enterprise price (default: usd)
+    #[cfg(feature = "billing")]
+    pub currency: Option<String>,
     /// Scheduled start date for subscription (ISO 8601 format)
     pub subscription_start_date: Option<String>,
     /// Payment method: "immediate", "invoice", or "trial"
@@ -243,6 +246,16 @@ pub struct UpdateUserRequest {
     /// For immediate downgrades: "refund" (money back to payment method) or "credit" (Stripe account credit)
     /// Defaults to "credit" for backwards compatibility
     pub refund_type: Option<String>,
+    /// Stripe reason code for refunds: "duplicate", "fraudulent" or "requested_by_customer" (default)
+    #[cfg(feature = "billing")]
+    pub refund_reason: Option<plexmcp_billing::RefundReason>,
+    /// Don't email the customer about a refund or credit from this change
+    #[cfg(feature = "billing")]
+    #[serde(default)]
+    pub skip_email: bool,
+    /// Memo, footer and custom fields (e.g. PO number) for invoice payment method
+    #[cfg(feature = "billing")]
+    pub invoice_details: Option<plexmcp_billing::InvoiceDetails>,
 }
 
 #[derive(Debug, Serialize)]
@@ -1093,18 +1106,27 @@ pub async fn update_user(
                     skip_payment_validation: req.trial_days.is_some(),
                     billing_interval: req.billing_interval.clone(),
                     custom_price_cents: req.custom_price_cents,
+                    currency: req.currency.clone(),
                     subscription_start_date: start_date,
                     payment_method: req.payment_method.clone(),
                     admin_user_id: Some(admin_user_id),
                     downgrade_timing: req.downgrade_timing.clone(),
                     refund_type: req.refund_type.clone(),
+                    refund_reason: req.refund_reason,
+                    skip_email: req.skip_email,
+                    invoice_details: req.invoice_details.clone(),
                 }
             ).await.map_err(|e| {
                 match e {
                     plexmcp_billing::BillingError::PaymentMethodRequired => ApiError::Validation(
                         "Cannot upgrade: Organization has no payment method. User must add a payment method before upgrading to a paid tier. Alternatively, specify trial_days to grant a trial period.".to_string()
                     ),
-                    plexmcp_billing::BillingError::InvalidTier(msg) => ApiError::Validation(msg),
+                    plexmcp_billing::BillingError::InvalidTier(msg)
+                    | plexmcp_billing::BillingError::InvalidInput(msg)
+                    | plexmcp_billing::BillingError::InvalidAmount(msg) => ApiError::Validation(msg),
+                    plexmcp_billing::BillingError::CurrencyMismatch { .. } => {
+                        ApiError::Validation(e.to_string())
+                    }
                     _ => ApiError::Database(format!("Billing error: {}", e)),
                 }
             })?
@@ -1425,7 +1447,12 @@ pub async fn revoke_user_sessions(
     .execute(&state.pool)
     .await?;
 
-    let sessions_revoked = result.rows_affected() as i64;
+    // JWT sessions (including refresh tokens) live in user_sessions
+    let jwt_sessions_revoked =
+        sessions::revoke_all_for_user(&state.pool, user_id, "admin_revoked", None).await?;
+    evict_cached_user(&state.token_cache, user_id, None, None).await;
+
+    let sessions_revoked = (result.rows_affected() + jwt_sessions_revoked) as i64;
 
     log_admin_action(
         &state.pool,
@@ -1506,7 +1533,12 @@ pub async fn force_password_reset(
     .execute(&state.pool)
     .await?;
 
-    let sessions_revoked = result.rows_affected() as i64;
+    // JWT sessions (including refresh tokens) live in user_sessions
+    let jwt_sessions_revoked =
+        sessions::revoke_all_for_user(&state.pool, user_id, "admin_password_reset", None).await?;
+    evict_cached_user(&state.token_cache, user_id, None, None).await;
+
+    let sessions_revoked = (result.rows_affected() + jwt_sessions_revoked) as i64;
 
     log_admin_action(
         &state.pool,
@@ -1704,7 +1736,12 @@ pub async fn suspend_user(
     .execute(&state.pool)
     .await?;
 
-    let sessions_revoked = result.rows_affected() as i64;
+    // JWT sessions (including refresh tokens) live in user_sessions
+    let jwt_sessions_revoked =
+        sessions::revoke_all_for_user(&state.pool, user_id, "user_suspended", None).await?;
+    evict_cached_user(&state.token_cache, user_id, None, None).await;
+
+    let sessions_revoked = (result.rows_affected() + jwt_sessions_revoked) as i64;
 
     log_admin_action(
         &state.pool,
@@ -2559,6 +2596,117 @@ pub async fn get_org_overages(
     }))
 }
 
+// =============================================================================
+// Scheduled Job Routes
+// =============================================================================
+
+/// Query parameters for job run history
+#[derive(Debug, Deserialize)]
+pub struct JobRunsQuery {
+    pub limit: Option<i64>,
+}
+
+/// Response for scheduled job run listings
+#[derive(Debug, Serialize)]
+pub struct JobRunsResponse {
+    pub runs: Vec<plexmcp_shared::JobRun>,
+}
+
+/// Get the last run of every scheduled worker job
+///
+/// Read-only, so staff can access it too.
+pub async fn list_last_job_runs(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<Json<JobRunsResponse>> {
+    require_platform_admin(&state, &auth_user, false).await?;
+
+    let runs = plexmcp_shared::job_runs::list_last_job_runs(&state.pool).await?;
+
+    Ok(Json(JobRunsResponse { runs }))
+}
+
+/// Get recent runs of a single scheduled worker job, newest first
+pub async fn list_job_runs(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(job_name): Path<String>,
+    Query(query): Query<JobRunsQuery>,
+) -> ApiResult<Json<JobRunsResponse>> {
+    require_platform_admin(&state, &auth_user, false).await?;
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let runs = plexmcp_shared::job_runs::list_job_runs(&state.pool, &job_name, limit).await?;
+
+    Ok(Json(JobRunsResponse { runs }))
+}
+
+// =============================================================================
+// Webhook Queue Routes
+// =============================================================================
+
+/// Query parameters for listing dead-lettered webhooks
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Response for dead-lettered webhook listings
+#[derive(Debug, Serialize)]
+pub struct DeadLetterWebhooksResponse {
+    pub webhooks: Vec<plexmcp_shared::webhook_queue::DeadLetterWebhook>,
+}
+
+/// List queued webhooks that exhausted their retries
+///
+/// Read-only, so staff can access it too.
+pub async fn list_dead_letter_webhooks(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<DeadLetterQuery>,
+) -> ApiResult<Json<DeadLetterWebhooksResponse>> {
+    require_platform_admin(&state, &auth_user, false).await?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let webhooks =
+        plexmcp_shared::webhook_queue::list_dead_letters(&state.pool, limit, offset).await?;
+
+    Ok(Json(DeadLetterWebhooksResponse { webhooks }))
+}
+
+/// Response for requeueing a dead-lettered webhook
+#[derive(Debug, Serialize)]
+pub struct RequeueWebhookResponse {
+    pub queue_id: Uuid,
+    pub message: String,
+}
+
+/// Requeue a dead-lettered webhook with a fresh retry budget
+pub async fn requeue_dead_letter_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(queue_id): Path<Uuid>,
+) -> ApiResult<Json<RequeueWebhookResponse>> {
+    let admin_user_id = require_platform_admin(&state, &auth_user, true).await?;
+
+    if !plexmcp_shared::webhook_queue::requeue_dead_letter(&state.pool, queue_id).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    tracing::info!(
+        admin_id = %admin_user_id,
+        queue_id = %queue_id,
+        "Admin requeued dead-lettered webhook"
+    );
+
+    Ok(Json(RequeueWebhookResponse {
+        queue_id,
+        message: "Webhook requeued; the worker processes it on its next run".to_string(),
+    }))
+}
+
 // =============================================================================
 // Billing Diagnostics Routes (requires billing feature)
 // =============================================================================
@@ -2642,6 +2790,232 @@ pub async fn check_billing_invariants(
     }))
 }
 
+/// Query parameters for tier change history
+#[cfg(feature = "billing")]
+#[derive(Debug, Deserialize)]
+pub struct TierHistoryQuery {
+    pub limit: Option<i64>,
+    /// RFC3339 `created_at` from a previous page's `next_cursor`
+    pub before: Option<String>,
+    /// `id` from a previous page's `next_cursor`; required with `before`
+    pub before_id: Option<Uuid>,
+}
+
+/// Response for tier change history
+#[cfg(feature = "billing")]
+#[derive(Debug, Serialize)]
+pub struct TierHistoryResponse {
+    pub org_id: Uuid,
+    pub records: Vec<plexmcp_billing::TierChangeAuditRecord>,
+    /// `None` when this was the last page
+    pub next_cursor: Option<plexmcp_billing::TierChangeHistoryCursor>,
+}
+
+/// Get an organization's tier change audit trail, newest first
+#[cfg(feature = "billing")]
+pub async fn get_tier_change_history(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(org_id): Path<Uuid>,
+    Query(query): Query<TierHistoryQuery>,
+) -> ApiResult<Json<TierHistoryResponse>> {
+    // Read-only, so staff can access too
+    require_platform_admin(&state, &auth_user, false).await?;
+
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    let before = query
+        .before
+        .as_deref()
+        .map(|s| OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339))
+        .transpose()
+        .map_err(|_| {
+            ApiError::Validation("Invalid 'before' date format. Use RFC3339".to_string())
+        })?;
+    let before = match (before, query.before_id) {
+        (Some(created_at), Some(id)) => {
+            Some(plexmcp_billing::TierChangeHistoryCursor { created_at, id })
+        }
+        (None, None) => None,
+        _ => {
+            return Err(ApiError::Validation(
+                "'before' and 'before_id' must be given together".to_string(),
+            ))
+        }
+    };
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+    let records = billing
+        .subscriptions
+        .tier_change_history_before(org_id, limit, before)
+        .await
+        .map_err(|e| {
+            tracing::error!(%org_id, error = %e, "Failed to load tier change history");
+            ApiError::Internal
+        })?;
+    let next_cursor = plexmcp_billing::tier_change_history_cursor(&records, limit);
+
+    Ok(Json(TierHistoryResponse {
+        org_id,
+        records,
+        next_cursor,
+    }))
+}
+
+/// Re-run the stored original payload of a Stripe webhook event
+#[cfg(feature = "billing")]
+pub async fn replay_stripe_webhook(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(stripe_event_id): Path<String>,
+) -> ApiResult<Json<plexmcp_billing::WebhookReplayResult>> {
+    let admin_user_id = require_platform_admin(&state, &auth_user, true).await?;
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    tracing::info!(
+        admin_id = %admin_user_id,
+        stripe_event_id = %stripe_event_id,
+        "Admin replaying stored webhook payload"
+    );
+
+    let result = billing
+        .webhooks
+        .replay_event(&stripe_event_id)
+        .await
+        .map_err(|e| match e {
+            plexmcp_billing::BillingError::NotFound(_) => ApiError::NotFound,
+            plexmcp_billing::BillingError::InvalidInput(msg) => ApiError::Validation(msg),
+            e => {
+                tracing::error!(
+                    stripe_event_id = %stripe_event_id,
+                    error = %e,
+                    "Failed to replay webhook"
+                );
+                ApiError::Internal
+            }
+        })?;
+
+    Ok(Json(result))
+}
+
+/// Response for an on-demand overage recalculation
+#[cfg(feature = "billing")]
+#[derive(Debug, Serialize)]
+pub struct RecalculateOverageResponse {
+    pub org_id: Uuid,
+    /// Current pending overage charge, or None when within limits
+    pub charge: Option<plexmcp_billing::OverageCharge>,
+}
+
+/// Recalculate one org's current overage (same logic as the worker job)
+#[cfg(feature = "billing")]
+pub async fn recalculate_org_overage(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(org_id): Path<Uuid>,
+) -> ApiResult<Json<RecalculateOverageResponse>> {
+    let admin_user_id = require_platform_admin(&state, &auth_user, true).await?;
+
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    tracing::info!(
+        admin_id = %admin_user_id,
+        org_id = %org_id,
+        "Admin recalculating org overage"
+    );
+
+    let charge = billing
+        .overage
+        .recalculate(org_id, &billing.spend_cap)
+        .await
+        .map_err(|e| {
+            tracing::error!(%org_id, error = %e, "Failed to recalculate overage");
+            ApiError::Internal
+        })?;
+
+    Ok(Json(RecalculateOverageResponse { org_id, charge }))
+}
+
+/// Query for an on-demand usage aggregation
+#[cfg(feature = "billing")]
+#[derive(Debug, Deserialize)]
+pub struct AggregateUsageQuery {
+    /// How far back to re-aggregate, in hours (default 24, max 90 days)
+    pub hours: Option<i64>,
+}
+
+/// Response for an on-demand usage aggregation
+#[cfg(feature = "billing")]
+#[derive(Debug, Serialize)]
+pub struct AggregateUsageResponse {
+    pub org_id: Uuid,
+    #[serde(with = "time::serde::rfc3339")]
+    pub since: OffsetDateTime,
+    /// Hourly usage_aggregates rows written
+    pub rows_written: u64,
+}
+
+/// Re-aggregate one org's usage records now instead of waiting for the hourly task
+#[cfg(feature = "billing")]
+pub async fn aggregate_org_usage(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Path(org_id): Path<Uuid>,
+    Query(query): Query<AggregateUsageQuery>,
+) -> ApiResult<Json<AggregateUsageResponse>> {
+    let admin_user_id = require_platform_admin(&state, &auth_user, true).await?;
+
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    let hours = query.hours.unwrap_or(24).clamp(1, 24 * 90);
+    let since = OffsetDateTime::now_utc() - time::Duration::hours(hours);
+
+    tracing::info!(
+        admin_id = %admin_user_id,
+        org_id = %org_id,
+        hours = hours,
+        "Admin triggering usage aggregation"
+    );
+
+    let rows_written = billing
+        .usage
+        .aggregate_for_org(org_id, since)
+        .await
+        .map_err(|e| {
+            tracing::error!(%org_id, error = %e, "Failed to aggregate usage");
+            ApiError::Internal
+        })?;
+
+    Ok(Json(AggregateUsageResponse {
+        org_id,
+        since,
+        rows_written,
+    }))
+}
+
+/// Preview grace period enforcement without blocking or unblocking anyone
+#[cfg(feature = "billing")]
+pub async fn preview_grace_period_enforcement(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+) -> ApiResult<Json<plexmcp_billing::GracePeriodEnforcement>> {
+    let admin_user_id = require_platform_admin(&state, &auth_user, false).await?;
+
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    tracing::info!(
+        admin_id = %admin_user_id,
+        "Admin previewing grace period enforcement"
+    );
+
+    let preview = billing.grace_period.enforce(true).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to preview grace period enforcement");
+        ApiError::Internal
+    })?;
+
+    Ok(Json(preview))
+}
+
 /// Response for billing debug endpoint
 #[cfg(feature = "billing")]
 #[derive(Debug, Serialize)]
@@ -340,7 +340,7 @@ fn parse_date_range(query: &AnalyticsQuery) -> (OffsetDateTime, OffsetDateTime,
 }
 
 /// Parse user agent for device type and browser
-fn parse_user_agent(ua: &str) -> (String, Option<String>, Option<String>) {
+pub(crate) fn parse_user_agent(ua: &str) -> (String, Option<String>, Option<String>) {
     let ua_lower = ua.to_lowercase();
 
     // Device type
@@ -484,23 +484,35 @@ fn detect_bot(user_agent: &str, ip: &str) -> BotDetectionResult {
     }
 }
 
+/// Approximate location of an IP address
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct IpLocation {
+    pub country_code: Option<String>,
+    pub region_code: Option<String>,
+    pub city: Option<String>,
+}
+
 /// Perform IP geolocation lookup using MaxMind database
 /// Returns (country_code, region_code) or (None, None) on failure
 fn geolocate_ip(
     geoip_reader: &Option<Arc<Reader<Vec<u8>>>>,
     ip_str: &str,
 ) -> (Option<String>, Option<String>) {
+    let location = lookup_location(geoip_reader, ip_str).unwrap_or_default();
+    (location.country_code, location.region_code)
+}
+
+/// Look up an IP in the MaxMind City database
+/// Returns None if no database is loaded, the IP is private, or it isn't found
+pub(crate) fn lookup_location(
+    geoip_reader: &Option<Arc<Reader<Vec<u8>>>>,
+    ip_str: &str,
+) -> Option<IpLocation> {
     // Early return if no database available
-    let reader = match geoip_reader {
-        Some(r) => r,
-        None => return (None, None),
-    };
+    let reader = geoip_reader.as_ref()?;
 
     // Parse IP address
-    let ip: IpAddr = match ip_str.parse() {
-        Ok(ip) => ip,
-        Err(_) => return (None, None),
-    };
+    let ip: IpAddr = ip_str.parse().ok()?;
 
     // Skip private/local IPs (127.0.0.1, 192.168.x.x, etc.)
     let is_private = match ip {
@@ -508,28 +520,20 @@ fn geolocate_ip(
         IpAddr::V6(ipv6) => ipv6.is_loopback(),
     };
     if is_private {
-        return (None, None);
+        return None;
     }
 
-    // Lookup IP in database
-    let lookup_result = match reader.lookup(ip) {
-        Ok(result) => result,
-        Err(_) => return (None, None),
-    };
-
-    // Decode as City data
-    let city = match lookup_result.decode::<geoip2::City>() {
-        Ok(Some(city)) => city,
-        _ => return (None, None),
-    };
-
-    let country_code = city.country.iso_code.map(|s| s.to_string());
-    let region_code = city
-        .subdivisions
-        .first()
-        .and_then(|sub| sub.iso_code.map(|s| s.to_string()));
-
-    (country_code, region_code)
+    // Lookup IP in database and decode as City data
+    let city = reader.lookup(ip).ok()?.decode::<geoip2::City>().ok()??;
+
+    Some(IpLocation {
+        country_code: city.country.iso_code.map(|s| s.to_string()),
+        region_code: city
+            .subdivisions
+            .first()
+            .and_then(|sub| sub.iso_code.map(|s| s.to_string())),
+        city: city.city.names.english.map(|s| s.to_string()),
+    })
 }
 
 /// Check if admin
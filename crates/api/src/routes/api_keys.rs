@@ -2,7 +2,7 @@
 
 use axum::{
     extract::{Extension, Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     Json,
 };
 use serde::{Deserialize, Serialize};
@@ -10,8 +10,10 @@ use sqlx::FromRow;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use super::auth::{extract_auth_audit_context, log_auth_event};
 use crate::{
-    auth::AuthUser,
+    audit_constants::{auth_event, event_type, severity},
+    auth::{api_key::ApiKeyError, scopes, AuthUser},
     error::{ApiError, ApiResult},
     state::AppState,
 };
@@ -24,6 +26,7 @@ use plexmcp_shared::types::{CustomLimits, EffectiveLimits, SubscriptionTier};
 #[derive(Debug, Deserialize)]
 pub struct CreateApiKeyRequest {
     pub name: String,
+    /// [`Scope`](crate::auth::Scope) names restricting the key; none means full access
     #[serde(default)]
     pub scopes: Vec<String>,
     pub rate_limit_rpm: Option<i32>,
@@ -134,6 +137,9 @@ pub struct ApiKeyRotatedResponse {
     pub old_key_prefix: String,
     #[serde(with = "time::serde::rfc3339")]
     pub rotated_at: OffsetDateTime,
+    /// Until when the old key keeps working (null if it was revoked immediately)
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub old_key_valid_until: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Serialize)]
@@ -309,6 +315,7 @@ pub async fn get_api_key(
 pub async fn create_api_key(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: HeaderMap,
     Json(req): Json<CreateApiKeyRequest>,
 ) -> ApiResult<(StatusCode, Json<ApiKeyCreatedResponse>)> {
     let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
@@ -382,6 +389,8 @@ pub async fn create_api_key(
             .map(|days| OffsetDateTime::now_utc() + time::Duration::days(days as i64))
     };
 
+    scopes::validate_scopes(&req.scopes).map_err(ApiError::Validation)?;
+
     // Insert into database
     let key_id = Uuid::new_v4();
     let scopes_json = serde_json::to_value(&req.scopes).unwrap_or_default();
@@ -437,6 +446,29 @@ pub async fn create_api_key(
         }
     }
 
+    let (ip_address, user_agent) = extract_auth_audit_context(&headers);
+    log_auth_event(
+        &state.pool,
+        auth_user.user_id,
+        auth_event::API_KEY_CREATED,
+        auth_user.email.clone(),
+        Some(serde_json::json!({
+            "key_id": key_id,
+            "org_id": org_id,
+            "key_prefix": key_prefix,
+            "scopes": req.scopes,
+            "expires_at": expires_at.map(|t| t.unix_timestamp()),
+        })),
+        event_type::SECURITY_SETTING,
+        severity::INFO,
+        ip_address,
+        user_agent,
+        true,
+        None,
+        None,
+    )
+    .await?;
+
     Ok((
         StatusCode::CREATED,
         Json(ApiKeyCreatedResponse {
@@ -504,6 +536,7 @@ pub async fn update_api_key(
 
     // Update scopes if provided
     if let Some(ref scopes) = req.scopes {
+        scopes::validate_scopes(scopes).map_err(ApiError::Validation)?;
         let scopes_json = serde_json::to_value(scopes).unwrap_or_default();
         sqlx::query("UPDATE api_keys SET scopes = $1 WHERE id = $2")
             .bind(&scopes_json)
@@ -631,10 +664,11 @@ pub async fn update_api_key(
     }))
 }
 
-/// Rotate an API key (generate new secret, invalidate old)
+/// Rotate an API key (generate new secret, old one expires after the grace window)
 pub async fn rotate_api_key(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
+    headers: HeaderMap,
     Path(key_id): Path<Uuid>,
     body: Option<Json<RotateApiKeyRequest>>,
 ) -> ApiResult<Json<ApiKeyRotatedResponse>> {
@@ -668,12 +702,6 @@ pub async fn rotate_api_key(
 
     let old_prefix = current.key_prefix.clone();
 
-    // Generate new key
-    let (full_key, key_hash, key_prefix) = state
-        .api_key_manager
-        .generate_key()
-        .map_err(|_| ApiError::Internal)?;
-
     // If PIN is provided, verify it BEFORE rotating the key
     if let Some(Json(ref req)) = body {
         if let Some(ref pin) = req.pin {
@@ -687,15 +715,16 @@ pub async fn rotate_api_key(
         }
     }
 
-    // Update the key hash and prefix, clear encrypted_key since it's a new key
-    sqlx::query(
-        "UPDATE api_keys SET key_hash = $1, key_prefix = $2, request_count = 0, encrypted_key = NULL, key_nonce = NULL WHERE id = $3"
-    )
-    .bind(&key_hash)
-    .bind(&key_prefix)
-    .bind(key_id)
-    .execute(&state.pool)
-    .await?;
+    let rotated = state
+        .api_key_manager
+        .rotate(&state.pool, key_id)
+        .await
+        .map_err(|e| match e {
+            ApiKeyError::NotFound => ApiError::NotFound,
+            ApiKeyError::Database(e) => e.into(),
+            ApiKeyError::HmacInitFailed => ApiError::Internal,
+        })?;
+    let full_key = rotated.full_key;
 
     // If PIN is provided, encrypt and store the new key for later reveal
     if let Some(Json(req)) = body {
@@ -711,13 +740,37 @@ pub async fn rotate_api_key(
         }
     }
 
+    let (ip_address, user_agent) = extract_auth_audit_context(&headers);
+    log_auth_event(
+        &state.pool,
+        auth_user.user_id,
+        auth_event::API_KEY_ROTATED,
+        auth_user.email.clone(),
+        Some(serde_json::json!({
+            "key_id": key_id,
+            "org_id": org_id,
+            "key_prefix": rotated.key_prefix,
+            "old_key_prefix": old_prefix,
+            "old_key_valid_until": rotated.old_key_valid_until.map(|t| t.unix_timestamp()),
+        })),
+        event_type::SECURITY_SETTING,
+        severity::WARNING,
+        ip_address,
+        user_agent,
+        true,
+        None,
+        None,
+    )
+    .await?;
+
     Ok(Json(ApiKeyRotatedResponse {
         id: key_id,
         name: current.name,
         key: full_key,
-        key_prefix,
+        key_prefix: rotated.key_prefix,
         old_key_prefix: old_prefix,
         rotated_at: OffsetDateTime::now_utc(),
+        old_key_valid_until: rotated.old_key_valid_until,
     }))
 }
 
@@ -1,7 +1,7 @@
 //! Authentication routes
 
 use axum::{
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     Json,
 };
@@ -13,10 +13,12 @@ use uuid::Uuid;
 use crate::{
     audit_constants::{auth_event, event_type, severity},
     auth::{
-        generate_impossible_hash, hash_password, sessions, totp, validate_password_strength,
-        verify_password, AuthUser, TokenManager, VerificationTokenType,
+        breach_count, evict_cached_user, extract_bearer_token, generate_impossible_hash,
+        hash_password, sessions, totp, validate_password_strength, verify_password, AuthUser,
+        PasswordValidationError, TokenError, TokenManager, VerificationTokenType,
     },
     error::{ApiError, ApiResult},
+    rate_limit::RateLimitHeaders,
     state::AppState,
 };
 
@@ -325,25 +327,29 @@ pub async fn register(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<RegisterRequest>,
-) -> ApiResult<(StatusCode, Json<AuthResponse>)> {
+) -> ApiResult<(StatusCode, RateLimitHeaders, Json<AuthResponse>)> {
     // Extract audit context
     let (ip_address, user_agent) = extract_auth_audit_context(&headers);
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit registration by IP to prevent mass account creation
     if let Some(ip) = &ip_address {
         match state.rate_limiter.check_registration(ip).await {
             Ok(result) if !result.allowed => {
                 tracing::warn!(ip = %ip, "register: Rate limit exceeded for IP");
-                let retry_after = result.retry_after_seconds.unwrap_or(60);
-                return Err(ApiError::TooManyRequests(format!(
-                    "Too many registration attempts. Please try again in {} seconds.",
-                    retry_after
-                )));
+                let retry_after = result.retry_after_secs.unwrap_or(60);
+                return Err(ApiError::TooManyRequests {
+                    message: format!(
+                        "Too many registration attempts. Please try again in {} seconds.",
+                        retry_after
+                    ),
+                    retry_after_secs: Some(retry_after),
+                });
             }
             Err(e) => {
                 tracing::error!(error = ?e, "register: Rate limit check failed, allowing request");
             }
-            _ => {}
+            Ok(result) => rate_limit = Some(result),
         }
     }
 
@@ -360,7 +366,7 @@ pub async fn register(
     }
 
     // Validate password strength
-    validate_password_strength(&req.password).map_err(|e| ApiError::Validation(e.to_string()))?;
+    validate_new_password(&state, &req.password).await?;
 
     // Validate org name
     if req.org_name.trim().is_empty() || req.org_name.len() > 100 {
@@ -448,9 +454,10 @@ pub async fn register(
     tx.commit().await?;
 
     // Generate tokens
+    let family_id = Uuid::new_v4(); // New refresh token family for this login
     let (access_token, access_jti, refresh_token, refresh_jti) = state
         .jwt_manager
-        .generate_token_pair(user_id, org_id, "owner", &req.email)
+        .generate_token_pair(user_id, org_id, "owner", &req.email, family_id)
         .map_err(|_| ApiError::Internal)?;
 
     // Save session for revocation support
@@ -459,11 +466,13 @@ pub async fn register(
     let refresh_expires_at = OffsetDateTime::now_utc() + Duration::days(30);
     sessions::save_session(
         &state.pool,
+        &state.geoip_reader,
         user_id,
         &access_jti,
         access_expires_at,
         &refresh_jti,
         refresh_expires_at,
+        family_id,
         ip_address.as_deref(),
         user_agent.as_deref(),
     )
@@ -520,6 +529,7 @@ pub async fn register(
 
     Ok((
         StatusCode::CREATED,
+        RateLimitHeaders(rate_limit),
         Json(AuthResponse {
             access_token,
             refresh_token,
@@ -549,7 +559,7 @@ pub async fn login(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<LoginRequest>,
-) -> ApiResult<Json<LoginResponse>> {
+) -> ApiResult<(RateLimitHeaders, Json<LoginResponse>)> {
     // SOC 2 CC6.1: Start timing for constant-time response
     let start = std::time::Instant::now();
     const MIN_RESPONSE_TIME: std::time::Duration = std::time::Duration::from_millis(500);
@@ -571,32 +581,36 @@ async fn login_inner(
     state: &AppState,
     headers: &HeaderMap,
     req: &LoginRequest,
-) -> ApiResult<Json<LoginResponse>> {
+) -> ApiResult<(RateLimitHeaders, Json<LoginResponse>)> {
     tracing::info!(email = %req.email, "login: Starting login attempt");
 
     // Extract audit context
     let (ip_address, user_agent) = extract_auth_audit_context(headers);
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit login attempts by IP to prevent brute force attacks
     if let Some(ip) = &ip_address {
         match state.rate_limiter.check_auth_by_ip(ip).await {
             Ok(result) if !result.allowed => {
                 tracing::warn!(
                     ip = %ip,
-                    retry_after = ?result.retry_after_seconds,
+                    retry_after = ?result.retry_after_secs,
                     "login: Rate limit exceeded for IP"
                 );
-                let retry_after = result.retry_after_seconds.unwrap_or(60);
-                return Err(ApiError::TooManyRequests(format!(
-                    "Too many login attempts. Please try again in {} seconds.",
-                    retry_after
-                )));
+                let retry_after = result.retry_after_secs.unwrap_or(60);
+                return Err(ApiError::TooManyRequests {
+                    message: format!(
+                        "Too many login attempts. Please try again in {} seconds.",
+                        retry_after
+                    ),
+                    retry_after_secs: Some(retry_after),
+                });
             }
             Err(e) => {
                 tracing::error!(error = ?e, "login: Rate limit check failed, allowing request");
                 // Fail open for rate limiting errors to avoid blocking legitimate users
             }
-            _ => {}
+            Ok(result) => rate_limit = Some(result),
         }
     }
 
@@ -731,13 +745,16 @@ async fn login_inner(
         .execute(&state.pool)
         .await?;
 
-        return Ok(Json(LoginResponse::TwoFactorRequired(
-            TwoFactorRequiredResponse {
-                requires_2fa: true,
-                temp_token,
-                user_id: user.id,
-            },
-        )));
+        return Ok((
+            RateLimitHeaders(rate_limit),
+            Json(LoginResponse::TwoFactorRequired(
+                TwoFactorRequiredResponse {
+                    requires_2fa: true,
+                    temp_token,
+                    user_id: user.id,
+                },
+            )),
+        ));
     }
 
     // No 2FA - proceed with normal login
@@ -752,9 +769,10 @@ async fn login_inner(
     tracing::info!(user_id = %user.id, "login: Updated last_login_at");
 
     // Generate tokens
+    let family_id = Uuid::new_v4(); // New refresh token family for this login
     let (access_token, access_jti, refresh_token, refresh_jti) = state
         .jwt_manager
-        .generate_token_pair(user.id, user.org_id, &user.role, &user.email)
+        .generate_token_pair(user.id, user.org_id, &user.role, &user.email, family_id)
         .map_err(|e| {
             tracing::error!(error = ?e, "login: JWT generation failed");
             ApiError::Internal
@@ -766,11 +784,13 @@ async fn login_inner(
     let refresh_expires_at = OffsetDateTime::now_utc() + Duration::days(30);
     sessions::save_session(
         &state.pool,
+        &state.geoip_reader,
         user.id,
         &access_jti,
         access_expires_at,
         &refresh_jti,
         refresh_expires_at,
+        family_id,
         ip_address.as_deref(),
         user_agent.as_deref(),
     )
@@ -795,22 +815,25 @@ async fn login_inner(
     )
     .await?;
 
-    Ok(Json(LoginResponse::Success(AuthResponse {
-        access_token,
-        refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: state.jwt_manager.access_token_expiry_seconds(),
-        user: UserResponse {
-            id: user.id,
-            email: user.email,
-            role: user.role,
-            org_id: user.org_id,
-            org_name: user.org_name,
-            is_admin: user.is_admin,
-            platform_role: user.platform_role,
-        },
-        device_token: None,
-    })))
+    Ok((
+        RateLimitHeaders(rate_limit),
+        Json(LoginResponse::Success(AuthResponse {
+            access_token,
+            refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: state.jwt_manager.access_token_expiry_seconds(),
+            user: UserResponse {
+                id: user.id,
+                email: user.email,
+                role: user.role,
+                org_id: user.org_id,
+                org_name: user.org_name,
+                is_admin: user.is_admin,
+                platform_role: user.platform_role,
+            },
+            device_token: None,
+        })),
+    ))
 }
 
 /// Complete login with 2FA code
@@ -819,12 +842,13 @@ pub async fn login_2fa(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<Login2FARequest>,
-) -> ApiResult<Json<AuthResponse>> {
+) -> ApiResult<(RateLimitHeaders, Json<AuthResponse>)> {
     // Extract audit context
     let (ip_address, user_agent) = extract_auth_audit_context(&headers);
 
     let token_hash = totp::hash_token(&req.temp_token);
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit 2FA attempts to prevent bypass attacks
     // Use token hash as identifier to rate limit per-token attempts
     match state.rate_limiter.check_2fa_attempts(&token_hash).await {
@@ -833,16 +857,19 @@ pub async fn login_2fa(
                 token_hash_prefix = %&token_hash[..20],
                 "login_2fa: Rate limit exceeded for token"
             );
-            let retry_after = result.retry_after_seconds.unwrap_or(60);
-            return Err(ApiError::TooManyRequests(format!(
-                "Too many 2FA attempts. Please try again in {} seconds.",
-                retry_after
-            )));
+            let retry_after = result.retry_after_secs.unwrap_or(60);
+            return Err(ApiError::TooManyRequests {
+                message: format!(
+                    "Too many 2FA attempts. Please try again in {} seconds.",
+                    retry_after
+                ),
+                retry_after_secs: Some(retry_after),
+            });
         }
         Err(e) => {
             tracing::error!(error = ?e, "login_2fa: Rate limit check failed, allowing request");
         }
-        _ => {}
+        Ok(result) => rate_limit = Some(result),
     }
 
     // DEBUG: Log the incoming request
@@ -928,10 +955,13 @@ pub async fn login_2fa(
     if let Some(locked_until) = tfa.locked_until {
         if locked_until > OffsetDateTime::now_utc() {
             let remaining = (locked_until - OffsetDateTime::now_utc()).whole_minutes();
-            return Err(ApiError::TooManyRequests(format!(
-                "Too many failed attempts. Try again in {} minutes.",
-                remaining + 1
-            )));
+            return Err(ApiError::TooManyRequests {
+                message: format!(
+                    "Too many failed attempts. Try again in {} minutes.",
+                    remaining + 1
+                ),
+                retry_after_secs: Some(((remaining + 1) * 60) as u64),
+            });
         }
     }
 
@@ -1004,53 +1034,20 @@ pub async fn login_2fa(
     let verified = if code_valid {
         true
     } else {
-        // Try backup codes
-        #[derive(Debug, FromRow)]
-        struct BackupCodeRow {
-            id: Uuid,
-            code_hash: String,
-        }
-
-        let backup_codes: Vec<BackupCodeRow> = sqlx::query_as(
-            "SELECT id, code_hash FROM user_2fa_backup_codes WHERE user_id = $1 AND used_at IS NULL"
-        )
-        .bind(user_id)
-        .fetch_all(&state.pool)
-        .await?;
-
-        let mut matched_code_id: Option<Uuid> = None;
-        for bc in &backup_codes {
-            if totp::verify_backup_code(&req.code, &bc.code_hash).unwrap_or(false) {
-                matched_code_id = Some(bc.id);
-                break;
-            }
-        }
-
-        if let Some(code_id) = matched_code_id {
-            // Mark backup code as used
-            sqlx::query("UPDATE user_2fa_backup_codes SET used_at = NOW() WHERE id = $1")
-                .bind(code_id)
-                .execute(&state.pool)
-                .await?;
-
-            // Count remaining backup codes
-            let remaining: (i64,) = sqlx::query_as(
-                "SELECT COUNT(*) FROM user_2fa_backup_codes WHERE user_id = $1 AND used_at IS NULL",
-            )
-            .bind(user_id)
-            .fetch_one(&state.pool)
-            .await?;
-
+        // Try backup codes (consumed atomically, single use)
+        if let Some(remaining) =
+            super::two_factor::verify_recovery_code(&state.pool, user_id, &req.code).await?
+        {
             tracing::info!(
                 user_id = %user_id,
-                remaining_codes = remaining.0,
+                remaining_codes = remaining,
                 "Backup code used for 2FA login"
             );
 
             // Send email notification about backup code usage (fire and forget)
             let email_service = state.security_email.clone();
             let email_to = user_email.clone();
-            let codes_remaining = remaining.0;
+            let codes_remaining = remaining;
             tokio::spawn(async move {
                 email_service
                     .send_backup_code_used(&email_to, codes_remaining)
@@ -1102,10 +1099,13 @@ pub async fn login_2fa(
                 tracing::error!(user_id = %user_id, error = ?e, "Failed to update lockout status");
             }
 
-            return Err(ApiError::TooManyRequests(format!(
-                "Too many failed attempts. Account locked for {} minutes.",
-                totp::LOCKOUT_DURATION_MINUTES
-            )));
+            return Err(ApiError::TooManyRequests {
+                message: format!(
+                    "Too many failed attempts. Account locked for {} minutes.",
+                    totp::LOCKOUT_DURATION_MINUTES
+                ),
+                retry_after_secs: Some(totp::LOCKOUT_DURATION_MINUTES as u64 * 60),
+            });
         } else {
             // Try to increment failed attempts, but don't fail if DB update fails
             if let Err(e) =
@@ -1274,9 +1274,10 @@ pub async fn login_2fa(
         .await?;
 
     // Generate tokens
+    let family_id = Uuid::new_v4(); // New refresh token family for this login
     let (access_token, access_jti, refresh_token, refresh_jti) = state
         .jwt_manager
-        .generate_token_pair(user.id, user.org_id, &user.role, &user.email)
+        .generate_token_pair(user.id, user.org_id, &user.role, &user.email, family_id)
         .map_err(|_| ApiError::Internal)?;
 
     // Save session for revocation support
@@ -1285,11 +1286,13 @@ pub async fn login_2fa(
     let refresh_expires_at = OffsetDateTime::now_utc() + Duration::days(30);
     sessions::save_session(
         &state.pool,
+        &state.geoip_reader,
         user.id,
         &access_jti,
         access_expires_at,
         &refresh_jti,
         refresh_expires_at,
+        family_id,
         ip_address.as_deref(),
         user_agent.as_deref(),
     )
@@ -1329,22 +1332,25 @@ pub async fn login_2fa(
         None
     };
 
-    Ok(Json(AuthResponse {
-        access_token,
-        refresh_token,
-        token_type: "Bearer".to_string(),
-        expires_in: state.jwt_manager.access_token_expiry_seconds(),
-        user: UserResponse {
-            id: user.id,
-            email: user.email,
-            role: user.role,
-            org_id: user.org_id,
-            org_name: user.org_name,
-            is_admin: user.is_admin,
-            platform_role: user.platform_role,
-        },
-        device_token,
-    }))
+    Ok((
+        RateLimitHeaders(rate_limit),
+        Json(AuthResponse {
+            access_token,
+            refresh_token,
+            token_type: "Bearer".to_string(),
+            expires_in: state.jwt_manager.access_token_expiry_seconds(),
+            user: UserResponse {
+                id: user.id,
+                email: user.email,
+                role: user.role,
+                org_id: user.org_id,
+                org_name: user.org_name,
+                is_admin: user.is_admin,
+                platform_role: user.platform_role,
+            },
+            device_token,
+        }),
+    ))
 }
 
 /// Refresh access token
@@ -1377,10 +1383,19 @@ pub async fn refresh(
     .await?
     .ok_or(ApiError::InvalidToken)?;
 
+    // Rotate: the presented refresh token can't be exchanged again
+    let family_id = sessions::consume_refresh_token(&state.pool, &claims)
+        .await
+        .map_err(|e| match e {
+            TokenError::ReuseDetected => ApiError::TokenReuseDetected,
+            TokenError::DatabaseError => ApiError::Internal,
+            _ => ApiError::InvalidToken,
+        })?;
+
     // Generate new tokens
     let (access_token, access_jti, refresh_token, refresh_jti) = state
         .jwt_manager
-        .generate_token_pair(user.id, user.org_id, &user.role, &user.email)
+        .generate_token_pair(user.id, user.org_id, &user.role, &user.email, family_id)
         .map_err(|_| ApiError::Internal)?;
 
     // Save session for revocation support
@@ -1389,11 +1404,13 @@ pub async fn refresh(
     let refresh_expires_at = OffsetDateTime::now_utc() + Duration::days(30);
     sessions::save_session(
         &state.pool,
+        &state.geoip_reader,
         user.id,
         &access_jti,
         access_expires_at,
         &refresh_jti,
         refresh_expires_at,
+        family_id,
         ip_address.as_deref(),
         user_agent.as_deref(),
     )
@@ -1423,13 +1440,14 @@ pub async fn forgot_password(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<ForgotPasswordRequest>,
-) -> ApiResult<Json<MessageResponse>> {
+) -> ApiResult<(RateLimitHeaders, Json<MessageResponse>)> {
     // Start timing for constant-time response
     let start = std::time::Instant::now();
 
     // Extract audit context
     let (ip_address, user_agent) = extract_auth_audit_context(&headers);
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit password reset requests to prevent abuse
     if let Some(ip) = &ip_address {
         match state.rate_limiter.check_password_reset(ip).await {
@@ -1441,16 +1459,19 @@ pub async fn forgot_password(
                 if elapsed < min_response_time {
                     tokio::time::sleep(min_response_time - elapsed).await;
                 }
-                let retry_after = result.retry_after_seconds.unwrap_or(60);
-                return Err(ApiError::TooManyRequests(format!(
-                    "Too many password reset requests. Please try again in {} seconds.",
-                    retry_after
-                )));
+                let retry_after = result.retry_after_secs.unwrap_or(60);
+                return Err(ApiError::TooManyRequests {
+                    message: format!(
+                        "Too many password reset requests. Please try again in {} seconds.",
+                        retry_after
+                    ),
+                    retry_after_secs: Some(retry_after),
+                });
             }
             Err(e) => {
                 tracing::error!(error = ?e, "forgot_password: Rate limit check failed, allowing request");
             }
-            _ => {}
+            Ok(result) => rate_limit = Some(result),
         }
     }
 
@@ -1518,10 +1539,13 @@ pub async fn forgot_password(
         tokio::time::sleep(min_response_time - elapsed).await;
     }
 
-    Ok(Json(MessageResponse {
-        message: "If an account exists with that email, a password reset link has been sent."
-            .to_string(),
-    }))
+    Ok((
+        RateLimitHeaders(rate_limit),
+        Json(MessageResponse {
+            message: "If an account exists with that email, a password reset link has been sent."
+                .to_string(),
+        }),
+    ))
 }
 
 /// Reset password with token
@@ -1534,7 +1558,7 @@ pub async fn reset_password(
     let (ip_address, user_agent) = extract_auth_audit_context(&headers);
 
     // Validate password strength
-    validate_password_strength(&req.password).map_err(|e| ApiError::Validation(e.to_string()))?;
+    validate_new_password(&state, &req.password).await?;
 
     // Validate and consume password reset token
     let token_manager = TokenManager::new(state.pool.clone());
@@ -1573,6 +1597,17 @@ pub async fn reset_password(
         .invalidate_user_tokens(user_id, VerificationTokenType::PasswordReset)
         .await;
 
+    // Log out every device - whoever triggered the reset may have had a stolen session
+    let revoked_count =
+        sessions::revoke_all_for_user(&state.pool, user_id, "password_reset", None).await?;
+    evict_cached_user(&state.token_cache, user_id, Some(&user_email), None).await;
+
+    tracing::info!(
+        user_id = %user_id,
+        revoked_sessions = %revoked_count,
+        "Revoked all user sessions after password reset"
+    );
+
     // Log password reset completion
     log_auth_event(
         &state.pool,
@@ -1793,8 +1828,7 @@ pub async fn change_password(
     let user_id = auth_user.user_id.ok_or(ApiError::Unauthorized)?;
 
     // Validate new password strength
-    validate_password_strength(&req.new_password)
-        .map_err(|e| ApiError::Validation(e.to_string()))?;
+    validate_new_password(&state, &req.new_password).await?;
 
     // Get current password hash and email
     let user: PasswordEmailRow =
@@ -1824,7 +1858,14 @@ pub async fn change_password(
     // Revoke all existing sessions to force re-authentication
     // This ensures compromised tokens can't be used after password change
     let revoked_count =
-        sessions::revoke_all_sessions(&state.pool, user_id, "password_changed").await?;
+        sessions::revoke_all_for_user(&state.pool, user_id, "password_changed", None).await?;
+    evict_cached_user(
+        &state.token_cache,
+        user_id,
+        auth_user.email.as_deref(),
+        None,
+    )
+    .await;
 
     tracing::info!(
         user_id = %user_id,
@@ -1971,6 +2012,26 @@ pub async fn check_password_strength(
 // Helpers
 // =============================================================================
 
+/// Checks a new password against the configured policy and, when
+/// `PWNED_PASSWORDS_URL` is set, against known breached passwords
+pub(crate) async fn validate_new_password(state: &AppState, password: &str) -> ApiResult<()> {
+    validate_password_strength(password, &state.config.password_policy())?;
+
+    let range_url = &state.config.pwned_passwords_url;
+    if !range_url.is_empty() {
+        match breach_count(&state.http_client, range_url, password).await {
+            Ok(0) => {}
+            Ok(occurrences) => {
+                return Err(PasswordValidationError::Breached { occurrences }.into());
+            }
+            // Fail open: an unreachable range API shouldn't block sign-ups or resets
+            Err(e) => tracing::warn!(error = %e, "Breached password check unavailable"),
+        }
+    }
+
+    Ok(())
+}
+
 /// Validates email address according to RFC 5322 (simplified)
 /// SOC 2 CC6.1: Strong input validation for authentication
 fn is_valid_email(email: &str) -> bool {
@@ -2179,25 +2240,27 @@ pub async fn oauth_init(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<OAuthInitRequest>,
-) -> ApiResult<Json<OAuthInitResponse>> {
+) -> ApiResult<(RateLimitHeaders, Json<OAuthInitResponse>)> {
     use rand::Rng;
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit OAuth init by IP
     let (ip_address, _) = extract_auth_audit_context(&headers);
     if let Some(ref ip) = ip_address {
         match state.rate_limiter.check_oauth(ip).await {
             Ok(result) if !result.allowed => {
                 tracing::warn!(ip = %ip, "OAuth init rate limit exceeded");
-                return Err(ApiError::TooManyRequests(format!(
-                    "Too many OAuth attempts. Retry in {} seconds.",
-                    result.retry_after_seconds.unwrap_or(60)
-                )));
+                let retry_after = result.retry_after_secs.unwrap_or(60);
+                return Err(ApiError::TooManyRequests {
+                    message: format!("Too many OAuth attempts. Retry in {} seconds.", retry_after),
+                    retry_after_secs: Some(retry_after),
+                });
             }
             Err(e) => {
                 tracing::error!(error = ?e, "Failed to check OAuth rate limit");
                 // Continue without rate limiting if check fails (fail open for availability)
             }
-            _ => {}
+            Ok(result) => rate_limit = Some(result),
         }
     }
 
@@ -2224,7 +2287,10 @@ pub async fn oauth_init(
         "oauth_init: Generated state token for OAuth flow"
     );
 
-    Ok(Json(OAuthInitResponse { state: state_token }))
+    Ok((
+        RateLimitHeaders(rate_limit),
+        Json(OAuthInitResponse { state: state_token }),
+    ))
 }
 
 // =============================================================================
@@ -2264,25 +2330,27 @@ pub async fn oauth_exchange(
     State(state): State<AppState>,
     headers: HeaderMap,
     Json(req): Json<OAuthExchangeRequest>,
-) -> ApiResult<Json<OAuthExchangeResponse>> {
+) -> ApiResult<(RateLimitHeaders, Json<OAuthExchangeResponse>)> {
     // Extract audit context
     let (ip_address, user_agent) = extract_auth_audit_context(&headers);
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit OAuth exchange by IP
     if let Some(ref ip) = ip_address {
         match state.rate_limiter.check_oauth(ip).await {
             Ok(result) if !result.allowed => {
                 tracing::warn!(ip = %ip, "OAuth exchange rate limit exceeded");
-                return Err(ApiError::TooManyRequests(format!(
-                    "Too many OAuth attempts. Retry in {} seconds.",
-                    result.retry_after_seconds.unwrap_or(60)
-                )));
+                let retry_after = result.retry_after_secs.unwrap_or(60);
+                return Err(ApiError::TooManyRequests {
+                    message: format!("Too many OAuth attempts. Retry in {} seconds.", retry_after),
+                    retry_after_secs: Some(retry_after),
+                });
             }
             Err(e) => {
                 tracing::error!(error = ?e, "Failed to check OAuth rate limit");
                 // Continue without rate limiting if check fails (fail open for availability)
             }
-            _ => {}
+            Ok(result) => rate_limit = Some(result),
         }
     }
 
@@ -2438,16 +2506,19 @@ pub async fn oauth_exchange(
     )
     .await?;
 
-    Ok(Json(OAuthExchangeResponse {
-        access_token: token_response.access_token,
-        refresh_token: token_response.refresh_token,
-        expires_in: token_response.expires_in,
-        token_type: token_response.token_type,
-        user: OAuthUserInfo {
-            id: token_response.user.id,
-            email: token_response.user.email,
-        },
-    }))
+    Ok((
+        RateLimitHeaders(rate_limit),
+        Json(OAuthExchangeResponse {
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token,
+            expires_in: token_response.expires_in,
+            token_type: token_response.token_type,
+            user: OAuthUserInfo {
+                id: token_response.user.id,
+                email: token_response.user.email,
+            },
+        }),
+    ))
 }
 
 // =============================================================================
@@ -2589,17 +2660,17 @@ pub async fn resend_verification_email(
 /// Session list response
 #[derive(Debug, Serialize)]
 pub struct SessionListResponse {
-    pub sessions: Vec<sessions::UserSession>,
+    pub sessions: Vec<sessions::SessionInfo>,
 }
 
-/// List active sessions for the authenticated user
+/// List active sessions (devices) for the authenticated user
 pub async fn list_sessions(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
 ) -> ApiResult<Json<SessionListResponse>> {
     let user_id = auth_user.user_id.ok_or(ApiError::Unauthorized)?;
 
-    let sessions = sessions::list_sessions(&state.pool, user_id).await?;
+    let sessions = sessions::list_active(&state.pool, user_id, auth_user.session_id).await?;
 
     Ok(Json(SessionListResponse { sessions }))
 }
@@ -2687,16 +2758,43 @@ pub async fn revoke_session(
     }))
 }
 
+/// Query parameters for logging out everywhere
+#[derive(Debug, Default, Deserialize)]
+pub struct LogoutAllQuery {
+    /// Keep the session making this request (log out all *other* devices)
+    #[serde(default)]
+    pub except_current: bool,
+}
+
 /// Revoke all sessions for the authenticated user (logout everywhere)
 pub async fn logout_all(
     State(state): State<AppState>,
     headers: HeaderMap,
     Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<LogoutAllQuery>,
 ) -> ApiResult<Json<MessageResponse>> {
     let user_id = auth_user.user_id.ok_or(ApiError::Unauthorized)?;
 
+    let keep_session = if query.except_current {
+        auth_user.session_id
+    } else {
+        None
+    };
     let revoked_count =
-        sessions::revoke_all_sessions(&state.pool, user_id, "user_logout_all").await?;
+        sessions::revoke_all_for_user(&state.pool, user_id, "user_logout_all", keep_session)
+            .await?;
+    let keep_token = if query.except_current {
+        extract_bearer_token(&headers)
+    } else {
+        None
+    };
+    evict_cached_user(
+        &state.token_cache,
+        user_id,
+        auth_user.email.as_deref(),
+        keep_token.as_deref(),
+    )
+    .await;
 
     // SOC 2 CC6.1: Audit log bulk session revocation
     let (ip_address, user_agent) = extract_auth_audit_context(&headers);
@@ -2713,6 +2811,7 @@ pub async fn logout_all(
         Some(serde_json::json!({
             "action": "logout_all",
             "revoked_count": revoked_count,
+            "except_current": query.except_current,
         })),
         event_type::AUTHENTICATION,
         severity::INFO,
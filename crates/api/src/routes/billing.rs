@@ -3,6 +3,7 @@
 use axum::{
     extract::{Extension, Query, State},
     http::{HeaderMap, StatusCode},
+    response::Redirect,
     Json,
 };
 use serde::{Deserialize, Serialize};
@@ -27,6 +28,11 @@ pub struct CreateCheckoutRequest {
     pub is_upgrade: bool,
     /// Billing interval (monthly or annual)
     pub billing_interval: Option<String>,
+    /// Stripe promotion code to apply (new subscriptions only)
+    pub promo_code: Option<String>,
+    /// Zero the first invoice with the first-month-free coupon (new subscriptions only)
+    #[serde(default)]
+    pub first_month_free: bool,
 }
 
 /// Response from creating a checkout session
@@ -34,6 +40,9 @@ pub struct CreateCheckoutRequest {
 pub struct CheckoutResponse {
     pub session_id: String,
     pub url: Option<String>,
+    pub billing_interval: plexmcp_billing::BillingInterval,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_coupon: Option<plexmcp_billing::AppliedCoupon>,
 }
 
 /// Response from creating a portal session
@@ -55,6 +64,20 @@ pub struct SubscriptionInfo {
     pub scheduled_downgrade: Option<ScheduledDowngradeInfo>,
 }
 
+/// RFC 3339 bound of a Stripe subscription's billing period, `None` when Stripe
+/// reported an invalid period
+fn period_bound(
+    subscription: &stripe::Subscription,
+    bound: impl FnOnce((OffsetDateTime, OffsetDateTime)) -> OffsetDateTime,
+) -> Option<String> {
+    plexmcp_billing::subscription_period(subscription)
+        .map(bound)
+        .and_then(|t| {
+            t.format(&time::format_description::well_known::Rfc3339)
+                .ok()
+        })
+}
+
 /// Info about a scheduled downgrade
 #[derive(Debug, Serialize)]
 pub struct ScheduledDowngradeInfo {
@@ -103,15 +126,24 @@ pub async fn preview_proration(
                 error_debug = ?e,
                 "preview_proration failed"
             );
-            ApiError::Database(format!("Failed to preview proration: {}", e))
+            match e {
+                plexmcp_billing::BillingError::SubscriptionNotFound(_) => {
+                    ApiError::SubscriptionRequired
+                }
+                plexmcp_billing::BillingError::StripeRateLimited(_) => ApiError::TooManyRequests {
+                    message: "Billing provider is busy, please retry shortly".to_string(),
+                    retry_after_secs: None,
+                },
+                _ => ApiError::Database(format!("Failed to preview proration: {}", e)),
+            }
         })?;
 
     Ok(Json(ProrationPreviewResponse {
         current_tier: preview.current_tier,
         new_tier: preview.new_tier,
-        proration_amount_cents: preview.proration_amount_cents,
-        overage_amount_cents: preview.overage_amount_cents,
-        total_amount_cents: preview.total_amount_cents,
+        proration_amount_cents: preview.proration_amount.amount_cents,
+        overage_amount_cents: preview.overage_amount.amount_cents,
+        total_amount_cents: preview.total_amount.amount_cents,
         days_remaining: preview.days_remaining,
         description: preview.description,
     }))
@@ -130,12 +162,29 @@ pub async fn create_checkout(
     let email = auth_user.email.as_deref().unwrap_or("");
     let customer_id = get_or_create_customer(&state, billing, org_id, email).await?;
 
-    // Parse billing interval
-    let billing_interval = req
-        .billing_interval
+    // Parse billing interval; an unrecognised value is an error, not monthly
+    let billing_interval = match req.billing_interval.as_deref() {
+        None => plexmcp_billing::BillingInterval::default(),
+        Some(interval) => {
+            plexmcp_billing::BillingInterval::from_str(interval).ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Invalid billing interval '{}' (expected monthly or annual)",
+                    interval
+                ))
+            })?
+        }
+    };
+
+    let promo_code = req
+        .promo_code
         .as_deref()
-        .and_then(plexmcp_billing::BillingInterval::from_str)
-        .unwrap_or_default();
+        .map(str::trim)
+        .filter(|code| !code.is_empty());
+    if req.is_upgrade && (promo_code.is_some() || req.first_month_free) {
+        return Err(ApiError::BadRequest(
+            "Discounts can only be applied to new subscriptions".to_string(),
+        ));
+    }
 
     // Use upgrade checkout if this is an upgrade from existing subscription
     // This will include pending overages in the checkout total
@@ -154,7 +203,28 @@ pub async fn create_checkout(
                 billing_interval,
             )
             .await
-            .map_err(|e| ApiError::Database(format!("Failed to create upgrade checkout: {}", e)))?
+            .map_err(|e| match e {
+                plexmcp_billing::BillingError::InvalidTier(msg) => ApiError::BadRequest(msg),
+                e => ApiError::Database(format!("Failed to create upgrade checkout: {}", e)),
+            })?
+    } else if promo_code.is_some() || req.first_month_free {
+        // The discount stacking rules pick one if both are requested
+        billing
+            .checkout
+            .create_subscription_checkout_with_offers(
+                org_id,
+                &customer_id,
+                &req.tier,
+                billing_interval,
+                promo_code,
+                req.first_month_free,
+            )
+            .await
+            .map_err(|e| match e {
+                plexmcp_billing::BillingError::InvalidCoupon(msg)
+                | plexmcp_billing::BillingError::InvalidTier(msg) => ApiError::BadRequest(msg),
+                e => ApiError::Database(format!("Failed to create checkout: {}", e)),
+            })?
     } else {
         billing
             .checkout
@@ -165,36 +235,86 @@ pub async fn create_checkout(
                 billing_interval,
             )
             .await
-            .map_err(|e| ApiError::Database(format!("Failed to create checkout: {}", e)))?
+            .map_err(|e| match e {
+                plexmcp_billing::BillingError::InvalidTier(msg) => ApiError::BadRequest(msg),
+                e => ApiError::Database(format!("Failed to create checkout: {}", e)),
+            })?
     };
 
+    let applied_coupon = plexmcp_billing::AppliedCoupon::from_session(&session);
     Ok(Json(CheckoutResponse {
         session_id: session.id.to_string(),
         url: session.url,
+        billing_interval,
+        applied_coupon,
     }))
 }
 
-/// Create a billing portal session
+/// Query for opening the billing portal through a signed link
+#[derive(Debug, Deserialize)]
+pub struct PortalLinkQuery {
+    pub token: String,
+}
+
+/// Create a one-time billing portal link
+///
+/// Returns a short-lived signed `/billing/portal?token=...` link rather than a
+/// Stripe portal URL; the Stripe session is only created when it is opened.
 pub async fn create_portal_session(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
 ) -> Result<Json<PortalResponse>, ApiError> {
     let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
 
-    // Get org's Stripe customer ID (create if needed)
+    // Make sure the org has a Stripe customer before handing out a link
+    let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
+    let email = auth_user.email.as_deref().unwrap_or("");
+    get_or_create_customer(&state, billing, org_id, email).await?;
+
+    let token = billing
+        .portal
+        .create_portal_link_token(org_id)
+        .map_err(|e| {
+            tracing::error!(org_id = %org_id, error = %e, "Failed to create portal link");
+            ApiError::Internal
+        })?;
+
+    Ok(Json(PortalResponse {
+        portal_url: format!(
+            "{}/api/v1/billing/portal?token={}",
+            state.config.public_url, token
+        ),
+    }))
+}
+
+/// Open the billing portal from a one-time link
+///
+/// Validates the link's signature, expiry, org and one-time use before
+/// redirecting to a freshly created Stripe portal session.
+pub async fn open_portal_link(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<PortalLinkQuery>,
+) -> Result<Redirect, ApiError> {
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
     let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
     let email = auth_user.email.as_deref().unwrap_or("");
     let customer_id = get_or_create_customer(&state, billing, org_id, email).await?;
 
     let session = billing
         .portal
-        .create_portal_session(org_id, &customer_id)
+        .redeem_portal_link(&query.token, org_id, &customer_id)
         .await
-        .map_err(|e| ApiError::Database(format!("Failed to create portal session: {}", e)))?;
+        .map_err(|e| match e {
+            plexmcp_billing::BillingError::Unauthorized(msg) => {
+                tracing::warn!(org_id = %org_id, reason = %msg, "Rejected billing portal link");
+                ApiError::InvalidToken
+            }
+            e => ApiError::Database(format!("Failed to create portal session: {}", e)),
+        })?;
 
-    Ok(Json(PortalResponse {
-        portal_url: session.url,
-    }))
+    Ok(Redirect::to(&session.url))
 }
 
 /// Get current subscription info
@@ -290,22 +410,8 @@ pub async fn get_subscription(
                 SubscriptionInfo {
                     status: format!("{:?}", sub.status).to_lowercase(),
                     tier,
-                    current_period_start: Some(
-                        time::OffsetDateTime::from_unix_timestamp(sub.current_period_start)
-                            .map(|t| {
-                                t.format(&time::format_description::well_known::Rfc3339)
-                                    .unwrap_or_default()
-                            })
-                            .unwrap_or_default(),
-                    ),
-                    current_period_end: Some(
-                        time::OffsetDateTime::from_unix_timestamp(sub.current_period_end)
-                            .map(|t| {
-                                t.format(&time::format_description::well_known::Rfc3339)
-                                    .unwrap_or_default()
-                            })
-                            .unwrap_or_default(),
-                    ),
+                    current_period_start: period_bound(&sub, |(start, _)| start),
+                    current_period_end: period_bound(&sub, |(_, end)| end),
                     cancel_at_period_end: sub.cancel_at_period_end,
                     scheduled_downgrade,
                 }
@@ -356,7 +462,7 @@ pub async fn update_subscription(
     // Auto-bill any pending overages before tier change
     // This ensures a clean slate for the new tier and avoids surprise charges later
     if let Ok(accumulated) = billing.overage.get_accumulated_overage(org_id).await {
-        if accumulated.total_cents > 0 {
+        if accumulated.total.amount_cents > 0 {
             // Get customer ID to bill overages
             match get_customer_id(&state, org_id).await {
                 Ok(Some(customer_id)) => {
@@ -364,7 +470,7 @@ pub async fn update_subscription(
                         Ok(result) => {
                             tracing::info!(
                                 org_id = %org_id,
-                                amount_cents = accumulated.total_cents,
+                                amount_cents = accumulated.total.amount_cents,
                                 result = ?result,
                                 "Auto-billed pending overages before subscription upgrade"
                             );
@@ -463,22 +569,8 @@ pub async fn update_subscription(
     Ok(Json(SubscriptionInfo {
         status: format!("{:?}", subscription.status).to_lowercase(),
         tier,
-        current_period_start: Some(
-            time::OffsetDateTime::from_unix_timestamp(subscription.current_period_start)
-                .map(|t| {
-                    t.format(&time::format_description::well_known::Rfc3339)
-                        .unwrap_or_default()
-                })
-                .unwrap_or_default(),
-        ),
-        current_period_end: Some(
-            time::OffsetDateTime::from_unix_timestamp(subscription.current_period_end)
-                .map(|t| {
-                    t.format(&time::format_description::well_known::Rfc3339)
-                        .unwrap_or_default()
-                })
-                .unwrap_or_default(),
-        ),
+        current_period_start: period_bound(&subscription, |(start, _)| start),
+        current_period_end: period_bound(&subscription, |(_, end)| end),
         cancel_at_period_end: subscription.cancel_at_period_end,
         scheduled_downgrade: None, // Just upgraded, no downgrade scheduled
     }))
@@ -603,22 +695,8 @@ pub async fn resume_subscription(
     Ok(Json(SubscriptionInfo {
         status: format!("{:?}", subscription.status).to_lowercase(),
         tier,
-        current_period_start: Some(
-            time::OffsetDateTime::from_unix_timestamp(subscription.current_period_start)
-                .map(|t| {
-                    t.format(&time::format_description::well_known::Rfc3339)
-                        .unwrap_or_default()
-                })
-                .unwrap_or_default(),
-        ),
-        current_period_end: Some(
-            time::OffsetDateTime::from_unix_timestamp(subscription.current_period_end)
-                .map(|t| {
-                    t.format(&time::format_description::well_known::Rfc3339)
-                        .unwrap_or_default()
-                })
-                .unwrap_or_default(),
-        ),
+        current_period_start: period_bound(&subscription, |(start, _)| start),
+        current_period_end: period_bound(&subscription, |(_, end)| end),
         cancel_at_period_end: subscription.cancel_at_period_end,
         scheduled_downgrade: None, // Resumed, no downgrade scheduled
     }))
@@ -645,7 +723,7 @@ pub struct ReactivationResponse {
     pub credit_applied_cents: i64,
     pub extra_trial_days: i32,
     pub overages_deducted_cents: i64,
-    pub current_period_end: String,
+    pub current_period_end: Option<String>,
     pub trial_end: Option<String>,
     pub message: String,
 }
@@ -697,15 +775,20 @@ pub async fn reactivate_subscription(
                 .unwrap_or(plexmcp_billing::BillingInterval::Monthly);
 
             let checkout_url = if let Some(coupon) = coupon_id {
-                // Create checkout with coupon
+                // Create checkout with coupon (subject to discount stacking rules)
+                let discounts = [plexmcp_billing::Discount::amount_off(
+                    coupon,
+                    plexmcp_billing::DiscountSource::Reactivation,
+                    credit_cents,
+                )];
                 match billing
                     .checkout
-                    .create_subscription_checkout_with_coupon(
+                    .create_subscription_checkout_with_discounts(
                         org_id,
                         &customer_id,
                         &tier,
                         billing_interval,
-                        &coupon,
+                        &discounts,
                     )
                     .await
                 {
@@ -841,10 +924,10 @@ pub async fn reactivate_subscription(
         credit_applied_cents: result.credit_applied_cents,
         extra_trial_days: result.extra_trial_days,
         overages_deducted_cents: result.overages_deducted_cents,
-        current_period_end: result
-            .current_period_end
-            .format(&time::format_description::well_known::Rfc3339)
-            .unwrap_or_default(),
+        current_period_end: result.current_period_end.map(|t| {
+            t.format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default()
+        }),
         trial_end: result.trial_end.map(|t| {
             t.format(&time::format_description::well_known::Rfc3339)
                 .unwrap_or_default()
@@ -887,11 +970,15 @@ pub async fn webhook(
         "Stripe webhook event verified"
     );
 
-    // Handle the event
-    billing.webhooks.handle_event(event).await.map_err(|e| {
-        tracing::error!("Webhook handling error: {}", e);
-        ApiError::Database(format!("Webhook handling error: {}", e))
-    })?;
+    // Handle the event, keeping the raw delivery for replays
+    billing
+        .webhooks
+        .handle_delivery(event, &body, signature)
+        .await
+        .map_err(|e| {
+            tracing::error!("Webhook handling error: {}", e);
+            ApiError::Database(format!("Webhook handling error: {}", e))
+        })?;
 
     tracing::info!("Stripe webhook processed successfully");
 
@@ -1109,13 +1196,13 @@ pub async fn get_current_overage(
     ) =
         subscription.as_ref()
     {
-        let start =
-            OffsetDateTime::from_unix_timestamp(sub.current_period_start).unwrap_or_else(|_| {
+        match plexmcp_billing::subscription_period(sub) {
+            Some((start, end)) => (start, Some(end)),
+            None => {
                 let now = OffsetDateTime::now_utc();
-                now.replace_day(1).unwrap_or(now)
-            });
-        let end = OffsetDateTime::from_unix_timestamp(sub.current_period_end).ok();
-        (start, end)
+                (now.replace_day(1).unwrap_or(now), None)
+            }
+        }
     } else {
         // Try to get from database subscription
         // Note: subscriptions.customer_id stores org_id as text
@@ -1282,6 +1369,7 @@ async fn get_customer_id(state: &AppState, org_id: uuid::Uuid) -> Result<Option<
 pub struct SpendCapStatusResponse {
     pub has_cap: bool,
     pub cap_amount_cents: Option<i32>,
+    pub cap_percent_of_base: Option<i16>,
     pub current_spend_cents: i32,
     pub percentage_used: f64,
     pub hard_pause_enabled: bool,
@@ -1289,15 +1377,33 @@ pub struct SpendCapStatusResponse {
     pub paused_at: Option<String>,
     pub has_override: bool,
     pub override_until: Option<String>,
+    pub members_over_cap: Vec<plexmcp_billing::MemberOverCap>,
 }
 
 /// Request to set spend cap
+///
+/// Either an absolute amount or a percentage of the plan's monthly base price.
+/// If both are given, `cap_amount_cents` wins.
 #[derive(Debug, Deserialize)]
 pub struct SetSpendCapRequest {
-    pub cap_amount_cents: i32,
+    pub cap_amount_cents: Option<i32>,
+    pub cap_percent_of_base: Option<u8>,
     pub hard_pause_enabled: bool,
 }
 
+/// Get what a tier grants (limits and features) for plan comparison
+pub async fn get_tier_entitlements(
+    axum::extract::Path(tier): axum::extract::Path<String>,
+) -> Result<Json<plexmcp_billing::TierEntitlements>, ApiError> {
+    let tier = tier
+        .parse::<plexmcp_shared::SubscriptionTier>()
+        .map_err(ApiError::BadRequest)?;
+
+    Ok(Json(
+        plexmcp_billing::EntitlementService::entitlements_for_tier(tier),
+    ))
+}
+
 /// Get spend cap status for the organization
 pub async fn get_spend_cap(
     State(state): State<AppState>,
@@ -1316,6 +1422,7 @@ pub async fn get_spend_cap(
     Ok(Json(SpendCapStatusResponse {
         has_cap: status.has_cap,
         cap_amount_cents: status.cap_amount_cents,
+        cap_percent_of_base: status.cap_percent_of_base,
         current_spend_cents: status.current_spend_cents,
         percentage_used: status.percentage_used,
         hard_pause_enabled: status.hard_pause_enabled,
@@ -1329,6 +1436,7 @@ pub async fn get_spend_cap(
             t.format(&time::format_description::well_known::Rfc3339)
                 .unwrap_or_default()
         }),
+        members_over_cap: status.members_over_cap,
     }))
 }
 
@@ -1345,15 +1453,17 @@ pub async fn set_spend_cap(
     // Validate cap amount (minimum $10, maximum $100,000)
     // SOC 2 CC6.1: Input validation prevents unreasonable values
     const MAX_SPEND_CAP_CENTS: i32 = 100_000_00; // $100,000
-    if req.cap_amount_cents < 1000 {
-        return Err(ApiError::BadRequest(
-            "Spend cap must be at least $10.00".to_string(),
-        ));
-    }
-    if req.cap_amount_cents > MAX_SPEND_CAP_CENTS {
-        return Err(ApiError::BadRequest(
-            "Spend cap cannot exceed $100,000.00".to_string(),
-        ));
+    if let Some(cap_amount_cents) = req.cap_amount_cents {
+        if cap_amount_cents < 1000 {
+            return Err(ApiError::BadRequest(
+                "Spend cap must be at least $10.00".to_string(),
+            ));
+        }
+        if cap_amount_cents > MAX_SPEND_CAP_CENTS {
+            return Err(ApiError::BadRequest(
+                "Spend cap cannot exceed $100,000.00".to_string(),
+            ));
+        }
     }
 
     let cap = billing
@@ -1362,11 +1472,20 @@ pub async fn set_spend_cap(
             org_id,
             plexmcp_billing::SpendCapRequest {
                 cap_amount_cents: req.cap_amount_cents,
+                cap_percent_of_base: req.cap_percent_of_base,
                 hard_pause_enabled: req.hard_pause_enabled,
             },
         )
         .await
-        .map_err(|e| ApiError::Database(format!("Failed to set spend cap: {}", e)))?;
+        .map_err(|e| match e {
+            plexmcp_billing::BillingError::InvalidInput(msg) => ApiError::BadRequest(msg),
+            e => ApiError::Database(format!("Failed to set spend cap: {}", e)),
+        })?;
+    let members_over_cap = billing
+        .spend_cap
+        .get_members_over_cap(org_id)
+        .await
+        .map_err(|e| ApiError::Database(format!("Failed to get member spend caps: {}", e)))?;
 
     let has_override = cap
         .override_until
@@ -1376,6 +1495,7 @@ pub async fn set_spend_cap(
     Ok(Json(SpendCapStatusResponse {
         has_cap: true,
         cap_amount_cents: Some(cap.cap_amount_cents),
+        cap_percent_of_base: cap.cap_percent_of_base,
         current_spend_cents: cap.current_period_spend_cents,
         percentage_used: if cap.cap_amount_cents > 0 {
             (cap.current_period_spend_cents as f64 / cap.cap_amount_cents as f64) * 100.0
@@ -1393,6 +1513,7 @@ pub async fn set_spend_cap(
             t.format(&time::format_description::well_known::Rfc3339)
                 .unwrap_or_default()
         }),
+        members_over_cap,
     }))
 }
 
@@ -1414,6 +1535,136 @@ pub async fn remove_spend_cap(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Request to set a member's spend sub-cap
+#[derive(Debug, Deserialize)]
+pub struct SetMemberSpendCapRequest {
+    pub cap_amount_cents: i32,
+}
+
+/// Member spend sub-cap response
+#[derive(Debug, Serialize)]
+pub struct MemberSpendCapResponse {
+    pub user_id: Uuid,
+    pub cap_amount_cents: i32,
+    pub current_spend_cents: i32,
+    pub exceeded_at: Option<String>,
+}
+
+/// Only owners and admins manage member spend caps
+fn require_member_cap_admin(auth_user: &AuthUser, org_id: Uuid) -> Result<(), ApiError> {
+    let role = auth_user.role.as_str();
+    if !["owner", "admin"].contains(&role) {
+        tracing::warn!(
+            user_id = ?auth_user.user_id,
+            org_id = %org_id,
+            role = %role,
+            "Unauthorized member spend cap change - insufficient role"
+        );
+        return Err(ApiError::Forbidden);
+    }
+    Ok(())
+}
+
+/// Set or update a member's spend sub-cap
+///
+/// A member reaching it is suspended on the next spend sync instead of the
+/// whole organization being paused.
+pub async fn set_member_spend_cap(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    axum::extract::Path(user_id): axum::extract::Path<Uuid>,
+    Json(req): Json<SetMemberSpendCapRequest>,
+) -> Result<Json<MemberSpendCapResponse>, ApiError> {
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
+    require_member_cap_admin(&auth_user, org_id)?;
+
+    let cap = billing
+        .spend_cap
+        .set_member_cap(org_id, user_id, req.cap_amount_cents)
+        .await
+        .map_err(|e| match e {
+            plexmcp_billing::BillingError::InvalidInput(msg) => ApiError::BadRequest(msg),
+            plexmcp_billing::BillingError::NotFound(_) => ApiError::NotFound,
+            e => ApiError::Database(format!("Failed to set member spend cap: {}", e)),
+        })?;
+
+    Ok(Json(MemberSpendCapResponse {
+        user_id: cap.user_id,
+        cap_amount_cents: cap.cap_amount_cents,
+        current_spend_cents: cap.current_period_spend_cents,
+        exceeded_at: cap.exceeded_at.map(|t| {
+            t.format(&time::format_description::well_known::Rfc3339)
+                .unwrap_or_default()
+        }),
+    }))
+}
+
+/// Remove a member's spend sub-cap
+pub async fn remove_member_spend_cap(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    axum::extract::Path(user_id): axum::extract::Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
+    require_member_cap_admin(&auth_user, org_id)?;
+
+    billing
+        .spend_cap
+        .remove_member_cap(org_id, user_id)
+        .await
+        .map_err(|e| ApiError::Database(format!("Failed to remove member spend cap: {}", e)))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Query for simulating spend cap notifications
+#[derive(Debug, Deserialize)]
+pub struct SimulateSpendCapQuery {
+    /// Hypothetical spend for the current period, in cents
+    pub spend_cents: i32,
+}
+
+/// Simulated spend cap notifications response
+#[derive(Debug, Serialize)]
+pub struct SimulateSpendCapResponse {
+    pub spend_cents: i32,
+    pub thresholds: Vec<plexmcp_billing::Threshold>,
+}
+
+/// Preview which spend cap notifications a hypothetical spend would trigger
+///
+/// Nothing is sent; used by the "set your cap" UI.
+pub async fn simulate_spend_cap_notifications(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<SimulateSpendCapQuery>,
+) -> Result<Json<SimulateSpendCapResponse>, ApiError> {
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
+
+    if query.spend_cents < 0 {
+        return Err(ApiError::BadRequest(
+            "spend_cents cannot be negative".to_string(),
+        ));
+    }
+
+    let thresholds = billing
+        .spend_cap
+        .simulate_notifications(org_id, query.spend_cents)
+        .await
+        .map_err(|e| ApiError::Database(format!("Failed to simulate spend cap: {}", e)))?;
+
+    Ok(Json(SimulateSpendCapResponse {
+        spend_cents: query.spend_cents,
+        thresholds,
+    }))
+}
+
 // ============================================================================
 // Pay Now Endpoints
 // ============================================================================
@@ -1421,7 +1672,7 @@ pub async fn remove_spend_cap(
 /// Accumulated overage response
 #[derive(Debug, Serialize)]
 pub struct AccumulatedOverageResponse {
-    pub total_cents: i32,
+    pub total_cents: i64,
     pub total_requests: i64,
     pub charge_count: i32,
 }
@@ -1436,12 +1687,12 @@ pub enum PayNowResponse {
     PaymentRequired {
         checkout_session_id: String,
         checkout_url: String,
-        amount_cents: i32,
+        amount_cents: i64,
         charge_count: i32,
     },
     /// Payment already completed
     AlreadyPaid {
-        amount_cents: i32,
+        amount_cents: i64,
         charge_count: i32,
     },
 }
@@ -1472,7 +1723,7 @@ pub async fn get_accumulated_overage(
         .map_err(|e| ApiError::Database(format!("Failed to get accumulated overage: {}", e)))?;
 
     Ok(Json(AccumulatedOverageResponse {
-        total_cents: overage.total_cents,
+        total_cents: overage.total.amount_cents,
         total_requests: overage.total_requests,
         charge_count: overage.charge_count,
     }))
@@ -1504,19 +1755,19 @@ pub async fn pay_overages_now(
         plexmcp_billing::PayNowResult::PaymentRequired {
             checkout_session_id,
             checkout_url,
-            amount_cents,
+            amount,
             charge_count,
         } => Ok(Json(PayNowResponse::PaymentRequired {
             checkout_session_id,
             checkout_url,
-            amount_cents,
+            amount_cents: amount.amount_cents,
             charge_count,
         })),
         plexmcp_billing::PayNowResult::AlreadyPaid {
-            amount_cents,
+            amount,
             charge_count,
         } => Ok(Json(PayNowResponse::AlreadyPaid {
-            amount_cents,
+            amount_cents: amount.amount_cents,
             charge_count,
         })),
     }
@@ -2275,6 +2526,62 @@ pub async fn get_invoice_detail(
     }))
 }
 
+/// Invoice PDF download response
+#[derive(Debug, Serialize)]
+pub struct InvoicePdfResponse {
+    pub url: String,
+}
+
+/// Get the PDF download URL for an invoice
+///
+/// SOC 2 CC6.1: RBAC - Only owners and admins can download invoices
+pub async fn get_invoice_pdf(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    axum::extract::Path(invoice_id): axum::extract::Path<Uuid>,
+) -> Result<Json<InvoicePdfResponse>, ApiError> {
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
+
+    // SOC 2 CC6.1: RBAC check - only owners and admins can download invoices
+    let role = auth_user.role.as_str();
+    if !["owner", "admin"].contains(&role) {
+        tracing::warn!(
+            user_id = ?auth_user.user_id,
+            org_id = %org_id,
+            role = %role,
+            invoice_id = %invoice_id,
+            "Unauthorized invoice PDF access attempt - insufficient role"
+        );
+        return Err(ApiError::Forbidden);
+    }
+
+    let stripe_invoice_id: Option<Option<String>> =
+        sqlx::query_scalar("SELECT stripe_invoice_id FROM invoices WHERE id = $1 AND org_id = $2")
+            .bind(invoice_id)
+            .bind(org_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| ApiError::Database(format!("Failed to fetch invoice: {}", e)))?;
+    let stripe_invoice_id = stripe_invoice_id.flatten().ok_or(ApiError::NotFound)?;
+
+    let history = plexmcp_billing::BillingHistoryService::new(
+        billing.subscriptions.stripe().clone(),
+        state.pool.clone(),
+    );
+    let url = history
+        .get_invoice_pdf_url(org_id, &stripe_invoice_id)
+        .await
+        .map_err(|e| match e {
+            plexmcp_billing::BillingError::NotFound(_) => ApiError::NotFound,
+            plexmcp_billing::BillingError::InvoiceNotReady => ApiError::Conflict(e.to_string()),
+            e => ApiError::Database(format!("Failed to fetch invoice PDF: {}", e)),
+        })?;
+
+    Ok(Json(InvoicePdfResponse { url }))
+}
+
 /// Pay an invoice via Stripe
 ///
 /// SOC 2 CC6.1: RBAC - Only owners and admins can pay invoices
@@ -2565,9 +2872,7 @@ pub async fn sync_invoices(
         let period_end = inv
             .period_end
             .and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok());
-        let billing_reason = inv
-            .billing_reason
-            .map(|r| format!("{:?}", r).to_lowercase());
+        let billing_reason = plexmcp_billing::invoice_billing_reason(&inv);
         let created_at = OffsetDateTime::from_unix_timestamp(inv.created.unwrap_or(0))
             .unwrap_or_else(|_| OffsetDateTime::now_utc());
 
@@ -2588,6 +2893,7 @@ pub async fn sync_invoices(
                 paid_at = EXCLUDED.paid_at,
                 invoice_pdf_url = EXCLUDED.invoice_pdf_url,
                 hosted_invoice_url = EXCLUDED.hosted_invoice_url,
+                billing_reason = COALESCE(EXCLUDED.billing_reason, invoices.billing_reason),
                 updated_at = NOW()
             "#
         )
@@ -15,11 +15,9 @@ use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
 use crate::{
-    auth::{
-        generate_impossible_hash, hash_password, sessions, validate_password_strength, AuthUser,
-    },
+    auth::{generate_impossible_hash, hash_password, sessions, AuthUser},
     error::{ApiError, ApiResult},
-    routes::auth::extract_auth_audit_context,
+    routes::auth::{extract_auth_audit_context, validate_new_password},
     state::AppState,
 };
 use plexmcp_shared::types::{CustomLimits, EffectiveLimits, SubscriptionTier};
@@ -757,7 +755,7 @@ pub async fn accept_invitation(
         password_hash = generate_impossible_hash().map_err(|_| ApiError::Internal)?;
     } else if let Some(ref password) = req.password {
         // Password-based account creation
-        validate_password_strength(password).map_err(|e| ApiError::Validation(e.to_string()))?;
+        validate_new_password(&state, password).await?;
 
         user_id = Uuid::new_v4();
         password_hash = hash_password(password).map_err(|_| ApiError::Internal)?;
@@ -792,6 +790,7 @@ pub async fn accept_invitation(
     tx.commit().await?;
 
     // Generate JWT tokens
+    let family_id = Uuid::new_v4(); // New refresh token family for this login
     let (access_token, access_jti, refresh_token, refresh_jti) = state
         .jwt_manager
         .generate_token_pair(
@@ -799,6 +798,7 @@ pub async fn accept_invitation(
             invitation.org_id,
             &invitation.role,
             &invitation.email,
+            family_id,
         )
         .map_err(|_| ApiError::Internal)?;
 
@@ -808,11 +808,13 @@ pub async fn accept_invitation(
     let refresh_expires_at = OffsetDateTime::now_utc() + Duration::days(30);
     sessions::save_session(
         &state.pool,
+        &state.geoip_reader,
         user_id,
         &access_jti,
         access_expires_at,
         &refresh_jti,
         refresh_expires_at,
+        family_id,
         ip_address.as_deref(),
         user_agent.as_deref(),
     )
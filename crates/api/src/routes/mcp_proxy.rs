@@ -18,8 +18,10 @@ use axum::{
 };
 use futures::stream;
 #[cfg(feature = "billing")]
-use plexmcp_billing::UsageEvent;
-use plexmcp_shared::SubscriptionTier;
+use plexmcp_billing::{QuotaWarning, UsageEvent, QUOTA_WARNING_HEADER};
+#[cfg(feature = "billing")]
+use plexmcp_shared::OverageMode;
+use plexmcp_shared::{SubscriptionTier, MEMBER_SPEND_CAP_SUSPENSION_REASON};
 use std::convert::Infallible;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -32,6 +34,7 @@ use crate::mcp::{
     streaming::McpStreamEvent,
     types::{JsonRpcError, JsonRpcId, JsonRpcRequest, JsonRpcResponse},
 };
+use crate::rate_limit::set_rate_limit_headers;
 use crate::routing::HostResolveError;
 use crate::state::AppState;
 
@@ -125,7 +128,7 @@ pub async fn handle_mcp_request(
     };
 
     // 4.5. Check rate limit for this API key
-    match state
+    let rate_limit = match state
         .rate_limiter
         .check_api_key(
             api_key_validation.org_id,
@@ -141,13 +144,13 @@ pub async fn handle_mcp_request(
                 "Rate limit exceeded"
             );
 
-            return error_response(
+            let mut response = error_response(
                 None,
                 JsonRpcError {
                     code: -32029, // Custom rate limit error code
                     message: "Rate limit exceeded".to_string(),
                     data: Some(serde_json::json!({
-                        "retry_after_seconds": result.retry_after_seconds,
+                        "retry_after_seconds": result.retry_after_secs,
                         "limit_rpm": api_key_validation.rate_limit_rpm,
                         "remaining_minute": result.remaining_minute,
                         "reset_at": result.reset_at.unix_timestamp(),
@@ -155,10 +158,11 @@ pub async fn handle_mcp_request(
                 },
                 StatusCode::TOO_MANY_REQUESTS,
             );
+            set_rate_limit_headers(response.headers_mut(), &result);
+            return response;
         }
-        Ok(_) => {
-            // Rate limit check passed
-        }
+        // Rate limit check passed
+        Ok(result) => Some(result),
         Err(e) => {
             // Fail-open: log error but allow request (availability > strict enforcement)
             tracing::error!(
@@ -166,8 +170,9 @@ pub async fn handle_mcp_request(
                 error = %e,
                 "Rate limit check failed, allowing request"
             );
+            None
         }
-    }
+    };
 
     // 5. Verify API key belongs to the resolved org (if host-based routing was used)
     let org_id = if let Some(ref resolved) = resolved_org {
@@ -190,7 +195,7 @@ pub async fn handle_mcp_request(
 
     // 6. Check monthly usage limit (Free tier blocks when over limit) - only when billing feature is enabled
     #[cfg(feature = "billing")]
-    {
+    let quota_warning = {
         let limit_check = match check_monthly_limit(&state, org_id).await {
             Ok(check) => check,
             Err(e) => {
@@ -203,6 +208,7 @@ pub async fn handle_mcp_request(
                     resets_at: OffsetDateTime::now_utc(),
                     tier: SubscriptionTier::Free,
                     overages_disabled: false,
+                    period_start: OffsetDateTime::now_utc(),
                 }
             }
         };
@@ -263,7 +269,30 @@ pub async fn handle_mcp_request(
                 StatusCode::TOO_MANY_REQUESTS,
             );
         }
-    }
+
+        // 6.5. Soft limit: warn (header + one-time notification) without blocking
+        let warning = QuotaWarning::check(
+            limit_check.current_usage,
+            limit_check.limit,
+            plexmcp_billing::quota::get_soft_limit_percent(),
+        );
+        if let (Some(warning), Some(billing)) = (warning, state.billing.clone()) {
+            let period_start = limit_check.period_start;
+            // Only the first request of the period pays for a task and DB round trip
+            if billing.quota.should_notify(org_id, period_start) {
+                tokio::spawn(async move {
+                    if let Err(e) = billing
+                        .quota
+                        .notify_soft_limit(org_id, period_start, warning)
+                        .await
+                    {
+                        tracing::error!(org_id = %org_id, error = %e, "Soft quota notification failed");
+                    }
+                });
+            }
+        }
+        warning
+    };
 
     // 7. Check if org is paused due to spend cap (only when billing feature is enabled)
     #[cfg(feature = "billing")]
@@ -298,6 +327,38 @@ pub async fn handle_mcp_request(
         }
     }
 
+    // 8. Check if org is paused pending a payment dispute
+    #[cfg(feature = "billing")]
+    if let Some(ref billing) = state.billing {
+        match billing.spend_cap.check_dispute_paused(org_id).await {
+            Ok(true) => {
+                let billing_url = if state.config.base_domain == "localhost" {
+                    "http://localhost:3000/billing".to_string()
+                } else {
+                    format!("https://dashboard.{}/billing", state.config.base_domain)
+                };
+                return error_response(
+                    None,
+                    JsonRpcError {
+                        code: -32031, // Custom payment dispute code
+                        message: "API access paused due to an open payment dispute".to_string(),
+                        data: Some(serde_json::json!({
+                            "billing_url": billing_url,
+                            "reason": "payment_dispute_open",
+                            "action_required": "Contact support to resolve the payment dispute",
+                        })),
+                    },
+                    StatusCode::PAYMENT_REQUIRED,
+                );
+            }
+            Ok(false) => {}
+            Err(e) => {
+                // Fail-open, same as the spend cap check
+                tracing::error!("Dispute pause check failed: {}", e);
+            }
+        }
+    }
+
     // Extract MCP access control settings
     let mcp_filter = McpFilter {
         mode: api_key_validation.mcp_access_mode,
@@ -378,12 +439,37 @@ pub async fn handle_mcp_request(
     )
     .await;
 
-    if wants_stream {
+    let mut response = if wants_stream {
         // Return SSE stream
         stream_response(tracked_response.response)
     } else {
         // Return JSON response
         json_response(tracked_response.response)
+    };
+
+    if let Some(ref result) = rate_limit {
+        set_rate_limit_headers(response.headers_mut(), result);
+    }
+
+    #[cfg(feature = "billing")]
+    let response = {
+        let mut response = response;
+        if let Some(ref warning) = quota_warning {
+            add_quota_warning_header(&mut response, warning);
+        }
+        response
+    };
+
+    response
+}
+
+/// Add the `X-Quota-Warning` header for usage above the soft limit
+#[cfg(feature = "billing")]
+fn add_quota_warning_header(response: &mut Response, warning: &QuotaWarning) {
+    if let Ok(value) = header::HeaderValue::from_str(&warning.header_value()) {
+        response
+            .headers_mut()
+            .insert(header::HeaderName::from_static(QUOTA_WARNING_HEADER), value);
     }
 }
 
@@ -440,8 +526,10 @@ struct MonthlyLimitCheck {
     resets_at: OffsetDateTime,
     /// Current subscription tier
     tier: SubscriptionTier,
-    /// Whether overages are disabled for this org (admin override or Free tier)
+    /// Whether overages are disabled for this org (admin override, HardStop mode or Free tier)
     overages_disabled: bool,
+    /// Start of the current billing period
+    period_start: OffsetDateTime,
 }
 
 /// Check monthly usage limit for an organization (only available with billing feature)
@@ -449,6 +537,7 @@ struct MonthlyLimitCheck {
 /// Returns whether the request should proceed:
 /// - Free tier: BLOCKED when over limit (overages not available)
 /// - Pro/Team/Enterprise: ALLOWED (overage billing) unless admin disabled overages
+///   or the org's overage mode is HardStop
 #[cfg(feature = "billing")]
 async fn check_monthly_limit(state: &AppState, org_id: Uuid) -> Result<MonthlyLimitCheck, String> {
     // If billing is not configured, allow all requests
@@ -462,6 +551,7 @@ async fn check_monthly_limit(state: &AppState, org_id: Uuid) -> Result<MonthlyLi
                 resets_at: OffsetDateTime::now_utc(),
                 tier: SubscriptionTier::Enterprise,
                 overages_disabled: false,
+                period_start: OffsetDateTime::now_utc(),
             });
         }
     };
@@ -473,21 +563,20 @@ async fn check_monthly_limit(state: &AppState, org_id: Uuid) -> Result<MonthlyLi
         .await
         .map_err(|e| format!("Failed to check usage: {}", e))?;
 
-    // Check if overages are disabled for this org (admin override)
-    let overages_disabled_by_admin: bool = sqlx::query_scalar(
-        "SELECT COALESCE(overages_disabled, false) FROM organizations WHERE id = $1",
+    // Check if overages are disabled for this org (admin override) or the org
+    // opted into a hard stop at its limit
+    let (overages_disabled_by_admin, overage_mode): (bool, String) = sqlx::query_as(
+        "SELECT COALESCE(overages_disabled, false), overage_mode FROM organizations WHERE id = $1",
     )
     .bind(org_id)
     .fetch_optional(&state.pool)
     .await
-    .map_err(|e| format!("Failed to check overages_disabled: {}", e))?
-    .unwrap_or(false);
+    .map_err(|e| format!("Failed to check overage settings: {}", e))?
+    .unwrap_or((false, OverageMode::Allow.to_string()));
+    let overage_mode: OverageMode = overage_mode.parse().unwrap_or_default();
 
     // Free tier always has overages disabled
-    let overages_disabled = match usage.tier {
-        SubscriptionTier::Free | SubscriptionTier::Starter => true,
-        _ => overages_disabled_by_admin,
-    };
+    let overages_disabled = overage_mode.blocks_at_limit(usage.tier, overages_disabled_by_admin);
 
     // Determine if request should be allowed
     let allowed = if overages_disabled {
@@ -521,6 +610,7 @@ async fn check_monthly_limit(state: &AppState, org_id: Uuid) -> Result<MonthlyLi
         resets_at: period_end,
         tier: usage.tier,
         overages_disabled,
+        period_start: usage.period_start,
     })
 }
 
@@ -557,32 +647,9 @@ async fn validate_api_key(
 
     // Hash the key and look it up in the database
     let key_hash = state.api_key_manager.hash_key(api_key);
-
-    #[derive(sqlx::FromRow)]
-    struct ApiKeyRow {
-        id: Uuid,
-        org_id: Uuid,
-        status: String,
-        expires_at: Option<time::OffsetDateTime>,
-        org_status: String,
-        mcp_access_mode: String,
-        allowed_mcp_ids: Option<Vec<Uuid>>,
-        rate_limit_rpm: i32,
-    }
-
-    let result: Option<ApiKeyRow> = sqlx::query_as(
-        r#"
-        SELECT ak.id, ak.org_id, ak.status, ak.expires_at, o.status as org_status,
-               ak.mcp_access_mode, ak.allowed_mcp_ids, ak.rate_limit_rpm
-        FROM api_keys ak
-        JOIN organizations o ON ak.org_id = o.id
-        WHERE ak.key_hash = $1
-        "#,
-    )
-    .bind(&key_hash)
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| format!("Database error: {}", e))?;
+    let result = lookup_api_key(&state.pool, &key_hash)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
 
     match result {
         Some(row) => {
@@ -624,6 +691,21 @@ async fn validate_api_key(
                 }
             }
 
+            // Usage is billed to the key's creator, so a creator over their
+            // spend sub-cap can't keep spending through their keys
+            if creator_over_spend_cap(row.creator_suspended_reason.as_deref()) {
+                log_mcp_auth_failure(
+                    state.pool.clone(),
+                    key_prefix.clone(),
+                    "creator_spend_cap_suspended".to_string(),
+                    ip_address.clone(),
+                    user_agent.clone(),
+                );
+                return Err(
+                    "The member who created this API key has reached their spend limit".to_string(),
+                );
+            }
+
             // Check if MCP access is disabled
             if row.mcp_access_mode == "none" {
                 log_mcp_auth_failure(
@@ -657,6 +739,50 @@ async fn validate_api_key(
     }
 }
 
+#[derive(sqlx::FromRow)]
+struct ApiKeyRow {
+    id: Uuid,
+    org_id: Uuid,
+    status: String,
+    expires_at: Option<time::OffsetDateTime>,
+    org_status: String,
+    mcp_access_mode: String,
+    allowed_mcp_ids: Option<Vec<Uuid>>,
+    rate_limit_rpm: i32,
+    /// Set when the key's creator is a suspended member of the org
+    creator_suspended_reason: Option<String>,
+}
+
+/// Find an API key (current or within its rotation grace) by hash
+async fn lookup_api_key(
+    pool: &sqlx::PgPool,
+    key_hash: &str,
+) -> Result<Option<ApiKeyRow>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT ak.id, ak.org_id, ak.status, ak.expires_at, o.status as org_status,
+               ak.mcp_access_mode, ak.allowed_mcp_ids, ak.rate_limit_rpm,
+               om.suspended_reason as creator_suspended_reason
+        FROM api_keys ak
+        JOIN organizations o ON ak.org_id = o.id
+        LEFT JOIN organization_members om
+            ON om.org_id = ak.org_id
+           AND om.user_id = ak.created_by
+           AND om.status = 'suspended'
+        WHERE ak.key_hash = $1
+           OR (ak.previous_key_hash = $1 AND ak.previous_key_expires_at > NOW())
+        "#,
+    )
+    .bind(key_hash)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Whether a key's creator was suspended for going over their spend sub-cap
+fn creator_over_spend_cap(creator_suspended_reason: Option<&str>) -> bool {
+    creator_suspended_reason == Some(MEMBER_SPEND_CAP_SUSPENSION_REASON)
+}
+
 /// Log the MCP request for usage tracking and billing
 ///
 /// Records the request in `mcp_proxy_logs` for debugging and creates usage
@@ -679,11 +805,16 @@ async fn log_request(
         created_by: Option<Uuid>, // Nullable in api_keys table
     }
 
-    let key_result: Result<Option<KeyIdRow>, _> =
-        sqlx::query_as("SELECT id, created_by FROM api_keys WHERE key_hash = $1")
-            .bind(&key_hash)
-            .fetch_optional(&state.pool)
-            .await;
+    let key_result: Result<Option<KeyIdRow>, _> = sqlx::query_as(
+        r#"
+        SELECT id, created_by FROM api_keys
+        WHERE key_hash = $1
+           OR (previous_key_hash = $1 AND previous_key_expires_at > NOW())
+        "#,
+    )
+    .bind(&key_hash)
+    .fetch_optional(&state.pool)
+    .await;
 
     let (api_key_id, user_id) = match key_result {
         Ok(Some(row)) => (row.id, row.created_by),
@@ -1028,4 +1159,125 @@ mod tests {
         let headers = HeaderMap::new();
         assert_eq!(extract_api_key(&headers), None);
     }
+
+    #[cfg(feature = "billing")]
+    fn ok_response() -> Response {
+        json_response(JsonRpcResponse::success(
+            Some(JsonRpcId::Number(1)),
+            serde_json::json!({}),
+        ))
+    }
+
+    #[cfg(feature = "billing")]
+    #[test]
+    fn test_soft_limit_adds_quota_warning_header_once() {
+        let warning = QuotaWarning::check(45_500, 50_000, 90).expect("above soft limit");
+        let mut response = ok_response();
+        add_quota_warning_header(&mut response, &warning);
+        // A second pass (e.g. from a retry path) must not duplicate it
+        add_quota_warning_header(&mut response, &warning);
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let values: Vec<_> = response
+            .headers()
+            .get_all(QUOTA_WARNING_HEADER)
+            .iter()
+            .collect();
+        assert_eq!(values.len(), 1);
+        assert_eq!(
+            values[0].to_str().unwrap(),
+            "91% of monthly request quota used (45500/50000)"
+        );
+    }
+
+    #[cfg(feature = "billing")]
+    #[test]
+    fn test_below_soft_limit_no_quota_warning() {
+        assert!(QuotaWarning::check(44_999, 50_000, 90).is_none());
+        let response = ok_response();
+        assert!(response.headers().get(QUOTA_WARNING_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_creator_over_spend_cap() {
+        assert!(creator_over_spend_cap(Some(
+            MEMBER_SPEND_CAP_SUSPENSION_REASON
+        )));
+        // Active creator, or suspended for something else (read-only members)
+        assert!(!creator_over_spend_cap(None));
+        assert!(!creator_over_spend_cap(Some("downgrade")));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_lookup_api_key_reports_spend_capped_creator() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+
+        let org_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let key_hash = format!("hash-{}", Uuid::new_v4());
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Key Org', $2, 'team')",
+        )
+        .bind(org_id)
+        .bind(format!("key-org-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO users (id, org_id, email, password_hash, role) VALUES ($1, $2, $3, 'TEST_HASH', 'member')",
+        )
+        .bind(user_id)
+        .bind(org_id)
+        .bind(format!("member-{}@example.com", user_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO organization_members (org_id, user_id, role, status) VALUES ($1, $2, 'member', 'active')",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO api_keys (org_id, name, key_hash, key_prefix, created_by)
+             VALUES ($1, 'member key', $2, 'pmcp_', $3)",
+        )
+        .bind(org_id)
+        .bind(&key_hash)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let row = lookup_api_key(&pool, &key_hash).await.unwrap().unwrap();
+        assert!(!creator_over_spend_cap(
+            row.creator_suspended_reason.as_deref()
+        ));
+
+        // Member goes over their sub-cap: their key is rejected
+        sqlx::query(
+            "UPDATE organization_members SET status = 'suspended', suspended_reason = $3
+             WHERE org_id = $1 AND user_id = $2",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(MEMBER_SPEND_CAP_SUSPENSION_REASON)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let row = lookup_api_key(&pool, &key_hash).await.unwrap().unwrap();
+        assert!(creator_over_spend_cap(
+            row.creator_suspended_reason.as_deref()
+        ));
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
 }
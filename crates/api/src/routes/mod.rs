@@ -35,14 +35,18 @@ use axum::{
     Router,
 };
 
+use plexmcp_shared::rate_limit::{AUTH_PROFILE, DASHBOARD_PROFILE};
+
 use crate::{
     auth::{optional_auth, require_auth},
+    rate_limit::{enforce_route_profile, RouteRateLimit},
     state::AppState,
     websocket::ws_handler,
 };
 
 /// Extract client IP address from request headers.
-/// Checks common proxy headers in order of preference.
+/// `client_ip_middleware` replaces these with `X-Real-IP` resolved from a
+/// trusted proxy, so client-supplied values never reach handlers.
 pub fn extract_client_ip(headers: &HeaderMap) -> Option<String> {
     // Check common proxy headers in order of preference
     headers
@@ -63,8 +67,9 @@ pub fn create_router(state: AppState) -> Router {
         .route("/health/live", get(health::liveness))
         .route("/health/ready", get(health::readiness));
 
-    // Public API routes (no auth required) - under /api/v1
-    let mut public_api_routes = Router::new()
+    // Public auth routes share the auth profile per client IP; handlers with
+    // own limits (login, register, 2FA, password reset) check those as well
+    let auth_routes = Router::new()
         .route("/auth/register", post(auth::register))
         .route("/auth/login", post(auth::login))
         .route("/auth/login/2fa", post(auth::login_2fa))
@@ -80,6 +85,20 @@ pub fn create_router(state: AppState) -> Router {
             "/auth/check-password-strength",
             post(auth::check_password_strength),
         )
+        // OAuth 2FA check (uses Supabase JWT, not our JWT)
+        .route("/auth/check-2fa", post(auth::check_2fa_required))
+        // OAuth state token generation (CSRF protection - SOC 2 CC6.1)
+        .route("/auth/oauth-init", post(auth::oauth_init))
+        // OAuth code exchange (uses service_role to bypass rate limits)
+        .route("/auth/oauth-exchange", post(auth::oauth_exchange))
+        .route_layer(middleware::from_fn_with_state(
+            RouteRateLimit::new(state.rate_limiter.clone(), AUTH_PROFILE),
+            enforce_route_profile,
+        ));
+
+    // Public API routes (no auth required) - under /api/v1
+    let mut public_api_routes = Router::new()
+        .merge(auth_routes)
         // Audit logging endpoints (public - called during OAuth flow)
         .route("/audit/oauth-initiated", post(audit::oauth_initiated))
         .route("/audit/oauth-callback", post(audit::oauth_callback))
@@ -91,12 +110,6 @@ pub fn create_router(state: AppState) -> Router {
         // PIN reset (public - user forgot their PIN)
         .route("/pin/forgot", post(pin::forgot_pin))
         .route("/pin/reset", post(pin::reset_pin))
-        // OAuth 2FA check (uses Supabase JWT, not our JWT)
-        .route("/auth/check-2fa", post(auth::check_2fa_required))
-        // OAuth state token generation (CSRF protection - SOC 2 CC6.1)
-        .route("/auth/oauth-init", post(auth::oauth_init))
-        // OAuth code exchange (uses service_role to bypass rate limits)
-        .route("/auth/oauth-exchange", post(auth::oauth_exchange))
         // Invitation acceptance (public - invitee doesn't have an account yet)
         .route(
             "/invitations/validate",
@@ -291,6 +304,17 @@ pub fn create_router(state: AppState) -> Router {
         )
         // Admin MCP proxy logs route
         .route("/admin/mcp/logs", get(admin::get_mcp_logs))
+        // Admin scheduled job status routes
+        .route("/admin/jobs", get(admin::list_last_job_runs))
+        .route("/admin/jobs/:job_name/runs", get(admin::list_job_runs))
+        .route(
+            "/admin/webhooks/dead-letter",
+            get(admin::list_dead_letter_webhooks),
+        )
+        .route(
+            "/admin/webhooks/dead-letter/:queue_id/requeue",
+            post(admin::requeue_dead_letter_webhook),
+        )
         // Admin support ticket routes
         .route("/admin/support/tickets", get(support::admin_list_tickets))
         .route("/admin/support/stats", get(support::admin_get_ticket_stats))
@@ -445,6 +469,7 @@ pub fn create_router(state: AppState) -> Router {
             // Billing routes
             .route("/billing/checkout", post(billing::create_checkout))
             .route("/billing/portal", post(billing::create_portal_session))
+            .route("/billing/portal", get(billing::open_portal_link))
             .route("/billing/subscription", get(billing::get_subscription))
             .route("/billing/subscription", patch(billing::update_subscription))
             .route(
@@ -463,6 +488,10 @@ pub fn create_router(state: AppState) -> Router {
                 "/billing/subscription/preview-proration",
                 get(billing::preview_proration),
             )
+            .route(
+                "/billing/plans/:tier/entitlements",
+                get(billing::get_tier_entitlements),
+            )
             // Overage routes
             .route("/billing/overages", get(billing::get_overages))
             .route(
@@ -478,6 +507,18 @@ pub fn create_router(state: AppState) -> Router {
             .route("/billing/spend-cap", get(billing::get_spend_cap))
             .route("/billing/spend-cap", post(billing::set_spend_cap))
             .route("/billing/spend-cap", delete(billing::remove_spend_cap))
+            .route(
+                "/billing/spend-cap/simulate",
+                get(billing::simulate_spend_cap_notifications),
+            )
+            .route(
+                "/billing/spend-cap/members/:user_id",
+                put(billing::set_member_spend_cap),
+            )
+            .route(
+                "/billing/spend-cap/members/:user_id",
+                delete(billing::remove_member_spend_cap),
+            )
             // Instant charge routes
             .route(
                 "/billing/instant-charges",
@@ -503,6 +544,10 @@ pub fn create_router(state: AppState) -> Router {
                 "/billing/invoices/:invoice_id",
                 get(billing::get_invoice_detail),
             )
+            .route(
+                "/billing/invoices/:invoice_id/pdf",
+                get(billing::get_invoice_pdf),
+            )
             .route(
                 "/billing/invoices/:invoice_id/pay",
                 post(billing::pay_invoice),
@@ -531,6 +576,7 @@ pub fn create_router(state: AppState) -> Router {
             .route("/usage/by-api-key", get(usage::get_usage_by_api_key))
             .route("/usage/by-mcp", get(usage::get_usage_by_mcp))
             .route("/usage/hourly", get(usage::get_hourly_usage))
+            .route("/usage/timeseries", get(usage::get_usage_timeseries))
             .route("/usage/check-limit", get(usage::check_usage_limit))
             .route("/usage/limits", get(usage::get_effective_limits))
             .route("/usage/errors", get(usage::get_recent_errors))
@@ -546,14 +592,40 @@ pub fn create_router(state: AppState) -> Router {
             .route(
                 "/admin/billing/debug/:org_id",
                 get(admin::debug_org_billing),
+            )
+            .route(
+                "/admin/billing/tier-history/:org_id",
+                get(admin::get_tier_change_history),
+            )
+            .route(
+                "/admin/billing/overages/:org_id/recalculate",
+                post(admin::recalculate_org_overage),
+            )
+            .route(
+                "/admin/billing/usage/:org_id/aggregate",
+                post(admin::aggregate_org_usage),
+            )
+            .route(
+                "/admin/billing/webhooks/:stripe_event_id/replay",
+                post(admin::replay_stripe_webhook),
+            )
+            .route(
+                "/admin/billing/grace-period/preview",
+                get(admin::preview_grace_period_enforcement),
             );
     }
 
-    // Apply auth middleware to protected routes
-    let protected_api_routes = protected_api_routes.layer(middleware::from_fn_with_state(
-        auth_state.clone(),
-        require_auth,
-    ));
+    // Apply auth middleware to protected routes; the dashboard profile runs
+    // inside it so requests are counted per user
+    let protected_api_routes = protected_api_routes
+        .route_layer(middleware::from_fn_with_state(
+            RouteRateLimit::new(state.rate_limiter.clone(), DASHBOARD_PROFILE),
+            enforce_route_profile,
+        ))
+        .layer(middleware::from_fn_with_state(
+            auth_state.clone(),
+            require_auth,
+        ));
 
     // WebSocket routes (auth handled in handler via query parameter)
     let websocket_routes = Router::new().route("/ws/support", get(ws_handler));
@@ -5,6 +5,7 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use plexmcp_shared::OverageMode;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use time::OffsetDateTime;
@@ -32,6 +33,8 @@ pub struct UpdateOrgRequest {
     pub name: Option<String>,
     pub settings: Option<serde_json::Value>,
     pub custom_subdomain: Option<String>,
+    /// Block at the plan limit instead of accruing overage (paid tiers only)
+    pub overage_mode: Option<OverageMode>,
 }
 
 #[derive(Debug, Serialize)]
@@ -43,6 +46,7 @@ pub struct OrgResponse {
     pub custom_subdomain: Option<String>,
     pub subscription_tier: String,
     pub settings: serde_json::Value,
+    pub overage_mode: String,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
 }
@@ -98,6 +102,7 @@ struct OrgRow {
     custom_subdomain: Option<String>,
     subscription_tier: String,
     settings: serde_json::Value,
+    overage_mode: String,
     created_at: OffsetDateTime,
     updated_at: OffsetDateTime,
 }
@@ -123,7 +128,7 @@ pub async fn get_org(
 
     let org: OrgRow = sqlx::query_as(
         r#"
-        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, created_at, updated_at
+        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, overage_mode, created_at, updated_at
         FROM organizations
         WHERE id = $1
         "#
@@ -140,6 +145,7 @@ pub async fn get_org(
         custom_subdomain: org.custom_subdomain,
         subscription_tier: org.subscription_tier,
         settings: org.settings,
+        overage_mode: org.overage_mode,
         created_at: org.created_at,
         updated_at: org.updated_at,
     }))
@@ -168,7 +174,7 @@ pub async fn get_org_by_id(
 
     let org: OrgRow = sqlx::query_as(
         r#"
-        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, created_at, updated_at
+        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, overage_mode, created_at, updated_at
         FROM organizations
         WHERE id = $1
         "#
@@ -185,6 +191,7 @@ pub async fn get_org_by_id(
         custom_subdomain: org.custom_subdomain,
         subscription_tier: org.subscription_tier,
         settings: org.settings,
+        overage_mode: org.overage_mode,
         created_at: org.created_at,
         updated_at: org.updated_at,
     }))
@@ -227,6 +234,22 @@ pub async fn update_org(
             .await?;
     }
 
+    // Update overage mode if provided
+    if let Some(mode) = req.overage_mode {
+        sqlx::query("UPDATE organizations SET overage_mode = $1, updated_at = NOW() WHERE id = $2")
+            .bind(mode.as_str())
+            .bind(org_id)
+            .execute(&state.pool)
+            .await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            user_id = ?auth_user.user_id,
+            overage_mode = %mode,
+            "Updated organization overage mode"
+        );
+    }
+
     // Update custom_subdomain if provided (paid tiers only)
     if let Some(ref custom_subdomain) = req.custom_subdomain {
         // Get current tier
@@ -290,7 +313,7 @@ pub async fn update_org(
     // Fetch updated org
     let org: OrgRow = sqlx::query_as(
         r#"
-        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, created_at, updated_at
+        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, overage_mode, created_at, updated_at
         FROM organizations
         WHERE id = $1
         "#
@@ -307,6 +330,7 @@ pub async fn update_org(
         custom_subdomain: org.custom_subdomain,
         subscription_tier: org.subscription_tier,
         settings: org.settings,
+        overage_mode: org.overage_mode,
         created_at: org.created_at,
         updated_at: org.updated_at,
     }))
@@ -371,7 +395,7 @@ pub async fn get_subscription(
     // Get org tier
     let org: OrgRow = sqlx::query_as(
         r#"
-        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, created_at, updated_at
+        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, overage_mode, created_at, updated_at
         FROM organizations
         WHERE id = $1
         "#
@@ -558,7 +582,7 @@ pub async fn list_orgs(
     // Get all organizations the user is a member of
     let orgs: Vec<OrgRow> = sqlx::query_as(
         r#"
-        SELECT o.id, o.name, o.slug, o.auto_subdomain, o.custom_subdomain, o.subscription_tier, o.settings, o.created_at, o.updated_at
+        SELECT o.id, o.name, o.slug, o.auto_subdomain, o.custom_subdomain, o.subscription_tier, o.settings, o.overage_mode, o.created_at, o.updated_at
         FROM organizations o
         INNER JOIN organization_members om ON o.id = om.org_id
         WHERE om.user_id = $1
@@ -579,6 +603,7 @@ pub async fn list_orgs(
                 custom_subdomain: org.custom_subdomain,
                 subscription_tier: org.subscription_tier,
                 settings: org.settings,
+                overage_mode: org.overage_mode,
                 created_at: org.created_at,
                 updated_at: org.updated_at,
             })
@@ -666,7 +691,7 @@ pub async fn create_org(
     // Fetch the created organization (includes auto_subdomain generated by DB trigger)
     let org: OrgRow = sqlx::query_as(
         r#"
-        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, created_at, updated_at
+        SELECT id, name, slug, auto_subdomain, custom_subdomain, subscription_tier, settings, overage_mode, created_at, updated_at
         FROM organizations
         WHERE id = $1
         "#
@@ -683,6 +708,7 @@ pub async fn create_org(
         custom_subdomain: org.custom_subdomain,
         subscription_tier: org.subscription_tier,
         settings: org.settings,
+        overage_mode: org.overage_mode,
         created_at: org.created_at,
         updated_at: org.updated_at,
     }))
@@ -16,6 +16,7 @@ use crate::{
     audit_constants::{admin_action, event_type, severity, target_type},
     auth::{AuthMethod, AuthUser},
     error::{ApiError, ApiResult},
+    rate_limit::RateLimitHeaders,
     state::AppState,
     websocket::events::{ServerEvent, TicketMessageEvent},
 };
@@ -289,24 +290,28 @@ pub async fn create_ticket(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthUser>,
     Json(req): Json<CreateTicketRequest>,
-) -> ApiResult<Json<SupportTicket>> {
+) -> ApiResult<(RateLimitHeaders, Json<SupportTicket>)> {
     let (user_id, org_id) = resolve_user_context(&state.pool, &auth_user).await?;
 
+    let mut rate_limit = None;
     // SOC 2 CC6.1: Rate limit ticket creation to prevent support system abuse
     if let Some(oid) = org_id {
         match state.rate_limiter.check_ticket_creation(oid).await {
             Ok(result) if !result.allowed => {
                 tracing::warn!(org_id = %oid, "create_ticket: Rate limit exceeded for organization");
-                let retry_after = result.retry_after_seconds.unwrap_or(60);
-                return Err(ApiError::TooManyRequests(format!(
-                    "Too many support tickets created. Please try again in {} seconds.",
-                    retry_after
-                )));
+                let retry_after = result.retry_after_secs.unwrap_or(60);
+                return Err(ApiError::TooManyRequests {
+                    message: format!(
+                        "Too many support tickets created. Please try again in {} seconds.",
+                        retry_after
+                    ),
+                    retry_after_secs: Some(retry_after),
+                });
             }
             Err(e) => {
                 tracing::error!(error = ?e, "create_ticket: Rate limit check failed, allowing request");
             }
-            _ => {}
+            Ok(result) => rate_limit = Some(result),
         }
     }
 
@@ -374,7 +379,7 @@ pub async fn create_ticket(
         "Support ticket created"
     );
 
-    Ok(Json(ticket.into()))
+    Ok((RateLimitHeaders(rate_limit), Json(ticket.into())))
 }
 
 /// List tickets for the current user's organization
@@ -88,6 +88,9 @@ pub struct TwoFactorVerifyResponse {
     pub remaining_attempts: Option<i32>,
     /// Whether the account is now locked
     pub is_locked: bool,
+    /// Unused backup codes left, when a backup code was just consumed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_codes_remaining: Option<i64>,
 }
 
 /// Request to disable 2FA
@@ -130,13 +133,20 @@ struct SetupTokenRow {
 #[derive(Debug, FromRow)]
 struct BackupCodeRow {
     id: Uuid,
-    #[allow(dead_code)]
-    user_id: Uuid,
     code_hash: String,
-    #[allow(dead_code)]
-    used_at: Option<OffsetDateTime>,
-    #[allow(dead_code)]
-    created_at: OffsetDateTime,
+}
+
+/// Outcome of checking a 2FA code
+enum CodeCheck {
+    Invalid,
+    Totp,
+    BackupCode { remaining: i64 },
+}
+
+impl CodeCheck {
+    fn is_valid(&self) -> bool {
+        !matches!(self, CodeCheck::Invalid)
+    }
 }
 
 // =============================================================================
@@ -174,13 +184,59 @@ async fn count_backup_codes(state: &AppState, user_id: Uuid) -> Result<i64, ApiE
     Ok(count.0)
 }
 
+/// Verify and consume a backup (recovery) code
+///
+/// Returns the number of unused codes left when `code` matched one, or `None`.
+/// The code is marked spent with a conditional update, so concurrent requests
+/// can't both redeem it.
+pub(crate) async fn verify_recovery_code(
+    pool: &sqlx::PgPool,
+    user_id: Uuid,
+    code: &str,
+) -> Result<Option<i64>, sqlx::Error> {
+    let backup_codes: Vec<BackupCodeRow> = sqlx::query_as(
+        "SELECT id, code_hash FROM user_2fa_backup_codes WHERE user_id = $1 AND used_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let Some(matched) = backup_codes
+        .iter()
+        .find(|bc| totp::verify_backup_code(code, &bc.code_hash).unwrap_or(false))
+    else {
+        return Ok(None);
+    };
+
+    let consumed = sqlx::query(
+        "UPDATE user_2fa_backup_codes SET used_at = NOW() WHERE id = $1 AND used_at IS NULL",
+    )
+    .bind(matched.id)
+    .execute(pool)
+    .await?;
+
+    if consumed.rows_affected() == 0 {
+        // Spent by a concurrent request between the select and the update
+        return Ok(None);
+    }
+
+    let remaining: (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM user_2fa_backup_codes WHERE user_id = $1 AND used_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Some(remaining.0))
+}
+
 /// Verify a 2FA code (TOTP or backup) for a user
 async fn verify_2fa_code_internal(
     state: &AppState,
     user_id: Uuid,
     email: &str,
     code: &str,
-) -> Result<bool, ApiError> {
+) -> Result<CodeCheck, ApiError> {
     let tfa = get_2fa_record(state, user_id)
         .await?
         .ok_or_else(|| ApiError::BadRequest("2FA is not enabled".to_string()))?;
@@ -216,41 +272,26 @@ async fn verify_2fa_code_internal(
             .await
             .map_err(|e| ApiError::Database(e.to_string()))?;
 
-        return Ok(true);
+        return Ok(CodeCheck::Totp);
     }
 
-    // Try backup codes (XXXX-XXXX format or without hyphen)
-    let backup_codes: Vec<BackupCodeRow> = sqlx::query_as(
-        "SELECT id, user_id, code_hash, used_at, created_at \
-         FROM user_2fa_backup_codes WHERE user_id = $1 AND used_at IS NULL",
-    )
-    .bind(user_id)
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| ApiError::Database(e.to_string()))?;
+    // Try backup codes (xxxxx-xxxxx format or without hyphen)
+    let consumed = verify_recovery_code(&state.pool, user_id, code)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    for bc in backup_codes {
-        if totp::verify_backup_code(code, &bc.code_hash).map_err(|_| ApiError::Internal)? {
-            // Mark backup code as used
-            sqlx::query("UPDATE user_2fa_backup_codes SET used_at = $1 WHERE id = $2")
-                .bind(now)
-                .bind(bc.id)
-                .execute(&state.pool)
-                .await
-                .map_err(|e| ApiError::Database(e.to_string()))?;
-
-            // Reset failed attempts
-            sqlx::query(
-                "UPDATE user_2fa SET failed_attempts = 0, locked_until = NULL, last_used_at = $1 WHERE user_id = $2"
-            )
-            .bind(now)
-            .bind(user_id)
-            .execute(&state.pool)
-            .await
-            .map_err(|e| ApiError::Database(e.to_string()))?;
+    if let Some(remaining) = consumed {
+        // Reset failed attempts
+        sqlx::query(
+            "UPDATE user_2fa SET failed_attempts = 0, locked_until = NULL, last_used_at = $1 WHERE user_id = $2"
+        )
+        .bind(now)
+        .bind(user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| ApiError::Database(e.to_string()))?;
 
-            return Ok(true);
-        }
+        return Ok(CodeCheck::BackupCode { remaining });
     }
 
     // Failed - increment attempts
@@ -269,7 +310,7 @@ async fn verify_2fa_code_internal(
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
-    Ok(false)
+    Ok(CodeCheck::Invalid)
 }
 
 // =============================================================================
@@ -554,18 +595,24 @@ pub async fn verify_2fa(
                 valid: false,
                 remaining_attempts: Some(0),
                 is_locked: true,
+                backup_codes_remaining: None,
             }));
         }
     }
 
     // Verify code
-    let valid = verify_2fa_code_internal(&state, user_id, email, &req.code).await?;
+    let check = verify_2fa_code_internal(&state, user_id, email, &req.code).await?;
 
-    if valid {
+    if check.is_valid() {
+        let backup_codes_remaining = match check {
+            CodeCheck::BackupCode { remaining } => Some(remaining),
+            _ => None,
+        };
         Ok(Json(TwoFactorVerifyResponse {
             valid: true,
             remaining_attempts: None,
             is_locked: false,
+            backup_codes_remaining,
         }))
     } else {
         // Get updated record for remaining attempts
@@ -579,6 +626,7 @@ pub async fn verify_2fa(
             valid: false,
             remaining_attempts: Some(remaining.max(0)),
             is_locked,
+            backup_codes_remaining: None,
         }))
     }
 }
@@ -596,7 +644,9 @@ pub async fn disable_2fa(
     let email = auth_user.email.as_deref().ok_or(ApiError::Unauthorized)?;
 
     // Verify code first
-    let verified = verify_2fa_code_internal(&state, user_id, email, &req.code).await?;
+    let verified = verify_2fa_code_internal(&state, user_id, email, &req.code)
+        .await?
+        .is_valid();
 
     if !verified {
         return Err(ApiError::Validation(
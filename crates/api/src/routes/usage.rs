@@ -62,6 +62,22 @@ pub struct McpUsageItem {
     pub avg_latency_ms: Option<i32>,
 }
 
+/// Query params for a usage time series
+#[derive(Debug, Deserialize)]
+pub struct UsageTimeseriesQuery {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    /// hour, day, week or month (default: day)
+    pub granularity: Option<plexmcp_billing::Granularity>,
+}
+
+/// One bucket of a usage time series
+#[derive(Debug, Serialize)]
+pub struct UsagePointItem {
+    pub bucket_start: String,
+    pub requests: i64,
+}
+
 /// Hourly usage data point
 #[derive(Debug, Serialize)]
 pub struct HourlyUsageItem {
@@ -330,6 +346,58 @@ pub async fn get_hourly_usage(
     ))
 }
 
+/// Get request counts per hour, day, week or month for charts
+///
+/// Buckets without usage are included as zero.
+pub async fn get_usage_timeseries(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthUser>,
+    Query(query): Query<UsageTimeseriesQuery>,
+) -> Result<Json<Vec<UsagePointItem>>, ApiError> {
+    let org_id = auth_user.org_id.ok_or(ApiError::NoOrganization)?;
+
+    let billing = state.billing.as_ref().ok_or(ApiError::ServiceUnavailable)?;
+
+    // Default to last 30 days
+    let now = OffsetDateTime::now_utc();
+    let start = query
+        .start
+        .as_ref()
+        .and_then(|s| {
+            time::OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339).ok()
+        })
+        .unwrap_or_else(|| now - time::Duration::days(30));
+    let end = query
+        .end
+        .as_ref()
+        .and_then(|s| {
+            time::OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339).ok()
+        })
+        .unwrap_or(now);
+    let granularity = query
+        .granularity
+        .unwrap_or(plexmcp_billing::Granularity::Day);
+
+    let points = billing
+        .usage
+        .usage_timeseries(org_id, granularity, start, end)
+        .await
+        .map_err(|e| match e {
+            plexmcp_billing::BillingError::InvalidInput(msg) => ApiError::BadRequest(msg),
+            e => ApiError::Database(format!("Failed to get usage time series: {}", e)),
+        })?;
+
+    Ok(Json(
+        points
+            .into_iter()
+            .map(|point| UsagePointItem {
+                bucket_start: format_datetime(point.bucket_start),
+                requests: point.requests,
+            })
+            .collect(),
+    ))
+}
+
 /// Check if current usage is within limits
 pub async fn check_usage_limit(
     State(state): State<AppState>,
@@ -0,0 +1,356 @@
+//! Client IP Resolution
+//!
+//! SOC 2 CC6.1: Behind Fly.io or a load balancer the TCP peer is the proxy,
+//! and the client IP arrives in a header such as `Fly-Client-IP` or
+//! `X-Forwarded-For`. Anyone can send those headers, so the configured header
+//! is only believed when the request comes from a trusted proxy address.
+//!
+//! The middleware strips all client IP headers from the incoming request and
+//! sets `X-Real-IP` to the resolved address, so `extract_client_ip` and the
+//! auth middleware see a value that can't be spoofed.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, HeaderValue, Request, Response},
+    middleware::Next,
+};
+
+/// Headers clients could use to claim an IP; removed before handlers run
+const CLIENT_IP_HEADERS: [&str; 4] = [
+    "fly-client-ip",
+    "x-forwarded-for",
+    "x-real-ip",
+    "cf-connecting-ip",
+];
+
+/// Networks trusted by default when a proxy header is configured:
+/// loopback and private ranges (Fly.io's proxy connects over its private `fdaa::/16` network)
+const DEFAULT_TRUSTED_PROXIES: [&str; 6] = [
+    "127.0.0.0/8",
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "::1/128",
+    "fc00::/7",
+];
+
+/// Header a trusted proxy puts the client IP in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientIpHeader {
+    /// `Fly-Client-IP` (Fly.io)
+    FlyClientIp,
+    /// `X-Forwarded-For` (generic load balancers)
+    XForwardedFor,
+    /// `X-Real-IP` (nginx)
+    XRealIp,
+    /// `CF-Connecting-IP` (Cloudflare)
+    CfConnectingIp,
+}
+
+impl ClientIpHeader {
+    /// Header name, lowercase
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClientIpHeader::FlyClientIp => "fly-client-ip",
+            ClientIpHeader::XForwardedFor => "x-forwarded-for",
+            ClientIpHeader::XRealIp => "x-real-ip",
+            ClientIpHeader::CfConnectingIp => "cf-connecting-ip",
+        }
+    }
+}
+
+impl std::str::FromStr for ClientIpHeader {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fly-client-ip" => Ok(ClientIpHeader::FlyClientIp),
+            "x-forwarded-for" => Ok(ClientIpHeader::XForwardedFor),
+            "x-real-ip" => Ok(ClientIpHeader::XRealIp),
+            "cf-connecting-ip" => Ok(ClientIpHeader::CfConnectingIp),
+            other => Err(format!("Unsupported client IP header: {}", other)),
+        }
+    }
+}
+
+/// An IP network in CIDR notation, e.g. `10.0.0.0/8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Whether `ip` is inside this network
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix_len))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            // IPv4-mapped IPv6 peers (dual-stack listeners)
+            (IpAddr::V4(_), IpAddr::V6(ip)) => ip
+                .to_ipv4_mapped()
+                .is_some_and(|ip| self.contains(IpAddr::V4(ip))),
+            (IpAddr::V6(_), IpAddr::V4(_)) => false,
+        }
+    }
+}
+
+impl std::str::FromStr for IpNetwork {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, len)) => (addr, Some(len)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("Invalid proxy address: {}", s))?;
+        let max_len = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(len) => len
+                .parse::<u8>()
+                .ok()
+                .filter(|len| *len <= max_len)
+                .ok_or_else(|| format!("Invalid prefix length: {}", s))?,
+            None => max_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// Which proxies to trust, and which header they put the client IP in
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxyConfig {
+    /// `None` to always use the TCP peer address
+    pub header: Option<ClientIpHeader>,
+    /// Peers whose client IP header is believed
+    pub trusted_proxies: Vec<IpNetwork>,
+}
+
+impl TrustedProxyConfig {
+    /// Load from `TRUSTED_PROXY_HEADER` and `TRUSTED_PROXIES`
+    ///
+    /// The header defaults to `Fly-Client-IP` when running on Fly.io
+    /// (`FLY_APP_NAME` is set) and to none otherwise; `none` disables it
+    /// explicitly. Proxies are a comma-separated list of addresses or CIDR
+    /// networks, defaulting to loopback and private ranges. Invalid entries
+    /// are logged and skipped.
+    pub fn from_env() -> Self {
+        let header = match std::env::var("TRUSTED_PROXY_HEADER") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("none") => None,
+            Ok(value) => match value.parse() {
+                Ok(header) => Some(header),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Ignoring TRUSTED_PROXY_HEADER");
+                    None
+                }
+            },
+            Err(_) if std::env::var("FLY_APP_NAME").is_ok() => Some(ClientIpHeader::FlyClientIp),
+            Err(_) => None,
+        };
+
+        let proxies = std::env::var("TRUSTED_PROXIES").unwrap_or_default();
+        let trusted_proxies = if proxies.trim().is_empty() {
+            DEFAULT_TRUSTED_PROXIES
+                .iter()
+                .filter_map(|net| net.parse().ok())
+                .collect()
+        } else {
+            proxies
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .filter_map(|s| match s.parse() {
+                    Ok(net) => Some(net),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Ignoring TRUSTED_PROXIES entry");
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        Self {
+            header,
+            trusted_proxies,
+        }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    /// Resolve the client IP for a request from `peer`
+    ///
+    /// The configured header is only read when `peer` is a trusted proxy.
+    /// For `X-Forwarded-For` the right-most entry that isn't itself a trusted
+    /// proxy is used, since entries to its left are client-supplied.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let Some(header) = self.header else {
+            return peer;
+        };
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let Some(value) = headers.get(header.name()).and_then(|h| h.to_str().ok()) else {
+            return peer;
+        };
+
+        let forwarded = match header {
+            ClientIpHeader::XForwardedFor => value
+                .rsplit(',')
+                .filter_map(|entry| entry.trim().parse::<IpAddr>().ok())
+                .find(|ip| !self.is_trusted(*ip)),
+            _ => value.trim().parse().ok(),
+        };
+
+        forwarded.unwrap_or(peer)
+    }
+}
+
+/// Middleware that replaces client-supplied IP headers with the resolved client IP
+///
+/// Requires the server to be started with `into_make_service_with_connect_info`;
+/// without a peer address no client IP is set.
+pub async fn client_ip_middleware(
+    State(config): State<Arc<TrustedProxyConfig>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response<Body> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let client_ip = peer.map(|peer| config.resolve(peer, request.headers()));
+
+    let headers = request.headers_mut();
+    for name in CLIENT_IP_HEADERS {
+        headers.remove(name);
+    }
+    if let Some(ip) = client_ip {
+        if let Ok(value) = HeaderValue::from_str(&ip.to_string()) {
+            headers.insert("x-real-ip", value);
+        }
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fly_config() -> TrustedProxyConfig {
+        TrustedProxyConfig {
+            header: Some(ClientIpHeader::FlyClientIp),
+            trusted_proxies: vec!["fdaa::/16".parse().unwrap(), "10.0.0.0/8".parse().unwrap()],
+        }
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_header_used_from_trusted_proxy() {
+        let config = fly_config();
+        let peer: IpAddr = "fdaa:0:1::3".parse().unwrap();
+        let headers = headers(&[("fly-client-ip", "203.0.113.7")]);
+
+        assert_eq!(
+            config.resolve(peer, &headers),
+            "203.0.113.7".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_header_ignored_from_untrusted_peer() {
+        let config = fly_config();
+        let peer: IpAddr = "198.51.100.20".parse().unwrap();
+        let headers = headers(&[
+            ("fly-client-ip", "203.0.113.7"),
+            ("x-forwarded-for", "203.0.113.8"),
+        ]);
+
+        assert_eq!(config.resolve(peer, &headers), peer);
+    }
+
+    #[test]
+    fn test_no_header_configured_uses_peer() {
+        let config = TrustedProxyConfig {
+            header: None,
+            ..fly_config()
+        };
+        let peer: IpAddr = "10.1.2.3".parse().unwrap();
+        let headers = headers(&[("fly-client-ip", "203.0.113.7")]);
+
+        assert_eq!(config.resolve(peer, &headers), peer);
+    }
+
+    #[test]
+    fn test_forwarded_for_skips_spoofed_and_proxy_entries() {
+        let config = TrustedProxyConfig {
+            header: Some(ClientIpHeader::XForwardedFor),
+            ..fly_config()
+        };
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+        // Client claimed 1.1.1.1; the edge appended the real address, then an internal hop
+        let headers = headers(&[("x-forwarded-for", "1.1.1.1, 203.0.113.9, 10.0.0.5")]);
+
+        assert_eq!(
+            config.resolve(peer, &headers),
+            "203.0.113.9".parse::<IpAddr>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_malformed_header_falls_back_to_peer() {
+        let config = fly_config();
+        let peer: IpAddr = "10.0.0.2".parse().unwrap();
+        let headers = headers(&[("fly-client-ip", "not-an-ip")]);
+
+        assert_eq!(config.resolve(peer, &headers), peer);
+    }
+
+    #[test]
+    fn test_ip_network_parsing_and_matching() {
+        let net: IpNetwork = "172.16.0.0/12".parse().unwrap();
+        assert!(net.contains("172.31.255.1".parse().unwrap()));
+        assert!(!net.contains("172.32.0.1".parse().unwrap()));
+        assert!(net.contains("::ffff:172.16.0.9".parse().unwrap()));
+
+        let single: IpNetwork = "203.0.113.5".parse().unwrap();
+        assert!(single.contains("203.0.113.5".parse().unwrap()));
+        assert!(!single.contains("203.0.113.6".parse().unwrap()));
+
+        let all: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains("8.8.8.8".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<IpNetwork>().is_err());
+        assert!("not-a-network".parse::<IpNetwork>().is_err());
+        assert_eq!(
+            "Fly-Client-IP".parse::<ClientIpHeader>(),
+            Ok(ClientIpHeader::FlyClientIp)
+        );
+    }
+}
@@ -3,8 +3,10 @@
 //! This module contains tests and utilities for validating security controls
 //! required for SOC 2 Type II compliance.
 
+mod client_ip;
 mod headers;
 
+pub use client_ip::{client_ip_middleware, TrustedProxyConfig};
 pub use headers::security_headers_middleware;
 
 #[cfg(test)]
@@ -92,7 +92,9 @@ impl AppState {
             tracing::warn!("Supabase JWT validation not configured (missing SUPABASE_JWT_SECRET)");
             JwtManager::new(&config.jwt_secret, config.jwt_expiry_hours)
         };
-        let api_key_manager = ApiKeyManager::new(&config.api_key_hmac_secret);
+        let api_key_manager = ApiKeyManager::new(&config.api_key_hmac_secret).with_rotation_grace(
+            time::Duration::hours(config.api_key_rotation_grace_hours.max(0)),
+        );
 
         // Try to initialize billing if Stripe env vars are set (only when feature is enabled)
         #[cfg(feature = "billing")]
@@ -166,8 +168,30 @@ impl AppState {
         }
 
         // Initialize rate limiter from shared crate (always available, no billing dependency)
-        let rate_limiter = RateLimiter::new_in_memory();
-        tracing::info!("Rate limiter initialized");
+        // Counters live in Postgres so all instances share them; RATE_LIMIT_BACKEND=memory
+        // keeps them per process (single-instance or local development only)
+        let rate_limiter = match std::env::var("RATE_LIMIT_BACKEND").as_deref() {
+            Ok("memory") => {
+                tracing::warn!("Rate limiter initialized in memory (limits are per instance)");
+                RateLimiter::new_in_memory()
+            }
+            _ => {
+                tracing::info!("Rate limiter initialized with Postgres-backed buckets");
+                RateLimiter::new_postgres(pool.clone())
+            }
+        };
+
+        // Prune stale rate limit windows (runs every 10 minutes)
+        let limiter_for_cleanup = rate_limiter.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(600));
+            loop {
+                interval.tick().await;
+                if let Err(e) = limiter_for_cleanup.cleanup().await {
+                    tracing::warn!(error = %e, "Failed to clean up rate limit buckets");
+                }
+            }
+        });
 
         // Initialize shared MCP client for HTTP session caching
         let mcp_client = Arc::new(crate::mcp::client::McpClient::new());
@@ -0,0 +1,355 @@
+//! Account Status Module
+//!
+//! Composes the individual billing signals (admin block, spend cap pause,
+//! open disputes, overdue invoices) into a single "good standing" answer so
+//! the API can gate features consistently.
+//!
+//! ## Precedence
+//!
+//! When several conditions apply, the most severe wins:
+//!
+//! 1. `Blocked` - billing blocked by an administrator or non-payment enforcement
+//! 2. `Frozen` - API paused by a hard spend cap (without an active override)
+//! 3. `Disputed` - an unresolved chargeback is open against the org
+//! 4. `PastDue` - at least one open invoice is past its due date
+//! 5. `Good` - none of the above
+//!
+//! ## Non-payment grace period
+//!
+//! Unpaid invoices block the org once their grace period ends.
+//!
+//! - `INVOICE_GRACE_PERIOD_DAYS`: grace period after the due date (default: 30)
+//! - `INVOICE_GRACE_PERIOD_DAYS_<TIER>`: per-tier override, e.g.
+//!   `INVOICE_GRACE_PERIOD_DAYS_ENTERPRISE=90`
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use plexmcp_shared::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{BillingError, BillingResult};
+
+/// Composite billing standing for an organization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountStatus {
+    /// No billing problems
+    Good,
+    /// One or more invoices are overdue
+    PastDue,
+    /// Billing blocked (`organizations.billing_blocked_at` set)
+    Blocked,
+    /// API paused by hard spend cap
+    Frozen,
+    /// Open payment dispute (chargeback)
+    Disputed,
+}
+
+impl AccountStatus {
+    /// Resolve the composite status from individual flags, applying precedence
+    pub fn from_flags(flags: &AccountStatusFlags) -> Self {
+        if flags.billing_blocked {
+            AccountStatus::Blocked
+        } else if flags.spend_cap_paused {
+            AccountStatus::Frozen
+        } else if flags.open_disputes > 0 {
+            AccountStatus::Disputed
+        } else if flags.overdue_invoices > 0 {
+            AccountStatus::PastDue
+        } else {
+            AccountStatus::Good
+        }
+    }
+
+    /// Whether the account is in good standing
+    pub fn is_good(&self) -> bool {
+        *self == AccountStatus::Good
+    }
+}
+
+impl std::fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountStatus::Good => write!(f, "good"),
+            AccountStatus::PastDue => write!(f, "past_due"),
+            AccountStatus::Blocked => write!(f, "blocked"),
+            AccountStatus::Frozen => write!(f, "frozen"),
+            AccountStatus::Disputed => write!(f, "disputed"),
+        }
+    }
+}
+
+/// Default grace period after an invoice's due date before blocking
+pub const DEFAULT_INVOICE_GRACE_PERIOD_DAYS: i64 = 30;
+
+/// Longest accepted grace period
+const MAX_INVOICE_GRACE_PERIOD_DAYS: i64 = 365;
+
+/// Default days to keep retrying after the final failed payment attempt
+pub const DEFAULT_DECLINE_RETRY_GRACE_DAYS: i64 = 3;
+
+/// Grace period before unpaid invoices block the org
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvoiceGraceConfig {
+    /// Days after the due date for tiers without an override
+    pub default_days: i64,
+    /// Per-tier overrides, keyed by lowercase tier name
+    pub tier_days: HashMap<String, i64>,
+    /// Days after the final failed payment attempt before the org is blocked
+    pub decline_retry_days: i64,
+}
+
+impl Default for InvoiceGraceConfig {
+    fn default() -> Self {
+        Self {
+            default_days: DEFAULT_INVOICE_GRACE_PERIOD_DAYS,
+            tier_days: HashMap::new(),
+            decline_retry_days: DEFAULT_DECLINE_RETRY_GRACE_DAYS,
+        }
+    }
+}
+
+impl InvoiceGraceConfig {
+    /// Create config from environment variables
+    ///
+    /// Values outside 1-365 days are ignored. `DECLINE_RETRY_GRACE_DAYS` also
+    /// accepts 0, which blocks on the next enforcement run.
+    pub fn from_env() -> Self {
+        let parse = |name: &str, min: i64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|d| (min..=MAX_INVOICE_GRACE_PERIOD_DAYS).contains(d))
+        };
+        let days = |name: &str| parse(name, 1);
+
+        let mut config = Self::default();
+        if let Some(default_days) = days("INVOICE_GRACE_PERIOD_DAYS") {
+            config.default_days = default_days;
+        }
+        for tier in ["free", "starter", "pro", "team", "enterprise"] {
+            let name = format!("INVOICE_GRACE_PERIOD_DAYS_{}", tier.to_uppercase());
+            if let Some(tier_days) = days(&name) {
+                config.tier_days.insert(tier.to_string(), tier_days);
+            }
+        }
+        if let Some(decline_retry_days) = parse("DECLINE_RETRY_GRACE_DAYS", 0) {
+            config.decline_retry_days = decline_retry_days;
+        }
+        config
+    }
+
+    /// Grace period in days for an org on `tier`
+    pub fn grace_period_days_for_tier(&self, tier: &str) -> i64 {
+        self.tier_days
+            .get(&tier.to_lowercase())
+            .copied()
+            .unwrap_or(self.default_days)
+    }
+}
+
+/// Get the configured invoice grace period
+pub fn get_invoice_grace_config() -> &'static InvoiceGraceConfig {
+    static CONFIG: OnceLock<InvoiceGraceConfig> = OnceLock::new();
+    CONFIG.get_or_init(InvoiceGraceConfig::from_env)
+}
+
+/// Configured grace period in days for an org on `tier`
+pub fn grace_period_days_for_tier(tier: &str) -> i64 {
+    get_invoice_grace_config().grace_period_days_for_tier(tier)
+}
+
+/// `billing_block_reason` for an org blocked after its grace period
+pub fn grace_period_block_reason(grace_days: i64, total_due_cents: i64) -> String {
+    format!(
+        "Unpaid invoices past {}-day grace period. Outstanding balance: {}",
+        grace_days,
+        Money::from_cents_usd(total_due_cents)
+    )
+}
+
+/// Raw signals that feed into `AccountStatus`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AccountStatusFlags {
+    pub billing_blocked: bool,
+    pub spend_cap_paused: bool,
+    pub open_disputes: i64,
+    pub overdue_invoices: i64,
+}
+
+/// Service for computing account standing
+pub struct AccountStatusService {
+    pool: PgPool,
+}
+
+impl AccountStatusService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Load the raw standing signals for an organization
+    pub async fn get_flags(&self, org_id: Uuid) -> BillingResult<AccountStatusFlags> {
+        let flags: Option<AccountStatusFlags> = sqlx::query_as(
+            r#"
+            SELECT
+                o.billing_blocked_at IS NOT NULL AS billing_blocked,
+                COALESCE(
+                    sc.is_paused
+                        AND sc.hard_pause_enabled
+                        AND (sc.override_until IS NULL OR sc.override_until <= NOW()),
+                    false
+                ) AS spend_cap_paused,
+                (
+                    SELECT COUNT(*)
+                    FROM billing_disputes d
+                    WHERE d.org_id = o.id
+                      AND d.resolved_at IS NULL
+                      AND LOWER(d.status) NOT IN ('won', 'lost')
+                ) AS open_disputes,
+                (
+                    SELECT COUNT(*)
+                    FROM invoices i
+                    WHERE i.org_id = o.id
+                      AND i.status IN ('open', 'uncollectible')
+                      AND i.due_date < NOW()
+                ) AS overdue_invoices
+            FROM organizations o
+            LEFT JOIN spend_caps sc ON sc.org_id = o.id
+            WHERE o.id = $1
+            "#,
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        flags.ok_or_else(|| BillingError::NotFound(format!("Organization {} not found", org_id)))
+    }
+
+    /// Compute the composite account status for an organization
+    pub async fn get_status(&self, org_id: Uuid) -> BillingResult<AccountStatus> {
+        let flags = self.get_flags(org_id).await?;
+        Ok(AccountStatus::from_flags(&flags))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grace_period_per_tier() {
+        let config = InvoiceGraceConfig {
+            tier_days: HashMap::from([("enterprise".to_string(), 90)]),
+            ..Default::default()
+        };
+
+        assert_eq!(config.grace_period_days_for_tier("enterprise"), 90);
+        assert_eq!(config.grace_period_days_for_tier("Enterprise"), 90);
+        assert_eq!(
+            config.grace_period_days_for_tier("pro"),
+            DEFAULT_INVOICE_GRACE_PERIOD_DAYS
+        );
+    }
+
+    #[test]
+    fn test_decline_retry_grace_defaults_to_a_few_days() {
+        let config = InvoiceGraceConfig::default();
+        assert_eq!(config.decline_retry_days, DEFAULT_DECLINE_RETRY_GRACE_DAYS);
+        assert!(config.decline_retry_days > 0);
+    }
+
+    #[test]
+    fn test_grace_period_block_reason_uses_configured_days() {
+        assert_eq!(
+            grace_period_block_reason(60, 12_345),
+            "Unpaid invoices past 60-day grace period. Outstanding balance: $123.45"
+        );
+    }
+
+    #[test]
+    fn test_no_flags_is_good() {
+        let status = AccountStatus::from_flags(&AccountStatusFlags::default());
+        assert_eq!(status, AccountStatus::Good);
+        assert!(status.is_good());
+    }
+
+    #[test]
+    fn test_each_single_flag() {
+        let cases = [
+            (
+                AccountStatusFlags {
+                    billing_blocked: true,
+                    ..Default::default()
+                },
+                AccountStatus::Blocked,
+            ),
+            (
+                AccountStatusFlags {
+                    spend_cap_paused: true,
+                    ..Default::default()
+                },
+                AccountStatus::Frozen,
+            ),
+            (
+                AccountStatusFlags {
+                    open_disputes: 1,
+                    ..Default::default()
+                },
+                AccountStatus::Disputed,
+            ),
+            (
+                AccountStatusFlags {
+                    overdue_invoices: 2,
+                    ..Default::default()
+                },
+                AccountStatus::PastDue,
+            ),
+        ];
+
+        for (flags, expected) in cases {
+            let status = AccountStatus::from_flags(&flags);
+            assert_eq!(status, expected, "flags: {:?}", flags);
+            assert!(!status.is_good());
+        }
+    }
+
+    #[test]
+    fn test_precedence_ordering() {
+        let mut flags = AccountStatusFlags {
+            billing_blocked: true,
+            spend_cap_paused: true,
+            open_disputes: 1,
+            overdue_invoices: 1,
+        };
+        assert_eq!(AccountStatus::from_flags(&flags), AccountStatus::Blocked);
+
+        flags.billing_blocked = false;
+        assert_eq!(AccountStatus::from_flags(&flags), AccountStatus::Frozen);
+
+        flags.spend_cap_paused = false;
+        assert_eq!(AccountStatus::from_flags(&flags), AccountStatus::Disputed);
+
+        flags.open_disputes = 0;
+        assert_eq!(AccountStatus::from_flags(&flags), AccountStatus::PastDue);
+
+        flags.overdue_invoices = 0;
+        assert_eq!(AccountStatus::from_flags(&flags), AccountStatus::Good);
+    }
+
+    #[test]
+    fn test_display_matches_serde() {
+        for status in [
+            AccountStatus::Good,
+            AccountStatus::PastDue,
+            AccountStatus::Blocked,
+            AccountStatus::Frozen,
+            AccountStatus::Disputed,
+        ] {
+            let json = serde_json::to_value(status).unwrap();
+            assert_eq!(json, serde_json::Value::String(status.to_string()));
+        }
+    }
+}
@@ -13,6 +13,8 @@
 
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+// Item-level proration behavior (the subscription-level enum is a different type)
+use stripe::generated::billing::subscription_item::SubscriptionProrationBehavior;
 use stripe::{
     CreateSubscriptionItem, PriceId, SubscriptionId, SubscriptionItem, SubscriptionItemId,
     UpdateSubscriptionItem,
@@ -24,9 +26,93 @@ use crate::{
     client::StripeClient,
     customer::CustomerService,
     error::{BillingError, BillingResult},
-    subscriptions::SubscriptionService,
+    subscriptions::{SubscriptionKind, SubscriptionService},
 };
 
+/// When an add-on removal takes effect
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddonRemovalTiming {
+    /// Remove now and credit the unused time on the next invoice
+    #[default]
+    Immediate,
+    /// Keep access until the current period ends; no credit is issued
+    PeriodEnd,
+}
+
+impl AddonRemovalTiming {
+    /// Proration behavior used when deleting the Stripe subscription item
+    ///
+    /// For `PeriodEnd` the item is deleted right away without proration: the
+    /// customer keeps what they already paid for and isn't billed at renewal.
+    pub fn proration_behavior(&self) -> SubscriptionProrationBehavior {
+        match self {
+            AddonRemovalTiming::Immediate => SubscriptionProrationBehavior::CreateProrations,
+            AddonRemovalTiming::PeriodEnd => SubscriptionProrationBehavior::None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AddonRemovalTiming::Immediate => "immediate",
+            AddonRemovalTiming::PeriodEnd => "period_end",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "immediate" => Some(AddonRemovalTiming::Immediate),
+            "period_end" => Some(AddonRemovalTiming::PeriodEnd),
+            _ => None,
+        }
+    }
+}
+
+/// Query params for `DELETE /v1/subscription_items/:id`
+///
+/// async-stripe's `SubscriptionItem::delete` takes no params, so this is sent
+/// through `delete_query` to control proration.
+#[derive(Debug, Clone, Serialize)]
+struct DeleteSubscriptionItemParams {
+    proration_behavior: SubscriptionProrationBehavior,
+}
+
+impl DeleteSubscriptionItemParams {
+    fn for_timing(timing: AddonRemovalTiming) -> Self {
+        Self {
+            proration_behavior: timing.proration_behavior(),
+        }
+    }
+}
+
+/// What happens to an addon-only base subscription after an add-on is removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddonOnlyCleanup {
+    /// Add-ons are still billed on it
+    Keep,
+    /// Nothing left on it; cancel now
+    CancelNow,
+    /// Only period-end removals remain; cancel when the period ends
+    CancelAtPeriodEnd,
+}
+
+impl AddonOnlyCleanup {
+    /// Decide from the org's remaining active add-ons
+    ///
+    /// `billed` still have a Stripe item; `ending` were removed with
+    /// `PeriodEnd` timing and stay active until the period ends.
+    pub fn for_remaining(billed: i64, ending: i64) -> Self {
+        if billed > 0 {
+            Self::Keep
+        } else if ending > 0 {
+            Self::CancelAtPeriodEnd
+        } else {
+            Self::CancelNow
+        }
+    }
+}
+
 /// Add-on category for UI grouping (2 categories as of Dec 2024)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -454,8 +540,9 @@ impl AddonService {
         }
 
         // Get the organization's active subscription
-        let subscription: Option<(Uuid, Option<String>)> = sqlx::query_as(
-            "SELECT id, stripe_subscription_id FROM subscriptions
+        let subscription: Option<(Uuid, Option<String>, Option<String>, bool)> = sqlx::query_as(
+            "SELECT id, stripe_subscription_id, stripe_price_id, cancel_at_period_end
+             FROM subscriptions
              WHERE org_id = $1 AND status = 'active'
              LIMIT 1",
         )
@@ -466,7 +553,19 @@ impl AddonService {
 
         // If no subscription exists, create an add-on only subscription for free tier users
         let (subscription_id, stripe_subscription_id) = match subscription {
-            Some((id, Some(stripe_id))) => (id, stripe_id),
+            Some((id, Some(stripe_id), price_id, cancel_at_period_end)) => {
+                // An addon-only base set to end with its last add-on carries on
+                let addon_only_price = self.stripe.config().price_ids.addon_only.as_deref();
+                if cancel_at_period_end
+                    && SubscriptionKind::from_price_id(price_id.as_deref(), addon_only_price)
+                        == SubscriptionKind::AddonOnly
+                {
+                    SubscriptionService::new(self.stripe.clone(), self.pool.clone())
+                        .resume_subscription(org_id)
+                        .await?;
+                }
+                (id, stripe_id)
+            }
             _ => {
                 // No active subscription - create add-on only subscription
                 let _stripe_sub = self.create_addon_only_subscription_for_org(org_id).await?;
@@ -723,7 +822,8 @@ impl AddonService {
             }
             Some(_) if quantity == 0 => {
                 // Disable add-on if quantity is 0
-                self.disable_addon(org_id, addon_type).await?;
+                self.disable_addon(org_id, addon_type, AddonRemovalTiming::Immediate)
+                    .await?;
                 Err(BillingError::NotFound(
                     "Add-on disabled due to zero quantity".to_string(),
                 ))
@@ -737,7 +837,16 @@ impl AddonService {
     }
 
     /// Disable an add-on for an organization
-    pub async fn disable_addon(&self, org_id: Uuid, addon_type: AddonType) -> BillingResult<()> {
+    ///
+    /// `Immediate` removes access now and credits unused time. `PeriodEnd`
+    /// stops billing without a credit and keeps the add-on active until the
+    /// current period ends (see `expire_period_end_removals`).
+    pub async fn disable_addon(
+        &self,
+        org_id: Uuid,
+        addon_type: AddonType,
+        timing: AddonRemovalTiming,
+    ) -> BillingResult<()> {
         // Get the add-on
         let addon: Option<(Uuid, Option<String>)> = sqlx::query_as(
             "SELECT id, stripe_item_id FROM subscription_addons
@@ -757,19 +866,146 @@ impl AddonService {
             let item_id = stripe_item_id.parse::<SubscriptionItemId>().map_err(|e| {
                 BillingError::StripeApi(format!("Invalid subscription item ID: {}", e))
             })?;
-            SubscriptionItem::delete(self.stripe.inner(), &item_id).await?;
+            let _: serde_json::Value = self
+                .stripe
+                .inner()
+                .delete_query(
+                    &format!("/subscription_items/{}", item_id),
+                    DeleteSubscriptionItemParams::for_timing(timing),
+                )
+                .await?;
         }
 
         // Update database status
-        sqlx::query(
-            "UPDATE subscription_addons SET status = 'canceled', canceled_at = NOW() WHERE id = $1",
+        match timing {
+            AddonRemovalTiming::Immediate => {
+                sqlx::query(
+                    "UPDATE subscription_addons SET status = 'canceled', canceled_at = NOW() WHERE id = $1",
+                )
+                .bind(addon_id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| BillingError::Database(e.to_string()))?;
+            }
+            AddonRemovalTiming::PeriodEnd => {
+                // Stays active until the period the customer paid for ends
+                sqlx::query(
+                    r#"
+                    UPDATE subscription_addons sa SET
+                        stripe_item_id = NULL,
+                        canceled_at = COALESCE(s.current_period_end, NOW()),
+                        metadata = COALESCE(sa.metadata, '{}'::jsonb)
+                            || '{"remove_at_period_end": true}'::jsonb
+                    FROM subscriptions s
+                    WHERE sa.id = $1 AND s.id = sa.subscription_id
+                    "#,
+                )
+                .bind(addon_id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| BillingError::Database(e.to_string()))?;
+            }
+        }
+
+        tracing::info!(
+            org_id = %org_id,
+            addon_type = %addon_type.as_str(),
+            timing = %timing.as_str(),
+            "Disabled add-on"
+        );
+
+        // The add-on is already gone; a leftover $0 base is retried on the next removal
+        if let Err(e) = self.cleanup_addon_only_subscription(org_id).await {
+            tracing::warn!(
+                org_id = %org_id,
+                error = %e,
+                "Failed to clean up add-on only subscription"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Cancel the org's addon-only base subscription once no add-ons remain on it
+    ///
+    /// Paid tier subscriptions are never touched.
+    pub async fn cleanup_addon_only_subscription(
+        &self,
+        org_id: Uuid,
+    ) -> BillingResult<AddonOnlyCleanup> {
+        let current: Option<(Option<String>,)> = sqlx::query_as(
+            "SELECT stripe_price_id FROM subscriptions
+             WHERE org_id = $1 AND status IN ('active', 'trialing', 'past_due')
+               AND cancel_at_period_end = false",
         )
-        .bind(addon_id)
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        let Some((price_id,)) = current else {
+            return Ok(AddonOnlyCleanup::Keep);
+        };
+        let addon_only_price = self.stripe.config().price_ids.addon_only.as_deref();
+        if SubscriptionKind::from_price_id(price_id.as_deref(), addon_only_price)
+            != SubscriptionKind::AddonOnly
+        {
+            return Ok(AddonOnlyCleanup::Keep);
+        }
+
+        let (billed, ending): (i64, i64) = sqlx::query_as(
+            "SELECT COUNT(*) FILTER (WHERE stripe_item_id IS NOT NULL),
+                    COUNT(*) FILTER (WHERE stripe_item_id IS NULL)
+             FROM subscription_addons
+             WHERE org_id = $1 AND status = 'active'",
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        let cleanup = AddonOnlyCleanup::for_remaining(billed, ending);
+        if cleanup != AddonOnlyCleanup::Keep {
+            let sub_service = SubscriptionService::new(self.stripe.clone(), self.pool.clone());
+            sub_service
+                .cancel_addon_only_subscription(
+                    org_id,
+                    cleanup == AddonOnlyCleanup::CancelAtPeriodEnd,
+                )
+                .await?;
+        }
+
+        Ok(cleanup)
+    }
+
+    /// Cancel add-ons whose period-end removal date has passed
+    ///
+    /// Called when a new billing period starts (invoice.paid).
+    pub async fn expire_period_end_removals(&self, org_id: Uuid) -> BillingResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE subscription_addons SET status = 'canceled'
+            WHERE org_id = $1
+              AND status = 'active'
+              AND (metadata->>'remove_at_period_end')::boolean IS TRUE
+              AND canceled_at <= NOW()
+            "#,
+        )
+        .bind(org_id)
         .execute(&self.pool)
         .await
         .map_err(|e| BillingError::Database(e.to_string()))?;
 
-        Ok(())
+        let expired = result.rows_affected();
+        if expired > 0 {
+            tracing::info!(
+                org_id = %org_id,
+                expired = expired,
+                "Expired add-ons scheduled for removal at period end"
+            );
+        }
+
+        Ok(expired)
     }
 
     /// List all active add-ons for an organization
@@ -866,6 +1102,7 @@ impl AddonService {
                     "SELECT u.email FROM users u
                      JOIN org_members om ON u.id = om.user_id
                      WHERE om.org_id = $1 AND om.role = 'owner'
+                     ORDER BY u.created_at ASC, u.id ASC
                      LIMIT 1",
                 )
                 .bind(org_id)
@@ -986,3 +1223,76 @@ pub struct AddonQuantities {
     pub extra_api_keys: u32,
     pub extra_team_members: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_timing_is_immediate() {
+        assert_eq!(AddonRemovalTiming::default(), AddonRemovalTiming::Immediate);
+    }
+
+    #[test]
+    fn test_timing_proration_mapping() {
+        assert_eq!(
+            AddonRemovalTiming::Immediate.proration_behavior(),
+            SubscriptionProrationBehavior::CreateProrations
+        );
+        assert_eq!(
+            AddonRemovalTiming::PeriodEnd.proration_behavior(),
+            SubscriptionProrationBehavior::None
+        );
+    }
+
+    #[test]
+    fn test_delete_params_serialization() {
+        let immediate = serde_json::to_value(DeleteSubscriptionItemParams::for_timing(
+            AddonRemovalTiming::Immediate,
+        ))
+        .unwrap();
+        assert_eq!(immediate["proration_behavior"], "create_prorations");
+
+        let period_end = serde_json::to_value(DeleteSubscriptionItemParams::for_timing(
+            AddonRemovalTiming::PeriodEnd,
+        ))
+        .unwrap();
+        assert_eq!(period_end["proration_behavior"], "none");
+    }
+
+    #[test]
+    fn test_timing_string_roundtrip() {
+        for timing in [AddonRemovalTiming::Immediate, AddonRemovalTiming::PeriodEnd] {
+            assert_eq!(AddonRemovalTiming::from_str(timing.as_str()), Some(timing));
+        }
+        assert_eq!(AddonRemovalTiming::from_str("later"), None);
+    }
+
+    #[test]
+    fn test_addon_only_cleanup_when_empty() {
+        assert_eq!(
+            AddonOnlyCleanup::for_remaining(0, 0),
+            AddonOnlyCleanup::CancelNow
+        );
+    }
+
+    #[test]
+    fn test_addon_only_cleanup_waits_for_period_end_removals() {
+        assert_eq!(
+            AddonOnlyCleanup::for_remaining(0, 2),
+            AddonOnlyCleanup::CancelAtPeriodEnd
+        );
+    }
+
+    #[test]
+    fn test_addon_only_cleanup_keeps_billed_addons() {
+        assert_eq!(
+            AddonOnlyCleanup::for_remaining(1, 0),
+            AddonOnlyCleanup::Keep
+        );
+        assert_eq!(
+            AddonOnlyCleanup::for_remaining(1, 3),
+            AddonOnlyCleanup::Keep
+        );
+    }
+}
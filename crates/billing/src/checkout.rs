@@ -1,15 +1,18 @@
 //! Stripe Checkout sessions
 
+use plexmcp_shared::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use stripe::{
     CheckoutSession, CheckoutSessionMode, CreateCheckoutSession, CreateCheckoutSessionDiscounts,
     CreateCheckoutSessionLineItems, CreateCheckoutSessionLineItemsPriceData,
-    CreateCheckoutSessionLineItemsPriceDataProductData, CustomerId,
+    CreateCheckoutSessionLineItemsPriceDataProductData, CustomerId, ListPromotionCodes,
+    PromotionCode,
 };
 use uuid::Uuid;
 
 use crate::client::StripeClient;
+use crate::discounts::{Discount, DiscountResolver, DiscountSource};
 use crate::error::{BillingError, BillingResult};
 use crate::metered::MeteredBillingService;
 use crate::subscriptions::SubscriptionService;
@@ -32,17 +35,188 @@ impl BillingInterval {
             _ => None,
         }
     }
+
+    /// Value written to and read back from checkout `billing_interval` metadata
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Monthly => "monthly",
+            Self::Annual => "annual",
+        }
+    }
+
+    /// Interval recorded in checkout metadata, monthly if missing or unrecognised
+    pub fn from_metadata(metadata: &std::collections::HashMap<String, String>) -> Self {
+        metadata
+            .get("billing_interval")
+            .and_then(|s| Self::from_str(s))
+            .unwrap_or_default()
+    }
+}
+
+/// A promotion code that passed validation, ready to attach to a checkout session
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromoCodeDiscount {
+    /// Customer-facing code, as entered
+    pub code: String,
+    /// Stripe coupon behind the promotion code
+    pub coupon_id: String,
+    /// Discount keyed by the Stripe promotion code id
+    pub discount: Discount,
+}
+
+/// Check that a Stripe promotion code can be redeemed by `customer_id` at `now`
+fn promo_code_discount(
+    promo: &PromotionCode,
+    customer_id: &str,
+    now: i64,
+) -> BillingResult<PromoCodeDiscount> {
+    let invalid = |reason: &str| {
+        Err(BillingError::InvalidCoupon(format!(
+            "Promo code {} {}",
+            promo.code, reason
+        )))
+    };
+    let coupon = &promo.coupon;
+
+    if !promo.active || coupon.deleted || coupon.valid == Some(false) {
+        return invalid("is no longer active");
+    }
+    if promo.expires_at.is_some_and(|at| at <= now) || coupon.redeem_by.is_some_and(|at| at <= now)
+    {
+        return invalid("has expired");
+    }
+    if promo
+        .max_redemptions
+        .is_some_and(|max| promo.times_redeemed >= max)
+    {
+        return invalid("has reached its redemption limit");
+    }
+    if let Some(restricted_to) = &promo.customer {
+        if restricted_to.id().as_str() != customer_id {
+            return invalid("is not available for this account");
+        }
+    }
+
+    let id = promo.id.to_string();
+    let discount = match (coupon.percent_off, coupon.amount_off) {
+        (Some(percent), _) => Discount::percent_off(id, DiscountSource::PromoCode, percent),
+        (None, Some(amount_cents)) => {
+            Discount::amount_off(id, DiscountSource::PromoCode, amount_cents)
+        }
+        (None, None) => return invalid("has no discount configured"),
+    };
+
+    Ok(PromoCodeDiscount {
+        code: promo.code.clone(),
+        coupon_id: coupon.id.to_string(),
+        discount,
+    })
+}
+
+/// Check that a coupon zeroes the first invoice and nothing after it
+fn check_first_month_free_coupon(coupon: &stripe::Coupon) -> BillingResult<()> {
+    if coupon.deleted || coupon.valid == Some(false) {
+        return Err(BillingError::InvalidCoupon(format!(
+            "First-month-free coupon {} is no longer valid",
+            coupon.id
+        )));
+    }
+    if coupon.percent_off != Some(100.0) || coupon.duration != Some(stripe::CouponDuration::Once) {
+        return Err(BillingError::Config(format!(
+            "First-month-free coupon {} must be 100% off with duration once",
+            coupon.id
+        )));
+    }
+    Ok(())
+}
+
+/// Session `discounts` entry for a coupon; promo codes are attached by promotion code id
+fn session_discount(coupon: &Discount) -> CreateCheckoutSessionDiscounts {
+    if coupon.source == DiscountSource::PromoCode {
+        CreateCheckoutSessionDiscounts {
+            promotion_code: Some(coupon.id.clone()),
+            ..Default::default()
+        }
+    } else {
+        CreateCheckoutSessionDiscounts {
+            coupon: Some(coupon.id.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+/// The coupon to attach to a checkout session after applying the stacking rules
+///
+/// Stripe Checkout accepts a single coupon per session, so when the rules
+/// allow several the most valuable one is used.
+fn checkout_coupon(
+    resolver: &DiscountResolver,
+    discounts: &[Discount],
+    subtotal_cents: i64,
+) -> Option<Discount> {
+    let resolved = resolver.resolve(discounts, subtotal_cents);
+    let mut coupons = resolved.coupons();
+    let coupon = coupons.next().cloned();
+    if coupons.next().is_some() {
+        tracing::warn!("Stripe Checkout supports one coupon per session; extra coupons dropped");
+    }
+    coupon
+}
+
+/// Whether an organization has already started a subscription with first month free
+async fn first_month_free_redeemed(pool: &PgPool, org_id: Uuid) -> BillingResult<bool> {
+    let (redeemed,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS(SELECT 1 FROM subscriptions WHERE org_id = $1 AND first_month_free)",
+    )
+    .bind(org_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(redeemed)
+}
+
+/// Flag a subscription as started with the first-month-free coupon
+///
+/// Kept separate from trial_start/trial_end: the subscriber is billed from
+/// day one, just with a zero first invoice. Returns whether a row matched.
+pub(crate) async fn mark_first_month_free(
+    pool: &PgPool,
+    org_id: Uuid,
+    stripe_subscription_id: &str,
+) -> BillingResult<bool> {
+    let result = sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET first_month_free = TRUE, updated_at = NOW()
+        WHERE org_id = $1 AND stripe_subscription_id = $2
+        "#,
+    )
+    .bind(org_id)
+    .bind(stripe_subscription_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
 }
 
 /// Checkout service for creating Stripe checkout sessions
 pub struct CheckoutService {
     stripe: StripeClient,
     pool: PgPool,
+    discount_resolver: DiscountResolver,
+    /// 100%-off-once coupon for first-month-free signups (`STRIPE_COUPON_FIRST_MONTH_FREE`)
+    first_month_free_coupon: Option<String>,
 }
 
 impl CheckoutService {
     pub fn new(stripe: StripeClient, pool: PgPool) -> Self {
-        Self { stripe, pool }
+        Self {
+            stripe,
+            pool,
+            discount_resolver: DiscountResolver::from_env(),
+            first_month_free_coupon: std::env::var("STRIPE_COUPON_FIRST_MONTH_FREE")
+                .ok()
+                .filter(|id| !id.trim().is_empty()),
+        }
     }
 
     /// Verify that a Stripe customer ID belongs to the given organization (defense-in-depth)
@@ -102,20 +276,10 @@ impl CheckoutService {
         self.verify_customer_ownership(org_id, customer_id).await?;
 
         // Get the appropriate price ID based on billing interval
-        let price_id = match billing_interval {
-            BillingInterval::Monthly => self
-                .stripe
-                .config()
-                .price_id_for_tier(tier)
-                .ok_or_else(|| BillingError::InvalidTier(tier.to_string()))?,
-            BillingInterval::Annual => self
-                .stripe
-                .config()
-                .annual_price_id_for_tier(tier)
-                .ok_or_else(|| {
-                    BillingError::InvalidTier(format!("{} (annual pricing not configured)", tier))
-                })?,
-        };
+        let price_id = self
+            .stripe
+            .config()
+            .price_id_for_interval(tier, billing_interval)?;
 
         let customer_id = customer_id
             .parse::<CustomerId>()
@@ -133,10 +297,7 @@ impl CheckoutService {
         metadata.insert("tier".to_string(), tier.to_string());
         metadata.insert(
             "billing_interval".to_string(),
-            match billing_interval {
-                BillingInterval::Monthly => "monthly".to_string(),
-                BillingInterval::Annual => "annual".to_string(),
-            },
+            billing_interval.as_str().to_string(),
         );
 
         // Build line items - start with the base subscription price
@@ -199,24 +360,213 @@ impl CheckoutService {
         tier: &str,
         billing_interval: BillingInterval,
         coupon_id: &str,
+    ) -> BillingResult<CheckoutSession> {
+        self.create_subscription_checkout_with_discounts(
+            org_id,
+            customer_id,
+            tier,
+            billing_interval,
+            &[Discount::amount_off(
+                coupon_id,
+                DiscountSource::Reactivation,
+                0,
+            )],
+        )
+        .await
+    }
+
+    /// Create a checkout session with the discounts allowed by the stacking rules
+    ///
+    /// Stripe Checkout accepts a single coupon per session, so only the most
+    /// valuable applied coupon is attached. Account credits are applied by
+    /// Stripe from the customer balance and are not sent with the session.
+    pub async fn create_subscription_checkout_with_discounts(
+        &self,
+        org_id: Uuid,
+        customer_id: &str,
+        tier: &str,
+        billing_interval: BillingInterval,
+        discounts: &[Discount],
+    ) -> BillingResult<CheckoutSession> {
+        self.create_discounted_checkout(
+            org_id,
+            customer_id,
+            tier,
+            billing_interval,
+            discounts,
+            None,
+        )
+        .await
+    }
+
+    /// Look up an active Stripe promotion code and check it can be redeemed
+    ///
+    /// Returns `BillingError::InvalidCoupon` if the code does not exist, is
+    /// inactive or expired, has no redemptions left, or belongs to another customer.
+    pub async fn validate_promo_code(
+        &self,
+        code: &str,
+        customer_id: &str,
+    ) -> BillingResult<PromoCodeDiscount> {
+        let code = code.trim();
+        if code.is_empty() {
+            return Err(BillingError::InvalidCoupon(
+                "Promo code is empty".to_string(),
+            ));
+        }
+
+        let params = ListPromotionCodes {
+            code: Some(code),
+            active: Some(true),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let promo = PromotionCode::list(self.stripe.inner(), &params)
+            .await?
+            .data
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                BillingError::InvalidCoupon(format!("Promo code {} is not valid", code))
+            })?;
+
+        promo_code_discount(
+            &promo,
+            customer_id,
+            time::OffsetDateTime::now_utc().unix_timestamp(),
+        )
+    }
+
+    /// Create a checkout session for a new subscription with a promo code applied
+    ///
+    /// The code is validated first; the applied coupon is recorded in the
+    /// session metadata so `CheckoutResponse` can report it.
+    pub async fn create_subscription_checkout_with_promo_code(
+        &self,
+        org_id: Uuid,
+        customer_id: &str,
+        tier: &str,
+        billing_interval: BillingInterval,
+        promo_code: &str,
+    ) -> BillingResult<CheckoutSession> {
+        self.create_subscription_checkout_with_offers(
+            org_id,
+            customer_id,
+            tier,
+            billing_interval,
+            Some(promo_code),
+            false,
+        )
+        .await
+    }
+
+    /// Create a checkout session whose first invoice is free
+    ///
+    /// Attaches the first-month-free coupon (100% off, once) instead of a
+    /// trial: a payment method is collected and the customer is a paying
+    /// subscriber from day one. Each organization can redeem it once, and
+    /// only if it has never had a free trial.
+    pub async fn create_subscription_checkout_first_month_free(
+        &self,
+        org_id: Uuid,
+        customer_id: &str,
+        tier: &str,
+        billing_interval: BillingInterval,
+    ) -> BillingResult<CheckoutSession> {
+        self.create_subscription_checkout_with_offers(
+            org_id,
+            customer_id,
+            tier,
+            billing_interval,
+            None,
+            true,
+        )
+        .await
+    }
+
+    /// Create a checkout session with a promo code and/or first month free
+    ///
+    /// Each offer is validated as in the single-offer methods. When both are
+    /// requested the stacking rules decide which one is attached.
+    pub async fn create_subscription_checkout_with_offers(
+        &self,
+        org_id: Uuid,
+        customer_id: &str,
+        tier: &str,
+        billing_interval: BillingInterval,
+        promo_code: Option<&str>,
+        first_month_free: bool,
+    ) -> BillingResult<CheckoutSession> {
+        let mut discounts = Vec::new();
+        if first_month_free {
+            discounts.push(self.first_month_free_discount(org_id).await?);
+        }
+        let promo = match promo_code {
+            Some(code) => Some(self.validate_promo_code(code, customer_id).await?),
+            None => None,
+        };
+
+        self.create_discounted_checkout(
+            org_id,
+            customer_id,
+            tier,
+            billing_interval,
+            &discounts,
+            promo,
+        )
+        .await
+    }
+
+    /// The first-month-free coupon, if this organization may still redeem it
+    async fn first_month_free_discount(&self, org_id: Uuid) -> BillingResult<Discount> {
+        let coupon_id = self.first_month_free_coupon.as_deref().ok_or_else(|| {
+            BillingError::Config("STRIPE_COUPON_FIRST_MONTH_FREE not set".to_string())
+        })?;
+
+        if first_month_free_redeemed(&self.pool, org_id).await? {
+            return Err(BillingError::InvalidCoupon(
+                "First month free has already been used by this organization".to_string(),
+            ));
+        }
+        // A free first month is another free period; orgs that trialed don't get one
+        if !SubscriptionService::new(self.stripe.clone(), self.pool.clone())
+            .is_trial_eligible(org_id)
+            .await?
+        {
+            return Err(BillingError::InvalidCoupon(
+                "First month free is not available after a free trial".to_string(),
+            ));
+        }
+
+        let parsed_coupon_id = coupon_id
+            .parse::<stripe::CouponId>()
+            .map_err(|e| BillingError::Config(format!("Invalid coupon ID: {}", e)))?;
+        let coupon = stripe::Coupon::retrieve(self.stripe.inner(), &parsed_coupon_id, &[]).await?;
+        check_first_month_free_coupon(&coupon)?;
+
+        Ok(Discount::percent_off(
+            coupon_id,
+            DiscountSource::FirstMonthFree,
+            100.0,
+        ))
+    }
+
+    async fn create_discounted_checkout(
+        &self,
+        org_id: Uuid,
+        customer_id: &str,
+        tier: &str,
+        billing_interval: BillingInterval,
+        discounts: &[Discount],
+        promo: Option<PromoCodeDiscount>,
     ) -> BillingResult<CheckoutSession> {
         // SOC 2 CC6.1: Verify customer ID belongs to this organization (defense-in-depth)
         self.verify_customer_ownership(org_id, customer_id).await?;
 
-        let price_id = match billing_interval {
-            BillingInterval::Monthly => self
-                .stripe
-                .config()
-                .price_id_for_tier(tier)
-                .ok_or_else(|| BillingError::InvalidTier(tier.to_string()))?,
-            BillingInterval::Annual => self
-                .stripe
-                .config()
-                .annual_price_id_for_tier(tier)
-                .ok_or_else(|| {
-                    BillingError::InvalidTier(format!("{} (annual pricing not configured)", tier))
-                })?,
-        };
+        let price_id = self
+            .stripe
+            .config()
+            .price_id_for_interval(tier, billing_interval)?;
 
         let customer_id = customer_id
             .parse::<CustomerId>()
@@ -234,12 +584,41 @@ impl CheckoutService {
         metadata.insert("tier".to_string(), tier.to_string());
         metadata.insert(
             "billing_interval".to_string(),
-            match billing_interval {
-                BillingInterval::Monthly => "monthly".to_string(),
-                BillingInterval::Annual => "annual".to_string(),
-            },
+            billing_interval.as_str().to_string(),
         );
-        metadata.insert("reactivation_coupon".to_string(), coupon_id.to_string());
+
+        let mut candidates = discounts.to_vec();
+        candidates.extend(promo.as_ref().map(|p| p.discount.clone()));
+
+        // Ranking only matters when there is more than one discount to choose from
+        let subtotal_cents = if candidates.len() > 1 {
+            SubscriptionService::new(self.stripe.clone(), self.pool.clone())
+                .get_price_amount(price_id)
+                .await
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let coupon = checkout_coupon(&self.discount_resolver, &candidates, subtotal_cents);
+        if let Some(ref coupon) = coupon {
+            match coupon.source {
+                DiscountSource::Reactivation => {
+                    metadata.insert("reactivation_coupon".to_string(), coupon.id.clone());
+                }
+                // Read back by the checkout webhook to flag the subscription
+                DiscountSource::FirstMonthFree => {
+                    metadata.insert("first_month_free".to_string(), "true".to_string());
+                }
+                // Read back by AppliedCoupon::from_session
+                DiscountSource::PromoCode => {
+                    if let Some(promo) = &promo {
+                        metadata.insert("promo_code".to_string(), promo.code.clone());
+                        metadata.insert("promo_coupon_id".to_string(), promo.coupon_id.clone());
+                    }
+                }
+                DiscountSource::AccountCredit => {}
+            }
+        }
 
         // Build line items
         let mut line_items = vec![CreateCheckoutSessionLineItems {
@@ -259,10 +638,7 @@ impl CheckoutService {
         }
 
         // Apply the coupon discount
-        let discounts = vec![CreateCheckoutSessionDiscounts {
-            coupon: Some(coupon_id.to_string()),
-            ..Default::default()
-        }];
+        let session_discounts = coupon.as_ref().map(|coupon| vec![session_discount(coupon)]);
 
         let params = CreateCheckoutSession {
             customer: Some(customer_id),
@@ -271,9 +647,11 @@ impl CheckoutService {
             success_url: Some(&success_url),
             cancel_url: Some(&cancel_url),
             metadata: Some(metadata),
-            discounts: Some(discounts),
+            discounts: session_discounts,
             // Don't allow promotion codes when a coupon is already applied
             allow_promotion_codes: None,
+            // Collect a card even when a 100%-off coupon zeroes the first invoice
+            payment_method_collection: Some(stripe::CheckoutSessionPaymentMethodCollection::Always),
             billing_address_collection: Some(stripe::CheckoutSessionBillingAddressCollection::Auto),
             ..Default::default()
         };
@@ -285,7 +663,7 @@ impl CheckoutService {
             session_id = %session.id,
             tier = %tier,
             billing_interval = ?billing_interval,
-            coupon_id = %coupon_id,
+            coupon_id = ?coupon.as_ref().map(|c| c.id.as_str()),
             "Created checkout session with coupon"
         );
 
@@ -322,6 +700,12 @@ impl CheckoutService {
         // SOC 2 CC6.1: Verify customer ID belongs to this organization (defense-in-depth)
         self.verify_customer_ownership(org_id, customer_id).await?;
 
+        // The webhook switches the subscription to this price after payment, so
+        // refuse up front rather than take payment for an unconfigured interval
+        self.stripe
+            .config()
+            .price_id_for_interval(new_tier, billing_interval)?;
+
         let customer_id_parsed = customer_id
             .parse::<CustomerId>()
             .map_err(|e| BillingError::StripeApi(format!("Invalid customer ID: {}", e)))?;
@@ -335,7 +719,7 @@ impl CheckoutService {
             .preview_upgrade_proration(org_id, new_tier)
             .await?;
 
-        let proration_cents = proration_preview.proration_amount_cents;
+        let proration_cents = proration_preview.proration_amount.amount_cents;
 
         // IMPORTANT: Overages must always be paid in full. Proration credits should not
         // offset overage charges. Calculate total carefully:
@@ -393,10 +777,7 @@ impl CheckoutService {
         metadata.insert("new_tier".to_string(), new_tier.to_string());
         metadata.insert(
             "billing_interval".to_string(),
-            match billing_interval {
-                BillingInterval::Monthly => "monthly".to_string(),
-                BillingInterval::Annual => "annual".to_string(),
-            },
+            billing_interval.as_str().to_string(),
         );
         metadata.insert("proration_cents".to_string(), proration_cents.to_string());
         metadata.insert("overage_cents".to_string(), pending_overages.to_string());
@@ -411,10 +792,10 @@ impl CheckoutService {
 
         let description = if pending_overages > 0 {
             format!(
-                "Prorated upgrade to {} (${:.2}) + Outstanding overages (${:.2})",
+                "Prorated upgrade to {} ({}) + Outstanding overages ({})",
                 tier_display,
-                proration_cents as f64 / 100.0,
-                pending_overages as f64 / 100.0
+                Money::from_cents_usd(proration_cents),
+                Money::from_cents_usd(pending_overages)
             )
         } else {
             format!(
@@ -567,18 +948,313 @@ impl CheckoutService {
     }
 }
 
+/// Promo code applied to a checkout session, for the confirmation page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AppliedCoupon {
+    pub promo_code: String,
+    pub coupon_id: String,
+    /// Amounts as priced by Stripe when the session was created, in cents
+    pub amount_subtotal_cents: Option<i64>,
+    pub amount_discount_cents: Option<i64>,
+    pub amount_total_cents: Option<i64>,
+}
+
+impl AppliedCoupon {
+    /// Promo code recorded on a checkout session, if one was applied
+    pub fn from_session(session: &CheckoutSession) -> Option<Self> {
+        let metadata = session.metadata.as_ref()?;
+        Some(Self {
+            promo_code: metadata.get("promo_code")?.clone(),
+            coupon_id: metadata.get("promo_coupon_id")?.clone(),
+            amount_subtotal_cents: session.amount_subtotal,
+            amount_discount_cents: session.total_details.as_ref().map(|t| t.amount_discount),
+            amount_total_cents: session.amount_total,
+        })
+    }
+}
+
 /// Response for creating a checkout session
 #[derive(Debug, serde::Serialize)]
 pub struct CheckoutResponse {
     pub session_id: String,
     pub url: Option<String>,
+    pub billing_interval: BillingInterval,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_coupon: Option<AppliedCoupon>,
 }
 
 impl From<CheckoutSession> for CheckoutResponse {
     fn from(session: CheckoutSession) -> Self {
+        let billing_interval = session
+            .metadata
+            .as_ref()
+            .map(BillingInterval::from_metadata)
+            .unwrap_or_default();
+        let applied_coupon = AppliedCoupon::from_session(&session);
         Self {
             session_id: session.id.to_string(),
             url: session.url,
+            billing_interval,
+            applied_coupon,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discounts::StackingRules;
+
+    #[test]
+    fn test_annual_checkout_response_carries_interval() {
+        let session = CheckoutSession {
+            metadata: Some(
+                [(
+                    "billing_interval".to_string(),
+                    BillingInterval::Annual.as_str().to_string(),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        };
+        assert_eq!(
+            CheckoutResponse::from(session).billing_interval,
+            BillingInterval::Annual
+        );
+
+        // Sessions created before the interval was recorded are monthly
+        let response = CheckoutResponse::from(CheckoutSession::default());
+        assert_eq!(response.billing_interval, BillingInterval::Monthly);
+    }
+
+    fn promotion_code(percent_off: Option<f64>, amount_off: Option<i64>) -> PromotionCode {
+        PromotionCode {
+            id: "promo_launch".parse().unwrap(),
+            code: "LAUNCH20".to_string(),
+            active: true,
+            coupon: stripe::Coupon {
+                id: "coupon_launch".parse().unwrap(),
+                percent_off,
+                amount_off,
+                valid: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_promo_code_becomes_promo_discount() {
+        let promo = promo_code_discount(&promotion_code(Some(20.0), None), "cus_1", 1_000).unwrap();
+        assert_eq!(promo.code, "LAUNCH20");
+        assert_eq!(promo.coupon_id, "coupon_launch");
+        assert_eq!(
+            promo.discount,
+            Discount::percent_off("promo_launch", DiscountSource::PromoCode, 20.0)
+        );
+
+        let promo = promo_code_discount(&promotion_code(None, Some(500)), "cus_1", 1_000).unwrap();
+        assert_eq!(promo.discount.value_cents(2_900), 500);
+    }
+
+    #[test]
+    fn test_unredeemable_promo_codes_rejected() {
+        let mut inactive = promotion_code(Some(20.0), None);
+        inactive.active = false;
+        let mut expired = promotion_code(Some(20.0), None);
+        expired.expires_at = Some(1_000);
+        let mut coupon_invalid = promotion_code(Some(20.0), None);
+        coupon_invalid.coupon.valid = Some(false);
+        let mut exhausted = promotion_code(Some(20.0), None);
+        exhausted.max_redemptions = Some(100);
+        exhausted.times_redeemed = 100;
+        let mut other_customer = promotion_code(Some(20.0), None);
+        other_customer.customer = Some(stripe::Expandable::Id("cus_2".parse().unwrap()));
+        let no_discount = promotion_code(None, None);
+
+        for promo in [
+            inactive,
+            expired,
+            coupon_invalid,
+            exhausted,
+            other_customer,
+            no_discount,
+        ] {
+            assert!(matches!(
+                promo_code_discount(&promo, "cus_1", 1_000),
+                Err(BillingError::InvalidCoupon(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_first_month_free_attached_as_coupon() {
+        let discount = session_discount(&Discount::percent_off(
+            "first_month_free",
+            DiscountSource::FirstMonthFree,
+            100.0,
+        ));
+        assert_eq!(discount.coupon.as_deref(), Some("first_month_free"));
+        assert_eq!(discount.promotion_code, None);
+
+        let promo = session_discount(&Discount::percent_off(
+            "promo_launch",
+            DiscountSource::PromoCode,
+            20.0,
+        ));
+        assert_eq!(promo.coupon, None);
+        assert_eq!(promo.promotion_code.as_deref(), Some("promo_launch"));
+    }
+
+    #[test]
+    fn test_promo_and_first_month_free_reduced_to_best_coupon() {
+        let first_month_free =
+            Discount::percent_off("first_month_free", DiscountSource::FirstMonthFree, 100.0);
+        let promo = Discount::amount_off("promo_launch", DiscountSource::PromoCode, 500);
+        let discounts = [promo.clone(), first_month_free.clone()];
+
+        let coupon = checkout_coupon(&DiscountResolver::default(), &discounts, 2900);
+        assert_eq!(coupon, Some(first_month_free));
+
+        // A promo covering the whole first invoice ties; ties keep input order
+        let big_promo = Discount::amount_off("promo_big", DiscountSource::PromoCode, 5000);
+        let discounts = [big_promo.clone(), discounts[1].clone()];
+        let coupon = checkout_coupon(&DiscountResolver::default(), &discounts, 2900);
+        assert_eq!(coupon, Some(big_promo));
+    }
+
+    #[test]
+    fn test_checkout_attaches_one_coupon_when_stacking_allowed() {
+        let resolver = DiscountResolver::new(StackingRules {
+            allow_coupon_stacking: true,
+            ..Default::default()
+        });
+        let discounts = [
+            Discount::amount_off("promo_launch", DiscountSource::PromoCode, 500),
+            Discount::amount_off("rx_credit", DiscountSource::Reactivation, 1500),
+        ];
+
+        let coupon = checkout_coupon(&resolver, &discounts, 2900).unwrap();
+        assert_eq!(coupon.id, "rx_credit");
+    }
+
+    #[test]
+    fn test_first_month_free_coupon_must_be_full_and_once() {
+        let coupon = |percent_off, duration| stripe::Coupon {
+            id: "first_month_free".parse().unwrap(),
+            percent_off,
+            duration,
+            valid: Some(true),
+            ..Default::default()
+        };
+        assert!(check_first_month_free_coupon(&coupon(
+            Some(100.0),
+            Some(stripe::CouponDuration::Once)
+        ))
+        .is_ok());
+        assert!(matches!(
+            check_first_month_free_coupon(&coupon(Some(50.0), Some(stripe::CouponDuration::Once))),
+            Err(BillingError::Config(_))
+        ));
+        assert!(matches!(
+            check_first_month_free_coupon(&coupon(
+                Some(100.0),
+                Some(stripe::CouponDuration::Forever)
+            )),
+            Err(BillingError::Config(_))
+        ));
+
+        let mut expired = coupon(Some(100.0), Some(stripe::CouponDuration::Once));
+        expired.valid = Some(false);
+        assert!(matches!(
+            check_first_month_free_coupon(&expired),
+            Err(BillingError::InvalidCoupon(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_first_month_free_flag_blocks_second_redemption() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Promo Org', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("promo-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        let subscription_id = format!("sub_{}", org_id.simple());
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (id, org_id, stripe_subscription_id, status)
+            VALUES ($1, $2, $3, 'active')
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(org_id)
+        .bind(&subscription_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(!first_month_free_redeemed(&pool, org_id).await.unwrap());
+        assert!(!mark_first_month_free(&pool, org_id, "sub_other")
+            .await
+            .unwrap());
+        assert!(mark_first_month_free(&pool, org_id, &subscription_id)
+            .await
+            .unwrap());
+        assert!(first_month_free_redeemed(&pool, org_id).await.unwrap());
+
+        // Flagged, but not recorded as a trial
+        let (trial_start,): (Option<time::OffsetDateTime>,) =
+            sqlx::query_as("SELECT trial_start FROM subscriptions WHERE org_id = $1")
+                .bind(org_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(trial_start, None);
+    }
+
+    #[test]
+    fn test_checkout_response_reports_applied_coupon() {
+        let session = CheckoutSession {
+            metadata: Some(
+                [
+                    ("promo_code".to_string(), "LAUNCH20".to_string()),
+                    ("promo_coupon_id".to_string(), "coupon_launch".to_string()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            amount_subtotal: Some(2_900),
+            amount_total: Some(2_320),
+            total_details: Some(stripe::PaymentPagesCheckoutSessionTotalDetails {
+                amount_discount: 580,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let applied = CheckoutResponse::from(session).applied_coupon.unwrap();
+        assert_eq!(applied.promo_code, "LAUNCH20");
+        assert_eq!(applied.amount_discount_cents, Some(580));
+        assert_eq!(applied.amount_total_cents, Some(2_320));
+
+        assert_eq!(
+            CheckoutResponse::from(CheckoutSession::default()).applied_coupon,
+            None
+        );
+    }
+
+    #[test]
+    fn test_billing_interval_metadata_round_trips() {
+        for interval in [BillingInterval::Monthly, BillingInterval::Annual] {
+            assert_eq!(BillingInterval::from_str(interval.as_str()), Some(interval));
         }
     }
 }
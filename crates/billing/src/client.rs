@@ -1,8 +1,29 @@
 //! Stripe client configuration
 
-use stripe::Client;
+use sha2::{Digest, Sha256};
+use stripe::{Client, RequestStrategy};
+use uuid::Uuid;
 
+use crate::checkout::BillingInterval;
 use crate::error::{BillingError, BillingResult};
+use crate::webhooks::DEFAULT_TIMESTAMP_TOLERANCE_SECS;
+
+/// Deterministic Stripe idempotency key for a mutating call
+///
+/// Retrying the same operation for the same org, tier and scope yields the
+/// same key, so Stripe replays the original response instead of acting twice.
+/// Stripe remembers keys for 24 hours; `scope` must change for a deliberate
+/// repeat (e.g. a new idempotency attempt ID), and must not change between
+/// retries of the same attempt.
+pub fn idempotency_key(org_id: Uuid, operation: &str, tier: &str, scope: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(org_id.as_bytes());
+    hasher.update(b"|");
+    hasher.update(tier.as_bytes());
+    hasher.update(b"|");
+    hasher.update(scope.as_bytes());
+    format!("{}-{}", operation, &hex::encode(hasher.finalize())[..32])
+}
 
 /// Configuration for Stripe billing
 #[derive(Debug, Clone)]
@@ -11,10 +32,27 @@ pub struct StripeConfig {
     pub secret_key: String,
     /// Stripe webhook signing secret
     pub webhook_secret: String,
+    /// Maximum accepted difference (seconds) between our clock and a webhook
+    /// signature timestamp (`WEBHOOK_TIMESTAMP_TOLERANCE_SECS`, default 300)
+    ///
+    /// Per-org overrides live in `WebhookConfig`.
+    pub webhook_tolerance_secs: i64,
     /// Price IDs for each subscription tier
     pub price_ids: PriceIds,
     /// Base URL for success/cancel redirects
     pub app_base_url: String,
+    /// `Stripe-Version` pinned on direct REST calls (`STRIPE_API_VERSION`)
+    ///
+    /// Unset sends no header, so Stripe uses the account's default version.
+    /// Typed async-stripe calls always use [`library_api_version`].
+    pub api_version: Option<String>,
+}
+
+/// Stripe API version async-stripe sends on its typed requests
+///
+/// Fixed by the library; its `Client` has no way to override it.
+pub fn library_api_version() -> &'static str {
+    stripe::generated::core::version::VERSION.as_str()
 }
 
 /// Stripe price IDs for subscription tiers and add-ons
@@ -62,6 +100,11 @@ impl StripeConfig {
                 .map_err(|_| BillingError::Config("STRIPE_SECRET_KEY not set".to_string()))?,
             webhook_secret: std::env::var("STRIPE_WEBHOOK_SECRET")
                 .map_err(|_| BillingError::Config("STRIPE_WEBHOOK_SECRET not set".to_string()))?,
+            webhook_tolerance_secs: std::env::var("WEBHOOK_TIMESTAMP_TOLERANCE_SECS")
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .map(|secs| secs.max(0))
+                .unwrap_or(DEFAULT_TIMESTAMP_TOLERANCE_SECS),
             price_ids: PriceIds {
                 // Subscription tiers (required)
                 pro: std::env::var("STRIPE_PRICE_PRO")
@@ -73,8 +116,13 @@ impl StripeConfig {
                 })?,
 
                 // Annual tiers (optional)
-                pro_annual: std::env::var("STRIPE_PRICE_PRO_ANNUAL").ok(),
-                team_annual: std::env::var("STRIPE_PRICE_TEAM_ANNUAL").ok(),
+                // (an empty value means not configured, never a blank price)
+                pro_annual: std::env::var("STRIPE_PRICE_PRO_ANNUAL")
+                    .ok()
+                    .filter(|id| !id.trim().is_empty()),
+                team_annual: std::env::var("STRIPE_PRICE_TEAM_ANNUAL")
+                    .ok()
+                    .filter(|id| !id.trim().is_empty()),
 
                 // Resource pack add-ons (stackable) - Free + Pro
                 extra_requests: std::env::var("STRIPE_PRICE_EXTRA_REQUESTS").ok(),
@@ -105,6 +153,10 @@ impl StripeConfig {
             },
             app_base_url: std::env::var("APP_BASE_URL")
                 .unwrap_or_else(|_| "http://localhost:3000".to_string()),
+            api_version: std::env::var("STRIPE_API_VERSION")
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty()),
         })
     }
 
@@ -128,6 +180,22 @@ impl StripeConfig {
         }
     }
 
+    /// Get the subscription price ID for a tier billed at `interval`
+    pub fn price_id_for_interval(
+        &self,
+        tier: &str,
+        interval: BillingInterval,
+    ) -> BillingResult<&str> {
+        match interval {
+            BillingInterval::Monthly => self
+                .price_id_for_tier(tier)
+                .ok_or_else(|| BillingError::InvalidTier(tier.to_string())),
+            BillingInterval::Annual => self.annual_price_id_for_tier(tier).ok_or_else(|| {
+                BillingError::InvalidTier(format!("{} (annual pricing not configured)", tier))
+            }),
+        }
+    }
+
     /// Get tier from price ID (handles both monthly and annual prices)
     pub fn tier_for_price_id(&self, price_id: &str) -> Option<&'static str> {
         if price_id == self.price_ids.pro {
@@ -175,18 +243,36 @@ impl StripeConfig {
     }
 }
 
+/// Base URL for direct Stripe REST calls
+const STRIPE_API_BASE: &str = "https://api.stripe.com/v1";
+
 /// Stripe billing client
 #[derive(Clone)]
 pub struct StripeClient {
     client: Client,
+    http: reqwest::Client,
     config: StripeConfig,
 }
 
 impl StripeClient {
     /// Create a new Stripe client from config
     pub fn new(config: StripeConfig) -> Self {
+        if let Some(version) = &config.api_version {
+            if version != library_api_version() {
+                tracing::warn!(
+                    pinned_version = %version,
+                    library_version = library_api_version(),
+                    "STRIPE_API_VERSION only applies to direct REST calls; \
+                     typed Stripe calls use the library's version"
+                );
+            }
+        }
         let client = Client::new(&config.secret_key);
-        Self { client, config }
+        Self {
+            client,
+            http: reqwest::Client::new(),
+            config,
+        }
     }
 
     /// Create a new Stripe client from environment variables
@@ -200,8 +286,217 @@ impl StripeClient {
         &self.client
     }
 
+    /// Client that sends `key` as the `Idempotency-Key` on its requests
+    ///
+    /// Use for a single mutating call; see `idempotency_key`.
+    pub fn with_idempotency_key(&self, key: impl Into<String>) -> Client {
+        self.client
+            .clone()
+            .with_strategy(RequestStrategy::Idempotent(key.into()))
+    }
+
+    /// Request to a Stripe REST endpoint async-stripe doesn't cover
+    ///
+    /// `path` is relative to `/v1`. Authenticates with the secret key and
+    /// sends the configured `Stripe-Version`, if any.
+    pub fn rest_request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/{}", STRIPE_API_BASE, path.trim_start_matches('/'));
+        let request = self
+            .http
+            .request(method, url)
+            .bearer_auth(&self.config.secret_key);
+        match &self.config.api_version {
+            Some(version) => request.header("Stripe-Version", version),
+            None => request,
+        }
+    }
+
     /// Get the config
     pub fn config(&self) -> &StripeConfig {
         &self.config
     }
+
+    /// Whether the configured key is a live-mode key (`sk_live_` / `rk_live_`)
+    pub fn is_live_mode(&self) -> bool {
+        let key = self.config.secret_key.trim();
+        key.starts_with("sk_live_") || key.starts_with("rk_live_")
+    }
+
+    /// Return an error if a live-mode key is configured
+    pub fn ensure_test_mode(&self) -> BillingResult<()> {
+        if self.is_live_mode() {
+            return Err(BillingError::Config(
+                "Refusing to run against live Stripe: a live-mode secret key is configured"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Panic if a live-mode key is configured
+    ///
+    /// Call from test setup so tests can never hit production Stripe.
+    pub fn assert_test_mode(&self) {
+        if self.is_live_mode() {
+            panic!("Stripe live-mode key configured in tests - refusing to continue");
+        }
+    }
+}
+
+#[cfg(test)]
+impl StripeConfig {
+    /// Test-mode config with placeholder tier prices and no annual or add-on prices
+    pub(crate) fn for_tests() -> Self {
+        Self {
+            secret_key: "sk_test_123".to_string(),
+            webhook_secret: "whsec_test".to_string(),
+            webhook_tolerance_secs: DEFAULT_TIMESTAMP_TOLERANCE_SECS,
+            price_ids: PriceIds {
+                pro: "price_pro".to_string(),
+                team: "price_team".to_string(),
+                enterprise: "price_enterprise".to_string(),
+                pro_annual: None,
+                team_annual: None,
+                extra_requests: None,
+                extra_mcps: None,
+                extra_api_keys: None,
+                extra_team_members: None,
+                analytics_pro: None,
+                priority_support: None,
+                webhook_alerts: None,
+                data_export: None,
+                custom_domain: None,
+                ip_allowlisting: None,
+                higher_rate_limits: None,
+                extended_retention: None,
+                addon_only: None,
+            },
+            app_base_url: "http://localhost:3000".to_string(),
+            api_version: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client_with_key(secret_key: &str) -> StripeClient {
+        client_with(secret_key, None)
+    }
+
+    fn client_with(secret_key: &str, api_version: Option<&str>) -> StripeClient {
+        StripeClient::new(StripeConfig {
+            secret_key: secret_key.to_string(),
+            api_version: api_version.map(str::to_string),
+            ..StripeConfig::for_tests()
+        })
+    }
+
+    #[test]
+    fn test_rest_request_pins_configured_api_version() {
+        let client = client_with("sk_test_123", Some("2024-06-20"));
+        let request = client
+            .rest_request(reqwest::Method::POST, "invoices/create_preview")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://api.stripe.com/v1/invoices/create_preview"
+        );
+        assert_eq!(request.headers()["Stripe-Version"], "2024-06-20");
+        assert_eq!(request.headers()["Authorization"], "Bearer sk_test_123");
+
+        // Unpinned: Stripe falls back to the account's default version
+        let request = client_with_key("sk_test_123")
+            .rest_request(reqwest::Method::POST, "/invoices/in_123")
+            .build()
+            .unwrap();
+        assert_eq!(request.url().path(), "/v1/invoices/in_123");
+        assert!(!request.headers().contains_key("Stripe-Version"));
+    }
+
+    #[test]
+    fn test_price_id_for_interval_picks_annual_price() {
+        let mut config = client_with_key("sk_test_123").config().clone();
+        config.price_ids.team_annual = Some("price_team_annual".to_string());
+
+        assert_eq!(
+            config
+                .price_id_for_interval("team", BillingInterval::Annual)
+                .unwrap(),
+            "price_team_annual"
+        );
+        assert_eq!(
+            config
+                .price_id_for_interval("team", BillingInterval::Monthly)
+                .unwrap(),
+            "price_team"
+        );
+        // No silent fallback to the monthly price
+        assert!(matches!(
+            config.price_id_for_interval("pro", BillingInterval::Annual),
+            Err(BillingError::InvalidTier(_))
+        ));
+    }
+
+    #[test]
+    fn test_test_mode_key_passes_guard() {
+        let client = client_with_key("sk_test_123");
+        assert!(!client.is_live_mode());
+        assert!(client.ensure_test_mode().is_ok());
+        client.assert_test_mode();
+    }
+
+    #[test]
+    fn test_live_mode_key_is_rejected() {
+        for key in ["sk_live_123", "rk_live_123"] {
+            let client = client_with_key(key);
+            assert!(client.is_live_mode());
+            assert!(matches!(
+                client.ensure_test_mode(),
+                Err(BillingError::Config(_))
+            ));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "live-mode key")]
+    fn test_assert_test_mode_panics_on_live_key() {
+        client_with_key("sk_live_123").assert_test_mode();
+    }
+
+    #[test]
+    fn test_idempotency_key_is_deterministic() {
+        let org_id = Uuid::new_v4();
+        let key = idempotency_key(org_id, "create_subscription", "pro", "sub_1@100");
+        assert_eq!(
+            key,
+            idempotency_key(org_id, "create_subscription", "pro", "sub_1@100")
+        );
+        assert!(key.starts_with("create_subscription-"));
+        assert!(key.len() <= 255);
+    }
+
+    #[test]
+    fn test_idempotency_key_differs_per_input() {
+        let org_id = Uuid::new_v4();
+        let key = idempotency_key(org_id, "create_subscription", "pro", "none");
+        assert_ne!(
+            key,
+            idempotency_key(Uuid::new_v4(), "create_subscription", "pro", "none")
+        );
+        assert_ne!(
+            key,
+            idempotency_key(org_id, "update_subscription", "pro", "none")
+        );
+        assert_ne!(
+            key,
+            idempotency_key(org_id, "create_subscription", "team", "none")
+        );
+        assert_ne!(
+            key,
+            idempotency_key(org_id, "create_subscription", "pro", "sub_1@100")
+        );
+    }
 }
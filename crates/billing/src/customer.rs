@@ -1,12 +1,32 @@
 //! Stripe customer management
 
+use plexmcp_shared::Money;
 use sqlx::PgPool;
-use stripe::{CreateCustomer, Customer, CustomerId, UpdateCustomer};
+use stripe::{
+    CreateCustomer, Customer, CustomerId, Invoice, InvoiceStatus, ListInvoices, UpdateCustomer,
+};
 use uuid::Uuid;
 
 use crate::client::StripeClient;
 use crate::error::{BillingError, BillingResult};
 
+/// Amount still owed on `invoices`, in minor units
+///
+/// Counts `amount_remaining` on open and uncollectible invoices, the ones
+/// grace period enforcement blocks on.
+fn outstanding_cents(invoices: &[Invoice]) -> i64 {
+    invoices
+        .iter()
+        .filter(|invoice| {
+            matches!(
+                invoice.status,
+                Some(InvoiceStatus::Open | InvoiceStatus::Uncollectible)
+            )
+        })
+        .map(|invoice| invoice.amount_remaining.unwrap_or(0))
+        .sum()
+}
+
 /// Customer service for managing Stripe customers
 pub struct CustomerService {
     stripe: StripeClient,
@@ -178,4 +198,64 @@ impl CustomerService {
 
         Ok(has_pm)
     }
+
+    /// Live outstanding balance for a customer, read from Stripe
+    ///
+    /// Unlike local invoice rows, this reflects payments made through
+    /// channels we never received a webhook for.
+    pub async fn outstanding_balance(&self, customer_id: &CustomerId) -> BillingResult<Money> {
+        let mut total_cents = 0;
+        let mut currency = None;
+
+        for status in [InvoiceStatus::Open, InvoiceStatus::Uncollectible] {
+            let mut params = ListInvoices::new();
+            params.customer = Some(customer_id.clone());
+            params.status = Some(status);
+            params.limit = Some(100);
+
+            loop {
+                let page = Invoice::list(self.stripe.inner(), &params).await?;
+                total_cents += outstanding_cents(&page.data);
+                if currency.is_none() {
+                    currency = page.data.iter().find_map(|invoice| invoice.currency);
+                }
+                match page.data.last() {
+                    Some(last) if page.has_more => params.starting_after = Some(last.id.clone()),
+                    _ => break,
+                }
+            }
+        }
+
+        Ok(match currency {
+            Some(currency) => Money::new(total_cents, currency.to_string()),
+            None => Money::from_cents_usd(total_cents),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invoice(status: InvoiceStatus, amount_remaining: i64) -> Invoice {
+        Invoice {
+            status: Some(status),
+            amount_remaining: Some(amount_remaining),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_outstanding_cents_counts_unpaid_invoices_only() {
+        let invoices = [
+            invoice(InvoiceStatus::Open, 2_900),
+            invoice(InvoiceStatus::Uncollectible, 1_000),
+            invoice(InvoiceStatus::Paid, 0),
+            invoice(InvoiceStatus::Void, 500),
+        ];
+        assert_eq!(outstanding_cents(&invoices), 3_900);
+
+        // Paid out of band: Stripe still lists it open with nothing remaining
+        assert_eq!(outstanding_cents(&[invoice(InvoiceStatus::Open, 0)]), 0);
+    }
 }
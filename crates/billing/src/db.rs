@@ -0,0 +1,76 @@
+//! Database helpers
+//!
+//! sqlx errors convert to [`BillingError::Database`](crate::error::BillingError)
+//! through `?`, so queries need no `map_err`. Transactions are opened with
+//! `pool.begin().await?`, written through a function taking `&mut PgTx`, and
+//! finished with [`commit_or_rollback`].
+
+use sqlx::{Postgres, Transaction};
+
+use crate::error::BillingResult;
+
+/// An open Postgres transaction
+pub type PgTx = Transaction<'static, Postgres>;
+
+/// Commit `tx` if `result` is `Ok`, roll it back otherwise, and return `result`
+///
+/// A failing query therefore leaves no partial writes behind. The original
+/// error is returned as-is; a failed rollback is only logged, since Postgres
+/// discards the transaction when the connection is dropped anyway.
+pub async fn commit_or_rollback<T>(tx: PgTx, result: BillingResult<T>) -> BillingResult<T> {
+    match result {
+        Ok(value) => {
+            tx.commit().await?;
+            Ok(value)
+        }
+        Err(e) => {
+            if let Err(rollback_err) = tx.rollback().await {
+                tracing::warn!(error = %rollback_err, "Failed to roll back transaction");
+            }
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::BillingError;
+
+    async fn insert_twice(tx: &mut PgTx, table: &str) -> BillingResult<()> {
+        let insert = format!("INSERT INTO {} (id) VALUES (1)", table);
+        sqlx::query(&insert).execute(&mut **tx).await?;
+        // Duplicate key fails, which must undo the first insert
+        sqlx::query(&insert).execute(&mut **tx).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_failing_query_rolls_back() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let table = format!("commit_or_rollback_test_{}", uuid::Uuid::new_v4().simple());
+
+        sqlx::query(&format!("CREATE TABLE {} (id INT PRIMARY KEY)", table))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        let written = insert_twice(&mut tx, &table).await;
+        let result = commit_or_rollback(tx, written).await;
+
+        let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        sqlx::query(&format!("DROP TABLE {}", table))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(matches!(result, Err(BillingError::Database(_))));
+        assert_eq!(rows, 0);
+    }
+}
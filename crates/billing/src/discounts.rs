@@ -0,0 +1,330 @@
+//! Discount stacking rules
+//!
+//! Checkout promo codes, reactivation coupons, and account credits can apply
+//! to the same purchase. `DiscountResolver` decides which of them may be
+//! combined and returns the effective set to send to Stripe.
+//!
+//! ## Default rules
+//!
+//! - Account credits always stack with everything
+//! - At most one percentage coupon applies
+//! - Coupons do not stack with each other; the most valuable one wins
+
+use serde::{Deserialize, Serialize};
+
+/// Where a discount came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscountSource {
+    /// Promotion code entered at checkout
+    PromoCode,
+    /// Coupon created for reactivation credit
+    Reactivation,
+    /// 100%-off-once coupon for a first-month-free signup
+    FirstMonthFree,
+    /// Stripe customer balance / account credit
+    AccountCredit,
+}
+
+/// What a discount is worth
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DiscountKind {
+    /// Percentage off the subtotal (0-100)
+    PercentOff { percent: f64 },
+    /// Fixed amount off the subtotal
+    AmountOff { amount_cents: i64 },
+    /// Account credit applied against the amount due
+    Credit { amount_cents: i64 },
+}
+
+/// A discount that could apply to a purchase
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Discount {
+    /// Stripe coupon / promotion code id (empty for account credit)
+    pub id: String,
+    pub source: DiscountSource,
+    pub kind: DiscountKind,
+}
+
+impl Discount {
+    pub fn percent_off(id: impl Into<String>, source: DiscountSource, percent: f64) -> Self {
+        Self {
+            id: id.into(),
+            source,
+            kind: DiscountKind::PercentOff { percent },
+        }
+    }
+
+    pub fn amount_off(id: impl Into<String>, source: DiscountSource, amount_cents: i64) -> Self {
+        Self {
+            id: id.into(),
+            source,
+            kind: DiscountKind::AmountOff { amount_cents },
+        }
+    }
+
+    pub fn credit(amount_cents: i64) -> Self {
+        Self {
+            id: String::new(),
+            source: DiscountSource::AccountCredit,
+            kind: DiscountKind::Credit { amount_cents },
+        }
+    }
+
+    /// Whether this is a coupon (as opposed to an account credit)
+    pub fn is_coupon(&self) -> bool {
+        !matches!(self.kind, DiscountKind::Credit { .. })
+    }
+
+    /// Value in cents against the given subtotal, never more than the subtotal
+    pub fn value_cents(&self, subtotal_cents: i64) -> i64 {
+        let subtotal = subtotal_cents.max(0);
+        let value = match self.kind {
+            DiscountKind::PercentOff { percent } => {
+                (subtotal as f64 * percent.clamp(0.0, 100.0) / 100.0).round() as i64
+            }
+            DiscountKind::AmountOff { amount_cents } => amount_cents,
+            DiscountKind::Credit { amount_cents } => amount_cents,
+        };
+        value.clamp(0, subtotal)
+    }
+}
+
+/// Configurable stacking rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackingRules {
+    /// Allow more than one coupon on the same purchase
+    pub allow_coupon_stacking: bool,
+    /// Maximum number of percentage coupons, even when coupons may stack
+    pub max_percent_coupons: usize,
+    /// Account credits stack with coupons and each other
+    pub credits_always_stack: bool,
+}
+
+impl Default for StackingRules {
+    fn default() -> Self {
+        Self {
+            allow_coupon_stacking: false,
+            max_percent_coupons: 1,
+            credits_always_stack: true,
+        }
+    }
+}
+
+impl StackingRules {
+    /// Create rules from environment variables
+    ///
+    /// `DISCOUNT_ALLOW_COUPON_STACKING`, `DISCOUNT_MAX_PERCENT_COUPONS` and
+    /// `DISCOUNT_CREDITS_ALWAYS_STACK` override the defaults.
+    pub fn from_env() -> Self {
+        let mut rules = Self::default();
+        if let Ok(value) = std::env::var("DISCOUNT_ALLOW_COUPON_STACKING") {
+            rules.allow_coupon_stacking = value.trim().eq_ignore_ascii_case("true");
+        }
+        if let Some(max) = std::env::var("DISCOUNT_MAX_PERCENT_COUPONS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+        {
+            rules.max_percent_coupons = max;
+        }
+        if let Ok(value) = std::env::var("DISCOUNT_CREDITS_ALWAYS_STACK") {
+            rules.credits_always_stack = value.trim().eq_ignore_ascii_case("true");
+        }
+        rules
+    }
+}
+
+/// Effective discounts after applying stacking rules
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ResolvedDiscounts {
+    /// Discounts to apply, most valuable first
+    pub applied: Vec<Discount>,
+    /// Discounts dropped by the stacking rules
+    pub rejected: Vec<Discount>,
+    /// Combined value of the applied discounts, capped at the subtotal
+    pub total_discount_cents: i64,
+}
+
+impl ResolvedDiscounts {
+    /// Applied coupons (excluding account credits)
+    pub fn coupons(&self) -> impl Iterator<Item = &Discount> {
+        self.applied.iter().filter(|d| d.is_coupon())
+    }
+}
+
+/// Enforces `StackingRules` over a set of applicable discounts
+#[derive(Debug, Clone, Default)]
+pub struct DiscountResolver {
+    rules: StackingRules,
+}
+
+impl DiscountResolver {
+    pub fn new(rules: StackingRules) -> Self {
+        Self { rules }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(StackingRules::from_env())
+    }
+
+    pub fn rules(&self) -> &StackingRules {
+        &self.rules
+    }
+
+    /// Reduce `discounts` to the set allowed by the stacking rules
+    ///
+    /// When a combination is disallowed, the most valuable discount against
+    /// `subtotal_cents` is kept.
+    pub fn resolve(&self, discounts: &[Discount], subtotal_cents: i64) -> ResolvedDiscounts {
+        let mut candidates: Vec<&Discount> = discounts.iter().collect();
+        // Most valuable first; ties keep input order
+        candidates.sort_by_key(|d| std::cmp::Reverse(d.value_cents(subtotal_cents)));
+
+        let mut resolved = ResolvedDiscounts::default();
+        let mut coupons = 0;
+        let mut percent_coupons = 0;
+
+        for discount in candidates {
+            let allowed = match discount.kind {
+                DiscountKind::Credit { .. } if self.rules.credits_always_stack => true,
+                DiscountKind::Credit { .. } => resolved.applied.is_empty(),
+                DiscountKind::PercentOff { .. } => {
+                    self.coupon_allowed(coupons, &resolved)
+                        && percent_coupons < self.rules.max_percent_coupons
+                }
+                DiscountKind::AmountOff { .. } => self.coupon_allowed(coupons, &resolved),
+            };
+
+            if allowed {
+                if discount.is_coupon() {
+                    coupons += 1;
+                }
+                if matches!(discount.kind, DiscountKind::PercentOff { .. }) {
+                    percent_coupons += 1;
+                }
+                resolved.applied.push(discount.clone());
+            } else {
+                resolved.rejected.push(discount.clone());
+            }
+        }
+
+        let total: i64 = resolved
+            .applied
+            .iter()
+            .map(|d| d.value_cents(subtotal_cents))
+            .sum();
+        resolved.total_discount_cents = total.min(subtotal_cents.max(0));
+
+        if !resolved.rejected.is_empty() {
+            tracing::info!(
+                applied = resolved.applied.len(),
+                rejected = resolved.rejected.len(),
+                total_discount_cents = resolved.total_discount_cents,
+                "Discount stacking rules dropped discounts"
+            );
+        }
+
+        resolved
+    }
+
+    /// Whether another coupon may be added given what's already applied
+    fn coupon_allowed(&self, coupons: usize, resolved: &ResolvedDiscounts) -> bool {
+        let credit_blocks =
+            !self.rules.credits_always_stack && resolved.applied.iter().any(|d| !d.is_coupon());
+        !credit_blocks && (coupons == 0 || self.rules.allow_coupon_stacking)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn promo_percent(id: &str, percent: f64) -> Discount {
+        Discount::percent_off(id, DiscountSource::PromoCode, percent)
+    }
+
+    #[test]
+    fn test_disallowed_stack_reduced_to_best_single_discount() {
+        let resolver = DiscountResolver::default();
+        let discounts = vec![
+            promo_percent("promo_10", 10.0),
+            Discount::amount_off("rx_credit", DiscountSource::Reactivation, 1500),
+        ];
+
+        // 10% of $29 = $2.90 < $15.00
+        let resolved = resolver.resolve(&discounts, 2900);
+        assert_eq!(resolved.applied.len(), 1);
+        assert_eq!(resolved.applied[0].id, "rx_credit");
+        assert_eq!(resolved.rejected[0].id, "promo_10");
+        assert_eq!(resolved.total_discount_cents, 1500);
+    }
+
+    #[test]
+    fn test_only_one_percent_coupon() {
+        let resolver = DiscountResolver::new(StackingRules {
+            allow_coupon_stacking: true,
+            ..Default::default()
+        });
+        let discounts = vec![
+            promo_percent("promo_10", 10.0),
+            promo_percent("promo_25", 25.0),
+            Discount::amount_off("amount_5", DiscountSource::PromoCode, 500),
+        ];
+
+        let resolved = resolver.resolve(&discounts, 10_000);
+        let applied: Vec<&str> = resolved.applied.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(applied, vec!["promo_25", "amount_5"]);
+        assert_eq!(resolved.rejected[0].id, "promo_10");
+        assert_eq!(resolved.total_discount_cents, 3000);
+    }
+
+    #[test]
+    fn test_credits_always_stack() {
+        let resolver = DiscountResolver::default();
+        let discounts = vec![
+            Discount::credit(700),
+            promo_percent("promo_20", 20.0),
+            Discount::credit(300),
+        ];
+
+        let resolved = resolver.resolve(&discounts, 5000);
+        assert!(resolved.rejected.is_empty());
+        assert_eq!(resolved.coupons().count(), 1);
+        assert_eq!(resolved.total_discount_cents, 2000);
+    }
+
+    #[test]
+    fn test_credits_not_stacking_keeps_best() {
+        let resolver = DiscountResolver::new(StackingRules {
+            credits_always_stack: false,
+            ..Default::default()
+        });
+        let discounts = vec![Discount::credit(700), promo_percent("promo_50", 50.0)];
+
+        let resolved = resolver.resolve(&discounts, 2000);
+        assert_eq!(resolved.applied.len(), 1);
+        assert_eq!(resolved.applied[0].id, "promo_50");
+        assert_eq!(resolved.total_discount_cents, 1000);
+    }
+
+    #[test]
+    fn test_total_capped_at_subtotal() {
+        let resolver = DiscountResolver::default();
+        let discounts = vec![
+            Discount::credit(5000),
+            Discount::amount_off("big", DiscountSource::PromoCode, 4000),
+        ];
+
+        let resolved = resolver.resolve(&discounts, 2900);
+        assert_eq!(resolved.applied.len(), 2);
+        assert_eq!(resolved.total_discount_cents, 2900);
+    }
+
+    #[test]
+    fn test_empty_discounts() {
+        let resolved = DiscountResolver::default().resolve(&[], 2900);
+        assert!(resolved.applied.is_empty());
+        assert_eq!(resolved.total_discount_cents, 0);
+    }
+}
@@ -248,8 +248,15 @@ mod rate_limit_tests {
 
 #[cfg(test)]
 mod overage_tests {
-    use crate::overage::OverageRates;
+    use crate::overage::{
+        overage_invoice_item_params, overage_item_description, overage_rates_for_tier_name,
+        InvoiceNowResult, OverageCharge, OverageRates,
+    };
     use plexmcp_shared::types::SubscriptionTier;
+    use plexmcp_shared::Money;
+    use stripe::{CustomerId, InvoiceId};
+    use time::OffsetDateTime;
+    use uuid::Uuid;
 
     // =========================================================================
     // BILL-O01: 50,000 requests / 50K limit - no charge
@@ -399,6 +406,269 @@ mod overage_tests {
         let charge = rates.calculate_request_overage_cents(-100);
         assert_eq!(charge, 0, "Negative overage should be no charge");
     }
+
+    fn pending_charge(total_charge_cents: i32) -> OverageCharge {
+        OverageCharge {
+            id: Uuid::new_v4(),
+            org_id: Uuid::new_v4(),
+            billing_period_start: OffsetDateTime::from_unix_timestamp(1_767_225_600).unwrap(),
+            billing_period_end: OffsetDateTime::from_unix_timestamp(1_769_904_000).unwrap(),
+            resource_type: "requests".to_string(),
+            base_limit: 50_000,
+            actual_usage: 53_500,
+            overage_amount: 3_500,
+            rate_per_unit_cents: 50,
+            total_charge_cents,
+            stripe_invoice_item_id: None,
+            status: "pending".to_string(),
+            created_at: OffsetDateTime::from_unix_timestamp(1_768_435_200).unwrap(),
+            invoiced_at: None,
+            paid_at: None,
+        }
+    }
+
+    // =========================================================================
+    // Invoice now: item is attached to the on-demand invoice with the charge amount
+    // =========================================================================
+    #[test]
+    fn test_invoice_now_item_params() {
+        let charge = pending_charge(200);
+        let customer_id: CustomerId = "cus_test123".parse().unwrap();
+        let invoice_id: InvoiceId = "in_test123".parse().unwrap();
+        let description = overage_item_description(&charge);
+
+        let params =
+            overage_invoice_item_params(customer_id, Some(invoice_id), &charge, &description);
+
+        assert_eq!(params.amount, Some(200));
+        assert_eq!(params.currency, Some(stripe::Currency::USD));
+        assert_eq!(
+            params.invoice.as_ref().map(|id| id.as_str()),
+            Some("in_test123")
+        );
+        assert_eq!(
+            params.description,
+            Some("Request overage: 3500 requests over 50000 limit (2026-01-01 - 2026-02-01)")
+        );
+    }
+
+    // =========================================================================
+    // Billing-cycle items are not attached to an invoice
+    // =========================================================================
+    #[test]
+    fn test_cycle_item_params_have_no_invoice() {
+        let charge = pending_charge(50);
+        let customer_id: CustomerId = "cus_test123".parse().unwrap();
+        let params = overage_invoice_item_params(customer_id, None, &charge, "x");
+        assert!(params.invoice.is_none());
+    }
+
+    // =========================================================================
+    // Invoice now result carries the status transition outcome
+    // =========================================================================
+    #[test]
+    fn test_invoice_now_result_serialization() {
+        let none = serde_json::to_value(InvoiceNowResult::NoPendingCharges).unwrap();
+        assert_eq!(none["status"], "NoPendingCharges");
+
+        let invoiced = serde_json::to_value(InvoiceNowResult::Invoiced {
+            stripe_invoice_id: "in_test123".to_string(),
+            amount: Money::from_cents_usd(250),
+            charge_count: 2,
+            paid: true,
+        })
+        .unwrap();
+        assert_eq!(invoiced["status"], "Invoiced");
+        assert_eq!(invoiced["amount"]["amount_cents"], 250);
+        assert_eq!(invoiced["amount"]["currency"], "usd");
+        assert_eq!(invoiced["charge_count"], 2);
+        assert_eq!(invoiced["paid"], true);
+    }
+
+    // =========================================================================
+    // Free/Enterprise tiers never get overage rows
+    // =========================================================================
+    #[test]
+    fn test_overage_rows_only_for_pro_and_team() {
+        for tier in ["free", "starter", "enterprise", "unknown"] {
+            assert!(
+                overage_rates_for_tier_name(tier).is_none(),
+                "{} must not get overage rows",
+                tier
+            );
+        }
+
+        let (tier, rates) = overage_rates_for_tier_name("pro").unwrap();
+        assert_eq!(tier, SubscriptionTier::Pro);
+        assert!(rates.requests_per_1k_cents > 0);
+        assert!(overage_rates_for_tier_name("team").is_some());
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database and Stripe test config
+    async fn test_no_overage_row_for_free_or_enterprise() {
+        use crate::client::StripeClient;
+        use crate::overage::OverageService;
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let stripe = StripeClient::from_env().unwrap();
+        stripe.assert_test_mode();
+        let overage = OverageService::new(stripe, pool.clone());
+
+        for tier in ["free", "enterprise"] {
+            let org_id = Uuid::new_v4();
+            let period_start = OffsetDateTime::now_utc() - time::Duration::days(3);
+            let period_end = period_start + time::Duration::days(30);
+            sqlx::query(
+                "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Overage Guard', $2, $3)",
+            )
+            .bind(org_id)
+            .bind(format!("overage-guard-{}", org_id))
+            .bind(tier)
+            .execute(&pool)
+            .await
+            .unwrap();
+            // Far above any tier limit
+            sqlx::query(
+                r#"
+                INSERT INTO usage_records (org_id, request_count, period_start, period_end)
+                VALUES ($1, 2000000, $2, $2 + interval '1 day')
+                "#,
+            )
+            .bind(org_id)
+            .bind(period_start + time::Duration::days(1))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let charge = overage
+                .create_or_update_current_overage(org_id, tier, period_start, period_end)
+                .await
+                .unwrap();
+            assert!(charge.is_none(), "{} tier returned an overage charge", tier);
+
+            let rows: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM overage_charges WHERE org_id = $1")
+                    .bind(org_id)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(rows, 0, "{} tier created an overage row", tier);
+
+            sqlx::query("DELETE FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    // =========================================================================
+    // On-demand recalculation matches the worker job cycle for the org
+    // =========================================================================
+    #[tokio::test]
+    #[ignore] // Requires database and Stripe test config
+    async fn test_recalculate_matches_worker_cycle() {
+        use crate::client::StripeClient;
+        use crate::email::BillingEmailService;
+        use crate::overage::OverageService;
+        use crate::spend_cap::{SpendCapRequest, SpendCapService};
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let stripe = StripeClient::from_env().unwrap();
+        stripe.assert_test_mode();
+
+        let overage = OverageService::new(stripe, pool.clone());
+        let spend_cap = SpendCapService::new(pool.clone(), BillingEmailService::from_env());
+
+        let org_id = Uuid::new_v4();
+        let period_start = OffsetDateTime::now_utc() - time::Duration::days(3);
+        let period_end = period_start + time::Duration::days(30);
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Overage Test', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("overage-test-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (org_id, customer_id, status, current_period_start, current_period_end)
+            VALUES ($1, $1::text, 'active', $2, $3)
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start)
+        .bind(period_end)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // 51,500 requests on Pro (50K limit) = 2 batches
+        sqlx::query(
+            r#"
+            INSERT INTO usage_records (org_id, request_count, period_start, period_end)
+            VALUES ($1, 51500, $2, $2 + interval '1 day')
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start + time::Duration::days(1))
+        .execute(&pool)
+        .await
+        .unwrap();
+        spend_cap
+            .set_spend_cap(
+                org_id,
+                SpendCapRequest {
+                    cap_amount_cents: Some(10_000),
+                    cap_percent_of_base: None,
+                    hard_pause_enabled: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        // Worker job cycle
+        overage.recalculate_all(&spend_cap).await.unwrap();
+        let worker_charges = overage.get_pending_charges(org_id).await.unwrap();
+        assert_eq!(worker_charges.len(), 1);
+        let worker_charge = &worker_charges[0];
+        let worker_spend = spend_cap
+            .get_spend_cap(org_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .current_period_spend_cents;
+
+        // On-demand recalculation for the same org
+        let charge = overage
+            .recalculate(org_id, &spend_cap)
+            .await
+            .unwrap()
+            .expect("overage charge");
+        assert_eq!(charge.id, worker_charge.id);
+        assert_eq!(charge.actual_usage, worker_charge.actual_usage);
+        assert_eq!(charge.overage_amount, worker_charge.overage_amount);
+        assert_eq!(charge.total_charge_cents, worker_charge.total_charge_cents);
+        assert_eq!(charge.total_charge_cents, 100);
+
+        let spend = spend_cap
+            .get_spend_cap(org_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .current_period_spend_cents;
+        assert_eq!(spend, worker_spend);
+        assert_eq!(spend, 100);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -421,13 +691,14 @@ mod spend_cap_tests {
         // Minimum is 1000 cents ($10.00)
         // $9.99 = 999 cents should be rejected
         let request = SpendCapRequest {
-            cap_amount_cents: 999,
+            cap_amount_cents: Some(999),
+            cap_percent_of_base: None,
             hard_pause_enabled: true,
         };
         // The validation happens in set_spend_cap which requires DB
         // Here we validate the threshold value
         assert!(
-            request.cap_amount_cents < 1000,
+            request.cap_amount_cents.unwrap() < 1000,
             "$9.99 is less than $10 minimum"
         );
     }
@@ -473,6 +744,7 @@ mod spend_cap_tests {
         let status = SpendCapStatus {
             has_cap: false,
             cap_amount_cents: None,
+            cap_percent_of_base: None,
             current_spend_cents: 0,
             percentage_used: 0.0,
             hard_pause_enabled: false,
@@ -480,11 +752,60 @@ mod spend_cap_tests {
             paused_at: None,
             has_override: false,
             override_until: None,
+            members_over_cap: Vec::new(),
         };
         assert!(!status.has_cap);
         assert!(status.cap_amount_cents.is_none());
         assert!(!status.is_paused);
     }
+
+    // =========================================================================
+    // Simulated notifications against a $100 cap
+    // =========================================================================
+    #[test]
+    fn test_simulated_thresholds_for_hypothetical_spends() {
+        let percents = |spend: i32| -> Vec<i32> {
+            thresholds_reached(&NOTIFICATION_THRESHOLDS, 10_000, false, spend)
+                .iter()
+                .map(|t| t.percent)
+                .collect()
+        };
+
+        assert!(percents(0).is_empty());
+        assert!(percents(4_999).is_empty());
+        assert_eq!(percents(5_000), vec![50]);
+        assert_eq!(percents(8_000), vec![50, 75]);
+        assert_eq!(percents(9_000), vec![50, 75, 90]);
+        assert_eq!(percents(10_000), vec![50, 75, 90, 100]);
+        assert_eq!(percents(25_000), vec![50, 75, 90, 100]);
+    }
+
+    #[test]
+    fn test_simulated_thresholds_spend_and_pause() {
+        let reached = thresholds_reached(&NOTIFICATION_THRESHOLDS, 2_999, true, 3_000);
+        let spends: Vec<i64> = reached.iter().map(|t| t.spend_cents).collect();
+        // Rounded up so the spend shown actually reaches the threshold
+        assert_eq!(spends, vec![1_500, 2_250, 2_700, 2_999]);
+        assert_eq!(
+            reached
+                .iter()
+                .filter(|t| t.pauses)
+                .map(|t| t.percent)
+                .collect::<Vec<_>>(),
+            vec![100]
+        );
+
+        let no_pause = thresholds_reached(&NOTIFICATION_THRESHOLDS, 2_999, false, 3_000);
+        assert!(no_pause.iter().all(|t| !t.pauses));
+    }
+
+    #[test]
+    fn test_simulated_thresholds_zero_cap_always_at_limit() {
+        assert_eq!(
+            thresholds_reached(&NOTIFICATION_THRESHOLDS, 0, false, 0).len(),
+            NOTIFICATION_THRESHOLDS.len()
+        );
+    }
 }
 
 #[cfg(test)]
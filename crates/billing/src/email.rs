@@ -2,7 +2,22 @@
 //!
 //! Sends transactional emails via Resend API for billing-related events.
 
+use plexmcp_shared::Money;
+
 use crate::error::BillingResult;
+use crate::refund::RefundReason;
+
+/// Resend API endpoint for sending a single email
+const RESEND_API_URL: &str = "https://api.resend.com/emails";
+
+/// How billing emails are delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailMode {
+    /// No Resend API key configured: sends are skipped (self-hosted without email)
+    Disabled,
+    /// Send through the Resend API
+    Resend,
+}
 
 /// Email configuration
 #[derive(Debug, Clone)]
@@ -34,9 +49,18 @@ impl EmailConfig {
         }
     }
 
+    /// Delivery mode implied by the configuration
+    pub fn mode(&self) -> EmailMode {
+        if self.resend_api_key.trim().is_empty() {
+            EmailMode::Disabled
+        } else {
+            EmailMode::Resend
+        }
+    }
+
     /// Check if email sending is enabled
     pub fn is_enabled(&self) -> bool {
-        !self.resend_api_key.is_empty()
+        self.mode() != EmailMode::Disabled
     }
 }
 
@@ -44,14 +68,22 @@ impl EmailConfig {
 #[derive(Clone)]
 pub struct BillingEmailService {
     config: EmailConfig,
+    mode: EmailMode,
+    api_url: String,
     client: reqwest::Client,
 }
 
 impl BillingEmailService {
     /// Create a new email service
     pub fn new(config: EmailConfig) -> Self {
+        let mode = config.mode();
+        if mode == EmailMode::Disabled {
+            tracing::info!("RESEND_API_KEY not set, billing emails are disabled");
+        }
         Self {
             config,
+            mode,
+            api_url: RESEND_API_URL.to_string(),
             client: reqwest::Client::new(),
         }
     }
@@ -61,22 +93,31 @@ impl BillingEmailService {
         Self::new(EmailConfig::from_env())
     }
 
+    /// How this service delivers email
+    pub fn mode(&self) -> EmailMode {
+        self.mode
+    }
+
     /// Send an email via Resend API
     ///
     /// Returns `Ok(true)` if the email was sent successfully,
-    /// `Ok(false)` if sending failed (non-fatal - doesn't propagate error),
-    /// `Err` only for critical configuration issues.
+    /// `Ok(false)` if sending failed or email is disabled (non-fatal -
+    /// doesn't propagate error), `Err` only for critical configuration issues.
     ///
     /// The `Ok(false)` return allows callers to track email delivery status
     /// while not failing webhook processing due to email errors.
     async fn send_email(&self, to: &str, subject: &str, html: &str) -> BillingResult<bool> {
-        if !self.config.is_enabled() {
-            tracing::warn!(
-                to = %to,
-                subject = %subject,
-                "Email not configured, skipping"
-            );
-            return Ok(false);
+        match self.mode {
+            EmailMode::Disabled => {
+                // Expected on deployments without email; logged once at startup
+                tracing::debug!(
+                    to = %to,
+                    subject = %subject,
+                    "Billing email disabled, skipping"
+                );
+                return Ok(false);
+            }
+            EmailMode::Resend => {}
         }
 
         #[allow(clippy::disallowed_methods)]
@@ -90,7 +131,7 @@ impl BillingEmailService {
 
         let response = self
             .client
-            .post("https://api.resend.com/emails")
+            .post(&self.api_url)
             .header(
                 "Authorization",
                 format!("Bearer {}", self.config.resend_api_key),
@@ -137,7 +178,7 @@ impl BillingEmailService {
         amount_cents: i64,
         invoice_url: Option<&str>,
     ) -> BillingResult<bool> {
-        let amount = format!("${:.2}", amount_cents as f64 / 100.0);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
         let update_link = format!("{}/billing", self.config.dashboard_url);
         let invoice_section = invoice_url
             .map(|url| {
@@ -194,7 +235,7 @@ impl BillingEmailService {
         amount_cents: i32,
         error_message: &str,
     ) -> BillingResult<bool> {
-        let amount = format!("${:.2}", amount_cents as f64 / 100.0);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
         let update_link = format!("{}/billing", self.config.dashboard_url);
 
         let html = format!(
@@ -245,10 +286,10 @@ impl BillingEmailService {
         subscription_amount_cents: i32,
         overage_amount_cents: i32,
     ) -> BillingResult<bool> {
-        let subscription_amount = format!("${:.2}", subscription_amount_cents as f64 / 100.0);
-        let overage_amount = format!("${:.2}", overage_amount_cents as f64 / 100.0);
+        let subscription_amount = Money::from_cents_usd(subscription_amount_cents).to_string();
+        let overage_amount = Money::from_cents_usd(overage_amount_cents).to_string();
         let total_cents = subscription_amount_cents + overage_amount_cents;
-        let total_amount = format!("${:.2}", total_cents as f64 / 100.0);
+        let total_amount = Money::from_cents_usd(total_cents).to_string();
         let billing_link = format!("{}/billing", self.config.dashboard_url);
 
         let overage_section = if overage_amount_cents > 0 {
@@ -315,7 +356,7 @@ impl BillingEmailService {
         amount_cents: i32,
         reason: &str,
     ) -> BillingResult<bool> {
-        let amount = format!("${:.2}", amount_cents as f64 / 100.0);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
         let billing_link = format!("{}/billing", self.config.dashboard_url);
 
         let html = format!(
@@ -331,7 +372,7 @@ impl BillingEmailService {
         <p style="margin: 0 0 8px 0;"><strong>Amount:</strong> {amount}</p>
         <p style="margin: 0;"><strong>Reason:</strong> {reason}</p>
     </div>
-    <p><strong>Important:</strong> Disputes can result in service interruption and fees. Please contact our support team immediately if you have questions about this charge.</p>
+    <p><strong>Important:</strong> New MCP requests for your organization are paused until the dispute is resolved. Please contact our support team immediately if you have questions about this charge.</p>
     <p>If this dispute was filed by mistake, please contact your bank to withdraw it.</p>
     <p>
         <a href="{billing_link}" style="display: inline-block; padding: 12px 24px; background-color: #6366f1; color: white; text-decoration: none; border-radius: 6px; font-weight: bold;">
@@ -412,6 +453,53 @@ impl BillingEmailService {
         .await
     }
 
+    /// Remind an owner that their trial needs a payment method before access is blocked
+    pub async fn send_trial_payment_method_required(
+        &self,
+        to: &str,
+        org_name: &str,
+        block_at: time::OffsetDateTime,
+    ) -> BillingResult<bool> {
+        let billing_link = format!("{}/billing", self.config.dashboard_url);
+
+        let html = format!(
+            r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"></head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #f59e0b;">Add a Payment Method to Keep Your Trial</h2>
+    <p>Hi there,</p>
+    <p>The trial for <strong>{org_name}</strong> was started without a payment method on file.</p>
+    <div style="background: #fffbeb; border: 1px solid #fcd34d; border-radius: 8px; padding: 16px; margin: 20px 0;">
+        <p style="margin: 0; color: #b45309;"><strong>Unless a payment method is added, trial access will be paused on {block_date}.</strong></p>
+    </div>
+    <p>
+        <a href="{billing_link}" style="display: inline-block; padding: 12px 24px; background-color: #6366f1; color: white; text-decoration: none; border-radius: 6px; font-weight: bold;">
+            Add Payment Method
+        </a>
+    </p>
+    <p style="color: #666; font-size: 14px;">
+        Questions? Contact us at <a href="mailto:{support_email}">{support_email}</a>
+    </p>
+    <hr style="border: none; border-top: 1px solid #eee; margin: 20px 0;">
+    <p style="color: #999; font-size: 12px;">{app_name}</p>
+</body>
+</html>"#,
+            org_name = org_name,
+            block_date = block_at.date(),
+            billing_link = billing_link,
+            support_email = self.config.support_email,
+            app_name = self.config.app_name,
+        );
+
+        self.send_email(
+            to,
+            &format!("Payment Method Required - {}", self.config.app_name),
+            &html,
+        )
+        .await
+    }
+
     /// Send subscription past due notification
     pub async fn send_subscription_past_due(
         &self,
@@ -572,8 +660,8 @@ impl BillingEmailService {
         cap_amount_cents: i32,
     ) -> BillingResult<bool> {
         let billing_link = format!("{}/billing", self.config.dashboard_url);
-        let current_spend = format!("${:.2}", current_spend_cents as f64 / 100.0);
-        let cap_amount = format!("${:.2}", cap_amount_cents as f64 / 100.0);
+        let current_spend = Money::from_cents_usd(current_spend_cents).to_string();
+        let cap_amount = Money::from_cents_usd(cap_amount_cents).to_string();
 
         let (header_color, urgency) = match threshold {
             100 => ("#dc2626", "Your spend cap has been reached"),
@@ -627,6 +715,63 @@ impl BillingEmailService {
         .await
     }
 
+    /// Send soft quota warning (monthly request usage approaching the limit)
+    pub async fn send_quota_soft_limit(
+        &self,
+        to: &str,
+        org_name: &str,
+        percent_used: u64,
+        requests_used: i64,
+        requests_limit: u64,
+    ) -> BillingResult<bool> {
+        let billing_link = format!("{}/billing", self.config.dashboard_url);
+
+        let html = format!(
+            r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"></head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #ea580c;">{percent_used}% of Monthly Requests Used</h2>
+    <p>Hi there,</p>
+    <p><strong>{org_name}</strong> is approaching its monthly request limit.</p>
+    <div style="background: #f8fafc; border-radius: 8px; padding: 16px; margin: 20px 0;">
+        <p style="margin: 0 0 8px 0;"><strong>Requests Used:</strong> {requests_used}</p>
+        <p style="margin: 0 0 8px 0;"><strong>Monthly Limit:</strong> {requests_limit}</p>
+        <p style="margin: 0;"><strong>Usage:</strong> {percent_used}%</p>
+    </div>
+    <p>Requests are not blocked yet. Once the limit is reached, Free plans are paused until the next billing period and paid plans are billed for overages.</p>
+    <p>
+        <a href="{billing_link}" style="display: inline-block; padding: 12px 24px; background-color: #6366f1; color: white; text-decoration: none; border-radius: 6px; font-weight: bold;">
+            View Usage
+        </a>
+    </p>
+    <p style="color: #666; font-size: 14px;">
+        Questions? Contact us at <a href="mailto:{support_email}">{support_email}</a>
+    </p>
+    <hr style="border: none; border-top: 1px solid #eee; margin: 20px 0;">
+    <p style="color: #999; font-size: 12px;">{app_name}</p>
+</body>
+</html>"#,
+            percent_used = percent_used,
+            org_name = org_name,
+            requests_used = requests_used,
+            requests_limit = requests_limit,
+            billing_link = billing_link,
+            support_email = self.config.support_email,
+            app_name = self.config.app_name,
+        );
+
+        self.send_email(
+            to,
+            &format!(
+                "{}% of Monthly Requests Used - {}",
+                percent_used, self.config.app_name
+            ),
+            &html,
+        )
+        .await
+    }
+
     /// Send API paused notification (spend cap with hard pause enabled)
     pub async fn send_api_paused(
         &self,
@@ -636,8 +781,8 @@ impl BillingEmailService {
         cap_amount_cents: i32,
     ) -> BillingResult<bool> {
         let billing_link = format!("{}/billing", self.config.dashboard_url);
-        let current_spend = format!("${:.2}", current_spend_cents as f64 / 100.0);
-        let cap_amount = format!("${:.2}", cap_amount_cents as f64 / 100.0);
+        let current_spend = Money::from_cents_usd(current_spend_cents).to_string();
+        let cap_amount = Money::from_cents_usd(cap_amount_cents).to_string();
 
         let html = format!(
             r#"<!DOCTYPE html>
@@ -696,7 +841,7 @@ impl BillingEmailService {
         overage_count: i64,
     ) -> BillingResult<bool> {
         let billing_link = format!("{}/billing", self.config.dashboard_url);
-        let amount = format!("${:.2}", amount_cents as f64 / 100.0);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
 
         let html = format!(
             r#"<!DOCTYPE html>
@@ -751,7 +896,7 @@ impl BillingEmailService {
         charge_count: i32,
     ) -> BillingResult<bool> {
         let billing_link = format!("{}/billing", self.config.dashboard_url);
-        let amount = format!("${:.2}", amount_cents as f64 / 100.0);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
 
         let html = format!(
             r#"<!DOCTYPE html>
@@ -795,6 +940,161 @@ impl BillingEmailService {
         .await
     }
 
+    /// Send refund receipt (money returned to the original payment method)
+    pub async fn send_refund_issued(
+        &self,
+        to: &str,
+        org_name: &str,
+        amount_cents: i64,
+        reason: RefundReason,
+        original_charge_date: time::Date,
+    ) -> BillingResult<bool> {
+        let billing_link = format!("{}/billing", self.config.dashboard_url);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
+
+        let html = format!(
+            r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"></head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #16a34a;">Refund Issued</h2>
+    <p>Hi there,</p>
+    <p>We've issued a refund of <strong>{amount}</strong> for <strong>{org_name}</strong>.</p>
+    <div style="background: #f0fdf4; border: 1px solid #bbf7d0; border-radius: 8px; padding: 16px; margin: 20px 0;">
+        <p style="margin: 0 0 8px 0;"><strong>Amount Refunded:</strong> {amount}</p>
+        <p style="margin: 0 0 8px 0;"><strong>Original Charge:</strong> {charge_date}</p>
+        <p style="margin: 0;"><strong>Reason:</strong> {reason}</p>
+    </div>
+    <p>The refund goes back to the payment method used for the original charge. It usually appears on your statement within 5-10 business days.</p>
+    <p>
+        <a href="{billing_link}" style="display: inline-block; padding: 12px 24px; background-color: #6366f1; color: white; text-decoration: none; border-radius: 6px; font-weight: bold;">
+            View Billing
+        </a>
+    </p>
+    <p style="color: #666; font-size: 14px;">
+        Questions? Contact us at <a href="mailto:{support_email}">{support_email}</a>
+    </p>
+    <hr style="border: none; border-top: 1px solid #eee; margin: 20px 0;">
+    <p style="color: #999; font-size: 12px;">{app_name}</p>
+</body>
+</html>"#,
+            amount = amount,
+            org_name = org_name,
+            charge_date = original_charge_date,
+            reason = reason.description(),
+            billing_link = billing_link,
+            support_email = self.config.support_email,
+            app_name = self.config.app_name,
+        );
+
+        self.send_email(
+            to,
+            &format!("Refund Issued: {} - {}", amount, self.config.app_name),
+            &html,
+        )
+        .await
+    }
+
+    /// Send account credit notification (applied to future invoices, not refunded)
+    pub async fn send_account_credit_applied(
+        &self,
+        to: &str,
+        org_name: &str,
+        amount_cents: i64,
+    ) -> BillingResult<bool> {
+        let billing_link = format!("{}/billing", self.config.dashboard_url);
+        let amount = Money::from_cents_usd(amount_cents).to_string();
+
+        let html = format!(
+            r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"></head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #6366f1;">Account Credit Applied</h2>
+    <p>Hi there,</p>
+    <p>A credit of <strong>{amount}</strong> has been applied to the <strong>{org_name}</strong> account for the unused time on your previous plan.</p>
+    <div style="background: #f8fafc; border-radius: 8px; padding: 16px; margin: 20px 0;">
+        <p style="margin: 0;"><strong>Credit Amount:</strong> {amount}</p>
+    </div>
+    <p>This is not a refund to your card: the credit is deducted from your upcoming invoices automatically.</p>
+    <p>
+        <a href="{billing_link}" style="display: inline-block; padding: 12px 24px; background-color: #6366f1; color: white; text-decoration: none; border-radius: 6px; font-weight: bold;">
+            View Billing
+        </a>
+    </p>
+    <p style="color: #666; font-size: 14px;">
+        Questions? Contact us at <a href="mailto:{support_email}">{support_email}</a>
+    </p>
+    <hr style="border: none; border-top: 1px solid #eee; margin: 20px 0;">
+    <p style="color: #999; font-size: 12px;">{app_name}</p>
+</body>
+</html>"#,
+            amount = amount,
+            org_name = org_name,
+            billing_link = billing_link,
+            support_email = self.config.support_email,
+            app_name = self.config.app_name,
+        );
+
+        self.send_email(
+            to,
+            &format!(
+                "Account Credit Applied: {} - {}",
+                amount, self.config.app_name
+            ),
+            &html,
+        )
+        .await
+    }
+
+    /// Warn a member that they will be suspended when the downgrade grace period ends
+    pub async fn send_member_suspension_pending(
+        &self,
+        to: &str,
+        new_tier: &str,
+        suspend_after: time::OffsetDateTime,
+    ) -> BillingResult<bool> {
+        let tier_display = match new_tier {
+            "free" => "Free",
+            "pro" => "Pro",
+            "team" => "Team",
+            "enterprise" => "Enterprise",
+            _ => new_tier,
+        };
+
+        let html = format!(
+            r#"<!DOCTYPE html>
+<html>
+<head><meta charset="utf-8"></head>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; line-height: 1.6; color: #333; max-width: 600px; margin: 0 auto; padding: 20px;">
+    <h2 style="color: #f59e0b;">Your Access Will Change Soon</h2>
+    <p>Hi there,</p>
+    <p>Your organization's subscription has been changed to the <strong>{tier_display}</strong> plan, which has a limited number of team members.</p>
+    <div style="background: #fffbeb; border: 1px solid #fcd34d; border-radius: 8px; padding: 16px; margin: 20px 0;">
+        <p style="margin: 0; color: #b45309;"><strong>Unless the organization frees up a seat, your access will be set to read-only on {suspend_date}.</strong></p>
+    </div>
+    <p>Please contact your organization owner if you need to keep full access.</p>
+    <p style="color: #666; font-size: 14px;">
+        Questions? Contact us at <a href="mailto:{support_email}">{support_email}</a>
+    </p>
+    <hr style="border: none; border-top: 1px solid #eee; margin: 20px 0;">
+    <p style="color: #999; font-size: 12px;">{app_name}</p>
+</body>
+</html>"#,
+            tier_display = tier_display,
+            suspend_date = suspend_after.date(),
+            support_email = self.config.support_email,
+            app_name = self.config.app_name,
+        );
+
+        self.send_email(
+            to,
+            &format!("Your Access Will Change Soon - {}", self.config.app_name),
+            &html,
+        )
+        .await
+    }
+
     /// Send member suspended notification (due to plan downgrade)
     pub async fn send_member_suspended(&self, to: &str, new_tier: &str) -> BillingResult<bool> {
         let tier_display = match new_tier {
@@ -838,3 +1138,82 @@ impl BillingEmailService {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn config(api_key: &str) -> EmailConfig {
+        EmailConfig {
+            resend_api_key: api_key.to_string(),
+            email_from: "PlexMCP <noreply@example.com>".to_string(),
+            app_name: "PlexMCP".to_string(),
+            support_email: "support@example.com".to_string(),
+            dashboard_url: "https://example.com".to_string(),
+        }
+    }
+
+    /// Local stand-in for the Resend API; yields each request it receives
+    async fn mock_resend() -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/emails", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 16 * 1024];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let _ = tx.send(String::from_utf8_lossy(&buf[..n]).into_owned());
+                let _ = socket
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+                    )
+                    .await;
+            }
+        });
+        (url, rx)
+    }
+
+    #[test]
+    fn test_mode_from_config() {
+        assert_eq!(config("").mode(), EmailMode::Disabled);
+        assert_eq!(config("  ").mode(), EmailMode::Disabled);
+        assert_eq!(config("re_123").mode(), EmailMode::Resend);
+        assert!(!config("").is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_mode_is_a_no_op() {
+        let (url, mut requests) = mock_resend().await;
+        let service = BillingEmailService {
+            api_url: url,
+            ..BillingEmailService::new(config(""))
+        };
+        assert_eq!(service.mode(), EmailMode::Disabled);
+
+        let sent = service
+            .send_member_suspended("owner@example.com", "free")
+            .await;
+        assert!(matches!(sent, Ok(false)));
+        assert!(requests.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_configured_mode_attempts_send() {
+        let (url, mut requests) = mock_resend().await;
+        let service = BillingEmailService {
+            api_url: url,
+            ..BillingEmailService::new(config("re_test_key"))
+        };
+
+        let sent = service
+            .send_member_suspended("owner@example.com", "free")
+            .await;
+        assert!(matches!(sent, Ok(true)));
+
+        let request = requests.recv().await.unwrap();
+        assert!(request.starts_with("POST /emails"));
+        assert!(request.contains("Bearer re_test_key"));
+    }
+}
@@ -10,6 +10,8 @@
 //! 3. **Debuggable**: Entitlement includes source tracing for "why" questions
 //! 4. **Testable**: Pure function with clear inputs/outputs
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use plexmcp_shared::types::{CustomLimits, EffectiveLimits, SubscriptionTier};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -66,6 +68,8 @@ pub enum EntitlementSource {
     Trial,
     /// Admin override/manual setting
     AdminOverride,
+    /// Feature granted to this org individually, regardless of tier
+    Override,
     /// Enterprise custom configuration
     EnterpriseConfig,
     /// Default (free tier)
@@ -73,7 +77,7 @@ pub enum EntitlementSource {
 }
 
 /// Feature flags based on tier
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EntitlementFeatures {
     /// Custom domain support
     pub custom_domain: bool,
@@ -87,6 +91,9 @@ pub struct EntitlementFeatures {
     pub api_access: bool,
     /// Webhook integration
     pub webhooks: bool,
+    /// Features outside the tier table granted through overrides (e.g. `advanced_routing`)
+    #[serde(default)]
+    pub extra: BTreeSet<String>,
 }
 
 impl EntitlementFeatures {
@@ -100,6 +107,7 @@ impl EntitlementFeatures {
                 advanced_analytics: false,
                 api_access: true,
                 webhooks: false,
+                extra: BTreeSet::new(),
             },
             SubscriptionTier::Starter | SubscriptionTier::Pro => Self {
                 custom_domain: true,
@@ -108,6 +116,7 @@ impl EntitlementFeatures {
                 advanced_analytics: true,
                 api_access: true,
                 webhooks: true,
+                extra: BTreeSet::new(),
             },
             SubscriptionTier::Team => Self {
                 custom_domain: true,
@@ -116,6 +125,7 @@ impl EntitlementFeatures {
                 advanced_analytics: true,
                 api_access: true,
                 webhooks: true,
+                extra: BTreeSet::new(),
             },
             SubscriptionTier::Enterprise => Self {
                 custom_domain: true,
@@ -124,9 +134,87 @@ impl EntitlementFeatures {
                 advanced_analytics: true,
                 api_access: true,
                 webhooks: true,
+                extra: BTreeSet::new(),
             },
         }
     }
+
+    /// Whether `feature` is enabled
+    pub fn has(&self, feature: &str) -> bool {
+        match feature {
+            "custom_domain" => self.custom_domain,
+            "sso" => self.sso,
+            "priority_support" => self.priority_support,
+            "advanced_analytics" => self.advanced_analytics,
+            "api_access" => self.api_access,
+            "webhooks" => self.webhooks,
+            _ => self.extra.contains(feature),
+        }
+    }
+
+    /// Enable `feature`, tracking names outside the tier table in `extra`
+    fn enable(&mut self, feature: &str) {
+        match feature {
+            "custom_domain" => self.custom_domain = true,
+            "sso" => self.sso = true,
+            "priority_support" => self.priority_support = true,
+            "advanced_analytics" => self.advanced_analytics = true,
+            "api_access" => self.api_access = true,
+            "webhooks" => self.webhooks = true,
+            _ => {
+                self.extra.insert(feature.to_string());
+            }
+        }
+    }
+
+    /// Names of all enabled features
+    fn enabled(&self) -> Vec<String> {
+        let flags = [
+            ("custom_domain", self.custom_domain),
+            ("sso", self.sso),
+            ("priority_support", self.priority_support),
+            ("advanced_analytics", self.advanced_analytics),
+            ("api_access", self.api_access),
+            ("webhooks", self.webhooks),
+        ];
+        flags
+            .into_iter()
+            .filter(|(_, on)| *on)
+            .map(|(name, _)| name.to_string())
+            .chain(self.extra.iter().cloned())
+            .collect()
+    }
+}
+
+/// A feature granted to one org regardless of tier (`entitlement_overrides`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FeatureOverride {
+    pub org_id: Uuid,
+    pub feature: String,
+    /// `None` for a permanent grant
+    pub expires_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+impl FeatureOverride {
+    /// Whether the grant is still in effect at `now`
+    pub fn is_active(&self, now: OffsetDateTime) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// What a tier grants on its own, independent of any organization
+///
+/// Limits are the tier defaults with no custom overrides, add-ons or
+/// billing state applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierEntitlements {
+    /// Tier these entitlements describe
+    pub tier: SubscriptionTier,
+    /// Tier default limits
+    pub limits: EffectiveLimits,
+    /// Feature flags
+    pub features: EntitlementFeatures,
 }
 
 /// Complete entitlement information for an organization
@@ -138,8 +226,10 @@ pub struct Entitlement {
     pub tier: SubscriptionTier,
     /// Effective limits (tier defaults + custom overrides)
     pub limits: EffectiveLimits,
-    /// Feature flags
+    /// Feature flags (tier features plus active overrides)
     pub features: EntitlementFeatures,
+    /// Why each enabled feature is on; `Override` where only an override grants it
+    pub feature_sources: BTreeMap<String, EntitlementSource>,
     /// What determined this entitlement
     pub source: EntitlementSource,
     /// When this entitlement was computed
@@ -160,15 +250,7 @@ impl Entitlement {
 
     /// Check if a specific feature is enabled
     pub fn has_feature(&self, feature: &str) -> bool {
-        match feature {
-            "custom_domain" => self.features.custom_domain,
-            "sso" => self.features.sso,
-            "priority_support" => self.features.priority_support,
-            "advanced_analytics" => self.features.advanced_analytics,
-            "api_access" => self.features.api_access,
-            "webhooks" => self.features.webhooks,
-            _ => false,
-        }
+        self.features.has(feature)
     }
 }
 
@@ -200,14 +282,105 @@ impl EntitlementService {
         Self { pool }
     }
 
+    /// What `tier` would grant, for plan comparison and upgrade prompts
+    ///
+    /// Uses the same limits and feature flags as [`compute_entitlement`](Self::compute_entitlement),
+    /// so callers don't need to duplicate the plan table.
+    pub fn entitlements_for_tier(tier: SubscriptionTier) -> TierEntitlements {
+        TierEntitlements {
+            tier,
+            limits: tier.effective_limits(&CustomLimits::default()),
+            features: EntitlementFeatures::for_tier(tier),
+        }
+    }
+
     /// Compute the complete entitlement for an organization
     /// This is THE function that answers "what can this org do?"
     pub async fn compute_entitlement(&self, org_id: Uuid) -> BillingResult<Entitlement> {
         // Load all relevant data in one query
         let raw = self.load_raw_billing_data(org_id).await?;
+        let overrides = self.load_active_overrides(org_id).await?;
 
         // Compute the entitlement deterministically
-        Ok(self.compute_from_raw(&raw))
+        Ok(self.compute_from_raw(&raw, &overrides))
+    }
+
+    /// Grant `feature` to an org regardless of its tier
+    ///
+    /// Re-granting an existing feature replaces its expiry. With
+    /// `expires_at` set the grant lapses on its own at that time.
+    pub async fn grant_feature(
+        &self,
+        org_id: Uuid,
+        feature: &str,
+        expires_at: Option<OffsetDateTime>,
+    ) -> BillingResult<FeatureOverride> {
+        validate_feature_name(feature)?;
+        if let Some(expires_at) = expires_at {
+            if expires_at <= OffsetDateTime::now_utc() {
+                return Err(BillingError::InvalidInput(
+                    "Feature grant expiry must be in the future".to_string(),
+                ));
+            }
+        }
+
+        let grant: FeatureOverride = sqlx::query_as(
+            r#"
+            INSERT INTO entitlement_overrides (org_id, feature, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (org_id, feature) DO UPDATE SET
+                expires_at = EXCLUDED.expires_at,
+                updated_at = NOW()
+            RETURNING org_id, feature, expires_at, created_at
+            "#,
+        )
+        .bind(org_id)
+        .bind(feature)
+        .bind(expires_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            feature = %feature,
+            expires_at = ?expires_at,
+            "Granted feature override"
+        );
+
+        Ok(grant)
+    }
+
+    /// Remove a feature grant; returns false if the org had none
+    pub async fn revoke_feature(&self, org_id: Uuid, feature: &str) -> BillingResult<bool> {
+        let result =
+            sqlx::query("DELETE FROM entitlement_overrides WHERE org_id = $1 AND feature = $2")
+                .bind(org_id)
+                .bind(feature)
+                .execute(&self.pool)
+                .await?;
+
+        let revoked = result.rows_affected() > 0;
+        if revoked {
+            tracing::info!(org_id = %org_id, feature = %feature, "Revoked feature override");
+        }
+        Ok(revoked)
+    }
+
+    /// Unexpired feature grants for an org
+    async fn load_active_overrides(&self, org_id: Uuid) -> BillingResult<Vec<FeatureOverride>> {
+        let overrides = sqlx::query_as(
+            r#"
+            SELECT org_id, feature, expires_at, created_at
+            FROM entitlement_overrides
+            WHERE org_id = $1 AND (expires_at IS NULL OR expires_at > NOW())
+            ORDER BY feature
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(overrides)
     }
 
     /// Load raw billing data for an organization
@@ -243,40 +416,25 @@ impl EntitlementService {
 
     /// Pure function: compute entitlement from raw data
     /// This is deterministic and testable
-    fn compute_from_raw(&self, raw: &RawBillingData) -> Entitlement {
+    fn compute_from_raw(&self, raw: &RawBillingData, overrides: &[FeatureOverride]) -> Entitlement {
         let now = OffsetDateTime::now_utc();
 
-        // Parse tier (defaults to Free if invalid)
-        let tier: SubscriptionTier = raw
-            .subscription_tier
-            .parse()
-            .unwrap_or(SubscriptionTier::Free);
+        // Tier and effective limits (tier defaults + custom overrides)
+        let (tier, limits) = tier_and_limits(raw);
 
         // Determine state
         let (state, source, expires_at, api_allowed, api_blocked_reason) =
             self.determine_state(raw, &tier, now);
 
-        // Build custom limits from raw data
-        let custom_limits = CustomLimits {
-            max_mcps: raw.custom_max_mcps.map(|v| v as u32),
-            max_api_keys: None, // Not in raw data
-            max_team_members: raw.custom_max_team_members.map(|v| v as u32),
-            max_requests_monthly: raw.custom_max_monthly_requests.map(|v| v as u64),
-            overage_rate_cents: None,
-            monthly_price_cents: None,
-        };
-
-        // Compute effective limits (tier defaults + custom overrides)
-        let limits = tier.effective_limits(&custom_limits);
-
-        // Get features for tier
-        let features = EntitlementFeatures::for_tier(tier);
+        // Tier features, plus any unexpired overrides
+        let (features, feature_sources) = resolve_features(tier, &source, overrides, now);
 
         Entitlement {
             state,
             tier,
             limits,
             features,
+            feature_sources,
             source,
             computed_at: now,
             expires_at,
@@ -439,6 +597,71 @@ impl EntitlementService {
     }
 }
 
+/// Pure function: tier features unioned with the overrides active at `now`
+///
+/// Features the tier already grants keep the entitlement's `source`; the rest
+/// are attributed to `EntitlementSource::Override`.
+fn resolve_features(
+    tier: SubscriptionTier,
+    source: &EntitlementSource,
+    overrides: &[FeatureOverride],
+    now: OffsetDateTime,
+) -> (EntitlementFeatures, BTreeMap<String, EntitlementSource>) {
+    let mut features = EntitlementFeatures::for_tier(tier);
+    let mut sources: BTreeMap<String, EntitlementSource> = features
+        .enabled()
+        .into_iter()
+        .map(|feature| (feature, source.clone()))
+        .collect();
+
+    for grant in overrides.iter().filter(|o| o.is_active(now)) {
+        if !features.has(&grant.feature) {
+            features.enable(&grant.feature);
+            sources.insert(grant.feature.clone(), EntitlementSource::Override);
+        }
+    }
+
+    (features, sources)
+}
+
+/// Feature names are lowercase identifiers, e.g. `advanced_routing`
+fn validate_feature_name(feature: &str) -> BillingResult<()> {
+    let valid = !feature.is_empty()
+        && feature.len() <= 64
+        && feature
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(BillingError::InvalidInput(format!(
+            "Invalid feature name '{}': use lowercase letters, digits and underscores",
+            feature
+        )))
+    }
+}
+
+/// Pure function: parsed tier and effective limits for raw billing data
+fn tier_and_limits(raw: &RawBillingData) -> (SubscriptionTier, EffectiveLimits) {
+    // Parse tier (defaults to Free if invalid)
+    let tier: SubscriptionTier = raw
+        .subscription_tier
+        .parse()
+        .unwrap_or(SubscriptionTier::Free);
+
+    // Build custom limits from raw data
+    let custom_limits = CustomLimits {
+        max_mcps: raw.custom_max_mcps.map(|v| v as u32),
+        max_api_keys: None, // Not in raw data
+        max_team_members: raw.custom_max_team_members.map(|v| v as u32),
+        max_requests_monthly: raw.custom_max_monthly_requests.map(|v| v as u64),
+        overage_rate_cents: None,
+        monthly_price_cents: None,
+    };
+
+    (tier, tier.effective_limits(&custom_limits))
+}
+
 // Implement FromRow for RawBillingData
 impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for RawBillingData {
     fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
@@ -487,4 +710,136 @@ mod tests {
         assert!(features.sso);
         assert!(features.priority_support);
     }
+
+    #[test]
+    fn test_entitlements_for_each_tier() {
+        let all_features = EntitlementFeatures {
+            custom_domain: true,
+            sso: true,
+            priority_support: true,
+            advanced_analytics: true,
+            api_access: true,
+            webhooks: true,
+            extra: BTreeSet::new(),
+        };
+        let expected = [
+            (
+                SubscriptionTier::Free,
+                EntitlementFeatures {
+                    custom_domain: false,
+                    sso: false,
+                    priority_support: false,
+                    advanced_analytics: false,
+                    api_access: true,
+                    webhooks: false,
+                    extra: BTreeSet::new(),
+                },
+            ),
+            (
+                SubscriptionTier::Pro,
+                EntitlementFeatures {
+                    sso: false,
+                    priority_support: false,
+                    ..all_features.clone()
+                },
+            ),
+            (SubscriptionTier::Team, all_features.clone()),
+            (SubscriptionTier::Enterprise, all_features),
+        ];
+
+        for (tier, features) in expected {
+            let entitlements = EntitlementService::entitlements_for_tier(tier);
+            assert_eq!(entitlements.tier, tier);
+            assert_eq!(entitlements.features, features, "{} features", tier);
+
+            let limits = &entitlements.limits;
+            assert_eq!(limits.max_mcps, tier.max_mcps());
+            assert_eq!(limits.max_api_keys, tier.max_api_keys());
+            assert_eq!(limits.max_team_members, tier.max_team_members());
+            assert_eq!(limits.max_requests_monthly, tier.monthly_requests());
+            assert_eq!(limits.overage_rate_cents, tier.overage_rate_per_1k_cents());
+            assert_eq!(limits.monthly_price_cents, None);
+        }
+    }
+
+    fn grant(feature: &str, expires_at: Option<OffsetDateTime>) -> FeatureOverride {
+        FeatureOverride {
+            org_id: Uuid::nil(),
+            feature: feature.to_string(),
+            expires_at,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    #[test]
+    fn test_overrides_union_with_tier_features() {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let overrides = vec![
+            grant("advanced_routing", None),
+            grant("sso", Some(now + time::Duration::days(7))),
+            // Pro already includes webhooks
+            grant("webhooks", None),
+        ];
+
+        let (features, sources) = resolve_features(
+            SubscriptionTier::Pro,
+            &EntitlementSource::Subscription,
+            &overrides,
+            now,
+        );
+
+        assert!(features.has("advanced_routing"));
+        assert!(features.sso);
+        assert!(features.custom_domain);
+        assert!(!features.priority_support);
+        assert!(matches!(
+            sources.get("advanced_routing"),
+            Some(EntitlementSource::Override)
+        ));
+        assert!(matches!(
+            sources.get("sso"),
+            Some(EntitlementSource::Override)
+        ));
+        assert!(matches!(
+            sources.get("webhooks"),
+            Some(EntitlementSource::Subscription)
+        ));
+        assert!(!sources.contains_key("priority_support"));
+    }
+
+    #[test]
+    fn test_expired_override_lapses() {
+        let now = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let overrides = vec![grant("advanced_routing", Some(now))];
+
+        let (features, sources) = resolve_features(
+            SubscriptionTier::Free,
+            &EntitlementSource::Default,
+            &overrides,
+            now,
+        );
+        assert!(!features.has("advanced_routing"));
+        assert!(!sources.contains_key("advanced_routing"));
+        assert_eq!(
+            features,
+            EntitlementFeatures::for_tier(SubscriptionTier::Free)
+        );
+
+        let (features, _) = resolve_features(
+            SubscriptionTier::Free,
+            &EntitlementSource::Default,
+            &overrides,
+            now - time::Duration::seconds(1),
+        );
+        assert!(features.has("advanced_routing"));
+    }
+
+    #[test]
+    fn test_feature_name_validation() {
+        assert!(validate_feature_name("advanced_routing").is_ok());
+        assert!(validate_feature_name("sso").is_ok());
+        for bad in ["", "Advanced Routing", "beta-feature", &"x".repeat(65)] {
+            assert!(validate_feature_name(bad).is_err(), "{bad:?}");
+        }
+    }
 }
@@ -8,6 +8,9 @@ pub enum BillingError {
     #[error("Stripe API error: {0}")]
     StripeApi(String),
 
+    #[error("Stripe rate limit exceeded: {0}")]
+    StripeRateLimited(String),
+
     #[error("Customer not found: {0}")]
     CustomerNotFound(String),
 
@@ -91,9 +94,30 @@ pub enum BillingError {
     #[error("Concurrent modification detected: {0}")]
     ConcurrentModification(String),
 
+    #[error("Plan change confirmation is stale: {0}")]
+    ConfirmationStale(String),
+
+    #[error("Invalid coupon: {0}")]
+    InvalidCoupon(String),
+
+    #[error("Invoice PDF is available after the invoice is finalized")]
+    InvoiceNotReady,
+
+    #[error("Invalid tax ID: {0}")]
+    InvalidTaxId(String),
+
+    #[error("No tax rate configured for {0}")]
+    TaxRateMissing(String),
+
     #[error("Invalid amount: {0}")]
     InvalidAmount(String),
 
+    #[error("Currency mismatch: customer is billed in {customer_currency}, requested {requested_currency}")]
+    CurrencyMismatch {
+        customer_currency: String,
+        requested_currency: String,
+    },
+
     #[error("Subscription required: {0}")]
     SubscriptionRequired(String),
 
@@ -101,6 +125,59 @@ pub enum BillingError {
     Unauthorized(String),
 }
 
+/// Error envelope returned by Stripe's REST API
+#[derive(Debug, serde::Deserialize)]
+struct StripeErrorEnvelope {
+    error: StripeErrorBody,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct StripeErrorBody {
+    #[serde(rename = "type")]
+    error_type: Option<String>,
+    code: Option<String>,
+    message: Option<String>,
+    param: Option<String>,
+}
+
+impl BillingError {
+    /// Map a raw Stripe HTTP error response into a typed error
+    ///
+    /// Used for endpoints called without async-stripe (e.g. `invoices/create_preview`).
+    pub fn from_stripe_error_body(status: u16, body: &str) -> Self {
+        let Ok(StripeErrorEnvelope { error }) = serde_json::from_str(body) else {
+            return BillingError::StripeApi(format!("Stripe API error ({}): {}", status, body));
+        };
+
+        let message = error.message.unwrap_or_default();
+        let code = error.code.as_deref().unwrap_or_default();
+        let param = error.param.as_deref().unwrap_or_default();
+        let lower = message.to_lowercase();
+
+        if status == 429 || error.error_type.as_deref() == Some("rate_limit_error") {
+            return BillingError::StripeRateLimited(message);
+        }
+        if lower.contains("no subscription")
+            || (code == "resource_missing" && param.starts_with("subscription"))
+        {
+            return BillingError::SubscriptionNotFound(message);
+        }
+        if code == "resource_missing" && param == "customer" {
+            return BillingError::CustomerNotFound(message);
+        }
+
+        let kind = if code.is_empty() {
+            error.error_type.unwrap_or_else(|| "unknown".to_string())
+        } else {
+            code.to_string()
+        };
+        BillingError::StripeApi(format!(
+            "Stripe API error ({} {}): {}",
+            status, kind, message
+        ))
+    }
+}
+
 impl From<stripe::StripeError> for BillingError {
     fn from(err: stripe::StripeError) -> Self {
         BillingError::StripeApi(err.to_string())
@@ -114,3 +191,64 @@ impl From<sqlx::Error> for BillingError {
 }
 
 pub type BillingResult<T> = Result<T, BillingError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_subscription_body_maps_to_subscription_not_found() {
+        let body = r#"{"error":{"type":"invalid_request_error","message":"The customer cus_123 has no subscription sub_456."}}"#;
+        match BillingError::from_stripe_error_body(400, body) {
+            BillingError::SubscriptionNotFound(msg) => assert!(msg.contains("no subscription")),
+            other => panic!("Expected SubscriptionNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_subscription_param_maps_to_subscription_not_found() {
+        let body = r#"{"error":{"type":"invalid_request_error","code":"resource_missing","param":"subscription","message":"No such subscription: 'sub_456'"}}"#;
+        assert!(matches!(
+            BillingError::from_stripe_error_body(404, body),
+            BillingError::SubscriptionNotFound(_)
+        ));
+    }
+
+    #[test]
+    fn test_missing_customer_maps_to_customer_not_found() {
+        let body = r#"{"error":{"type":"invalid_request_error","code":"resource_missing","param":"customer","message":"No such customer: 'cus_123'"}}"#;
+        match BillingError::from_stripe_error_body(404, body) {
+            BillingError::CustomerNotFound(msg) => assert_eq!(msg, "No such customer: 'cus_123'"),
+            other => panic!("Expected CustomerNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_body() {
+        let body = r#"{"error":{"type":"rate_limit_error","message":"Too many requests"}}"#;
+        assert!(matches!(
+            BillingError::from_stripe_error_body(429, body),
+            BillingError::StripeRateLimited(_)
+        ));
+    }
+
+    #[test]
+    fn test_other_errors_keep_code_and_message() {
+        let body = r#"{"error":{"type":"invalid_request_error","code":"parameter_invalid_empty","message":"You passed an empty string for 'customer'."}}"#;
+        match BillingError::from_stripe_error_body(400, body) {
+            BillingError::StripeApi(msg) => {
+                assert!(msg.contains("400 parameter_invalid_empty"));
+                assert!(msg.contains("empty string"));
+            }
+            other => panic!("Expected StripeApi, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unparseable_body_falls_back_to_raw() {
+        match BillingError::from_stripe_error_body(502, "Bad Gateway") {
+            BillingError::StripeApi(msg) => assert_eq!(msg, "Stripe API error (502): Bad Gateway"),
+            other => panic!("Expected StripeApi, got {:?}", other),
+        }
+    }
+}
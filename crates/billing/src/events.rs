@@ -52,6 +52,8 @@ pub enum BillingEventType {
     CreditApplied,
     OverageRecorded,
     OverageCharged,
+    /// Paid/invoiced overage exceeds corrected usage
+    OverageDiscrepancy,
     InstantCharge,
     PaymentFailed,
 
@@ -61,6 +63,9 @@ pub enum BillingEventType {
     SpendCapSet,
     SpendCapThreshold,
 
+    // Usage quota
+    QuotaSoftLimit,
+
     // Refunds and disputes
     RefundIssued,
     DisputeCreated,
@@ -99,12 +104,14 @@ impl std::fmt::Display for BillingEventType {
             BillingEventType::CreditApplied => "CREDIT_APPLIED",
             BillingEventType::OverageRecorded => "OVERAGE_RECORDED",
             BillingEventType::OverageCharged => "OVERAGE_CHARGED",
+            BillingEventType::OverageDiscrepancy => "OVERAGE_DISCREPANCY",
             BillingEventType::InstantCharge => "INSTANT_CHARGE",
             BillingEventType::PaymentFailed => "PAYMENT_FAILED",
             BillingEventType::OrgPaused => "ORG_PAUSED",
             BillingEventType::OrgUnpaused => "ORG_UNPAUSED",
             BillingEventType::SpendCapSet => "SPEND_CAP_SET",
             BillingEventType::SpendCapThreshold => "SPEND_CAP_THRESHOLD",
+            BillingEventType::QuotaSoftLimit => "QUOTA_SOFT_LIMIT",
             BillingEventType::RefundIssued => "REFUND_ISSUED",
             BillingEventType::DisputeCreated => "DISPUTE_CREATED",
             BillingEventType::DisputeResolved => "DISPUTE_RESOLVED",
@@ -0,0 +1,601 @@
+//! Non-payment grace period enforcement
+//!
+//! Orgs with unpaid invoices past `grace_period_ends_at` get
+//! `organizations.billing_blocked_at` set; blocked orgs whose invoices have
+//! all been paid are unblocked again. The worker runs this hourly. A dry run
+//! reports the same actions without changing anything, so operators can
+//! preview blocks and unblocks before they happen.
+//!
+//! When Stripe's final payment retry fails, the webhook doesn't block the
+//! org itself: it pulls the invoice's grace period in to
+//! `DECLINE_RETRY_GRACE_DAYS` from now and leaves the block to this job, so
+//! customers get a few days to fix their card and only one path ever blocks.
+//!
+//! Local invoices only change through webhooks, so a payment the webhook
+//! missed would keep an org blocked. `reconcile_blocked` re-checks blocked
+//! orgs against their live Stripe balance to catch that.
+
+use std::future::Future;
+
+use plexmcp_shared::Money;
+use serde::Serialize;
+use sqlx::PgPool;
+use stripe::CustomerId;
+use uuid::Uuid;
+
+use crate::account_status::{grace_period_block_reason, grace_period_days_for_tier};
+use crate::customer::CustomerService;
+use crate::db::{commit_or_rollback, PgTx};
+use crate::error::BillingResult;
+use crate::trial_payment::TRIAL_PAYMENT_BLOCK_REASON;
+
+/// What enforcement does (or would do) to an org
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GracePeriodAction {
+    /// Unpaid invoices past the grace period: set `billing_blocked_at`
+    Block,
+    /// Blocked, but nothing is owed anymore: clear the block
+    Unblock,
+}
+
+/// One org affected by enforcement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GracePeriodOrgAction {
+    pub org_id: Uuid,
+    pub org_name: String,
+    pub total_due: Money,
+    pub action: GracePeriodAction,
+    /// Reason stored on the org when blocking
+    pub block_reason: Option<String>,
+}
+
+/// Result of an enforcement run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct GracePeriodEnforcement {
+    pub dry_run: bool,
+    /// Actions taken, or that would be taken on a dry run
+    pub actions: Vec<GracePeriodOrgAction>,
+    /// Orgs that could not be blocked or unblocked
+    pub errors: usize,
+}
+
+impl GracePeriodEnforcement {
+    /// Orgs blocked by this run (or that would be)
+    pub fn blocked(&self) -> impl Iterator<Item = &GracePeriodOrgAction> {
+        self.actions
+            .iter()
+            .filter(|a| a.action == GracePeriodAction::Block)
+    }
+
+    /// Orgs unblocked by this run (or that would be)
+    pub fn unblocked(&self) -> impl Iterator<Item = &GracePeriodOrgAction> {
+        self.actions
+            .iter()
+            .filter(|a| a.action == GracePeriodAction::Unblock)
+    }
+}
+
+/// Service for blocking and unblocking orgs over unpaid invoices
+#[derive(Clone)]
+pub struct GracePeriodService {
+    pool: PgPool,
+}
+
+impl GracePeriodService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Schedule a block for an invoice whose final payment attempt failed
+    ///
+    /// Moves `grace_period_ends_at` to `grace_days` from now unless it already
+    /// ends sooner, so re-delivered failures never push the block back.
+    /// Returns false if the invoice isn't stored or is no longer unpaid.
+    pub async fn schedule_block_after_final_decline(
+        &self,
+        stripe_invoice_id: &str,
+        grace_days: i64,
+    ) -> BillingResult<bool> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE invoices
+            SET grace_period_ends_at = LEAST(
+                    COALESCE(grace_period_ends_at, 'infinity'::timestamptz),
+                    NOW() + make_interval(days => $2)
+                )
+            WHERE stripe_invoice_id = $1
+              AND status IN ('open', 'uncollectible')
+            "#,
+        )
+        .bind(stripe_invoice_id)
+        .bind(grace_days as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(updated.rows_affected() > 0)
+    }
+
+    /// Block orgs past their grace period and unblock orgs that have paid
+    ///
+    /// With `dry_run`, returns the same actions without touching
+    /// `billing_blocked_at`.
+    pub async fn enforce(&self, dry_run: bool) -> BillingResult<GracePeriodEnforcement> {
+        let mut enforcement = GracePeriodEnforcement {
+            dry_run,
+            ..Default::default()
+        };
+
+        // Orgs with invoices past grace period that aren't already blocked
+        let overdue: Vec<(Uuid, String, String, i64)> = sqlx::query_as(
+            r#"
+            SELECT
+                i.org_id,
+                o.name,
+                o.subscription_tier,
+                SUM(i.amount_due_cents)::BIGINT as total_due
+            FROM invoices i
+            JOIN organizations o ON i.org_id = o.id
+            WHERE i.status IN ('open', 'uncollectible')
+              AND i.grace_period_ends_at < NOW()
+              AND o.billing_blocked_at IS NULL
+            GROUP BY i.org_id, o.name, o.subscription_tier
+            HAVING SUM(i.amount_due_cents) > 0
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (org_id, org_name, tier, total_due_cents) in overdue {
+            let block_reason =
+                grace_period_block_reason(grace_period_days_for_tier(&tier), total_due_cents);
+
+            if !dry_run {
+                let result = sqlx::query(
+                    r#"
+                    UPDATE organizations
+                    SET billing_blocked_at = NOW(),
+                        billing_block_reason = $2
+                    WHERE id = $1
+                      AND billing_blocked_at IS NULL
+                    "#,
+                )
+                .bind(org_id)
+                .bind(&block_reason)
+                .execute(&self.pool)
+                .await;
+
+                match result {
+                    Ok(rows) if rows.rows_affected() > 0 => {
+                        tracing::warn!(
+                            org_id = %org_id,
+                            org_name = %org_name,
+                            total_due_cents = total_due_cents,
+                            "Organization blocked for non-payment"
+                        );
+                    }
+                    // Blocked concurrently by someone else
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::error!(
+                            org_id = %org_id,
+                            error = %e,
+                            "Failed to block organization for non-payment"
+                        );
+                        enforcement.errors += 1;
+                        continue;
+                    }
+                }
+            }
+
+            enforcement.actions.push(GracePeriodOrgAction {
+                org_id,
+                org_name,
+                total_due: Money::from_cents_usd(total_due_cents),
+                action: GracePeriodAction::Block,
+                block_reason: Some(block_reason),
+            });
+        }
+
+        // Blocked orgs with nothing left owing
+        // (trial payment method blocks are lifted by adding one, not by paying)
+        let paid_up_filter = r#"
+            o.billing_blocked_at IS NOT NULL
+            AND o.billing_block_reason IS DISTINCT FROM $1
+            AND NOT EXISTS (
+                SELECT 1 FROM invoices i
+                WHERE i.org_id = o.id
+                  AND i.status IN ('open', 'uncollectible')
+                  AND i.amount_due_cents > 0
+            )
+        "#;
+
+        let paid_up: Vec<(Uuid, String)> = if dry_run {
+            sqlx::query_as(&format!(
+                "SELECT o.id, o.name FROM organizations o WHERE {}",
+                paid_up_filter
+            ))
+            .bind(TRIAL_PAYMENT_BLOCK_REASON)
+            .fetch_all(&self.pool)
+            .await?
+        } else {
+            match sqlx::query_as(&format!(
+                r#"
+                UPDATE organizations o
+                SET billing_blocked_at = NULL,
+                    billing_block_reason = NULL
+                WHERE {}
+                RETURNING o.id, o.name
+                "#,
+                paid_up_filter
+            ))
+            .bind(TRIAL_PAYMENT_BLOCK_REASON)
+            .fetch_all(&self.pool)
+            .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to unblock paid-up organizations");
+                    enforcement.errors += 1;
+                    Vec::new()
+                }
+            }
+        };
+
+        enforcement
+            .actions
+            .extend(
+                paid_up
+                    .into_iter()
+                    .map(|(org_id, org_name)| GracePeriodOrgAction {
+                        org_id,
+                        org_name,
+                        total_due: Money::from_cents_usd(0),
+                        action: GracePeriodAction::Unblock,
+                        block_reason: None,
+                    }),
+            );
+
+        tracing::info!(
+            dry_run = dry_run,
+            blocked = enforcement.blocked().count(),
+            unblocked = enforcement.unblocked().count(),
+            errors = enforcement.errors,
+            "Grace period enforcement complete"
+        );
+
+        Ok(enforcement)
+    }
+
+    /// Unblock blocked orgs whose live Stripe balance is zero
+    pub async fn reconcile_blocked(
+        &self,
+        customers: &CustomerService,
+    ) -> BillingResult<GracePeriodEnforcement> {
+        self.reconcile_blocked_with(|customer_id| async move {
+            customers.outstanding_balance(&customer_id).await
+        })
+        .await
+    }
+
+    /// `reconcile_blocked` with the Stripe balance lookup supplied by the caller
+    async fn reconcile_blocked_with<F, Fut>(
+        &self,
+        outstanding_balance: F,
+    ) -> BillingResult<GracePeriodEnforcement>
+    where
+        F: Fn(CustomerId) -> Fut,
+        Fut: Future<Output = BillingResult<Money>>,
+    {
+        let mut enforcement = GracePeriodEnforcement::default();
+
+        let blocked: Vec<(Uuid, String, String)> = sqlx::query_as(
+            r#"
+            SELECT id, name, stripe_customer_id
+            FROM organizations
+            WHERE billing_blocked_at IS NOT NULL
+              AND billing_block_reason IS DISTINCT FROM $1
+              AND stripe_customer_id IS NOT NULL
+            "#,
+        )
+        .bind(TRIAL_PAYMENT_BLOCK_REASON)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (org_id, org_name, customer_id) in blocked {
+            let balance = match customer_id.parse::<CustomerId>() {
+                Ok(customer_id) => outstanding_balance(customer_id).await,
+                Err(e) => {
+                    tracing::warn!(org_id = %org_id, error = %e, "Blocked org has an invalid Stripe customer ID");
+                    enforcement.errors += 1;
+                    continue;
+                }
+            };
+            let balance = match balance {
+                Ok(balance) => balance,
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to fetch Stripe balance for blocked organization"
+                    );
+                    enforcement.errors += 1;
+                    continue;
+                }
+            };
+            if balance.amount_cents > 0 {
+                continue;
+            }
+
+            let mut tx = self.pool.begin().await?;
+            let written = unblock_settled(&mut tx, org_id).await;
+            match commit_or_rollback(tx, written).await {
+                Ok(true) => {
+                    tracing::info!(
+                        org_id = %org_id,
+                        org_name = %org_name,
+                        "Unblocked organization with no outstanding Stripe balance"
+                    );
+                    enforcement.actions.push(GracePeriodOrgAction {
+                        org_id,
+                        org_name,
+                        total_due: balance,
+                        action: GracePeriodAction::Unblock,
+                        block_reason: None,
+                    });
+                }
+                // Unblocked concurrently by someone else
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to unblock organization after Stripe reconciliation"
+                    );
+                    enforcement.errors += 1;
+                }
+            }
+        }
+
+        tracing::info!(
+            unblocked = enforcement.unblocked().count(),
+            errors = enforcement.errors,
+            "Blocked organization reconciliation complete"
+        );
+
+        Ok(enforcement)
+    }
+}
+
+/// Clear an org's block and settle the local invoices Stripe no longer shows as owed
+///
+/// Without settling them, the next `enforce` run would block the org again.
+/// Returns false if the org was no longer blocked.
+async fn unblock_settled(tx: &mut PgTx, org_id: Uuid) -> BillingResult<bool> {
+    let unblocked = sqlx::query(
+        r#"
+        UPDATE organizations
+        SET billing_blocked_at = NULL,
+            billing_block_reason = NULL
+        WHERE id = $1
+          AND billing_blocked_at IS NOT NULL
+        "#,
+    )
+    .bind(org_id)
+    .execute(&mut **tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if unblocked {
+        sqlx::query(
+            r#"
+            UPDATE invoices
+            SET status = 'paid',
+                paid_at = COALESCE(paid_at, NOW())
+            WHERE org_id = $1
+              AND status IN ('open', 'uncollectible')
+            "#,
+        )
+        .bind(org_id)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(unblocked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(action: GracePeriodAction) -> GracePeriodOrgAction {
+        GracePeriodOrgAction {
+            org_id: Uuid::new_v4(),
+            org_name: "Acme".to_string(),
+            total_due: Money::from_cents_usd(0),
+            action,
+            block_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_enforcement_splits_blocks_and_unblocks() {
+        let enforcement = GracePeriodEnforcement {
+            dry_run: true,
+            actions: vec![
+                action(GracePeriodAction::Block),
+                action(GracePeriodAction::Unblock),
+                action(GracePeriodAction::Block),
+            ],
+            errors: 0,
+        };
+
+        assert_eq!(enforcement.blocked().count(), 2);
+        assert_eq!(enforcement.unblocked().count(), 1);
+    }
+
+    #[test]
+    fn test_action_serialization() {
+        assert_eq!(
+            serde_json::to_value(GracePeriodAction::Unblock).unwrap(),
+            serde_json::json!("unblock")
+        );
+    }
+
+    async fn blocked_at(pool: &PgPool, org_id: Uuid) -> Option<time::OffsetDateTime> {
+        sqlx::query_scalar("SELECT billing_blocked_at FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_final_decline_blocks_only_after_grace_and_once() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = GracePeriodService::new(pool.clone());
+
+        // Auto-charged subscription invoice: no due date, so no grace period yet
+        let org_id = Uuid::new_v4();
+        let stripe_invoice_id = format!("in_{}", org_id.simple());
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Declined Org', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("declined-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO invoices (org_id, stripe_invoice_id, amount_cents, amount_due_cents, status)
+            VALUES ($1, $2, 2900, 2900, 'uncollectible')
+            "#,
+        )
+        .bind(org_id)
+        .bind(&stripe_invoice_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Final decline with a 3 day grace: not blocked yet
+        assert!(service
+            .schedule_block_after_final_decline(&stripe_invoice_id, 3)
+            .await
+            .unwrap());
+        let enforcement = service.enforce(false).await.unwrap();
+        assert!(!enforcement.blocked().any(|a| a.org_id == org_id));
+        assert_eq!(blocked_at(&pool, org_id).await, None);
+
+        // Grace runs out (simulated with a 0 day grace, which only ever shortens it)
+        service
+            .schedule_block_after_final_decline(&stripe_invoice_id, 0)
+            .await
+            .unwrap();
+        let enforcement = service.enforce(false).await.unwrap();
+        assert_eq!(
+            enforcement.blocked().filter(|a| a.org_id == org_id).count(),
+            1
+        );
+        let first_block = blocked_at(&pool, org_id).await;
+        assert!(first_block.is_some());
+
+        // A re-delivered decline and another run leave the original block alone
+        service
+            .schedule_block_after_final_decline(&stripe_invoice_id, 3)
+            .await
+            .unwrap();
+        let enforcement = service.enforce(false).await.unwrap();
+        assert!(!enforcement.blocked().any(|a| a.org_id == org_id));
+        assert_eq!(blocked_at(&pool, org_id).await, first_block);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_reconcile_unblocks_org_with_zero_stripe_balance() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = GracePeriodService::new(pool.clone());
+
+        // Paid out of band (zero Stripe balance) vs. still owing
+        let paid_org = Uuid::new_v4();
+        let owing_org = Uuid::new_v4();
+        for org_id in [paid_org, owing_org] {
+            sqlx::query(
+                r#"
+                INSERT INTO organizations
+                    (id, name, slug, subscription_tier, stripe_customer_id,
+                     billing_blocked_at, billing_block_reason)
+                VALUES ($1, 'Blocked Org', $2, 'pro', $3, NOW(), 'Unpaid invoice')
+                "#,
+            )
+            .bind(org_id)
+            .bind(format!("blocked-{}", org_id))
+            .bind(format!("cus_{}", org_id.simple()))
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                r#"
+                INSERT INTO invoices (org_id, amount_cents, amount_due_cents, status, grace_period_ends_at)
+                VALUES ($1, 2900, 2900, 'open', NOW() - interval '1 day')
+                "#,
+            )
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let paid_customer = format!("cus_{}", paid_org.simple());
+        let enforcement = service
+            .reconcile_blocked_with(|customer_id| {
+                let owed = if customer_id.as_str() == paid_customer {
+                    0
+                } else {
+                    2900
+                };
+                async move { Ok(Money::from_cents_usd(owed)) }
+            })
+            .await
+            .unwrap();
+
+        let unblocked: Vec<Uuid> = enforcement.unblocked().map(|a| a.org_id).collect();
+        assert!(unblocked.contains(&paid_org));
+        assert!(!unblocked.contains(&owing_org));
+
+        for (org_id, still_blocked, invoice_status) in
+            [(paid_org, false, "paid"), (owing_org, true, "open")]
+        {
+            let (blocked, status): (bool, String) = sqlx::query_as(
+                r#"
+                SELECT o.billing_blocked_at IS NOT NULL, i.status
+                FROM organizations o
+                JOIN invoices i ON i.org_id = o.id
+                WHERE o.id = $1
+                "#,
+            )
+            .bind(org_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(blocked, still_blocked);
+            assert_eq!(status, invoice_status);
+
+            sqlx::query("DELETE FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+}
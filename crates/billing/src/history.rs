@@ -5,21 +5,61 @@
 //! - Customer billing statements
 //! - Financial reconciliation
 
+use std::ops::Range;
+
 use serde::Serialize;
 use sqlx::PgPool;
+use stripe::{Invoice, InvoiceId, InvoiceStatus};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::client::StripeClient;
 use crate::error::{BillingError, BillingResult};
 
 /// Service for exporting billing history
 pub struct BillingHistoryService {
+    stripe: StripeClient,
     pool: PgPool,
 }
 
 impl BillingHistoryService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(stripe: StripeClient, pool: PgPool) -> Self {
+        Self { stripe, pool }
+    }
+
+    /// Download URL for an invoice's PDF
+    ///
+    /// The invoice must be recorded for `org_id` and belong to the org's
+    /// Stripe customer; otherwise `NotFound` is returned so other orgs'
+    /// invoice IDs can't be probed. Draft invoices, and finalized ones whose
+    /// PDF Stripe hasn't rendered yet, return `InvoiceNotReady`.
+    pub async fn get_invoice_pdf_url(
+        &self,
+        org_id: Uuid,
+        stripe_invoice_id: &str,
+    ) -> BillingResult<String> {
+        let not_found = || BillingError::NotFound("Invoice not found".to_string());
+
+        let customer_id: Option<Option<String>> = sqlx::query_scalar(
+            r#"
+            SELECT o.stripe_customer_id
+            FROM invoices i
+            JOIN organizations o ON o.id = i.org_id
+            WHERE i.org_id = $1 AND i.stripe_invoice_id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(stripe_invoice_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let customer_id = customer_id.flatten().ok_or_else(not_found)?;
+
+        let invoice_id = stripe_invoice_id
+            .parse::<InvoiceId>()
+            .map_err(|_| not_found())?;
+        let invoice = Invoice::retrieve(self.stripe.inner(), &invoice_id, &[]).await?;
+
+        invoice_pdf_url(&invoice, &customer_id)
     }
 
     /// Export billing history for an organization to CSV format
@@ -58,6 +98,93 @@ impl BillingHistoryService {
         Ok(csv)
     }
 
+    /// Export invoices, overage charges, refunds and credits to a single CSV
+    ///
+    /// Columns are `date,type,description,amount_cents,currency,status,stripe_id`,
+    /// newest first. Refunds and credits are negative so `amount_cents` sums to
+    /// net revenue. Invoices already include billed overage, so overage charges
+    /// are only listed while still unbilled; draft and void invoices and failed
+    /// refunds are left out.
+    pub async fn export_csv(
+        &self,
+        org_id: Uuid,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> BillingResult<String> {
+        let mut rows: Vec<ExportRow> = sqlx::query_as(
+            r#"
+            SELECT
+                created_at AS date,
+                'invoice' AS entry_type,
+                COALESCE(description, billing_reason, 'Invoice') AS description,
+                amount_cents::BIGINT AS amount_cents,
+                LOWER(currency) AS currency,
+                status,
+                stripe_invoice_id AS stripe_id
+            FROM invoices
+            WHERE org_id = $1
+              AND created_at >= $2
+              AND created_at <= $3
+              AND status NOT IN ('draft', 'void')
+            "#,
+        )
+        .bind(org_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let overages: Vec<ExportRow> = sqlx::query_as(
+            r#"
+            SELECT
+                created_at AS date,
+                'overage' AS entry_type,
+                resource_type || ' overage (' || overage_amount || ' units)' AS description,
+                total_charge_cents::BIGINT AS amount_cents,
+                'usd' AS currency,
+                COALESCE(status, 'pending') AS status,
+                stripe_invoice_item_id AS stripe_id
+            FROM overage_charges
+            WHERE org_id = $1
+              AND created_at >= $2
+              AND created_at <= $3
+              AND status IN ('pending', 'awaiting_payment')
+            "#,
+        )
+        .bind(org_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.extend(overages);
+
+        let refunds: Vec<ExportRow> = sqlx::query_as(
+            r#"
+            SELECT
+                created_at AS date,
+                refund_type AS entry_type,
+                reason AS description,
+                -ABS(amount_cents)::BIGINT AS amount_cents,
+                'usd' AS currency,
+                status,
+                stripe_refund_id AS stripe_id
+            FROM admin_refunds
+            WHERE org_id = $1
+              AND created_at >= $2
+              AND created_at <= $3
+              AND status <> 'failed'
+            "#,
+        )
+        .bind(org_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.extend(refunds);
+
+        Ok(export_rows_to_csv(rows))
+    }
+
     /// Get billing history records for an organization
     pub async fn get_billing_history(
         &self,
@@ -301,6 +428,8 @@ impl BillingHistoryService {
             }
         }
 
+        let request_count = self.request_count(org_id, start_date, end_date).await?;
+
         Ok(BillingSummary {
             org_id,
             period_start: start_date
@@ -312,9 +441,59 @@ impl BillingHistoryService {
             overage_charges_cents: overage_charges,
             instant_charges_cents: instant_charges,
             subscription_charges_cents: subscription_charges,
+            request_count,
             record_count: records.len(),
         })
     }
+
+    /// Compare two periods' billing summaries
+    ///
+    /// `period_a` is the baseline (e.g. last month) and `period_b` the period
+    /// compared against it, so deltas are `b - a`.
+    pub async fn compare_periods(
+        &self,
+        org_id: Uuid,
+        period_a: Range<OffsetDateTime>,
+        period_b: Range<OffsetDateTime>,
+    ) -> BillingResult<PeriodComparison> {
+        let a = self
+            .get_billing_summary(org_id, Some(period_a.start), Some(period_a.end))
+            .await?;
+        let b = self
+            .get_billing_summary(org_id, Some(period_b.start), Some(period_b.end))
+            .await?;
+
+        Ok(PeriodComparison::new(a, b))
+    }
+
+    /// Requests recorded in usage_records for a period (same defaults as the history)
+    async fn request_count(
+        &self,
+        org_id: Uuid,
+        start_date: Option<OffsetDateTime>,
+        end_date: Option<OffsetDateTime>,
+    ) -> BillingResult<i64> {
+        let start =
+            start_date.unwrap_or_else(|| OffsetDateTime::now_utc() - time::Duration::days(365));
+        let end = end_date.unwrap_or_else(OffsetDateTime::now_utc);
+
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(request_count), 0)::BIGINT
+            FROM usage_records
+            WHERE org_id = $1
+              AND period_start >= $2
+              AND period_start <= $3
+            "#,
+        )
+        .bind(org_id)
+        .bind(start)
+        .bind(end)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
 }
 
 /// A billing history record
@@ -340,9 +519,61 @@ pub struct BillingSummary {
     pub overage_charges_cents: i64,
     pub instant_charges_cents: i64,
     pub subscription_charges_cents: i64,
+    pub request_count: i64,
     pub record_count: usize,
 }
 
+/// Change in one metric between two periods
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MetricDelta {
+    pub period_a: i64,
+    pub period_b: i64,
+    /// `period_b - period_a`
+    pub absolute: i64,
+    /// Change relative to `period_a`, or `None` when `period_a` is zero
+    pub percent: Option<f64>,
+}
+
+impl MetricDelta {
+    pub fn new(period_a: i64, period_b: i64) -> Self {
+        let absolute = period_b - period_a;
+        Self {
+            period_a,
+            period_b,
+            absolute,
+            percent: (period_a != 0).then(|| absolute as f64 / period_a.abs() as f64 * 100.0),
+        }
+    }
+}
+
+/// Two periods' billing summaries with the deltas between them
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodComparison {
+    pub period_a: BillingSummary,
+    pub period_b: BillingSummary,
+    pub total_charged: MetricDelta,
+    pub overage: MetricDelta,
+    pub request_count: MetricDelta,
+}
+
+impl PeriodComparison {
+    fn new(period_a: BillingSummary, period_b: BillingSummary) -> Self {
+        Self {
+            total_charged: MetricDelta::new(
+                period_a.total_charges_cents,
+                period_b.total_charges_cents,
+            ),
+            overage: MetricDelta::new(
+                period_a.overage_charges_cents,
+                period_b.overage_charges_cents,
+            ),
+            request_count: MetricDelta::new(period_a.request_count, period_b.request_count),
+            period_a,
+            period_b,
+        }
+    }
+}
+
 // Database row types
 #[derive(sqlx::FromRow)]
 struct TierChangeRow {
@@ -391,6 +622,63 @@ struct BillingEventRow {
     stripe_invoice_id: Option<String>,
 }
 
+/// PDF URL of an invoice owned by `customer_id`
+fn invoice_pdf_url(invoice: &Invoice, customer_id: &str) -> BillingResult<String> {
+    let owner = invoice.customer.as_ref().map(|customer| customer.id());
+    if owner.as_ref().map(|id| id.as_str()) != Some(customer_id) {
+        tracing::warn!(
+            invoice_id = %invoice.id,
+            "Invoice PDF requested for an invoice of another customer"
+        );
+        return Err(BillingError::NotFound("Invoice not found".to_string()));
+    }
+
+    if invoice.status == Some(InvoiceStatus::Draft) {
+        return Err(BillingError::InvoiceNotReady);
+    }
+    invoice
+        .invoice_pdf
+        .clone()
+        .filter(|url| !url.is_empty())
+        .ok_or(BillingError::InvoiceNotReady)
+}
+
+/// One line of the `export_csv` output
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct ExportRow {
+    date: OffsetDateTime,
+    entry_type: String,
+    description: String,
+    amount_cents: i64,
+    currency: String,
+    status: String,
+    stripe_id: Option<String>,
+}
+
+/// Render export rows as CSV, newest first
+fn export_rows_to_csv(mut rows: Vec<ExportRow>) -> String {
+    rows.sort_by_key(|row| std::cmp::Reverse(row.date));
+
+    let mut csv = String::from("date,type,description,amount_cents,currency,status,stripe_id\n");
+    for row in rows {
+        let date = row
+            .date
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_else(|_| "unknown".to_string());
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            date,
+            escape_csv_field(&row.entry_type),
+            escape_csv_field(&row.description),
+            row.amount_cents,
+            escape_csv_field(&row.currency),
+            escape_csv_field(&row.status),
+            escape_csv_field(row.stripe_id.as_deref().unwrap_or_default()),
+        ));
+    }
+    csv
+}
+
 /// Escape a field for CSV output
 fn escape_csv_field(field: &str) -> String {
     if field.contains(',') || field.contains('"') || field.contains('\n') {
@@ -399,3 +687,165 @@ fn escape_csv_field(field: &str) -> String {
         field.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(
+        days_ago: i64,
+        entry_type: &str,
+        amount_cents: i64,
+        stripe_id: Option<&str>,
+    ) -> ExportRow {
+        ExportRow {
+            date: OffsetDateTime::from_unix_timestamp(1_767_225_600).unwrap()
+                - time::Duration::days(days_ago),
+            entry_type: entry_type.to_string(),
+            description: format!("{} row", entry_type),
+            amount_cents,
+            currency: "usd".to_string(),
+            status: "paid".to_string(),
+            stripe_id: stripe_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_export_csv_orders_newest_first_and_nets_refunds() {
+        let csv = export_rows_to_csv(vec![
+            row(10, "invoice", 2900, Some("in_1")),
+            row(1, "refund", -1000, Some("re_1")),
+            row(5, "overage", 150, None),
+            row(3, "credit", -500, None),
+        ]);
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "date,type,description,amount_cents,currency,status,stripe_id"
+        );
+        assert_eq!(
+            lines[1],
+            "2025-12-31T00:00:00Z,refund,refund row,-1000,usd,paid,re_1"
+        );
+        let types: Vec<&str> = lines[1..]
+            .iter()
+            .map(|line| line.split(',').nth(1).unwrap())
+            .collect();
+        assert_eq!(types, ["refund", "credit", "overage", "invoice"]);
+
+        let net: i64 = lines[1..]
+            .iter()
+            .map(|line| line.split(',').nth(3).unwrap().parse::<i64>().unwrap())
+            .sum();
+        assert_eq!(net, 2900 + 150 - 1000 - 500);
+    }
+
+    fn invoice(customer: &str, status: InvoiceStatus, pdf: Option<&str>) -> Invoice {
+        Invoice {
+            id: "in_123".parse().unwrap(),
+            customer: Some(stripe::Expandable::Id(customer.parse().unwrap())),
+            status: Some(status),
+            invoice_pdf: pdf.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_invoice_pdf_url() {
+        let pdf = "https://pay.stripe.com/invoice/acct_1/in_123/pdf";
+        assert_eq!(
+            invoice_pdf_url(
+                &invoice("cus_org", InvoiceStatus::Paid, Some(pdf)),
+                "cus_org"
+            )
+            .unwrap(),
+            pdf
+        );
+
+        // Another customer's invoice looks the same as a missing one
+        assert!(matches!(
+            invoice_pdf_url(
+                &invoice("cus_other", InvoiceStatus::Paid, Some(pdf)),
+                "cus_org"
+            ),
+            Err(BillingError::NotFound(_))
+        ));
+
+        // Drafts and not-yet-rendered PDFs aren't ready
+        assert!(matches!(
+            invoice_pdf_url(
+                &invoice("cus_org", InvoiceStatus::Draft, Some(pdf)),
+                "cus_org"
+            ),
+            Err(BillingError::InvoiceNotReady)
+        ));
+        assert!(matches!(
+            invoice_pdf_url(&invoice("cus_org", InvoiceStatus::Open, None), "cus_org"),
+            Err(BillingError::InvoiceNotReady)
+        ));
+    }
+
+    fn summary(
+        total_charges_cents: i64,
+        overage_charges_cents: i64,
+        request_count: i64,
+    ) -> BillingSummary {
+        BillingSummary {
+            org_id: Uuid::nil(),
+            period_start: OffsetDateTime::UNIX_EPOCH,
+            period_end: OffsetDateTime::UNIX_EPOCH,
+            total_charges_cents,
+            total_refunds_cents: 0,
+            net_charges_cents: total_charges_cents,
+            overage_charges_cents,
+            instant_charges_cents: 0,
+            subscription_charges_cents: total_charges_cents - overage_charges_cents,
+            request_count,
+            record_count: 0,
+        }
+    }
+
+    #[test]
+    fn test_period_comparison_deltas() {
+        let comparison =
+            PeriodComparison::new(summary(4_000, 1_000, 80_000), summary(5_000, 500, 100_000));
+
+        assert_eq!(
+            comparison.total_charged,
+            MetricDelta {
+                period_a: 4_000,
+                period_b: 5_000,
+                absolute: 1_000,
+                percent: Some(25.0),
+            }
+        );
+        assert_eq!(comparison.overage.absolute, -500);
+        assert_eq!(comparison.overage.percent, Some(-50.0));
+        assert_eq!(comparison.request_count.percent, Some(25.0));
+    }
+
+    #[test]
+    fn test_period_comparison_zero_baseline_has_no_percent() {
+        let comparison = PeriodComparison::new(summary(0, 0, 0), summary(2_900, 0, 10_000));
+
+        assert_eq!(comparison.total_charged.absolute, 2_900);
+        assert_eq!(comparison.total_charged.percent, None);
+        assert_eq!(comparison.request_count.percent, None);
+        // Zero to zero is no change, but still has no meaningful percentage
+        assert_eq!(comparison.overage.absolute, 0);
+        assert_eq!(comparison.overage.percent, None);
+    }
+
+    #[test]
+    fn test_export_csv_escapes_fields() {
+        let mut refund = row(0, "refund", -250, None);
+        refund.description = "Duplicate charge, \"Pro\" plan".to_string();
+
+        let csv = export_rows_to_csv(vec![refund]);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "2026-01-01T00:00:00Z,refund,\"Duplicate charge, \"\"Pro\"\" plan\",-250,usd,paid,"
+        );
+    }
+}
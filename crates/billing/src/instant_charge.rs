@@ -5,10 +5,15 @@
 //!
 //! Configuration via environment variables:
 //! - `INSTANT_CHARGE_THRESHOLD_CENTS`: Threshold in cents (default: 5000 = $50.00)
+//! - `INSTANT_CHARGE_THRESHOLD_CENTS_<TIER>`: Threshold for one tier, e.g.
+//!   `INSTANT_CHARGE_THRESHOLD_CENTS_ENTERPRISE=50000`
 //! - `INSTANT_CHARGE_COOLDOWN_HOURS`: Cooldown period in hours (default: 1)
+//!
+//! An org's `instant_charge_threshold_cents` overrides both.
 
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
+use std::collections::HashMap;
 use std::sync::OnceLock;
 use stripe::{CreateInvoice, CreateInvoiceItem, CustomerId, Invoice, InvoiceId};
 use time::OffsetDateTime;
@@ -35,6 +40,34 @@ fn get_threshold_cents() -> i32 {
     })
 }
 
+/// Per-tier thresholds from `INSTANT_CHARGE_THRESHOLD_CENTS_<TIER>`, keyed by lowercase tier
+fn get_tier_thresholds() -> &'static HashMap<String, i32> {
+    static THRESHOLDS: OnceLock<HashMap<String, i32>> = OnceLock::new();
+    THRESHOLDS.get_or_init(|| tier_thresholds_from(std::env::vars()))
+}
+
+fn tier_thresholds_from(vars: impl Iterator<Item = (String, String)>) -> HashMap<String, i32> {
+    vars.filter_map(|(key, value)| {
+        let tier = key.strip_prefix("INSTANT_CHARGE_THRESHOLD_CENTS_")?;
+        let cents: i32 = value.trim().parse().ok().filter(|&c| c > 0)?;
+        Some((tier.to_lowercase(), cents))
+    })
+    .collect()
+}
+
+/// Threshold in effect for an org: its override, else its tier's, else the global one
+fn resolve_threshold_cents(
+    org_override: Option<i32>,
+    tier: &str,
+    tier_thresholds: &HashMap<String, i32>,
+    global: i32,
+) -> i32 {
+    org_override
+        .filter(|&cents| cents > 0)
+        .or_else(|| tier_thresholds.get(&tier.to_lowercase()).copied())
+        .unwrap_or(global)
+}
+
 /// Get configured cooldown period
 fn get_cooldown_hours() -> i64 {
     static COOLDOWN: OnceLock<i64> = OnceLock::new();
@@ -46,6 +79,11 @@ fn get_cooldown_hours() -> i64 {
     })
 }
 
+/// When the next charge is allowed, given the most recent one
+fn next_eligible_at(last_charge_at: OffsetDateTime, cooldown_hours: i64) -> OffsetDateTime {
+    last_charge_at + time::Duration::hours(cooldown_hours)
+}
+
 /// Threshold for instant charges in cents (for backwards compatibility)
 pub const INSTANT_CHARGE_THRESHOLD_CENTS: i32 = DEFAULT_THRESHOLD_CENTS;
 
@@ -64,15 +102,34 @@ pub struct InstantCharge {
     pub created_at: OffsetDateTime,
     pub processed_at: Option<OffsetDateTime>,
     pub paid_at: Option<OffsetDateTime>,
+    /// Threshold in effect when the charge was created (None for older charges)
+    pub threshold_cents: Option<i32>,
 }
 
 /// Response from instant charge check
 #[derive(Debug, Clone, Serialize)]
-pub struct InstantChargeResult {
-    pub charged: bool,
-    pub charge_id: Option<Uuid>,
-    pub amount_cents: Option<i32>,
-    pub reason: String,
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum InstantChargeResult {
+    /// Overage has not reached the org's threshold yet
+    BelowThreshold {
+        amount_cents: i32,
+        threshold_cents: i32,
+    },
+    /// An instant charge was created and sent to Stripe
+    Charged { charge_id: Uuid, amount_cents: i32 },
+    /// A charge was already made within the cooldown window; the overage keeps
+    /// accruing and is picked up by the next eligible charge or the billing cycle
+    Cooldown {
+        #[serde(with = "time::serde::rfc3339")]
+        next_eligible_at: OffsetDateTime,
+    },
+}
+
+impl InstantChargeResult {
+    /// Whether a charge was created
+    pub fn charged(&self) -> bool {
+        matches!(self, Self::Charged { .. })
+    }
 }
 
 /// Instant charge service
@@ -91,6 +148,59 @@ impl InstantChargeService {
         }
     }
 
+    /// Instant charge threshold for an org
+    ///
+    /// Uses the org's override if set, then its tier's configured threshold,
+    /// then the global `INSTANT_CHARGE_THRESHOLD_CENTS`.
+    pub async fn threshold_for_org(&self, org_id: Uuid) -> BillingResult<i32> {
+        let org: Option<(String, Option<i32>)> = sqlx::query_as(
+            "SELECT subscription_tier, instant_charge_threshold_cents FROM organizations WHERE id = $1",
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let (tier, org_override) = org.unwrap_or_default();
+
+        Ok(resolve_threshold_cents(
+            org_override,
+            &tier,
+            get_tier_thresholds(),
+            get_threshold_cents(),
+        ))
+    }
+
+    /// Set or clear (`None`) an org's instant charge threshold override
+    pub async fn set_threshold_override(
+        &self,
+        org_id: Uuid,
+        threshold_cents: Option<i32>,
+    ) -> BillingResult<()> {
+        if threshold_cents.is_some_and(|cents| cents <= 0) {
+            return Err(BillingError::InvalidAmount(
+                "Instant charge threshold must be positive".to_string(),
+            ));
+        }
+
+        let updated = sqlx::query(
+            "UPDATE organizations SET instant_charge_threshold_cents = $1 WHERE id = $2",
+        )
+        .bind(threshold_cents)
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            return Err(BillingError::NotFound(format!("Organization {}", org_id)));
+        }
+
+        tracing::info!(
+            org_id = %org_id,
+            threshold_cents = ?threshold_cents,
+            "Updated instant charge threshold override"
+        );
+        Ok(())
+    }
+
     /// Check if instant charge is needed and process if so
     /// Returns the charge result
     ///
@@ -103,20 +213,14 @@ impl InstantChargeService {
         usage_count: i64,
         overage_count: i64,
     ) -> BillingResult<InstantChargeResult> {
-        let threshold = get_threshold_cents();
+        let threshold = self.threshold_for_org(org_id).await?;
         let cooldown_hours = get_cooldown_hours();
 
         // Check if below threshold
         if current_overage_cents < threshold {
-            return Ok(InstantChargeResult {
-                charged: false,
-                charge_id: None,
-                amount_cents: None,
-                reason: format!(
-                    "Below threshold: ${:.2} < ${:.2}",
-                    current_overage_cents as f64 / 100.0,
-                    threshold as f64 / 100.0
-                ),
+            return Ok(InstantChargeResult::BelowThreshold {
+                amount_cents: current_overage_cents,
+                threshold_cents: threshold,
             });
         }
 
@@ -124,8 +228,8 @@ impl InstantChargeService {
         // This prevents race conditions where two requests could both pass the cooldown check
         let inserted: Option<InstantCharge> = sqlx::query_as(
             r#"
-            INSERT INTO instant_charges (org_id, amount_cents, usage_at_charge, overage_at_charge, status)
-            SELECT $1, $2, $3, $4, 'pending'
+            INSERT INTO instant_charges (org_id, amount_cents, usage_at_charge, overage_at_charge, status, threshold_cents)
+            SELECT $1, $2, $3, $4, 'pending', $6
             WHERE NOT EXISTS (
                 SELECT 1 FROM instant_charges
                 WHERE org_id = $1
@@ -140,6 +244,7 @@ impl InstantChargeService {
         .bind(usage_count)
         .bind(overage_count)
         .bind(cooldown_hours)
+        .bind(threshold)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -147,12 +252,19 @@ impl InstantChargeService {
         let charge = match inserted {
             Some(c) => c,
             None => {
-                return Ok(InstantChargeResult {
-                    charged: false,
-                    charge_id: None,
-                    amount_cents: None,
-                    reason: "Already charged within cooldown period".to_string(),
-                });
+                let next_eligible_at = self
+                    .cooldown_ends_at(org_id, cooldown_hours)
+                    .await?
+                    .unwrap_or_else(OffsetDateTime::now_utc);
+
+                tracing::info!(
+                    org_id = %org_id,
+                    amount_cents = current_overage_cents,
+                    next_eligible_at = %next_eligible_at,
+                    "Instant charge deferred by cooldown"
+                );
+
+                return Ok(InstantChargeResult::Cooldown { next_eligible_at });
             }
         };
 
@@ -196,14 +308,9 @@ impl InstantChargeService {
                 self.send_instant_charge_notification(org_id, current_overage_cents, overage_count)
                     .await;
 
-                Ok(InstantChargeResult {
-                    charged: true,
-                    charge_id: Some(charge.id),
-                    amount_cents: Some(charge.amount_cents),
-                    reason: format!(
-                        "Instant charge created: ${:.2}",
-                        charge.amount_cents as f64 / 100.0
-                    ),
+                Ok(InstantChargeResult::Charged {
+                    charge_id: charge.id,
+                    amount_cents: charge.amount_cents,
                 })
             }
             Err(e) => {
@@ -233,6 +340,26 @@ impl InstantChargeService {
         }
     }
 
+    /// End of the cooldown started by the org's most recent charge, if any
+    async fn cooldown_ends_at(
+        &self,
+        org_id: Uuid,
+        cooldown_hours: i64,
+    ) -> BillingResult<Option<OffsetDateTime>> {
+        let last_charge_at: Option<OffsetDateTime> = sqlx::query_scalar(
+            r#"
+            SELECT MAX(created_at) FROM instant_charges
+            WHERE org_id = $1
+              AND status IN ('pending', 'processing', 'succeeded')
+            "#,
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(last_charge_at.map(|at| next_eligible_at(at, cooldown_hours)))
+    }
+
     /// Process Stripe charge (create invoice item + invoice, then finalize)
     async fn process_stripe_charge(
         &self,
@@ -338,7 +465,7 @@ impl InstantChargeService {
         let charges: Vec<InstantCharge> = sqlx::query_as(
             "SELECT id, org_id, amount_cents, usage_at_charge, overage_at_charge,
                     stripe_invoice_id, stripe_payment_intent_id, status, error_message,
-                    created_at, processed_at, paid_at
+                    created_at, processed_at, paid_at, threshold_cents
              FROM instant_charges WHERE org_id = $1 AND status IN ('pending', 'processing') ORDER BY created_at DESC"
         )
         .bind(org_id)
@@ -353,7 +480,7 @@ impl InstantChargeService {
         let charges: Vec<InstantCharge> = sqlx::query_as(
             "SELECT id, org_id, amount_cents, usage_at_charge, overage_at_charge,
                     stripe_invoice_id, stripe_payment_intent_id, status, error_message,
-                    created_at, processed_at, paid_at
+                    created_at, processed_at, paid_at, threshold_cents
              FROM instant_charges WHERE org_id = $1 ORDER BY created_at DESC LIMIT $2",
         )
         .bind(org_id)
@@ -378,6 +505,7 @@ impl InstantChargeService {
             FROM users u
             JOIN organizations o ON o.id = u.org_id
             WHERE u.org_id = $1 AND u.role = 'owner'
+            ORDER BY u.created_at ASC, u.id ASC
             LIMIT 1
             "#,
         )
@@ -395,3 +523,72 @@ impl InstantChargeService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tier_thresholds_from_env() {
+        let vars = [
+            ("INSTANT_CHARGE_THRESHOLD_CENTS_ENTERPRISE", "50000"),
+            ("INSTANT_CHARGE_THRESHOLD_CENTS_TEAM", " 10000 "),
+            ("INSTANT_CHARGE_THRESHOLD_CENTS_PRO", "-1"),
+            ("INSTANT_CHARGE_THRESHOLD_CENTS", "7500"),
+            ("INSTANT_CHARGE_COOLDOWN_HOURS", "2"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+
+        let thresholds = tier_thresholds_from(vars.into_iter());
+        assert_eq!(thresholds.len(), 2);
+        assert_eq!(thresholds["enterprise"], 50_000);
+        assert_eq!(thresholds["team"], 10_000);
+    }
+
+    #[test]
+    fn test_resolve_threshold_precedence() {
+        let tiers = HashMap::from([("enterprise".to_string(), 50_000)]);
+
+        // Org override wins over the tier threshold
+        assert_eq!(
+            resolve_threshold_cents(Some(100_000), "enterprise", &tiers, 5_000),
+            100_000
+        );
+        // Tier threshold wins over the global default
+        assert_eq!(
+            resolve_threshold_cents(None, "Enterprise", &tiers, 5_000),
+            50_000
+        );
+        // Tiers without their own threshold use the global default
+        assert_eq!(resolve_threshold_cents(None, "pro", &tiers, 5_000), 5_000);
+        assert_eq!(
+            resolve_threshold_cents(Some(0), "pro", &tiers, 5_000),
+            5_000
+        );
+    }
+
+    #[test]
+    fn test_next_eligible_at_adds_cooldown() {
+        // 2026-01-08T12:30:00Z
+        let last = OffsetDateTime::from_unix_timestamp(1_767_875_400).unwrap();
+        assert_eq!(
+            next_eligible_at(last, 1).unix_timestamp(),
+            1_767_875_400 + 3_600
+        );
+        assert_eq!(
+            next_eligible_at(last, 24).unix_timestamp(),
+            1_767_875_400 + 86_400
+        );
+    }
+
+    #[test]
+    fn test_cooldown_result_serializes_next_eligible_at() {
+        let result = InstantChargeResult::Cooldown {
+            next_eligible_at: OffsetDateTime::from_unix_timestamp(1_767_879_000).unwrap(),
+        };
+        assert!(!result.charged());
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["result"], "cooldown");
+        assert_eq!(json["next_eligible_at"], "2026-01-08T13:30:00Z");
+    }
+}
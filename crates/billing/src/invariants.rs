@@ -10,13 +10,25 @@
 //! 2. **Explanatory**: Violations include enough context to debug
 //! 3. **Non-destructive**: Checks only read, never write
 //! 4. **Complete**: Covers all critical billing consistency requirements
+//!
+//! ## Repairs
+//!
+//! `InvariantChecker::repair` fixes the violations that have an unambiguous,
+//! idempotent fix and logs a billing event for each repair. Everything else
+//! is report-only and comes back as `RepairOutcome::ManualRequired`.
 
+use plexmcp_shared::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::error::BillingResult;
+use crate::events::{ActorType, BillingEventBuilder, BillingEventLogger, BillingEventType};
+use crate::subscriptions::SubscriptionService;
+
+/// A downgrade claim older than this is considered abandoned
+const STALE_DOWNGRADE_CLAIM_MINUTES: i64 = 60;
 
 /// Result of running a single invariant check
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +86,18 @@ pub struct InvariantCheckSummary {
     pub healthy: bool,
 }
 
+/// Result of attempting to repair a violation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum RepairOutcome {
+    /// The violation was fixed
+    Repaired { action: String },
+    /// Nothing to do: the violation no longer holds (e.g. repaired earlier)
+    AlreadyConsistent,
+    /// No safe automatic fix; needs a human
+    ManualRequired { reason: String },
+}
+
 /// Row type for multiple subscriptions violation
 #[derive(Debug, sqlx::FromRow)]
 struct MultipleSubsRow {
@@ -125,6 +149,34 @@ struct MissingStripeCustomerRow {
     subscription_tier: String,
 }
 
+/// Row type for stuck downgrade claim violation
+#[derive(Debug, sqlx::FromRow)]
+struct StuckDowngradeClaimRow {
+    org_id: Uuid,
+    scheduled_downgrade_tier: Option<String>,
+    scheduled_downgrade_claimed_at: Option<OffsetDateTime>,
+}
+
+/// Orgs whose tier doesn't match their live subscription's price.
+/// Note: Price IDs contain tier names (e.g., "price_xxx_pro_monthly")
+const TIER_MISMATCH_SELECT: &str = r#"
+    SELECT
+        o.id as org_id,
+        o.name as org_name,
+        o.subscription_tier as db_tier,
+        s.stripe_price_id
+    FROM organizations o
+    JOIN subscriptions s ON s.org_id = o.id
+    WHERE s.status IN ('active', 'trialing', 'past_due')
+      AND o.subscription_tier != 'enterprise'  -- Enterprise can have custom prices
+      AND o.subscription_tier != 'free'        -- Free tier shouldn't have subscription
+      AND NOT (
+          (o.subscription_tier = 'pro' AND s.stripe_price_id ILIKE '%pro%')
+          OR (o.subscription_tier = 'team' AND s.stripe_price_id ILIKE '%team%')
+          OR (o.subscription_tier = 'starter' AND s.stripe_price_id ILIKE '%starter%')
+      )
+"#;
+
 /// Service for running billing invariant checks
 pub struct InvariantChecker {
     pool: PgPool,
@@ -147,8 +199,9 @@ impl InvariantChecker {
         violations.extend(self.check_tier_changes_audited().await?);
         violations.extend(self.check_spend_cap_consistency().await?);
         violations.extend(self.check_stripe_customer_exists().await?);
+        violations.extend(self.check_downgrade_claim_not_stuck().await?);
 
-        let checks_run = 6;
+        let checks_run = 7;
         let checks_failed = violations
             .iter()
             .map(|v| &v.invariant)
@@ -206,28 +259,9 @@ impl InvariantChecker {
     /// customers may have wrong access or be charged incorrectly.
     async fn check_tier_matches_subscription(&self) -> BillingResult<Vec<InvariantViolation>> {
         // This query finds orgs where tier doesn't match subscription price
-        // Note: Price IDs contain tier names (e.g., "price_xxx_pro_monthly")
-        let rows: Vec<TierMismatchRow> = sqlx::query_as(
-            r#"
-            SELECT
-                o.id as org_id,
-                o.name as org_name,
-                o.subscription_tier as db_tier,
-                s.stripe_price_id
-            FROM organizations o
-            JOIN subscriptions s ON s.org_id = o.id
-            WHERE s.status IN ('active', 'trialing')
-              AND o.subscription_tier != 'enterprise'  -- Enterprise can have custom prices
-              AND o.subscription_tier != 'free'        -- Free tier shouldn't have subscription
-              AND NOT (
-                  (o.subscription_tier = 'pro' AND s.stripe_price_id ILIKE '%pro%')
-                  OR (o.subscription_tier = 'team' AND s.stripe_price_id ILIKE '%team%')
-                  OR (o.subscription_tier = 'starter' AND s.stripe_price_id ILIKE '%starter%')
-              )
-            "#,
-        )
-        .fetch_all(&self.pool)
-        .await?;
+        let rows: Vec<TierMismatchRow> = sqlx::query_as(TIER_MISMATCH_SELECT)
+            .fetch_all(&self.pool)
+            .await?;
 
         Ok(rows
             .into_iter()
@@ -355,9 +389,9 @@ impl InvariantChecker {
                 invariant: "spend_cap_consistency".to_string(),
                 org_ids: vec![row.org_id],
                 description: format!(
-                    "Organization is paused but spend (${:.2}) is under cap (${:.2})",
-                    row.current_period_spend_cents as f64 / 100.0,
-                    row.cap_amount_cents as f64 / 100.0
+                    "Organization is paused but spend ({}) is under cap ({})",
+                    Money::from_cents_usd(row.current_period_spend_cents),
+                    Money::from_cents_usd(row.cap_amount_cents)
                 ),
                 context: serde_json::json!({
                     "is_paused": row.is_paused,
@@ -410,6 +444,173 @@ impl InvariantChecker {
             .collect())
     }
 
+    /// Invariant 7: Scheduled downgrade claims don't outlive their processor
+    ///
+    /// `scheduled_downgrade_processing` is a lock taken while a downgrade is
+    /// applied. One left set (e.g. the process crashed) blocks the downgrade
+    /// from ever running.
+    async fn check_downgrade_claim_not_stuck(&self) -> BillingResult<Vec<InvariantViolation>> {
+        let rows: Vec<StuckDowngradeClaimRow> = sqlx::query_as(
+            r#"
+            SELECT org_id, scheduled_downgrade_tier, scheduled_downgrade_claimed_at
+            FROM subscriptions
+            WHERE scheduled_downgrade_processing = true
+              AND (scheduled_downgrade_claimed_at IS NULL
+                   OR scheduled_downgrade_claimed_at < NOW() - make_interval(mins => $1))
+            "#,
+        )
+        .bind(STALE_DOWNGRADE_CLAIM_MINUTES as i32)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| InvariantViolation {
+                invariant: "downgrade_claim_not_stuck".to_string(),
+                org_ids: vec![row.org_id],
+                description: format!(
+                    "Scheduled downgrade has been claimed for processing since {:?}",
+                    row.scheduled_downgrade_claimed_at
+                ),
+                context: serde_json::json!({
+                    "scheduled_downgrade_tier": row.scheduled_downgrade_tier,
+                    "claimed_at": row.scheduled_downgrade_claimed_at,
+                }),
+                severity: ViolationSeverity::Medium,
+            })
+            .collect())
+    }
+
+    /// Repair a violation if it has a safe automatic fix
+    ///
+    /// - `tier_matches_subscription`: re-sync the org's subscription from
+    ///   Stripe, then set the tier to the one configured for the live price
+    ///   (an audited system tier change)
+    /// - `downgrade_claim_not_stuck`: release the stale processing claim
+    ///
+    /// Repairs re-check the violation first, so running one twice is a no-op
+    /// (`AlreadyConsistent`). Each repair logs an `ADMIN_OVERRIDE` billing event.
+    pub async fn repair(
+        &self,
+        violation: &InvariantViolation,
+        subscriptions: &SubscriptionService,
+    ) -> BillingResult<RepairOutcome> {
+        self.repair_with(violation, |org_id| subscriptions.resync_tier(org_id))
+            .await
+    }
+
+    /// `repair` with the Stripe re-sync supplied by the caller
+    ///
+    /// `resync` returns false when the org has no Stripe subscription to sync.
+    async fn repair_with<F, Fut>(
+        &self,
+        violation: &InvariantViolation,
+        resync: F,
+    ) -> BillingResult<RepairOutcome>
+    where
+        F: FnOnce(Uuid) -> Fut,
+        Fut: std::future::Future<Output = BillingResult<bool>>,
+    {
+        let org_id = match violation.org_ids.as_slice() {
+            [org_id] => *org_id,
+            _ => {
+                return Ok(RepairOutcome::ManualRequired {
+                    reason: "Violation does not name exactly one organization".to_string(),
+                })
+            }
+        };
+
+        let outcome = match violation.invariant.as_str() {
+            "tier_matches_subscription" => {
+                if !self.has_tier_mismatch(org_id).await? {
+                    return Ok(RepairOutcome::AlreadyConsistent);
+                }
+                if !resync(org_id).await? {
+                    return Ok(RepairOutcome::ManualRequired {
+                        reason: "No Stripe subscription to re-sync from".to_string(),
+                    });
+                }
+                if self.has_tier_mismatch(org_id).await? {
+                    return Ok(RepairOutcome::ManualRequired {
+                        reason: "Tier still doesn't match the Stripe price after re-sync"
+                            .to_string(),
+                    });
+                }
+                RepairOutcome::Repaired {
+                    action: "resynced_subscription".to_string(),
+                }
+            }
+            "downgrade_claim_not_stuck" => {
+                let released = sqlx::query(
+                    r#"
+                    UPDATE subscriptions
+                    SET scheduled_downgrade_processing = false,
+                        scheduled_downgrade_claimed_at = NULL
+                    WHERE org_id = $1
+                      AND scheduled_downgrade_processing = true
+                      AND (scheduled_downgrade_claimed_at IS NULL
+                           OR scheduled_downgrade_claimed_at < NOW() - make_interval(mins => $2))
+                    "#,
+                )
+                .bind(org_id)
+                .bind(STALE_DOWNGRADE_CLAIM_MINUTES as i32)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+
+                if released == 0 {
+                    return Ok(RepairOutcome::AlreadyConsistent);
+                }
+                RepairOutcome::Repaired {
+                    action: "released_downgrade_claim".to_string(),
+                }
+            }
+            other => {
+                return Ok(RepairOutcome::ManualRequired {
+                    reason: format!("No automatic repair for '{}'", other),
+                })
+            }
+        };
+
+        if let RepairOutcome::Repaired { action } = &outcome {
+            tracing::info!(
+                org_id = %org_id,
+                invariant = %violation.invariant,
+                action = %action,
+                "Repaired billing invariant violation"
+            );
+
+            let event_logger = BillingEventLogger::new(self.pool.clone());
+            if let Err(e) = event_logger
+                .log_event(
+                    BillingEventBuilder::new(org_id, BillingEventType::AdminOverride)
+                        .subtype("invariant_repair")
+                        .data(serde_json::json!({
+                            "invariant": violation.invariant,
+                            "action": action,
+                            "context": violation.context,
+                        }))
+                        .actor_type(ActorType::System),
+                )
+                .await
+            {
+                tracing::warn!(org_id = %org_id, error = %e, "Failed to log invariant repair event");
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Whether the org currently violates `tier_matches_subscription`
+    async fn has_tier_mismatch(&self, org_id: Uuid) -> BillingResult<bool> {
+        let row: Option<TierMismatchRow> =
+            sqlx::query_as(&format!("{} AND o.id = $1", TIER_MISMATCH_SELECT))
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.is_some())
+    }
+
     /// Run a single invariant check by name
     pub async fn run_check(&self, name: &str) -> BillingResult<Vec<InvariantViolation>> {
         match name {
@@ -419,6 +620,7 @@ impl InvariantChecker {
             "tier_changes_audited" => self.check_tier_changes_audited().await,
             "spend_cap_consistency" => self.check_spend_cap_consistency().await,
             "stripe_customer_exists" => self.check_stripe_customer_exists().await,
+            "downgrade_claim_not_stuck" => self.check_downgrade_claim_not_stuck().await,
             _ => Ok(vec![]),
         }
     }
@@ -432,6 +634,7 @@ impl InvariantChecker {
             "tier_changes_audited",
             "spend_cap_consistency",
             "stripe_customer_exists",
+            "downgrade_claim_not_stuck",
         ]
     }
 }
@@ -439,6 +642,8 @@ impl InvariantChecker {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::{StripeClient, StripeConfig};
+    use stripe::Subscription;
 
     #[test]
     fn test_violation_severity_display() {
@@ -451,8 +656,156 @@ mod tests {
     #[test]
     fn test_available_checks() {
         let checks = InvariantChecker::available_checks();
-        assert_eq!(checks.len(), 6);
+        assert_eq!(checks.len(), 7);
         assert!(checks.contains(&"single_active_subscription"));
         assert!(checks.contains(&"tier_matches_subscription"));
     }
+
+    fn violation(invariant: &str, org_id: Uuid) -> InvariantViolation {
+        InvariantViolation {
+            invariant: invariant.to_string(),
+            org_ids: vec![org_id],
+            description: String::new(),
+            context: serde_json::json!({}),
+            severity: ViolationSeverity::High,
+        }
+    }
+
+    async fn insert_org_with_subscription(pool: &PgPool, tier: &str, price_id: &str) -> Uuid {
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Invariant Org', $2, $3)",
+        )
+        .bind(org_id)
+        .bind(format!("invariant-{}", org_id))
+        .bind(tier)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (id, org_id, stripe_subscription_id, stripe_price_id, status)
+            VALUES ($1, $2, $3, $4, 'active')
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(org_id)
+        .bind(format!("sub_{}", org_id.simple()))
+        .bind(price_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        org_id
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_repair_releases_stuck_downgrade_claim_once() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let checker = InvariantChecker::new(pool.clone());
+
+        let org_id = insert_org_with_subscription(&pool, "team", "price_team_monthly").await;
+        sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET scheduled_downgrade_tier = 'pro',
+                scheduled_downgrade_processing = true,
+                scheduled_downgrade_claimed_at = NOW() - interval '3 hours'
+            WHERE org_id = $1
+            "#,
+        )
+        .bind(org_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let stuck = violation("downgrade_claim_not_stuck", org_id);
+        let no_resync = |_| async { panic!("claim repair must not touch Stripe") };
+        assert_eq!(
+            checker.repair_with(&stuck, no_resync).await.unwrap(),
+            RepairOutcome::Repaired {
+                action: "released_downgrade_claim".to_string()
+            }
+        );
+        let processing: bool = sqlx::query_scalar(
+            "SELECT scheduled_downgrade_processing FROM subscriptions WHERE org_id = $1",
+        )
+        .bind(org_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(!processing);
+
+        // Idempotent: nothing left to release
+        assert_eq!(
+            checker.repair_with(&stuck, no_resync).await.unwrap(),
+            RepairOutcome::AlreadyConsistent
+        );
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_repair_resyncs_tier_mismatch_and_leaves_orphans_manual() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let checker = InvariantChecker::new(pool.clone());
+        let subscriptions =
+            SubscriptionService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone());
+
+        // Org says pro, Stripe is charging team
+        let org_id = insert_org_with_subscription(&pool, "pro", "price_team").await;
+        let mismatch = violation("tier_matches_subscription", org_id);
+        let live = Subscription {
+            id: format!("sub_{}", org_id.simple()).parse().unwrap(),
+            status: stripe::SubscriptionStatus::Active,
+            items: stripe::List {
+                data: vec![stripe::SubscriptionItem {
+                    price: Some(stripe::Price {
+                        id: "price_team".parse().unwrap(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            checker
+                .repair_with(&mismatch, |org_id| async move {
+                    subscriptions.apply_live_tier(org_id, &live).await?;
+                    Ok(true)
+                })
+                .await
+                .unwrap(),
+            RepairOutcome::Repaired {
+                action: "resynced_subscription".to_string()
+            }
+        );
+        let tier: String =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(tier, "team");
+        assert_eq!(
+            checker
+                .repair_with(&mismatch, |_| async { panic!("already consistent") })
+                .await
+                .unwrap(),
+            RepairOutcome::AlreadyConsistent
+        );
+
+        let orphan = violation("single_active_subscription", org_id);
+        assert!(matches!(
+            checker
+                .repair_with(&orphan, |_| async { Ok(true) })
+                .await
+                .unwrap(),
+            RepairOutcome::ManualRequired { .. }
+        ));
+    }
 }
@@ -24,56 +24,78 @@
 //! - **Email Notifications**: Payment failed, trial ending, spend cap alerts, etc.
 //! - **Webhooks**: Handle Stripe events
 
+pub mod account_status;
 pub mod addons;
 pub mod checkout;
 pub mod client;
 pub mod customer;
+pub mod db;
+pub mod discounts;
 pub mod email;
 pub mod entitlement;
 pub mod error;
 pub mod events;
+pub mod grace_period;
 pub mod history;
 pub mod instant_charge;
 pub mod invariants;
 pub mod member_suspension;
 pub mod metered;
 pub mod overage;
+pub mod owners;
 pub mod portal;
+pub mod quota;
 pub mod rate_limit;
 pub mod refund;
 pub mod spend_cap;
 pub mod subscriptions;
 pub mod tax;
+pub mod trial_payment;
 pub mod usage;
 pub mod webhooks;
 
 #[cfg(test)]
 mod edge_case_tests;
 
+// Account Status
+pub use account_status::{
+    get_invoice_grace_config, grace_period_block_reason, grace_period_days_for_tier, AccountStatus,
+    AccountStatusFlags, AccountStatusService, InvoiceGraceConfig, DEFAULT_DECLINE_RETRY_GRACE_DAYS,
+    DEFAULT_INVOICE_GRACE_PERIOD_DAYS,
+};
+
 // Add-ons
 pub use addons::{
-    AddonCategory, AddonInfo, AddonQuantities, AddonService, AddonType, AddonsListResponse,
-    EnableAddonRequest, SubscriptionAddon,
+    AddonCategory, AddonInfo, AddonOnlyCleanup, AddonQuantities, AddonRemovalTiming, AddonService,
+    AddonType, AddonsListResponse, EnableAddonRequest, SubscriptionAddon,
 };
 
 // Checkout
-pub use checkout::{BillingInterval, CheckoutResponse, CheckoutService};
+pub use checkout::{
+    AppliedCoupon, BillingInterval, CheckoutResponse, CheckoutService, PromoCodeDiscount,
+};
 
 // Client
-pub use client::{PriceIds, StripeClient, StripeConfig};
+pub use client::{idempotency_key, PriceIds, StripeClient, StripeConfig};
 
 // Customer
 pub use customer::CustomerService;
 
+// Discounts
+pub use discounts::{
+    Discount, DiscountKind, DiscountResolver, DiscountSource, ResolvedDiscounts, StackingRules,
+};
+
 // Email
-pub use email::{BillingEmailService, EmailConfig};
+pub use email::{BillingEmailService, EmailConfig, EmailMode};
 
 // Error
 pub use error::{BillingError, BillingResult};
 
 // Metered
 pub use metered::{
-    MeteredBillingService, MeteredSubscription, UsageReportResponse, UsageReportResult,
+    ItemOutcome, ItemReportResult, MeteredBillingService, MeteredSubscription, PartialReportPolicy,
+    UsageReportResponse, UsageReportResult,
 };
 
 // Instant Charge
@@ -83,52 +105,81 @@ pub use instant_charge::{
 
 // Overage
 pub use overage::{
-    AccumulatedOverage, OverageCharge, OverageRates, OverageService, OverageSummary, PayNowResult,
+    plan_charge_dedupe, AccumulatedOverage, ChargeDedupeSummary, ChargeMerge, InvoiceNowResult,
+    OverageBillingPeriod, OverageCharge, OverageDiscrepancy, OverageRates,
+    OverageRecalculationSummary, OverageRecompute, OverageService, OverageSummary,
+    PastDueOveragePolicy, PayNowResult,
 };
 
+// Owners
+pub use owners::{get_primary_owner, list_org_owners, primary_owner, OrgOwner};
+
 // Spend Cap
 pub use spend_cap::{
-    SpendCap, SpendCapCheckResult, SpendCapRequest, SpendCapService, SpendCapStatus,
-    NOTIFICATION_THRESHOLDS,
+    attributed_spend_cents, thresholds_reached, MemberOverCap, MemberSpendCap, SpendCap,
+    SpendCapCheckResult, SpendCapMode, SpendCapRequest, SpendCapService, SpendCapStatus, Threshold,
+    MEMBER_SPEND_CAP_SUSPENSION_REASON, NOTIFICATION_THRESHOLDS,
 };
 
 // Portal
-pub use portal::{PortalResponse, PortalService};
+pub use portal::{PortalLinkToken, PortalResponse, PortalService, PORTAL_LINK_TTL};
+
+// Quota
+pub use quota::{QuotaWarning, QuotaWarningService, QUOTA_WARNING_HEADER};
 
 // Rate Limit
 pub use rate_limit::{RateLimitConfig, RateLimitResult, RateLimiter};
 
 // Refund
-pub use refund::{AdminRefund, RefundResult, RefundService, RefundableCharge};
+pub use refund::{
+    allocate_refund, check_refund_amount, AdminRefund, RefundAllocation, RefundReason,
+    RefundResult, RefundService, RefundableCharge,
+};
 
 // Subscriptions
 pub use subscriptions::{
-    AdminTierChangeParams, AdminTierChangeResult, CancelledSubscriptionInfo, Plan,
-    ProrationPreview, ReactivationResult, ScheduledDowngrade, SubscriptionPauseResult,
-    SubscriptionPauseStatus, SubscriptionResumeResult, SubscriptionService,
+    is_entitled_status, subscription_mrr, subscription_period, tier_change_history_cursor,
+};
+pub use subscriptions::{
+    AdminTierChangeParams, AdminTierChangeResult, AuditBackfillSummary, CancelledSubscriptionInfo,
+    ChangeConfirmation, FieldChange, InvoiceCustomField, InvoiceDetails, Plan, ProrationPreview,
+    ProrationRounding, ReactivationResult, RepairAction, ScheduledDowngrade, SubscriptionDiff,
+    SubscriptionKind, SubscriptionPauseResult, SubscriptionPauseStatus, SubscriptionResumeResult,
+    SubscriptionService, TierChangeAuditRecord, TierChangeHistoryCursor, TierChangeImpact,
+    TierPriceFix, UpcomingCharge, UpcomingChargeCursor, UpcomingChargesPage,
 };
 
 // Usage
-pub use usage::{BillingPeriodUsage, UsageEvent, UsageMeter, UsageSummary};
+pub use usage::{
+    BillingPeriodUsage, Granularity, UsageEvent, UsageMeter, UsagePoint, UsageSummary,
+};
 
 // Webhooks
-pub use webhooks::{WebhookEventRecord, WebhookHandler, WebhookReplayResult};
+pub use webhooks::{
+    clock_skew_secs, invoice_billing_reason, ClockSkewCheck, WebhookConfig, WebhookEventRecord,
+    WebhookHandler, WebhookReplayResult,
+};
 
 // History
-pub use history::{BillingHistoryRecord, BillingHistoryService, BillingSummary};
+pub use history::{
+    BillingHistoryRecord, BillingHistoryService, BillingSummary, MetricDelta, PeriodComparison,
+};
 
 // Tax
-pub use tax::{TaxBreakdown, TaxConfig, TaxId, TaxIdType, TaxService, TaxSummary};
+pub use tax::{
+    compute_tax_amount, currency_decimals, normalize_tax_id, TaxBreakdown, TaxConfig, TaxId,
+    TaxIdType, TaxLineItem, TaxRounding, TaxService, TaxStrategy, TaxSummary,
+};
 
 // Entitlement
 pub use entitlement::{
     Entitlement, EntitlementFeatures, EntitlementService, EntitlementSource, EntitlementState,
-    RawBillingData,
+    FeatureOverride, RawBillingData, TierEntitlements,
 };
 
 // Invariants
 pub use invariants::{
-    InvariantCheckSummary, InvariantChecker, InvariantViolation, ViolationSeverity,
+    InvariantCheckSummary, InvariantChecker, InvariantViolation, RepairOutcome, ViolationSeverity,
 };
 
 // Events
@@ -136,29 +187,45 @@ pub use events::{
     ActorType, BillingEvent, BillingEventBuilder, BillingEventLogger, BillingEventType,
 };
 
+// Grace Period
+pub use grace_period::{
+    GracePeriodAction, GracePeriodEnforcement, GracePeriodOrgAction, GracePeriodService,
+};
+
 // Member Suspension
 pub use member_suspension::{
-    AffectedMembersInfo, MemberStatusSummary, MemberSuspensionService, MemberToSuspend,
-    SuspensionResult,
+    get_suspension_grace_period, suspension_due, AffectedMembersInfo, MemberStatusSummary,
+    MemberSuspensionService, MemberToSuspend, PendingSuspension, SuspensionResult,
+};
+
+// Trial Payment Method Enforcement
+pub use trial_payment::{
+    get_trial_payment_schedule, TrialPaymentAction, TrialPaymentEnforcement,
+    TrialPaymentEscalation, TrialPaymentOrgAction, TrialPaymentSchedule, TrialPaymentService,
+    TRIAL_PAYMENT_BLOCK_REASON,
 };
 
 use sqlx::PgPool;
 
 /// Main billing service that combines all billing functionality
 pub struct BillingService {
+    pub account: AccountStatusService,
     pub addons: AddonService,
     pub checkout: CheckoutService,
     pub customer: CustomerService,
     pub email: BillingEmailService,
+    pub grace_period: GracePeriodService,
     pub instant_charge: InstantChargeService,
     pub member_suspension: MemberSuspensionService,
     pub metered: MeteredBillingService,
     pub overage: OverageService,
     pub portal: PortalService,
+    pub quota: QuotaWarningService,
     pub rate_limiter: RateLimiter,
     pub refund: RefundService,
     pub spend_cap: SpendCapService,
     pub subscriptions: SubscriptionService,
+    pub trial_payment: TrialPaymentService,
     pub usage: UsageMeter,
     pub webhooks: WebhookHandler,
 }
@@ -170,10 +237,12 @@ impl BillingService {
         let email_service = BillingEmailService::from_env();
 
         Ok(Self {
+            account: AccountStatusService::new(pool.clone()),
             addons: AddonService::new(stripe.clone(), pool.clone()),
             checkout: CheckoutService::new(stripe.clone(), pool.clone()),
             customer: CustomerService::new(stripe.clone(), pool.clone()),
             email: email_service.clone(),
+            grace_period: GracePeriodService::new(pool.clone()),
             instant_charge: InstantChargeService::new(
                 stripe.clone(),
                 pool.clone(),
@@ -182,11 +251,14 @@ impl BillingService {
             member_suspension: MemberSuspensionService::new(pool.clone()),
             metered: MeteredBillingService::new(stripe.clone(), pool.clone()),
             overage: OverageService::new(stripe.clone(), pool.clone()),
-            portal: PortalService::new(stripe.clone()),
+            portal: PortalService::new(stripe.clone(), pool.clone()),
+            quota: QuotaWarningService::new(pool.clone(), email_service.clone()),
             rate_limiter: RateLimiter::new_in_memory(),
-            refund: RefundService::new(stripe.clone(), pool.clone()),
-            spend_cap: SpendCapService::new(pool.clone(), email_service.clone()),
+            refund: RefundService::new(stripe.clone(), pool.clone(), email_service.clone()),
+            spend_cap: SpendCapService::new(pool.clone(), email_service.clone())
+                .with_tax_from_env(TaxService::new(stripe.clone(), pool.clone())),
             subscriptions: SubscriptionService::new(stripe.clone(), pool.clone()),
+            trial_payment: TrialPaymentService::new(pool.clone()),
             usage: UsageMeter::new(pool.clone()),
             webhooks: WebhookHandler::new(stripe, pool, email_service),
         })
@@ -198,10 +270,12 @@ impl BillingService {
         let email_service = BillingEmailService::from_env();
 
         Self {
+            account: AccountStatusService::new(pool.clone()),
             addons: AddonService::new(stripe.clone(), pool.clone()),
             checkout: CheckoutService::new(stripe.clone(), pool.clone()),
             customer: CustomerService::new(stripe.clone(), pool.clone()),
             email: email_service.clone(),
+            grace_period: GracePeriodService::new(pool.clone()),
             instant_charge: InstantChargeService::new(
                 stripe.clone(),
                 pool.clone(),
@@ -210,13 +284,23 @@ impl BillingService {
             member_suspension: MemberSuspensionService::new(pool.clone()),
             metered: MeteredBillingService::new(stripe.clone(), pool.clone()),
             overage: OverageService::new(stripe.clone(), pool.clone()),
-            portal: PortalService::new(stripe.clone()),
+            portal: PortalService::new(stripe.clone(), pool.clone()),
+            quota: QuotaWarningService::new(pool.clone(), email_service.clone()),
             rate_limiter: RateLimiter::new_in_memory(),
-            refund: RefundService::new(stripe.clone(), pool.clone()),
-            spend_cap: SpendCapService::new(pool.clone(), email_service.clone()),
+            refund: RefundService::new(stripe.clone(), pool.clone(), email_service.clone()),
+            spend_cap: SpendCapService::new(pool.clone(), email_service.clone())
+                .with_tax_from_env(TaxService::new(stripe.clone(), pool.clone())),
             subscriptions: SubscriptionService::new(stripe.clone(), pool.clone()),
+            trial_payment: TrialPaymentService::new(pool.clone()),
             usage: UsageMeter::new(pool.clone()),
             webhooks: WebhookHandler::new(stripe, pool, email_service),
         }
     }
+
+    /// Composite "good standing" check for feature gating
+    ///
+    /// See the `account_status` module for how overlapping conditions are ranked.
+    pub async fn account_status(&self, org_id: uuid::Uuid) -> BillingResult<AccountStatus> {
+        self.account.get_status(org_id).await
+    }
 }
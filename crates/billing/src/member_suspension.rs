@@ -7,15 +7,43 @@
 //! - Suspends excess members (newest first, preserving owner)
 //! - Provides read-only access for suspended members
 //! - Allows owner to unsuspend members when slots are available
+//! - Gives orgs a grace period to remove excess members before suspension
+//!
+//! ## Configuration
+//!
+//! - `MEMBER_SUSPENSION_GRACE_DAYS`: days an org has after a downgrade before
+//!   excess members are suspended (default: 7, 0 = suspend immediately)
+
+use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
 use crate::error::{BillingError, BillingResult};
 use plexmcp_shared::types::SubscriptionTier;
 
+/// Default grace period before excess members are suspended
+const DEFAULT_GRACE_DAYS: i64 = 7;
+
+/// Get configured grace period before excess members are suspended
+pub fn get_suspension_grace_period() -> Duration {
+    static DAYS: OnceLock<i64> = OnceLock::new();
+    Duration::days(*DAYS.get_or_init(|| {
+        std::env::var("MEMBER_SUSPENSION_GRACE_DAYS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|d| (0..=365).contains(d))
+            .unwrap_or(DEFAULT_GRACE_DAYS)
+    }))
+}
+
+/// Whether a pending suspension's grace period has ended
+pub fn suspension_due(due_at: Option<OffsetDateTime>, now: OffsetDateTime) -> bool {
+    due_at.is_some_and(|due_at| due_at <= now)
+}
+
 /// Information about a member who will be suspended
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberToSuspend {
@@ -35,6 +63,18 @@ pub struct SuspensionResult {
     pub reason: String,
 }
 
+/// Excess members waiting out the grace period after a downgrade
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSuspension {
+    pub org_id: Uuid,
+    /// When the worker will suspend members still over the limit
+    pub suspend_after: OffsetDateTime,
+    /// Members who would be suspended if nothing changes
+    pub members: Vec<MemberToSuspend>,
+    /// `false` if the grace period was already running
+    pub newly_scheduled: bool,
+}
+
 /// Summary of organization member status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemberStatusSummary {
@@ -270,6 +310,119 @@ impl MemberSuspensionService {
         })
     }
 
+    /// Start the grace period for members beyond the new tier's limit
+    ///
+    /// Returns `None` (and clears any pending suspension) when the org is
+    /// within the limit. An already running grace period is not extended.
+    pub async fn schedule_excess_member_suspension(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+        reason: &str,
+        grace: Duration,
+    ) -> BillingResult<Option<PendingSuspension>> {
+        let Some(info) = self.get_affected_members_info(org_id, new_tier).await? else {
+            self.clear_pending_suspension(org_id).await?;
+            return Ok(None);
+        };
+
+        let scheduled = sqlx::query(
+            r#"
+            UPDATE organizations
+            SET member_suspension_due_at = $2,
+                member_suspension_reason = $3
+            WHERE id = $1 AND member_suspension_due_at IS NULL
+            "#,
+        )
+        .bind(org_id)
+        .bind(OffsetDateTime::now_utc() + grace)
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+
+        let suspend_after: Option<OffsetDateTime> =
+            sqlx::query_scalar("SELECT member_suspension_due_at FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .flatten();
+        let suspend_after = suspend_after
+            .ok_or_else(|| BillingError::NotFound(format!("Organization not found: {}", org_id)))?;
+
+        tracing::info!(
+            org_id = %org_id,
+            new_tier = new_tier,
+            excess_count = info.excess_count,
+            suspend_after = %suspend_after,
+            "Scheduled suspension of excess members"
+        );
+
+        Ok(Some(PendingSuspension {
+            org_id,
+            suspend_after,
+            members: info.members_to_suspend,
+            newly_scheduled: scheduled.rows_affected() > 0,
+        }))
+    }
+
+    /// Drop a pending suspension (e.g. after the org upgrades again)
+    pub async fn clear_pending_suspension(&self, org_id: Uuid) -> BillingResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE organizations
+            SET member_suspension_due_at = NULL,
+                member_suspension_reason = NULL
+            WHERE id = $1 AND member_suspension_due_at IS NOT NULL
+            "#,
+        )
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Suspend excess members for orgs whose grace period has ended
+    ///
+    /// The limit is re-checked against the org's current tier, so members
+    /// removed (or an upgrade made) during the grace period are respected.
+    /// Orgs that fail stay pending and are retried on the next run.
+    pub async fn process_due_suspensions(&self) -> BillingResult<Vec<SuspensionResult>> {
+        let pending: Vec<(Uuid, String, Option<OffsetDateTime>, Option<String>)> = sqlx::query_as(
+            r#"
+                SELECT id, subscription_tier, member_suspension_due_at, member_suspension_reason
+                FROM organizations
+                WHERE member_suspension_due_at IS NOT NULL
+                "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let now = OffsetDateTime::now_utc();
+        let mut results = Vec::new();
+        for (org_id, tier, due_at, reason) in pending {
+            if !suspension_due(due_at, now) {
+                continue;
+            }
+            let reason = reason.unwrap_or_else(|| "plan_downgrade".to_string());
+            match self.suspend_excess_members(org_id, &tier, &reason).await {
+                Ok(result) => {
+                    self.clear_pending_suspension(org_id).await?;
+                    results.push(result);
+                }
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to suspend excess members after grace period"
+                    );
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
     /// Unsuspend a specific member (owner action)
     pub async fn unsuspend_member(&self, org_id: Uuid, member_id: Uuid) -> BillingResult<()> {
         // First check if org has room for another active member
@@ -329,6 +482,73 @@ impl MemberSuspensionService {
         Ok(())
     }
 
+    /// Suspend a single member, e.g. for going over their spend sub-cap
+    ///
+    /// Owners are never suspended. Returns true if an active member was suspended.
+    pub async fn suspend_member(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        reason: &str,
+    ) -> BillingResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organization_members
+            SET status = 'suspended',
+                suspended_at = NOW(),
+                suspended_reason = $3
+            WHERE org_id = $1 AND user_id = $2 AND status = 'active' AND role <> 'owner'
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+
+        let suspended = result.rows_affected() > 0;
+        if suspended {
+            tracing::info!(
+                org_id = %org_id,
+                user_id = %user_id,
+                reason = reason,
+                "Suspended member"
+            );
+        }
+        Ok(suspended)
+    }
+
+    /// Unsuspend a member only if they were suspended for `reason`
+    ///
+    /// Goes through `unsuspend_member`, so the team limit still applies.
+    /// Returns false if the member isn't suspended for that reason.
+    pub async fn restore_member(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        reason: &str,
+    ) -> BillingResult<bool> {
+        let member_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id
+            FROM organization_members
+            WHERE org_id = $1 AND user_id = $2
+              AND status = 'suspended' AND suspended_reason = $3
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(reason)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(member_id) = member_id else {
+            return Ok(false);
+        };
+        self.unsuspend_member(org_id, member_id).await?;
+        Ok(true)
+    }
+
     /// Check if a member is suspended
     pub async fn is_member_suspended(&self, org_id: Uuid, user_id: Uuid) -> BillingResult<bool> {
         let result: Option<(String,)> = sqlx::query_as(
@@ -382,3 +602,30 @@ impl MemberSuspensionService {
         Ok(suspended)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suspension_fires_only_after_grace_period() {
+        let downgraded_at = OffsetDateTime::from_unix_timestamp(1_767_225_600).unwrap();
+        let due_at = downgraded_at + Duration::days(7);
+
+        assert!(!suspension_due(Some(due_at), downgraded_at));
+        assert!(!suspension_due(Some(due_at), due_at - Duration::seconds(1)));
+        assert!(suspension_due(Some(due_at), due_at));
+        assert!(suspension_due(Some(due_at), due_at + Duration::days(1)));
+    }
+
+    #[test]
+    fn test_no_pending_suspension_never_due() {
+        assert!(!suspension_due(None, OffsetDateTime::now_utc()));
+    }
+
+    #[test]
+    fn test_default_grace_period() {
+        let grace = get_suspension_grace_period();
+        assert!(grace >= Duration::ZERO && grace <= Duration::days(365));
+    }
+}
@@ -3,7 +3,11 @@
 //! Reports overage usage to Stripe for Pro and Team tier subscriptions.
 //! Usage is reported in units of 1,000 API calls over the included limit.
 
+use std::future::Future;
+use std::sync::OnceLock;
+
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use stripe::{CreateUsageRecord, SubscriptionItemId, UsageRecord, UsageRecordAction};
 use time::OffsetDateTime;
@@ -11,11 +15,75 @@ use uuid::Uuid;
 
 use crate::client::StripeClient;
 use crate::error::BillingResult;
+use crate::subscriptions::run_bounded;
 
 /// Included API calls per tier (matching types.rs source of truth)
 const PRO_INCLUDED_CALLS: i64 = 50_000;
 const TEAM_INCLUDED_CALLS: i64 = 250_000; // Corrected: was 200_000
 
+/// Default number of orgs reported to Stripe at once
+const DEFAULT_REPORT_CONCURRENCY: usize = 10;
+
+/// Get configured report concurrency (`METERED_REPORT_CONCURRENCY`)
+fn get_report_concurrency() -> usize {
+    static CONCURRENCY: OnceLock<usize> = OnceLock::new();
+    *CONCURRENCY.get_or_init(|| {
+        std::env::var("METERED_REPORT_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_REPORT_CONCURRENCY)
+    })
+}
+
+/// What to do when some items of a subscription report and others fail
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartialReportPolicy {
+    /// Keep the items that reached Stripe; retry only the failed ones next cycle
+    #[default]
+    RetryFailed,
+    /// Release every item's claim, so the next cycle resends the whole subscription
+    ResendAll,
+}
+
+impl PartialReportPolicy {
+    /// Load from `METERED_PARTIAL_REPORT_POLICY` (`retry_failed` or `resend_all`)
+    pub fn from_env() -> Self {
+        match std::env::var("METERED_PARTIAL_REPORT_POLICY") {
+            Ok(v) if v.trim().eq_ignore_ascii_case("resend_all") => Self::ResendAll,
+            Ok(v) if !v.trim().eq_ignore_ascii_case("retry_failed") => {
+                tracing::warn!(
+                    policy = %v,
+                    "Unknown METERED_PARTIAL_REPORT_POLICY, retrying failed items only"
+                );
+                Self::RetryFailed
+            }
+            _ => Self::RetryFailed,
+        }
+    }
+}
+
+/// Outcome of reporting one subscription item in a cycle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ItemOutcome {
+    /// Sent to Stripe this cycle
+    Reported,
+    /// The same quantity was already reported; nothing was sent
+    AlreadyReported,
+    /// Stripe rejected or never received the report; retried next cycle
+    Failed { error: String },
+}
+
+/// Per-item result of a usage report
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemReportResult {
+    pub metered_item_id: String,
+    pub overage_units: i64,
+    pub outcome: ItemOutcome,
+}
+
 /// Result of a usage report operation
 #[derive(Debug, Clone, Serialize)]
 pub enum UsageReportResult {
@@ -24,6 +92,7 @@ pub enum UsageReportResult {
         org_id: Uuid,
         total_usage: i64,
         included_limit: i64,
+        items: Vec<ItemReportResult>,
     },
     /// Usage was reported to Stripe
     Reported {
@@ -31,11 +100,167 @@ pub enum UsageReportResult {
         total_usage: i64,
         included_limit: i64,
         overage_units: i64,
+        items: Vec<ItemReportResult>,
+    },
+    /// Some items reached Stripe and others failed; `items` says which
+    PartiallyReported {
+        org_id: Uuid,
+        total_usage: i64,
+        included_limit: i64,
+        items: Vec<ItemReportResult>,
     },
     /// Error during reporting
     Error { org_id: Uuid, error: String },
     /// Subscription has no metered item (Free or Enterprise tier)
     NoMeteredItem { org_id: Uuid },
+    /// The same quantity was already reported for this billing period
+    AlreadyReported { org_id: Uuid },
+}
+
+/// Hash identifying a cumulative quantity reported for a metered item
+fn reported_quantity_hash(metered_item_id: &str, overage_units: i64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}|{}", metered_item_id, overage_units));
+    hex::encode(hasher.finalize())
+}
+
+/// A cumulative quantity to report for one subscription item in a billing period
+struct UsageClaim<'a> {
+    org_id: Uuid,
+    period_start: OffsetDateTime,
+    metered_item_id: &'a str,
+    overage_units: i64,
+}
+
+impl UsageClaim<'_> {
+    fn quantity_hash(&self) -> String {
+        reported_quantity_hash(self.metered_item_id, self.overage_units)
+    }
+}
+
+/// Outcome of reporting one subscription item
+enum ItemReport<T, E> {
+    /// The quantity is already in `usage_report_log`; nothing was sent
+    AlreadyReported,
+    /// The quantity was sent; an error means the claim was released
+    Sent(Result<T, E>),
+}
+
+/// Send a usage report for one subscription item at most once per quantity
+///
+/// The item is claimed in `usage_report_log` before `send` runs and the claim
+/// is released if `send` fails, so the next cycle retries only the items
+/// that did not make it to Stripe.
+async fn report_item_once<T, E, F, Fut>(
+    pool: &PgPool,
+    claim: &UsageClaim<'_>,
+    send: F,
+) -> BillingResult<ItemReport<T, E>>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    if !claim_usage_report(pool, claim).await? {
+        return Ok(ItemReport::AlreadyReported);
+    }
+
+    let sent = send().await;
+    if sent.is_err() {
+        if let Err(e) = release_usage_report(pool, claim).await {
+            tracing::warn!(
+                org_id = %claim.org_id,
+                metered_item_id = %claim.metered_item_id,
+                error = %e,
+                "Failed to release usage report claim"
+            );
+        }
+    }
+    Ok(ItemReport::Sent(sent))
+}
+
+/// Report each item of a subscription once, returning a result per item
+///
+/// When some items fail, `policy` decides whether the items that reached
+/// Stripe keep their claims (only the failed ones are retried) or are
+/// released so the whole subscription is resent next cycle.
+async fn report_items<F, Fut>(
+    pool: &PgPool,
+    claims: &[UsageClaim<'_>],
+    policy: PartialReportPolicy,
+    send: F,
+) -> BillingResult<Vec<ItemReportResult>>
+where
+    F: Fn(String, i64) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut results = Vec::with_capacity(claims.len());
+    for claim in claims {
+        let outcome = match report_item_once(pool, claim, || {
+            send(claim.metered_item_id.to_string(), claim.overage_units)
+        })
+        .await?
+        {
+            ItemReport::AlreadyReported => ItemOutcome::AlreadyReported,
+            ItemReport::Sent(Ok(())) => ItemOutcome::Reported,
+            ItemReport::Sent(Err(error)) => ItemOutcome::Failed { error },
+        };
+        results.push(ItemReportResult {
+            metered_item_id: claim.metered_item_id.to_string(),
+            overage_units: claim.overage_units,
+            outcome,
+        });
+    }
+
+    let any_failed = results
+        .iter()
+        .any(|r| matches!(r.outcome, ItemOutcome::Failed { .. }));
+    if any_failed && policy == PartialReportPolicy::ResendAll {
+        for (claim, result) in claims.iter().zip(&results) {
+            if !matches!(result.outcome, ItemOutcome::Failed { .. }) {
+                release_usage_report(pool, claim).await?;
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Record that a quantity is being reported; `false` if it already was
+async fn claim_usage_report(pool: &PgPool, claim: &UsageClaim<'_>) -> BillingResult<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO usage_report_log (
+            org_id, period_start, metered_item_id, reported_quantity_hash, overage_units
+        ) VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (org_id, period_start, reported_quantity_hash) DO NOTHING
+        "#,
+    )
+    .bind(claim.org_id)
+    .bind(claim.period_start)
+    .bind(claim.metered_item_id)
+    .bind(claim.quantity_hash())
+    .bind(claim.overage_units)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() == 1)
+}
+
+/// Remove a claim whose report to Stripe failed
+async fn release_usage_report(pool: &PgPool, claim: &UsageClaim<'_>) -> BillingResult<()> {
+    sqlx::query(
+        r#"
+        DELETE FROM usage_report_log
+        WHERE org_id = $1 AND period_start = $2 AND reported_quantity_hash = $3
+        "#,
+    )
+    .bind(claim.org_id)
+    .bind(claim.period_start)
+    .bind(claim.quantity_hash())
+    .execute(pool)
+    .await?;
+
+    Ok(())
 }
 
 /// Subscription with metered billing info
@@ -54,11 +279,22 @@ pub struct MeteredSubscription {
 pub struct MeteredBillingService {
     stripe: StripeClient,
     pool: PgPool,
+    partial_policy: PartialReportPolicy,
 }
 
 impl MeteredBillingService {
     pub fn new(stripe: StripeClient, pool: PgPool) -> Self {
-        Self { stripe, pool }
+        Self {
+            stripe,
+            pool,
+            partial_policy: PartialReportPolicy::from_env(),
+        }
+    }
+
+    /// Override the configured partial-failure policy
+    pub fn with_partial_policy(mut self, policy: PartialReportPolicy) -> Self {
+        self.partial_policy = policy;
+        self
     }
 
     /// Get all active subscriptions with metered items
@@ -163,83 +399,94 @@ impl MeteredBillingService {
             0
         };
 
-        // If no overage, still report 0 to reset any previous values
-        let item_id = match subscription
-            .stripe_metered_item_id
-            .parse::<SubscriptionItemId>()
+        // If no overage, still report 0 to reset any previous values. Items
+        // are claimed in the ledger first so an overlapping run skips them.
+        let claims = [UsageClaim {
+            org_id: subscription.org_id,
+            period_start: subscription.current_period_start,
+            metered_item_id: &subscription.stripe_metered_item_id,
+            overage_units,
+        }];
+
+        // Report to Stripe - use Set action to set the absolute value
+        let items = match report_items(
+            &self.pool,
+            &claims,
+            self.partial_policy,
+            |item_id, units| {
+                let stripe = self.stripe.clone();
+                async move {
+                    let item_id = item_id
+                        .parse::<SubscriptionItemId>()
+                        .map_err(|e| format!("Invalid metered item ID: {}", e))?;
+                    let params = CreateUsageRecord {
+                        quantity: units as u64,
+                        action: Some(UsageRecordAction::Set),
+                        timestamp: Some(OffsetDateTime::now_utc().unix_timestamp()),
+                    };
+                    UsageRecord::create(stripe.inner(), &item_id, params)
+                        .await
+                        .map(|_| ())
+                        .map_err(|e| format!("Stripe API error: {}", e))
+                }
+            },
+        )
+        .await
         {
-            Ok(id) => id,
+            Ok(items) => items,
             Err(e) => {
                 return UsageReportResult::Error {
                     org_id: subscription.org_id,
-                    error: format!("Invalid metered item ID: {}", e),
+                    error: format!("Failed to claim usage report: {}", e),
                 };
             }
         };
 
-        // Report to Stripe - use Set action to set the absolute value
-        let params = CreateUsageRecord {
-            quantity: overage_units as u64,
-            action: Some(UsageRecordAction::Set),
-            timestamp: Some(OffsetDateTime::now_utc().unix_timestamp()),
-        };
-
-        match UsageRecord::create(self.stripe.inner(), &item_id, params).await {
-            Ok(_record) => {
-                // Store the report in our database for audit trail
-                if let Err(e) = self
-                    .store_usage_report(subscription, total_usage, included_limit, overage_units)
-                    .await
-                {
-                    tracing::warn!(
+        for item in &items {
+            match &item.outcome {
+                ItemOutcome::Reported => {
+                    // Store the report in our database for audit trail
+                    if let Err(e) = self
+                        .store_usage_report(subscription, item, total_usage, included_limit)
+                        .await
+                    {
+                        tracing::warn!(
+                            org_id = %subscription.org_id,
+                            metered_item_id = %item.metered_item_id,
+                            error = %e,
+                            "Failed to store usage report record"
+                        );
+                    }
+                }
+                ItemOutcome::AlreadyReported => {
+                    tracing::debug!(
                         org_id = %subscription.org_id,
-                        error = %e,
-                        "Failed to store usage report record"
+                        metered_item_id = %item.metered_item_id,
+                        overage_units = %item.overage_units,
+                        "Usage already reported for this period, skipping"
                     );
                 }
-
-                if overage_units > 0 {
-                    tracing::info!(
+                ItemOutcome::Failed { error } => {
+                    tracing::error!(
                         org_id = %subscription.org_id,
-                        total_usage = %total_usage,
-                        overage_units = %overage_units,
-                        "Reported overage usage to Stripe"
+                        metered_item_id = %item.metered_item_id,
+                        error = %error,
+                        "Failed to report usage to Stripe"
                     );
-                    UsageReportResult::Reported {
-                        org_id: subscription.org_id,
-                        total_usage,
-                        included_limit,
-                        overage_units,
-                    }
-                } else {
-                    UsageReportResult::NoOverage {
-                        org_id: subscription.org_id,
-                        total_usage,
-                        included_limit,
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!(
-                    org_id = %subscription.org_id,
-                    error = %e,
-                    "Failed to report usage to Stripe"
-                );
-                UsageReportResult::Error {
-                    org_id: subscription.org_id,
-                    error: format!("Stripe API error: {}", e),
                 }
             }
         }
+
+        summarize_items(subscription.org_id, total_usage, included_limit, items)
     }
 
     /// Store a usage report record for audit trail
     async fn store_usage_report(
         &self,
         subscription: &MeteredSubscription,
+        item: &ItemReportResult,
         total_usage: i64,
         included_limit: i64,
-        overage_units: i64,
     ) -> BillingResult<()> {
         sqlx::query(
             r#"
@@ -252,12 +499,12 @@ impl MeteredBillingService {
         )
         .bind(subscription.org_id)
         .bind(subscription.subscription_id)
-        .bind(&subscription.stripe_metered_item_id)
+        .bind(&item.metered_item_id)
         .bind(subscription.current_period_start)
         .bind(subscription.current_period_end)
         .bind(total_usage)
         .bind(included_limit)
-        .bind(overage_units)
+        .bind(item.overage_units)
         .execute(&self.pool)
         .await?;
 
@@ -265,6 +512,9 @@ impl MeteredBillingService {
     }
 
     /// Report usage for all active metered subscriptions
+    ///
+    /// Up to `METERED_REPORT_CONCURRENCY` orgs (default 10) report at once;
+    /// results keep the order of the subscriptions.
     pub async fn report_all_usage(&self) -> Vec<UsageReportResult> {
         let subscriptions = match self.get_metered_subscriptions().await {
             Ok(subs) => subs,
@@ -279,12 +529,26 @@ impl MeteredBillingService {
             "Starting usage report for metered subscriptions"
         );
 
-        let mut results = Vec::with_capacity(subscriptions.len());
-
-        for subscription in subscriptions {
-            let result = self.report_usage_for_subscription(&subscription).await;
-            results.push(result);
-        }
+        // Orgs report independently; a panic in one becomes its own error result
+        let org_ids: Vec<Uuid> = subscriptions.iter().map(|s| s.org_id).collect();
+        let results: Vec<UsageReportResult> =
+            run_bounded(subscriptions, get_report_concurrency(), |subscription| {
+                let service = self.clone();
+                async move { service.report_usage_for_subscription(&subscription).await }
+            })
+            .await
+            .into_iter()
+            .zip(org_ids)
+            .map(|(result, org_id)| {
+                result.unwrap_or_else(|| {
+                    tracing::error!(org_id = %org_id, "Usage report task panicked");
+                    UsageReportResult::Error {
+                        org_id,
+                        error: "Usage report task panicked".to_string(),
+                    }
+                })
+            })
+            .collect();
 
         // Log summary
         let reported_count = results
@@ -295,6 +559,14 @@ impl MeteredBillingService {
             .iter()
             .filter(|r| matches!(r, UsageReportResult::NoOverage { .. }))
             .count();
+        let already_reported_count = results
+            .iter()
+            .filter(|r| matches!(r, UsageReportResult::AlreadyReported { .. }))
+            .count();
+        let partial_count = results
+            .iter()
+            .filter(|r| matches!(r, UsageReportResult::PartiallyReported { .. }))
+            .count();
         let error_count = results
             .iter()
             .filter(|r| matches!(r, UsageReportResult::Error { .. }))
@@ -303,6 +575,8 @@ impl MeteredBillingService {
         tracing::info!(
             reported = reported_count,
             no_overage = no_overage_count,
+            already_reported = already_reported_count,
+            partially_reported = partial_count,
             errors = error_count,
             "Completed usage report cycle"
         );
@@ -325,6 +599,71 @@ impl MeteredBillingService {
     }
 }
 
+/// Org-level result from the per-item results of a subscription
+///
+/// Any failed item makes the result `PartiallyReported` when another item
+/// reached Stripe, or `Error` when none did. Items that were all reported
+/// earlier give `AlreadyReported`.
+fn summarize_items(
+    org_id: Uuid,
+    total_usage: i64,
+    included_limit: i64,
+    items: Vec<ItemReportResult>,
+) -> UsageReportResult {
+    let failed: Vec<&str> = items
+        .iter()
+        .filter_map(|item| match &item.outcome {
+            ItemOutcome::Failed { error } => Some(error.as_str()),
+            _ => None,
+        })
+        .collect();
+    let reported = items
+        .iter()
+        .any(|item| item.outcome == ItemOutcome::Reported);
+
+    if !failed.is_empty() {
+        if reported {
+            return UsageReportResult::PartiallyReported {
+                org_id,
+                total_usage,
+                included_limit,
+                items,
+            };
+        }
+        return UsageReportResult::Error {
+            org_id,
+            error: failed.join("; "),
+        };
+    }
+    if !reported {
+        return UsageReportResult::AlreadyReported { org_id };
+    }
+
+    let overage_units: i64 = items.iter().map(|item| item.overage_units).sum();
+    if overage_units > 0 {
+        tracing::info!(
+            org_id = %org_id,
+            total_usage = %total_usage,
+            overage_units = %overage_units,
+            "Reported overage usage to Stripe"
+        );
+        UsageReportResult::Reported {
+            org_id,
+            total_usage,
+            included_limit,
+            overage_units,
+            items,
+        }
+    } else {
+        UsageReportResult::NoOverage {
+            org_id,
+            total_usage,
+            included_limit,
+            items,
+        }
+    }
+}
+
 /// Request to manually trigger usage reporting (for admin/testing)
 #[derive(Debug, Deserialize)]
 pub struct TriggerUsageReportRequest {
@@ -339,3 +678,169 @@ pub struct UsageReportResponse {
     pub total_reported: usize,
     pub total_errors: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reported_quantity_hash() {
+        let hash = reported_quantity_hash("si_123", 42);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, reported_quantity_hash("si_123", 42));
+        assert_ne!(hash, reported_quantity_hash("si_123", 43));
+        assert_ne!(hash, reported_quantity_hash("si_456", 42));
+    }
+
+    fn item(id: &str, outcome: ItemOutcome) -> ItemReportResult {
+        ItemReportResult {
+            metered_item_id: id.to_string(),
+            overage_units: 5,
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_summarize_items_reports_partial_failure() {
+        let org_id = Uuid::new_v4();
+        let failed = ItemOutcome::Failed {
+            error: "stripe unavailable".to_string(),
+        };
+
+        let partial = summarize_items(
+            org_id,
+            60_000,
+            50_000,
+            vec![
+                item("si_api_calls", ItemOutcome::Reported),
+                item("si_storage", failed.clone()),
+            ],
+        );
+        match partial {
+            UsageReportResult::PartiallyReported { items, .. } => {
+                assert_eq!(items[1].metered_item_id, "si_storage");
+                assert_eq!(items[1].outcome, failed);
+            }
+            other => panic!("expected PartiallyReported, got {:?}", other),
+        }
+
+        assert!(matches!(
+            summarize_items(org_id, 60_000, 50_000, vec![item("si_storage", failed)]),
+            UsageReportResult::Error { .. }
+        ));
+        assert!(matches!(
+            summarize_items(
+                org_id,
+                60_000,
+                50_000,
+                vec![item("si_api_calls", ItemOutcome::AlreadyReported)]
+            ),
+            UsageReportResult::AlreadyReported { .. }
+        ));
+    }
+
+    async fn insert_org(pool: &PgPool) -> Uuid {
+        let org_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO organizations (id, name, slug) VALUES ($1, $2, $3)")
+            .bind(org_id)
+            .bind("Metered Partial Report Test")
+            .bind(format!("metered-partial-{}", org_id.simple()))
+            .execute(pool)
+            .await
+            .unwrap();
+        org_id
+    }
+
+    fn two_item_claims(org_id: Uuid, period_start: OffsetDateTime) -> [UsageClaim<'static>; 2] {
+        [
+            UsageClaim {
+                org_id,
+                period_start,
+                metered_item_id: "si_api_calls",
+                overage_units: 12,
+            },
+            UsageClaim {
+                org_id,
+                period_start,
+                metered_item_id: "si_storage",
+                overage_units: 3,
+            },
+        ]
+    }
+
+    /// Run one cycle where `failing` items fail, returning the items sent
+    async fn run_cycle(
+        pool: &PgPool,
+        claims: &[UsageClaim<'_>],
+        policy: PartialReportPolicy,
+        failing: &[&str],
+    ) -> (Vec<ItemReportResult>, Vec<String>) {
+        let sent = std::sync::Mutex::new(Vec::new());
+        let results = report_items(pool, claims, policy, |item_id, _units| {
+            sent.lock().unwrap().push(item_id.clone());
+            let fail = failing.contains(&item_id.as_str());
+            async move {
+                if fail {
+                    Err("stripe unavailable".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await
+        .unwrap();
+        (results, sent.into_inner().unwrap())
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_partial_failure_retries_only_failed_item() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let org_id = insert_org(&pool).await;
+        let period_start = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+        let claims = two_item_claims(org_id, period_start);
+        let policy = PartialReportPolicy::RetryFailed;
+
+        // First cycle: the storage item fails at Stripe
+        let (first, _) = run_cycle(&pool, &claims, policy, &["si_storage"]).await;
+        assert_eq!(first[0].outcome, ItemOutcome::Reported);
+        assert!(matches!(first[1].outcome, ItemOutcome::Failed { .. }));
+
+        // Second cycle: only the failed item is sent again
+        let (second, sent) = run_cycle(&pool, &claims, policy, &[]).await;
+        assert_eq!(sent, vec!["si_storage"]);
+        assert_eq!(second[0].outcome, ItemOutcome::AlreadyReported);
+        assert_eq!(second[1].outcome, ItemOutcome::Reported);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_resend_all_policy_resends_every_item() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let org_id = insert_org(&pool).await;
+        let period_start = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+        let claims = two_item_claims(org_id, period_start);
+        let policy = PartialReportPolicy::ResendAll;
+
+        run_cycle(&pool, &claims, policy, &["si_storage"]).await;
+
+        // The item that reached Stripe lost its claim and is sent again
+        let (second, sent) = run_cycle(&pool, &claims, policy, &[]).await;
+        assert_eq!(sent, vec!["si_api_calls", "si_storage"]);
+        assert!(second.iter().all(|r| r.outcome == ItemOutcome::Reported));
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
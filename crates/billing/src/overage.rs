@@ -4,21 +4,25 @@
 //! Overages are calculated at the end of each billing period and added to the
 //! next invoice. Also supports "Pay Now" for early overage payment.
 
+use plexmcp_shared::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use stripe::{
     CheckoutSession, CheckoutSessionMode, CreateCheckoutSession, CreateCheckoutSessionLineItems,
     CreateCheckoutSessionLineItemsPriceData, CreateCheckoutSessionLineItemsPriceDataProductData,
-    CreateInvoiceItem, Currency, CustomerId, Invoice, InvoiceId, InvoiceItem,
+    CreateInvoice, CreateInvoiceItem, Currency, CustomerId, FinalizeInvoiceParams, Invoice,
+    InvoiceId, InvoiceItem, InvoicePendingInvoiceItemsBehavior,
 };
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::client::StripeClient;
 use crate::error::{BillingError, BillingResult};
+use crate::events::{ActorType, BillingEventBuilder, BillingEventLogger, BillingEventType};
+use crate::spend_cap::SpendCapService;
 use crate::usage::UsageMeter;
 
-use plexmcp_shared::types::SubscriptionTier;
+use plexmcp_shared::types::{OverageMode, SubscriptionTier};
 
 /// Overage rates per resource type
 #[derive(Debug, Clone)]
@@ -90,6 +94,78 @@ impl OverageRates {
     }
 }
 
+/// Overage rates for a tier name, or None if the tier never carries overage rows
+///
+/// Unknown tier names are treated as Free.
+pub(crate) fn overage_rates_for_tier_name(tier: &str) -> Option<(SubscriptionTier, OverageRates)> {
+    let tier: SubscriptionTier = tier.parse().unwrap_or(SubscriptionTier::Free);
+    if tier.monthly_requests() == u64::MAX {
+        return None;
+    }
+    OverageRates::for_tier(tier).map(|rates| (tier, rates))
+}
+
+/// Whether usage past `limit` should be billed as overage. HardStop orgs are
+/// blocked at their limit, so they never accrue any new overage.
+fn overage_accrues(mode: OverageMode, total_usage: i64, limit: i64) -> bool {
+    mode == OverageMode::Allow && limit != i64::MAX && total_usage > limit
+}
+
+/// How overage is handled while the org's subscription is `past_due`
+///
+/// A past-due org keeps using the service (within its grace period), so its
+/// usage keeps growing. Configure with `OVERAGE_PAST_DUE_POLICY`:
+/// - `accumulate` (default): overage keeps accruing as normal, but charges
+///   are marked `accrued_while_past_due` for review.
+/// - `pause`: the pending charge is frozen while past due. Usage is still
+///   recorded, so the charge catches up once the subscription recovers.
+/// - `collect`: overage accrues (flagged) and the worker invoices pending
+///   charges immediately rather than waiting for the period to end.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PastDueOveragePolicy {
+    #[default]
+    Accumulate,
+    Pause,
+    Collect,
+}
+
+impl PastDueOveragePolicy {
+    /// Load the policy from `OVERAGE_PAST_DUE_POLICY`, defaulting to `Accumulate`
+    pub fn from_env() -> Self {
+        std::env::var("OVERAGE_PAST_DUE_POLICY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether the pending charge should be left untouched
+    fn freezes(self, past_due: bool) -> bool {
+        past_due && self == Self::Pause
+    }
+
+    /// Whether pending charges should be invoiced right away
+    fn collects(self, past_due: bool) -> bool {
+        past_due && self == Self::Collect
+    }
+}
+
+impl std::str::FromStr for PastDueOveragePolicy {
+    type Err = BillingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "accumulate" => Ok(Self::Accumulate),
+            "pause" => Ok(Self::Pause),
+            "collect" => Ok(Self::Collect),
+            other => Err(BillingError::Config(format!(
+                "Invalid past-due overage policy: {}",
+                other
+            ))),
+        }
+    }
+}
+
 /// Overage charge record
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct OverageCharge {
@@ -111,11 +187,211 @@ pub struct OverageCharge {
     pub paid_at: Option<OffsetDateTime>,
 }
 
+/// Line item description for an overage charge
+pub(crate) fn overage_item_description(charge: &OverageCharge) -> String {
+    format!(
+        "Request overage: {} requests over {} limit ({} - {})",
+        charge.overage_amount,
+        charge.base_limit,
+        charge.billing_period_start.date(),
+        charge.billing_period_end.date()
+    )
+}
+
+/// Stripe invoice item params for an overage charge
+///
+/// With `invoice` unset the item lands on the customer's next invoice;
+/// otherwise it is attached to that (draft) invoice.
+pub(crate) fn overage_invoice_item_params<'a>(
+    customer_id: CustomerId,
+    invoice: Option<InvoiceId>,
+    charge: &OverageCharge,
+    description: &'a str,
+) -> CreateInvoiceItem<'a> {
+    let mut params = CreateInvoiceItem::new(customer_id);
+    params.amount = Some(charge.total_charge_cents as i64);
+    params.currency = Some(stripe::Currency::USD);
+    params.description = Some(description);
+    params.invoice = invoice;
+    params
+}
+
+/// An org's active billing period eligible for real-time overage tracking
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct OverageBillingPeriod {
+    pub org_id: Uuid,
+    pub tier: String,
+    pub period_start: OffsetDateTime,
+    pub period_end: OffsetDateTime,
+}
+
+/// Pro/Team orgs with an active subscription and known billing period.
+/// Note: subscriptions.customer_id stores org_id as text
+const OVERAGE_PERIODS_SELECT: &str = r#"
+    SELECT o.id AS org_id, o.subscription_tier AS tier,
+           s.current_period_start AS period_start, s.current_period_end AS period_end
+    FROM organizations o
+    JOIN subscriptions s ON s.customer_id = o.id::text
+    WHERE o.subscription_tier IN ('pro', 'team')
+      AND s.status IN ('active', 'past_due')
+      AND s.current_period_start IS NOT NULL
+      AND s.current_period_end IS NOT NULL
+"#;
+
+/// Result of a real-time overage recalculation across all orgs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OverageRecalculationSummary {
+    pub total_orgs: usize,
+    pub updated: usize,
+    pub errors: usize,
+}
+
+/// Paid or invoiced overage for a period that corrected usage no longer supports
+///
+/// Settled charges are never rewritten; this is flagged for a refund or
+/// credit instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OverageDiscrepancy {
+    pub org_id: Uuid,
+    pub period_start: OffsetDateTime,
+    /// Total already paid or invoiced for the period
+    pub settled_cents: i32,
+    /// What the period's corrected usage supports
+    pub corrected_cents: i32,
+    /// The paid/invoiced charges involved
+    pub charge_ids: Vec<Uuid>,
+}
+
+impl OverageDiscrepancy {
+    /// Amount billed beyond corrected usage
+    pub fn overbilled_cents(&self) -> i32 {
+        self.settled_cents - self.corrected_cents
+    }
+}
+
+/// Outcome of recomputing a period's overage from its usage records
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OverageRecompute {
+    /// The period's pending charge, `None` if nothing is left to bill
+    pub charge: Option<OverageCharge>,
+    /// Set when settled charges exceed corrected usage
+    pub discrepancy: Option<OverageDiscrepancy>,
+}
+
+/// Overage and charge still unbilled after the period's settled (paid or
+/// invoiced) charges, or `None` if those already cover it
+fn unbilled_overage(
+    total_overage: i64,
+    total_charge_cents: i32,
+    settled: &[OverageCharge],
+) -> Option<(i64, i32)> {
+    let billed_cents: i32 = settled.iter().map(|c| c.total_charge_cents).sum();
+    let incremental_cents = total_charge_cents - billed_cents;
+    if incremental_cents <= 0 {
+        return None;
+    }
+    let billed_overage: i64 = settled.iter().map(|c| c.overage_amount).sum();
+    Some((total_overage - billed_overage, incremental_cents))
+}
+
+/// Flag settled charges that exceed what corrected usage supports
+fn overage_discrepancy(
+    org_id: Uuid,
+    period_start: OffsetDateTime,
+    corrected_cents: i32,
+    settled: &[OverageCharge],
+) -> Option<OverageDiscrepancy> {
+    let settled_cents: i32 = settled.iter().map(|c| c.total_charge_cents).sum();
+    (settled_cents > corrected_cents).then(|| OverageDiscrepancy {
+        org_id,
+        period_start,
+        settled_cents,
+        corrected_cents,
+        charge_ids: settled.iter().map(|c| c.id).collect(),
+    })
+}
+
+/// Rows that count as the period's active (unpaid, not yet invoiced) charge.
+/// At most one of these may exist per org, period and resource type.
+const ACTIVE_CHARGE_FILTER: &str = r#"
+    status IN ('pending', 'awaiting_payment')
+    AND (paid_early IS NULL OR paid_early = false)
+"#;
+
+/// How to collapse one group of duplicate active charges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChargeMerge {
+    /// Row kept and updated with the latest calculation
+    pub keep_id: Uuid,
+    pub actual_usage: i64,
+    pub overage_amount: i64,
+    pub total_charge_cents: i32,
+    /// Duplicates to void
+    pub void_ids: Vec<Uuid>,
+}
+
+/// Result of deduplicating an org's active overage charges
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChargeDedupeSummary {
+    /// Periods that had more than one active charge
+    pub periods_merged: usize,
+    /// Duplicate rows voided
+    pub charges_voided: usize,
+}
+
+/// Plan merges so each (org, period, resource type) keeps one active charge
+///
+/// Every active row holds the full incremental charge as of its last
+/// recalculation, so duplicates are not added together. One row is kept (a
+/// row with a Pay Now checkout in flight if there is one, otherwise the
+/// oldest) and takes the figures from the row with the highest usage, i.e.
+/// the most recent calculation; the rest are voided.
+pub fn plan_charge_dedupe(charges: &[OverageCharge]) -> Vec<ChargeMerge> {
+    type PeriodKey<'a> = (Uuid, OffsetDateTime, OffsetDateTime, &'a str);
+
+    let mut groups: Vec<(PeriodKey, Vec<&OverageCharge>)> = Vec::new();
+    for charge in charges {
+        let key = (
+            charge.org_id,
+            charge.billing_period_start,
+            charge.billing_period_end,
+            charge.resource_type.as_str(),
+        );
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, group)) => group.push(charge),
+            None => groups.push((key, vec![charge])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(_, mut group)| {
+            group.sort_by_key(|c| (c.status != "awaiting_payment", c.created_at, c.id));
+            let keep = group[0];
+            let latest = group
+                .iter()
+                .max_by_key(|c| (c.actual_usage, c.created_at))
+                .copied()
+                .unwrap_or(keep);
+
+            ChargeMerge {
+                keep_id: keep.id,
+                actual_usage: latest.actual_usage,
+                overage_amount: latest.overage_amount,
+                total_charge_cents: latest.total_charge_cents,
+                void_ids: group[1..].iter().map(|c| c.id).collect(),
+            }
+        })
+        .collect()
+}
+
 /// Overage service for calculating and billing usage overages
 pub struct OverageService {
     stripe: StripeClient,
     pool: PgPool,
     rates: OverageRates,
+    past_due_policy: PastDueOveragePolicy,
 }
 
 impl OverageService {
@@ -124,6 +400,7 @@ impl OverageService {
             stripe,
             pool,
             rates: OverageRates::from_env(),
+            past_due_policy: PastDueOveragePolicy::from_env(),
         }
     }
 
@@ -132,9 +409,16 @@ impl OverageService {
             stripe,
             pool,
             rates,
+            past_due_policy: PastDueOveragePolicy::from_env(),
         }
     }
 
+    /// Override the configured past-due overage policy
+    pub fn with_past_due_policy(mut self, policy: PastDueOveragePolicy) -> Self {
+        self.past_due_policy = policy;
+        self
+    }
+
     /// Calculate and record overage for a billing period
     /// Does NOT create Stripe invoice item (call `bill_overage` for that)
     pub async fn calculate_period_overage(
@@ -145,10 +429,13 @@ impl OverageService {
         limit: u64,
         actual_usage: u64,
     ) -> BillingResult<Option<OverageCharge>> {
-        // No overage if within limits
+        // No overage if within limits or the org blocks at its limit
         if actual_usage <= limit || limit == u64::MAX {
             return Ok(None);
         }
+        if self.get_overage_mode(org_id).await? == OverageMode::HardStop {
+            return Ok(None);
+        }
 
         let overage_amount = (actual_usage - limit) as i64;
         let total_charge_cents = self.rates.calculate_request_overage_cents(overage_amount);
@@ -217,18 +504,8 @@ impl OverageService {
             .parse::<CustomerId>()
             .map_err(|e| BillingError::StripeApi(format!("Invalid customer ID: {}", e)))?;
 
-        let description = format!(
-            "Request overage: {} requests over {} limit ({} - {})",
-            charge.overage_amount,
-            charge.base_limit,
-            charge.billing_period_start.date(),
-            charge.billing_period_end.date()
-        );
-
-        let mut params = CreateInvoiceItem::new(customer_id);
-        params.amount = Some(charge.total_charge_cents as i64);
-        params.currency = Some(stripe::Currency::USD);
-        params.description = Some(&description);
+        let description = overage_item_description(&charge);
+        let params = overage_invoice_item_params(customer_id, None, &charge, &description);
 
         let invoice_item = InvoiceItem::create(self.stripe.inner(), params).await?;
 
@@ -337,6 +614,75 @@ impl OverageService {
         Ok(charges)
     }
 
+    /// Collapse duplicate active charges so each period has one
+    ///
+    /// Duplicates inflate the Pay Now total. See [`plan_charge_dedupe`] for
+    /// how rows are merged; voided rows are marked `waived`. Once no
+    /// duplicates remain, the partial unique index from the
+    /// `overage_single_active_charge` migration keeps it that way.
+    pub async fn dedupe_period_charges(&self, org_id: Uuid) -> BillingResult<ChargeDedupeSummary> {
+        let mut tx = self.pool.begin().await?;
+
+        // Lock the org's active rows so a concurrent recalculation can't
+        // update a row we're about to void
+        let charges: Vec<OverageCharge> = sqlx::query_as(&format!(
+            r#"
+            SELECT id, org_id, billing_period_start, billing_period_end, resource_type,
+                   base_limit, actual_usage, overage_amount, rate_per_unit_cents,
+                   total_charge_cents, stripe_invoice_item_id, status, created_at,
+                   invoiced_at, paid_at
+            FROM overage_charges
+            WHERE org_id = $1 AND {}
+            FOR UPDATE
+            "#,
+            ACTIVE_CHARGE_FILTER
+        ))
+        .bind(org_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let merges = plan_charge_dedupe(&charges);
+        let mut summary = ChargeDedupeSummary::default();
+
+        for merge in &merges {
+            sqlx::query("UPDATE overage_charges SET status = 'waived' WHERE id = ANY($1)")
+                .bind(&merge.void_ids)
+                .execute(&mut *tx)
+                .await?;
+
+            sqlx::query(
+                r#"
+                UPDATE overage_charges SET
+                    actual_usage = $2,
+                    overage_amount = $3,
+                    total_charge_cents = $4
+                WHERE id = $1
+                "#,
+            )
+            .bind(merge.keep_id)
+            .bind(merge.actual_usage)
+            .bind(merge.overage_amount)
+            .bind(merge.total_charge_cents)
+            .execute(&mut *tx)
+            .await?;
+
+            tracing::warn!(
+                org_id = %org_id,
+                kept_charge_id = %merge.keep_id,
+                voided = ?merge.void_ids,
+                total_charge_cents = merge.total_charge_cents,
+                "Merged duplicate active overage charges"
+            );
+
+            summary.periods_merged += 1;
+            summary.charges_voided += merge.void_ids.len();
+        }
+
+        tx.commit().await?;
+
+        Ok(summary)
+    }
+
     /// Process end-of-period overage for an organization
     /// This should be called when a billing period ends (e.g., via webhook or cron)
     pub async fn process_billing_period_overage(
@@ -372,7 +718,7 @@ impl OverageService {
 #[derive(Debug, Clone, Serialize)]
 pub struct OverageSummary {
     pub has_pending_overages: bool,
-    pub pending_total_cents: i32,
+    pub pending_total: Money,
     pub pending_charges: Vec<OverageCharge>,
 }
 
@@ -390,11 +736,11 @@ impl OverageSummary {
         .await
         .map_err(|e| BillingError::Database(e.to_string()))?;
 
-        let pending_total_cents: i32 = charges.iter().map(|c| c.total_charge_cents).sum();
+        let pending_total_cents: i64 = charges.iter().map(|c| c.total_charge_cents as i64).sum();
 
         Ok(Self {
             has_pending_overages: !charges.is_empty(),
-            pending_total_cents,
+            pending_total: Money::from_cents_usd(pending_total_cents),
             pending_charges: charges,
         })
     }
@@ -403,8 +749,8 @@ impl OverageSummary {
 /// Accumulated overage information for Pay Now functionality
 #[derive(Debug, Clone, Serialize)]
 pub struct AccumulatedOverage {
-    /// Total overage amount in cents
-    pub total_cents: i32,
+    /// Total overage amount
+    pub total: Money,
     /// Total number of overage requests
     pub total_requests: i64,
     /// Number of pending charges
@@ -425,20 +771,40 @@ pub enum PayNowResult {
         checkout_session_id: String,
         /// Checkout URL for user to complete payment
         checkout_url: String,
-        /// Total amount in cents
-        amount_cents: i32,
+        /// Total amount
+        amount: Money,
         /// Number of charges included
         charge_count: i32,
     },
     /// Payment already completed (from existing session)
     AlreadyPaid {
-        /// Total amount in cents
-        amount_cents: i32,
+        /// Total amount
+        amount: Money,
         /// Number of charges
         charge_count: i32,
     },
 }
 
+/// Result of invoicing accumulated overages on demand
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status")]
+pub enum InvoiceNowResult {
+    /// No pending charges to invoice
+    NoPendingCharges,
+    /// Invoice finalized and charged against the default payment method
+    Invoiced {
+        /// Stripe invoice ID
+        stripe_invoice_id: String,
+        /// Total amount
+        amount: Money,
+        /// Number of charges included
+        charge_count: i32,
+        /// Whether the immediate payment attempt succeeded
+        /// (if not, Stripe keeps retrying via auto-advance)
+        paid: bool,
+    },
+}
+
 impl OverageService {
     /// Get accumulated overage for Pay Now display
     /// Shows charges that are pending OR awaiting payment (invoice created but not yet paid)
@@ -460,12 +826,12 @@ impl OverageService {
         .await
         .map_err(|e| BillingError::Database(e.to_string()))?;
 
-        let total_cents: i32 = charges.iter().map(|c| c.total_charge_cents).sum();
+        let total_cents: i64 = charges.iter().map(|c| c.total_charge_cents as i64).sum();
         let total_requests: i64 = charges.iter().map(|c| c.overage_amount).sum();
         let charge_count = charges.len() as i32;
 
         Ok(AccumulatedOverage {
-            total_cents,
+            total: Money::from_cents_usd(total_cents),
             total_requests,
             charge_count,
             charges,
@@ -518,7 +884,7 @@ impl OverageService {
                         // Already paid - mark charges as paid and return
                         self.mark_early_payment_paid(&session_id).await?;
                         return Ok(PayNowResult::AlreadyPaid {
-                            amount_cents,
+                            amount: Money::from_cents_usd(amount_cents),
                             charge_count,
                         });
                     }
@@ -528,7 +894,7 @@ impl OverageService {
                         return Ok(PayNowResult::PaymentRequired {
                             checkout_session_id: session_id,
                             checkout_url: url,
-                            amount_cents,
+                            amount: Money::from_cents_usd(amount_cents),
                             charge_count,
                         });
                     } else {
@@ -710,8 +1076,273 @@ impl OverageService {
         Ok(PayNowResult::PaymentRequired {
             checkout_session_id: session_id,
             checkout_url,
-            amount_cents: total_cents,
+            amount: Money::from_cents_usd(total_cents),
+            charge_count,
+        })
+    }
+
+    /// Invoice all pending overages immediately (admin action)
+    ///
+    /// Unlike `pay_overages_now`, this does not wait for the user: it creates a
+    /// `charge_automatically` invoice containing one item per pending charge,
+    /// finalizes it and attempts payment. Charges move pending -> processing ->
+    /// invoiced, and are marked paid by the `invoice.paid` webhook through
+    /// `mark_invoiced_charges_paid`.
+    pub async fn invoice_now(&self, org_id: Uuid) -> BillingResult<InvoiceNowResult> {
+        let stripe_customer_id: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT stripe_customer_id FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| BillingError::Database(e.to_string()))?;
+        let stripe_customer_id = stripe_customer_id
+            .and_then(|(id,)| id)
+            .ok_or_else(|| BillingError::CustomerNotFound(org_id.to_string()))?;
+        let customer_id = stripe_customer_id
+            .parse::<CustomerId>()
+            .map_err(|e| BillingError::StripeApi(format!("Invalid customer ID: {}", e)))?;
+
+        let charges = self.claim_pending_charges(org_id).await?;
+        if charges.is_empty() {
+            tracing::info!(org_id = %org_id, "No pending overages to invoice");
+            return Ok(InvoiceNowResult::NoPendingCharges);
+        }
+        let charge_ids: Vec<Uuid> = charges.iter().map(|c| c.id).collect();
+
+        match self
+            .create_overage_invoice(org_id, customer_id, &charges)
+            .await
+        {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                tracing::error!(
+                    org_id = %org_id,
+                    error = %e,
+                    charge_count = charges.len(),
+                    "Failed to invoice overages on demand"
+                );
+                self.reset_processing_charges(&charge_ids).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Move an org's pending charges to 'processing' and return them
+    ///
+    /// The rows are locked while they move, so Pay Now and concurrent
+    /// `invoice_now` calls can't pick them up. Returns an empty list when
+    /// nothing is pending.
+    async fn claim_pending_charges(&self, org_id: Uuid) -> BillingResult<Vec<OverageCharge>> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        let charges: Vec<OverageCharge> = sqlx::query_as(
+            r#"
+            SELECT id, org_id, billing_period_start, billing_period_end, resource_type,
+                   base_limit, actual_usage, overage_amount, rate_per_unit_cents,
+                   total_charge_cents, stripe_invoice_item_id, status, created_at,
+                   invoiced_at, paid_at
+            FROM overage_charges
+            WHERE org_id = $1
+              AND status = 'pending'
+              AND (paid_early IS NULL OR paid_early = false)
+            ORDER BY created_at ASC
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        if charges.is_empty() {
+            tx.rollback().await.ok();
+            return Ok(charges);
+        }
+
+        let charge_ids: Vec<Uuid> = charges.iter().map(|c| c.id).collect();
+        sqlx::query("UPDATE overage_charges SET status = 'processing' WHERE id = ANY($1)")
+            .bind(&charge_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        Ok(charges)
+    }
+
+    /// Record claimed charges as invoiced with their Stripe invoice item ids
+    ///
+    /// All or nothing: on error every charge is still 'processing'.
+    async fn mark_charges_invoiced(&self, item_ids: &[(Uuid, String)]) -> BillingResult<()> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        for (charge_id, item_id) in item_ids {
+            sqlx::query(
+                r#"
+                UPDATE overage_charges SET
+                    status = 'invoiced',
+                    stripe_invoice_item_id = $1,
+                    invoiced_at = NOW()
+                WHERE id = $2
+                  AND status = 'processing'
+                "#,
+            )
+            .bind(item_id)
+            .bind(charge_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))
+    }
+
+    /// Attach an invoice item per charge to a draft invoice, then finalize it
+    ///
+    /// Returns each charge id with the id of its invoice item.
+    async fn add_items_and_finalize(
+        &self,
+        invoice_id: &InvoiceId,
+        customer_id: CustomerId,
+        charges: &[OverageCharge],
+    ) -> BillingResult<Vec<(Uuid, String)>> {
+        let mut item_ids = Vec::with_capacity(charges.len());
+        for charge in charges {
+            let description = overage_item_description(charge);
+            let params = overage_invoice_item_params(
+                customer_id.clone(),
+                Some(invoice_id.clone()),
+                charge,
+                &description,
+            );
+            let item = InvoiceItem::create(self.stripe.inner(), params).await?;
+            item_ids.push((charge.id, item.id.to_string()));
+        }
+
+        Invoice::finalize(
+            self.stripe.inner(),
+            invoice_id,
+            FinalizeInvoiceParams {
+                auto_advance: Some(true),
+            },
+        )
+        .await?;
+
+        Ok(item_ids)
+    }
+
+    /// Remove an on-demand overage invoice that failed part way through
+    ///
+    /// A draft is deleted along with its items; one that was already
+    /// finalized is voided instead.
+    async fn discard_overage_invoice(&self, org_id: Uuid, invoice_id: &InvoiceId) {
+        let outcome = match Invoice::delete(self.stripe.inner(), invoice_id).await {
+            Ok(_) => Ok("deleted"),
+            Err(_) => Invoice::void(self.stripe.inner(), invoice_id)
+                .await
+                .map(|_| "voided"),
+        };
+        match outcome {
+            Ok(action) => tracing::info!(
+                org_id = %org_id,
+                invoice_id = %invoice_id,
+                action = action,
+                "Discarded failed overage invoice"
+            ),
+            Err(e) => tracing::error!(
+                org_id = %org_id,
+                invoice_id = %invoice_id,
+                error = %e,
+                "Failed to discard overage invoice - manual intervention may be needed"
+            ),
+        }
+    }
+
+    /// Create, finalize and charge an invoice for the given (processing) charges
+    async fn create_overage_invoice(
+        &self,
+        org_id: Uuid,
+        customer_id: CustomerId,
+        charges: &[OverageCharge],
+    ) -> BillingResult<InvoiceNowResult> {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("org_id".to_string(), org_id.to_string());
+        metadata.insert(
+            "invoice_type".to_string(),
+            "overage_invoice_now".to_string(),
+        );
+
+        // Draft without auto-advance so a failure below can't leave an invoice
+        // that Stripe finalizes on its own; auto-advance is enabled on finalize
+        let mut invoice_params = CreateInvoice::new();
+        invoice_params.customer = Some(customer_id.clone());
+        invoice_params.auto_advance = Some(false);
+        invoice_params.collection_method = Some(stripe::CollectionMethod::ChargeAutomatically);
+        invoice_params.pending_invoice_items_behavior =
+            Some(InvoicePendingInvoiceItemsBehavior::Exclude);
+        invoice_params.description = Some("Overage charges");
+        invoice_params.metadata = Some(metadata);
+
+        let invoice = Invoice::create(self.stripe.inner(), invoice_params).await?;
+
+        // Items are on a finalized invoice now; record that before attempting payment.
+        // On failure the invoice is discarded and the caller resets the charges.
+        let recorded = match self
+            .add_items_and_finalize(&invoice.id, customer_id, charges)
+            .await
+        {
+            Ok(item_ids) => self.mark_charges_invoiced(&item_ids).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            self.discard_overage_invoice(org_id, &invoice.id).await;
+            return Err(e);
+        }
+
+        let paid = match Invoice::pay(self.stripe.inner(), &invoice.id).await {
+            Ok(paid_invoice) => paid_invoice.paid.unwrap_or(false),
+            Err(e) => {
+                // Invoice stays open; Stripe retries and invoice.paid settles the charges
+                tracing::warn!(
+                    org_id = %org_id,
+                    invoice_id = %invoice.id,
+                    error = %e,
+                    "Immediate payment attempt for overage invoice failed"
+                );
+                false
+            }
+        };
+
+        let amount_cents: i32 = charges.iter().map(|c| c.total_charge_cents).sum();
+        let charge_count = charges.len() as i32;
+
+        tracing::info!(
+            org_id = %org_id,
+            invoice_id = %invoice.id,
+            amount_cents = amount_cents,
+            charge_count = charge_count,
+            paid = paid,
+            "Invoiced pending overages on demand"
+        );
+
+        Ok(InvoiceNowResult::Invoiced {
+            stripe_invoice_id: invoice.id.to_string(),
+            amount: Money::from_cents_usd(amount_cents),
             charge_count,
+            paid,
         })
     }
 
@@ -845,125 +1476,165 @@ impl OverageService {
         Ok(rows_affected)
     }
 
-    /// Create or update overage_charges record for current billing period.
-    /// Called from worker job and when user views billing page.
-    /// This populates the overage_charges table in real-time as usage occurs.
-    /// Handles incremental charges when previous charges have been paid early.
-    pub async fn create_or_update_current_overage(
-        &self,
-        org_id: Uuid,
-        tier: &str,
-        period_start: OffsetDateTime,
-        period_end: OffsetDateTime,
-    ) -> BillingResult<Option<OverageCharge>> {
-        // Get current usage from usage_records (source of truth for billing)
-        // usage_aggregates is for analytics only and may contain test/batch data
-        // Truncate to day boundaries since usage_records use daily periods but Stripe
-        // billing periods can start at any time of day (e.g., 03:42:05)
-        let total_usage: i64 = sqlx::query_scalar::<_, Option<i64>>(
-            r#"
-            SELECT COALESCE(SUM(request_count), 0)::BIGINT
-            FROM usage_records
-            WHERE org_id = $1
-              AND period_start >= date_trunc('day', $2::timestamptz)
-              AND period_start < date_trunc('day', $3::timestamptz) + interval '1 day'
+    /// Get the org's overage mode (`Allow` if the org doesn't exist)
+    pub async fn get_overage_mode(&self, org_id: Uuid) -> BillingResult<OverageMode> {
+        let mode: Option<String> =
+            sqlx::query_scalar("SELECT overage_mode FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        mode.map(|m| m.parse().map_err(BillingError::Internal))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Whether the org's subscription is currently `past_due`
+    async fn is_past_due(&self, org_id: Uuid) -> BillingResult<bool> {
+        let past_due: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM subscriptions WHERE org_id = $1 AND status = 'past_due')",
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(past_due)
+    }
+
+    /// Delete the period's pending charge if payment hasn't started on it
+    async fn delete_unstarted_pending_charge(&self, org_id: Uuid, period_start: OffsetDateTime) {
+        sqlx::query(
+            r#"
+            DELETE FROM overage_charges
+            WHERE org_id = $1
+              AND billing_period_start = $2
+              AND resource_type = 'requests'
+              AND status = 'pending'
+              AND (paid_early IS NULL OR paid_early = false)
             "#,
         )
         .bind(org_id)
         .bind(period_start)
-        .bind(period_end)
-        .fetch_one(&self.pool)
+        .execute(&self.pool)
         .await
-        .map_err(|e| BillingError::Database(e.to_string()))?
-        .unwrap_or(0);
-
-        // 2. Get limit for tier
-        let tier_parsed: SubscriptionTier = tier.parse().unwrap_or(SubscriptionTier::Free);
-        let limit = tier_parsed.monthly_requests() as i64;
+        .ok(); // Ignore errors on cleanup
+    }
 
-        // 3. No overage if within limits or unlimited
-        if total_usage <= limit || limit == i64::MAX {
-            // Delete any existing pending overage that hasn't started payment (user dropped below limit)
-            sqlx::query(
-                r#"
-                DELETE FROM overage_charges
-                WHERE org_id = $1
-                  AND billing_period_start = $2
-                  AND resource_type = 'requests'
-                  AND status = 'pending'
-                  AND (paid_early IS NULL OR paid_early = false)
-                "#,
-            )
-            .bind(org_id)
-            .bind(period_start)
-            .execute(&self.pool)
-            .await
-            .ok(); // Ignore errors on cleanup
+    /// The period's unsettled charge, left untouched while accrual is frozen
+    async fn frozen_pending_charge(
+        &self,
+        org_id: Uuid,
+        period_start: OffsetDateTime,
+    ) -> BillingResult<Option<OverageCharge>> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM overage_charges
+            WHERE org_id = $1
+              AND billing_period_start = $2
+              AND resource_type = 'requests'
+              AND status IN ('pending', 'awaiting_payment')
+              AND (paid_early IS NULL OR paid_early = false)
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| BillingError::Database(e.to_string()))
+    }
 
-            return Ok(None);
-        }
+    /// Create or update overage_charges record for current billing period.
+    /// Called from worker job and when user views billing page.
+    /// This populates the overage_charges table in real-time as usage occurs.
+    /// Handles incremental charges when previous charges have been paid early.
+    pub async fn create_or_update_current_overage(
+        &self,
+        org_id: Uuid,
+        tier: &str,
+        period_start: OffsetDateTime,
+        period_end: OffsetDateTime,
+    ) -> BillingResult<Option<OverageCharge>> {
+        let recompute = self
+            .sync_period_charge(org_id, tier, period_start, period_end)
+            .await?;
+        Ok(recompute.charge)
+    }
 
-        let total_overage_amount = total_usage - limit;
+    /// Bring the period's pending charge in line with its usage records
+    ///
+    /// Paid and invoiced charges are left alone; if they exceed what usage
+    /// supports the result carries a discrepancy.
+    async fn sync_period_charge(
+        &self,
+        org_id: Uuid,
+        tier: &str,
+        period_start: OffsetDateTime,
+        period_end: OffsetDateTime,
+    ) -> BillingResult<OverageRecompute> {
+        // Free and unlimited tiers never get overage rows, even if called directly
+        let Some((tier_parsed, rates)) = overage_rates_for_tier_name(tier) else {
+            tracing::warn!(
+                org_id = %org_id,
+                tier = %tier,
+                "Refusing to create overage charge for tier without overages"
+            );
+            return Ok(OverageRecompute::default());
+        };
 
-        // 4. Get rate for tier
-        let rates = OverageRates::for_tier(tier_parsed);
-        let rate_per_unit = rates.as_ref().map(|r| r.requests_per_1k_cents).unwrap_or(0);
-        let total_charge_cents = rates
-            .as_ref()
-            .map(|r| r.calculate_request_overage_cents(total_overage_amount))
-            .unwrap_or(0);
+        // Past-due orgs follow the configured policy; paused orgs keep their
+        // pending charge as-is until the subscription recovers
+        let past_due = self.is_past_due(org_id).await?;
+        if self.past_due_policy.freezes(past_due) {
+            tracing::info!(
+                org_id = %org_id,
+                "Subscription past due, overage accumulation paused"
+            );
+            return Ok(OverageRecompute {
+                charge: self.frozen_pending_charge(org_id, period_start).await?,
+                discrepancy: None,
+            });
+        }
 
-        if total_charge_cents == 0 {
-            return Ok(None);
+        // HardStop only stops new accrual: overage consumed before the org
+        // switched modes is still owed, so its pending charge is kept as-is
+        let mode = self.get_overage_mode(org_id).await?;
+        if mode == OverageMode::HardStop {
+            return Ok(OverageRecompute {
+                charge: self.frozen_pending_charge(org_id, period_start).await?,
+                discrepancy: None,
+            });
         }
 
-        // 5. Check how much has already been paid/invoiced for this billing period
-        let already_charged: i32 = sqlx::query_scalar::<_, Option<i32>>(
+        // Get current usage from usage_records (source of truth for billing)
+        // usage_aggregates is for analytics only and may contain test/batch data
+        // Truncate to day boundaries since usage_records use daily periods but Stripe
+        // billing periods can start at any time of day (e.g., 03:42:05)
+        let total_usage: i64 = sqlx::query_scalar::<_, Option<i64>>(
             r#"
-            SELECT COALESCE(SUM(total_charge_cents), 0)::INT
-            FROM overage_charges
+            SELECT COALESCE(SUM(request_count), 0)::BIGINT
+            FROM usage_records
             WHERE org_id = $1
-              AND billing_period_start = $2
-              AND resource_type = 'requests'
-              AND status IN ('paid', 'invoiced')
+              AND period_start >= date_trunc('day', $2::timestamptz)
+              AND period_start < date_trunc('day', $3::timestamptz) + interval '1 day'
             "#,
         )
         .bind(org_id)
         .bind(period_start)
+        .bind(period_end)
         .fetch_one(&self.pool)
         .await
         .map_err(|e| BillingError::Database(e.to_string()))?
         .unwrap_or(0);
 
-        // Calculate the incremental charge (new overage minus what's already paid)
-        let incremental_charge_cents = total_charge_cents - already_charged;
-
-        // No new charge needed if already paid up
-        if incremental_charge_cents <= 0 {
-            // Delete any pending charge that hasn't started payment since we're fully paid
-            sqlx::query(
-                r#"
-                DELETE FROM overage_charges
-                WHERE org_id = $1
-                  AND billing_period_start = $2
-                  AND resource_type = 'requests'
-                  AND status = 'pending'
-                  AND (paid_early IS NULL OR paid_early = false)
-                "#,
-            )
-            .bind(org_id)
-            .bind(period_start)
-            .execute(&self.pool)
-            .await
-            .ok();
-
-            return Ok(None);
-        }
+        // 2. Get limit for tier
+        let limit = tier_parsed.monthly_requests() as i64;
 
-        // Calculate the incremental overage amount (requests not yet charged for)
-        let already_charged_overage: i64 = sqlx::query_scalar::<_, Option<i64>>(
+        // 3. Charges already paid/invoiced for this billing period
+        let settled: Vec<OverageCharge> = sqlx::query_as(
             r#"
-            SELECT COALESCE(SUM(overage_amount), 0)::BIGINT
+            SELECT id, org_id, billing_period_start, billing_period_end, resource_type,
+                   base_limit, actual_usage, overage_amount, rate_per_unit_cents,
+                   total_charge_cents, stripe_invoice_item_id, status, created_at,
+                   invoiced_at, paid_at
             FROM overage_charges
             WHERE org_id = $1
               AND billing_period_start = $2
@@ -973,14 +1644,56 @@ impl OverageService {
         )
         .bind(org_id)
         .bind(period_start)
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await
-        .map_err(|e| BillingError::Database(e.to_string()))?
-        .unwrap_or(0);
+        .map_err(|e| BillingError::Database(e.to_string()))?;
 
-        let incremental_overage = total_overage_amount - already_charged_overage;
+        // 4. No overage if within limits or unlimited
+        if !overage_accrues(mode, total_usage, limit) {
+            // Delete any existing pending overage that hasn't started payment (user dropped below limit)
+            self.delete_unstarted_pending_charge(org_id, period_start)
+                .await;
 
-        // 6. Check if there's an existing pending/awaiting charge to update, or if we need a new one
+            return Ok(OverageRecompute {
+                charge: None,
+                discrepancy: overage_discrepancy(org_id, period_start, 0, &settled),
+            });
+        }
+
+        let total_overage_amount = total_usage - limit;
+
+        // 5. Get rate for tier
+        let rate_per_unit = rates.requests_per_1k_cents;
+        let total_charge_cents = rates.calculate_request_overage_cents(total_overage_amount);
+
+        if total_charge_cents == 0 {
+            return Ok(OverageRecompute {
+                charge: None,
+                discrepancy: overage_discrepancy(org_id, period_start, 0, &settled),
+            });
+        }
+
+        // 6. Only the increment over what's already paid/invoiced stays pending
+        let already_charged: i32 = settled.iter().map(|c| c.total_charge_cents).sum();
+        let Some((incremental_overage, incremental_charge_cents)) =
+            unbilled_overage(total_overage_amount, total_charge_cents, &settled)
+        else {
+            // Delete any pending charge that hasn't started payment since we're fully paid
+            self.delete_unstarted_pending_charge(org_id, period_start)
+                .await;
+
+            return Ok(OverageRecompute {
+                charge: None,
+                discrepancy: overage_discrepancy(
+                    org_id,
+                    period_start,
+                    total_charge_cents,
+                    &settled,
+                ),
+            });
+        };
+
+        // 7. Check if there's an existing pending/awaiting charge to update, or if we need a new one
         // Include 'awaiting_payment' to prevent duplicates when user cancels Stripe checkout
         let existing_pending: Option<Uuid> = sqlx::query_scalar(
             r#"
@@ -1005,7 +1718,8 @@ impl OverageService {
                 UPDATE overage_charges SET
                     actual_usage = $1,
                     overage_amount = $2,
-                    total_charge_cents = $3
+                    total_charge_cents = $3,
+                    accrued_while_past_due = accrued_while_past_due OR $5
                 WHERE id = $4
                 RETURNING *
                 "#,
@@ -1014,6 +1728,7 @@ impl OverageService {
             .bind(incremental_overage)
             .bind(incremental_charge_cents)
             .bind(charge_id)
+            .bind(past_due)
             .fetch_one(&self.pool)
             .await
             .map_err(|e| BillingError::Database(e.to_string()))?
@@ -1024,9 +1739,10 @@ impl OverageService {
                 INSERT INTO overage_charges (
                     org_id, billing_period_start, billing_period_end,
                     resource_type, base_limit, actual_usage, overage_amount,
-                    rate_per_unit_cents, total_charge_cents, status
+                    rate_per_unit_cents, total_charge_cents, status,
+                    accrued_while_past_due
                 )
-                VALUES ($1, $2, $3, 'requests', $4, $5, $6, $7, $8, 'pending')
+                VALUES ($1, $2, $3, 'requests', $4, $5, $6, $7, $8, 'pending', $9)
                 RETURNING *
                 "#,
             )
@@ -1038,6 +1754,7 @@ impl OverageService {
             .bind(incremental_overage)
             .bind(rate_per_unit)
             .bind(incremental_charge_cents)
+            .bind(past_due)
             .fetch_one(&self.pool)
             .await
             .map_err(|e| BillingError::Database(e.to_string()))?
@@ -1052,7 +1769,192 @@ impl OverageService {
             "Created/updated real-time overage charge"
         );
 
-        Ok(Some(charge))
+        if past_due {
+            tracing::warn!(
+                org_id = %org_id,
+                charge_id = %charge.id,
+                "Overage accrued while subscription past due, flagged for review"
+            );
+        }
+
+        Ok(OverageRecompute {
+            charge: Some(charge),
+            discrepancy: None,
+        })
+    }
+
+    /// Recompute a period's overage after its usage records were corrected
+    ///
+    /// For use after a dedup or backfill of `usage_records`. The period's
+    /// pending charge is recalculated from the corrected usage. Paid and
+    /// invoiced charges are never modified: if they exceed what the corrected
+    /// usage supports, the discrepancy is logged as an `OVERAGE_DISCREPANCY`
+    /// billing event and returned for follow-up (refund or credit).
+    pub async fn recompute_period(
+        &self,
+        period: &OverageBillingPeriod,
+        spend_cap: &SpendCapService,
+    ) -> BillingResult<OverageRecompute> {
+        let recompute = self
+            .sync_period_charge(
+                period.org_id,
+                &period.tier,
+                period.period_start,
+                period.period_end,
+            )
+            .await?;
+
+        // Spend cap tracking follows the corrected charge (won't double-count)
+        if let Err(e) = spend_cap.sync_spend_from_overages(period.org_id).await {
+            tracing::error!(org_id = %period.org_id, error = %e, "Failed to sync spend cap");
+        }
+
+        if let Some(discrepancy) = &recompute.discrepancy {
+            tracing::warn!(
+                org_id = %period.org_id,
+                period_start = %period.period_start,
+                settled_cents = discrepancy.settled_cents,
+                corrected_cents = discrepancy.corrected_cents,
+                "Settled overage exceeds corrected usage"
+            );
+
+            let event_logger = BillingEventLogger::new(self.pool.clone());
+            if let Err(e) = event_logger
+                .log_event(
+                    BillingEventBuilder::new(period.org_id, BillingEventType::OverageDiscrepancy)
+                        .data(serde_json::json!({
+                            "period_start": period.period_start.unix_timestamp(),
+                            "settled_cents": discrepancy.settled_cents,
+                            "corrected_cents": discrepancy.corrected_cents,
+                            "overbilled_cents": discrepancy.overbilled_cents(),
+                            "charge_ids": discrepancy.charge_ids,
+                        }))
+                        .actor_type(ActorType::System),
+                )
+                .await
+            {
+                tracing::warn!(
+                    org_id = %period.org_id,
+                    error = %e,
+                    "Failed to log overage discrepancy event"
+                );
+            }
+        }
+
+        Ok(recompute)
+    }
+
+    /// List the billing periods the overage worker job recalculates
+    pub async fn list_overage_periods(&self) -> BillingResult<Vec<OverageBillingPeriod>> {
+        let periods = sqlx::query_as(OVERAGE_PERIODS_SELECT)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(periods)
+    }
+
+    /// Recalculate the current overage for one billing period and sync the spend cap
+    ///
+    /// Spend cap sync failures are logged, not returned, so the overage figure
+    /// is still reported.
+    pub async fn recalculate_period(
+        &self,
+        period: &OverageBillingPeriod,
+        spend_cap: &SpendCapService,
+    ) -> BillingResult<Option<OverageCharge>> {
+        let charge = self
+            .create_or_update_current_overage(
+                period.org_id,
+                &period.tier,
+                period.period_start,
+                period.period_end,
+            )
+            .await?;
+
+        if charge.is_some() {
+            // Sync spend cap tracking (won't double-count)
+            if let Err(e) = spend_cap.sync_spend_from_overages(period.org_id).await {
+                tracing::error!(org_id = %period.org_id, error = %e, "Failed to sync spend cap");
+            }
+
+            // Collect from past-due orgs now instead of at period end
+            if self
+                .past_due_policy
+                .collects(self.is_past_due(period.org_id).await?)
+            {
+                match self.invoice_now(period.org_id).await {
+                    Ok(result) => tracing::info!(
+                        org_id = %period.org_id,
+                        result = ?result,
+                        "Invoiced overage immediately for past-due subscription"
+                    ),
+                    Err(e) => tracing::warn!(
+                        org_id = %period.org_id,
+                        error = %e,
+                        "Failed to invoice overage for past-due subscription"
+                    ),
+                }
+            }
+        }
+
+        Ok(charge)
+    }
+
+    /// Recalculate current overages for every eligible org (worker job cycle)
+    pub async fn recalculate_all(
+        &self,
+        spend_cap: &SpendCapService,
+    ) -> BillingResult<OverageRecalculationSummary> {
+        let periods = self.list_overage_periods().await?;
+        let mut summary = OverageRecalculationSummary {
+            total_orgs: periods.len(),
+            ..Default::default()
+        };
+
+        for period in &periods {
+            match self.recalculate_period(period, spend_cap).await {
+                Ok(Some(_)) => summary.updated += 1,
+                Ok(None) => {} // No overage (within limits)
+                Err(e) => {
+                    tracing::error!(org_id = %period.org_id, error = %e, "Failed to calculate overage");
+                    summary.errors += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Recalculate one org's current overage on demand
+    ///
+    /// Runs the same logic as the worker job cycle for a single org, so support
+    /// can fix a stale overage figure without waiting for the next run. Returns
+    /// `None` when the org is within its limits or has no eligible billing period.
+    pub async fn recalculate(
+        &self,
+        org_id: Uuid,
+        spend_cap: &SpendCapService,
+    ) -> BillingResult<Option<OverageCharge>> {
+        let period: Option<OverageBillingPeriod> =
+            sqlx::query_as(&format!("{} AND o.id = $1 LIMIT 1", OVERAGE_PERIODS_SELECT))
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        let Some(period) = period else {
+            tracing::info!(org_id = %org_id, "No active Pro/Team billing period to recalculate");
+            return Ok(None);
+        };
+
+        let charge = self.recalculate_period(&period, spend_cap).await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            charge_id = ?charge.as_ref().map(|c| c.id),
+            total_charge_cents = ?charge.as_ref().map(|c| c.total_charge_cents),
+            "Recalculated overage on demand"
+        );
+
+        Ok(charge)
     }
 
     /// Sync early payment status by checking Stripe invoice status.
@@ -1301,3 +2203,447 @@ impl OverageService {
         Ok(rows_affected)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Duration;
+
+    fn charge(org_id: Uuid, created_at: OffsetDateTime, actual_usage: i64) -> OverageCharge {
+        let period_start = OffsetDateTime::UNIX_EPOCH + Duration::days(20_000);
+        let overage_amount = actual_usage - 50_000;
+        OverageCharge {
+            id: Uuid::new_v4(),
+            org_id,
+            billing_period_start: period_start,
+            billing_period_end: period_start + Duration::days(30),
+            resource_type: "requests".to_string(),
+            base_limit: 50_000,
+            actual_usage,
+            overage_amount,
+            rate_per_unit_cents: 50,
+            total_charge_cents: OverageRates::default()
+                .calculate_request_overage_cents(overage_amount),
+            stripe_invoice_item_id: None,
+            status: "pending".to_string(),
+            created_at,
+            invoiced_at: None,
+            paid_at: None,
+        }
+    }
+
+    #[test]
+    fn test_hard_stop_accrues_no_overage() {
+        let limit = 50_000;
+
+        assert!(overage_accrues(OverageMode::Allow, limit + 1, limit));
+        assert!(!overage_accrues(OverageMode::Allow, limit, limit));
+        assert!(!overage_accrues(OverageMode::Allow, limit + 1, i64::MAX));
+
+        // Even far past the limit, HardStop never produces an overage row
+        assert!(!overage_accrues(OverageMode::HardStop, limit + 1, limit));
+        assert!(!overage_accrues(OverageMode::HardStop, limit * 10, limit));
+    }
+
+    #[test]
+    fn test_dedupe_merges_two_duplicates_into_one() {
+        let org_id = Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+        let older = charge(org_id, now - Duration::hours(2), 52_000);
+        let newer = charge(org_id, now, 55_000);
+
+        let merges = plan_charge_dedupe(&[newer.clone(), older.clone()]);
+
+        assert_eq!(
+            merges,
+            vec![ChargeMerge {
+                keep_id: older.id,
+                actual_usage: 55_000,
+                overage_amount: 5_000,
+                total_charge_cents: newer.total_charge_cents,
+                void_ids: vec![newer.id],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_dedupe_keeps_checkout_in_flight() {
+        let org_id = Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+        let older = charge(org_id, now - Duration::hours(2), 52_000);
+        let mut awaiting = charge(org_id, now, 52_000);
+        awaiting.status = "awaiting_payment".to_string();
+
+        let merges = plan_charge_dedupe(&[older.clone(), awaiting.clone()]);
+
+        assert_eq!(merges.len(), 1);
+        assert_eq!(merges[0].keep_id, awaiting.id);
+        assert_eq!(merges[0].void_ids, vec![older.id]);
+    }
+
+    #[test]
+    fn test_dedupe_leaves_distinct_periods_alone() {
+        let now = OffsetDateTime::now_utc();
+        let a = charge(Uuid::new_v4(), now, 52_000);
+        let b = charge(Uuid::new_v4(), now, 52_000);
+        let mut next_period = charge(a.org_id, now, 52_000);
+        next_period.billing_period_start += Duration::days(30);
+        next_period.billing_period_end += Duration::days(30);
+
+        assert!(plan_charge_dedupe(&[a, b, next_period]).is_empty());
+    }
+
+    #[test]
+    fn test_recompute_updates_pending_to_corrected_usage() {
+        let org_id = Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+        let rates = OverageRates::default();
+
+        // Dedup drops usage from 58k to 55k; nothing settled yet
+        let corrected = charge(org_id, now, 55_000);
+        assert_eq!(
+            unbilled_overage(5_000, corrected.total_charge_cents, &[]),
+            Some((5_000, corrected.total_charge_cents))
+        );
+
+        // With 52k already paid early, only the remainder stays pending
+        let mut paid = charge(org_id, now - Duration::days(3), 52_000);
+        paid.status = "paid".to_string();
+        let settled = [paid.clone()];
+        assert_eq!(
+            unbilled_overage(5_000, corrected.total_charge_cents, &settled),
+            Some((
+                3_000,
+                rates.calculate_request_overage_cents(5_000) - paid.total_charge_cents
+            ))
+        );
+        assert_eq!(
+            overage_discrepancy(
+                org_id,
+                corrected.billing_period_start,
+                corrected.total_charge_cents,
+                &settled
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_recompute_flags_paid_charge_above_corrected_usage() {
+        let org_id = Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+
+        // 60k was invoiced and paid, a dedup corrects usage down to 54k
+        let mut paid = charge(org_id, now - Duration::days(3), 60_000);
+        paid.status = "paid".to_string();
+        let corrected = charge(org_id, now, 54_000);
+        let settled = [paid.clone()];
+
+        assert_eq!(
+            unbilled_overage(4_000, corrected.total_charge_cents, &settled),
+            None
+        );
+        let discrepancy = overage_discrepancy(
+            org_id,
+            paid.billing_period_start,
+            corrected.total_charge_cents,
+            &settled,
+        )
+        .unwrap();
+        assert_eq!(discrepancy.settled_cents, paid.total_charge_cents);
+        assert_eq!(discrepancy.corrected_cents, corrected.total_charge_cents);
+        assert_eq!(
+            discrepancy.overbilled_cents(),
+            paid.total_charge_cents - corrected.total_charge_cents
+        );
+        assert_eq!(discrepancy.charge_ids, vec![paid.id]);
+
+        // Usage corrected below the limit: everything settled is overbilled
+        let discrepancy =
+            overage_discrepancy(org_id, paid.billing_period_start, 0, &settled).unwrap();
+        assert_eq!(discrepancy.overbilled_cents(), paid.total_charge_cents);
+    }
+
+    #[test]
+    fn test_past_due_overage_policy() {
+        assert_eq!(
+            PastDueOveragePolicy::default(),
+            PastDueOveragePolicy::Accumulate
+        );
+        assert_eq!(
+            " Pause ".parse::<PastDueOveragePolicy>().unwrap(),
+            PastDueOveragePolicy::Pause
+        );
+        assert!(matches!(
+            "stop".parse::<PastDueOveragePolicy>(),
+            Err(BillingError::Config(_))
+        ));
+
+        for policy in [
+            PastDueOveragePolicy::Accumulate,
+            PastDueOveragePolicy::Pause,
+            PastDueOveragePolicy::Collect,
+        ] {
+            // Policy only applies while past due
+            assert!(!policy.freezes(false));
+            assert!(!policy.collects(false));
+        }
+        assert!(!PastDueOveragePolicy::Accumulate.freezes(true));
+        assert!(!PastDueOveragePolicy::Accumulate.collects(true));
+        assert!(PastDueOveragePolicy::Pause.freezes(true));
+        assert!(!PastDueOveragePolicy::Pause.collects(true));
+        assert!(!PastDueOveragePolicy::Collect.freezes(true));
+        assert!(PastDueOveragePolicy::Collect.collects(true));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_past_due_overage_follows_policy() {
+        use crate::client::StripeConfig;
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = |policy| {
+            OverageService::with_rates(
+                StripeClient::new(StripeConfig::for_tests()),
+                pool.clone(),
+                OverageRates::default(),
+            )
+            .with_past_due_policy(policy)
+        };
+
+        let org_id = Uuid::new_v4();
+        let period_start = OffsetDateTime::now_utc() - Duration::days(3);
+        let period_end = period_start + Duration::days(30);
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Past Due', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("past-due-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (org_id, status, current_period_start, current_period_end)
+            VALUES ($1, 'past_due', $2, $3)
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start)
+        .bind(period_end)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // 51,500 requests on Pro (50K limit)
+        sqlx::query(
+            r#"
+            INSERT INTO usage_records (org_id, request_count, period_start, period_end)
+            VALUES ($1, 51500, $2, $2 + interval '1 day')
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start + Duration::days(1))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Paused: no charge accrues while past due
+        let charge = service(PastDueOveragePolicy::Pause)
+            .create_or_update_current_overage(org_id, "pro", period_start, period_end)
+            .await
+            .unwrap();
+        assert!(charge.is_none());
+
+        // Accumulate: charge accrues and is flagged for review
+        let charge = service(PastDueOveragePolicy::Accumulate)
+            .create_or_update_current_overage(org_id, "pro", period_start, period_end)
+            .await
+            .unwrap()
+            .expect("overage charge");
+        assert_eq!(charge.overage_amount, 1_500);
+        let flagged: bool =
+            sqlx::query_scalar("SELECT accrued_while_past_due FROM overage_charges WHERE id = $1")
+                .bind(charge.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(flagged);
+
+        // Paused again after more usage: the pending charge stays frozen
+        sqlx::query("UPDATE usage_records SET request_count = 60000 WHERE org_id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let frozen = service(PastDueOveragePolicy::Pause)
+            .create_or_update_current_overage(org_id, "pro", period_start, period_end)
+            .await
+            .unwrap()
+            .expect("pending charge");
+        assert_eq!(frozen.id, charge.id);
+        assert_eq!(frozen.overage_amount, 1_500);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_switch_to_hard_stop_keeps_accrued_overage() {
+        use crate::client::StripeConfig;
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = OverageService::with_rates(
+            StripeClient::new(StripeConfig::for_tests()),
+            pool.clone(),
+            OverageRates::default(),
+        );
+
+        let org_id = Uuid::new_v4();
+        let period_start = OffsetDateTime::now_utc() - Duration::days(3);
+        let period_end = period_start + Duration::days(30);
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Hard Stop', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("hard-stop-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        // 51,500 requests on Pro (50K limit) while overage is allowed
+        sqlx::query(
+            r#"
+            INSERT INTO usage_records (org_id, request_count, period_start, period_end)
+            VALUES ($1, 51500, $2, $2 + interval '1 day')
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start + Duration::days(1))
+        .execute(&pool)
+        .await
+        .unwrap();
+        let charge = service
+            .create_or_update_current_overage(org_id, "pro", period_start, period_end)
+            .await
+            .unwrap()
+            .expect("overage charge");
+        assert_eq!(charge.overage_amount, 1_500);
+
+        // Mid-period switch to HardStop; later usage doesn't add to the charge
+        sqlx::query("UPDATE organizations SET overage_mode = 'hard_stop' WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE usage_records SET request_count = 60000 WHERE org_id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let frozen = service
+            .create_or_update_current_overage(org_id, "pro", period_start, period_end)
+            .await
+            .unwrap()
+            .expect("accrued charge kept");
+        assert_eq!(frozen.id, charge.id);
+        assert_eq!(frozen.overage_amount, 1_500);
+        assert_eq!(frozen.total_charge_cents, charge.total_charge_cents);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_invoice_now_charge_status_transitions() {
+        use crate::client::StripeConfig;
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = OverageService::with_rates(
+            StripeClient::new(StripeConfig::for_tests()),
+            pool.clone(),
+            OverageRates::default(),
+        );
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Invoice Now', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("invoice-now-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        let period_start = OffsetDateTime::now_utc() - Duration::days(60);
+        for months in 0..2 {
+            sqlx::query(
+                r#"
+                INSERT INTO overage_charges (
+                    org_id, billing_period_start, billing_period_end,
+                    resource_type, base_limit, actual_usage, overage_amount,
+                    rate_per_unit_cents, total_charge_cents, status
+                )
+                VALUES ($1, $2, $2 + interval '30 days', 'requests', 50000, 51000, 1000, 50, 50, 'pending')
+                "#,
+            )
+            .bind(org_id)
+            .bind(period_start + Duration::days(30 * months))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let statuses = || async {
+            sqlx::query_as::<_, (Uuid, String, Option<String>)>(
+                "SELECT id, status, stripe_invoice_item_id FROM overage_charges WHERE org_id = $1 ORDER BY billing_period_start",
+            )
+            .bind(org_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+        };
+
+        // pending -> processing; a second claim finds nothing
+        let claimed = service.claim_pending_charges(org_id).await.unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert!(statuses().await.iter().all(|(_, s, _)| s == "processing"));
+        assert!(service
+            .claim_pending_charges(org_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // processing -> invoiced with the item id
+        let first = claimed[0].id;
+        service
+            .mark_charges_invoiced(&[(first, "ii_first".to_string())])
+            .await
+            .unwrap();
+
+        // A failed invoice returns the rest to pending; invoiced charges stay put
+        let ids: Vec<Uuid> = claimed.iter().map(|c| c.id).collect();
+        service.reset_processing_charges(&ids).await;
+        let after = statuses().await;
+        assert_eq!(
+            after,
+            vec![
+                (first, "invoiced".to_string(), Some("ii_first".to_string())),
+                (claimed[1].id, "pending".to_string(), None),
+            ]
+        );
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
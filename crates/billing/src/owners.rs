@@ -0,0 +1,90 @@
+//! Organization owner lookup
+//!
+//! An organization can have several owners. Billing emails and Stripe customer
+//! creation need a single "primary" owner, which must not change between
+//! calls: it is the earliest-created owner account, with ties broken by user id.
+
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::error::BillingResult;
+
+/// An owner of an organization
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct OrgOwner {
+    pub user_id: Uuid,
+    pub email: String,
+    pub org_name: String,
+    pub created_at: OffsetDateTime,
+}
+
+/// Pick the primary owner: earliest `created_at`, then lowest `user_id`
+pub fn primary_owner(owners: &[OrgOwner]) -> Option<&OrgOwner> {
+    owners.iter().min_by_key(|o| (o.created_at, o.user_id))
+}
+
+/// List all owners of an organization, primary owner first
+pub async fn list_org_owners(pool: &PgPool, org_id: Uuid) -> BillingResult<Vec<OrgOwner>> {
+    let owners: Vec<OrgOwner> = sqlx::query_as(
+        r#"
+        SELECT u.id AS user_id, u.email, o.name AS org_name, u.created_at
+        FROM users u
+        JOIN organizations o ON o.id = u.org_id
+        WHERE u.org_id = $1 AND u.role = 'owner'
+        ORDER BY u.created_at ASC, u.id ASC
+        "#,
+    )
+    .bind(org_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(owners)
+}
+
+/// Get the primary owner of an organization, if it has any owner
+pub async fn get_primary_owner(pool: &PgPool, org_id: Uuid) -> BillingResult<Option<OrgOwner>> {
+    let owners = list_org_owners(pool, org_id).await?;
+    Ok(primary_owner(&owners).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner(user_id: u128, created_at: i64) -> OrgOwner {
+        OrgOwner {
+            user_id: Uuid::from_u128(user_id),
+            email: format!("owner{}@example.com", user_id),
+            org_name: "Acme".to_string(),
+            created_at: OffsetDateTime::from_unix_timestamp(created_at).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_primary_owner_is_earliest_created() {
+        let owners = vec![owner(1, 300), owner(2, 100), owner(3, 200)];
+        assert_eq!(primary_owner(&owners).unwrap().user_id, Uuid::from_u128(2));
+    }
+
+    #[test]
+    fn test_primary_owner_independent_of_order() {
+        let mut owners = vec![owner(1, 300), owner(2, 100), owner(3, 200)];
+        let first = primary_owner(&owners).cloned();
+        owners.reverse();
+        assert_eq!(primary_owner(&owners).cloned(), first);
+        owners.rotate_left(1);
+        assert_eq!(primary_owner(&owners).cloned(), first);
+    }
+
+    #[test]
+    fn test_primary_owner_tie_broken_by_user_id() {
+        let owners = vec![owner(9, 100), owner(4, 100), owner(7, 100)];
+        assert_eq!(primary_owner(&owners).unwrap().user_id, Uuid::from_u128(4));
+    }
+
+    #[test]
+    fn test_no_owners() {
+        assert!(primary_owner(&[]).is_none());
+    }
+}
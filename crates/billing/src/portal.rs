@@ -1,19 +1,198 @@
 //! Stripe Billing Portal
+//!
+//! Portal sessions are handed out through short-lived, one-time links
+//! (`/billing/portal?token=...`). The token is signed with HMAC-SHA256 and
+//! binds the org and an expiry; it is validated server-side and its nonce
+//! recorded before a fresh Stripe portal session is created, so a shared or
+//! replayed URL can't open someone else's portal.
 
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::PgPool;
 use stripe::{BillingPortalSession, CreateBillingPortalSession, CustomerId};
+use time::{Duration, OffsetDateTime};
 use uuid::Uuid;
 
 use crate::client::StripeClient;
 use crate::error::{BillingError, BillingResult};
 
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a portal link stays valid after it is issued
+pub const PORTAL_LINK_TTL: Duration = Duration::minutes(2);
+
+/// Domain separation for the signing key, which is shared with the Stripe client
+const PORTAL_LINK_CONTEXT: &str = "plexmcp-billing-portal-link";
+
+/// Verified contents of a portal link token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortalLinkToken {
+    pub org_id: Uuid,
+    pub nonce: Uuid,
+    pub expires_at: OffsetDateTime,
+}
+
+/// HMAC over a token's fields
+fn portal_link_mac(
+    key: &[u8],
+    org_id: Uuid,
+    nonce: Uuid,
+    expires_at: i64,
+) -> BillingResult<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(key)
+        .map_err(|_| BillingError::Internal("Invalid portal link signing key".to_string()))?;
+    mac.update(
+        format!(
+            "{}:{}.{}.{}",
+            PORTAL_LINK_CONTEXT,
+            org_id.simple(),
+            nonce.simple(),
+            expires_at
+        )
+        .as_bytes(),
+    );
+    Ok(mac)
+}
+
+/// Sign a portal link token: `org.nonce.expires_at.signature`
+fn sign_portal_link(
+    key: &[u8],
+    org_id: Uuid,
+    nonce: Uuid,
+    expires_at: OffsetDateTime,
+) -> BillingResult<String> {
+    let expires_at = expires_at.unix_timestamp();
+    let signature = portal_link_mac(key, org_id, nonce, expires_at)?
+        .finalize()
+        .into_bytes();
+    Ok(format!(
+        "{}.{}.{}.{}",
+        org_id.simple(),
+        nonce.simple(),
+        expires_at,
+        hex::encode(signature)
+    ))
+}
+
+/// Validate a portal link token for `org_id` at `now`
+///
+/// Checks the signature (in constant time), then expiry, then that the token
+/// was issued to `org_id`. Does not check one-time use.
+fn verify_portal_link(
+    key: &[u8],
+    token: &str,
+    org_id: Uuid,
+    now: OffsetDateTime,
+) -> BillingResult<PortalLinkToken> {
+    let invalid = || BillingError::Unauthorized("Invalid portal link".to_string());
+
+    let mut parts = token.split('.');
+    let (Some(token_org), Some(nonce), Some(expires_at), Some(signature), None) = (
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+        parts.next(),
+    ) else {
+        return Err(invalid());
+    };
+    let token_org: Uuid = token_org.parse().map_err(|_| invalid())?;
+    let nonce: Uuid = nonce.parse().map_err(|_| invalid())?;
+    let expires_at: i64 = expires_at.parse().map_err(|_| invalid())?;
+    let signature = hex::decode(signature).map_err(|_| invalid())?;
+
+    portal_link_mac(key, token_org, nonce, expires_at)?
+        .verify_slice(&signature)
+        .map_err(|_| invalid())?;
+
+    let expires_at = OffsetDateTime::from_unix_timestamp(expires_at).map_err(|_| invalid())?;
+    if now >= expires_at {
+        return Err(BillingError::Unauthorized(
+            "Portal link has expired".to_string(),
+        ));
+    }
+    if token_org != org_id {
+        return Err(BillingError::Unauthorized(
+            "Portal link was issued to a different organization".to_string(),
+        ));
+    }
+
+    Ok(PortalLinkToken {
+        org_id: token_org,
+        nonce,
+        expires_at,
+    })
+}
+
 /// Portal service for Stripe billing portal sessions
 pub struct PortalService {
     stripe: StripeClient,
+    pool: PgPool,
 }
 
 impl PortalService {
-    pub fn new(stripe: StripeClient) -> Self {
-        Self { stripe }
+    pub fn new(stripe: StripeClient, pool: PgPool) -> Self {
+        Self { stripe, pool }
+    }
+
+    /// Key for signing portal links (the Stripe secret key, never sent to clients)
+    fn link_key(&self) -> &[u8] {
+        self.stripe.config().secret_key.as_bytes()
+    }
+
+    /// Issue a one-time portal link token for an org, valid for `PORTAL_LINK_TTL`
+    pub fn create_portal_link_token(&self, org_id: Uuid) -> BillingResult<String> {
+        sign_portal_link(
+            self.link_key(),
+            org_id,
+            Uuid::new_v4(),
+            OffsetDateTime::now_utc() + PORTAL_LINK_TTL,
+        )
+    }
+
+    /// Redeem a portal link token and create a fresh portal session
+    ///
+    /// The token must be validly signed, unexpired, issued to `org_id` and not
+    /// redeemed before; otherwise returns `Unauthorized`.
+    pub async fn redeem_portal_link(
+        &self,
+        token: &str,
+        org_id: Uuid,
+        customer_id: &str,
+    ) -> BillingResult<BillingPortalSession> {
+        let link = verify_portal_link(self.link_key(), token, org_id, OffsetDateTime::now_utc())?;
+
+        // Expired nonces can no longer verify, so they don't need to be kept
+        sqlx::query("DELETE FROM portal_link_redemptions WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        let redeemed = sqlx::query(
+            r#"
+            INSERT INTO portal_link_redemptions (nonce, org_id, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (nonce) DO NOTHING
+            "#,
+        )
+        .bind(link.nonce)
+        .bind(link.org_id)
+        .bind(link.expires_at)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if redeemed == 0 {
+            tracing::warn!(
+                org_id = %org_id,
+                nonce = %link.nonce,
+                "Rejected replayed billing portal link"
+            );
+            return Err(BillingError::Unauthorized(
+                "Portal link has already been used".to_string(),
+            ));
+        }
+
+        self.create_portal_session(org_id, customer_id).await
     }
 
     /// Create a billing portal session for a customer
@@ -54,3 +233,93 @@ impl From<BillingPortalSession> for PortalResponse {
         Self { url: session.url }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"sk_test_portal_link_key";
+
+    #[test]
+    fn test_valid_portal_link() {
+        let org_id = Uuid::new_v4();
+        let nonce = Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+        let expires_at = now + PORTAL_LINK_TTL;
+        let token = sign_portal_link(KEY, org_id, nonce, expires_at).unwrap();
+
+        let link = verify_portal_link(KEY, &token, org_id, now).unwrap();
+        assert_eq!(link.org_id, org_id);
+        assert_eq!(link.nonce, nonce);
+        assert_eq!(
+            link.expires_at.unix_timestamp(),
+            expires_at.unix_timestamp()
+        );
+    }
+
+    #[test]
+    fn test_expired_portal_link() {
+        let org_id = Uuid::new_v4();
+        let issued = OffsetDateTime::now_utc() - Duration::minutes(10);
+        let token =
+            sign_portal_link(KEY, org_id, Uuid::new_v4(), issued + PORTAL_LINK_TTL).unwrap();
+
+        let err = verify_portal_link(KEY, &token, org_id, OffsetDateTime::now_utc()).unwrap_err();
+        assert!(matches!(err, BillingError::Unauthorized(msg) if msg.contains("expired")));
+    }
+
+    #[test]
+    fn test_wrong_org_portal_link() {
+        let now = OffsetDateTime::now_utc();
+        let token =
+            sign_portal_link(KEY, Uuid::new_v4(), Uuid::new_v4(), now + PORTAL_LINK_TTL).unwrap();
+
+        let err = verify_portal_link(KEY, &token, Uuid::new_v4(), now).unwrap_err();
+        assert!(
+            matches!(err, BillingError::Unauthorized(msg) if msg.contains("different organization"))
+        );
+    }
+
+    #[test]
+    fn test_tampered_portal_link() {
+        let org_id = Uuid::new_v4();
+        let other_org = Uuid::new_v4();
+        let now = OffsetDateTime::now_utc();
+        let token = sign_portal_link(KEY, org_id, Uuid::new_v4(), now + PORTAL_LINK_TTL).unwrap();
+
+        // Re-pointing the token at another org or extending it breaks the signature
+        let retargeted = token.replacen(
+            &org_id.simple().to_string(),
+            &other_org.simple().to_string(),
+            1,
+        );
+        let parts: Vec<&str> = token.split('.').collect();
+        let extended = format!(
+            "{}.{}.{}.{}",
+            parts[0],
+            parts[1],
+            (now + Duration::days(1)).unix_timestamp(),
+            parts[3]
+        );
+        let other_key =
+            sign_portal_link(b"other", org_id, Uuid::new_v4(), now + PORTAL_LINK_TTL).unwrap();
+
+        for token in [
+            retargeted.as_str(),
+            extended.as_str(),
+            other_key.as_str(),
+            "",
+            "a.b.c.d",
+        ] {
+            let err = verify_portal_link(KEY, token, org_id, now).unwrap_err();
+            assert!(
+                matches!(&err, BillingError::Unauthorized(msg) if msg == "Invalid portal link"),
+                "token {:?} gave {:?}",
+                token,
+                err
+            );
+        }
+        // The retargeted token is also rejected for the org it now names
+        assert!(verify_portal_link(KEY, &retargeted, other_org, now).is_err());
+    }
+}
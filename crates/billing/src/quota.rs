@@ -0,0 +1,373 @@
+//! Soft quota warnings
+//!
+//! Before the hard monthly request limit is enforced, orgs that cross a soft
+//! limit (90% by default) get an `X-Quota-Warning` header on proxied responses
+//! and a single event + email per billing period. Requests are never blocked
+//! here; blocking stays with the hard limit check.
+//!
+//! ## Configuration
+//!
+//! - `QUOTA_SOFT_LIMIT_PERCENT`: soft limit as a percentage of the monthly
+//!   request limit (1-99, default: 90)
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::email::BillingEmailService;
+use crate::error::BillingResult;
+use crate::events::{BillingEventBuilder, BillingEventLogger, BillingEventType};
+use crate::owners::get_primary_owner;
+
+/// Response header carrying the soft limit warning
+pub const QUOTA_WARNING_HEADER: &str = "x-quota-warning";
+
+/// Default soft limit as a percentage of the monthly request limit
+const DEFAULT_SOFT_LIMIT_PERCENT: u8 = 90;
+
+/// Get configured soft limit percentage
+pub fn get_soft_limit_percent() -> u8 {
+    static PERCENT: OnceLock<u8> = OnceLock::new();
+    *PERCENT.get_or_init(|| {
+        std::env::var("QUOTA_SOFT_LIMIT_PERCENT")
+            .ok()
+            .and_then(|v| v.trim().parse::<u8>().ok())
+            .filter(|p| (1..100).contains(p))
+            .unwrap_or(DEFAULT_SOFT_LIMIT_PERCENT)
+    })
+}
+
+/// Usage at or above the soft limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaWarning {
+    pub requests_used: i64,
+    pub requests_limit: u64,
+    /// Usage as a whole percentage of the limit (rounded down)
+    pub percent_used: u64,
+    /// Soft limit that was crossed
+    pub soft_limit_percent: u8,
+}
+
+impl QuotaWarning {
+    /// Check usage against the soft limit
+    ///
+    /// Returns `None` below the soft limit and for unlimited tiers.
+    pub fn check(requests_used: i64, requests_limit: u64, soft_limit_percent: u8) -> Option<Self> {
+        if requests_limit == 0 || requests_limit == u64::MAX || requests_used <= 0 {
+            return None;
+        }
+        let used = requests_used as u128;
+        let limit = requests_limit as u128;
+        if used * 100 < limit * soft_limit_percent as u128 {
+            return None;
+        }
+        Some(Self {
+            requests_used,
+            requests_limit,
+            percent_used: (used * 100 / limit).min(u64::MAX as u128) as u64,
+            soft_limit_percent,
+        })
+    }
+
+    /// Value for the `X-Quota-Warning` header
+    pub fn header_value(&self) -> String {
+        format!(
+            "{}% of monthly request quota used ({}/{})",
+            self.percent_used, self.requests_used, self.requests_limit
+        )
+    }
+}
+
+/// Maximum orgs tracked by `SeenPeriods` before the oldest entry is evicted
+const MAX_SEEN_ORGS: usize = 10_000;
+
+/// Latest billing period already handled for each org by this process
+///
+/// Skips the notification task and its DB round trip on every request above
+/// the soft limit. Holds one entry per org (a new period replaces the old
+/// one) and at most `capacity` orgs. An evicted org only costs one more
+/// insert, which the `quota_warning_notifications` unique key turns away.
+#[derive(Debug)]
+struct SeenPeriods {
+    periods: Mutex<HashMap<Uuid, i64>>,
+    capacity: usize,
+}
+
+impl Default for SeenPeriods {
+    fn default() -> Self {
+        Self::with_capacity(MAX_SEEN_ORGS)
+    }
+}
+
+impl SeenPeriods {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            periods: Mutex::new(HashMap::new()),
+            capacity,
+        }
+    }
+
+    /// Returns `true` only the first time for a given org and period
+    fn first_seen(&self, org_id: Uuid, period_start: OffsetDateTime) -> bool {
+        let period = period_start.unix_timestamp();
+        let mut periods = self.periods.lock().unwrap_or_else(|e| e.into_inner());
+        if periods.get(&org_id).is_some_and(|seen| *seen >= period) {
+            return false;
+        }
+
+        if periods.len() >= self.capacity && !periods.contains_key(&org_id) {
+            if let Some(oldest) = periods
+                .iter()
+                .min_by_key(|(_, seen)| **seen)
+                .map(|(id, _)| *id)
+            {
+                periods.remove(&oldest);
+            }
+        }
+        periods.insert(org_id, period);
+        true
+    }
+}
+
+/// Sends the one-time soft limit event and email
+#[derive(Clone)]
+pub struct QuotaWarningService {
+    pool: PgPool,
+    email: BillingEmailService,
+    seen: Arc<SeenPeriods>,
+}
+
+impl QuotaWarningService {
+    pub fn new(pool: PgPool, email: BillingEmailService) -> Self {
+        Self {
+            pool,
+            email,
+            seen: Arc::new(SeenPeriods::default()),
+        }
+    }
+
+    /// Whether this process still has to notify the org for this period
+    ///
+    /// Cheap, in-memory and `true` at most once per org and period; check it
+    /// before spawning `notify_soft_limit` for a request above the soft limit.
+    pub fn should_notify(&self, org_id: Uuid, period_start: OffsetDateTime) -> bool {
+        self.seen.first_seen(org_id, period_start)
+    }
+
+    /// Emit the soft limit event and email once per billing period
+    ///
+    /// Returns `true` if this call sent the notification. The
+    /// `quota_warning_notifications` unique key keeps it to one even across
+    /// API instances.
+    pub async fn notify_soft_limit(
+        &self,
+        org_id: Uuid,
+        period_start: OffsetDateTime,
+        warning: QuotaWarning,
+    ) -> BillingResult<bool> {
+        let inserted: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            INSERT INTO quota_warning_notifications
+                (org_id, billing_period_start, threshold_percent, requests_used, requests_limit)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (org_id, billing_period_start, threshold_percent) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start)
+        .bind(warning.soft_limit_percent as i32)
+        .bind(warning.requests_used)
+        .bind(warning.requests_limit.min(i64::MAX as u64) as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if inserted.is_none() {
+            return Ok(false);
+        }
+
+        tracing::info!(
+            org_id = %org_id,
+            requests_used = warning.requests_used,
+            requests_limit = warning.requests_limit,
+            soft_limit_percent = warning.soft_limit_percent,
+            "Org crossed soft quota limit"
+        );
+
+        let event_logger = BillingEventLogger::new(self.pool.clone());
+        if let Err(e) = event_logger
+            .log_event(
+                BillingEventBuilder::new(org_id, BillingEventType::QuotaSoftLimit).data(
+                    serde_json::json!({
+                        "requests_used": warning.requests_used,
+                        "requests_limit": warning.requests_limit,
+                        "percent_used": warning.percent_used,
+                        "soft_limit_percent": warning.soft_limit_percent,
+                    }),
+                ),
+            )
+            .await
+        {
+            tracing::warn!(org_id = %org_id, error = %e, "Failed to log soft quota event");
+        }
+
+        match get_primary_owner(&self.pool, org_id).await {
+            Ok(Some(owner)) => {
+                if let Err(e) = self
+                    .email
+                    .send_quota_soft_limit(
+                        &owner.email,
+                        &owner.org_name,
+                        warning.percent_used,
+                        warning.requests_used,
+                        warning.requests_limit,
+                    )
+                    .await
+                {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to send soft quota warning email"
+                    );
+                }
+            }
+            Ok(None) => {
+                tracing::warn!(org_id = %org_id, "No owner to notify of soft quota limit");
+            }
+            Err(e) => {
+                tracing::error!(org_id = %org_id, error = %e, "Failed to look up org owner");
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_below_soft_limit_no_warning() {
+        assert_eq!(QuotaWarning::check(44_999, 50_000, 90), None);
+        assert_eq!(QuotaWarning::check(0, 50_000, 90), None);
+    }
+
+    #[test]
+    fn test_soft_limit_crossed() {
+        let warning = QuotaWarning::check(45_000, 50_000, 90).unwrap();
+        assert_eq!(warning.percent_used, 90);
+        assert_eq!(
+            warning.header_value(),
+            "90% of monthly request quota used (45000/50000)"
+        );
+
+        // Still a warning (not a block) past the hard limit
+        let over = QuotaWarning::check(60_000, 50_000, 90).unwrap();
+        assert_eq!(over.percent_used, 120);
+    }
+
+    #[test]
+    fn test_unlimited_tier_never_warns() {
+        assert_eq!(QuotaWarning::check(i64::MAX, u64::MAX, 90), None);
+        assert_eq!(QuotaWarning::check(10, 0, 90), None);
+    }
+
+    #[test]
+    fn test_default_soft_limit_percent() {
+        let percent = get_soft_limit_percent();
+        assert!((1..100).contains(&percent));
+    }
+
+    const JANUARY: i64 = 1_767_225_600;
+    const FEBRUARY: i64 = 1_769_904_000;
+
+    fn period(unix: i64) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(unix).unwrap()
+    }
+
+    #[test]
+    fn test_notification_seen_once_per_period() {
+        let seen = SeenPeriods::default();
+        let org_id = Uuid::new_v4();
+
+        assert!(seen.first_seen(org_id, period(JANUARY)));
+        assert!(!seen.first_seen(org_id, period(JANUARY)));
+        assert!(seen.first_seen(org_id, period(FEBRUARY)));
+        // The new period replaced the old one; a late request for it is not re-sent
+        assert!(!seen.first_seen(org_id, period(JANUARY)));
+        assert_eq!(seen.periods.lock().unwrap().len(), 1);
+        assert!(seen.first_seen(Uuid::new_v4(), period(JANUARY)));
+    }
+
+    #[test]
+    fn test_seen_periods_evicts_oldest_at_capacity() {
+        let seen = SeenPeriods::with_capacity(2);
+        let (stale, current, new) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(seen.first_seen(stale, period(JANUARY)));
+        assert!(seen.first_seen(current, period(FEBRUARY)));
+        assert!(seen.first_seen(new, period(FEBRUARY)));
+
+        let periods = seen.periods.lock().unwrap().clone();
+        assert_eq!(periods.len(), 2);
+        assert!(!periods.contains_key(&stale));
+        // Kept orgs are still deduplicated in memory
+        assert!(!seen.first_seen(current, period(FEBRUARY)));
+        assert!(!seen.first_seen(new, period(FEBRUARY)));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_soft_limit_notified_once_across_instances() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let org_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO organizations (id, name, slug) VALUES ($1, 'Quota Org', $2)")
+            .bind(org_id)
+            .bind(format!("quota-org-{}", org_id))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let email = BillingEmailService::new(crate::email::EmailConfig {
+            resend_api_key: String::new(),
+            ..crate::email::EmailConfig::from_env()
+        });
+        let warning = QuotaWarning::check(45_000, 50_000, 90).unwrap();
+        let first = QuotaWarningService::new(pool.clone(), email.clone());
+        let second = QuotaWarningService::new(pool.clone(), email);
+
+        assert!(first.should_notify(org_id, period(JANUARY)));
+        assert!(first
+            .notify_soft_limit(org_id, period(JANUARY), warning)
+            .await
+            .unwrap());
+        assert!(!first.should_notify(org_id, period(JANUARY)));
+
+        // Another API instance has its own marker but the stored one stops it
+        assert!(second.should_notify(org_id, period(JANUARY)));
+        assert!(!second
+            .notify_soft_limit(org_id, period(JANUARY), warning)
+            .await
+            .unwrap());
+
+        let stored: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM quota_warning_notifications WHERE org_id = $1",
+        )
+        .bind(org_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stored, 1);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
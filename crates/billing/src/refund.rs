@@ -3,14 +3,56 @@
 //! Handles issuing actual refunds to payment methods (vs credits which use prorations).
 //! Used for immediate downgrades when admin selects "refund" instead of "credit".
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use stripe::{CreateRefund, Invoice, Refund, RefundReasonFilter};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::client::StripeClient;
+use crate::email::BillingEmailService;
 use crate::error::{BillingError, BillingResult};
+use crate::owners::get_primary_owner;
+
+/// Stripe's structured refund reason, used for finance reconciliation
+///
+/// The admin's free-text explanation is kept separately as the refund note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "VARCHAR", rename_all = "snake_case")]
+pub enum RefundReason {
+    Duplicate,
+    Fraudulent,
+    #[default]
+    RequestedByCustomer,
+}
+
+impl RefundReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Duplicate => "duplicate",
+            Self::Fraudulent => "fraudulent",
+            Self::RequestedByCustomer => "requested_by_customer",
+        }
+    }
+
+    /// Customer-facing wording for receipts
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Duplicate => "Duplicate charge",
+            Self::Fraudulent => "Fraudulent charge",
+            Self::RequestedByCustomer => "Requested by customer",
+        }
+    }
+
+    fn stripe_reason(self) -> RefundReasonFilter {
+        match self {
+            Self::Duplicate => RefundReasonFilter::Duplicate,
+            Self::Fraudulent => RefundReasonFilter::Fraudulent,
+            Self::RequestedByCustomer => RefundReasonFilter::RequestedByCustomer,
+        }
+    }
+}
 
 /// Result of a refund operation
 #[derive(Debug, Clone, Serialize)]
@@ -23,6 +65,8 @@ pub struct RefundResult {
     pub amount_cents: i64,
     /// Refund type: "refund" (actual money back) or "credit" (Stripe account credit)
     pub refund_type: String,
+    /// Reason code sent to Stripe
+    pub refund_reason: RefundReason,
 }
 
 /// Information about a refundable charge
@@ -34,6 +78,8 @@ pub struct RefundableCharge {
     pub invoice_id: String,
     /// Amount paid in cents
     pub amount_cents: i64,
+    /// Amount already refunded in cents
+    pub refunded_cents: i64,
     /// When the billing period started
     pub period_start: OffsetDateTime,
     /// When the billing period ends
@@ -42,6 +88,141 @@ pub struct RefundableCharge {
     pub created_at: OffsetDateTime,
 }
 
+impl RefundableCharge {
+    /// Amount that can still be refunded
+    pub fn refundable_cents(&self) -> i64 {
+        (self.amount_cents - self.refunded_cents).max(0)
+    }
+}
+
+/// Check that refunding `requested_cents` won't over-refund a charge
+///
+/// `refunded_cents` is what Stripe has already refunded on the charge, so
+/// only `amount_cents - refunded_cents` remains available.
+pub fn check_refund_amount(
+    requested_cents: i64,
+    amount_cents: i64,
+    refunded_cents: i64,
+) -> BillingResult<()> {
+    if requested_cents <= 0 {
+        return Err(BillingError::InvalidInput(
+            "Refund amount must be positive".to_string(),
+        ));
+    }
+
+    let available_cents = (amount_cents - refunded_cents).max(0);
+    if requested_cents > available_cents {
+        return Err(BillingError::RefundAmountExceedsCharge {
+            requested_cents,
+            available_cents,
+        });
+    }
+
+    Ok(())
+}
+
+/// Portion of a refund assigned to one charge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RefundAllocation {
+    pub charge_id: String,
+    pub invoice_id: String,
+    pub amount_cents: i64,
+}
+
+/// Split a refund across charges, most recent charge first
+///
+/// Fails with `RefundAmountExceedsCharge` when the charges can't cover `total_cents`.
+pub fn allocate_refund(
+    charges: &[RefundableCharge],
+    total_cents: i64,
+) -> BillingResult<Vec<RefundAllocation>> {
+    if total_cents <= 0 {
+        return Err(BillingError::InvalidInput(
+            "Refund amount must be positive".to_string(),
+        ));
+    }
+
+    let available_cents: i64 = charges.iter().map(|c| c.refundable_cents()).sum();
+    if available_cents < total_cents {
+        return Err(BillingError::RefundAmountExceedsCharge {
+            requested_cents: total_cents,
+            available_cents,
+        });
+    }
+
+    let mut ordered: Vec<&RefundableCharge> = charges.iter().collect();
+    ordered.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+
+    let mut remaining = total_cents;
+    let mut allocations = Vec::new();
+    for charge in ordered {
+        if remaining == 0 {
+            break;
+        }
+        let amount_cents = charge.refundable_cents().min(remaining);
+        if amount_cents == 0 {
+            continue;
+        }
+        allocations.push(RefundAllocation {
+            charge_id: charge.charge_id.clone(),
+            invoice_id: charge.invoice_id.clone(),
+            amount_cents,
+        });
+        remaining -= amount_cents;
+    }
+
+    Ok(allocations)
+}
+
+/// Parse a Unix timestamp from an invoice, falling back to now
+fn invoice_timestamp(
+    invoice_id: &stripe::InvoiceId,
+    field: &str,
+    ts: Option<i64>,
+) -> OffsetDateTime {
+    match ts {
+        Some(ts) => OffsetDateTime::from_unix_timestamp(ts).unwrap_or_else(|e| {
+            tracing::warn!(
+                invoice_id = %invoice_id,
+                timestamp = ts,
+                error = %e,
+                "Failed to parse {} timestamp, using now",
+                field
+            );
+            OffsetDateTime::now_utc()
+        }),
+        None => {
+            tracing::warn!(invoice_id = %invoice_id, "Missing {}, using now", field);
+            OffsetDateTime::now_utc()
+        }
+    }
+}
+
+/// Build a refundable charge from a paid invoice
+///
+/// Returns `None` if the invoice has no charge. The refunded amount is only
+/// known when the charge is expanded.
+fn refundable_charge_from_invoice(invoice: &Invoice) -> BillingResult<Option<RefundableCharge>> {
+    let (charge_id, refunded_cents) = match invoice.charge.as_ref() {
+        Some(stripe::Expandable::Id(id)) => (id.to_string(), 0),
+        Some(stripe::Expandable::Object(charge)) => (charge.id.to_string(), charge.amount_refunded),
+        None => return Ok(None),
+    };
+
+    let created_at = OffsetDateTime::from_unix_timestamp(invoice.created.unwrap_or(0))
+        .map_err(|_| BillingError::RefundFailed("Invalid charge timestamp".to_string()))?;
+
+    Ok(Some(RefundableCharge {
+        charge_id,
+        invoice_id: invoice.id.to_string(),
+        amount_cents: invoice.amount_paid.unwrap_or(0),
+        refunded_cents,
+        period_start: invoice_timestamp(&invoice.id, "period_start", invoice.period_start),
+        period_end: invoice_timestamp(&invoice.id, "period_end", invoice.period_end),
+        created_at,
+    }))
+}
+
 /// Admin refund record for audit trail
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct AdminRefund {
@@ -53,6 +234,9 @@ pub struct AdminRefund {
     pub stripe_invoice_id: Option<String>,
     pub amount_cents: i32,
     pub refund_type: String,
+    /// Stripe reason code; `None` for credits
+    pub refund_reason: Option<RefundReason>,
+    /// Admin's free-text note
     pub reason: String,
     pub old_tier: String,
     pub new_tier: String,
@@ -66,17 +250,24 @@ pub struct AdminRefund {
 pub struct RefundService {
     stripe: StripeClient,
     pool: PgPool,
+    email: BillingEmailService,
 }
 
 impl RefundService {
-    pub fn new(stripe: StripeClient, pool: PgPool) -> Self {
-        Self { stripe, pool }
+    pub fn new(stripe: StripeClient, pool: PgPool, email: BillingEmailService) -> Self {
+        Self {
+            stripe,
+            pool,
+            email,
+        }
     }
 
     /// Issue a refund to the customer's payment method
     ///
     /// This creates an actual Stripe refund (money back to card/bank),
-    /// not a credit on the Stripe account.
+    /// not a credit on the Stripe account. `refund_reason` is Stripe's reason
+    /// code; `note` is the admin's explanation, kept in the refund metadata.
+    /// The org's primary owner gets a receipt unless `skip_email` is set.
     pub async fn issue_refund(
         &self,
         org_id: Uuid,
@@ -84,10 +275,35 @@ impl RefundService {
         charge_id: &str,
         invoice_id: &str,
         amount_cents: i64,
-        reason: &str,
+        refund_reason: RefundReason,
+        note: &str,
         old_tier: &str,
         new_tier: &str,
+        skip_email: bool,
     ) -> BillingResult<RefundResult> {
+        let charge: stripe::ChargeId = charge_id
+            .parse()
+            .map_err(|e| BillingError::RefundFailed(format!("Invalid charge ID: {}", e)))?;
+
+        // Check against Stripe's live refunded total so earlier refunds
+        // (including ones made outside this service) can't be exceeded
+        let stripe_charge = stripe::Charge::retrieve(self.stripe.inner(), &charge, &[]).await?;
+        if let Err(e) = check_refund_amount(
+            amount_cents,
+            stripe_charge.amount,
+            stripe_charge.amount_refunded,
+        ) {
+            tracing::warn!(
+                org_id = %org_id,
+                charge_id = %charge_id,
+                requested_cents = amount_cents,
+                charge_cents = stripe_charge.amount,
+                refunded_cents = stripe_charge.amount_refunded,
+                "Rejected refund that would exceed the charge"
+            );
+            return Err(e);
+        }
+
         // Create audit record first (pending status)
         let refund_record_id = self
             .create_refund_record(
@@ -97,7 +313,8 @@ impl RefundService {
                 Some(invoice_id),
                 amount_cents,
                 "refund",
-                reason,
+                Some(refund_reason),
+                note,
                 old_tier,
                 new_tier,
             )
@@ -105,13 +322,9 @@ impl RefundService {
 
         // Create the Stripe refund
         let mut params = CreateRefund::new();
-        params.charge = Some(
-            charge_id
-                .parse()
-                .map_err(|e| BillingError::RefundFailed(format!("Invalid charge ID: {}", e)))?,
-        );
+        params.charge = Some(charge);
         params.amount = Some(amount_cents);
-        params.reason = Some(RefundReasonFilter::RequestedByCustomer);
+        params.reason = Some(refund_reason.stripe_reason());
 
         // Add metadata for audit purposes
         let mut metadata = std::collections::HashMap::new();
@@ -120,7 +333,7 @@ impl RefundService {
         metadata.insert("org_id".to_string(), org_id.to_string());
         metadata.insert("old_tier".to_string(), old_tier.to_string());
         metadata.insert("new_tier".to_string(), new_tier.to_string());
-        metadata.insert("reason".to_string(), reason.to_string());
+        metadata.insert("note".to_string(), note.to_string());
         params.metadata = Some(metadata);
 
         match Refund::create(self.stripe.inner(), params).await {
@@ -140,14 +353,26 @@ impl RefundService {
                     refund_id = %refund.id,
                     charge_id = %charge_id,
                     amount_cents = %amount_cents,
+                    refund_reason = refund_reason.as_str(),
                     "Issued refund"
                 );
 
+                if !skip_email {
+                    self.send_refund_receipt(
+                        org_id,
+                        amount_cents,
+                        refund_reason,
+                        stripe_charge.created,
+                    )
+                    .await;
+                }
+
                 Ok(RefundResult {
                     stripe_refund_id: refund.id.to_string(),
                     stripe_charge_id: charge_id.to_string(),
                     amount_cents,
                     refund_type: "refund".to_string(),
+                    refund_reason,
                 })
             }
             Err(e) => {
@@ -169,6 +394,9 @@ impl RefundService {
     }
 
     /// Record a credit operation (for audit trail when using prorations instead of refund)
+    ///
+    /// Unless `skip_email` is set, the primary owner is told a credit was
+    /// applied, since no money goes back to their card.
     pub async fn record_credit(
         &self,
         org_id: Uuid,
@@ -177,6 +405,7 @@ impl RefundService {
         reason: &str,
         old_tier: &str,
         new_tier: &str,
+        skip_email: bool,
     ) -> BillingResult<Uuid> {
         let record_id = self
             .create_refund_record(
@@ -186,6 +415,7 @@ impl RefundService {
                 None,
                 amount_cents,
                 "credit",
+                None,
                 reason,
                 old_tier,
                 new_tier,
@@ -203,6 +433,10 @@ impl RefundService {
             "Recorded credit for tier change"
         );
 
+        if !skip_email {
+            self.send_credit_notice(org_id, amount_cents).await;
+        }
+
         Ok(record_id)
     }
 
@@ -221,6 +455,7 @@ impl RefundService {
                 BillingError::RefundFailed(format!("Invalid subscription ID: {}", e))
             })?);
         params.status = Some(stripe::InvoiceStatus::Paid);
+        params.expand = &["data.charge"];
         params.limit = Some(1);
 
         let invoices = Invoice::list(self.stripe.inner(), &params).await?;
@@ -231,75 +466,218 @@ impl RefundService {
             .next()
             .ok_or(BillingError::NoRefundableCharge)?;
 
-        // Get the charge ID from the invoice
-        let charge_id = invoice
-            .charge
-            .as_ref()
-            .map(|c| match c {
-                stripe::Expandable::Id(id) => id.to_string(),
-                stripe::Expandable::Object(charge) => charge.id.to_string(),
-            })
-            .ok_or(BillingError::NoRefundableCharge)?;
+        let charge =
+            refundable_charge_from_invoice(&invoice)?.ok_or(BillingError::NoRefundableCharge)?;
 
         // Check if charge is too old (Stripe 90-day limit)
-        let charge_created = invoice.created.unwrap_or(0);
-        let charge_time = OffsetDateTime::from_unix_timestamp(charge_created)
-            .map_err(|_| BillingError::RefundFailed("Invalid charge timestamp".to_string()))?;
+        let days_old = (OffsetDateTime::now_utc() - charge.created_at).whole_days();
+        if days_old > 90 {
+            return Err(BillingError::ChargeExpiredForRefund);
+        }
+
+        Ok(charge)
+    }
+
+    /// List refundable charges for an org's current billing period
+    ///
+    /// Charges past Stripe's ~90 day refund window are skipped.
+    pub async fn get_period_refundable_charges(
+        &self,
+        org_id: Uuid,
+    ) -> BillingResult<Vec<RefundableCharge>> {
+        let row: Option<(Option<String>, Option<OffsetDateTime>)> = sqlx::query_as(
+            r#"
+            SELECT o.stripe_customer_id, s.current_period_start
+            FROM organizations o
+            LEFT JOIN subscriptions s ON s.org_id = o.id
+            WHERE o.id = $1
+            "#,
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let (customer_id, period_start) = match row {
+            Some((Some(customer_id), Some(period_start))) => (customer_id, period_start),
+            Some(_) => return Err(BillingError::NoRefundableCharge),
+            None => return Err(BillingError::NotFound(format!("Organization {}", org_id))),
+        };
+
+        let mut params = stripe::ListInvoices::new();
+        params.customer = Some(
+            customer_id
+                .parse()
+                .map_err(|e| BillingError::RefundFailed(format!("Invalid customer ID: {}", e)))?,
+        );
+        params.status = Some(stripe::InvoiceStatus::Paid);
+        params.created = Some(stripe::RangeQuery::gte(period_start.unix_timestamp()));
+        params.expand = &["data.charge"];
+        params.limit = Some(100);
 
+        let invoices = Invoice::list(self.stripe.inner(), &params).await?;
         let now = OffsetDateTime::now_utc();
-        let days_old = (now - charge_time).whole_days();
 
-        if days_old > 90 {
-            return Err(BillingError::ChargeExpiredForRefund);
+        let mut charges = Vec::new();
+        for invoice in &invoices.data {
+            if let Some(charge) = refundable_charge_from_invoice(invoice)? {
+                if (now - charge.created_at).whole_days() <= 90 && charge.refundable_cents() > 0 {
+                    charges.push(charge);
+                }
+            }
         }
 
-        // Get period information from the invoice lines
-        let period_start = invoice
-            .period_start
-            .map(|ts| {
-                OffsetDateTime::from_unix_timestamp(ts).unwrap_or_else(|e| {
-                    tracing::warn!(
-                        invoice_id = %invoice.id,
-                        timestamp = ts,
-                        error = %e,
-                        "Failed to parse period_start timestamp, using now"
-                    );
-                    OffsetDateTime::now_utc()
-                })
-            })
-            .unwrap_or_else(|| {
-                tracing::warn!(invoice_id = %invoice.id, "Missing period_start, using now");
-                OffsetDateTime::now_utc()
-            });
-
-        let period_end = invoice
-            .period_end
-            .map(|ts| {
-                OffsetDateTime::from_unix_timestamp(ts).unwrap_or_else(|e| {
-                    tracing::warn!(
-                        invoice_id = %invoice.id,
-                        timestamp = ts,
+        Ok(charges)
+    }
+
+    /// Refund an amount spread across the current period's charges
+    ///
+    /// Refunds the most recent charge first, moving to older charges until
+    /// `total_cents` is covered. Each Stripe refund gets its own audit record.
+    /// If a refund fails part way, the refunds already issued stand and the
+    /// error reports how much was refunded.
+    ///
+    /// The customer gets one receipt for the whole amount rather than one
+    /// per charge, unless `skip_email` is set.
+    pub async fn refund_amount_across_charges(
+        &self,
+        org_id: Uuid,
+        total_cents: i64,
+        refund_reason: RefundReason,
+        note: &str,
+        admin_user_id: Uuid,
+        skip_email: bool,
+    ) -> BillingResult<Vec<RefundResult>> {
+        let charges = self.get_period_refundable_charges(org_id).await?;
+        let allocations = allocate_refund(&charges, total_cents)?;
+
+        // No tier change: record the current tier as both old and new
+        let tier: Option<String> =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let tier = tier.unwrap_or_else(|| "unknown".to_string());
+
+        let mut results = Vec::with_capacity(allocations.len());
+        for allocation in &allocations {
+            match self
+                .issue_refund(
+                    org_id,
+                    admin_user_id,
+                    &allocation.charge_id,
+                    &allocation.invoice_id,
+                    allocation.amount_cents,
+                    refund_reason,
+                    note,
+                    &tier,
+                    &tier,
+                    true,
+                )
+                .await
+            {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    let refunded: i64 = results.iter().map(|r| r.amount_cents).sum();
+                    tracing::error!(
+                        org_id = %org_id,
+                        charge_id = %allocation.charge_id,
+                        refunded_cents = refunded,
+                        total_cents = total_cents,
                         error = %e,
-                        "Failed to parse period_end timestamp, using now"
+                        "Multi-charge refund stopped part way"
                     );
-                    OffsetDateTime::now_utc()
-                })
-            })
-            .unwrap_or_else(|| {
-                tracing::warn!(invoice_id = %invoice.id, "Missing period_end, using now");
-                OffsetDateTime::now_utc()
-            });
+                    return Err(BillingError::RefundFailed(format!(
+                        "{} of {} cents refunded before failure: {}",
+                        refunded, total_cents, e
+                    )));
+                }
+            }
+        }
+
+        tracing::info!(
+            org_id = %org_id,
+            admin_user_id = %admin_user_id,
+            total_cents = total_cents,
+            charges = results.len(),
+            "Issued refund across charges"
+        );
+
+        if !skip_email {
+            // Date the receipt by the oldest charge that was refunded
+            let oldest_charge = charges
+                .iter()
+                .filter(|c| allocations.iter().any(|a| a.charge_id == c.charge_id))
+                .map(|c| c.created_at)
+                .min()
+                .unwrap_or_else(OffsetDateTime::now_utc);
+            self.send_refund_receipt(
+                org_id,
+                total_cents,
+                refund_reason,
+                oldest_charge.unix_timestamp(),
+            )
+            .await;
+        }
 
-        let amount_cents = invoice.amount_paid.unwrap_or(0);
+        Ok(results)
+    }
 
-        Ok(RefundableCharge {
-            charge_id,
-            invoice_id: invoice.id.to_string(),
-            amount_cents,
-            period_start,
-            period_end,
-            created_at: charge_time,
-        })
+    /// Email the primary owner a refund receipt; failures are only logged
+    async fn send_refund_receipt(
+        &self,
+        org_id: Uuid,
+        amount_cents: i64,
+        refund_reason: RefundReason,
+        charged_at: i64,
+    ) {
+        let charge_date = OffsetDateTime::from_unix_timestamp(charged_at)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc())
+            .date();
+
+        match get_primary_owner(&self.pool, org_id).await {
+            Ok(Some(owner)) => {
+                if let Err(e) = self
+                    .email
+                    .send_refund_issued(
+                        &owner.email,
+                        &owner.org_name,
+                        amount_cents,
+                        refund_reason,
+                        charge_date,
+                    )
+                    .await
+                {
+                    tracing::warn!(org_id = %org_id, error = %e, "Failed to send refund receipt");
+                }
+            }
+            Ok(None) => {
+                tracing::warn!(org_id = %org_id, "No owner to send refund receipt to");
+            }
+            Err(e) => {
+                tracing::warn!(org_id = %org_id, error = %e, "Failed to look up owner for refund receipt");
+            }
+        }
+    }
+
+    /// Email the primary owner that an account credit was applied; failures are only logged
+    async fn send_credit_notice(&self, org_id: Uuid, amount_cents: i64) {
+        match get_primary_owner(&self.pool, org_id).await {
+            Ok(Some(owner)) => {
+                if let Err(e) = self
+                    .email
+                    .send_account_credit_applied(&owner.email, &owner.org_name, amount_cents)
+                    .await
+                {
+                    tracing::warn!(org_id = %org_id, error = %e, "Failed to send credit notice");
+                }
+            }
+            Ok(None) => {
+                tracing::warn!(org_id = %org_id, "No owner to send credit notice to");
+            }
+            Err(e) => {
+                tracing::warn!(org_id = %org_id, error = %e, "Failed to look up owner for credit notice");
+            }
+        }
     }
 
     /// Calculate prorated refund amount based on remaining days in billing period
@@ -337,6 +715,7 @@ impl RefundService {
         invoice_id: Option<&str>,
         amount_cents: i64,
         refund_type: &str,
+        refund_reason: Option<RefundReason>,
         reason: &str,
         old_tier: &str,
         new_tier: &str,
@@ -350,12 +729,13 @@ impl RefundService {
                 stripe_invoice_id,
                 amount_cents,
                 refund_type,
+                refund_reason,
                 reason,
                 old_tier,
                 new_tier,
                 status
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, 'pending')
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'pending')
             RETURNING id
             "#,
         )
@@ -365,6 +745,7 @@ impl RefundService {
         .bind(invoice_id)
         .bind(amount_cents as i32)
         .bind(refund_type)
+        .bind(refund_reason)
         .bind(reason)
         .bind(old_tier)
         .bind(new_tier)
@@ -415,6 +796,7 @@ impl RefundService {
                 stripe_invoice_id,
                 amount_cents,
                 refund_type,
+                refund_reason,
                 reason,
                 old_tier,
                 new_tier,
@@ -478,4 +860,138 @@ mod tests {
         // With the -1 second buffer, remaining should be at least 29 days: 9000 * 29/30 = 8700
         assert!(refund >= 8700, "Expected refund >= 8700, got {}", refund);
     }
+
+    fn charge(id: &str, amount_cents: i64, refunded_cents: i64, days_ago: i64) -> RefundableCharge {
+        let created_at = OffsetDateTime::now_utc() - time::Duration::days(days_ago);
+        RefundableCharge {
+            charge_id: format!("ch_{}", id),
+            invoice_id: format!("in_{}", id),
+            amount_cents,
+            refunded_cents,
+            period_start: created_at,
+            period_end: created_at + time::Duration::days(30),
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_allocate_refund_across_two_charges() {
+        // Older subscription charge, then a newer overage charge
+        let charges = vec![charge("sub", 2900, 0, 20), charge("overage", 1000, 0, 2)];
+
+        let allocations = allocate_refund(&charges, 3400).unwrap();
+        assert_eq!(
+            allocations,
+            vec![
+                RefundAllocation {
+                    charge_id: "ch_overage".to_string(),
+                    invoice_id: "in_overage".to_string(),
+                    amount_cents: 1000,
+                },
+                RefundAllocation {
+                    charge_id: "ch_sub".to_string(),
+                    invoice_id: "in_sub".to_string(),
+                    amount_cents: 2400,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_allocate_refund_single_charge_when_it_covers_total() {
+        let charges = vec![charge("old", 2900, 0, 20), charge("new", 2900, 0, 1)];
+
+        let allocations = allocate_refund(&charges, 500).unwrap();
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].charge_id, "ch_new");
+        assert_eq!(allocations[0].amount_cents, 500);
+    }
+
+    #[test]
+    fn test_allocate_refund_skips_already_refunded() {
+        let charges = vec![charge("old", 2900, 0, 20), charge("new", 1000, 1000, 1)];
+
+        let allocations = allocate_refund(&charges, 1500).unwrap();
+        assert_eq!(allocations.len(), 1);
+        assert_eq!(allocations[0].charge_id, "ch_old");
+        assert_eq!(allocations[0].amount_cents, 1500);
+    }
+
+    #[test]
+    fn test_allocate_refund_insufficient_total() {
+        let charges = vec![charge("a", 2900, 900, 20), charge("b", 1000, 0, 2)];
+
+        match allocate_refund(&charges, 5000) {
+            Err(BillingError::RefundAmountExceedsCharge {
+                requested_cents,
+                available_cents,
+            }) => {
+                assert_eq!(requested_cents, 5000);
+                assert_eq!(available_cents, 3000);
+            }
+            other => panic!("Expected RefundAmountExceedsCharge, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_allocate_refund_rejects_non_positive_amount() {
+        let charges = vec![charge("a", 2900, 0, 1)];
+        assert!(matches!(
+            allocate_refund(&charges, 0),
+            Err(BillingError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_refund_reason_defaults_and_maps_to_stripe() {
+        assert_eq!(RefundReason::default(), RefundReason::RequestedByCustomer);
+
+        for (reason, stripe_reason) in [
+            (RefundReason::Duplicate, RefundReasonFilter::Duplicate),
+            (RefundReason::Fraudulent, RefundReasonFilter::Fraudulent),
+            (
+                RefundReason::RequestedByCustomer,
+                RefundReasonFilter::RequestedByCustomer,
+            ),
+        ] {
+            assert_eq!(reason.stripe_reason(), stripe_reason);
+            // The stored code is Stripe's own name for the reason
+            assert_eq!(reason.as_str(), stripe_reason.as_str());
+            assert_eq!(
+                serde_json::to_value(reason).unwrap(),
+                serde_json::json!(reason.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_refund_amount_on_partially_refunded_charge() {
+        // $29.00 charge with $10.00 already refunded leaves $19.00
+        assert!(check_refund_amount(1900, 2900, 1000).is_ok());
+        assert!(check_refund_amount(500, 2900, 1000).is_ok());
+
+        match check_refund_amount(1901, 2900, 1000) {
+            Err(BillingError::RefundAmountExceedsCharge {
+                requested_cents,
+                available_cents,
+            }) => {
+                assert_eq!(requested_cents, 1901);
+                assert_eq!(available_cents, 1900);
+            }
+            other => panic!("Expected RefundAmountExceedsCharge, got {:?}", other),
+        }
+
+        // Fully refunded charge has nothing left
+        assert!(matches!(
+            check_refund_amount(1, 2900, 2900),
+            Err(BillingError::RefundAmountExceedsCharge {
+                available_cents: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            check_refund_amount(0, 2900, 0),
+            Err(BillingError::InvalidInput(_))
+        ));
+    }
 }
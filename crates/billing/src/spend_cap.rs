@@ -3,6 +3,8 @@
 //! Handles user-configurable spend limits with hard pause functionality.
 //! Inspired by Supabase and Vercel spend management patterns.
 
+use plexmcp_shared::SubscriptionTier;
+pub use plexmcp_shared::MEMBER_SPEND_CAP_SUSPENSION_REASON;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::sync::OnceLock;
@@ -11,6 +13,8 @@ use uuid::Uuid;
 
 use crate::email::BillingEmailService;
 use crate::error::{BillingError, BillingResult};
+use crate::member_suspension::MemberSuspensionService;
+use crate::tax::{TaxBreakdown, TaxService};
 
 /// Default notification thresholds (percentage of spend cap)
 /// Can be overridden via SPEND_CAP_NOTIFICATION_THRESHOLDS env var (comma-separated, e.g., "50,75,90,100")
@@ -31,15 +35,83 @@ fn get_notification_thresholds() -> &'static [i32] {
     })
 }
 
+/// Whether spend caps count tax on overage charges (`SPEND_CAP_INCLUDES_TAX`)
+///
+/// Off by default, so caps compare against pre-tax overage.
+fn spend_cap_includes_tax() -> bool {
+    static INCLUDES_TAX: OnceLock<bool> = OnceLock::new();
+    *INCLUDES_TAX.get_or_init(|| {
+        std::env::var("SPEND_CAP_INCLUDES_TAX")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+    })
+}
+
+/// Spend counted against a cap for a set of overage charges
+///
+/// `tax` is the breakdown for those same charges; it only adds to the total
+/// when `includes_tax` is set.
+fn counted_spend_cents(
+    charges_cents: &[i64],
+    tax: Option<&TaxBreakdown>,
+    includes_tax: bool,
+) -> i64 {
+    let pre_tax: i64 = charges_cents.iter().sum();
+    match tax {
+        Some(tax) if includes_tax => pre_tax + tax.tax_amount_cents,
+        _ => pre_tax,
+    }
+}
+
 /// Notification thresholds (for backwards compatibility)
 pub const NOTIFICATION_THRESHOLDS: [i32; 4] = DEFAULT_NOTIFICATION_THRESHOLDS;
 
+/// Smallest spend cap an org can have ($10)
+const MIN_SPEND_CAP_CENTS: i32 = 1000;
+
+/// Effective cap for `percent` of a monthly base price
+///
+/// Rounds up to whole cents and never goes below the $10 minimum, so a
+/// downgrade can't shrink the cap to one that pauses on the first charge.
+pub fn percent_of_base_cents(base_price_cents: i32, percent: u8) -> i32 {
+    let cents = (base_price_cents as i64 * percent as i64 + 99) / 100;
+    (cents as i32).max(MIN_SPEND_CAP_CENTS)
+}
+
+/// What happens when an org's spend reaches its cap
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendCapMode {
+    /// Threshold notifications only; API access is never paused
+    WarnOnly,
+    /// Pause API access once spend reaches 100% of the cap
+    HardPause,
+}
+
+impl SpendCapMode {
+    /// Mode for a cap's `hard_pause_enabled` flag
+    pub fn from_hard_pause(hard_pause_enabled: bool) -> Self {
+        if hard_pause_enabled {
+            Self::HardPause
+        } else {
+            Self::WarnOnly
+        }
+    }
+
+    /// Whether spend at `percentage` of the cap pauses the org
+    pub fn pauses_at(self, percentage: f64) -> bool {
+        self == Self::HardPause && percentage >= 100.0
+    }
+}
+
 /// Spend cap configuration for an organization
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct SpendCap {
     pub id: Uuid,
     pub org_id: Uuid,
+    /// Effective cap; recomputed from the base price when `cap_percent_of_base` is set
     pub cap_amount_cents: i32,
+    pub cap_percent_of_base: Option<i16>,
     pub hard_pause_enabled: bool,
     pub is_paused: bool,
     pub paused_at: Option<OffsetDateTime>,
@@ -52,18 +124,75 @@ pub struct SpendCap {
     pub updated_at: OffsetDateTime,
 }
 
+impl SpendCap {
+    /// Pause behaviour set by `hard_pause_enabled`
+    pub fn mode(&self) -> SpendCapMode {
+        SpendCapMode::from_hard_pause(self.hard_pause_enabled)
+    }
+}
+
 /// Request to create/update a spend cap
+///
+/// The cap is either an absolute amount or a percentage of the plan's monthly
+/// base price. If both are set, the absolute amount wins and the percentage is
+/// dropped.
 #[derive(Debug, Clone, Deserialize)]
 pub struct SpendCapRequest {
-    pub cap_amount_cents: i32,
+    pub cap_amount_cents: Option<i32>,
+    /// Percentage of the monthly base price, resolved again at every check so
+    /// the cap scales with tier changes
+    pub cap_percent_of_base: Option<u8>,
     pub hard_pause_enabled: bool,
 }
 
+/// Spend sub-cap for a single member of an org
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MemberSpendCap {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub user_id: Uuid,
+    pub cap_amount_cents: i32,
+    /// Share of the org's counted spend attributed to this member
+    pub current_period_spend_cents: i32,
+    /// When the member reached the cap and was suspended
+    pub exceeded_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+}
+
+/// A member at or over their spend sub-cap
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MemberOverCap {
+    pub user_id: Uuid,
+    pub cap_amount_cents: i32,
+    pub current_spend_cents: i32,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub exceeded_at: Option<OffsetDateTime>,
+}
+
+/// Share of an org's spend attributed to a member
+///
+/// Proportional to the member's share of the period's requests, made with
+/// the API keys they created. Rounds down.
+pub fn attributed_spend_cents(
+    org_spend_cents: i64,
+    member_requests: i64,
+    total_requests: i64,
+) -> i32 {
+    if org_spend_cents <= 0 || member_requests <= 0 || total_requests <= 0 {
+        return 0;
+    }
+    let share = org_spend_cents as i128 * member_requests.min(total_requests) as i128
+        / total_requests as i128;
+    i32::try_from(share).unwrap_or(i32::MAX)
+}
+
 /// Spend cap status for API responses
 #[derive(Debug, Clone, Serialize)]
 pub struct SpendCapStatus {
     pub has_cap: bool,
     pub cap_amount_cents: Option<i32>,
+    pub cap_percent_of_base: Option<i16>,
     pub current_spend_cents: i32,
     pub percentage_used: f64,
     pub hard_pause_enabled: bool,
@@ -73,6 +202,8 @@ pub struct SpendCapStatus {
     pub has_override: bool,
     #[serde(with = "time::serde::rfc3339::option")]
     pub override_until: Option<OffsetDateTime>,
+    /// Members at or over their spend sub-cap
+    pub members_over_cap: Vec<MemberOverCap>,
 }
 
 /// Result of spend cap check
@@ -82,39 +213,166 @@ pub enum SpendCapCheckResult {
     NoCap,
     /// Within cap limits
     Ok { spend_cents: i32, percentage: f64 },
-    /// Cap exceeded and org is paused
+    /// Cap exceeded and this check paused the org (always `HardPause`)
     Paused { spend_cents: i32 },
-    /// Cap exceeded but hard pause not enabled
-    Exceeded { spend_cents: i32, percentage: f64 },
+    /// Cap exceeded without pausing: `WarnOnly`, or `HardPause` with the org
+    /// already paused
+    Exceeded {
+        spend_cents: i32,
+        percentage: f64,
+        mode: SpendCapMode,
+    },
+}
+
+/// A notification threshold reached by a given spend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Threshold {
+    /// Percentage of the spend cap
+    pub percent: i32,
+    /// Spend in cents at which this threshold is reached
+    pub spend_cents: i64,
+    /// Whether reaching it pauses the org (100% with hard pause enabled)
+    pub pauses: bool,
+}
+
+/// Thresholds that `spend_cents` reaches against a cap
+///
+/// A 0 cap counts as always at the limit, matching `update_spend`.
+pub fn thresholds_reached(
+    thresholds: &[i32],
+    cap_amount_cents: i32,
+    hard_pause_enabled: bool,
+    spend_cents: i32,
+) -> Vec<Threshold> {
+    let percentage = if cap_amount_cents > 0 {
+        (spend_cents as f64 / cap_amount_cents as f64) * 100.0
+    } else {
+        100.0
+    };
+
+    thresholds
+        .iter()
+        .filter(|&&threshold| percentage >= threshold as f64)
+        .map(|&threshold| Threshold {
+            percent: threshold,
+            spend_cents: (cap_amount_cents as i64 * threshold as i64 + 99) / 100,
+            pauses: threshold >= 100 && hard_pause_enabled,
+        })
+        .collect()
 }
 
 /// Spend cap service
 pub struct SpendCapService {
     pool: PgPool,
     email: BillingEmailService,
+    /// Calculates tax on overage when caps count it
+    tax: Option<TaxService>,
 }
 
 impl SpendCapService {
     pub fn new(pool: PgPool, email: BillingEmailService) -> Self {
-        Self { pool, email }
+        Self {
+            pool,
+            email,
+            tax: None,
+        }
+    }
+
+    /// Count tax on overage charges toward caps, calculated by `tax`
+    pub fn with_tax(mut self, tax: TaxService) -> Self {
+        self.tax = Some(tax);
+        self
+    }
+
+    /// Count tax toward caps when `SPEND_CAP_INCLUDES_TAX` is set
+    pub fn with_tax_from_env(self, tax: TaxService) -> Self {
+        if spend_cap_includes_tax() {
+            self.with_tax(tax)
+        } else {
+            self
+        }
     }
 
     /// Get spend cap for an organization
+    ///
+    /// A percentage cap is resolved against the current base price first.
     pub async fn get_spend_cap(&self, org_id: Uuid) -> BillingResult<Option<SpendCap>> {
         let cap: Option<SpendCap> = sqlx::query_as(
-            "SELECT id, org_id, cap_amount_cents, hard_pause_enabled, is_paused, paused_at,
-                    current_period_spend_cents, last_charge_at, override_until,
-                    override_by_user_id, override_reason, created_at, updated_at
+            "SELECT id, org_id, cap_amount_cents, cap_percent_of_base, hard_pause_enabled,
+                    is_paused, paused_at, current_period_spend_cents, last_charge_at,
+                    override_until, override_by_user_id, override_reason, created_at, updated_at
              FROM spend_caps WHERE org_id = $1",
         )
         .bind(org_id)
         .fetch_optional(&self.pool)
         .await?;
-        Ok(cap)
+
+        let Some(mut cap) = cap else {
+            return Ok(None);
+        };
+        if let Some(percent) = cap.cap_percent_of_base.and_then(|p| u8::try_from(p).ok()) {
+            self.refresh_percent_cap(&mut cap, percent).await?;
+        }
+        Ok(Some(cap))
+    }
+
+    /// Monthly base price an org pays, used to resolve percentage caps
+    ///
+    /// A custom (enterprise) price wins over the tier's list price. `None` if
+    /// the org has neither, e.g. on Free.
+    async fn base_price_cents(&self, org_id: Uuid) -> BillingResult<Option<i32>> {
+        let row: Option<(String, Option<i32>)> = sqlx::query_as(
+            "SELECT subscription_tier, custom_monthly_price_cents FROM organizations WHERE id = $1",
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|(tier, custom_price)| {
+            custom_price.or_else(|| tier.parse::<SubscriptionTier>().ok()?.monthly_price_cents())
+        }))
+    }
+
+    /// Recompute a percentage cap from the org's current base price
+    ///
+    /// Runs on every read, so a tier change takes effect at the next check.
+    /// A changed amount is written back so queries reading `cap_amount_cents`
+    /// directly see it too. Without a base price the last amount is kept.
+    async fn refresh_percent_cap(&self, cap: &mut SpendCap, percent: u8) -> BillingResult<()> {
+        let Some(base_price_cents) = self.base_price_cents(cap.org_id).await? else {
+            return Ok(());
+        };
+        let effective = percent_of_base_cents(base_price_cents, percent);
+        if effective == cap.cap_amount_cents {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "UPDATE spend_caps SET cap_amount_cents = $1, updated_at = NOW()
+             WHERE org_id = $2 AND cap_percent_of_base = $3",
+        )
+        .bind(effective)
+        .bind(cap.org_id)
+        .bind(percent as i16)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!(
+            org_id = %cap.org_id,
+            cap_percent_of_base = percent,
+            old_cap_cents = cap.cap_amount_cents,
+            new_cap_cents = effective,
+            "Percentage spend cap recomputed for new base price"
+        );
+
+        cap.cap_amount_cents = effective;
+        Ok(())
     }
 
     /// Get spend cap status for API response
     pub async fn get_status(&self, org_id: Uuid) -> BillingResult<SpendCapStatus> {
+        let members_over_cap = self.get_members_over_cap(org_id).await?;
+
         match self.get_spend_cap(org_id).await? {
             Some(cap) => {
                 let has_override = cap
@@ -125,6 +383,7 @@ impl SpendCapService {
                 Ok(SpendCapStatus {
                     has_cap: true,
                     cap_amount_cents: Some(cap.cap_amount_cents),
+                    cap_percent_of_base: cap.cap_percent_of_base,
                     current_spend_cents: cap.current_period_spend_cents,
                     percentage_used: if cap.cap_amount_cents > 0 {
                         (cap.current_period_spend_cents as f64 / cap.cap_amount_cents as f64)
@@ -137,11 +396,13 @@ impl SpendCapService {
                     paused_at: cap.paused_at,
                     has_override,
                     override_until: cap.override_until,
+                    members_over_cap,
                 })
             }
             None => Ok(SpendCapStatus {
                 has_cap: false,
                 cap_amount_cents: None,
+                cap_percent_of_base: None,
                 current_spend_cents: 0,
                 percentage_used: 0.0,
                 hard_pause_enabled: false,
@@ -149,36 +410,75 @@ impl SpendCapService {
                 paused_at: None,
                 has_override: false,
                 override_until: None,
+                members_over_cap,
             }),
         }
     }
 
     /// Create or update spend cap
+    ///
+    /// An absolute `cap_amount_cents` takes precedence over
+    /// `cap_percent_of_base` and clears any stored percentage. Turning hard
+    /// pause off (`WarnOnly`) lifts an existing spend cap pause.
     pub async fn set_spend_cap(
         &self,
         org_id: Uuid,
         req: SpendCapRequest,
     ) -> BillingResult<SpendCap> {
-        // Validate cap amount (minimum $10)
-        if req.cap_amount_cents < 1000 {
-            return Err(BillingError::InvalidInput(
-                "Spend cap must be at least $10.00".to_string(),
-            ));
-        }
+        let (cap_amount_cents, cap_percent_of_base) =
+            match (req.cap_amount_cents, req.cap_percent_of_base) {
+                (Some(cents), _) => {
+                    // Validate cap amount (minimum $10)
+                    if cents < MIN_SPEND_CAP_CENTS {
+                        return Err(BillingError::InvalidInput(
+                            "Spend cap must be at least $10.00".to_string(),
+                        ));
+                    }
+                    (cents, None)
+                }
+                (None, Some(0)) => {
+                    return Err(BillingError::InvalidInput(
+                        "Spend cap percentage must be at least 1%".to_string(),
+                    ));
+                }
+                (None, Some(percent)) => {
+                    let base_price_cents =
+                        self.base_price_cents(org_id).await?.ok_or_else(|| {
+                            BillingError::InvalidInput(
+                                "A percentage spend cap requires a plan with a monthly price"
+                                    .to_string(),
+                            )
+                        })?;
+                    (
+                        percent_of_base_cents(base_price_cents, percent),
+                        Some(percent as i16),
+                    )
+                }
+                (None, None) => {
+                    return Err(BillingError::InvalidInput(
+                        "Spend cap requires cap_amount_cents or cap_percent_of_base".to_string(),
+                    ));
+                }
+            };
 
         let cap: SpendCap = sqlx::query_as(
             r#"
-            INSERT INTO spend_caps (org_id, cap_amount_cents, hard_pause_enabled)
-            VALUES ($1, $2, $3)
+            INSERT INTO spend_caps (org_id, cap_amount_cents, cap_percent_of_base, hard_pause_enabled)
+            VALUES ($1, $2, $3, $4)
             ON CONFLICT (org_id) DO UPDATE SET
                 cap_amount_cents = EXCLUDED.cap_amount_cents,
+                cap_percent_of_base = EXCLUDED.cap_percent_of_base,
                 hard_pause_enabled = EXCLUDED.hard_pause_enabled,
+                -- Switching to warn-only lifts an existing spend cap pause
+                is_paused = spend_caps.is_paused AND EXCLUDED.hard_pause_enabled,
+                paused_at = CASE WHEN EXCLUDED.hard_pause_enabled THEN spend_caps.paused_at END,
                 updated_at = NOW()
             RETURNING *
             "#,
         )
         .bind(org_id)
-        .bind(req.cap_amount_cents)
+        .bind(cap_amount_cents)
+        .bind(cap_percent_of_base)
         .bind(req.hard_pause_enabled)
         .fetch_one(&self.pool)
         .await?;
@@ -186,6 +486,7 @@ impl SpendCapService {
         tracing::info!(
             org_id = %org_id,
             cap_amount_cents = cap.cap_amount_cents,
+            cap_percent_of_base = ?cap.cap_percent_of_base,
             hard_pause_enabled = cap.hard_pause_enabled,
             "Spend cap updated"
         );
@@ -211,6 +512,11 @@ impl SpendCapService {
             None => return Ok(false), // No cap configured
         };
 
+        // Warn-only caps never block requests
+        if cap.mode() == SpendCapMode::WarnOnly {
+            return Ok(false);
+        }
+
         // Check if override is active
         if let Some(override_until) = cap.override_until {
             if override_until > OffsetDateTime::now_utc() {
@@ -272,10 +578,10 @@ impl SpendCapService {
             }
         }
 
-        // Check if should pause (at 100% with hard pause enabled)
+        // Check if should pause (at 100% in HardPause mode; WarnOnly never pauses)
         // Uses atomic update to prevent race condition where two concurrent requests
         // could both see is_paused=false and both try to pause
-        if percentage >= 100.0 && cap.hard_pause_enabled && !cap.is_paused {
+        if cap.mode().pauses_at(percentage) && !cap.is_paused {
             let was_paused = self.try_pause_org_atomic(org_id).await?;
             if was_paused {
                 return Ok(SpendCapCheckResult::Paused {
@@ -291,6 +597,7 @@ impl SpendCapService {
             return Ok(SpendCapCheckResult::Exceeded {
                 spend_cents: new_spend,
                 percentage,
+                mode: cap.mode(),
             });
         }
 
@@ -302,30 +609,48 @@ impl SpendCapService {
 
     /// Sync spend from overage charges - queries total pending overage and SETS the spend
     /// Use this in worker jobs that run periodically, as it won't double-count
+    ///
+    /// Member sub-caps are synced from the same spend, with or without an org cap.
     pub async fn sync_spend_from_overages(
         &self,
         org_id: Uuid,
     ) -> BillingResult<SpendCapCheckResult> {
-        let cap = match self.get_spend_cap(org_id).await? {
-            Some(c) => c,
-            None => return Ok(SpendCapCheckResult::NoCap),
-        };
-
-        // Query total pending overage charges for current billing period
-        let total_pending_cents: i32 = sqlx::query_scalar(
+        // Query pending overage charges for current billing period
+        let pending: Vec<(Uuid, i64)> = sqlx::query_as(
             r#"
-            SELECT COALESCE(SUM(total_charge_cents), 0)::INT
+            SELECT id, total_charge_cents::BIGINT
             FROM overage_charges
             WHERE org_id = $1
               AND status IN ('pending', 'awaiting_payment', 'invoiced')
             "#,
         )
         .bind(org_id)
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
+        let pending_charges: Vec<i64> = pending.iter().map(|(_, cents)| *cents).collect();
+        let tax = match &self.tax {
+            Some(tax_service) => self.overage_tax(tax_service, org_id, &pending).await,
+            None => None,
+        };
+
+        let new_spend = i32::try_from(counted_spend_cents(
+            &pending_charges,
+            tax.as_ref(),
+            self.tax.is_some(),
+        ))
+        .unwrap_or(i32::MAX);
+
+        // A member sub-cap failure must not hold up the org-level cap
+        if let Err(e) = self.sync_member_spend(org_id, new_spend as i64).await {
+            tracing::error!(org_id = %org_id, error = %e, "Failed to sync member spend caps");
+        }
+
+        let cap = match self.get_spend_cap(org_id).await? {
+            Some(c) => c,
+            None => return Ok(SpendCapCheckResult::NoCap),
+        };
         let old_spend = cap.current_period_spend_cents;
-        let new_spend = total_pending_cents;
 
         // No change needed
         if old_spend == new_spend {
@@ -377,9 +702,9 @@ impl SpendCapService {
             }
         }
 
-        // Check if should pause (at 100% with hard pause enabled)
+        // Check if should pause (at 100% in HardPause mode; WarnOnly never pauses)
         // ATOMIC: Use single UPDATE with WHERE conditions to prevent race condition
-        if percentage >= 100.0 && cap.hard_pause_enabled {
+        if cap.mode().pauses_at(percentage) {
             let paused = self.try_pause_org_atomic(org_id).await?;
             if paused {
                 return Ok(SpendCapCheckResult::Paused {
@@ -393,6 +718,7 @@ impl SpendCapService {
             return Ok(SpendCapCheckResult::Exceeded {
                 spend_cents: new_spend,
                 percentage,
+                mode: cap.mode(),
             });
         }
 
@@ -402,11 +728,241 @@ impl SpendCapService {
         })
     }
 
+    /// Set a member's spend sub-cap
+    ///
+    /// Owners can't have one, since going over it suspends the member.
+    pub async fn set_member_cap(
+        &self,
+        org_id: Uuid,
+        user_id: Uuid,
+        cap_amount_cents: i32,
+    ) -> BillingResult<MemberSpendCap> {
+        if cap_amount_cents <= 0 {
+            return Err(BillingError::InvalidInput(
+                "Member spend cap must be greater than $0.00".to_string(),
+            ));
+        }
+
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM organization_members WHERE org_id = $1 AND user_id = $2",
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        match role.as_deref() {
+            None => {
+                return Err(BillingError::NotFound(format!(
+                    "Member {} not found in organization",
+                    user_id
+                )))
+            }
+            Some("owner") => {
+                return Err(BillingError::InvalidInput(
+                    "Owners can't have a member spend cap".to_string(),
+                ))
+            }
+            Some(_) => {}
+        }
+
+        let cap: MemberSpendCap = sqlx::query_as(
+            r#"
+            INSERT INTO member_spend_caps (org_id, user_id, cap_amount_cents)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (org_id, user_id) DO UPDATE SET
+                cap_amount_cents = EXCLUDED.cap_amount_cents,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(org_id)
+        .bind(user_id)
+        .bind(cap_amount_cents)
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            user_id = %user_id,
+            cap_amount_cents = cap_amount_cents,
+            "Member spend cap updated"
+        );
+
+        Ok(cap)
+    }
+
+    /// Remove a member's spend sub-cap, restoring them if it had suspended them
+    pub async fn remove_member_cap(&self, org_id: Uuid, user_id: Uuid) -> BillingResult<()> {
+        sqlx::query("DELETE FROM member_spend_caps WHERE org_id = $1 AND user_id = $2")
+            .bind(org_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        MemberSuspensionService::new(self.pool.clone())
+            .restore_member(org_id, user_id, MEMBER_SPEND_CAP_SUSPENSION_REASON)
+            .await?;
+
+        tracing::info!(org_id = %org_id, user_id = %user_id, "Member spend cap removed");
+        Ok(())
+    }
+
+    /// Members at or over their spend sub-cap
+    pub async fn get_members_over_cap(&self, org_id: Uuid) -> BillingResult<Vec<MemberOverCap>> {
+        let members = sqlx::query_as(
+            r#"
+            SELECT user_id, cap_amount_cents,
+                   current_period_spend_cents AS current_spend_cents, exceeded_at
+            FROM member_spend_caps
+            WHERE org_id = $1 AND current_period_spend_cents >= cap_amount_cents
+            ORDER BY exceeded_at ASC NULLS LAST, user_id
+            "#,
+        )
+        .bind(org_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(members)
+    }
+
+    /// Attribute the org's spend to members with a sub-cap and enforce the caps
+    ///
+    /// A member reaching their cap is suspended (not the whole org); one back
+    /// under it, e.g. after the period's charges are paid, is restored.
+    async fn sync_member_spend(&self, org_id: Uuid, org_spend_cents: i64) -> BillingResult<()> {
+        let caps: Vec<MemberSpendCap> =
+            sqlx::query_as("SELECT * FROM member_spend_caps WHERE org_id = $1")
+                .bind(org_id)
+                .fetch_all(&self.pool)
+                .await?;
+        if caps.is_empty() {
+            return Ok(());
+        }
+
+        let period_start = self.get_billing_period_start(org_id).await?;
+        let member_requests: Vec<(Uuid, i64)> = sqlx::query_as(
+            r#"
+            SELECT k.created_by, SUM(r.request_count)::BIGINT
+            FROM usage_records r
+            JOIN api_keys k ON k.id = r.api_key_id
+            WHERE r.org_id = $1 AND r.period_start >= $2 AND k.created_by IS NOT NULL
+            GROUP BY k.created_by
+            "#,
+        )
+        .bind(org_id)
+        .bind(period_start)
+        .fetch_all(&self.pool)
+        .await?;
+        let total_requests: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(request_count), 0)::BIGINT FROM usage_records
+             WHERE org_id = $1 AND period_start >= $2",
+        )
+        .bind(org_id)
+        .bind(period_start)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let members = MemberSuspensionService::new(self.pool.clone());
+        for cap in caps {
+            let requests = member_requests
+                .iter()
+                .find(|(user_id, _)| *user_id == cap.user_id)
+                .map_or(0, |(_, requests)| *requests);
+            let spend = attributed_spend_cents(org_spend_cents, requests, total_requests);
+            let mut exceeded = spend >= cap.cap_amount_cents;
+
+            match (exceeded, cap.exceeded_at.is_some()) {
+                (true, false) => {
+                    members
+                        .suspend_member(org_id, cap.user_id, MEMBER_SPEND_CAP_SUSPENSION_REASON)
+                        .await?;
+                    tracing::warn!(
+                        org_id = %org_id,
+                        user_id = %cap.user_id,
+                        spend_cents = spend,
+                        cap_amount_cents = cap.cap_amount_cents,
+                        "Member reached spend cap, suspended"
+                    );
+                }
+                (false, true) => {
+                    if let Err(e) = members
+                        .restore_member(org_id, cap.user_id, MEMBER_SPEND_CAP_SUSPENSION_REASON)
+                        .await
+                    {
+                        // Retried on the next sync
+                        tracing::error!(
+                            org_id = %org_id,
+                            user_id = %cap.user_id,
+                            error = %e,
+                            "Failed to restore member back under spend cap"
+                        );
+                        exceeded = true;
+                    }
+                }
+                _ => {}
+            }
+
+            sqlx::query(
+                r#"
+                UPDATE member_spend_caps SET
+                    current_period_spend_cents = $1,
+                    exceeded_at = CASE WHEN $2 THEN COALESCE(exceeded_at, NOW()) END,
+                    updated_at = NOW()
+                WHERE id = $3
+                "#,
+            )
+            .bind(spend)
+            .bind(exceeded)
+            .bind(cap.id)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Tax on an org's pending overage charges, from the tax module
+    ///
+    /// Uses the org's billing location and exemption with the configured tax
+    /// strategy. Returns `None`, counting pre-tax spend, when tax can't be
+    /// calculated (no rate for the org's location, Stripe Tax unavailable).
+    async fn overage_tax(
+        &self,
+        tax: &TaxService,
+        org_id: Uuid,
+        charges: &[(Uuid, i64)],
+    ) -> Option<TaxBreakdown> {
+        if charges.is_empty() {
+            return None;
+        }
+        let lines: Vec<(String, i64)> = charges
+            .iter()
+            .map(|(id, cents)| (format!("overage_{}", id), *cents))
+            .collect();
+
+        let breakdown = match tax.get_tax_config(org_id).await {
+            Ok(config) => tax.calculate(&config, "usd", &lines).await,
+            Err(e) => Err(e),
+        };
+        match breakdown {
+            Ok(breakdown) => Some(breakdown),
+            Err(e) => {
+                tracing::warn!(
+                    org_id = %org_id,
+                    error = %e,
+                    "Could not calculate tax on overage, counting pre-tax spend"
+                );
+                None
+            }
+        }
+    }
+
     /// Atomically try to pause an organization's API access
     ///
     /// Uses a single UPDATE with WHERE conditions to prevent race conditions
     /// where multiple concurrent requests could all pass the "should pause" check.
     /// Returns true if this call actually paused the org, false if already paused.
+    /// A `WarnOnly` cap is never paused, even if this is called over the cap.
     async fn try_pause_org_atomic(&self, org_id: Uuid) -> BillingResult<bool> {
         // ATOMIC: Only pause if not already paused
         let result = sqlx::query(
@@ -462,6 +1018,70 @@ impl SpendCapService {
         Ok(())
     }
 
+    /// Pause an organization's API access while a payment dispute is open
+    ///
+    /// Independent of the spend cap: works for orgs without one, and
+    /// `unpause_org` doesn't clear it. Returns true if this call paused the org.
+    pub async fn pause_org_for_dispute(&self, org_id: Uuid) -> BillingResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organizations
+            SET dispute_paused_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND dispute_paused_at IS NULL
+            "#,
+        )
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?;
+
+        let paused = result.rows_affected() > 0;
+        if paused {
+            tracing::warn!(org_id = %org_id, "Organization paused due to payment dispute");
+        }
+        Ok(paused)
+    }
+
+    /// Lift a dispute pause once no dispute for the org remains open
+    ///
+    /// Returns true if the org was unpaused.
+    pub async fn unpause_org_after_dispute(&self, org_id: Uuid) -> BillingResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organizations o
+            SET dispute_paused_at = NULL, updated_at = NOW()
+            WHERE o.id = $1
+              AND o.dispute_paused_at IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM billing_disputes d
+                  WHERE d.org_id = o.id
+                    AND d.resolved_at IS NULL
+                    AND LOWER(d.status) NOT IN ('won', 'lost')
+              )
+            "#,
+        )
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?;
+
+        let unpaused = result.rows_affected() > 0;
+        if unpaused {
+            tracing::info!(org_id = %org_id, "Organization unpaused after dispute resolution");
+        }
+        Ok(unpaused)
+    }
+
+    /// Check if org is paused pending a payment dispute
+    pub async fn check_dispute_paused(&self, org_id: Uuid) -> BillingResult<bool> {
+        let paused: Option<bool> = sqlx::query_scalar(
+            "SELECT dispute_paused_at IS NOT NULL FROM organizations WHERE id = $1",
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(paused.unwrap_or(false))
+    }
+
     /// Set temporary override (admin function)
     pub async fn set_override(
         &self,
@@ -519,6 +1139,26 @@ impl SpendCapService {
         Ok(())
     }
 
+    /// Thresholds a hypothetical period spend would reach against the org's current cap
+    ///
+    /// Nothing is recorded or sent. Returns an empty list when the org has no cap.
+    pub async fn simulate_notifications(
+        &self,
+        org_id: Uuid,
+        hypothetical_spend_cents: i32,
+    ) -> BillingResult<Vec<Threshold>> {
+        let Some(cap) = self.get_spend_cap(org_id).await? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(thresholds_reached(
+            get_notification_thresholds(),
+            cap.cap_amount_cents,
+            cap.hard_pause_enabled,
+            hypothetical_spend_cents,
+        ))
+    }
+
     /// Reset spend for new billing period
     pub async fn reset_period_spend(&self, org_id: Uuid) -> BillingResult<()> {
         sqlx::query(
@@ -551,6 +1191,7 @@ impl SpendCapService {
             FROM users u
             JOIN organizations o ON o.id = u.org_id
             WHERE u.org_id = $1 AND u.role = 'owner'
+            ORDER BY u.created_at ASC, u.id ASC
             LIMIT 1
             ON CONFLICT (org_id, billing_period_start, threshold_percent) DO NOTHING
             RETURNING id
@@ -596,7 +1237,7 @@ impl SpendCapService {
     async fn get_billing_period_start(&self, org_id: Uuid) -> BillingResult<OffsetDateTime> {
         // Get from subscription or default to start of month
         let sub: Option<(OffsetDateTime,)> = sqlx::query_as(
-            "SELECT current_period_start FROM subscriptions WHERE org_id = $1 AND status = 'active'"
+            "SELECT current_period_start FROM subscriptions WHERE org_id = $1 AND status IN ('active', 'trialing', 'past_due')"
         )
         .bind(org_id)
         .fetch_optional(&self.pool)
@@ -622,6 +1263,7 @@ impl SpendCapService {
             JOIN organizations o ON o.id = u.org_id
             LEFT JOIN spend_caps sc ON sc.org_id = o.id
             WHERE u.org_id = $1 AND u.role = 'owner'
+            ORDER BY u.created_at ASC, u.id ASC
             LIMIT 1
             "#,
         )
@@ -632,3 +1274,384 @@ impl SpendCapService {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tax::TaxRounding;
+
+    #[test]
+    fn test_counted_spend_with_and_without_tax() {
+        let charges = [1_000, 2_500];
+        let tax = TaxBreakdown::compute("DE", "eur", 19.0, &charges, TaxRounding::HalfUp);
+        assert_eq!(tax.tax_amount_cents, 665);
+
+        // Default: caps compare against pre-tax overage
+        assert_eq!(counted_spend_cents(&charges, Some(&tax), false), 3_500);
+        assert_eq!(counted_spend_cents(&charges, Some(&tax), true), 4_165);
+        // Tax could not be calculated
+        assert_eq!(counted_spend_cents(&charges, None, true), 3_500);
+    }
+
+    #[test]
+    fn test_spend_cap_mode_pauses_only_in_hard_pause() {
+        assert_eq!(SpendCapMode::from_hard_pause(false), SpendCapMode::WarnOnly);
+        assert_eq!(SpendCapMode::from_hard_pause(true), SpendCapMode::HardPause);
+
+        for percentage in [50.0, 100.0, 250.0] {
+            assert!(!SpendCapMode::WarnOnly.pauses_at(percentage));
+        }
+        assert!(!SpendCapMode::HardPause.pauses_at(99.9));
+        assert!(SpendCapMode::HardPause.pauses_at(100.0));
+    }
+
+    #[test]
+    fn test_attributed_spend_follows_request_share() {
+        // A quarter of the requests carries a quarter of the spend
+        assert_eq!(attributed_spend_cents(10_000, 250, 1_000), 2_500);
+        // Rounds down
+        assert_eq!(attributed_spend_cents(1_000, 1, 3), 333);
+        assert_eq!(attributed_spend_cents(10_000, 1_000, 1_000), 10_000);
+        // No usage, no spend, or nothing to share
+        assert_eq!(attributed_spend_cents(10_000, 0, 1_000), 0);
+        assert_eq!(attributed_spend_cents(0, 250, 1_000), 0);
+        assert_eq!(attributed_spend_cents(10_000, 250, 0), 0);
+    }
+
+    #[test]
+    fn test_percent_of_base_rounds_up_and_keeps_minimum() {
+        // 50% of Team ($99) and Pro ($29)
+        assert_eq!(percent_of_base_cents(9_900, 50), 4_950);
+        assert_eq!(percent_of_base_cents(2_900, 50), 1_450);
+        // 33% of $29 = 957 cents, below the $10 minimum
+        assert_eq!(percent_of_base_cents(2_900, 33), MIN_SPEND_CAP_CENTS);
+        // Fractional cents round up
+        assert_eq!(percent_of_base_cents(10_001, 50), 5_001);
+        assert_eq!(percent_of_base_cents(9_900, 255), 25_245);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_percent_cap_follows_tier_and_absolute_wins() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = SpendCapService::new(pool.clone(), BillingEmailService::from_env());
+        let org_id = Uuid::new_v4();
+
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, $2, $3, 'team')",
+        )
+        .bind(org_id)
+        .bind("Spend Cap Percent Test")
+        .bind(format!("spend-cap-percent-{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let cap = service
+            .set_spend_cap(
+                org_id,
+                SpendCapRequest {
+                    cap_amount_cents: None,
+                    cap_percent_of_base: Some(50),
+                    hard_pause_enabled: true,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(cap.cap_amount_cents, 4_950);
+
+        // Downgrade to Pro: the cap is recomputed at the next check
+        sqlx::query("UPDATE organizations SET subscription_tier = 'pro' WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let cap = service.get_spend_cap(org_id).await.unwrap().unwrap();
+        assert_eq!(cap.cap_amount_cents, 1_450);
+        let stored: i32 =
+            sqlx::query_scalar("SELECT cap_amount_cents FROM spend_caps WHERE org_id = $1")
+                .bind(org_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(stored, 1_450);
+
+        // Both set: the absolute amount wins and the percentage is dropped
+        let cap = service
+            .set_spend_cap(
+                org_id,
+                SpendCapRequest {
+                    cap_amount_cents: Some(20_000),
+                    cap_percent_of_base: Some(50),
+                    hard_pause_enabled: true,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(cap.cap_amount_cents, 20_000);
+        assert_eq!(cap.cap_percent_of_base, None);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_tax_inclusive_cap_trips_earlier() {
+        use crate::client::{StripeClient, StripeConfig};
+        use crate::tax::TaxStrategy;
+
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let pre_tax = SpendCapService::new(pool.clone(), BillingEmailService::from_env());
+        let with_tax = SpendCapService::new(pool.clone(), BillingEmailService::from_env())
+            .with_tax(
+                TaxService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone())
+                    .with_strategy(TaxStrategy::ManualRates(
+                        [("DE".to_string(), 19.0)].into_iter().collect(),
+                    )),
+            );
+        let org_id = Uuid::new_v4();
+
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier, billing_country)
+             VALUES ($1, $2, $3, 'team', 'DE')",
+        )
+        .bind(org_id)
+        .bind("Spend Cap Tax Test")
+        .bind(format!("spend-cap-tax-{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pre_tax
+            .set_spend_cap(
+                org_id,
+                SpendCapRequest {
+                    cap_amount_cents: Some(10_000),
+                    cap_percent_of_base: None,
+                    hard_pause_enabled: false,
+                },
+            )
+            .await
+            .unwrap();
+
+        // $90 of overage: under a $100 cap, over it with 19% tax ($107.10)
+        sqlx::query(
+            r#"
+            INSERT INTO overage_charges (
+                org_id, billing_period_start, billing_period_end,
+                resource_type, base_limit, actual_usage, overage_amount,
+                rate_per_unit_cents, total_charge_cents, status
+            )
+            VALUES ($1, NOW() - INTERVAL '10 days', NOW() + INTERVAL '20 days',
+                    'requests', 1000, 10000, 9000, 1, 9000, 'pending')
+            "#,
+        )
+        .bind(org_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let result = pre_tax.sync_spend_from_overages(org_id).await.unwrap();
+        assert!(
+            matches!(
+                result,
+                SpendCapCheckResult::Ok {
+                    spend_cents: 9_000,
+                    ..
+                }
+            ),
+            "{:?}",
+            result
+        );
+
+        let result = with_tax.sync_spend_from_overages(org_id).await.unwrap();
+        assert!(
+            matches!(
+                result,
+                SpendCapCheckResult::Exceeded {
+                    spend_cents: 10_710,
+                    mode: SpendCapMode::WarnOnly,
+                    ..
+                }
+            ),
+            "{:?}",
+            result
+        );
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_member_over_sub_cap_is_suspended_not_org() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = SpendCapService::new(pool.clone(), BillingEmailService::from_env());
+        let org_id = Uuid::new_v4();
+        let owner_id = Uuid::new_v4();
+        let member_id = Uuid::new_v4();
+
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, $2, $3, 'team')",
+        )
+        .bind(org_id)
+        .bind("Member Spend Cap Test")
+        .bind(format!("member-spend-cap-{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (user_id, role) in [(owner_id, "owner"), (member_id, "member")] {
+            sqlx::query(
+                "INSERT INTO organization_members (org_id, user_id, role, status) VALUES ($1, $2, $3, 'active')",
+            )
+            .bind(org_id)
+            .bind(user_id)
+            .bind(role)
+            .execute(&pool)
+            .await
+            .unwrap();
+            // Owner and member each make half of the period's requests
+            let key_id: Uuid = sqlx::query_scalar(
+                "INSERT INTO api_keys (org_id, name, key_hash, key_prefix, created_by)
+                 VALUES ($1, 'test', 'hash', 'pmcp_', $2) RETURNING id",
+            )
+            .bind(org_id)
+            .bind(user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO usage_records (org_id, api_key_id, request_count, period_start, period_end)
+                 VALUES ($1, $2, 500, NOW(), NOW() + INTERVAL '1 hour')",
+            )
+            .bind(org_id)
+            .bind(key_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert!(matches!(
+            service.set_member_cap(org_id, owner_id, 1_000).await,
+            Err(BillingError::InvalidInput(_))
+        ));
+        service
+            .set_member_cap(org_id, member_id, 1_000)
+            .await
+            .unwrap();
+        let members = MemberSuspensionService::new(pool.clone());
+
+        // $30 org spend puts the member at $15, over their $10 sub-cap
+        service.sync_member_spend(org_id, 3_000).await.unwrap();
+        assert!(members
+            .is_member_suspended(org_id, member_id)
+            .await
+            .unwrap());
+        assert!(!members.is_member_suspended(org_id, owner_id).await.unwrap());
+        assert!(!service.check_paused(org_id).await.unwrap());
+        let over = service.get_status(org_id).await.unwrap().members_over_cap;
+        assert_eq!(over.len(), 1);
+        assert_eq!(over[0].user_id, member_id);
+        assert_eq!(over[0].current_spend_cents, 1_500);
+
+        // Charges paid: back under the cap and restored
+        service.sync_member_spend(org_id, 0).await.unwrap();
+        assert!(!members
+            .is_member_suspended(org_id, member_id)
+            .await
+            .unwrap());
+        assert!(service
+            .get_members_over_cap(org_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_warn_only_never_pauses() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = SpendCapService::new(pool.clone(), BillingEmailService::from_env());
+        let org_id = Uuid::new_v4();
+
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, $2, $3, 'pro')",
+        )
+        .bind(org_id)
+        .bind("Warn Only Test")
+        .bind(format!("warn-only-{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Start paused under HardPause, then switch to WarnOnly
+        service
+            .set_spend_cap(
+                org_id,
+                SpendCapRequest {
+                    cap_amount_cents: Some(1_000),
+                    cap_percent_of_base: None,
+                    hard_pause_enabled: true,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.update_spend(org_id, 1_500).await.unwrap(),
+            SpendCapCheckResult::Paused { .. }
+        ));
+        assert!(service.check_paused(org_id).await.unwrap());
+
+        let cap = service
+            .set_spend_cap(
+                org_id,
+                SpendCapRequest {
+                    cap_amount_cents: Some(1_000),
+                    cap_percent_of_base: None,
+                    hard_pause_enabled: false,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(cap.mode(), SpendCapMode::WarnOnly);
+        assert!(!cap.is_paused);
+
+        // Far over the cap: reported as exceeded, never paused
+        let result = service.update_spend(org_id, 5_000).await.unwrap();
+        assert!(matches!(
+            result,
+            SpendCapCheckResult::Exceeded {
+                mode: SpendCapMode::WarnOnly,
+                ..
+            }
+        ));
+        assert!(!service.try_pause_org_atomic(org_id).await.unwrap());
+        assert!(!service.check_paused(org_id).await.unwrap());
+        let cap = service.get_spend_cap(org_id).await.unwrap().unwrap();
+        assert!(!cap.is_paused);
+        assert_eq!(cap.current_period_spend_cents, 6_500);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
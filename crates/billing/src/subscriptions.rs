@@ -1,21 +1,33 @@
 //! Subscription management
 
-use plexmcp_shared::SubscriptionTier;
+use std::sync::OnceLock;
+
+use plexmcp_shared::{Money, SubscriptionTier};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use stripe::{
     CancelSubscription, CreateCustomer, CreateSubscription, CreateSubscriptionItems, Customer,
-    CustomerId, ListSubscriptions, Subscription, SubscriptionId,
-    SubscriptionStatus as StripeSubStatus, UpdateSubscription, UpdateSubscriptionItems,
+    CustomerId, ListSubscriptions, RecurringInterval, RecurringUsageType, Subscription,
+    SubscriptionId, SubscriptionStatus as StripeSubStatus, SubscriptionStatusFilter,
+    UpdateSubscription, UpdateSubscriptionItems,
 };
 // Import the proration behavior enum from the subscription module (not subscription_item)
 use stripe::generated::billing::subscription::SubscriptionProrationBehavior;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
-use crate::client::StripeClient;
+use crate::checkout::BillingInterval;
+use crate::client::{idempotency_key, StripeClient, StripeConfig};
+use crate::db::{commit_or_rollback, PgTx};
+use crate::email::BillingEmailService;
 use crate::error::{BillingError, BillingResult};
-use crate::events::{ActorType, BillingEventBuilder, BillingEventLogger, BillingEventType};
-use crate::refund::RefundService;
+use crate::events::{
+    ActorType, BillingEvent, BillingEventBuilder, BillingEventLogger, BillingEventType,
+};
+use crate::member_suspension::{AffectedMembersInfo, MemberSuspensionService};
+use crate::owners::get_primary_owner;
+use crate::refund::{RefundReason, RefundService};
+use crate::trial_payment::TrialPaymentService;
 
 /// Custom SubscriptionItemFilter that uses `price` instead of `plan`
 /// The async-stripe 0.39 library's SubscriptionItemFilter only has `plan`,
@@ -55,6 +67,264 @@ pub struct ScheduledDowngrade {
     pub effective_date: OffsetDateTime,
 }
 
+/// A row from the `tier_change_audit` trail
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+pub struct TierChangeAuditRecord {
+    pub id: Uuid,
+    pub org_id: Uuid,
+    pub from_tier: String,
+    pub to_tier: String,
+    /// admin_panel, user_upgrade, user_downgrade, stripe_webhook, system
+    pub source: String,
+    pub changed_by: Option<Uuid>,
+    pub reason: Option<String>,
+    pub stripe_event_id: Option<String>,
+    pub metadata: Option<serde_json::Value>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Position after the last record of a page, in `created_at DESC, id DESC` order
+///
+/// Carries the id as well as the timestamp so records logged in the same
+/// instant are neither skipped nor repeated across pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct TierChangeHistoryCursor {
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    pub id: Uuid,
+}
+
+/// Cursor for the next page of tier change history
+///
+/// `records` must be newest first. Returns `None` when the page was not
+/// full, i.e. there is nothing older to fetch.
+pub fn tier_change_history_cursor(
+    records: &[TierChangeAuditRecord],
+    limit: i64,
+) -> Option<TierChangeHistoryCursor> {
+    if limit <= 0 || (records.len() as i64) < limit {
+        return None;
+    }
+    records.last().map(|r| TierChangeHistoryCursor {
+        created_at: r.created_at,
+        id: r.id,
+    })
+}
+
+/// A subscription renewal expected within a forecast window
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct UpcomingCharge {
+    pub org_id: Uuid,
+    pub stripe_subscription_id: Option<String>,
+    pub tier: String,
+    /// When the subscription renews (or its trial converts)
+    #[serde(with = "time::serde::rfc3339")]
+    pub renews_at: OffsetDateTime,
+    /// Plan price: the org's custom price, else the Stripe list price, else the tier default
+    pub base_cents: i64,
+    /// Active add-ons at their recorded unit price and quantity
+    pub addons_cents: i64,
+    /// Pending overage charges that will be added to the renewal invoice
+    pub estimated_overage_cents: i64,
+    /// Sum of the above
+    pub expected: Money,
+}
+
+/// Position after the last charge of a page, in `renews_at, org_id` order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UpcomingChargeCursor {
+    #[serde(with = "time::serde::rfc3339")]
+    pub renews_at: OffsetDateTime,
+    pub org_id: Uuid,
+}
+
+/// One page of [`SubscriptionService::upcoming_charges`]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct UpcomingChargesPage {
+    pub charges: Vec<UpcomingCharge>,
+    /// `None` when this was the last page
+    pub next_cursor: Option<UpcomingChargeCursor>,
+}
+
+/// Renewal row loaded for the upcoming charges forecast
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct UpcomingRenewal {
+    org_id: Uuid,
+    stripe_subscription_id: Option<String>,
+    stripe_price_id: Option<String>,
+    tier: String,
+    custom_monthly_price_cents: Option<i32>,
+    renews_at: OffsetDateTime,
+    addons_cents: i64,
+    overage_cents: i64,
+}
+
+impl UpcomingRenewal {
+    /// Compose the expected charge, given the Stripe list price of the plan if known
+    fn into_charge(self, list_price_cents: Option<i64>) -> UpcomingCharge {
+        let base_cents = self
+            .custom_monthly_price_cents
+            .map(i64::from)
+            .or(list_price_cents)
+            .or_else(|| {
+                self.tier
+                    .parse::<SubscriptionTier>()
+                    .ok()
+                    .and_then(|t| t.monthly_price_cents())
+                    .map(i64::from)
+            })
+            .unwrap_or(0);
+
+        UpcomingCharge {
+            org_id: self.org_id,
+            stripe_subscription_id: self.stripe_subscription_id,
+            tier: self.tier,
+            renews_at: self.renews_at,
+            base_cents,
+            addons_cents: self.addons_cents,
+            estimated_overage_cents: self.overage_cents,
+            expected: Money::from_cents_usd(base_cents + self.addons_cents + self.overage_cents),
+        }
+    }
+}
+
+/// Cursor for the page after `charges`, or `None` if the page was not full
+fn upcoming_charges_cursor(charges: &[UpcomingCharge], limit: i64) -> Option<UpcomingChargeCursor> {
+    if limit <= 0 || (charges.len() as i64) < limit {
+        return None;
+    }
+    charges.last().map(|c| UpcomingChargeCursor {
+        renews_at: c.renews_at,
+        org_id: c.org_id,
+    })
+}
+
+/// How far apart an audit row and its billing event may be logged
+///
+/// `change_tier` writes the audit row inside its transaction and logs the
+/// event after commit, so the two timestamps differ slightly.
+const AUDIT_EVENT_MATCH_WINDOW: time::Duration = time::Duration::minutes(5);
+
+/// Audit row reconstructed from a `TIER_CHANGED`/`TIER_CHANGE_SCHEDULED` event
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BackfilledAudit {
+    event_id: Uuid,
+    org_id: Uuid,
+    from_tier: String,
+    to_tier: String,
+    source: &'static str,
+    changed_by: Option<Uuid>,
+    reason: Option<String>,
+    scheduled: bool,
+    created_at: OffsetDateTime,
+}
+
+impl BackfilledAudit {
+    /// Returns `None` for other event types or events missing the tiers
+    fn from_event(event: &BillingEvent) -> Option<Self> {
+        let scheduled = if event.event_type == BillingEventType::TierChanged.to_string() {
+            false
+        } else if event.event_type == BillingEventType::TierChangeScheduled.to_string() {
+            true
+        } else {
+            return None;
+        };
+
+        let field = |name: &str| {
+            event
+                .event_data
+                .get(name)
+                .and_then(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+        };
+        let from_tier = field("from_tier")?;
+        let to_tier = field("to_tier")?;
+
+        let is_downgrade = event
+            .event_data
+            .get("is_downgrade")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        // Newer events carry the source; older ones only have the actor
+        let source = match (field("source"), event.actor_type.as_str()) {
+            (Some("admin_panel"), _) | (None, "admin") => TierChangeSource::AdminPanel,
+            (Some("user_upgrade"), _) => TierChangeSource::UserUpgrade,
+            (Some("user_downgrade"), _) => TierChangeSource::UserDowngrade,
+            (None, "user") if is_downgrade => TierChangeSource::UserDowngrade,
+            (None, "user") => TierChangeSource::UserUpgrade,
+            (Some("stripe_webhook"), _) | (None, "stripe") => TierChangeSource::StripeWebhook,
+            _ => TierChangeSource::System,
+        };
+
+        Some(Self {
+            event_id: event.id,
+            org_id: event.org_id,
+            from_tier: from_tier.to_string(),
+            to_tier: to_tier.to_string(),
+            source: source.as_str(),
+            changed_by: event.actor_id,
+            reason: field("reason").map(str::to_string),
+            scheduled,
+            created_at: event.created_at,
+        })
+    }
+
+    /// Whether `audit` already records this change
+    fn is_recorded_by(&self, audit: &TierChangeAuditRecord) -> bool {
+        let backfilled_from = audit
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("backfilled_from_event"))
+            .and_then(|v| v.as_str());
+        if let Some(event_id) = backfilled_from {
+            return event_id == self.event_id.to_string();
+        }
+
+        audit.org_id == self.org_id
+            && audit.from_tier == self.from_tier
+            && audit.to_tier == self.to_tier
+            && (audit.created_at - self.created_at).abs() <= AUDIT_EVENT_MATCH_WINDOW
+    }
+}
+
+/// Audit rows to create for tier change events with no matching audit row
+///
+/// Each existing audit row accounts for at most one event, so repeated
+/// identical changes close together are not collapsed.
+fn plan_audit_backfill(
+    events: &[BillingEvent],
+    existing: &[TierChangeAuditRecord],
+) -> Vec<BackfilledAudit> {
+    let mut used = vec![false; existing.len()];
+    let mut missing = Vec::new();
+
+    for audit in events.iter().filter_map(BackfilledAudit::from_event) {
+        match existing
+            .iter()
+            .enumerate()
+            .find(|(i, record)| !used[*i] && audit.is_recorded_by(record))
+        {
+            Some((i, _)) => used[i] = true,
+            None => missing.push(audit),
+        }
+    }
+
+    missing
+}
+
+/// Outcome of `SubscriptionService::backfill_audit_from_events`
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct AuditBackfillSummary {
+    /// Tier change events examined
+    pub events_scanned: usize,
+    /// Audit rows created
+    pub created: usize,
+    /// Events that already had an audit row
+    pub already_audited: usize,
+}
+
 /// Parameters for admin-initiated tier changes
 #[derive(Debug, Clone)]
 pub struct AdminTierChangeParams {
@@ -70,6 +340,8 @@ pub struct AdminTierChangeParams {
     pub billing_interval: Option<String>,
     /// Custom price in cents for Enterprise tier
     pub custom_price_cents: Option<i64>,
+    /// ISO 4217 currency of the custom Enterprise price (default: usd)
+    pub currency: Option<String>,
     /// Scheduled start date for subscription
     pub subscription_start_date: Option<OffsetDateTime>,
     /// Payment method: "immediate", "invoice", or "trial"
@@ -81,6 +353,328 @@ pub struct AdminTierChangeParams {
     /// For immediate downgrades: "refund" (money back to payment method) or "credit" (Stripe account credit)
     /// Defaults to "credit" for backwards compatibility
     pub refund_type: Option<String>,
+    /// Stripe reason code for refunds (default: requested_by_customer)
+    pub refund_reason: Option<RefundReason>,
+    /// Don't email the customer about refunds or credits (e.g. bulk changes)
+    pub skip_email: bool,
+    /// Memo, footer and custom fields for invoices created by this change
+    pub invoice_details: Option<InvoiceDetails>,
+}
+
+impl AdminTierChangeParams {
+    /// Check the tier, trial length and pricing before touching Stripe
+    pub fn validate(&self) -> BillingResult<()> {
+        let valid_tiers = ["free", "pro", "team", "enterprise"];
+        if !valid_tiers.contains(&self.new_tier.as_str()) {
+            return Err(BillingError::InvalidTier(format!(
+                "Invalid tier '{}'. Must be one of: {}",
+                self.new_tier,
+                valid_tiers.join(", ")
+            )));
+        }
+
+        // Stripe allows 0-730 trial days
+        if let Some(trial_days) = self.trial_days {
+            if trial_days > 730 {
+                return Err(BillingError::InvalidTier(format!(
+                    "Trial period must be between 0 and 730 days, got {}",
+                    trial_days
+                )));
+            }
+        }
+
+        if let Some(details) = &self.invoice_details {
+            details.validate()?;
+        }
+
+        self.currency()?;
+
+        // Refuse out-of-range custom prices
+        if self.new_tier == "enterprise" {
+            if let Some(custom_price) = self.custom_price_cents {
+                get_custom_price_bounds().validate(custom_price)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Currency for custom prices, defaulting to USD
+    pub fn currency(&self) -> BillingResult<stripe::Currency> {
+        match self.currency.as_deref() {
+            Some(code) => code.trim().to_ascii_lowercase().parse().map_err(|_| {
+                BillingError::InvalidInput(format!("Unsupported currency '{}'", code))
+            }),
+            None => Ok(stripe::Currency::USD),
+        }
+    }
+}
+
+/// Refuse a price in a different currency from the customer's
+///
+/// Stripe fixes a customer's currency with their first subscription or
+/// invoice and rejects anything mixing currencies. Customers without one yet
+/// accept any currency.
+fn check_customer_currency(
+    customer_currency: Option<stripe::Currency>,
+    requested: stripe::Currency,
+) -> BillingResult<()> {
+    match customer_currency {
+        Some(current) if current != requested => Err(BillingError::CurrencyMismatch {
+            customer_currency: current.to_string(),
+            requested_currency: requested.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// How many orgs `bulk_change_tier` changes at once
+const BULK_TIER_CHANGE_CONCURRENCY: usize = 5;
+
+/// Run `f` over `items` with at most `limit` running at once
+///
+/// Output is in input order; `None` marks a task that panicked.
+pub(crate) async fn run_bounded<T, R, F, Fut>(items: Vec<T>, limit: usize, f: F) -> Vec<Option<R>>
+where
+    F: Fn(T) -> Fut,
+    Fut: std::future::Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let mut results: Vec<Option<R>> = (0..items.len()).map(|_| None).collect();
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (i, item) in items.into_iter().enumerate() {
+        if tasks.len() >= limit.max(1) {
+            if let Some(Ok((done, result))) = tasks.join_next().await {
+                results[done] = Some(result);
+            }
+        }
+        let task = f(item);
+        tasks.spawn(async move { (i, task.await) });
+    }
+
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((done, result)) = joined {
+            results[done] = Some(result);
+        }
+    }
+
+    results
+}
+
+/// Maximum number of custom fields Stripe allows on an invoice
+pub const MAX_INVOICE_CUSTOM_FIELDS: usize = 4;
+
+/// Maximum length of a custom field name or value
+const MAX_INVOICE_CUSTOM_FIELD_LEN: usize = 30;
+
+/// A custom field shown on the invoice (e.g. PO number)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvoiceCustomField {
+    pub name: String,
+    pub value: String,
+}
+
+/// Extra details carried on admin-created invoices
+///
+/// Enterprise customers often require a PO number or memo on the invoice
+/// before they will pay it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvoiceDetails {
+    /// Memo shown on the invoice
+    pub description: Option<String>,
+    /// Footer shown on the invoice
+    pub footer: Option<String>,
+    /// Custom fields shown in the invoice header
+    #[serde(default)]
+    pub custom_fields: Vec<InvoiceCustomField>,
+}
+
+impl InvoiceDetails {
+    /// Whether there is nothing to set on the invoice
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none() && self.footer.is_none() && self.custom_fields.is_empty()
+    }
+
+    /// Check the details against Stripe's limits
+    pub fn validate(&self) -> BillingResult<()> {
+        if self.custom_fields.len() > MAX_INVOICE_CUSTOM_FIELDS {
+            return Err(BillingError::InvalidInput(format!(
+                "At most {} invoice custom fields are allowed, got {}",
+                MAX_INVOICE_CUSTOM_FIELDS,
+                self.custom_fields.len()
+            )));
+        }
+        for field in &self.custom_fields {
+            if field.name.trim().is_empty() || field.value.trim().is_empty() {
+                return Err(BillingError::InvalidInput(
+                    "Invoice custom fields need a name and a value".to_string(),
+                ));
+            }
+            if field.name.chars().count() > MAX_INVOICE_CUSTOM_FIELD_LEN
+                || field.value.chars().count() > MAX_INVOICE_CUSTOM_FIELD_LEN
+            {
+                return Err(BillingError::InvalidInput(format!(
+                    "Invoice custom field '{}' exceeds {} characters",
+                    field.name, MAX_INVOICE_CUSTOM_FIELD_LEN
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the details on invoice creation params
+    pub fn apply_to<'a>(&'a self, params: &mut stripe::CreateInvoice<'a>) {
+        params.description = self.description.as_deref();
+        params.footer = self.footer.as_deref();
+        if !self.custom_fields.is_empty() {
+            params.custom_fields = Some(
+                self.custom_fields
+                    .iter()
+                    .map(|f| stripe::CreateInvoiceCustomFields {
+                        name: f.name.clone(),
+                        value: f.value.clone(),
+                    })
+                    .collect(),
+            );
+        }
+    }
+
+    /// Form params for updating an existing draft invoice
+    ///
+    /// async-stripe 0.39 has no invoice update call, so these are posted
+    /// directly to `/v1/invoices/{id}`.
+    pub fn form_params(&self) -> Vec<(String, String)> {
+        let mut form = Vec::new();
+        if let Some(description) = &self.description {
+            form.push(("description".to_string(), description.clone()));
+        }
+        if let Some(footer) = &self.footer {
+            form.push(("footer".to_string(), footer.clone()));
+        }
+        for (i, field) in self.custom_fields.iter().enumerate() {
+            form.push((format!("custom_fields[{}][name]", i), field.name.clone()));
+            form.push((format!("custom_fields[{}][value]", i), field.value.clone()));
+        }
+        form
+    }
+}
+
+/// Default floor for custom Enterprise prices ($100)
+const DEFAULT_CUSTOM_PRICE_MIN_CENTS: i64 = 10_000;
+
+/// Default platform ceiling for custom Enterprise prices ($1,000,000)
+const DEFAULT_CUSTOM_PRICE_MAX_CENTS: i64 = 100_000_000;
+
+/// Allowed range for custom Enterprise price amounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CustomPriceBounds {
+    pub min_cents: i64,
+    pub max_cents: i64,
+}
+
+impl Default for CustomPriceBounds {
+    fn default() -> Self {
+        Self {
+            min_cents: DEFAULT_CUSTOM_PRICE_MIN_CENTS,
+            max_cents: DEFAULT_CUSTOM_PRICE_MAX_CENTS,
+        }
+    }
+}
+
+impl CustomPriceBounds {
+    /// Create bounds from environment variables
+    ///
+    /// `ENTERPRISE_PRICE_MIN_CENTS` and `ENTERPRISE_PRICE_MAX_CENTS` override
+    /// the defaults. The floor is never below 1 cent.
+    pub fn from_env() -> Self {
+        let mut bounds = Self::default();
+        if let Some(min) = std::env::var("ENTERPRISE_PRICE_MIN_CENTS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+        {
+            bounds.min_cents = min.max(1);
+        }
+        if let Some(max) = std::env::var("ENTERPRISE_PRICE_MAX_CENTS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+        {
+            bounds.max_cents = max;
+        }
+        if bounds.max_cents < bounds.min_cents {
+            tracing::warn!(
+                min_cents = bounds.min_cents,
+                max_cents = bounds.max_cents,
+                "Enterprise price ceiling below floor, using defaults"
+            );
+            return Self::default();
+        }
+        bounds
+    }
+
+    /// Reject amounts outside the configured range
+    pub fn validate(&self, amount_cents: i64) -> BillingResult<()> {
+        if amount_cents < self.min_cents || amount_cents > self.max_cents {
+            return Err(BillingError::InvalidAmount(format!(
+                "Custom Enterprise price must be between {} and {} cents, got {}",
+                self.min_cents, self.max_cents, amount_cents
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Get configured custom Enterprise price bounds
+fn get_custom_price_bounds() -> CustomPriceBounds {
+    static BOUNDS: OnceLock<CustomPriceBounds> = OnceLock::new();
+    *BOUNDS.get_or_init(CustomPriceBounds::from_env)
+}
+
+/// Stripe interval for a custom price ("annual" or "year" bill yearly)
+fn custom_price_interval(interval: &str) -> stripe::CreatePriceRecurringInterval {
+    match interval {
+        "annual" | "year" => stripe::CreatePriceRecurringInterval::Year,
+        _ => stripe::CreatePriceRecurringInterval::Month,
+    }
+}
+
+/// Lookup key shared by identical custom prices for the same org
+///
+/// USD keys carry no currency suffix so prices created before other
+/// currencies were supported are still found.
+fn custom_price_lookup_key(
+    org_id: Uuid,
+    interval: stripe::CreatePriceRecurringInterval,
+    amount_cents: i64,
+    currency: stripe::Currency,
+) -> String {
+    let key = format!(
+        "enterprise_custom_{}_{}_{}",
+        org_id,
+        interval.as_str(),
+        amount_cents
+    );
+    if currency == stripe::Currency::USD {
+        key
+    } else {
+        format!("{}_{}", key, currency)
+    }
+}
+
+/// Whether an existing Stripe price can stand in for a new custom price
+fn is_reusable_custom_price(
+    price: &stripe::Price,
+    amount_cents: i64,
+    interval: stripe::CreatePriceRecurringInterval,
+    currency: stripe::Currency,
+) -> bool {
+    price.active == Some(true)
+        && price.unit_amount == Some(amount_cents)
+        && price.currency == Some(currency)
+        && price
+            .recurring
+            .as_ref()
+            .is_some_and(|r| r.interval.as_str() == interval.as_str() && r.interval_count == 1)
 }
 
 /// Result of an admin-initiated tier change
@@ -110,8 +704,8 @@ pub struct AdminTierChangeResult {
     pub scheduled_effective_date: Option<OffsetDateTime>,
     /// Whether a refund/credit was issued for this tier change
     pub refund_issued: bool,
-    /// Amount refunded/credited in cents (if any)
-    pub refund_amount_cents: Option<i64>,
+    /// Amount refunded/credited (if any)
+    pub refund_amount: Option<Money>,
     /// Type of refund: "refund" (money back) or "credit" (Stripe account credit)
     pub refund_type: Option<String>,
     /// Message explaining what happened
@@ -266,14 +860,73 @@ pub struct ReactivationResult {
     pub extra_trial_days: i32,
     /// Overages deducted from credit (in cents)
     pub overages_deducted_cents: i64,
-    /// Current period end of the new subscription
-    pub current_period_end: OffsetDateTime,
+    /// Current period end of the new subscription, if Stripe reported a valid period
+    pub current_period_end: Option<OffsetDateTime>,
     /// Trial end date if credit was applied
     pub trial_end: Option<OffsetDateTime>,
     /// Message explaining what happened
     pub message: String,
 }
 
+/// What a tier change would do to an org, shown to the user before they confirm
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TierChangeImpact {
+    pub current_tier: String,
+    pub new_tier: String,
+    pub is_downgrade: bool,
+    /// Members suspended once a downgrade takes effect
+    pub affected_members: Option<AffectedMembersInfo>,
+    pub mcp_count: i64,
+    pub max_mcps: u32,
+    pub api_key_count: i64,
+    pub max_api_keys: u32,
+    /// Immediate charge for an upgrade; None for downgrades
+    pub proration: Option<ProrationPreview>,
+    /// Pass back in a `ChangeConfirmation` to apply exactly this change
+    pub impact_token: String,
+}
+
+/// Caller's confirmation of a previewed tier change
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ChangeConfirmation {
+    /// `impact_token` from the `TierChangeImpact` the user saw
+    pub impact_token: String,
+}
+
+/// Fingerprint of the state a tier change preview was computed from
+///
+/// Changes whenever the tiers, the members that would be suspended, or the
+/// org's MCP and API key counts change.
+fn impact_token(
+    org_id: Uuid,
+    current_tier: &str,
+    new_tier: &str,
+    suspended_member_ids: &[Uuid],
+    mcp_count: i64,
+    api_key_count: i64,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(org_id.as_bytes());
+    hasher.update(format!(
+        "|{}|{}|{}|{}|",
+        current_tier, new_tier, mcp_count, api_key_count
+    ));
+    for member_id in suspended_member_ids {
+        hasher.update(member_id.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Reject a confirmation made against a different state than `current_token`
+fn check_confirmation(current_token: &str, confirmation: &ChangeConfirmation) -> BillingResult<()> {
+    if confirmation.impact_token != current_token {
+        return Err(BillingError::ConfirmationStale(
+            "members or resources changed since the preview; review the change again".to_string(),
+        ));
+    }
+    Ok(())
+}
+
 /// Preview of proration for subscription upgrade
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ProrationPreview {
@@ -281,82 +934,635 @@ pub struct ProrationPreview {
     pub current_tier: String,
     /// Target subscription tier
     pub new_tier: String,
-    /// Prorated amount in cents (credit or charge for remaining period)
-    pub proration_amount_cents: i64,
-    /// Outstanding overage charges in cents
-    pub overage_amount_cents: i64,
-    /// Total amount to be charged in cents (proration + overages)
-    pub total_amount_cents: i64,
+    /// Prorated amount (credit or charge for remaining period)
+    pub proration_amount: Money,
+    /// Outstanding overage charges
+    pub overage_amount: Money,
+    /// Total amount to be charged (proration + overages)
+    pub total_amount: Money,
     /// Days remaining in current billing period
     pub days_remaining: i32,
     /// Human-readable description of the proration
     pub description: String,
 }
 
-/// Subscription plan configuration
-#[derive(Debug, Clone)]
-pub struct Plan {
-    pub tier: SubscriptionTier,
-    pub stripe_price_id: String,
-    pub monthly_requests: u64,
-    pub max_mcps: u32,
-    pub max_users: u32,
-    pub max_api_keys: u32,
+/// How `preview_upgrade_proration` rounds the remaining period to whole days
+///
+/// Defaults to `Floor`: the preview shows only full days left, so it never
+/// promises more time than remains. `Ceil` was the historical behavior and
+/// overstates by a day for any partial day. Configure with
+/// `BILLING_PRORATION_DAY_ROUNDING` (`ceil`, `floor` or `round`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProrationRounding {
+    Ceil,
+    #[default]
+    Floor,
+    Round,
 }
 
-impl Plan {
-    /// Free tier: 5 MCPs, 5 API keys, 1K calls/month, 1 team member
-    pub fn free() -> Self {
-        Self {
-            tier: SubscriptionTier::Free,
-            stripe_price_id: String::new(),
-            monthly_requests: 1_000,
-            max_mcps: 5,
-            max_users: 1,
-            max_api_keys: 5,
-        }
+impl ProrationRounding {
+    /// Whole days remaining between `now` and `period_end` (unix seconds), never negative
+    pub fn days_remaining(self, period_end: i64, now: i64) -> i32 {
+        let days = (period_end - now).max(0) as f64 / 86400.0;
+        let rounded = match self {
+            Self::Ceil => days.ceil(),
+            Self::Floor => days.floor(),
+            Self::Round => days.round(),
+        };
+        rounded as i32
     }
+}
 
-    /// Pro tier: 20 MCPs, 20 API keys, 50K calls/month, 5 team members
-    pub fn pro(price_id: &str) -> Self {
-        Self {
-            tier: SubscriptionTier::Pro,
-            stripe_price_id: price_id.to_string(),
-            monthly_requests: 50_000,
-            max_mcps: 20,
-            max_users: 5,
-            max_api_keys: 20,
+impl std::str::FromStr for ProrationRounding {
+    type Err = BillingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ceil" => Ok(Self::Ceil),
+            "floor" => Ok(Self::Floor),
+            "round" => Ok(Self::Round),
+            other => Err(BillingError::Config(format!(
+                "Invalid proration rounding mode: {}",
+                other
+            ))),
         }
     }
+}
 
-    /// Team tier: 50 MCPs, 50 API keys, 250K calls/month, unlimited team members
-    pub fn team(price_id: &str) -> Self {
-        Self {
-            tier: SubscriptionTier::Team,
-            stripe_price_id: price_id.to_string(),
-            monthly_requests: 250_000,
-            max_mcps: 50,
-            max_users: u32::MAX,
-            max_api_keys: 50,
+/// Get configured day rounding for proration previews
+fn get_proration_rounding() -> ProrationRounding {
+    static ROUNDING: OnceLock<ProrationRounding> = OnceLock::new();
+    *ROUNDING.get_or_init(|| {
+        std::env::var("BILLING_PRORATION_DAY_ROUNDING")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    })
+}
+
+/// Human-readable description for a proration preview
+fn proration_description(current_tier: &str, new_tier: &str, days_remaining: i32) -> String {
+    let unit = if days_remaining == 1 { "day" } else { "days" };
+    format!(
+        "Upgrade from {} to {} with {} {} remaining",
+        current_tier, new_tier, days_remaining, unit
+    )
+}
+
+/// Old and new value of a single field changed by a sync
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FieldChange<T> {
+    pub old: T,
+    pub new: T,
+}
+
+/// What a `sync_subscription_to_db` call changed in the `subscriptions` row
+///
+/// Only material fields are compared. An idempotent re-sync of the same
+/// Stripe state produces an empty diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct SubscriptionDiff {
+    /// True when no row existed for the org before the sync
+    pub created: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<FieldChange<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_id: Option<FieldChange<Option<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_period_start: Option<FieldChange<Option<OffsetDateTime>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_period_end: Option<FieldChange<Option<OffsetDateTime>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_at_period_end: Option<FieldChange<bool>>,
+}
+
+impl SubscriptionDiff {
+    /// Compare the stored row (if any) against the incoming Stripe state
+    fn between(old: Option<&SubscriptionSnapshot>, new: &SubscriptionSnapshot) -> Self {
+        let Some(old) = old else {
+            return Self {
+                created: true,
+                ..Default::default()
+            };
+        };
+
+        fn change<T: Clone + PartialEq>(old: &T, new: &T) -> Option<FieldChange<T>> {
+            (old != new).then(|| FieldChange {
+                old: old.clone(),
+                new: new.clone(),
+            })
         }
-    }
 
-    /// Enterprise tier: Unlimited everything
-    pub fn enterprise(price_id: &str) -> Self {
         Self {
-            tier: SubscriptionTier::Enterprise,
-            stripe_price_id: price_id.to_string(),
-            monthly_requests: u64::MAX,
-            max_mcps: u32::MAX,
-            max_users: u32::MAX,
-            max_api_keys: u32::MAX,
+            created: false,
+            status: change(&old.status, &new.status),
+            price_id: change(&old.stripe_price_id, &new.stripe_price_id),
+            current_period_start: change(&old.current_period_start, &new.current_period_start),
+            current_period_end: change(&old.current_period_end, &new.current_period_end),
+            cancel_at_period_end: change(&old.cancel_at_period_end, &new.cancel_at_period_end),
         }
     }
+
+    /// True when nothing material changed
+    pub fn is_empty(&self) -> bool {
+        !self.created
+            && self.status.is_none()
+            && self.price_id.is_none()
+            && self.current_period_start.is_none()
+            && self.current_period_end.is_none()
+            && self.cancel_at_period_end.is_none()
+    }
 }
 
-/// Subscription service for managing Stripe subscriptions
-pub struct SubscriptionService {
-    stripe: StripeClient,
+/// Whether a subscription status entitles the org to its paid tier
+///
+/// Trialing and past-due (dunning grace) subscriptions keep the tier's quota;
+/// `incomplete` ones never paid and stay on free limits. Matches the
+/// `status IN ('active', 'trialing', 'past_due')` filter used in SQL.
+pub fn is_entitled_status(status: &str) -> bool {
+    matches!(status, "active" | "trialing" | "past_due")
+}
+
+/// Tier to apply when syncing a subscription, if its status entitles one
+fn entitled_tier(status: &str, price_tier: Option<&'static str>) -> Option<&'static str> {
+    price_tier.filter(|_| is_entitled_status(status))
+}
+
+/// Whether a paid upgrade is already in place, e.g. for a re-delivered checkout
+///
+/// Stripe must already be on the target price and the org on the target tier.
+/// If only Stripe is, the upgrade runs again to finish the database side.
+fn upgrade_already_applied(
+    current_price_id: Option<&str>,
+    target_price_id: &str,
+    org_tier: Option<&str>,
+    new_tier: &str,
+) -> bool {
+    current_price_id == Some(target_price_id)
+        && org_tier.is_some_and(|tier| tier.eq_ignore_ascii_case(new_tier))
+}
+
+/// Material subscription fields as stored in the `subscriptions` table
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+struct SubscriptionSnapshot {
+    status: String,
+    stripe_price_id: Option<String>,
+    current_period_start: Option<OffsetDateTime>,
+    current_period_end: Option<OffsetDateTime>,
+    cancel_at_period_end: bool,
+}
+
+impl SubscriptionSnapshot {
+    /// Whether the stored subscription is still billing
+    fn is_live(&self) -> bool {
+        is_entitled_status(&self.status)
+    }
+}
+
+/// Whether Stripe gave a definite answer, so the attempt's key can be retired
+///
+/// Stripe stores rejected results under the key as well, so a rejection is
+/// final. 409 means the key is still in use by a concurrent request, and a
+/// network error or timeout leaves the outcome unknown; both keep the key.
+fn stripe_outcome_is_confirmed<T>(result: &Result<T, stripe::StripeError>) -> bool {
+    match result {
+        Ok(_) => true,
+        Err(stripe::StripeError::Stripe(e)) => e.http_status != 409,
+        Err(_) => false,
+    }
+}
+
+/// What a Stripe subscription exists for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubscriptionKind {
+    /// Paid (or trialing) tier subscription
+    Tier,
+    /// $0 base created so free-tier orgs can buy add-ons
+    AddonOnly,
+}
+
+impl SubscriptionKind {
+    /// Classify by base price (the first subscription item)
+    pub fn from_price_id(price_id: Option<&str>, addon_only_price_id: Option<&str>) -> Self {
+        match (price_id, addon_only_price_id) {
+            (Some(price), Some(addon_only)) if price == addon_only => Self::AddonOnly,
+            _ => Self::Tier,
+        }
+    }
+
+    /// Classify a Stripe subscription by its metadata or base price
+    pub fn of(subscription: &Subscription, addon_only_price_id: Option<&str>) -> Self {
+        if subscription.metadata.get("type").map(String::as_str) == Some("addon_only") {
+            return Self::AddonOnly;
+        }
+        let price_id = subscription
+            .items
+            .data
+            .first()
+            .and_then(|item| item.price.as_ref())
+            .map(|p| p.id.as_str());
+        Self::from_price_id(price_id, addon_only_price_id)
+    }
+}
+
+/// Fix for a subscription whose Stripe price disagrees with the stored tier
+///
+/// The organization's stored tier is authoritative; the Stripe price is
+/// either moved to match it or flagged for someone to look at.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TierPriceFix {
+    /// Move the subscription item from `from_price_id` to `to_price_id`
+    AlignPrice {
+        from_price_id: String,
+        to_price_id: String,
+    },
+    /// Needs a human; changing the price automatically would be unsafe
+    Flag { reason: String },
+}
+
+/// A tier/price mismatch found by `repair_tier_price_mismatches`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RepairAction {
+    pub org_id: Uuid,
+    pub stripe_subscription_id: String,
+    /// Tier stored for the organization
+    pub db_tier: String,
+    /// Tier of the Stripe price
+    pub stripe_tier: String,
+    pub fix: TierPriceFix,
+    /// Whether the fix was carried out (always false in a dry run and for flags)
+    pub applied: bool,
+    /// Why applying the fix failed, if it did
+    pub error: Option<String>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct TierPriceRow {
+    org_id: Uuid,
+    db_tier: String,
+    stripe_subscription_id: String,
+    stripe_price_id: String,
+}
+
+/// Decide how to reconcile a stored tier with the subscription's Stripe price
+///
+/// Returns the Stripe price's tier with the fix, or `None` when they agree.
+/// Prices the config doesn't know (custom and negotiated prices) aren't
+/// judged; the add-on-only base is consistent with the free tier only.
+fn plan_tier_price_fix(
+    config: &StripeConfig,
+    db_tier: &str,
+    price_id: &str,
+) -> Option<(String, TierPriceFix)> {
+    let addon_only = config.price_ids.addon_only.as_deref();
+    let stripe_tier = match SubscriptionKind::from_price_id(Some(price_id), addon_only) {
+        SubscriptionKind::AddonOnly => "free",
+        SubscriptionKind::Tier => config.tier_for_price_id(price_id)?,
+    };
+    if stripe_tier == db_tier {
+        return None;
+    }
+
+    let flag = |reason: String| Some((stripe_tier.to_string(), TierPriceFix::Flag { reason }));
+    match db_tier {
+        "free" => flag(format!(
+            "Organization is on free but Stripe bills the {} price",
+            stripe_tier
+        )),
+        "enterprise" => {
+            flag("Enterprise pricing is negotiated; align the price by hand".to_string())
+        }
+        _ if stripe_tier == "free" => flag(format!(
+            "Organization is on {} but only has the add-on-only base subscription",
+            db_tier
+        )),
+        _ => {
+            let interval = if config.is_annual_price(price_id) {
+                BillingInterval::Annual
+            } else {
+                BillingInterval::Monthly
+            };
+            match config.price_id_for_interval(db_tier, interval) {
+                Ok(to_price_id) => Some((
+                    stripe_tier.to_string(),
+                    TierPriceFix::AlignPrice {
+                        from_price_id: price_id.to_string(),
+                        to_price_id: to_price_id.to_string(),
+                    },
+                )),
+                Err(e) => flag(format!("No {} price to align to: {}", interval.as_str(), e)),
+            }
+        }
+    }
+}
+
+/// How a sync treats an incoming subscription that replaces a different stored one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubscriptionReplacement {
+    /// Overwrite the stored row as usual
+    Apply,
+    /// A paid subscription replaces the addon-only base: move its add-ons first
+    MigrateAddons,
+    /// Keep the stored live subscription; the incoming one is stale or secondary
+    Ignore,
+}
+
+impl SubscriptionReplacement {
+    fn decide(
+        stored: SubscriptionKind,
+        stored_live: bool,
+        incoming: SubscriptionKind,
+        incoming_live: bool,
+    ) -> Self {
+        match (stored, incoming) {
+            (SubscriptionKind::AddonOnly, SubscriptionKind::Tier) if incoming_live => {
+                Self::MigrateAddons
+            }
+            (stored, incoming) if stored != incoming && stored_live => Self::Ignore,
+            _ => Self::Apply,
+        }
+    }
+}
+
+/// An add-on item to move from the addon-only subscription onto a paid one
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AddonItemMove {
+    from_item_id: String,
+    price_id: String,
+    quantity: u64,
+    /// Item on the paid subscription that already has this price
+    existing_item_id: Option<String>,
+}
+
+/// Add-on items on an addon-only subscription that need to move to the paid one
+///
+/// The $0 base item stays behind and is canceled with the subscription.
+fn plan_addon_item_moves(
+    addon_only_items: &[stripe::SubscriptionItem],
+    paid_items: &[stripe::SubscriptionItem],
+    addon_only_price_id: Option<&str>,
+) -> Vec<AddonItemMove> {
+    addon_only_items
+        .iter()
+        .filter(|item| !item.deleted)
+        .filter_map(|item| {
+            let price_id = item.price.as_ref()?.id.as_str();
+            if Some(price_id) == addon_only_price_id {
+                return None;
+            }
+            let existing_item_id = paid_items
+                .iter()
+                .find(|paid| paid.price.as_ref().map(|p| p.id.as_str()) == Some(price_id))
+                .map(|paid| paid.id.to_string());
+            Some(AddonItemMove {
+                from_item_id: item.id.to_string(),
+                price_id: price_id.to_string(),
+                quantity: item.quantity.unwrap_or(1),
+                existing_item_id,
+            })
+        })
+        .collect()
+}
+
+/// Pick the subscription to restore for an org from its Stripe customer's list
+///
+/// Only live subscriptions (active, trialing, past_due) qualify, and any
+/// subscription tagged with a different `org_id` in its metadata is skipped.
+/// Active/trialing win over past_due; ties go to the most recently created.
+fn pick_recoverable_subscription(
+    subscriptions: &[Subscription],
+    org_id: Uuid,
+) -> Option<&Subscription> {
+    let org_id = org_id.to_string();
+
+    subscriptions
+        .iter()
+        .filter(|sub| {
+            sub.metadata
+                .get("org_id")
+                .is_none_or(|tagged| *tagged == org_id)
+        })
+        .filter_map(|sub| {
+            let rank = match sub.status {
+                StripeSubStatus::Active | StripeSubStatus::Trialing => 1,
+                StripeSubStatus::PastDue => 0,
+                _ => return None,
+            };
+            Some((rank, sub.created, sub))
+        })
+        .max_by_key(|(rank, created, _)| (*rank, *created))
+        .map(|(_, _, sub)| sub)
+}
+
+/// Price a scheduled downgrade should land on
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PendingPrice {
+    /// The tier's standard monthly price
+    Monthly,
+    /// The tier's standard annual price
+    Annual,
+    /// A custom Enterprise price; `interval` is "month" or "year"
+    Custom {
+        amount_cents: i64,
+        interval: &'static str,
+    },
+}
+
+/// Everything a scheduled downgrade changes at period end, in apply order:
+/// the tier first, then the price for that tier (`None` when downgrading to
+/// free, which cancels instead)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PeriodEndChange {
+    tier: String,
+    price: Option<PendingPrice>,
+}
+
+impl PeriodEndChange {
+    /// Combine a scheduled tier with the admin's pending price for it
+    ///
+    /// The price is resolved against the *new* tier, so a custom price only
+    /// applies when landing on Enterprise (as with immediate admin changes).
+    fn plan(tier: &str, custom_price_cents: Option<i64>, billing_interval: Option<&str>) -> Self {
+        let annual = billing_interval == Some("annual");
+        let price = match (tier, custom_price_cents) {
+            ("free", _) => None,
+            ("enterprise", Some(amount_cents)) => Some(PendingPrice::Custom {
+                amount_cents,
+                interval: if annual { "year" } else { "month" },
+            }),
+            _ if annual => Some(PendingPrice::Annual),
+            _ => Some(PendingPrice::Monthly),
+        };
+        Self {
+            tier: tier.to_string(),
+            price,
+        }
+    }
+}
+
+/// Page size when listing subscriptions for `total_mrr`
+const MRR_PAGE_SIZE: u64 = 100;
+
+/// Statuses that count towards MRR: billing now, not trialing or canceled
+const MRR_STATUSES: [SubscriptionStatusFilter; 2] = [
+    SubscriptionStatusFilter::Active,
+    SubscriptionStatusFilter::PastDue,
+];
+
+/// A recurring amount normalized to one month, rounded to the nearest cent
+///
+/// Yearly prices are divided by 12; weekly and daily ones are scaled by
+/// 52 weeks / 365 days per year.
+fn monthly_amount_cents(
+    amount_cents: i64,
+    interval: RecurringInterval,
+    interval_count: u64,
+) -> i64 {
+    let intervals_per_year: i128 = match interval {
+        RecurringInterval::Day => 365,
+        RecurringInterval::Week => 52,
+        RecurringInterval::Month => 12,
+        RecurringInterval::Year => 1,
+    };
+    let numerator = amount_cents as i128 * intervals_per_year;
+    let denominator = 12 * interval_count.max(1) as i128;
+    ((numerator * 2 + denominator) / (denominator * 2)) as i64
+}
+
+/// Monthly recurring revenue of a Stripe subscription at its list prices
+///
+/// Sums every licensed (per-seat or flat) item, add-ons included; metered
+/// items have no fixed amount and are skipped, as are discounts. Trialing,
+/// canceled, unpaid and collection-paused subscriptions contribute nothing.
+pub fn subscription_mrr(subscription: &Subscription) -> Money {
+    let currency = subscription.currency.to_string();
+    let billing = matches!(
+        subscription.status,
+        StripeSubStatus::Active | StripeSubStatus::PastDue
+    ) && subscription.pause_collection.is_none();
+    if !billing {
+        return Money::new(0, currency);
+    }
+
+    let amount_cents = subscription
+        .items
+        .data
+        .iter()
+        .filter_map(|item| {
+            let price = item.price.as_ref()?;
+            let recurring = price.recurring.as_ref()?;
+            if recurring.usage_type != RecurringUsageType::Licensed {
+                return None;
+            }
+            let amount = price.unit_amount? * item.quantity.unwrap_or(1) as i64;
+            Some(monthly_amount_cents(
+                amount,
+                recurring.interval,
+                recurring.interval_count,
+            ))
+        })
+        .sum();
+
+    Money::new(amount_cents, currency)
+}
+
+/// Validated billing period of a Stripe subscription
+///
+/// Returns `None` when either bound is zero, negative or unrepresentable, or
+/// when the period does not end after it starts. Callers must handle `None`
+/// explicitly: substituting "now" silently corrupts proration and renewal dates.
+pub fn subscription_period(
+    subscription: &Subscription,
+) -> Option<(OffsetDateTime, OffsetDateTime)> {
+    period_from_timestamps(
+        subscription.current_period_start,
+        subscription.current_period_end,
+    )
+}
+
+fn period_from_timestamps(start: i64, end: i64) -> Option<(OffsetDateTime, OffsetDateTime)> {
+    if start <= 0 || end <= start {
+        return None;
+    }
+    let start = OffsetDateTime::from_unix_timestamp(start).ok()?;
+    let end = OffsetDateTime::from_unix_timestamp(end).ok()?;
+    Some((start, end))
+}
+
+/// Add `amount` into the running per-currency `totals`
+fn add_to_currency_totals(totals: &mut Vec<Money>, amount: Money) {
+    match totals.iter_mut().find(|t| t.currency == amount.currency) {
+        Some(total) => total.amount_cents += amount.amount_cents,
+        None => totals.push(amount),
+    }
+}
+
+/// Subscription plan configuration
+#[derive(Debug, Clone)]
+pub struct Plan {
+    pub tier: SubscriptionTier,
+    pub stripe_price_id: String,
+    pub monthly_requests: u64,
+    pub max_mcps: u32,
+    pub max_users: u32,
+    pub max_api_keys: u32,
+}
+
+impl Plan {
+    /// Free tier: 5 MCPs, 5 API keys, 1K calls/month, 1 team member
+    pub fn free() -> Self {
+        Self {
+            tier: SubscriptionTier::Free,
+            stripe_price_id: String::new(),
+            monthly_requests: 1_000,
+            max_mcps: 5,
+            max_users: 1,
+            max_api_keys: 5,
+        }
+    }
+
+    /// Pro tier: 20 MCPs, 20 API keys, 50K calls/month, 5 team members
+    pub fn pro(price_id: &str) -> Self {
+        Self {
+            tier: SubscriptionTier::Pro,
+            stripe_price_id: price_id.to_string(),
+            monthly_requests: 50_000,
+            max_mcps: 20,
+            max_users: 5,
+            max_api_keys: 20,
+        }
+    }
+
+    /// Team tier: 50 MCPs, 50 API keys, 250K calls/month, unlimited team members
+    pub fn team(price_id: &str) -> Self {
+        Self {
+            tier: SubscriptionTier::Team,
+            stripe_price_id: price_id.to_string(),
+            monthly_requests: 250_000,
+            max_mcps: 50,
+            max_users: u32::MAX,
+            max_api_keys: 50,
+        }
+    }
+
+    /// Enterprise tier: Unlimited everything
+    pub fn enterprise(price_id: &str) -> Self {
+        Self {
+            tier: SubscriptionTier::Enterprise,
+            stripe_price_id: price_id.to_string(),
+            monthly_requests: u64::MAX,
+            max_mcps: u32::MAX,
+            max_users: u32::MAX,
+            max_api_keys: u32::MAX,
+        }
+    }
+}
+
+/// Subscription service for managing Stripe subscriptions
+pub struct SubscriptionService {
+    stripe: StripeClient,
     pool: PgPool,
     event_logger: BillingEventLogger,
 }
@@ -401,6 +1607,21 @@ impl SubscriptionService {
         org_id: Uuid,
         new_tier: &str,
         options: TierChangeOptions,
+    ) -> BillingResult<TierChangeResult> {
+        let tx = self.pool.begin().await?;
+        self.change_tier_in(tx, org_id, new_tier, options).await
+    }
+
+    /// `change_tier` inside a transaction the caller already opened
+    ///
+    /// Lets a caller that holds the org row lock record the change before
+    /// releasing it. Commits `tx`.
+    async fn change_tier_in(
+        &self,
+        mut tx: PgTx,
+        org_id: Uuid,
+        new_tier: &str,
+        options: TierChangeOptions,
     ) -> BillingResult<TierChangeResult> {
         let source = options.source.unwrap_or(TierChangeSource::System);
 
@@ -420,66 +1641,163 @@ impl SubscriptionService {
             )));
         }
 
-        // Start a transaction for atomic updates
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| BillingError::Database(e.to_string()))?;
+        // Atomic update: the scheduled or immediate change and its audit row
+        // commit together
+        let written = self
+            .write_tier_change(&mut tx, org_id, new_tier, source, &options)
+            .await;
+        let (current_tier, scheduled_at) = commit_or_rollback(tx, written).await?;
 
-        // Get current state with row lock (FOR UPDATE)
-        let current: Option<(String, i64)> = sqlx::query_as(
-            "SELECT subscription_tier, tier_version FROM organizations WHERE id = $1 FOR UPDATE",
-        )
-        .bind(org_id)
-        .fetch_optional(&mut *tx)
-        .await
-        .map_err(|e| BillingError::Database(e.to_string()))?;
+        if let Some(effective_date) = scheduled_at {
+            tracing::info!(
+                org_id = %org_id,
+                from_tier = %current_tier,
+                to_tier = %new_tier,
+                effective_date = %effective_date,
+                "Scheduled tier downgrade at period end"
+            );
 
-        let (current_tier, current_version) = current
-            .ok_or_else(|| BillingError::NotFound(format!("Organization {} not found", org_id)))?;
+            // Log billing event for scheduled tier change
+            let actor_type = match source {
+                TierChangeSource::AdminPanel => ActorType::Admin,
+                TierChangeSource::UserUpgrade | TierChangeSource::UserDowngrade => ActorType::User,
+                _ => ActorType::System,
+            };
+            if let Err(e) = self
+                .event_logger
+                .log_event(
+                    BillingEventBuilder::new(org_id, BillingEventType::TierChangeScheduled)
+                        .data(serde_json::json!({
+                            "from_tier": current_tier,
+                            "to_tier": new_tier,
+                            "effective_date": effective_date.to_string(),
+                            "is_downgrade": true,
+                        }))
+                        .actor_opt(options.changed_by, actor_type),
+                )
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to log scheduled tier change event");
+            }
 
-        // Check if this is a downgrade
-        let is_downgrade = self.is_tier_downgrade(&current_tier, new_tier);
-        let downgrade_timing = options
-            .downgrade_timing
-            .as_deref()
-            .unwrap_or(if is_downgrade {
-                "end_of_period"
-            } else {
-                "immediate"
+            return Ok(TierChangeResult {
+                success: true,
+                from_tier: current_tier,
+                to_tier: new_tier.to_string(),
+                stripe_subscription_id: None,
+                scheduled: true,
+                effective_date: Some(effective_date),
+                credit_cents: None,
+                message: format!("Downgrade to {} scheduled for {}", new_tier, effective_date),
             });
+        }
 
-        // If this is a scheduled downgrade (end of period), record it and return
-        if is_downgrade && downgrade_timing == "end_of_period" {
-            // Check if there's already a scheduled downgrade (prevents silent overwrites)
-            let existing_scheduled: Option<(Option<String>,)> = sqlx::query_as(
-                "SELECT scheduled_downgrade_tier FROM subscriptions WHERE org_id = $1",
-            )
-            .bind(org_id)
-            .fetch_optional(&mut *tx)
-            .await
-            .map_err(|e| BillingError::Database(e.to_string()))?;
-
-            if let Some((Some(existing_tier),)) = existing_scheduled {
-                if existing_tier != new_tier {
-                    tracing::warn!(
-                        org_id = %org_id,
-                        existing_scheduled_tier = %existing_tier,
-                        new_scheduled_tier = %new_tier,
-                        "Overwriting existing scheduled downgrade - previous scheduling will be replaced"
-                    );
-                }
-            }
+        tracing::info!(
+            org_id = %org_id,
+            from_tier = %current_tier,
+            to_tier = %new_tier,
+            source = %source,
+            "Tier changed successfully (DB is authoritative)"
+        );
+
+        // Log billing event for immediate tier change
+        let actor_type = match source {
+            TierChangeSource::AdminPanel => ActorType::Admin,
+            TierChangeSource::UserUpgrade | TierChangeSource::UserDowngrade => ActorType::User,
+            _ => ActorType::System,
+        };
+        let is_downgrade = self.is_tier_downgrade(&current_tier, new_tier);
+        if let Err(e) = self
+            .event_logger
+            .log_event(
+                BillingEventBuilder::new(org_id, BillingEventType::TierChanged)
+                    .data(serde_json::json!({
+                        "from_tier": current_tier,
+                        "to_tier": new_tier,
+                        "is_downgrade": is_downgrade,
+                        "source": source.as_str(),
+                    }))
+                    .actor_opt(options.changed_by, actor_type),
+            )
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to log tier change event");
+        }
+
+        let message = format!("Tier changed from {} to {}", &current_tier, new_tier);
+        Ok(TierChangeResult {
+            success: true,
+            from_tier: current_tier,
+            to_tier: new_tier.to_string(),
+            stripe_subscription_id: None,
+            scheduled: false,
+            effective_date: None,
+            credit_cents: None,
+            message,
+        })
+    }
+
+    /// Database half of `change_tier`, run inside its transaction
+    ///
+    /// Returns the previous tier and, for a scheduled downgrade, its effective date.
+    async fn write_tier_change(
+        &self,
+        tx: &mut PgTx,
+        org_id: Uuid,
+        new_tier: &str,
+        source: TierChangeSource,
+        options: &TierChangeOptions,
+    ) -> BillingResult<(String, Option<OffsetDateTime>)> {
+        // Get current state with row lock (FOR UPDATE)
+        let current: Option<(String, i64)> = sqlx::query_as(
+            "SELECT subscription_tier, tier_version FROM organizations WHERE id = $1 FOR UPDATE",
+        )
+        .bind(org_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let (current_tier, current_version) = current
+            .ok_or_else(|| BillingError::NotFound(format!("Organization {} not found", org_id)))?;
+
+        // Check if this is a downgrade
+        let is_downgrade = self.is_tier_downgrade(&current_tier, new_tier);
+        let downgrade_timing = options
+            .downgrade_timing
+            .as_deref()
+            .unwrap_or(if is_downgrade {
+                "end_of_period"
+            } else {
+                "immediate"
+            });
+
+        // If this is a scheduled downgrade (end of period), record it and return
+        if is_downgrade && downgrade_timing == "end_of_period" {
+            // Check if there's already a scheduled downgrade (prevents silent overwrites)
+            let existing_scheduled: Option<(Option<String>,)> = sqlx::query_as(
+                "SELECT scheduled_downgrade_tier FROM subscriptions WHERE org_id = $1",
+            )
+            .bind(org_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+            if let Some((Some(existing_tier),)) = existing_scheduled {
+                if existing_tier != new_tier {
+                    tracing::warn!(
+                        org_id = %org_id,
+                        existing_scheduled_tier = %existing_tier,
+                        new_scheduled_tier = %new_tier,
+                        "Overwriting existing scheduled downgrade - previous scheduling will be replaced"
+                    );
+                }
+            }
 
             // Get current period end from subscription
             let period_end: Option<OffsetDateTime> = sqlx::query_scalar(
                 "SELECT current_period_end FROM subscriptions WHERE org_id = $1",
             )
             .bind(org_id)
-            .fetch_optional(&mut *tx)
-            .await
-            .map_err(|e| BillingError::Database(e.to_string()))?
+            .fetch_optional(&mut **tx)
+            .await?
             .flatten();
 
             let effective_date = period_end.unwrap_or_else(OffsetDateTime::now_utc);
@@ -503,68 +1821,23 @@ impl SubscriptionService {
             .bind(options.changed_by)
             .bind(&options.reason)
             .bind(org_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| BillingError::Database(e.to_string()))?;
+            .execute(&mut **tx)
+            .await?;
 
             // Audit log
             self.log_tier_change_audit(
-                &mut tx,
+                tx,
                 org_id,
                 &current_tier,
                 new_tier,
                 source,
                 options.changed_by,
-                &options,
+                options,
                 true, // scheduled
             )
             .await?;
 
-            tx.commit()
-                .await
-                .map_err(|e| BillingError::Database(e.to_string()))?;
-
-            tracing::info!(
-                org_id = %org_id,
-                from_tier = %current_tier,
-                to_tier = %new_tier,
-                effective_date = %effective_date,
-                "Scheduled tier downgrade at period end"
-            );
-
-            // Log billing event for scheduled tier change
-            let actor_type = match source {
-                TierChangeSource::AdminPanel => ActorType::Admin,
-                TierChangeSource::UserUpgrade | TierChangeSource::UserDowngrade => ActorType::User,
-                _ => ActorType::System,
-            };
-            if let Err(e) = self
-                .event_logger
-                .log_event(
-                    BillingEventBuilder::new(org_id, BillingEventType::TierChangeScheduled)
-                        .data(serde_json::json!({
-                            "from_tier": current_tier,
-                            "to_tier": new_tier,
-                            "effective_date": effective_date.to_string(),
-                            "is_downgrade": true,
-                        }))
-                        .actor_opt(options.changed_by, actor_type),
-                )
-                .await
-            {
-                tracing::warn!(error = %e, "Failed to log scheduled tier change event");
-            }
-
-            return Ok(TierChangeResult {
-                success: true,
-                from_tier: current_tier,
-                to_tier: new_tier.to_string(),
-                stripe_subscription_id: None,
-                scheduled: true,
-                effective_date: Some(effective_date),
-                credit_cents: None,
-                message: format!("Downgrade to {} scheduled for {}", new_tier, effective_date),
-            });
+            return Ok((current_tier, Some(effective_date)));
         }
 
         // Immediate tier change - update the database (SOURCE OF TRUTH)
@@ -585,9 +1858,8 @@ impl SubscriptionService {
         .bind(options.changed_by)
         .bind(org_id)
         .bind(current_version)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| BillingError::Database(e.to_string()))?
+        .execute(&mut **tx)
+        .await?
         .rows_affected();
 
         if rows_affected == 0 {
@@ -602,7 +1874,7 @@ impl SubscriptionService {
             "UPDATE subscriptions SET version = version + 1, last_synced_at = NOW() WHERE org_id = $1"
         )
         .bind(org_id)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await
         .ok(); // Ignore if no subscription record exists
 
@@ -617,70 +1889,24 @@ impl SubscriptionService {
             "#,
         )
         .bind(org_id)
-        .execute(&mut *tx)
+        .execute(&mut **tx)
         .await
         .ok();
 
         // Audit log
         self.log_tier_change_audit(
-            &mut tx,
+            tx,
             org_id,
             &current_tier,
             new_tier,
             source,
             options.changed_by,
-            &options,
+            options,
             false, // not scheduled
         )
         .await?;
 
-        tx.commit()
-            .await
-            .map_err(|e| BillingError::Database(e.to_string()))?;
-
-        tracing::info!(
-            org_id = %org_id,
-            from_tier = %current_tier,
-            to_tier = %new_tier,
-            source = %source,
-            "Tier changed successfully (DB is authoritative)"
-        );
-
-        // Log billing event for immediate tier change
-        let actor_type = match source {
-            TierChangeSource::AdminPanel => ActorType::Admin,
-            TierChangeSource::UserUpgrade | TierChangeSource::UserDowngrade => ActorType::User,
-            _ => ActorType::System,
-        };
-        let is_downgrade = self.is_tier_downgrade(&current_tier, new_tier);
-        if let Err(e) = self
-            .event_logger
-            .log_event(
-                BillingEventBuilder::new(org_id, BillingEventType::TierChanged)
-                    .data(serde_json::json!({
-                        "from_tier": current_tier,
-                        "to_tier": new_tier,
-                        "is_downgrade": is_downgrade,
-                        "source": source.as_str(),
-                    }))
-                    .actor_opt(options.changed_by, actor_type),
-            )
-            .await
-        {
-            tracing::warn!(error = %e, "Failed to log tier change event");
-        }
-
-        let message = format!("Tier changed from {} to {}", &current_tier, new_tier);
-        Ok(TierChangeResult {
-            success: true,
-            from_tier: current_tier,
-            to_tier: new_tier.to_string(),
-            stripe_subscription_id: None,
-            scheduled: false,
-            effective_date: None,
-            credit_cents: None,
-            message,
-        })
+        Ok((current_tier, None))
     }
 
     /// Helper to determine if a tier change is a downgrade
@@ -769,7 +1995,11 @@ impl SubscriptionService {
         }]);
         params.metadata = Some(metadata);
 
-        let subscription = Subscription::create(self.stripe.inner(), params).await?;
+        let subscription = self
+            .idempotent(org_id, "create_subscription", tier, |stripe| async move {
+                Subscription::create(&stripe, params).await
+            })
+            .await?;
 
         // Store subscription in database
         self.sync_subscription_to_db(org_id, &subscription).await?;
@@ -818,7 +2048,14 @@ impl SubscriptionService {
         }]);
         params.metadata = Some(metadata);
 
-        let subscription = Subscription::create(self.stripe.inner(), params).await?;
+        let subscription = self
+            .idempotent(
+                org_id,
+                "create_addon_only_subscription",
+                "free",
+                |stripe| async move { Subscription::create(&stripe, params).await },
+            )
+            .await?;
 
         // Store subscription in database
         self.sync_subscription_to_db(org_id, &subscription).await?;
@@ -832,109 +2069,207 @@ impl SubscriptionService {
         Ok(subscription)
     }
 
-    /// Update an existing subscription to a new tier
-    pub async fn update_subscription(
+    /// Cancel an org's addon-only base subscription
+    ///
+    /// Used once no add-ons remain on it. With `at_period_end` the base stays
+    /// until the add-ons already paid for run out.
+    pub async fn cancel_addon_only_subscription(
         &self,
         org_id: Uuid,
-        new_tier: &str,
+        at_period_end: bool,
     ) -> BillingResult<Subscription> {
         let sub_id = self.get_subscription_id(org_id).await?;
 
-        let price_id = self
-            .stripe
-            .config()
-            .price_id_for_tier(new_tier)
-            .ok_or_else(|| BillingError::InvalidTier(new_tier.to_string()))?;
-
-        // Get current subscription to get the item ID
-        let current = Subscription::retrieve(self.stripe.inner(), &sub_id, &[]).await?;
-
-        let item_id = current
-            .items
-            .data
-            .first()
-            .map(|item| item.id.to_string())
-            .ok_or_else(|| BillingError::Internal("No subscription items found".to_string()))?;
-
-        let mut metadata = std::collections::HashMap::new();
-        metadata.insert("tier".to_string(), new_tier.to_string());
-
-        // Use struct initialization to satisfy clippy::field_reassign_with_default
-        let params = UpdateSubscription {
-            items: Some(vec![UpdateSubscriptionItems {
-                id: Some(item_id),
-                price: Some(price_id.to_string()),
+        let subscription = if at_period_end {
+            let params = UpdateSubscription {
+                cancel_at_period_end: Some(true),
                 ..Default::default()
-            }]),
-            metadata: Some(metadata),
-            // Explicitly enable proration so users are charged the prorated difference when upgrading
-            proration_behavior: Some(SubscriptionProrationBehavior::CreateProrations),
-            ..Default::default()
+            };
+            self.idempotent(
+                org_id,
+                "end_addon_only_subscription",
+                "free",
+                |stripe| async move { Subscription::update(&stripe, &sub_id, params).await },
+            )
+            .await?
+        } else {
+            let params = CancelSubscription {
+                cancellation_details: None,
+                invoice_now: None,
+                prorate: Some(true),
+            };
+            self.idempotent(
+                org_id,
+                "cancel_addon_only_subscription",
+                "free",
+                |stripe| async move { Subscription::cancel(&stripe, &sub_id, params).await },
+            )
+            .await?
         };
 
-        let subscription = Subscription::update(self.stripe.inner(), &sub_id, params)
-            .await
-            .map_err(|e| {
-                // Check if this is a payment method required error from Stripe
-                let err_str = e.to_string();
-                if err_str.contains("no attached payment source")
-                    || err_str.contains("no default payment method")
-                    || err_str.contains("resource_missing")
-                {
-                    tracing::warn!(
-                        org_id = %org_id,
-                        error = %err_str,
-                        "Subscription update failed: customer has no payment method"
-                    );
-                    return BillingError::PaymentMethodRequired;
-                }
-                BillingError::StripeApi(err_str)
-            })?;
-
-        // Update database
         self.sync_subscription_to_db(org_id, &subscription).await?;
 
-        // Use consolidated change_tier() for DB update + audit logging
-        let tier_options = TierChangeOptions {
-            source: Some(TierChangeSource::UserUpgrade),
-            ..Default::default()
-        };
-
-        self.change_tier(org_id, new_tier, tier_options).await?;
-
         tracing::info!(
             org_id = %org_id,
             subscription_id = %subscription.id,
-            new_tier = %new_tier,
-            "Updated subscription tier"
+            at_period_end = at_period_end,
+            "Cancelled add-on only subscription with no add-ons left"
         );
 
         Ok(subscription)
     }
 
-    /// Upgrade subscription to a new tier AFTER payment has been collected via checkout
-    /// This is called from the webhook when an upgrade_payment checkout completes
-    /// Uses proration_behavior::None since payment was already collected
-    pub async fn upgrade_subscription_after_payment(
+    /// Move add-on items from an org's addon-only subscription onto its new paid one
+    ///
+    /// Items whose price is already on the paid subscription are reused. The
+    /// add-on rows are repointed at the new items and the addon-only
+    /// subscription is canceled with credit for unused time. Safe to retry:
+    /// an already-canceled addon-only subscription is left alone.
+    async fn migrate_addon_only_items(
+        &self,
+        org_id: Uuid,
+        addon_only_subscription_id: &str,
+        paid: &Subscription,
+    ) -> BillingResult<usize> {
+        use stripe::{CreateSubscriptionItem, PriceId, SubscriptionItem};
+
+        let addon_only_id = addon_only_subscription_id
+            .parse::<SubscriptionId>()
+            .map_err(|e| BillingError::StripeApi(format!("Invalid subscription ID: {}", e)))?;
+        let addon_only = Subscription::retrieve(self.stripe.inner(), &addon_only_id, &[]).await?;
+        if addon_only.status == StripeSubStatus::Canceled {
+            return Ok(0);
+        }
+
+        let moves = plan_addon_item_moves(
+            &addon_only.items.data,
+            &paid.items.data,
+            self.stripe.config().price_ids.addon_only.as_deref(),
+        );
+
+        for item_move in &moves {
+            let new_item_id = match &item_move.existing_item_id {
+                Some(id) => id.clone(),
+                None => {
+                    let price_id = item_move
+                        .price_id
+                        .parse::<PriceId>()
+                        .map_err(|e| BillingError::StripeApi(format!("Invalid price ID: {}", e)))?;
+                    let mut params = CreateSubscriptionItem::new(paid.id.clone());
+                    params.price = Some(price_id);
+                    params.quantity = Some(item_move.quantity);
+                    // Each item moves once, so its own id is enough scope
+                    let stripe = self.stripe.with_idempotency_key(idempotency_key(
+                        org_id,
+                        "migrate_addon_item",
+                        &item_move.price_id,
+                        &item_move.from_item_id,
+                    ));
+                    SubscriptionItem::create(&stripe, params)
+                        .await?
+                        .id
+                        .to_string()
+                }
+            };
+
+            sqlx::query(
+                r#"
+                UPDATE subscription_addons
+                SET stripe_item_id = $1, updated_at = NOW()
+                WHERE org_id = $2 AND stripe_item_id = $3
+                "#,
+            )
+            .bind(&new_item_id)
+            .bind(org_id)
+            .bind(&item_move.from_item_id)
+            .execute(&self.pool)
+            .await?;
+        }
+
+        let params = CancelSubscription {
+            cancellation_details: None,
+            invoice_now: None,
+            prorate: Some(true),
+        };
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            "cancel_migrated_addon_only",
+            "free",
+            addon_only_id.as_str(),
+        ));
+        Subscription::cancel(&stripe, &addon_only_id, params).await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            addon_only_subscription_id = %addon_only_id,
+            paid_subscription_id = %paid.id,
+            moved_items = moves.len(),
+            "Migrated add-ons onto paid subscription"
+        );
+
+        Ok(moves.len())
+    }
+
+    /// Update an existing subscription to a new tier
+    pub async fn update_subscription(
         &self,
         org_id: Uuid,
         new_tier: &str,
-        billing_interval: &str,
     ) -> BillingResult<Subscription> {
-        let sub_id = self.get_subscription_id(org_id).await?;
+        let price_id = self
+            .stripe
+            .config()
+            .price_id_for_tier(new_tier)
+            .ok_or_else(|| BillingError::InvalidTier(new_tier.to_string()))?
+            .to_string();
 
-        // Get the appropriate price ID based on billing interval
-        let price_id = if billing_interval == "annual" {
-            self.stripe
-                .config()
-                .annual_price_id_for_tier(new_tier)
-                .ok_or_else(|| BillingError::InvalidTier(format!("{} (annual)", new_tier)))?
-        } else {
-            self.stripe
-                .config()
-                .price_id_for_tier(new_tier)
-                .ok_or_else(|| BillingError::InvalidTier(new_tier.to_string()))?
-        };
+        self.apply_tier_price(
+            org_id,
+            new_tier,
+            &price_id,
+            TierChangeOptions::user_upgrade(),
+        )
+        .await
+    }
+
+    /// Move the subscription's base item to `price_id` and record `new_tier`
+    ///
+    /// Tier metadata and price go to Stripe in one update, so a tier change
+    /// and a price change never race each other.
+    async fn apply_tier_price(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+        price_id: &str,
+        tier_options: TierChangeOptions,
+    ) -> BillingResult<Subscription> {
+        let subscription = self.update_stripe_price(org_id, new_tier, price_id).await?;
+
+        // Update database
+        self.sync_subscription_to_db(org_id, &subscription).await?;
+
+        // Use consolidated change_tier() for DB update + audit logging
+        self.change_tier(org_id, new_tier, tier_options).await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            subscription_id = %subscription.id,
+            new_tier = %new_tier,
+            price_id = %price_id,
+            "Updated subscription tier"
+        );
+
+        Ok(subscription)
+    }
+
+    /// Stripe half of `apply_tier_price`: move the base item to `price_id`
+    async fn update_stripe_price(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+        price_id: &str,
+    ) -> BillingResult<Subscription> {
+        let sub_id = self.get_subscription_id(org_id).await?;
 
         // Get current subscription to get the item ID
         let current = Subscription::retrieve(self.stripe.inner(), &sub_id, &[]).await?;
@@ -946,6 +2281,93 @@ impl SubscriptionService {
             .map(|item| item.id.to_string())
             .ok_or_else(|| BillingError::Internal("No subscription items found".to_string()))?;
 
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("tier".to_string(), new_tier.to_string());
+
+        // Use struct initialization to satisfy clippy::field_reassign_with_default
+        let params = UpdateSubscription {
+            items: Some(vec![UpdateSubscriptionItems {
+                id: Some(item_id),
+                price: Some(price_id.to_string()),
+                ..Default::default()
+            }]),
+            metadata: Some(metadata),
+            // Explicitly enable proration so users are charged the prorated difference when upgrading
+            proration_behavior: Some(SubscriptionProrationBehavior::CreateProrations),
+            ..Default::default()
+        };
+
+        self.idempotent(
+            org_id,
+            "update_subscription",
+            &format!("{}:{}", new_tier, price_id),
+            |stripe| async move { Subscription::update(&stripe, &sub_id, params).await },
+        )
+        .await
+        .map_err(|e| {
+            // Check if this is a payment method required error from Stripe
+            let err_str = e.to_string();
+            if err_str.contains("no attached payment source")
+                || err_str.contains("no default payment method")
+                || err_str.contains("resource_missing")
+            {
+                tracing::warn!(
+                    org_id = %org_id,
+                    error = %err_str,
+                    "Subscription update failed: customer has no payment method"
+                );
+                return BillingError::PaymentMethodRequired;
+            }
+            BillingError::StripeApi(err_str)
+        })
+    }
+
+    /// Upgrade subscription to a new tier AFTER payment has been collected via checkout
+    /// This is called from the webhook when an upgrade_payment checkout completes
+    /// Uses proration_behavior::None since payment was already collected
+    ///
+    /// Returns `None` without changing anything if the org is already on the
+    /// new tier and price, so a re-delivered checkout doesn't upgrade twice.
+    pub async fn upgrade_subscription_after_payment(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+        billing_interval: BillingInterval,
+    ) -> BillingResult<Option<Subscription>> {
+        let sub_id = self.get_subscription_id(org_id).await?;
+
+        let price_id = self
+            .stripe
+            .config()
+            .price_id_for_interval(new_tier, billing_interval)?;
+
+        // Get current subscription to get the item ID
+        let current = Subscription::retrieve(self.stripe.inner(), &sub_id, &[]).await?;
+
+        let current_item = current
+            .items
+            .data
+            .first()
+            .ok_or_else(|| BillingError::Internal("No subscription items found".to_string()))?;
+
+        let org_tier: Option<String> =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let current_price_id = current_item.price.as_ref().map(|p| p.id.as_str());
+        if upgrade_already_applied(current_price_id, price_id, org_tier.as_deref(), new_tier) {
+            tracing::info!(
+                org_id = %org_id,
+                subscription_id = %sub_id,
+                new_tier = %new_tier,
+                "Upgrade already applied, skipping"
+            );
+            return Ok(None);
+        }
+
+        let item_id = current_item.id.to_string();
+
         let mut metadata = std::collections::HashMap::new();
         metadata.insert("tier".to_string(), new_tier.to_string());
         metadata.insert("upgraded_via".to_string(), "checkout_payment".to_string());
@@ -963,7 +2385,13 @@ impl SubscriptionService {
             ..Default::default()
         };
 
-        let subscription = Subscription::update(self.stripe.inner(), &sub_id, params)
+        let subscription = self
+            .idempotent(
+                org_id,
+                "upgrade_after_payment",
+                new_tier,
+                |stripe| async move { Subscription::update(&stripe, &sub_id, params).await },
+            )
             .await
             .map_err(|e| BillingError::StripeApi(e.to_string()))?;
 
@@ -985,7 +2413,7 @@ impl SubscriptionService {
             "Upgraded subscription after checkout payment"
         );
 
-        Ok(subscription)
+        Ok(Some(subscription))
     }
 
     /// Preview the proration for upgrading to a new tier
@@ -1060,29 +2488,55 @@ impl SubscriptionService {
             ),
         ];
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post("https://api.stripe.com/v1/invoices/create_preview")
-            .bearer_auth(&self.stripe.config().secret_key)
+        let started = std::time::Instant::now();
+        let response = self
+            .stripe
+            .rest_request(reqwest::Method::POST, "invoices/create_preview")
             .form(&form_params)
             .send()
-            .await
-            .map_err(|e| BillingError::StripeApi(format!("Failed to call Stripe API: {}", e)))?;
+            .await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::error!(
+                    stripe_call = "invoices.create_preview",
+                    outcome = "failure",
+                    latency_ms = latency_ms,
+                    error = %e,
+                    "Stripe invoices/create_preview request failed"
+                );
+                return Err(BillingError::StripeApi(format!(
+                    "Failed to call Stripe API: {}",
+                    e
+                )));
+            }
+        };
 
         if !response.status().is_success() {
             let status = response.status();
             let error_body = response.text().await.unwrap_or_default();
+            let error = BillingError::from_stripe_error_body(status.as_u16(), &error_body);
             tracing::error!(
+                stripe_call = "invoices.create_preview",
+                outcome = "failure",
+                latency_ms = latency_ms,
                 status = %status,
                 error_body = %error_body,
+                error = %error,
                 "Stripe invoices/create_preview API failed"
             );
-            return Err(BillingError::StripeApi(format!(
-                "Stripe API error ({}): {}",
-                status, error_body
-            )));
+            return Err(error);
         }
 
+        tracing::info!(
+            stripe_call = "invoices.create_preview",
+            outcome = "success",
+            latency_ms = latency_ms,
+            "Stripe invoices/create_preview API succeeded"
+        );
+
         let upcoming_invoice: serde_json::Value = response.json().await.map_err(|e| {
             BillingError::StripeApi(format!("Failed to parse Stripe response: {}", e))
         })?;
@@ -1107,7 +2561,9 @@ impl SubscriptionService {
         // Calculate period info
         let period_end = current.current_period_end;
         let now = chrono::Utc::now().timestamp();
-        let days_remaining = ((period_end - now) as f64 / 86400.0).ceil() as i32;
+        // Stripe prorates to the second; the day count is display-only and the
+        // description is built from the same value so the two always agree
+        let days_remaining = get_proration_rounding().days_remaining(period_end, now);
 
         tracing::info!(
             org_id = %org_id,
@@ -1122,14 +2578,11 @@ impl SubscriptionService {
         Ok(ProrationPreview {
             current_tier: current_tier.to_string(),
             new_tier: new_tier.to_string(),
-            proration_amount_cents: total_amount,
-            overage_amount_cents: overage_cents,
-            total_amount_cents: total_amount + overage_cents,
+            proration_amount: Money::from_cents_usd(total_amount),
+            overage_amount: Money::from_cents_usd(overage_cents),
+            total_amount: Money::from_cents_usd(total_amount + overage_cents),
             days_remaining,
-            description: format!(
-                "Upgrade from {} to {} with {} days remaining",
-                current_tier, new_tier, days_remaining
-            ),
+            description: proration_description(current_tier, new_tier, days_remaining),
         })
     }
 
@@ -1143,7 +2596,11 @@ impl SubscriptionService {
             prorate: None,
         };
 
-        let subscription = Subscription::cancel(self.stripe.inner(), &sub_id, params).await?;
+        let subscription = self
+            .idempotent(org_id, "cancel_subscription", "", |stripe| async move {
+                Subscription::cancel(&stripe, &sub_id, params).await
+            })
+            .await?;
 
         // Update database
         self.sync_subscription_to_db(org_id, &subscription).await?;
@@ -1166,7 +2623,11 @@ impl SubscriptionService {
             ..Default::default()
         };
 
-        let subscription = Subscription::update(self.stripe.inner(), &sub_id, params).await?;
+        let subscription = self
+            .idempotent(org_id, "resume_subscription", "", |stripe| async move {
+                Subscription::update(&stripe, &sub_id, params).await
+            })
+            .await?;
 
         // Update database
         self.sync_subscription_to_db(org_id, &subscription).await?;
@@ -1180,71 +2641,240 @@ impl SubscriptionService {
         Ok(subscription)
     }
 
-    /// Schedule a downgrade to take effect at the end of the billing period
-    /// User keeps current tier until period ends, then automatically switches to new tier
-    pub async fn schedule_downgrade(
+    /// Preview a tier change: suspended members, resource usage against the
+    /// new limits and, for upgrades, the immediate charge
+    ///
+    /// The returned `impact_token` must be passed to `change_tier_confirmed`.
+    pub async fn downgrade_impact(
         &self,
         org_id: Uuid,
         new_tier: &str,
-    ) -> BillingResult<ScheduledDowngrade> {
-        // Validate the tier exists (skip for free tier which has no Stripe price)
-        if new_tier != "free" {
-            let _price_id = self
-                .stripe
-                .config()
-                .price_id_for_tier(new_tier)
-                .ok_or_else(|| BillingError::InvalidTier(new_tier.to_string()))?;
+    ) -> BillingResult<TierChangeImpact> {
+        let mut impact = self.tier_change_impact(org_id, new_tier).await?;
+        if !impact.is_downgrade {
+            impact.proration = Some(self.preview_upgrade_proration(org_id, new_tier).await?);
         }
+        Ok(impact)
+    }
 
-        // Get current subscription to verify it exists and get period end
-        let subscription = self
-            .get_subscription(org_id)
-            .await?
-            .ok_or_else(|| BillingError::SubscriptionNotFound(org_id.to_string()))?;
-
-        // Get current tier from metadata or price - must be resolvable for downgrade scheduling
-        let current_tier = subscription.metadata.get("tier")
-            .cloned()
-            .or_else(|| {
-                subscription.items.data.first()
-                    .and_then(|item| item.price.as_ref())
-                    .and_then(|p| self.stripe.config().tier_for_price_id(&p.id))
-                    .map(|s| s.to_string())
-            })
-            .ok_or_else(|| {
-                tracing::error!(
-                    org_id = %org_id,
-                    subscription_id = %subscription.id,
-                    "Cannot determine current tier for downgrade scheduling - no tier in metadata or recognizable price"
-                );
-                BillingError::InvalidTier(
-                    "Cannot determine current subscription tier. Please contact support.".to_string()
-                )
-            })?;
+    /// Apply a tier change previewed with `downgrade_impact`
+    ///
+    /// The impact is recomputed first; if members or resources changed since
+    /// the preview the change is refused with `ConfirmationStale`, so nobody is
+    /// suspended without having seen it. Downgrades are scheduled for period
+    /// end, upgrades are applied (and prorated) immediately.
+    ///
+    /// The org row stays locked from the check until the change is recorded,
+    /// so two confirmations (or a confirmation and an admin change) can't
+    /// both pass the check against the same state.
+    pub async fn change_tier_confirmed(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+        confirmation: ChangeConfirmation,
+    ) -> BillingResult<TierChangeResult> {
+        let mut tx = self.pool.begin().await?;
+        // NO KEY UPDATE: still exclusive against other tier changes, but lets
+        // the Stripe idempotency rows (which reference the org) be inserted
+        // while we hold it
+        sqlx::query_scalar::<_, Uuid>(
+            "SELECT id FROM organizations WHERE id = $1 FOR NO KEY UPDATE",
+        )
+        .bind(org_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| BillingError::NotFound(format!("Organization {} not found", org_id)))?;
 
-        // Verify this is actually a downgrade
-        let tier_order = |t: &str| -> u8 {
-            match t {
-                "free" => 0,
-                "pro" => 1,
-                "team" => 2,
-                "enterprise" => 3,
-                _ => 0,
-            }
-        };
+        let impact = self.tier_change_impact(org_id, new_tier).await?;
+        check_confirmation(&impact.impact_token, &confirmation)?;
 
-        if tier_order(new_tier) >= tier_order(&current_tier) {
-            return Err(BillingError::InvalidTier(format!(
-                "Cannot schedule downgrade from {} to {} - use upgrade flow instead",
-                current_tier, new_tier
-            )));
+        if impact.is_downgrade {
+            // Only writes the subscription row, so it runs under the org lock
+            let scheduled = self.schedule_downgrade(org_id, new_tier).await?;
+            tx.commit().await?;
+            return Ok(TierChangeResult {
+                success: true,
+                from_tier: scheduled.current_tier,
+                to_tier: scheduled.new_tier,
+                stripe_subscription_id: None,
+                scheduled: true,
+                effective_date: Some(scheduled.effective_date),
+                credit_cents: None,
+                message: format!(
+                    "Downgrade to {} scheduled for {}",
+                    new_tier, scheduled.effective_date
+                ),
+            });
         }
 
-        let period_end = OffsetDateTime::from_unix_timestamp(subscription.current_period_end)
-            .unwrap_or(OffsetDateTime::now_utc());
+        let price_id = self
+            .stripe
+            .config()
+            .price_id_for_tier(new_tier)
+            .ok_or_else(|| BillingError::InvalidTier(new_tier.to_string()))?
+            .to_string();
+        let subscription = self
+            .update_stripe_price(org_id, new_tier, &price_id)
+            .await?;
+        // Record the tier in the locked transaction; the subscription sync
+        // takes the org row itself, so it has to wait for the commit
+        self.change_tier_in(tx, org_id, new_tier, TierChangeOptions::user_upgrade())
+            .await?;
+        self.sync_subscription_to_db(org_id, &subscription).await?;
 
-        // Store the scheduled downgrade in database
-        sqlx::query(
+        Ok(TierChangeResult {
+            success: true,
+            from_tier: impact.current_tier.clone(),
+            to_tier: new_tier.to_string(),
+            stripe_subscription_id: Some(subscription.id.to_string()),
+            scheduled: false,
+            effective_date: None,
+            credit_cents: None,
+            message: format!("Tier changed from {} to {}", impact.current_tier, new_tier),
+        })
+    }
+
+    /// Database half of `downgrade_impact`, without the Stripe proration preview
+    async fn tier_change_impact(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+    ) -> BillingResult<TierChangeImpact> {
+        let tier: SubscriptionTier = new_tier
+            .parse()
+            .map_err(|e: String| BillingError::InvalidTier(e))?;
+
+        let current_tier: String =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| {
+                    BillingError::NotFound(format!("Organization {} not found", org_id))
+                })?;
+        if current_tier == new_tier {
+            return Err(BillingError::InvalidInput(format!(
+                "Organization is already on the {} tier",
+                new_tier
+            )));
+        }
+
+        let (mcp_count, api_key_count): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM mcp_instances WHERE org_id = $1),
+                (SELECT COUNT(*) FROM api_keys WHERE org_id = $1)
+            "#,
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let is_downgrade = self.is_tier_downgrade(&current_tier, new_tier);
+        let affected_members = if is_downgrade {
+            MemberSuspensionService::new(self.pool.clone())
+                .get_affected_members_info(org_id, new_tier)
+                .await?
+        } else {
+            None
+        };
+        let suspended_member_ids: Vec<Uuid> = affected_members
+            .iter()
+            .flat_map(|info| info.members_to_suspend.iter().map(|m| m.member_id))
+            .collect();
+
+        Ok(TierChangeImpact {
+            impact_token: impact_token(
+                org_id,
+                &current_tier,
+                new_tier,
+                &suspended_member_ids,
+                mcp_count,
+                api_key_count,
+            ),
+            current_tier,
+            new_tier: new_tier.to_string(),
+            is_downgrade,
+            affected_members,
+            mcp_count,
+            max_mcps: tier.max_mcps(),
+            api_key_count,
+            max_api_keys: tier.max_api_keys(),
+            proration: None,
+        })
+    }
+
+    /// Schedule a downgrade to take effect at the end of the billing period
+    /// User keeps current tier until period ends, then automatically switches to new tier
+    pub async fn schedule_downgrade(
+        &self,
+        org_id: Uuid,
+        new_tier: &str,
+    ) -> BillingResult<ScheduledDowngrade> {
+        // Validate the tier exists (skip for free tier which has no Stripe price)
+        if new_tier != "free" {
+            let _price_id = self
+                .stripe
+                .config()
+                .price_id_for_tier(new_tier)
+                .ok_or_else(|| BillingError::InvalidTier(new_tier.to_string()))?;
+        }
+
+        // Get current subscription to verify it exists and get period end
+        let subscription = self
+            .get_subscription(org_id)
+            .await?
+            .ok_or_else(|| BillingError::SubscriptionNotFound(org_id.to_string()))?;
+
+        // Get current tier from metadata or price - must be resolvable for downgrade scheduling
+        let current_tier = subscription.metadata.get("tier")
+            .cloned()
+            .or_else(|| {
+                subscription.items.data.first()
+                    .and_then(|item| item.price.as_ref())
+                    .and_then(|p| self.stripe.config().tier_for_price_id(&p.id))
+                    .map(|s| s.to_string())
+            })
+            .ok_or_else(|| {
+                tracing::error!(
+                    org_id = %org_id,
+                    subscription_id = %subscription.id,
+                    "Cannot determine current tier for downgrade scheduling - no tier in metadata or recognizable price"
+                );
+                BillingError::InvalidTier(
+                    "Cannot determine current subscription tier. Please contact support.".to_string()
+                )
+            })?;
+
+        // Verify this is actually a downgrade
+        let tier_order = |t: &str| -> u8 {
+            match t {
+                "free" => 0,
+                "pro" => 1,
+                "team" => 2,
+                "enterprise" => 3,
+                _ => 0,
+            }
+        };
+
+        if tier_order(new_tier) >= tier_order(&current_tier) {
+            return Err(BillingError::InvalidTier(format!(
+                "Cannot schedule downgrade from {} to {} - use upgrade flow instead",
+                current_tier, new_tier
+            )));
+        }
+
+        // The downgrade takes effect at period end; without a valid period there
+        // is no safe effective date to promise the customer
+        let (_, period_end) = subscription_period(&subscription).ok_or_else(|| {
+            BillingError::Internal(format!(
+                "Subscription {} has no valid billing period; cannot schedule downgrade",
+                subscription.id
+            ))
+        })?;
+
+        // Store the scheduled downgrade in database
+        sqlx::query(
             r#"
             UPDATE subscriptions
             SET scheduled_downgrade_tier = $1,
@@ -1265,7 +2895,14 @@ impl SubscriptionService {
                 cancel_at_period_end: Some(true),
                 ..Default::default()
             };
-            Subscription::update(self.stripe.inner(), &subscription.id, params).await?;
+            let sub_id = &subscription.id;
+            self.idempotent(
+                org_id,
+                "schedule_downgrade",
+                new_tier,
+                |stripe| async move { Subscription::update(&stripe, sub_id, params).await },
+            )
+            .await?;
 
             tracing::info!(
                 org_id = %org_id,
@@ -1348,7 +2985,7 @@ impl SubscriptionService {
             scheduled: true,
             scheduled_effective_date: Some(scheduled.effective_date),
             refund_issued: false,
-            refund_amount_cents: None,
+            refund_amount: None,
             refund_type: None,
             message: format!(
                 "Downgrade to {} scheduled for end of billing period ({})",
@@ -1379,8 +3016,13 @@ impl SubscriptionService {
             (params.new_tier == "enterprise", params.custom_price_cents)
         {
             // Create custom price for Enterprise
-            self.create_custom_enterprise_price(custom_price, &billing_interval, org_id)
-                .await?
+            self.create_custom_enterprise_price(
+                custom_price,
+                &billing_interval,
+                org_id,
+                params.currency()?,
+            )
+            .await?
         } else if billing_interval == "annual" {
             // Use annual price
             self.stripe
@@ -1430,10 +3072,16 @@ impl SubscriptionService {
             ..Default::default()
         };
 
-        let updated_subscription =
-            Subscription::update(self.stripe.inner(), &subscription.id, update_params)
-                .await
-                .map_err(|e| BillingError::StripeApi(e.to_string()))?;
+        let sub_id = &subscription.id;
+        let updated_subscription = self
+            .idempotent(
+                org_id,
+                "admin_immediate_downgrade",
+                &params.new_tier,
+                |stripe| async move { Subscription::update(&stripe, sub_id, update_params).await },
+            )
+            .await
+            .map_err(|e| BillingError::StripeApi(e.to_string()))?;
 
         // 5. Use consolidated change_tier() for DB update + audit logging
         // This ensures proper version locking and audit trail
@@ -1475,7 +3123,11 @@ impl SubscriptionService {
             });
 
         // Calculate and record the prorated credit for audit purposes
-        let refund_service = RefundService::new(self.stripe.clone(), self.pool.clone());
+        let refund_service = RefundService::new(
+            self.stripe.clone(),
+            self.pool.clone(),
+            BillingEmailService::from_env(),
+        );
         let credit_amount: Option<i64> = match refund_service
             .get_refundable_charge(subscription.id.as_str())
             .await
@@ -1498,6 +3150,7 @@ impl SubscriptionService {
                                 &params.reason,
                                 &current_tier,
                                 &params.new_tier,
+                                params.skip_email,
                             )
                             .await
                         {
@@ -1533,7 +3186,7 @@ impl SubscriptionService {
 
         // For immediate downgrades, credit is always issued via Stripe prorations
         let credit_msg = credit_amount
-            .map(|c| format!(" (${:.2} credit)", c as f64 / 100.0))
+            .map(|c| format!(" ({} credit)", Money::from_cents_usd(c)))
             .unwrap_or_default();
 
         Ok(AdminTierChangeResult {
@@ -1553,7 +3206,7 @@ impl SubscriptionService {
             scheduled: false,
             scheduled_effective_date: None,
             refund_issued: credit_amount.map(|c| c > 0).unwrap_or(false),
-            refund_amount_cents: credit_amount,
+            refund_amount: credit_amount.map(Money::from_cents_usd),
             refund_type: Some("credit".to_string()), // Using prorations = credit
             message: format!(
                 "Immediate downgrade to {} completed with prorated credit for unused time{}",
@@ -1615,7 +3268,7 @@ impl SubscriptionService {
                     scheduled: false,
                     scheduled_effective_date: None,
                     refund_issued: false,
-                    refund_amount_cents: None,
+                    refund_amount: None,
                     refund_type: None,
                     message: "Organization set to Free tier (no subscription existed)".to_string(),
                 });
@@ -1650,19 +3303,20 @@ impl SubscriptionService {
             metadata.insert("reason".to_string(), params.reason.clone());
             update_params.metadata = Some(metadata);
 
-            let updated =
-                stripe::Subscription::update(self.stripe.inner(), &subscription_id, update_params)
-                    .await?;
+            let sub_id = &subscription_id;
+            let updated = self
+                .idempotent(
+                    org_id,
+                    "admin_free_tier_downgrade",
+                    "free",
+                    |stripe| async move {
+                        stripe::Subscription::update(&stripe, sub_id, update_params).await
+                    },
+                )
+                .await?;
 
             // Get period end for the effective date
-            let period_end = if updated.current_period_end > 0 {
-                Some(
-                    OffsetDateTime::from_unix_timestamp(updated.current_period_end)
-                        .unwrap_or_else(|_| OffsetDateTime::now_utc()),
-                )
-            } else {
-                None
-            };
+            let period_end = subscription_period(&updated).map(|(_, end)| end);
 
             // Record scheduled downgrade in DB
             sqlx::query(
@@ -1700,7 +3354,7 @@ impl SubscriptionService {
                 scheduled: true,
                 scheduled_effective_date: period_end,
                 refund_issued: false,
-                refund_amount_cents: None,
+                refund_amount: None,
                 refund_type: None,
                 message: format!(
                     "Downgrade to Free tier scheduled for end of billing period{}",
@@ -1763,7 +3417,11 @@ impl SubscriptionService {
             .is_some()
             && !subscription_already_canceled
         {
-            let refund_service = RefundService::new(self.stripe.clone(), self.pool.clone());
+            let refund_service = RefundService::new(
+                self.stripe.clone(),
+                self.pool.clone(),
+                BillingEmailService::from_env(),
+            );
             let admin_user_id = params.admin_user_id.ok_or_else(|| {
                 BillingError::Internal("Admin user ID required for refund".to_string())
             })?;
@@ -1775,11 +3433,13 @@ impl SubscriptionService {
             {
                 Ok(charge) => {
                     // Calculate prorated amount
+                    // Never more than what's left after earlier partial refunds
                     let prorated_amount = RefundService::calculate_prorated_amount(
                         charge.amount_cents,
                         charge.period_start,
                         charge.period_end,
-                    );
+                    )
+                    .min(charge.refundable_cents());
 
                     if prorated_amount > 0 {
                         let refund_type = params.refund_type.as_deref().unwrap_or("credit");
@@ -1793,9 +3453,11 @@ impl SubscriptionService {
                                     &charge.charge_id,
                                     &charge.invoice_id,
                                     prorated_amount,
+                                    params.refund_reason.unwrap_or_default(),
                                     &params.reason,
                                     current_tier,
                                     "free",
+                                    params.skip_email,
                                 )
                                 .await
                             {
@@ -1827,6 +3489,7 @@ impl SubscriptionService {
                                     &params.reason,
                                     current_tier,
                                     "free",
+                                    params.skip_email,
                                 )
                                 .await
                             {
@@ -1893,15 +3556,15 @@ impl SubscriptionService {
             scheduled: false,
             scheduled_effective_date: None,
             refund_issued,
-            refund_amount_cents,
+            refund_amount: refund_amount_cents.map(Money::from_cents_usd),
             refund_type: params.refund_type.clone(),
             message: if subscription_already_canceled {
                 "Subscription was already canceled, organization set to Free tier".to_string()
             } else {
                 let refund_msg = match (refund_issued, refund_amount_cents) {
                     (true, Some(amount)) => format!(
-                        " (${:.2} {} issued)",
-                        amount as f64 / 100.0,
+                        " ({} {} issued)",
+                        Money::from_cents_usd(amount),
                         params.refund_type.as_deref().unwrap_or("credit")
                     ),
                     (false, Some(0)) => " (no prorated amount due)".to_string(),
@@ -2105,12 +3768,41 @@ impl SubscriptionService {
 
                 Ok(Some(sub))
             } else {
-                // Downgrade to different paid tier
-                let sub = self.update_subscription(org_id, &new_tier).await?;
+                // Downgrade to a different paid tier, landing directly on any
+                // pending price for that tier in the same Stripe update
+                let change = PeriodEndChange::plan(
+                    &new_tier,
+                    custom_price,
+                    claimed.admin_downgrade_billing_interval.as_deref(),
+                );
+                let price_id = match &change.price {
+                    Some(price) => {
+                        self.resolve_pending_price(org_id, &change.tier, price)
+                            .await?
+                    }
+                    None => return Err(BillingError::InvalidTier(new_tier.clone())),
+                };
+
+                let tier_options = TierChangeOptions {
+                    source: Some(if is_admin_scheduled {
+                        TierChangeSource::AdminPanel
+                    } else {
+                        TierChangeSource::UserDowngrade
+                    }),
+                    reason: Some(format!("Scheduled downgrade to {} tier", change.tier)),
+                    downgrade_timing: Some("immediate".to_string()),
+                    custom_price_cents: custom_price,
+                    billing_interval: claimed.admin_downgrade_billing_interval.clone(),
+                    ..Default::default()
+                };
+                let sub = self
+                    .apply_tier_price(org_id, &change.tier, &price_id, tier_options)
+                    .await?;
 
                 tracing::info!(
                     org_id = %org_id,
                     new_tier = %new_tier,
+                    price_id = %price_id,
                     "Successfully processed scheduled downgrade"
                 );
 
@@ -2160,24 +3852,12 @@ impl SubscriptionService {
         org_id: Uuid,
         params: AdminTierChangeParams,
     ) -> BillingResult<AdminTierChangeResult> {
-        // Step 1: Validate tier
-        let valid_tiers = ["free", "pro", "team", "enterprise"];
-        if !valid_tiers.contains(&params.new_tier.as_str()) {
-            return Err(BillingError::InvalidTier(format!(
-                "Invalid tier '{}'. Must be one of: {}",
-                params.new_tier,
-                valid_tiers.join(", ")
-            )));
-        }
-
-        // Step 2: Validate trial_days (Stripe allows 0-730 days)
-        if let Some(trial_days) = params.trial_days {
-            if trial_days > 730 {
-                return Err(BillingError::InvalidTier(format!(
-                    "Trial period must be between 0 and 730 days, got {}",
-                    trial_days
-                )));
-            }
+        // Steps 1-2: Validate tier, trial and pricing before touching Stripe
+        params.validate()?;
+        if params.trial_days.is_some() && !self.may_set_trial(org_id).await? {
+            return Err(BillingError::InvalidInput(
+                "Organization has already used its free trial".to_string(),
+            ));
         }
 
         // Step 2.5: Detect upgrade vs downgrade and route accordingly
@@ -2264,7 +3944,7 @@ impl SubscriptionService {
                     scheduled: false,
                     scheduled_effective_date: None,
                     refund_issued: false, // TODO: Integrate with RefundService for Free tier downgrades
-                    refund_amount_cents: None,
+                    refund_amount: None,
                     refund_type: None,
                     message: "Subscription cancelled, downgraded to Free tier immediately"
                         .to_string(),
@@ -2310,7 +3990,7 @@ impl SubscriptionService {
                     scheduled: false,
                     scheduled_effective_date: None,
                     refund_issued: false, // No subscription = no refund needed
-                    refund_amount_cents: None,
+                    refund_amount: None,
                     refund_type: None,
                     message: "Organization set to Free tier immediately".to_string(),
                 });
@@ -2363,42 +4043,43 @@ impl SubscriptionService {
 
         // Step 7: Determine price ID (custom for Enterprise, default otherwise)
         let billing_interval = params.billing_interval.as_deref().unwrap_or("monthly");
-        let (price_id, custom_price_id) =
-            if params.new_tier == "enterprise" && params.custom_price_cents.is_some() {
-                // Create custom Enterprise price
-                let interval = match billing_interval {
-                    "annual" => "year",
-                    _ => "month",
-                };
-                let custom_price = params.custom_price_cents.ok_or_else(|| {
-                    BillingError::InvalidTier("Custom tier requires custom_price_cents".to_string())
-                })?;
-                let custom_id = self
-                    .create_custom_enterprise_price(custom_price, interval, org_id)
-                    .await?;
-                (custom_id.clone(), Some(custom_id))
+        let (price_id, custom_price_id) = if params.new_tier == "enterprise"
+            && params.custom_price_cents.is_some()
+        {
+            // Create custom Enterprise price
+            let interval = match billing_interval {
+                "annual" => "year",
+                _ => "month",
+            };
+            let custom_price = params.custom_price_cents.ok_or_else(|| {
+                BillingError::InvalidTier("Custom tier requires custom_price_cents".to_string())
+            })?;
+            let custom_id = self
+                .create_custom_enterprise_price(custom_price, interval, org_id, params.currency()?)
+                .await?;
+            (custom_id.clone(), Some(custom_id))
+        } else {
+            // Use default Stripe price IDs
+            let default_id = if billing_interval == "annual" {
+                self.stripe
+                    .config()
+                    .annual_price_id_for_tier(&params.new_tier)
+                    .ok_or_else(|| {
+                        BillingError::InvalidTier(format!(
+                            "{} annual pricing not configured",
+                            params.new_tier
+                        ))
+                    })?
+                    .to_string()
             } else {
-                // Use default Stripe price IDs
-                let default_id = if billing_interval == "annual" {
-                    self.stripe
-                        .config()
-                        .annual_price_id_for_tier(&params.new_tier)
-                        .ok_or_else(|| {
-                            BillingError::InvalidTier(format!(
-                                "{} annual pricing not configured",
-                                params.new_tier
-                            ))
-                        })?
-                        .to_string()
-                } else {
-                    self.stripe
-                        .config()
-                        .price_id_for_tier(&params.new_tier)
-                        .ok_or_else(|| BillingError::InvalidTier(params.new_tier.clone()))?
-                        .to_string()
-                };
-                (default_id, None)
+                self.stripe
+                    .config()
+                    .price_id_for_tier(&params.new_tier)
+                    .ok_or_else(|| BillingError::InvalidTier(params.new_tier.clone()))?
+                    .to_string()
             };
+            (default_id, None)
+        };
 
         // Step 8: Get existing subscription
         let existing_sub = self.get_subscription(org_id).await.ok().flatten();
@@ -2440,13 +4121,21 @@ impl SubscriptionService {
                     update_params.days_until_due = Some(30);
                 }
 
-                let mut sub =
-                    stripe::Subscription::update(self.stripe.inner(), &existing.id, update_params)
-                        .await?;
+                let existing_id = &existing.id;
+                let mut sub = self
+                    .idempotent(
+                        org_id,
+                        "admin_change_tier_update",
+                        &params.new_tier,
+                        |stripe| async move {
+                            stripe::Subscription::update(&stripe, existing_id, update_params).await
+                        },
+                    )
+                    .await?;
 
                 // Apply trial period to existing subscription if specified
                 if let Some(trial_days) = params.trial_days {
-                    sub = self.apply_trial_period(&sub.id, trial_days).await?;
+                    sub = self.apply_trial_period(org_id, &sub.id, trial_days).await?;
                 }
 
                 sub
@@ -2494,7 +4183,13 @@ impl SubscriptionService {
                     create_params.days_until_due = Some(30);
                 }
 
-                Subscription::create(self.stripe.inner(), create_params).await?
+                self.idempotent(
+                    org_id,
+                    "admin_change_tier_create",
+                    &params.new_tier,
+                    |stripe| async move { Subscription::create(&stripe, create_params).await },
+                )
+                .await?
             }
         };
 
@@ -2511,7 +4206,7 @@ impl SubscriptionService {
                     "Setting subscription start date via trial period"
                 );
                 final_subscription = self
-                    .apply_trial_period(&final_subscription.id, trial_days as u32)
+                    .apply_trial_period(org_id, &final_subscription.id, trial_days as u32)
                     .await?;
             }
         }
@@ -2531,6 +4226,10 @@ impl SubscriptionService {
                     invoice_id = %invoice_id_str,
                     "Stripe created invoice automatically for send_invoice subscription"
                 );
+                if let Some(details) = params.invoice_details.as_ref().filter(|d| !d.is_empty()) {
+                    self.update_invoice_details(org_id, &invoice_id_str, details)
+                        .await?;
+                }
                 (Some(invoice_id_str), Some("draft".to_string()))
             } else {
                 (None, None)
@@ -2543,6 +4242,20 @@ impl SubscriptionService {
         self.sync_subscription_to_db(org_id, &final_subscription)
             .await?;
 
+        // Trials without a payment method are escalated by the worker
+        if !has_payment_method && params.trial_days.is_some() {
+            if let Err(e) = TrialPaymentService::new(self.pool.clone())
+                .require_payment_method(final_subscription.id.as_str())
+                .await
+            {
+                tracing::warn!(
+                    org_id = %org_id,
+                    error = %e,
+                    "Failed to flag trial without payment method"
+                );
+            }
+        }
+
         tracing::info!(
             org_id = %org_id,
             subscription_id = %final_subscription.id,
@@ -2570,7 +4283,7 @@ impl SubscriptionService {
             scheduled: false,
             scheduled_effective_date: None,
             refund_issued: false, // This is an upgrade, not a downgrade
-            refund_amount_cents: None,
+            refund_amount: None,
             refund_type: None,
             message: format!(
                 "Tier changed to {} immediately with proration",
@@ -2579,24 +4292,62 @@ impl SubscriptionService {
         })
     }
 
-    /// Get owner email and organization name for customer creation
-    async fn get_owner_email(&self, org_id: Uuid) -> BillingResult<(String, String)> {
-        let result: Option<(String, String)> = sqlx::query_as(
-            r#"
-            SELECT u.email, o.name
-            FROM users u
-            JOIN organizations o ON o.id = u.org_id
-            WHERE u.org_id = $1 AND u.role = 'owner'
-            LIMIT 1
-            "#,
-        )
-        .bind(org_id)
-        .fetch_optional(&self.pool)
-        .await?;
+    /// Run `admin_change_tier` for many orgs, a few at a time
+    ///
+    /// Every change is attempted; a failure for one org doesn't stop the
+    /// rest. Results are returned in input order.
+    pub async fn bulk_change_tier(
+        &self,
+        changes: Vec<(Uuid, AdminTierChangeParams)>,
+    ) -> Vec<(Uuid, BillingResult<AdminTierChangeResult>)> {
+        let org_ids: Vec<Uuid> = changes.iter().map(|(org_id, _)| *org_id).collect();
 
-        result.ok_or_else(|| {
-            BillingError::CustomerNotFound(format!("No owner found for organization {}", org_id))
+        let results = run_bounded(changes, BULK_TIER_CHANGE_CONCURRENCY, |(org_id, params)| {
+            let service = SubscriptionService::new(self.stripe.clone(), self.pool.clone());
+            async move { service.admin_change_tier(org_id, params).await }
         })
+        .await;
+
+        let results: Vec<(Uuid, BillingResult<AdminTierChangeResult>)> = org_ids
+            .into_iter()
+            .zip(results)
+            .map(|(org_id, result)| {
+                let result = result.unwrap_or_else(|| {
+                    Err(BillingError::Internal(
+                        "Tier change task panicked".to_string(),
+                    ))
+                });
+                (org_id, result)
+            })
+            .collect();
+
+        let failed: Vec<String> = results
+            .iter()
+            .filter(|(_, result)| result.is_err())
+            .map(|(org_id, _)| org_id.to_string())
+            .collect();
+        tracing::info!(
+            total = results.len(),
+            succeeded = results.len() - failed.len(),
+            failed = failed.len(),
+            failed_org_ids = ?failed,
+            "Bulk tier change completed"
+        );
+
+        results
+    }
+
+    /// Get primary owner email and organization name for customer creation
+    async fn get_owner_email(&self, org_id: Uuid) -> BillingResult<(String, String)> {
+        get_primary_owner(&self.pool, org_id)
+            .await?
+            .map(|o| (o.email, o.org_name))
+            .ok_or_else(|| {
+                BillingError::CustomerNotFound(format!(
+                    "No owner found for organization {}",
+                    org_id
+                ))
+            })
     }
 
     /// Get or create a Stripe customer for an organization
@@ -2643,7 +4394,11 @@ impl SubscriptionService {
             ..Default::default()
         };
 
-        let customer = Customer::create(self.stripe.inner(), params).await?;
+        let customer = self
+            .idempotent(org_id, "create_customer", "", |stripe| async move {
+                Customer::create(&stripe, params).await
+            })
+            .await?;
 
         // Store customer ID in database
         sqlx::query(
@@ -2666,6 +4421,7 @@ impl SubscriptionService {
     /// Apply or extend trial period on an existing subscription
     async fn apply_trial_period(
         &self,
+        org_id: Uuid,
         subscription_id: &SubscriptionId,
         trial_days: u32,
     ) -> BillingResult<Subscription> {
@@ -2683,8 +4439,15 @@ impl SubscriptionService {
             ..Default::default()
         };
 
-        let subscription =
-            Subscription::update(self.stripe.inner(), subscription_id, params).await?;
+        // Keyed on the exact trial end: a retry sends the same request, while
+        // a later extension is a different one
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            "apply_trial_period",
+            "",
+            &format!("{}@{}", subscription_id, trial_end_timestamp),
+        ));
+        let subscription = Subscription::update(&stripe, subscription_id, params).await?;
 
         tracing::info!(
             subscription_id = %subscription_id,
@@ -2696,25 +4459,91 @@ impl SubscriptionService {
         Ok(subscription)
     }
 
+    /// Stripe price ID for a scheduled downgrade's pending price
+    async fn resolve_pending_price(
+        &self,
+        org_id: Uuid,
+        tier: &str,
+        price: &PendingPrice,
+    ) -> BillingResult<String> {
+        let config = self.stripe.config();
+        let standard = match price {
+            PendingPrice::Monthly => config.price_id_for_tier(tier),
+            PendingPrice::Annual => config.annual_price_id_for_tier(tier),
+            PendingPrice::Custom {
+                amount_cents,
+                interval,
+            } => {
+                let currency = match self.get_stripe_customer_id(org_id).await {
+                    Ok(customer_id) => self.customer_currency(&customer_id).await?,
+                    Err(_) => None,
+                };
+                return self
+                    .create_custom_enterprise_price(
+                        *amount_cents,
+                        interval,
+                        org_id,
+                        currency.unwrap_or(stripe::Currency::USD),
+                    )
+                    .await;
+            }
+        };
+
+        standard.map(str::to_string).ok_or_else(|| {
+            BillingError::InvalidTier(format!("No {:?} price for tier: {}", price, tier))
+        })
+    }
+
     /// Create a custom Stripe price for Enterprise tier
     ///
     /// This creates a unique price object in Stripe for custom Enterprise pricing.
-    /// The price is tagged with org_id metadata for tracking.
+    /// The price is tagged with org_id metadata for tracking. Amounts outside
+    /// `CustomPriceBounds` are refused, and an active price with the same
+    /// org, interval, amount and currency is reused instead of creating
+    /// another. The price must be in the customer's billing currency.
     async fn create_custom_enterprise_price(
         &self,
         amount_cents: i64,
-        interval: &str, // "month" or "year"
+        interval: &str, // "month"/"monthly" or "year"/"annual"
         org_id: Uuid,
+        currency: stripe::Currency,
     ) -> BillingResult<String> {
-        use stripe::{CreatePrice, CreatePriceRecurring, CreatePriceRecurringInterval, Currency};
+        use stripe::{CreatePrice, CreatePriceRecurring, ListPrices};
 
-        let recurring_interval = match interval {
-            "annual" => CreatePriceRecurringInterval::Year,
-            _ => CreatePriceRecurringInterval::Month,
-        };
+        get_custom_price_bounds().validate(amount_cents)?;
+
+        if let Ok(customer_id) = self.get_stripe_customer_id(org_id).await {
+            check_customer_currency(self.customer_currency(&customer_id).await?, currency)?;
+        }
+
+        let recurring_interval = custom_price_interval(interval);
+        let lookup_key =
+            custom_price_lookup_key(org_id, recurring_interval, amount_cents, currency);
+
+        let mut list_params = ListPrices::new();
+        list_params.active = Some(true);
+        list_params.lookup_keys = Some(vec![lookup_key.clone()]);
+        let existing = stripe::Price::list(self.stripe.inner(), &list_params).await?;
+        if let Some(price) = existing
+            .data
+            .iter()
+            .find(|p| is_reusable_custom_price(p, amount_cents, recurring_interval, currency))
+        {
+            tracing::info!(
+                org_id = %org_id,
+                price_id = %price.id,
+                amount_cents = amount_cents,
+                interval = recurring_interval.as_str(),
+                "Reusing existing custom Enterprise price"
+            );
+            return Ok(price.id.to_string());
+        }
 
-        let mut params = CreatePrice::new(Currency::USD);
+        let mut params = CreatePrice::new(currency);
         params.unit_amount = Some(amount_cents);
+        params.lookup_key = Some(&lookup_key);
+        // Move the key off any archived price with the same terms
+        params.transfer_lookup_key = Some(true);
         params.recurring = Some(CreatePriceRecurring {
             interval: recurring_interval,
             interval_count: None,
@@ -2729,13 +4558,21 @@ impl SubscriptionService {
         metadata.insert("tier".to_string(), "enterprise".to_string());
         params.metadata = Some(metadata);
 
-        let price = stripe::Price::create(self.stripe.inner(), params).await?;
+        // The lookup key already identifies identical prices
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            "create_custom_price",
+            "enterprise",
+            &lookup_key,
+        ));
+        let price = stripe::Price::create(&stripe, params).await?;
 
         tracing::info!(
             org_id = %org_id,
             price_id = %price.id,
             amount_cents = amount_cents,
             interval = interval,
+            currency = %currency,
             "Created custom Enterprise price"
         );
 
@@ -2749,8 +4586,10 @@ impl SubscriptionService {
     #[allow(dead_code)]
     async fn create_and_send_invoice(
         &self,
+        org_id: Uuid,
         customer_id: &CustomerId,
         price_id: &str,
+        details: &InvoiceDetails,
     ) -> BillingResult<(String, String)> {
         use stripe::{CreateInvoice, CreateInvoiceItem};
 
@@ -2762,7 +4601,13 @@ impl SubscriptionService {
         // Create invoice item
         let mut item_params = CreateInvoiceItem::new(customer_id.clone());
         item_params.price = Some(stripe_price_id);
-        stripe::InvoiceItem::create(self.stripe.inner(), item_params).await?;
+        self.idempotent(
+            org_id,
+            "create_invoice_item",
+            price_id,
+            |stripe| async move { stripe::InvoiceItem::create(&stripe, item_params).await },
+        )
+        .await?;
 
         // Create invoice
         let mut invoice_params = CreateInvoice::new();
@@ -2770,12 +4615,22 @@ impl SubscriptionService {
         invoice_params.auto_advance = Some(true);
         invoice_params.collection_method = Some(stripe::CollectionMethod::SendInvoice);
         invoice_params.days_until_due = Some(30);
+        details.apply_to(&mut invoice_params);
 
-        let invoice = stripe::Invoice::create(self.stripe.inner(), invoice_params).await?;
+        let invoice = self
+            .idempotent(org_id, "create_invoice", price_id, |stripe| async move {
+                stripe::Invoice::create(&stripe, invoice_params).await
+            })
+            .await?;
 
         // Finalize invoice to send it
-        let finalized =
-            stripe::Invoice::finalize(self.stripe.inner(), &invoice.id, Default::default()).await?;
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            "finalize_invoice",
+            "",
+            invoice.id.as_str(),
+        ));
+        let finalized = stripe::Invoice::finalize(&stripe, &invoice.id, Default::default()).await?;
 
         let status = match finalized.status {
             Some(s) => format!("{:?}", s),
@@ -2792,6 +4647,53 @@ impl SubscriptionService {
         Ok((finalized.id.to_string(), status))
     }
 
+    /// Set memo, footer and custom fields on a draft invoice
+    async fn update_invoice_details(
+        &self,
+        org_id: Uuid,
+        invoice_id: &str,
+        details: &InvoiceDetails,
+    ) -> BillingResult<()> {
+        let form = details.form_params();
+        let key = idempotency_key(
+            org_id,
+            "update_invoice_details",
+            "",
+            &format!("{}:{:?}", invoice_id, form),
+        );
+        let response = self
+            .stripe
+            .rest_request(reqwest::Method::POST, &format!("invoices/{}", invoice_id))
+            .header("Idempotency-Key", key)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| BillingError::StripeApi(format!("Failed to call Stripe API: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_body = response.text().await.unwrap_or_default();
+            tracing::error!(
+                invoice_id = %invoice_id,
+                status = %status,
+                error_body = %error_body,
+                "Failed to set invoice details"
+            );
+            return Err(BillingError::StripeApi(format!(
+                "Stripe API error ({}): {}",
+                status, error_body
+            )));
+        }
+
+        tracing::info!(
+            invoice_id = %invoice_id,
+            custom_fields = details.custom_fields.len(),
+            "Set invoice details"
+        );
+
+        Ok(())
+    }
+
     /// Get subscription for an organization
     pub async fn get_subscription(&self, org_id: Uuid) -> BillingResult<Option<Subscription>> {
         let result: Option<(Option<String>,)> = sqlx::query_as(
@@ -2820,6 +4722,344 @@ impl SubscriptionService {
         }
     }
 
+    /// Normalized monthly value of the org's current subscription
+    ///
+    /// See `subscription_mrr`. Orgs without a subscription contribute $0.
+    pub async fn monthly_recurring_revenue(&self, org_id: Uuid) -> BillingResult<Money> {
+        Ok(self
+            .get_subscription(org_id)
+            .await?
+            .map(|subscription| subscription_mrr(&subscription))
+            .unwrap_or_else(|| Money::from_cents_usd(0)))
+    }
+
+    /// Annual run rate of the org's current subscription (MRR x 12)
+    pub async fn annual_recurring_revenue(&self, org_id: Uuid) -> BillingResult<Money> {
+        let mrr = self.monthly_recurring_revenue(org_id).await?;
+        Ok(Money::new(mrr.amount_cents * 12, mrr.currency))
+    }
+
+    /// MRR across every billing subscription in the Stripe account
+    ///
+    /// Pages through active and past-due subscriptions `MRR_PAGE_SIZE` at a
+    /// time. Amounts in different currencies aren't converted: one total per
+    /// currency is returned, largest first.
+    pub async fn total_mrr(&self) -> BillingResult<Vec<Money>> {
+        let mut totals: Vec<Money> = Vec::new();
+
+        for status in MRR_STATUSES {
+            let mut starting_after: Option<SubscriptionId> = None;
+            loop {
+                let params = ListSubscriptions {
+                    status: Some(status),
+                    limit: Some(MRR_PAGE_SIZE),
+                    starting_after: starting_after.take(),
+                    ..Default::default()
+                };
+                let page = Subscription::list(self.stripe.inner(), &params).await?;
+
+                for subscription in &page.data {
+                    add_to_currency_totals(&mut totals, subscription_mrr(subscription));
+                }
+
+                match page.data.last() {
+                    Some(last) if page.has_more => starting_after = Some(last.id.clone()),
+                    _ => break,
+                }
+            }
+        }
+
+        totals.sort_by_key(|total| std::cmp::Reverse(total.amount_cents));
+        Ok(totals)
+    }
+
+    /// Subscriptions renewing within `within` from now, with their expected charge
+    ///
+    /// For cash flow forecasting across all orgs. Active, past-due and
+    /// trialing subscriptions count; ones set to cancel at period end don't.
+    /// Ordered by renewal time; pass the previous page's `next_cursor` as
+    /// `after` to continue. Plan list prices are looked up in Stripe once per
+    /// price per page.
+    pub async fn upcoming_charges(
+        &self,
+        within: time::Duration,
+        after: Option<UpcomingChargeCursor>,
+        limit: i64,
+    ) -> BillingResult<UpcomingChargesPage> {
+        self.upcoming_charges_with(within, after, limit, |price_id| async move {
+            self.get_price_amount(&price_id).await
+        })
+        .await
+    }
+
+    /// `upcoming_charges` with the Stripe list price lookup supplied by the caller
+    async fn upcoming_charges_with<F, Fut>(
+        &self,
+        within: time::Duration,
+        after: Option<UpcomingChargeCursor>,
+        limit: i64,
+        list_price_cents: F,
+    ) -> BillingResult<UpcomingChargesPage>
+    where
+        F: Fn(String) -> Fut,
+        Fut: std::future::Future<Output = BillingResult<i64>>,
+    {
+        let now = OffsetDateTime::now_utc();
+        let renewals: Vec<UpcomingRenewal> = sqlx::query_as(
+            r#"
+            SELECT
+                s.org_id,
+                s.stripe_subscription_id,
+                s.stripe_price_id,
+                o.subscription_tier AS tier,
+                o.custom_monthly_price_cents,
+                s.current_period_end AS renews_at,
+                COALESCE((
+                    SELECT SUM(COALESCE(a.unit_price_cents, 0)::BIGINT * COALESCE(a.quantity, 1))
+                    FROM subscription_addons a
+                    WHERE a.subscription_id = s.id AND a.status = 'active'
+                ), 0)::BIGINT AS addons_cents,
+                COALESCE((
+                    SELECT SUM(c.total_charge_cents)
+                    FROM overage_charges c
+                    WHERE c.org_id = s.org_id AND c.status = 'pending'
+                ), 0)::BIGINT AS overage_cents
+            FROM subscriptions s
+            JOIN organizations o ON o.id = s.org_id
+            WHERE s.status IN ('active', 'past_due', 'trialing')
+              AND NOT s.cancel_at_period_end
+              AND s.current_period_end > $1
+              AND s.current_period_end <= $2
+              AND ($3::TIMESTAMPTZ IS NULL OR (s.current_period_end, s.org_id) > ($3, $4))
+            ORDER BY s.current_period_end, s.org_id
+            LIMIT $5
+            "#,
+        )
+        .bind(now)
+        .bind(now + within)
+        .bind(after.map(|c| c.renews_at))
+        .bind(after.map(|c| c.org_id))
+        .bind(limit.max(0))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut list_prices: std::collections::HashMap<String, Option<i64>> =
+            std::collections::HashMap::new();
+        let mut charges = Vec::with_capacity(renewals.len());
+        for renewal in renewals {
+            let list_price = match &renewal.stripe_price_id {
+                Some(price_id) if renewal.custom_monthly_price_cents.is_none() => {
+                    if !list_prices.contains_key(price_id) {
+                        let amount = match list_price_cents(price_id.clone()).await {
+                            Ok(amount) => Some(amount),
+                            Err(e) => {
+                                tracing::warn!(
+                                    price_id = %price_id,
+                                    error = %e,
+                                    "Failed to fetch list price, using tier default"
+                                );
+                                None
+                            }
+                        };
+                        list_prices.insert(price_id.clone(), amount);
+                    }
+                    list_prices[price_id]
+                }
+                _ => None,
+            };
+            charges.push(renewal.into_charge(list_price));
+        }
+
+        let next_cursor = upcoming_charges_cursor(&charges, limit);
+        Ok(UpcomingChargesPage {
+            charges,
+            next_cursor,
+        })
+    }
+
+    /// Find subscriptions whose Stripe price disagrees with the stored tier, and fix them
+    ///
+    /// The stored tier is the source of truth: where the fix is unambiguous the
+    /// Stripe price is moved to the tier's price (same interval, no proration),
+    /// otherwise the mismatch is flagged. With `dry_run` nothing is changed and
+    /// the proposed actions are returned.
+    pub async fn repair_tier_price_mismatches(
+        &self,
+        dry_run: bool,
+    ) -> BillingResult<Vec<RepairAction>> {
+        self.repair_tier_price_mismatches_with(dry_run, |action| async move {
+            self.align_subscription_price(&action).await
+        })
+        .await
+    }
+
+    /// `repair_tier_price_mismatches` with the Stripe price change supplied by the caller
+    async fn repair_tier_price_mismatches_with<F, Fut>(
+        &self,
+        dry_run: bool,
+        align: F,
+    ) -> BillingResult<Vec<RepairAction>>
+    where
+        F: Fn(RepairAction) -> Fut,
+        Fut: std::future::Future<Output = BillingResult<()>>,
+    {
+        let rows: Vec<TierPriceRow> = sqlx::query_as(
+            r#"
+            SELECT
+                o.id AS org_id,
+                o.subscription_tier AS db_tier,
+                s.stripe_subscription_id,
+                s.stripe_price_id
+            FROM organizations o
+            JOIN subscriptions s ON s.org_id = o.id
+            WHERE s.status IN ('active', 'trialing', 'past_due')
+              AND s.stripe_subscription_id IS NOT NULL
+              AND s.stripe_price_id IS NOT NULL
+            ORDER BY o.id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let config = self.stripe.config();
+        let mut actions = Vec::new();
+        for row in rows {
+            let Some((stripe_tier, fix)) =
+                plan_tier_price_fix(config, &row.db_tier, &row.stripe_price_id)
+            else {
+                continue;
+            };
+            let mut action = RepairAction {
+                org_id: row.org_id,
+                stripe_subscription_id: row.stripe_subscription_id,
+                db_tier: row.db_tier,
+                stripe_tier,
+                fix,
+                applied: false,
+                error: None,
+            };
+
+            match &action.fix {
+                TierPriceFix::Flag { reason } => {
+                    tracing::warn!(
+                        org_id = %action.org_id,
+                        db_tier = %action.db_tier,
+                        stripe_tier = %action.stripe_tier,
+                        reason = %reason,
+                        "Tier/price mismatch needs manual repair"
+                    );
+                }
+                TierPriceFix::AlignPrice { .. } if !dry_run => match align(action.clone()).await {
+                    Ok(()) => {
+                        action.applied = true;
+                        self.log_tier_price_repair(&action).await;
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            org_id = %action.org_id,
+                            error = %e,
+                            "Failed to align Stripe price with stored tier"
+                        );
+                        action.error = Some(e.to_string());
+                    }
+                },
+                TierPriceFix::AlignPrice { .. } => {}
+            }
+            actions.push(action);
+        }
+
+        tracing::info!(
+            dry_run = dry_run,
+            mismatches = actions.len(),
+            applied = actions.iter().filter(|a| a.applied).count(),
+            "Tier/price mismatch repair finished"
+        );
+        Ok(actions)
+    }
+
+    /// Move the subscription item on the mismatched price to the stored tier's price
+    ///
+    /// No proration: the organization has been entitled to the stored tier all
+    /// along, so the customer isn't charged or credited for the correction.
+    async fn align_subscription_price(&self, action: &RepairAction) -> BillingResult<()> {
+        let TierPriceFix::AlignPrice {
+            from_price_id,
+            to_price_id,
+        } = &action.fix
+        else {
+            return Ok(());
+        };
+        let sub_id = action
+            .stripe_subscription_id
+            .parse::<stripe::SubscriptionId>()
+            .map_err(|e| BillingError::StripeApi(format!("Invalid subscription ID: {}", e)))?;
+
+        let current = Subscription::retrieve(self.stripe.inner(), &sub_id, &[]).await?;
+        let item_id = current
+            .items
+            .data
+            .iter()
+            .find(|item| item.price.as_ref().map(|p| p.id.as_str()) == Some(from_price_id))
+            .map(|item| item.id.to_string())
+            .ok_or_else(|| {
+                BillingError::Internal(format!(
+                    "Subscription {} no longer has price {}",
+                    sub_id, from_price_id
+                ))
+            })?;
+
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("tier".to_string(), action.db_tier.clone());
+        let params = UpdateSubscription {
+            items: Some(vec![UpdateSubscriptionItems {
+                id: Some(item_id),
+                price: Some(to_price_id.clone()),
+                ..Default::default()
+            }]),
+            metadata: Some(metadata),
+            proration_behavior: Some(SubscriptionProrationBehavior::None),
+            ..Default::default()
+        };
+
+        let subscription = self
+            .idempotent(
+                action.org_id,
+                "repair_tier_price",
+                &format!("{}:{}", action.db_tier, to_price_id),
+                |stripe| async move { Subscription::update(&stripe, &sub_id, params).await },
+            )
+            .await?;
+
+        self.sync_subscription_to_db(action.org_id, &subscription)
+            .await?;
+        Ok(())
+    }
+
+    /// Record an applied tier/price repair in the billing event log
+    async fn log_tier_price_repair(&self, action: &RepairAction) {
+        let logger = BillingEventLogger::new(self.pool.clone());
+        if let Err(e) = logger
+            .log_event(
+                BillingEventBuilder::new(action.org_id, BillingEventType::AdminOverride)
+                    .subtype("tier_price_repair")
+                    .data(serde_json::json!({
+                        "stripe_subscription_id": action.stripe_subscription_id,
+                        "db_tier": action.db_tier,
+                        "stripe_tier": action.stripe_tier,
+                        "fix": action.fix,
+                    }))
+                    .actor_type(ActorType::System),
+            )
+            .await
+        {
+            tracing::warn!(
+                org_id = %action.org_id,
+                error = %e,
+                "Failed to log tier/price repair event"
+            );
+        }
+    }
+
     /// List all subscriptions for a customer
     pub async fn list_customer_subscriptions(
         &self,
@@ -2838,12 +5078,189 @@ impl SubscriptionService {
         Ok(subscriptions.data)
     }
 
+    /// Rebuild an org's `subscriptions` row from Stripe
+    ///
+    /// Used when the DB row has been lost but the Stripe subscription still
+    /// exists. Lists the org's Stripe customer subscriptions, picks the live
+    /// one (see `pick_recoverable_subscription`) and re-syncs it.
+    pub async fn recover_from_stripe(&self, org_id: Uuid) -> BillingResult<Subscription> {
+        let customer_id = self.get_stripe_customer_id(org_id).await?;
+        let subscriptions = self.list_customer_subscriptions(&customer_id).await?;
+
+        let subscription = pick_recoverable_subscription(&subscriptions, org_id)
+            .cloned()
+            .ok_or_else(|| BillingError::SubscriptionNotFound(org_id.to_string()))?;
+
+        let diff = self.sync_subscription_to_db(org_id, &subscription).await?;
+
+        tracing::info!(
+            org_id = %org_id,
+            customer_id = %customer_id,
+            subscription_id = %subscription.id,
+            created = diff.created,
+            "Recovered subscription from Stripe"
+        );
+
+        Ok(subscription)
+    }
+
+    /// Most recent tier changes for an organization, newest first
+    pub async fn tier_change_history(
+        &self,
+        org_id: Uuid,
+        limit: i64,
+    ) -> BillingResult<Vec<TierChangeAuditRecord>> {
+        self.tier_change_history_before(org_id, limit, None).await
+    }
+
+    /// A page of tier changes older than `before`, newest first
+    ///
+    /// Pass the cursor from `tier_change_history_cursor` to fetch the next page.
+    pub async fn tier_change_history_before(
+        &self,
+        org_id: Uuid,
+        limit: i64,
+        before: Option<TierChangeHistoryCursor>,
+    ) -> BillingResult<Vec<TierChangeAuditRecord>> {
+        let records: Vec<TierChangeAuditRecord> = sqlx::query_as(
+            r#"
+            SELECT id, org_id, from_tier, to_tier, source, changed_by, reason,
+                   stripe_event_id, metadata, created_at
+            FROM tier_change_audit
+            WHERE org_id = $1
+              AND ($3::TIMESTAMPTZ IS NULL OR (created_at, id) < ($3, $4))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(limit.clamp(1, 500))
+        .bind(before.map(|c| c.created_at))
+        .bind(before.map(|c| c.id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Reconstruct missing `tier_change_audit` rows from billing events
+    ///
+    /// Tier changes made before the audit table existed only have a
+    /// `TIER_CHANGED`/`TIER_CHANGE_SCHEDULED` event. Backfilled rows keep the
+    /// event's timestamp and record its id in `metadata.backfilled_from_event`,
+    /// so running this again creates nothing new.
+    pub async fn backfill_audit_from_events(&self) -> BillingResult<AuditBackfillSummary> {
+        let events: Vec<BillingEvent> = sqlx::query_as(
+            r#"
+            SELECT
+                id,
+                org_id,
+                event_type,
+                event_subtype,
+                event_data,
+                stripe_event_id,
+                stripe_invoice_id,
+                stripe_subscription_id,
+                stripe_customer_id,
+                actor_id,
+                actor_type,
+                entitlement_snapshot,
+                created_at
+            FROM billing_events
+            WHERE event_type IN ($1, $2)
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(BillingEventType::TierChanged.to_string())
+        .bind(BillingEventType::TierChangeScheduled.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let org_ids: Vec<Uuid> = events
+            .iter()
+            .map(|e| e.org_id)
+            .collect::<std::collections::HashSet<_>>()
+            .into_iter()
+            .collect();
+
+        let existing: Vec<TierChangeAuditRecord> = sqlx::query_as(
+            r#"
+            SELECT id, org_id, from_tier, to_tier, source, changed_by, reason,
+                   stripe_event_id, metadata, created_at
+            FROM tier_change_audit
+            WHERE org_id = ANY($1)
+            ORDER BY created_at ASC, id ASC
+            "#,
+        )
+        .bind(&org_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let missing = plan_audit_backfill(&events, &existing);
+        let events_scanned = events
+            .iter()
+            .filter_map(BackfilledAudit::from_event)
+            .count();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        for audit in &missing {
+            sqlx::query(
+                r#"
+                INSERT INTO tier_change_audit
+                    (org_id, from_tier, to_tier, source, changed_by, reason, metadata, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+            )
+            .bind(audit.org_id)
+            .bind(&audit.from_tier)
+            .bind(&audit.to_tier)
+            .bind(audit.source)
+            .bind(audit.changed_by)
+            .bind(&audit.reason)
+            .bind(serde_json::json!({
+                "scheduled": audit.scheduled,
+                "backfilled_from_event": audit.event_id,
+            }))
+            .bind(audit.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| BillingError::Database(e.to_string()))?;
+
+        let summary = AuditBackfillSummary {
+            events_scanned,
+            created: missing.len(),
+            already_audited: events_scanned - missing.len(),
+        };
+
+        tracing::info!(
+            events_scanned = summary.events_scanned,
+            created = summary.created,
+            already_audited = summary.already_audited,
+            "Backfilled tier change audit from billing events"
+        );
+
+        Ok(summary)
+    }
+
     /// Sync subscription state to database
+    ///
+    /// Returns a diff of the material fields that changed, so callers can
+    /// skip side effects (and event noise) on idempotent re-syncs.
     pub async fn sync_subscription_to_db(
         &self,
         org_id: Uuid,
         subscription: &Subscription,
-    ) -> BillingResult<()> {
+    ) -> BillingResult<SubscriptionDiff> {
         let status = match subscription.status {
             StripeSubStatus::Active => "active",
             StripeSubStatus::PastDue => "past_due",
@@ -2886,13 +5303,20 @@ impl SubscriptionService {
             );
         }
 
-        let current_period_start =
-            OffsetDateTime::from_unix_timestamp(subscription.current_period_start)
-                .unwrap_or(OffsetDateTime::now_utc());
-
-        let current_period_end =
-            OffsetDateTime::from_unix_timestamp(subscription.current_period_end)
-                .unwrap_or(OffsetDateTime::now_utc());
+        // An invalid period keeps whatever is stored rather than being
+        // overwritten with "now", which would corrupt proration downstream
+        let period = subscription_period(subscription);
+        if period.is_none() {
+            tracing::warn!(
+                org_id = %org_id,
+                subscription_id = %subscription.id,
+                current_period_start = subscription.current_period_start,
+                current_period_end = subscription.current_period_end,
+                "Stripe subscription has no valid billing period; keeping stored period"
+            );
+        }
+        let current_period_start = period.map(|(start, _)| start);
+        let current_period_end = period.map(|(_, end)| end);
 
         let canceled_at = subscription
             .canceled_at
@@ -2906,30 +5330,105 @@ impl SubscriptionService {
             .trial_end
             .map(|t| OffsetDateTime::from_unix_timestamp(t).unwrap_or(OffsetDateTime::now_utc()));
 
-        // Upsert subscription record (including metered item ID)
-        // Use ON CONFLICT (org_id) because there's a unique index on org_id,
-        // and when creating a new subscription after a canceled one, we get a new stripe_subscription_id
-        sqlx::query(
+        // Snapshot the stored row before overwriting it, for change detection
+        let previous: Option<SubscriptionSnapshot> = sqlx::query_as(
             r#"
-            INSERT INTO subscriptions (
-                id, org_id, stripe_subscription_id, stripe_price_id, stripe_metered_item_id, status,
-                current_period_start, current_period_end, cancel_at_period_end,
-                canceled_at, trial_start, trial_end, created_at, updated_at
-            ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW(), NOW()
-            )
-            ON CONFLICT (org_id) DO UPDATE SET
-                stripe_subscription_id = EXCLUDED.stripe_subscription_id,
-                stripe_price_id = EXCLUDED.stripe_price_id,
-                stripe_metered_item_id = EXCLUDED.stripe_metered_item_id,
-                status = EXCLUDED.status,
-                current_period_start = EXCLUDED.current_period_start,
-                current_period_end = EXCLUDED.current_period_end,
-                cancel_at_period_end = EXCLUDED.cancel_at_period_end,
-                canceled_at = EXCLUDED.canceled_at,
-                trial_start = EXCLUDED.trial_start,
-                trial_end = EXCLUDED.trial_end,
-                updated_at = NOW()
+            SELECT status, stripe_price_id, current_period_start, current_period_end,
+                   cancel_at_period_end
+            FROM subscriptions
+            WHERE org_id = $1
+            "#,
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let diff = SubscriptionDiff::between(
+            previous.as_ref(),
+            &SubscriptionSnapshot {
+                status: status.to_string(),
+                stripe_price_id: price_id.clone(),
+                current_period_start: current_period_start
+                    .or_else(|| previous.as_ref()?.current_period_start),
+                current_period_end: current_period_end
+                    .or_else(|| previous.as_ref()?.current_period_end),
+                cancel_at_period_end: subscription.cancel_at_period_end,
+            },
+        );
+
+        // A different subscription of another kind is replacing the stored one
+        // (e.g. a paid upgrade over the addon-only base)
+        if let Some(previous) = &previous {
+            let addon_only_price = self.stripe.config().price_ids.addon_only.as_deref();
+            let stored_kind = SubscriptionKind::from_price_id(
+                previous.stripe_price_id.as_deref(),
+                addon_only_price,
+            );
+            let incoming_kind = SubscriptionKind::of(subscription, addon_only_price);
+
+            if stored_kind != incoming_kind {
+                let stored_id: Option<String> = sqlx::query_scalar(
+                    "SELECT stripe_subscription_id FROM subscriptions WHERE org_id = $1",
+                )
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .flatten();
+
+                if let Some(stored_id) = stored_id.filter(|id| id != subscription.id.as_str()) {
+                    let incoming_live = matches!(
+                        subscription.status,
+                        StripeSubStatus::Active | StripeSubStatus::Trialing
+                    );
+                    match SubscriptionReplacement::decide(
+                        stored_kind,
+                        previous.is_live(),
+                        incoming_kind,
+                        incoming_live,
+                    ) {
+                        SubscriptionReplacement::MigrateAddons => {
+                            self.migrate_addon_only_items(org_id, &stored_id, subscription)
+                                .await?;
+                        }
+                        SubscriptionReplacement::Ignore => {
+                            tracing::info!(
+                                org_id = %org_id,
+                                subscription_id = %subscription.id,
+                                stored_subscription_id = %stored_id,
+                                "Subscription sync: keeping stored live subscription of another kind"
+                            );
+                            return Ok(SubscriptionDiff::default());
+                        }
+                        SubscriptionReplacement::Apply => {}
+                    }
+                }
+            }
+        }
+
+        // Upsert subscription record (including metered item ID)
+        // Use ON CONFLICT (org_id) because there's a unique index on org_id,
+        // and when creating a new subscription after a canceled one, we get a new stripe_subscription_id
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (
+                id, org_id, stripe_subscription_id, stripe_price_id, stripe_metered_item_id, status,
+                current_period_start, current_period_end, cancel_at_period_end,
+                canceled_at, trial_start, trial_end, created_at, updated_at
+            ) VALUES (
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW(), NOW()
+            )
+            ON CONFLICT (org_id) DO UPDATE SET
+                stripe_subscription_id = EXCLUDED.stripe_subscription_id,
+                stripe_price_id = EXCLUDED.stripe_price_id,
+                stripe_metered_item_id = EXCLUDED.stripe_metered_item_id,
+                status = EXCLUDED.status,
+                current_period_start = COALESCE(EXCLUDED.current_period_start, subscriptions.current_period_start),
+                current_period_end = COALESCE(EXCLUDED.current_period_end, subscriptions.current_period_end),
+                cancel_at_period_end = EXCLUDED.cancel_at_period_end,
+                canceled_at = EXCLUDED.canceled_at,
+                trial_start = EXCLUDED.trial_start,
+                trial_end = EXCLUDED.trial_end,
+                updated_at = NOW()
             "#,
         )
         .bind(Uuid::new_v4())
@@ -2947,6 +5446,11 @@ impl SubscriptionService {
         .execute(&self.pool)
         .await?;
 
+        // The row above is overwritten by the next subscription; keep the trial history
+        if let Some(trial_start) = trial_start {
+            self.record_trial_started(org_id, trial_start).await?;
+        }
+
         // Update org tier based on price ID
         // Note: Using change_tier() for audit logging. In fully DB-authoritative architecture,
         // this sync might be skipped, but for now we keep it to support checkout flows
@@ -2958,7 +5462,16 @@ impl SubscriptionService {
             .and_then(|item| item.price.as_ref())
             .map(|p| p.id.as_str())
         {
-            if let Some(tier) = self.stripe.config().tier_for_price_id(price_id) {
+            let price_tier = self.stripe.config().tier_for_price_id(price_id);
+            if price_tier.is_some() && entitled_tier(status, price_tier).is_none() {
+                tracing::info!(
+                    org_id = %org_id,
+                    subscription_id = %subscription.id,
+                    status = %status,
+                    "Subscription status does not entitle a tier - leaving org tier unchanged"
+                );
+            }
+            if let Some(tier) = entitled_tier(status, price_tier) {
                 // IMPORTANT: Use "immediate" timing since we're syncing what Stripe says
                 // (Stripe has already made the decision about when this takes effect)
                 let tier_options = TierChangeOptions {
@@ -2980,6 +5493,132 @@ impl SubscriptionService {
             }
         }
 
+        if diff.is_empty() {
+            tracing::debug!(
+                org_id = %org_id,
+                subscription_id = %subscription.id,
+                "Subscription sync: no material changes"
+            );
+        } else {
+            tracing::info!(
+                org_id = %org_id,
+                subscription_id = %subscription.id,
+                diff = ?diff,
+                "Subscription sync: material changes applied"
+            );
+        }
+
+        Ok(diff)
+    }
+
+    /// Re-sync an org's subscription from Stripe and set its tier from the live price
+    ///
+    /// Returns false when the org has no Stripe subscription.
+    pub async fn resync_tier(&self, org_id: Uuid) -> BillingResult<bool> {
+        match self.get_subscription(org_id).await? {
+            Some(subscription) => {
+                self.apply_live_tier(org_id, &subscription).await?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Sync `subscription` and set the org's tier from its live price
+    ///
+    /// Unlike a webhook sync, a failed tier change is returned rather than
+    /// logged. A price with no configured tier (e.g. a custom enterprise
+    /// price) leaves the stored tier alone.
+    pub(crate) async fn apply_live_tier(
+        &self,
+        org_id: Uuid,
+        subscription: &Subscription,
+    ) -> BillingResult<()> {
+        self.sync_subscription_to_db(org_id, subscription).await?;
+
+        let Some(tier) = subscription
+            .items
+            .data
+            .first()
+            .and_then(|item| item.price.as_ref())
+            .and_then(|price| self.stripe.config().tier_for_price_id(price.id.as_str()))
+        else {
+            return Ok(());
+        };
+
+        let stored_tier: Option<String> =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        if stored_tier.as_deref() == Some(tier) {
+            return Ok(());
+        }
+
+        self.change_tier(
+            org_id,
+            tier,
+            TierChangeOptions {
+                source: Some(TierChangeSource::System),
+                reason: Some("Tier re-synced from the live Stripe price".to_string()),
+                downgrade_timing: Some("immediate".to_string()),
+                ..Default::default()
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Whether an organization may still be given a free trial
+    ///
+    /// False once any of its subscriptions has trialed, including trials
+    /// cancelled early and ones since replaced by a paid subscription.
+    pub async fn is_trial_eligible(&self, org_id: Uuid) -> BillingResult<bool> {
+        let (eligible,): (bool,) = sqlx::query_as(
+            r#"
+            SELECT NOT EXISTS (
+                SELECT 1 FROM organizations WHERE id = $1 AND trial_used_at IS NOT NULL
+            ) AND NOT EXISTS (
+                SELECT 1 FROM subscriptions WHERE org_id = $1 AND trial_start IS NOT NULL
+            )
+            "#,
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(eligible)
+    }
+
+    /// Whether an admin may set trial days for an organization
+    ///
+    /// A trial that is still running may be extended; a new one needs
+    /// [`is_trial_eligible`](Self::is_trial_eligible).
+    async fn may_set_trial(&self, org_id: Uuid) -> BillingResult<bool> {
+        let (trialing,): (bool,) = sqlx::query_as(
+            "SELECT EXISTS (SELECT 1 FROM subscriptions WHERE org_id = $1 AND status = 'trialing')",
+        )
+        .bind(org_id)
+        .fetch_one(&self.pool)
+        .await?;
+        if trialing {
+            return Ok(true);
+        }
+        self.is_trial_eligible(org_id).await
+    }
+
+    /// Record that an organization has started a trial, keeping the first one
+    async fn record_trial_started(
+        &self,
+        org_id: Uuid,
+        trial_start: OffsetDateTime,
+    ) -> BillingResult<()> {
+        sqlx::query(
+            "UPDATE organizations SET trial_used_at = $2 WHERE id = $1 AND trial_used_at IS NULL",
+        )
+        .bind(org_id)
+        .bind(trial_start)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
@@ -3030,6 +5669,18 @@ impl SubscriptionService {
         }
     }
 
+    /// Currency a Stripe customer is billed in, if Stripe has fixed one
+    async fn customer_currency(
+        &self,
+        customer_id: &str,
+    ) -> BillingResult<Option<stripe::Currency>> {
+        let customer_id = customer_id
+            .parse::<CustomerId>()
+            .map_err(|e| BillingError::StripeApi(format!("Invalid customer ID: {}", e)))?;
+        let customer = Customer::retrieve(self.stripe.inner(), &customer_id, &[]).await?;
+        Ok(customer.currency)
+    }
+
     /// Get Stripe customer ID for an organization
     async fn get_stripe_customer_id(&self, org_id: Uuid) -> BillingResult<String> {
         let result: Option<(Option<String>,)> =
@@ -3197,7 +5848,17 @@ impl SubscriptionService {
             // Try to create a one-time coupon for the credit amount
             // If coupon creation fails (e.g., amount too small, Stripe error),
             // fall back to regular checkout without coupon
-            match self.create_credit_coupon(net_credit, org_id).await {
+            // The coupon must be in the customer's currency to apply
+            let currency = self
+                .customer_currency(&customer_id)
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(stripe::Currency::USD);
+            match self
+                .create_credit_coupon(net_credit, org_id, currency)
+                .await
+            {
                 Ok(coupon) => {
                     // Coupon created successfully - return data for API to create checkout
                     return Err(BillingError::UseCheckoutFlow {
@@ -3242,7 +5903,13 @@ impl SubscriptionService {
             "Creating Stripe subscription for reactivation"
         );
 
-        let subscription = Subscription::create(self.stripe.inner(), params)
+        let subscription = self
+            .idempotent(
+                org_id,
+                "reactivate_subscription",
+                new_tier,
+                |stripe| async move { Subscription::create(&stripe, params).await },
+            )
             .await
             .map_err(|e| {
                 tracing::error!(
@@ -3271,22 +5938,21 @@ impl SubscriptionService {
 
         self.change_tier(org_id, new_tier, tier_options).await?;
 
-        let current_period_end =
-            OffsetDateTime::from_unix_timestamp(subscription.current_period_end).unwrap_or(now);
+        let current_period_end = subscription_period(&subscription).map(|(_, end)| end);
 
         let message = if trial_days > 0 {
             format!(
-                "Subscription reactivated with {} days credit applied (${:.2} credit - ${:.2} overages = ${:.2} net)",
+                "Subscription reactivated with {} days credit applied ({} credit - {} overages = {} net)",
                 trial_days,
-                credit_cents as f64 / 100.0,
-                overage_cents as f64 / 100.0,
-                net_credit as f64 / 100.0
+                Money::from_cents_usd(credit_cents),
+                Money::from_cents_usd(overage_cents),
+                Money::from_cents_usd(net_credit)
             )
         } else {
             format!(
-                "Subscription reactivated. ${:.2} in overages were deducted from your ${:.2} credit.",
-                overage_cents as f64 / 100.0,
-                credit_cents as f64 / 100.0
+                "Subscription reactivated. {} in overages were deducted from your {} credit.",
+                Money::from_cents_usd(overage_cents),
+                Money::from_cents_usd(credit_cents)
             )
         };
 
@@ -3315,7 +5981,7 @@ impl SubscriptionService {
     }
 
     /// Get the price amount in cents for a Stripe price ID
-    async fn get_price_amount(&self, price_id: &str) -> BillingResult<i64> {
+    pub(crate) async fn get_price_amount(&self, price_id: &str) -> BillingResult<i64> {
         let price_id = price_id
             .parse::<stripe::PriceId>()
             .map_err(|e| BillingError::StripeApi(format!("Invalid price ID: {}", e)))?;
@@ -3367,6 +6033,7 @@ impl SubscriptionService {
         &self,
         amount_cents: i64,
         org_id: Uuid,
+        currency: stripe::Currency,
     ) -> BillingResult<stripe::Coupon> {
         use stripe::{CouponDuration, CreateCoupon};
 
@@ -3386,15 +6053,26 @@ impl SubscriptionService {
         // Create strings first to avoid lifetime issues
         let coupon_name = format!("Reactivation credit for org {}", org_id);
         // Stripe coupon IDs must be 40 chars max
-        // Format: rx_<8-char-org-prefix>_<10-digit-timestamp>_<8-char-random> = 31 chars
+        // Format: rx_<8-char-org-prefix>_<16-char-key-digest> = 28 chars
+        // The digest follows the idempotency attempt, so a retry after an
+        // unanswered request reuses the same coupon ID and key.
         let org_prefix = &org_id.simple().to_string()[..8];
-        let timestamp = chrono::Utc::now().timestamp();
-        let unique_suffix = &Uuid::new_v4().simple().to_string()[..8];
-        let coupon_id = format!("rx_{}_{:010}_{}", org_prefix, timestamp, unique_suffix);
+        let amount = amount_cents.to_string();
+        let attempt_id = self
+            .idempotency_attempt(org_id, "reactivation_coupon", &amount)
+            .await?;
+        let key = idempotency_key(
+            org_id,
+            "reactivation_coupon",
+            &amount,
+            &attempt_id.to_string(),
+        );
+        let digest = key.rsplit('-').next().unwrap_or_default();
+        let coupon_id = format!("rx_{}_{}", org_prefix, &digest[..16]);
 
         let mut params = CreateCoupon::new();
         params.amount_off = Some(amount_cents);
-        params.currency = Some(stripe::Currency::USD);
+        params.currency = Some(currency);
         params.duration = Some(CouponDuration::Once);
         params.name = Some(&coupon_name);
         params.max_redemptions = Some(1);
@@ -3407,18 +6085,22 @@ impl SubscriptionService {
             "Attempting to create Stripe coupon"
         );
 
-        let coupon = stripe::Coupon::create(self.stripe.inner(), params)
-            .await
-            .map_err(|e| {
-                tracing::error!(
-                    org_id = %org_id,
-                    coupon_id = %coupon_id,
-                    error = %e,
-                    error_debug = ?e,
-                    "Failed to create Stripe coupon"
-                );
-                e
-            })?;
+        let stripe = self.stripe.with_idempotency_key(key);
+        let result = stripe::Coupon::create(&stripe, params).await;
+        if stripe_outcome_is_confirmed(&result) {
+            self.finish_idempotency_attempt(org_id, "reactivation_coupon", &amount, attempt_id)
+                .await;
+        }
+        let coupon = result.map_err(|e| {
+            tracing::error!(
+                org_id = %org_id,
+                coupon_id = %coupon_id,
+                error = %e,
+                error_debug = ?e,
+                "Failed to create Stripe coupon"
+            );
+            e
+        })?;
 
         tracing::info!(
             org_id = %org_id,
@@ -3430,6 +6112,112 @@ impl SubscriptionService {
         Ok(coupon)
     }
 
+    /// Run a mutating Stripe call with an idempotency key
+    ///
+    /// The key covers the org, operation and tier plus an attempt ID that is
+    /// stored before the call is sent. A retry after a network failure (e.g. a
+    /// timeout while creating a subscription that Stripe did create) finds the
+    /// same attempt, even if a webhook has updated the subscription row since,
+    /// so Stripe replays the original response instead of acting twice. The
+    /// attempt is retired once Stripe has answered, so the next deliberate
+    /// call gets a fresh key.
+    async fn idempotent<T, F, Fut>(
+        &self,
+        org_id: Uuid,
+        operation: &str,
+        tier: &str,
+        call: F,
+    ) -> Result<T, stripe::StripeError>
+    where
+        F: FnOnce(stripe::Client) -> Fut,
+        Fut: std::future::Future<Output = Result<T, stripe::StripeError>>,
+    {
+        let attempt_id = match self.idempotency_attempt(org_id, operation, tier).await {
+            Ok(attempt_id) => attempt_id,
+            Err(e) => {
+                tracing::warn!(
+                    org_id = %org_id,
+                    operation = operation,
+                    error = %e,
+                    "Could not record Stripe idempotency attempt, sending without a key"
+                );
+                return call(self.stripe.inner().clone()).await;
+            }
+        };
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            operation,
+            tier,
+            &attempt_id.to_string(),
+        ));
+
+        let result = call(stripe).await;
+
+        if stripe_outcome_is_confirmed(&result) {
+            self.finish_idempotency_attempt(org_id, operation, tier, attempt_id)
+                .await;
+        }
+
+        result
+    }
+
+    /// Attempt ID for an org's pending Stripe mutation, recorded before sending
+    ///
+    /// Returns the existing attempt if an earlier call never got an answer.
+    async fn idempotency_attempt(
+        &self,
+        org_id: Uuid,
+        operation: &str,
+        tier: &str,
+    ) -> BillingResult<Uuid> {
+        let (attempt_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO stripe_idempotency_attempts (org_id, operation, tier)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (org_id, operation, tier)
+            DO UPDATE SET operation = EXCLUDED.operation
+            RETURNING attempt_id
+            "#,
+        )
+        .bind(org_id)
+        .bind(operation)
+        .bind(tier)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(attempt_id)
+    }
+
+    /// Retire an attempt once Stripe has confirmed its outcome
+    async fn finish_idempotency_attempt(
+        &self,
+        org_id: Uuid,
+        operation: &str,
+        tier: &str,
+        attempt_id: Uuid,
+    ) {
+        if let Err(e) = sqlx::query(
+            r#"
+            DELETE FROM stripe_idempotency_attempts
+            WHERE org_id = $1 AND operation = $2 AND tier = $3 AND attempt_id = $4
+            "#,
+        )
+        .bind(org_id)
+        .bind(operation)
+        .bind(tier)
+        .bind(attempt_id)
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(
+                org_id = %org_id,
+                operation = operation,
+                error = %e,
+                "Failed to retire Stripe idempotency attempt"
+            );
+        }
+    }
+
     /// Get the Stripe subscription ID for an organization
     async fn get_subscription_id(&self, org_id: Uuid) -> BillingResult<SubscriptionId> {
         let result: Option<(Option<String>,)> = sqlx::query_as(
@@ -3488,10 +6276,8 @@ impl SubscriptionService {
         // Note: Stripe's async-stripe library may need manual JSON for pause_collection
         // Using metadata as fallback tracking mechanism
         let mut metadata = subscription.metadata.clone();
-        metadata.insert(
-            "paused_at".to_string(),
-            OffsetDateTime::now_utc().to_string(),
-        );
+        let paused_at = OffsetDateTime::now_utc().to_string();
+        metadata.insert("paused_at".to_string(), paused_at.clone());
         metadata.insert("paused_by".to_string(), user_id.to_string());
         if let Some(ref r) = reason {
             metadata.insert("pause_reason".to_string(), r.clone());
@@ -3508,7 +6294,13 @@ impl SubscriptionService {
         // that it's actually a pause that can be resumed
 
         // Update subscription metadata to track pause
-        Subscription::update(self.stripe.inner(), &subscription_id, update).await?;
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            "pause_subscription",
+            "",
+            &format!("{}@{}", subscription_id, paused_at),
+        ));
+        Subscription::update(&stripe, &subscription_id, update).await?;
 
         // Update database
         sqlx::query(
@@ -3582,10 +6374,8 @@ impl SubscriptionService {
         metadata.remove("paused_by");
         metadata.remove("pause_reason");
         metadata.remove("resume_at");
-        metadata.insert(
-            "resumed_at".to_string(),
-            OffsetDateTime::now_utc().to_string(),
-        );
+        let resumed_at = OffsetDateTime::now_utc().to_string();
+        metadata.insert("resumed_at".to_string(), resumed_at.clone());
         metadata.insert("resumed_by".to_string(), user_id.to_string());
 
         let mut update = UpdateSubscription::new();
@@ -3596,7 +6386,13 @@ impl SubscriptionService {
             update.cancel_at_period_end = Some(false);
         }
 
-        Subscription::update(self.stripe.inner(), &subscription_id, update).await?;
+        let stripe = self.stripe.with_idempotency_key(idempotency_key(
+            org_id,
+            "unpause_subscription",
+            "",
+            &format!("{}@{}", subscription_id, resumed_at),
+        ));
+        Subscription::update(&stripe, &subscription_id, update).await?;
 
         // Update database
         let updated: Option<(Option<OffsetDateTime>, Option<String>)> = sqlx::query_as(
@@ -3729,6 +6525,76 @@ pub struct SubscriptionPauseStatus {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_redelivered_upgrade_is_already_applied() {
+        // Re-delivered checkout: Stripe and the org are both on the new plan
+        assert!(upgrade_already_applied(
+            Some("price_team"),
+            "price_team",
+            Some("team"),
+            "team"
+        ));
+        // First delivery: still on the old price
+        assert!(!upgrade_already_applied(
+            Some("price_pro"),
+            "price_team",
+            Some("pro"),
+            "team"
+        ));
+        // Stripe updated but the database write failed: run again
+        assert!(!upgrade_already_applied(
+            Some("price_team"),
+            "price_team",
+            Some("pro"),
+            "team"
+        ));
+        // Same tier, other interval (monthly to annual) is still an upgrade
+        assert!(!upgrade_already_applied(
+            Some("price_team"),
+            "price_team_annual",
+            Some("team"),
+            "team"
+        ));
+        assert!(!upgrade_already_applied(None, "price_team", None, "team"));
+    }
+
+    #[test]
+    fn test_confirmation_matches_unchanged_impact() {
+        let org_id = Uuid::new_v4();
+        let members = [Uuid::new_v4(), Uuid::new_v4()];
+        let previewed = impact_token(org_id, "team", "pro", &members, 3, 2);
+        let confirmation = ChangeConfirmation {
+            impact_token: previewed,
+        };
+
+        let current = impact_token(org_id, "team", "pro", &members, 3, 2);
+        assert!(check_confirmation(&current, &confirmation).is_ok());
+    }
+
+    #[test]
+    fn test_confirmation_stale_after_members_or_resources_change() {
+        let org_id = Uuid::new_v4();
+        let members = [Uuid::new_v4(), Uuid::new_v4()];
+        let confirmation = ChangeConfirmation {
+            impact_token: impact_token(org_id, "team", "pro", &members, 3, 2),
+        };
+
+        // A different member would now be suspended
+        let swapped = [members[0], Uuid::new_v4()];
+        let stale = [
+            impact_token(org_id, "team", "pro", &swapped, 3, 2),
+            impact_token(org_id, "team", "pro", &members, 4, 2),
+            impact_token(org_id, "team", "pro", &members, 3, 1),
+            impact_token(org_id, "enterprise", "pro", &members, 3, 2),
+        ];
+        for current in stale {
+            assert!(matches!(
+                check_confirmation(&current, &confirmation),
+                Err(BillingError::ConfirmationStale(_))
+            ));
+        }
+    }
+
     // =========================================================================
     // Plan Tests
     // =========================================================================
@@ -3790,11 +6656,15 @@ mod tests {
             skip_payment_validation: false,
             billing_interval: None,
             custom_price_cents: None,
+            currency: None,
             subscription_start_date: None,
             payment_method: None,
             admin_user_id: None,
             downgrade_timing: None,
             refund_type: None,
+            refund_reason: None,
+            skip_email: false,
+            invoice_details: None,
         };
 
         assert_eq!(params.new_tier, "pro");
@@ -3802,6 +6672,65 @@ mod tests {
         assert!(!params.skip_payment_validation);
     }
 
+    fn tier_change_params(tier: &str) -> AdminTierChangeParams {
+        AdminTierChangeParams {
+            new_tier: tier.to_string(),
+            trial_days: None,
+            reason: "Price book migration".to_string(),
+            skip_payment_validation: true,
+            billing_interval: None,
+            custom_price_cents: None,
+            currency: None,
+            subscription_start_date: None,
+            payment_method: None,
+            admin_user_id: None,
+            downgrade_timing: None,
+            refund_type: None,
+            refund_reason: None,
+            skip_email: false,
+            invoice_details: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tier_change_continues_past_failures() {
+        let changes: Vec<(Uuid, AdminTierChangeParams)> = ["pro", "platinum", "team", "", "free"]
+            .iter()
+            .map(|tier| (Uuid::new_v4(), tier_change_params(tier)))
+            .collect();
+        let org_ids: Vec<Uuid> = changes.iter().map(|(org_id, _)| *org_id).collect();
+
+        let running = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let results = run_bounded(changes, 2, |(org_id, params)| {
+            let (running, peak) = (running.clone(), peak.clone());
+            async move {
+                use std::sync::atomic::Ordering;
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                (org_id, params.validate().map(|()| params.new_tier))
+            }
+        })
+        .await;
+
+        let results: Vec<(Uuid, BillingResult<String>)> = results.into_iter().flatten().collect();
+        assert_eq!(results.len(), 5);
+        assert!(peak.load(std::sync::atomic::Ordering::SeqCst) <= 2);
+
+        // Input order is kept and the valid changes still completed
+        let returned: Vec<Uuid> = results.iter().map(|(org_id, _)| *org_id).collect();
+        assert_eq!(returned, org_ids);
+        let completed: Vec<&str> = results
+            .iter()
+            .filter_map(|(_, result)| result.as_deref().ok())
+            .collect();
+        assert_eq!(completed, vec!["pro", "team", "free"]);
+        assert!(matches!(results[1].1, Err(BillingError::InvalidTier(_))));
+        assert!(matches!(results[3].1, Err(BillingError::InvalidTier(_))));
+    }
+
     #[test]
     fn test_admin_tier_change_params_with_trial() {
         let params = AdminTierChangeParams {
@@ -3811,11 +6740,15 @@ mod tests {
             skip_payment_validation: true,
             billing_interval: Some("annual".to_string()),
             custom_price_cents: None,
+            currency: None,
             subscription_start_date: None,
             payment_method: Some("trial".to_string()),
             admin_user_id: Some(Uuid::new_v4()),
             downgrade_timing: None,
             refund_type: None,
+            refund_reason: None,
+            skip_email: false,
+            invoice_details: None,
         };
 
         assert_eq!(params.trial_days, Some(30));
@@ -3833,11 +6766,15 @@ mod tests {
             skip_payment_validation: false,
             billing_interval: Some("annual".to_string()),
             custom_price_cents: Some(499900), // $4,999/year
+            currency: None,
             subscription_start_date: None,
             payment_method: Some("invoice".to_string()),
             admin_user_id: None,
             downgrade_timing: None,
             refund_type: None,
+            refund_reason: None,
+            skip_email: false,
+            invoice_details: None,
         };
 
         assert_eq!(params.custom_price_cents, Some(499900));
@@ -3845,17 +6782,111 @@ mod tests {
     }
 
     // =========================================================================
-    // AdminTierChangeResult Tests
+    // InvoiceDetails Tests
     // =========================================================================
 
+    fn po_details() -> InvoiceDetails {
+        InvoiceDetails {
+            description: Some("Annual Enterprise agreement".to_string()),
+            footer: Some("Net 30. Wire details on file.".to_string()),
+            custom_fields: vec![InvoiceCustomField {
+                name: "PO Number".to_string(),
+                value: "PO-2026-0042".to_string(),
+            }],
+        }
+    }
+
     #[test]
-    fn test_admin_tier_change_result_serialization() {
-        let result = AdminTierChangeResult {
-            stripe_subscription_id: Some("sub_123".to_string()),
-            stripe_customer_id: "cus_456".to_string(),
-            tier: "pro".to_string(),
-            trial_end: None,
-            stripe_invoice_id: None,
+    fn test_invoice_details_applied_to_create_params() {
+        let details = po_details();
+        let mut params = stripe::CreateInvoice::new();
+        details.apply_to(&mut params);
+
+        assert_eq!(params.description, Some("Annual Enterprise agreement"));
+        assert_eq!(params.footer, Some("Net 30. Wire details on file."));
+        let fields = params.custom_fields.expect("custom fields set");
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].name, "PO Number");
+        assert_eq!(fields[0].value, "PO-2026-0042");
+    }
+
+    #[test]
+    fn test_empty_invoice_details_leave_params_bare() {
+        let details = InvoiceDetails::default();
+        assert!(details.is_empty());
+
+        let mut params = stripe::CreateInvoice::new();
+        details.apply_to(&mut params);
+        assert!(params.description.is_none());
+        assert!(params.footer.is_none());
+        assert!(params.custom_fields.is_none());
+        assert!(details.form_params().is_empty());
+    }
+
+    #[test]
+    fn test_invoice_details_form_params() {
+        let form = po_details().form_params();
+        let get = |key: &str| form.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+
+        assert_eq!(get("description"), Some("Annual Enterprise agreement"));
+        assert_eq!(get("footer"), Some("Net 30. Wire details on file."));
+        assert_eq!(get("custom_fields[0][name]"), Some("PO Number"));
+        assert_eq!(get("custom_fields[0][value]"), Some("PO-2026-0042"));
+    }
+
+    #[test]
+    fn test_invoice_details_validation() {
+        assert!(po_details().validate().is_ok());
+
+        let field = |name: &str, value: &str| InvoiceCustomField {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+
+        let too_many = InvoiceDetails {
+            custom_fields: (0..=MAX_INVOICE_CUSTOM_FIELDS)
+                .map(|i| field(&format!("F{}", i), "x"))
+                .collect(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            too_many.validate(),
+            Err(BillingError::InvalidInput(_))
+        ));
+
+        let too_long = InvoiceDetails {
+            custom_fields: vec![field("PO Number", &"9".repeat(31))],
+            ..Default::default()
+        };
+        assert!(too_long.validate().is_err());
+
+        let blank = InvoiceDetails {
+            custom_fields: vec![field("PO Number", " ")],
+            ..Default::default()
+        };
+        assert!(blank.validate().is_err());
+    }
+
+    #[test]
+    fn test_invoice_details_deserialize_without_custom_fields() {
+        let details: InvoiceDetails =
+            serde_json::from_str(r#"{"description":"memo","footer":null}"#).unwrap();
+        assert_eq!(details.description.as_deref(), Some("memo"));
+        assert!(details.custom_fields.is_empty());
+    }
+
+    // =========================================================================
+    // AdminTierChangeResult Tests
+    // =========================================================================
+
+    #[test]
+    fn test_admin_tier_change_result_serialization() {
+        let result = AdminTierChangeResult {
+            stripe_subscription_id: Some("sub_123".to_string()),
+            stripe_customer_id: "cus_456".to_string(),
+            tier: "pro".to_string(),
+            trial_end: None,
+            stripe_invoice_id: None,
             invoice_status: None,
             subscription_start_date: None,
             billing_interval: "monthly".to_string(),
@@ -3863,7 +6894,7 @@ mod tests {
             scheduled: false,
             scheduled_effective_date: None,
             refund_issued: false,
-            refund_amount_cents: None,
+            refund_amount: None,
             refund_type: None,
             message: "Tier changed successfully".to_string(),
         };
@@ -3940,6 +6971,744 @@ mod tests {
         assert!(tier_order("pro") == tier_order("pro"));
     }
 
+    // =========================================================================
+    // SubscriptionDiff Tests
+    // =========================================================================
+
+    fn snapshot() -> SubscriptionSnapshot {
+        SubscriptionSnapshot {
+            status: "active".to_string(),
+            stripe_price_id: Some("price_pro".to_string()),
+            current_period_start: OffsetDateTime::from_unix_timestamp(1_700_000_000).ok(),
+            current_period_end: OffsetDateTime::from_unix_timestamp(1_702_592_000).ok(),
+            cancel_at_period_end: false,
+        }
+    }
+
+    #[test]
+    fn test_idempotent_resync_produces_empty_diff() {
+        let stored = snapshot();
+        let diff = SubscriptionDiff::between(Some(&stored), &snapshot());
+        assert!(diff.is_empty());
+        assert_eq!(diff, SubscriptionDiff::default());
+    }
+
+    #[test]
+    fn test_first_sync_is_created() {
+        let diff = SubscriptionDiff::between(None, &snapshot());
+        assert!(diff.created);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_captures_changed_fields_only() {
+        let stored = snapshot();
+        let incoming = SubscriptionSnapshot {
+            status: "past_due".to_string(),
+            stripe_price_id: Some("price_team".to_string()),
+            ..snapshot()
+        };
+
+        let diff = SubscriptionDiff::between(Some(&stored), &incoming);
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.status,
+            Some(FieldChange {
+                old: "active".to_string(),
+                new: "past_due".to_string()
+            })
+        );
+        assert_eq!(
+            diff.price_id.map(|c| c.new),
+            Some(Some("price_team".to_string()))
+        );
+        assert!(diff.current_period_start.is_none());
+        assert!(diff.current_period_end.is_none());
+        assert!(diff.cancel_at_period_end.is_none());
+
+        let json = serde_json::to_value(SubscriptionDiff::between(Some(&stored), &incoming))
+            .expect("Failed to serialize");
+        assert!(json.get("current_period_end").is_none());
+    }
+
+    // =========================================================================
+    // Stripe Recovery Tests
+    // =========================================================================
+
+    fn stripe_sub(
+        id: &str,
+        status: StripeSubStatus,
+        created: i64,
+        org_id: Option<Uuid>,
+    ) -> Subscription {
+        let mut sub = Subscription {
+            id: id.parse().unwrap(),
+            status,
+            created,
+            ..Default::default()
+        };
+        if let Some(org_id) = org_id {
+            sub.metadata
+                .insert("org_id".to_string(), org_id.to_string());
+        }
+        sub
+    }
+
+    #[test]
+    fn test_recover_picks_active_subscription() {
+        let org_id = Uuid::new_v4();
+        let listed = vec![
+            stripe_sub("sub_old", StripeSubStatus::Canceled, 100, Some(org_id)),
+            stripe_sub("sub_live", StripeSubStatus::Active, 200, Some(org_id)),
+            stripe_sub("sub_expired", StripeSubStatus::IncompleteExpired, 300, None),
+        ];
+
+        let picked = pick_recoverable_subscription(&listed, org_id).unwrap();
+        assert_eq!(picked.id.as_str(), "sub_live");
+    }
+
+    #[test]
+    fn test_recover_prefers_active_over_past_due_then_newest() {
+        let org_id = Uuid::new_v4();
+        let listed = vec![
+            stripe_sub("sub_past_due", StripeSubStatus::PastDue, 500, None),
+            stripe_sub("sub_active_old", StripeSubStatus::Active, 100, None),
+            stripe_sub("sub_trial_new", StripeSubStatus::Trialing, 200, None),
+        ];
+
+        let picked = pick_recoverable_subscription(&listed, org_id).unwrap();
+        assert_eq!(picked.id.as_str(), "sub_trial_new");
+    }
+
+    #[test]
+    fn test_recover_skips_other_org_and_dead_subscriptions() {
+        let org_id = Uuid::new_v4();
+        let listed = vec![
+            stripe_sub(
+                "sub_other",
+                StripeSubStatus::Active,
+                100,
+                Some(Uuid::new_v4()),
+            ),
+            stripe_sub("sub_canceled", StripeSubStatus::Canceled, 200, Some(org_id)),
+        ];
+
+        assert!(pick_recoverable_subscription(&listed, org_id).is_none());
+        assert!(pick_recoverable_subscription(&[], org_id).is_none());
+    }
+
+    // =========================================================================
+    // Scheduled Downgrade Tests
+    // =========================================================================
+
+    #[test]
+    fn test_period_end_change_applies_tier_then_its_pending_price() {
+        // Team monthly -> Pro with a pending switch to annual billing
+        let change = PeriodEndChange::plan("pro", None, Some("annual"));
+        assert_eq!(change.tier, "pro");
+        assert_eq!(change.price, Some(PendingPrice::Annual));
+
+        // A pending custom price resolves against the new tier
+        let change = PeriodEndChange::plan("enterprise", Some(499_900), Some("annual"));
+        assert_eq!(
+            change.price,
+            Some(PendingPrice::Custom {
+                amount_cents: 499_900,
+                interval: "year",
+            })
+        );
+        // ...and is ignored for tiers without custom pricing
+        let change = PeriodEndChange::plan("team", Some(499_900), None);
+        assert_eq!(change.price, Some(PendingPrice::Monthly));
+    }
+
+    #[test]
+    fn test_period_end_change_to_free_has_no_price() {
+        let change = PeriodEndChange::plan("free", Some(10_000), Some("annual"));
+        assert_eq!(change.tier, "free");
+        assert_eq!(change.price, None);
+    }
+
+    // =========================================================================
+    // MRR Tests
+    // =========================================================================
+
+    fn priced_item(
+        unit_amount: i64,
+        quantity: u64,
+        interval: RecurringInterval,
+        usage_type: RecurringUsageType,
+    ) -> stripe::SubscriptionItem {
+        stripe::SubscriptionItem {
+            price: Some(stripe::Price {
+                unit_amount: Some(unit_amount),
+                recurring: Some(stripe::Recurring {
+                    interval,
+                    interval_count: 1,
+                    usage_type,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+
+    fn sub_with_items(
+        status: StripeSubStatus,
+        items: Vec<stripe::SubscriptionItem>,
+    ) -> Subscription {
+        let mut sub = stripe_sub("sub_mrr", status, 100, None);
+        sub.currency = stripe::Currency::USD;
+        sub.items.data = items;
+        sub
+    }
+
+    #[test]
+    fn test_annual_subscription_normalized_to_monthly() {
+        // $290/year Pro plan is $24.17/month
+        assert_eq!(
+            monthly_amount_cents(29_000, RecurringInterval::Year, 1),
+            2417
+        );
+        assert_eq!(
+            monthly_amount_cents(2_900, RecurringInterval::Month, 1),
+            2900
+        );
+        // Quarterly billing
+        assert_eq!(
+            monthly_amount_cents(9_000, RecurringInterval::Month, 3),
+            3000
+        );
+
+        let sub = sub_with_items(
+            StripeSubStatus::Active,
+            vec![priced_item(
+                120_000,
+                1,
+                RecurringInterval::Year,
+                RecurringUsageType::Licensed,
+            )],
+        );
+        assert_eq!(subscription_mrr(&sub), Money::from_cents_usd(10_000));
+    }
+
+    #[test]
+    fn test_mrr_includes_addons_and_skips_metered() {
+        let sub = sub_with_items(
+            StripeSubStatus::Active,
+            vec![
+                priced_item(
+                    2_900,
+                    1,
+                    RecurringInterval::Month,
+                    RecurringUsageType::Licensed,
+                ),
+                // Two seats of an add-on
+                priced_item(
+                    500,
+                    2,
+                    RecurringInterval::Month,
+                    RecurringUsageType::Licensed,
+                ),
+                priced_item(50, 1, RecurringInterval::Month, RecurringUsageType::Metered),
+            ],
+        );
+        assert_eq!(subscription_mrr(&sub).amount_cents, 3_900);
+    }
+
+    #[test]
+    fn test_mrr_excludes_trialing_and_canceled() {
+        let items = || {
+            vec![priced_item(
+                2_900,
+                1,
+                RecurringInterval::Month,
+                RecurringUsageType::Licensed,
+            )]
+        };
+
+        for status in [StripeSubStatus::Trialing, StripeSubStatus::Canceled] {
+            let sub = sub_with_items(status, items());
+            assert_eq!(subscription_mrr(&sub).amount_cents, 0, "{status:?}");
+        }
+        let past_due = sub_with_items(StripeSubStatus::PastDue, items());
+        assert_eq!(subscription_mrr(&past_due).amount_cents, 2_900);
+    }
+
+    #[test]
+    fn test_tier_price_fix_aligns_to_stored_tier() {
+        let mut config = StripeConfig::for_tests();
+        config.price_ids.pro_annual = Some("price_pro_annual".to_string());
+        config.price_ids.team_annual = Some("price_team_annual".to_string());
+        config.price_ids.addon_only = Some("price_addon_only".to_string());
+
+        // Matching prices, and prices the config doesn't know, need nothing
+        assert_eq!(plan_tier_price_fix(&config, "pro", "price_pro"), None);
+        assert_eq!(
+            plan_tier_price_fix(&config, "team", "price_team_annual"),
+            None
+        );
+        assert_eq!(
+            plan_tier_price_fix(&config, "pro", "price_custom_123"),
+            None
+        );
+        assert_eq!(
+            plan_tier_price_fix(&config, "free", "price_addon_only"),
+            None
+        );
+
+        // The interval is kept when moving to the stored tier's price
+        assert_eq!(
+            plan_tier_price_fix(&config, "pro", "price_team_annual"),
+            Some((
+                "team".to_string(),
+                TierPriceFix::AlignPrice {
+                    from_price_id: "price_team_annual".to_string(),
+                    to_price_id: "price_pro_annual".to_string(),
+                }
+            ))
+        );
+
+        for (db_tier, price_id) in [
+            ("free", "price_pro"),
+            ("enterprise", "price_team"),
+            ("team", "price_addon_only"),
+        ] {
+            assert!(matches!(
+                plan_tier_price_fix(&config, db_tier, price_id),
+                Some((_, TierPriceFix::Flag { .. }))
+            ));
+        }
+
+        // No annual team price to move to
+        config.price_ids.team_annual = None;
+        assert!(matches!(
+            plan_tier_price_fix(&config, "team", "price_pro_annual"),
+            Some((_, TierPriceFix::Flag { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_tier_price_repair_dry_run_proposes_alignment() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service =
+            SubscriptionService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone());
+
+        // Stored as pro, but Stripe bills the team price
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Mismatch Org', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("mismatch-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (id, org_id, stripe_subscription_id, stripe_price_id, status)
+            VALUES ($1, $2, $3, 'price_team', 'active')
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(org_id)
+        .bind(format!("sub_{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let aligned = std::sync::atomic::AtomicUsize::new(0);
+        let actions = service
+            .repair_tier_price_mismatches_with(true, |_| {
+                aligned.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Ok(()) }
+            })
+            .await
+            .unwrap();
+
+        let action = actions.iter().find(|a| a.org_id == org_id).unwrap();
+        assert_eq!(
+            action,
+            &RepairAction {
+                org_id,
+                stripe_subscription_id: format!("sub_{}", org_id.simple()),
+                db_tier: "pro".to_string(),
+                stripe_tier: "team".to_string(),
+                fix: TierPriceFix::AlignPrice {
+                    from_price_id: "price_team".to_string(),
+                    to_price_id: "price_pro".to_string(),
+                },
+                applied: false,
+                error: None,
+            }
+        );
+        // A dry run never touches Stripe
+        assert_eq!(aligned.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_tier_change_history_pages_through_equal_timestamps() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service =
+            SubscriptionService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone());
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'History Org', $2, 'free')",
+        )
+        .bind(org_id)
+        .bind(format!("history-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Five changes logged in the same instant
+        sqlx::query(
+            r#"
+            INSERT INTO tier_change_audit (org_id, from_tier, to_tier, source, created_at)
+            SELECT $1, 'free', 'pro', 'admin', '2026-01-01T00:00:00Z'::TIMESTAMPTZ
+            FROM generate_series(1, 5)
+            "#,
+        )
+        .bind(org_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = service
+                .tier_change_history_before(org_id, 2, cursor)
+                .await
+                .unwrap();
+            seen.extend(page.iter().map(|r| r.id));
+            cursor = tier_change_history_cursor(&page, 2);
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        let mut unique = seen.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(seen.len(), 5);
+        assert_eq!(unique.len(), 5);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_retry_after_webhook_update_reuses_idempotency_key() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service =
+            SubscriptionService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone());
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Retry Org', $2, 'free')",
+        )
+        .bind(org_id)
+        .bind(format!("retry-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // First create_subscription call times out before Stripe answers
+        let key_for = |attempt_id: Uuid| {
+            idempotency_key(
+                org_id,
+                "create_subscription",
+                "pro",
+                &attempt_id.to_string(),
+            )
+        };
+        let first = service
+            .idempotency_attempt(org_id, "create_subscription", "pro")
+            .await
+            .unwrap();
+
+        // Stripe did create it; the webhook writes the subscription row
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (id, org_id, stripe_subscription_id, stripe_price_id, status, updated_at)
+            VALUES ($1, $2, $3, 'price_pro', 'active', NOW() + INTERVAL '1 second')
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(org_id)
+        .bind(format!("sub_{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // The retry still sends the original key, so Stripe replays the create
+        let retry = service
+            .idempotency_attempt(org_id, "create_subscription", "pro")
+            .await
+            .unwrap();
+        assert_eq!(key_for(retry), key_for(first));
+
+        // Once Stripe has answered, the next deliberate call is a new attempt
+        service
+            .finish_idempotency_attempt(org_id, "create_subscription", "pro", retry)
+            .await;
+        let next = service
+            .idempotency_attempt(org_id, "create_subscription", "pro")
+            .await
+            .unwrap();
+        assert_ne!(key_for(next), key_for(first));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_confirmed_change_waits_for_concurrent_tier_change() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service =
+            SubscriptionService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone());
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Lock Org', $2, 'team')",
+        )
+        .bind(org_id)
+        .bind(format!("lock-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        let preview = service.tier_change_impact(org_id, "pro").await.unwrap();
+
+        // Another tier change holds the org row while the confirmation arrives
+        let mut other = pool.begin().await.unwrap();
+        sqlx::query("SELECT id FROM organizations WHERE id = $1 FOR UPDATE")
+            .bind(org_id)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE organizations SET subscription_tier = 'enterprise' WHERE id = $1")
+            .bind(org_id)
+            .execute(&mut *other)
+            .await
+            .unwrap();
+
+        let confirmed = tokio::spawn(async move {
+            service
+                .change_tier_confirmed(
+                    org_id,
+                    "pro",
+                    ChangeConfirmation {
+                        impact_token: preview.impact_token,
+                    },
+                )
+                .await
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+        assert!(!confirmed.is_finished());
+
+        // Once it commits, the check sees the new tier and refuses the old preview
+        other.commit().await.unwrap();
+        assert!(matches!(
+            confirmed.await.unwrap(),
+            Err(BillingError::ConfirmationStale(_))
+        ));
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_trial_cancelled_early_blocks_another_trial() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service =
+            SubscriptionService::new(StripeClient::new(StripeConfig::for_tests()), pool.clone());
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Trial Org', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("trial-{}", org_id))
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(service.is_trial_eligible(org_id).await.unwrap());
+
+        // Trial starts, then is cancelled before it converts
+        let trial_start = OffsetDateTime::now_utc() - time::Duration::days(3);
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (id, org_id, stripe_subscription_id, status, trial_start)
+            VALUES ($1, $2, $3, 'trialing', $4)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(org_id)
+        .bind(format!("sub_{}", org_id.simple()))
+        .bind(trial_start)
+        .execute(&pool)
+        .await
+        .unwrap();
+        service
+            .record_trial_started(org_id, trial_start)
+            .await
+            .unwrap();
+        // The running trial may still be extended
+        assert!(!service.is_trial_eligible(org_id).await.unwrap());
+        assert!(service.may_set_trial(org_id).await.unwrap());
+
+        sqlx::query("UPDATE subscriptions SET status = 'canceled' WHERE org_id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(!service.is_trial_eligible(org_id).await.unwrap());
+        assert!(!service.may_set_trial(org_id).await.unwrap());
+
+        // Resubscribing overwrites the row's trial_start; the org flag remains
+        sqlx::query(
+            "UPDATE subscriptions SET status = 'active', trial_start = NULL WHERE org_id = $1",
+        )
+        .bind(org_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(!service.is_trial_eligible(org_id).await.unwrap());
+
+        // An admin trial for the same org is refused before Stripe is called
+        let mut params = tier_change_params("team");
+        params.trial_days = Some(14);
+        assert!(matches!(
+            service.admin_change_tier(org_id, params).await,
+            Err(BillingError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_subscription_period_rejects_zero_and_negative_timestamps() {
+        let mut sub = stripe_sub("sub_period", StripeSubStatus::Active, 100, None);
+        sub.current_period_start = 1_700_000_000;
+        sub.current_period_end = 1_702_592_000;
+        let (start, end) = subscription_period(&sub).unwrap();
+        assert_eq!(start.unix_timestamp(), 1_700_000_000);
+        assert_eq!(end.unix_timestamp(), 1_702_592_000);
+
+        // Stripe's zero default, negative values and out-of-range values
+        for (start, end) in [
+            (0, 0),
+            (1_700_000_000, 0),
+            (0, 1_702_592_000),
+            (-1, 1_702_592_000),
+            (1_700_000_000, -1),
+            (1_700_000_000, i64::MAX),
+        ] {
+            sub.current_period_start = start;
+            sub.current_period_end = end;
+            assert_eq!(subscription_period(&sub), None, "({start}, {end})");
+        }
+    }
+
+    #[test]
+    fn test_subscription_period_rejects_inverted_period() {
+        assert_eq!(period_from_timestamps(1_702_592_000, 1_700_000_000), None);
+        assert_eq!(period_from_timestamps(1_700_000_000, 1_700_000_000), None);
+    }
+
+    #[test]
+    fn test_mrr_totals_are_kept_per_currency() {
+        let mut totals = Vec::new();
+        add_to_currency_totals(&mut totals, Money::from_cents_usd(2_900));
+        add_to_currency_totals(&mut totals, Money::new(1_000, "eur"));
+        add_to_currency_totals(&mut totals, Money::from_cents_usd(100));
+
+        assert_eq!(
+            totals,
+            vec![Money::from_cents_usd(3_000), Money::new(1_000, "eur")]
+        );
+    }
+
+    // =========================================================================
+    // Proration Rounding Tests
+    // =========================================================================
+
+    #[test]
+    fn test_proration_rounding_default_is_floor() {
+        assert_eq!(ProrationRounding::default(), ProrationRounding::Floor);
+    }
+
+    #[test]
+    fn test_proration_rounding_ceil_vs_round_boundary() {
+        let now = 1_700_000_000;
+        // 10 days and 1 hour: ceil overstates by a day, round and floor don't
+        let period_end = now + 10 * 86400 + 3600;
+        assert_eq!(ProrationRounding::Ceil.days_remaining(period_end, now), 11);
+        assert_eq!(ProrationRounding::Round.days_remaining(period_end, now), 10);
+        assert_eq!(ProrationRounding::Floor.days_remaining(period_end, now), 10);
+
+        // 10.5 days: round goes up, floor stays
+        let period_end = now + 10 * 86400 + 43200;
+        assert_eq!(ProrationRounding::Round.days_remaining(period_end, now), 11);
+        assert_eq!(ProrationRounding::Floor.days_remaining(period_end, now), 10);
+
+        // Exact day boundary: all modes agree
+        let period_end = now + 10 * 86400;
+        for mode in [
+            ProrationRounding::Ceil,
+            ProrationRounding::Floor,
+            ProrationRounding::Round,
+        ] {
+            assert_eq!(mode.days_remaining(period_end, now), 10);
+        }
+    }
+
+    #[test]
+    fn test_proration_rounding_never_negative() {
+        let now = 1_700_000_000;
+        assert_eq!(ProrationRounding::Ceil.days_remaining(now - 500, now), 0);
+    }
+
+    #[test]
+    fn test_proration_rounding_parsing() {
+        assert_eq!(
+            "ceil".parse::<ProrationRounding>().unwrap(),
+            ProrationRounding::Ceil
+        );
+        assert_eq!(
+            " ROUND ".parse::<ProrationRounding>().unwrap(),
+            ProrationRounding::Round
+        );
+        assert!("nearest".parse::<ProrationRounding>().is_err());
+    }
+
+    #[test]
+    fn test_proration_description_matches_days() {
+        assert_eq!(
+            proration_description("starter", "pro", 1),
+            "Upgrade from starter to pro with 1 day remaining"
+        );
+        assert_eq!(
+            proration_description("starter", "pro", 10),
+            "Upgrade from starter to pro with 10 days remaining"
+        );
+    }
+
     #[test]
     fn test_billing_interval_parsing() {
         // Test default billing interval
@@ -3950,11 +7719,15 @@ mod tests {
             skip_payment_validation: false,
             billing_interval: None,
             custom_price_cents: None,
+            currency: None,
             subscription_start_date: None,
             payment_method: None,
             admin_user_id: None,
             downgrade_timing: None,
             refund_type: None,
+            refund_reason: None,
+            skip_email: false,
+            invoice_details: None,
         };
 
         let interval = params1.billing_interval.as_deref().unwrap_or("monthly");
@@ -3969,4 +7742,572 @@ mod tests {
         let interval = params2.billing_interval.as_deref().unwrap_or("monthly");
         assert_eq!(interval, "annual");
     }
+
+    fn audit_record(id: u128, created_at: i64) -> TierChangeAuditRecord {
+        TierChangeAuditRecord {
+            id: Uuid::from_u128(id),
+            org_id: Uuid::from_u128(1),
+            from_tier: "free".to_string(),
+            to_tier: "pro".to_string(),
+            source: "user_upgrade".to_string(),
+            changed_by: None,
+            reason: None,
+            stripe_event_id: None,
+            metadata: None,
+            created_at: OffsetDateTime::from_unix_timestamp(created_at).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_tier_change_history_cursor_keeps_id_for_equal_timestamps() {
+        // Newest first with ties broken by id, as returned by tier_change_history
+        let page = [
+            audit_record(3, 100),
+            audit_record(2, 100),
+            audit_record(1, 100),
+        ];
+        assert_eq!(
+            tier_change_history_cursor(&page[..2], 2),
+            Some(TierChangeHistoryCursor {
+                created_at: OffsetDateTime::from_unix_timestamp(100).unwrap(),
+                id: Uuid::from_u128(2),
+            })
+        );
+    }
+
+    #[test]
+    fn test_tier_change_history_cursor_last_page() {
+        let page = vec![audit_record(2, 200), audit_record(1, 100)];
+        assert_eq!(tier_change_history_cursor(&page, 3), None);
+        assert_eq!(tier_change_history_cursor(&[], 3), None);
+        assert_eq!(tier_change_history_cursor(&page, 0), None);
+    }
+
+    #[test]
+    fn test_tier_change_audit_record_serializes_timestamp() {
+        let json = serde_json::to_value(audit_record(1, 0)).unwrap();
+        assert_eq!(json["created_at"], "1970-01-01T00:00:00Z");
+        assert_eq!(json["from_tier"], "free");
+        assert_eq!(json["to_tier"], "pro");
+    }
+
+    #[test]
+    fn test_tier_change_currency_defaults_to_usd() {
+        let mut params = tier_change_params("enterprise");
+        assert_eq!(params.currency().unwrap(), stripe::Currency::USD);
+
+        params.currency = Some("EUR".to_string());
+        assert_eq!(params.currency().unwrap(), stripe::Currency::EUR);
+
+        params.currency = Some("xyz".to_string());
+        assert!(matches!(
+            params.validate(),
+            Err(BillingError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_customer_currency_mismatch() {
+        use stripe::Currency;
+        match check_customer_currency(Some(Currency::USD), Currency::EUR) {
+            Err(BillingError::CurrencyMismatch {
+                customer_currency,
+                requested_currency,
+            }) => {
+                assert_eq!(customer_currency, "usd");
+                assert_eq!(requested_currency, "eur");
+            }
+            other => panic!("Expected CurrencyMismatch, got {:?}", other),
+        }
+        assert!(check_customer_currency(Some(Currency::EUR), Currency::EUR).is_ok());
+        // No currency fixed yet: anything goes
+        assert!(check_customer_currency(None, Currency::EUR).is_ok());
+    }
+
+    #[test]
+    fn test_custom_price_bounds() {
+        let bounds = CustomPriceBounds::default();
+        assert!(matches!(
+            bounds.validate(0),
+            Err(BillingError::InvalidAmount(_))
+        ));
+        assert!(bounds.validate(-500).is_err());
+        assert!(bounds.validate(DEFAULT_CUSTOM_PRICE_MIN_CENTS - 1).is_err());
+        assert!(bounds.validate(DEFAULT_CUSTOM_PRICE_MIN_CENTS).is_ok());
+        assert!(bounds.validate(DEFAULT_CUSTOM_PRICE_MAX_CENTS).is_ok());
+        assert!(bounds.validate(DEFAULT_CUSTOM_PRICE_MAX_CENTS + 1).is_err());
+
+        let custom = CustomPriceBounds {
+            min_cents: 1,
+            max_cents: 5_000,
+        };
+        assert!(custom.validate(1).is_ok());
+        assert!(custom.validate(5_001).is_err());
+    }
+
+    #[test]
+    fn test_custom_price_interval_accepts_year_and_annual() {
+        use stripe::CreatePriceRecurringInterval as Interval;
+        assert_eq!(custom_price_interval("annual"), Interval::Year);
+        assert_eq!(custom_price_interval("year"), Interval::Year);
+        assert_eq!(custom_price_interval("monthly"), Interval::Month);
+        assert_eq!(custom_price_interval("month"), Interval::Month);
+    }
+
+    #[test]
+    fn test_custom_price_lookup_key_identifies_identical_prices() {
+        use stripe::{CreatePriceRecurringInterval as Interval, Currency};
+        let org_id = Uuid::new_v4();
+        let key = custom_price_lookup_key(org_id, Interval::Year, 500_000, Currency::USD);
+
+        // Same org, interval and amount reuse the same price
+        assert_eq!(
+            key,
+            custom_price_lookup_key(
+                org_id,
+                custom_price_interval("annual"),
+                500_000,
+                Currency::USD
+            )
+        );
+        assert_ne!(
+            key,
+            custom_price_lookup_key(org_id, Interval::Month, 500_000, Currency::USD)
+        );
+        assert_ne!(
+            key,
+            custom_price_lookup_key(org_id, Interval::Year, 500_001, Currency::USD)
+        );
+        assert_ne!(
+            key,
+            custom_price_lookup_key(Uuid::new_v4(), Interval::Year, 500_000, Currency::USD)
+        );
+        // USD keys are unchanged; other currencies get their own price
+        assert_eq!(key, format!("enterprise_custom_{}_year_500000", org_id));
+        assert_ne!(
+            key,
+            custom_price_lookup_key(org_id, Interval::Year, 500_000, Currency::EUR)
+        );
+    }
+
+    #[test]
+    fn test_reusable_custom_price() {
+        use stripe::{
+            CreatePriceRecurringInterval as Interval, Currency, Recurring, RecurringInterval,
+        };
+        let price = stripe::Price {
+            active: Some(true),
+            unit_amount: Some(500_000),
+            currency: Some(Currency::USD),
+            recurring: Some(Recurring {
+                interval: RecurringInterval::Year,
+                interval_count: 1,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(is_reusable_custom_price(
+            &price,
+            500_000,
+            Interval::Year,
+            Currency::USD
+        ));
+        assert!(!is_reusable_custom_price(
+            &price,
+            400_000,
+            Interval::Year,
+            Currency::USD
+        ));
+        assert!(!is_reusable_custom_price(
+            &price,
+            500_000,
+            Interval::Month,
+            Currency::USD
+        ));
+        assert!(!is_reusable_custom_price(
+            &price,
+            500_000,
+            Interval::Year,
+            Currency::EUR
+        ));
+
+        let archived = stripe::Price {
+            active: Some(false),
+            ..price.clone()
+        };
+        assert!(!is_reusable_custom_price(
+            &archived,
+            500_000,
+            Interval::Year,
+            Currency::USD
+        ));
+
+        let one_time = stripe::Price {
+            recurring: None,
+            ..price
+        };
+        assert!(!is_reusable_custom_price(
+            &one_time,
+            500_000,
+            Interval::Year,
+            Currency::USD
+        ));
+    }
+
+    fn sub_item(id: &str, price_id: &str, quantity: u64) -> stripe::SubscriptionItem {
+        stripe::SubscriptionItem {
+            id: id.parse().unwrap(),
+            price: Some(stripe::Price {
+                id: price_id.parse().unwrap(),
+                ..Default::default()
+            }),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_subscription_kind() {
+        let addon_only = Some("price_addon_only");
+        assert_eq!(
+            SubscriptionKind::from_price_id(Some("price_addon_only"), addon_only),
+            SubscriptionKind::AddonOnly
+        );
+        assert_eq!(
+            SubscriptionKind::from_price_id(Some("price_pro"), addon_only),
+            SubscriptionKind::Tier
+        );
+        assert_eq!(
+            SubscriptionKind::from_price_id(Some("price_addon_only"), None),
+            SubscriptionKind::Tier
+        );
+
+        let mut sub = stripe_sub("sub_addon", StripeSubStatus::Active, 0, None);
+        assert_eq!(SubscriptionKind::of(&sub, None), SubscriptionKind::Tier);
+        sub.metadata
+            .insert("type".to_string(), "addon_only".to_string());
+        assert_eq!(
+            SubscriptionKind::of(&sub, None),
+            SubscriptionKind::AddonOnly
+        );
+    }
+
+    #[test]
+    fn test_upgrade_over_addon_only_migrates_addons() {
+        use SubscriptionKind::{AddonOnly, Tier};
+        assert_eq!(
+            SubscriptionReplacement::decide(AddonOnly, true, Tier, true),
+            SubscriptionReplacement::MigrateAddons
+        );
+        // A stale paid subscription doesn't pull add-ons onto itself
+        assert_eq!(
+            SubscriptionReplacement::decide(AddonOnly, true, Tier, false),
+            SubscriptionReplacement::Ignore
+        );
+        // Late events for the old addon-only base never replace the paid one
+        assert_eq!(
+            SubscriptionReplacement::decide(Tier, true, AddonOnly, false),
+            SubscriptionReplacement::Ignore
+        );
+        // Free org whose paid plan ended buys an add-on
+        assert_eq!(
+            SubscriptionReplacement::decide(Tier, false, AddonOnly, true),
+            SubscriptionReplacement::Apply
+        );
+        assert_eq!(
+            SubscriptionReplacement::decide(Tier, true, Tier, true),
+            SubscriptionReplacement::Apply
+        );
+    }
+
+    #[test]
+    fn test_addon_item_moves_skip_base_and_reuse_existing() {
+        let addon_only_items = vec![
+            sub_item("si_base", "price_addon_only", 1),
+            sub_item("si_domain", "price_custom_domain", 1),
+            sub_item("si_requests", "price_extra_requests", 3),
+        ];
+        let paid_items = vec![
+            sub_item("si_pro", "price_pro", 1),
+            sub_item("si_paid_requests", "price_extra_requests", 1),
+        ];
+
+        let moves = plan_addon_item_moves(&addon_only_items, &paid_items, Some("price_addon_only"));
+        assert_eq!(
+            moves,
+            vec![
+                AddonItemMove {
+                    from_item_id: "si_domain".to_string(),
+                    price_id: "price_custom_domain".to_string(),
+                    quantity: 1,
+                    existing_item_id: None,
+                },
+                AddonItemMove {
+                    from_item_id: "si_requests".to_string(),
+                    price_id: "price_extra_requests".to_string(),
+                    quantity: 3,
+                    existing_item_id: Some("si_paid_requests".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_addon_item_moves_empty_base() {
+        let addon_only_items = vec![sub_item("si_base", "price_addon_only", 1)];
+        assert!(plan_addon_item_moves(&addon_only_items, &[], Some("price_addon_only")).is_empty());
+    }
+
+    #[test]
+    fn test_only_answered_stripe_calls_retire_the_attempt() {
+        let rejected = |http_status| {
+            Err::<(), _>(stripe::StripeError::Stripe(stripe::RequestError {
+                http_status,
+                ..Default::default()
+            }))
+        };
+
+        assert!(stripe_outcome_is_confirmed(&Ok::<(), stripe::StripeError>(
+            ()
+        )));
+        assert!(stripe_outcome_is_confirmed(&rejected(400)));
+        assert!(stripe_outcome_is_confirmed(&rejected(402)));
+        // Key still in use by a concurrent request
+        assert!(!stripe_outcome_is_confirmed(&rejected(409)));
+        // Outcome unknown: Stripe may have acted
+        assert!(!stripe_outcome_is_confirmed(&Err::<(), _>(
+            stripe::StripeError::Timeout
+        )));
+        assert!(!stripe_outcome_is_confirmed(&Err::<(), _>(
+            stripe::StripeError::ClientError("connection reset".to_string())
+        )));
+    }
+
+    #[test]
+    fn test_trialing_subscription_gets_paid_quota() {
+        let quota = |status: &str| {
+            entitled_tier(status, Some("pro"))
+                .and_then(|tier| tier.parse::<SubscriptionTier>().ok())
+                .unwrap_or(SubscriptionTier::Free)
+                .monthly_requests()
+        };
+
+        assert_eq!(quota("trialing"), SubscriptionTier::Pro.monthly_requests());
+        assert_eq!(quota("active"), SubscriptionTier::Pro.monthly_requests());
+        assert_eq!(quota("past_due"), SubscriptionTier::Pro.monthly_requests());
+        assert_eq!(
+            quota("incomplete"),
+            SubscriptionTier::Free.monthly_requests()
+        );
+    }
+
+    fn tier_event(
+        org_id: Uuid,
+        event_type: BillingEventType,
+        from: &str,
+        to: &str,
+        at: OffsetDateTime,
+    ) -> BillingEvent {
+        BillingEvent {
+            id: Uuid::new_v4(),
+            org_id,
+            event_type: event_type.to_string(),
+            event_subtype: None,
+            event_data: serde_json::json!({ "from_tier": from, "to_tier": to }),
+            stripe_event_id: None,
+            stripe_invoice_id: None,
+            stripe_subscription_id: None,
+            stripe_customer_id: None,
+            actor_id: None,
+            actor_type: "admin".to_string(),
+            entitlement_snapshot: None,
+            created_at: at,
+        }
+    }
+
+    fn audit_row(org_id: Uuid, from: &str, to: &str, at: OffsetDateTime) -> TierChangeAuditRecord {
+        TierChangeAuditRecord {
+            id: Uuid::new_v4(),
+            org_id,
+            from_tier: from.to_string(),
+            to_tier: to.to_string(),
+            source: "admin_panel".to_string(),
+            changed_by: None,
+            reason: None,
+            stripe_event_id: None,
+            metadata: None,
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_audit_backfill_creates_missing_rows_only() {
+        let org_id = Uuid::new_v4();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let t1 = t0 + time::Duration::days(30);
+
+        let events = vec![
+            // Predates the audit table
+            tier_event(org_id, BillingEventType::TierChanged, "free", "pro", t0),
+            // Already audited, logged just after the audit row
+            tier_event(
+                org_id,
+                BillingEventType::TierChangeScheduled,
+                "pro",
+                "free",
+                t1 + time::Duration::milliseconds(40),
+            ),
+            tier_event(org_id, BillingEventType::SubscriptionCreated, "", "", t0),
+        ];
+        let existing = vec![audit_row(org_id, "pro", "free", t1)];
+
+        let missing = plan_audit_backfill(&events, &existing);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].event_id, events[0].id);
+        assert_eq!(missing[0].from_tier, "free");
+        assert_eq!(missing[0].to_tier, "pro");
+        assert_eq!(missing[0].source, "admin_panel");
+        assert_eq!(missing[0].created_at, t0);
+        assert!(!missing[0].scheduled);
+    }
+
+    #[test]
+    fn test_audit_backfill_is_idempotent() {
+        let org_id = Uuid::new_v4();
+        let t0 = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let events = vec![
+            tier_event(org_id, BillingEventType::TierChanged, "free", "pro", t0),
+            tier_event(org_id, BillingEventType::TierChanged, "free", "pro", t0),
+        ];
+
+        // Identical changes at the same time each need their own row
+        let missing = plan_audit_backfill(&events, &[]);
+        assert_eq!(missing.len(), 2);
+
+        // Rows written by a previous run match their events by id
+        let backfilled: Vec<TierChangeAuditRecord> = missing
+            .iter()
+            .map(|audit| TierChangeAuditRecord {
+                metadata: Some(serde_json::json!({
+                    "backfilled_from_event": audit.event_id,
+                })),
+                ..audit_row(org_id, "free", "pro", t0)
+            })
+            .collect();
+        assert!(plan_audit_backfill(&events, &backfilled).is_empty());
+    }
+
+    #[test]
+    fn test_backfilled_audit_source_from_actor() {
+        let org_id = Uuid::new_v4();
+        let at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        let mut event = tier_event(org_id, BillingEventType::TierChanged, "team", "pro", at);
+        event.actor_type = "user".to_string();
+        event.event_data["is_downgrade"] = serde_json::json!(true);
+        assert_eq!(
+            BackfilledAudit::from_event(&event).unwrap().source,
+            "user_downgrade"
+        );
+
+        event.actor_type = "stripe".to_string();
+        assert_eq!(
+            BackfilledAudit::from_event(&event).unwrap().source,
+            "stripe_webhook"
+        );
+
+        // An explicit source wins over the actor
+        event.event_data["source"] = serde_json::json!("admin_panel");
+        assert_eq!(
+            BackfilledAudit::from_event(&event).unwrap().source,
+            "admin_panel"
+        );
+    }
+
+    #[test]
+    fn test_entitled_statuses() {
+        for status in ["active", "trialing", "past_due"] {
+            assert!(is_entitled_status(status), "{status}");
+        }
+        for status in [
+            "incomplete",
+            "incomplete_expired",
+            "unpaid",
+            "canceled",
+            "paused",
+        ] {
+            assert!(!is_entitled_status(status), "{status}");
+        }
+        assert_eq!(entitled_tier("active", None), None);
+    }
+
+    fn renewal(org_id: Uuid, tier: &str, renews_at: OffsetDateTime) -> UpcomingRenewal {
+        UpcomingRenewal {
+            org_id,
+            stripe_subscription_id: Some(format!("sub_{}", tier)),
+            stripe_price_id: Some(format!("price_{}", tier)),
+            tier: tier.to_string(),
+            custom_monthly_price_cents: None,
+            renews_at,
+            addons_cents: 0,
+            overage_cents: 0,
+        }
+    }
+
+    #[test]
+    fn test_upcoming_charges_compose_expected_amounts() {
+        let at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        // Pro org on the list price with an add-on and pending overage
+        let pro = UpcomingRenewal {
+            addons_cents: 1_500,
+            overage_cents: 420,
+            ..renewal(Uuid::new_v4(), "pro", at)
+        };
+        let charge = pro.into_charge(Some(2_900));
+        assert_eq!(charge.base_cents, 2_900);
+        assert_eq!(charge.expected, Money::from_cents_usd(2_900 + 1_500 + 420));
+
+        // Negotiated price wins over the Stripe list price
+        let enterprise = UpcomingRenewal {
+            custom_monthly_price_cents: Some(50_000),
+            overage_cents: 10_000,
+            ..renewal(Uuid::new_v4(), "enterprise", at)
+        };
+        let charge = enterprise.into_charge(Some(99_900));
+        assert_eq!(charge.base_cents, 50_000);
+        assert_eq!(charge.expected, Money::from_cents_usd(60_000));
+
+        // Stripe price unavailable: fall back to the tier default
+        let team = renewal(Uuid::new_v4(), "team", at).into_charge(None);
+        let team_default = SubscriptionTier::Team.monthly_price_cents().map(i64::from);
+        assert_eq!(Some(team.base_cents), team_default);
+        assert_eq!(team.expected, Money::from_cents_usd(team.base_cents));
+
+        // Unknown tier with no price forecasts only what is known
+        let legacy = UpcomingRenewal {
+            addons_cents: 700,
+            ..renewal(Uuid::new_v4(), "legacy", at)
+        };
+        let charge = legacy.into_charge(None);
+        assert_eq!(charge.base_cents, 0);
+        assert_eq!(charge.expected, Money::from_cents_usd(700));
+    }
+
+    #[test]
+    fn test_upcoming_charges_cursor_only_for_full_pages() {
+        let at = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        let charges: Vec<UpcomingCharge> = (0..3)
+            .map(|i| {
+                renewal(Uuid::new_v4(), "pro", at + time::Duration::hours(i)).into_charge(None)
+            })
+            .collect();
+
+        let cursor = upcoming_charges_cursor(&charges, 3).unwrap();
+        assert_eq!(cursor.renews_at, charges[2].renews_at);
+        assert_eq!(cursor.org_id, charges[2].org_id);
+
+        assert!(upcoming_charges_cursor(&charges, 4).is_none());
+        assert!(upcoming_charges_cursor(&[], 0).is_none());
+    }
 }
@@ -5,7 +5,11 @@
 //! - Tax rate configuration per region
 //! - Tax reporting for compliance
 //!
-//! This module integrates with Stripe Tax for automatic tax calculation.
+//! Tax is calculated with Stripe Tax by default, or from manually configured
+//! rates for jurisdictions where we self-calculate (see `TaxStrategy`).
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
@@ -65,6 +69,149 @@ impl TaxIdType {
     }
 }
 
+/// Country prefixes accepted for EU VAT numbers (Greece uses EL, Northern Ireland XI)
+const EU_VAT_PREFIXES: &[&str] = &[
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "EL", "ES", "FI", "FR", "HR", "HU", "IE", "IT",
+    "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK", "XI",
+];
+
+fn all_digits(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn all_alphanumeric(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// Expected format for a tax ID type, shown when a value doesn't match
+fn tax_id_format(tax_id_type: TaxIdType) -> &'static str {
+    match tax_id_type {
+        TaxIdType::EuVat => "EU VAT number must be an EU country prefix followed by 2-12 letters or digits (e.g. DE123456789)",
+        TaxIdType::GbVat => "GB VAT number must be GB followed by 9 or 12 digits (e.g. GB123456789)",
+        TaxIdType::UsEin => "US EIN must be 9 digits (e.g. 12-3456789)",
+        TaxIdType::AuAbn => "Australian ABN must be 11 digits (e.g. 12345678912)",
+        TaxIdType::CaGstHst => "Canadian GST/HST number must be 9 digits, RT and 4 digits (e.g. 123456789RT0002)",
+        TaxIdType::BrCnpj => "Brazilian CNPJ must be 14 digits (e.g. 01.234.456/5432-10)",
+        TaxIdType::InGst => "Indian GSTIN must be 15 characters (e.g. 12ABCDE3456FGZH)",
+        TaxIdType::Other => "Tax ID must be 1-64 letters, digits, dots, dashes or slashes",
+    }
+}
+
+/// Normalize and validate a tax ID value for its type
+///
+/// Strips whitespace (and separators where the format has none), uppercases,
+/// and checks the format Stripe expects. Returns the value to store and send
+/// to Stripe, or `InvalidTaxId` describing the expected format.
+pub fn normalize_tax_id(tax_id_type: TaxIdType, value: &str) -> BillingResult<String> {
+    let compact: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    let stripped: String = compact
+        .chars()
+        .filter(|c| !matches!(c, '.' | '-' | '/'))
+        .collect();
+    let invalid = || BillingError::InvalidTaxId(tax_id_format(tax_id_type).to_string());
+    // Every format is ASCII; checking first keeps the byte slicing below safe
+    if !compact.is_ascii() {
+        return Err(invalid());
+    }
+
+    match tax_id_type {
+        TaxIdType::EuVat => {
+            let (prefix, number) = stripped.split_at(stripped.len().min(2));
+            if EU_VAT_PREFIXES.contains(&prefix)
+                && (2..=12).contains(&number.len())
+                && all_alphanumeric(number)
+            {
+                Ok(stripped)
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::GbVat => {
+            let number = stripped.strip_prefix("GB").unwrap_or(&stripped);
+            let valid = (all_digits(number) && matches!(number.len(), 9 | 12))
+                || ((number.starts_with("GD") || number.starts_with("HA"))
+                    && number.len() == 5
+                    && all_digits(&number[2..]));
+            if valid {
+                Ok(format!("GB{}", number))
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::UsEin => {
+            if stripped.len() == 9 && all_digits(&stripped) {
+                Ok(format!("{}-{}", &stripped[..2], &stripped[2..]))
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::AuAbn => {
+            if stripped.len() == 11 && all_digits(&stripped) {
+                Ok(stripped)
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::CaGstHst => {
+            let valid = stripped.len() == 15
+                && all_digits(&stripped[..9])
+                && &stripped[9..11] == "RT"
+                && all_digits(&stripped[11..]);
+            if valid {
+                Ok(stripped)
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::BrCnpj => {
+            if stripped.len() == 14 && all_digits(&stripped) {
+                Ok(format!(
+                    "{}.{}.{}/{}-{}",
+                    &stripped[..2],
+                    &stripped[2..5],
+                    &stripped[5..8],
+                    &stripped[8..12],
+                    &stripped[12..]
+                ))
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::InGst => {
+            // State code, PAN (5 letters, 4 digits, letter), entity code, Z, check character
+            let b = stripped.as_bytes();
+            let valid = b.len() == 15
+                && b[..2].iter().all(u8::is_ascii_digit)
+                && b[2..7].iter().all(u8::is_ascii_uppercase)
+                && b[7..11].iter().all(u8::is_ascii_digit)
+                && b[11].is_ascii_uppercase()
+                && b[12].is_ascii_alphanumeric()
+                && b[13] == b'Z'
+                && b[14].is_ascii_alphanumeric();
+            if valid {
+                Ok(stripped)
+            } else {
+                Err(invalid())
+            }
+        }
+        TaxIdType::Other => {
+            let valid = (1..=64).contains(&compact.len())
+                && compact
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'/'));
+            if valid {
+                Ok(compact)
+            } else {
+                Err(invalid())
+            }
+        }
+    }
+}
+
 /// Stored tax ID for an organization
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 pub struct TaxId {
@@ -79,6 +226,92 @@ pub struct TaxId {
     pub updated_at: OffsetDateTime,
 }
 
+/// How tax is calculated
+///
+/// Configure with `BILLING_TAX_STRATEGY` (`stripe_tax` or `manual`). Manual
+/// rates come from `BILLING_TAX_MANUAL_RATES`, a comma-separated list of
+/// `JURISDICTION=PERCENT` entries keyed by country (`DE=19`) or
+/// country-region (`US-CA=8.25`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(tag = "type", content = "rates", rename_all = "snake_case")]
+pub enum TaxStrategy {
+    /// Calculate through the Stripe Tax API
+    #[default]
+    StripeTax,
+    /// Self-calculate from a percentage rate per jurisdiction
+    ManualRates(HashMap<String, f64>),
+}
+
+impl TaxStrategy {
+    /// Load the strategy from the environment, defaulting to `StripeTax`
+    ///
+    /// Malformed manual rate entries are skipped with a warning, so the
+    /// affected jurisdictions fail with `TaxRateMissing` rather than being
+    /// charged at a wrong rate.
+    pub fn from_env() -> Self {
+        match std::env::var("BILLING_TAX_STRATEGY") {
+            Ok(v) if v.trim().eq_ignore_ascii_case("manual") => {
+                let rates = std::env::var("BILLING_TAX_MANUAL_RATES").unwrap_or_default();
+                Self::ManualRates(parse_manual_rates(&rates))
+            }
+            Ok(v) if !v.trim().eq_ignore_ascii_case("stripe_tax") => {
+                tracing::warn!(
+                    strategy = %v,
+                    "Unknown BILLING_TAX_STRATEGY, using Stripe Tax"
+                );
+                Self::StripeTax
+            }
+            _ => Self::StripeTax,
+        }
+    }
+}
+
+/// Parse `JURISDICTION=PERCENT` entries, keyed by uppercased jurisdiction
+fn parse_manual_rates(value: &str) -> HashMap<String, f64> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry.split_once('=').and_then(|(jurisdiction, rate)| {
+                let rate: f64 = rate.trim().parse().ok()?;
+                let jurisdiction = jurisdiction.trim().to_uppercase();
+                ((0.0..=100.0).contains(&rate) && !jurisdiction.is_empty())
+                    .then_some((jurisdiction, rate))
+            });
+            if parsed.is_none() {
+                tracing::warn!(entry = %entry, "Ignoring invalid manual tax rate");
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Manual rate for a customer's location
+///
+/// A country-region rate (`US-CA`) takes precedence over the country rate
+/// (`US`). Returns the matched jurisdiction with its rate.
+fn manual_rate(
+    rates: &HashMap<String, f64>,
+    country: &str,
+    region: Option<&str>,
+) -> Option<(String, f64)> {
+    let country = country.trim().to_uppercase();
+    let regional = region
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(|r| format!("{}-{}", country, r.to_uppercase()));
+
+    regional
+        .into_iter()
+        .chain(std::iter::once(country))
+        .find_map(|jurisdiction| {
+            rates
+                .get(&jurisdiction)
+                .map(|&rate| (jurisdiction.clone(), rate))
+        })
+}
+
 /// Tax configuration for an organization
 #[derive(Debug, Clone, Serialize)]
 pub struct TaxConfig {
@@ -87,6 +320,8 @@ pub struct TaxConfig {
     pub tax_ids: Vec<TaxId>,
     pub billing_country: Option<String>,
     pub billing_postal_code: Option<String>,
+    pub billing_state: Option<String>,
+    pub strategy: TaxStrategy,
 }
 
 /// Tax summary for reporting
@@ -101,26 +336,223 @@ pub struct TaxSummary {
 }
 
 /// Tax breakdown by jurisdiction
+///
+/// Amounts are in the currency's smallest unit (cents for USD, yen for JPY).
 #[derive(Debug, Clone, Serialize)]
 pub struct TaxBreakdown {
     pub jurisdiction: String,
+    /// Lowercase ISO currency code
+    pub currency: String,
     pub tax_rate_percent: f64,
     pub taxable_amount_cents: i64,
     pub tax_amount_cents: i64,
+    /// Per-line amounts, when calculated from line items
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub line_items: Vec<TaxLineItem>,
+}
+
+/// Tax on a single line item, in the currency's smallest unit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaxLineItem {
+    pub reference: String,
+    pub amount_cents: i64,
+    pub tax_amount_cents: i64,
+}
+
+impl TaxBreakdown {
+    /// Compute the breakdown for a set of line item amounts
+    ///
+    /// Tax is rounded per line and then summed, which is how Stripe computes
+    /// invoice tax, so the result reconciles with the charged amount.
+    pub fn compute(
+        jurisdiction: impl Into<String>,
+        currency: &str,
+        tax_rate_percent: f64,
+        line_amounts: &[i64],
+        rounding: TaxRounding,
+    ) -> Self {
+        let lines: Vec<(String, i64)> = line_amounts
+            .iter()
+            .enumerate()
+            .map(|(i, &amount)| (format!("line_{}", i), amount))
+            .collect();
+        Self::compute_lines(jurisdiction, currency, tax_rate_percent, &lines, rounding)
+    }
+
+    /// Compute the breakdown for `(reference, amount)` line items
+    fn compute_lines(
+        jurisdiction: impl Into<String>,
+        currency: &str,
+        tax_rate_percent: f64,
+        lines: &[(String, i64)],
+        rounding: TaxRounding,
+    ) -> Self {
+        let line_items: Vec<TaxLineItem> = lines
+            .iter()
+            .map(|(reference, amount)| TaxLineItem {
+                reference: reference.clone(),
+                amount_cents: *amount,
+                tax_amount_cents: compute_tax_amount(*amount, tax_rate_percent, currency, rounding),
+            })
+            .collect();
+
+        Self {
+            jurisdiction: jurisdiction.into(),
+            currency: currency.to_lowercase(),
+            tax_rate_percent,
+            taxable_amount_cents: line_items.iter().map(|l| l.amount_cents).sum(),
+            tax_amount_cents: line_items.iter().map(|l| l.tax_amount_cents).sum(),
+            line_items,
+        }
+    }
+}
+
+/// Currencies with no minor unit (amounts are whole units)
+///
+/// Matches Stripe's zero-decimal currency list.
+const ZERO_DECIMAL_CURRENCIES: &[&str] = &[
+    "bif", "clp", "djf", "gnf", "jpy", "kmf", "krw", "mga", "pyg", "rwf", "ugx", "vnd", "vuv",
+    "xaf", "xof", "xpf",
+];
+
+/// Currencies with three decimals; Stripe requires amounts rounded to a multiple of 10
+const THREE_DECIMAL_CURRENCIES: &[&str] = &["bhd", "jod", "kwd", "omr", "tnd"];
+
+/// Number of decimals in a currency's minor unit
+pub fn currency_decimals(currency: &str) -> u32 {
+    let currency = currency.to_lowercase();
+    if ZERO_DECIMAL_CURRENCIES.contains(&currency.as_str()) {
+        0
+    } else if THREE_DECIMAL_CURRENCIES.contains(&currency.as_str()) {
+        3
+    } else {
+        2
+    }
+}
+
+/// Smallest chargeable step, in minor units
+fn currency_rounding_increment(currency: &str) -> i64 {
+    if currency_decimals(currency) == 3 {
+        10
+    } else {
+        1
+    }
+}
+
+/// Rounding mode for computed tax amounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxRounding {
+    /// Round halves away from zero (Stripe's behavior)
+    #[default]
+    HalfUp,
+    /// Round halves to the nearest even step (banker's rounding)
+    HalfEven,
+}
+
+impl std::str::FromStr for TaxRounding {
+    type Err = BillingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "half_up" => Ok(Self::HalfUp),
+            "half_even" => Ok(Self::HalfEven),
+            other => Err(BillingError::Config(format!(
+                "Invalid tax rounding mode: {}",
+                other
+            ))),
+        }
+    }
+}
+
+/// Get configured tax rounding mode (`BILLING_TAX_ROUNDING`)
+pub fn get_tax_rounding() -> TaxRounding {
+    static ROUNDING: OnceLock<TaxRounding> = OnceLock::new();
+    *ROUNDING.get_or_init(|| {
+        std::env::var("BILLING_TAX_ROUNDING")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    })
+}
+
+/// Tax on `amount` (minor units) at `rate_percent`, rounded to the currency's smallest step
+///
+/// Uses integer math on the rate (to 1/10000 of a percent) so results do not
+/// drift with floating point error.
+pub fn compute_tax_amount(
+    amount: i64,
+    rate_percent: f64,
+    currency: &str,
+    rounding: TaxRounding,
+) -> i64 {
+    const RATE_SCALE: i128 = 10_000;
+
+    let rate = (rate_percent * RATE_SCALE as f64).round() as i128;
+    let increment = currency_rounding_increment(currency) as i128;
+    // tax = amount * rate / (100 * RATE_SCALE), expressed in rounding increments
+    let numerator = amount as i128 * rate;
+    let denominator = 100 * RATE_SCALE * increment;
+
+    let quotient = numerator / denominator;
+    let remainder = (numerator % denominator).abs();
+    let sign = if numerator < 0 { -1 } else { 1 };
+
+    let round_away = match (remainder * 2).cmp(&denominator) {
+        std::cmp::Ordering::Greater => true,
+        std::cmp::Ordering::Less => false,
+        std::cmp::Ordering::Equal => match rounding {
+            TaxRounding::HalfUp => true,
+            TaxRounding::HalfEven => quotient % 2 != 0,
+        },
+    };
+
+    let steps = if round_away {
+        quotient + sign
+    } else {
+        quotient
+    };
+    (steps * increment) as i64
 }
 
 /// Tax service for managing tax IDs and calculations
 pub struct TaxService {
     stripe: StripeClient,
     pool: PgPool,
+    strategy: TaxStrategy,
 }
 
 impl TaxService {
     pub fn new(stripe: StripeClient, pool: PgPool) -> Self {
-        Self { stripe, pool }
+        Self {
+            stripe,
+            pool,
+            strategy: TaxStrategy::from_env(),
+        }
+    }
+
+    /// Override the configured tax strategy
+    pub fn with_strategy(mut self, strategy: TaxStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Check a tax ID's value against the format for its type
+    ///
+    /// Whitespace and case differences are accepted; see `normalize_tax_id`
+    /// for the stored form.
+    pub fn validate_tax_id(tax_id: &TaxId) -> BillingResult<()> {
+        normalize_tax_id(
+            TaxIdType::from_stripe_type(&tax_id.tax_id_type),
+            &tax_id.tax_id_value,
+        )
+        .map(|_| ())
     }
 
     /// Add a tax ID for an organization
+    ///
+    /// The value is validated and normalized before anything is stored or
+    /// sent to Stripe.
     pub async fn add_tax_id(
         &self,
         org_id: Uuid,
@@ -128,6 +560,8 @@ impl TaxService {
         tax_id_value: &str,
         country: Option<&str>,
     ) -> BillingResult<TaxId> {
+        let tax_id_value = normalize_tax_id(tax_id_type, tax_id_value)?;
+
         // Get organization's Stripe customer ID
         let customer_id: Option<(String,)> =
             sqlx::query_as("SELECT stripe_customer_id FROM organizations WHERE id = $1")
@@ -164,7 +598,7 @@ impl TaxService {
         )
         .bind(org_id)
         .bind(tax_id_type.as_stripe_type())
-        .bind(tax_id_value)
+        .bind(&tax_id_value)
         .bind(&stripe_tax_id_str)
         .bind("pending")
         .bind(country)
@@ -252,16 +686,16 @@ impl TaxService {
         let tax_ids = self.get_tax_ids(org_id).await?;
 
         // Get organization tax exempt status and billing address
-        let org_info: Option<(bool, Option<String>, Option<String>)> = sqlx::query_as(
-            "SELECT COALESCE(tax_exempt, false), billing_country, billing_postal_code FROM organizations WHERE id = $1"
+        let org_info: Option<(bool, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+            "SELECT COALESCE(tax_exempt, false), billing_country, billing_postal_code, billing_state FROM organizations WHERE id = $1"
         )
         .bind(org_id)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| BillingError::Database(e.to_string()))?;
 
-        let (tax_exempt, billing_country, billing_postal_code) =
-            org_info.unwrap_or((false, None, None));
+        let (tax_exempt, billing_country, billing_postal_code, billing_state) =
+            org_info.unwrap_or((false, None, None, None));
 
         Ok(TaxConfig {
             org_id,
@@ -269,9 +703,134 @@ impl TaxService {
             tax_ids,
             billing_country,
             billing_postal_code,
+            billing_state,
+            strategy: self.strategy.clone(),
         })
     }
 
+    /// Calculate tax on `(reference, amount)` line items for an org
+    ///
+    /// Uses Stripe Tax or the manual rate for the org's billing country or
+    /// region, per `config.strategy`. Exempt orgs get a zero-rate breakdown.
+    /// With manual rates, a location without a configured rate returns
+    /// `TaxRateMissing` rather than silently charging no tax.
+    pub async fn calculate(
+        &self,
+        config: &TaxConfig,
+        currency: &str,
+        lines: &[(String, i64)],
+    ) -> BillingResult<TaxBreakdown> {
+        let rounding = get_tax_rounding();
+        if config.tax_exempt {
+            return Ok(TaxBreakdown::compute_lines(
+                "exempt", currency, 0.0, lines, rounding,
+            ));
+        }
+
+        match &config.strategy {
+            TaxStrategy::ManualRates(rates) => calculate_manual(config, rates, currency, lines),
+            TaxStrategy::StripeTax => self.calculate_stripe_tax(config, currency, lines).await,
+        }
+    }
+
+    /// Calculate through the Stripe Tax calculations API
+    async fn calculate_stripe_tax(
+        &self,
+        config: &TaxConfig,
+        currency: &str,
+        lines: &[(String, i64)],
+    ) -> BillingResult<TaxBreakdown> {
+        let country = config.billing_country.as_deref().ok_or_else(|| {
+            BillingError::InvalidInput("Billing country required for tax calculation".to_string())
+        })?;
+
+        let mut form_params = vec![
+            ("currency".to_string(), currency.to_lowercase()),
+            (
+                "customer_details[address][country]".to_string(),
+                country.to_string(),
+            ),
+            (
+                "customer_details[address_source]".to_string(),
+                "billing".to_string(),
+            ),
+        ];
+        if let Some(postal_code) = &config.billing_postal_code {
+            form_params.push((
+                "customer_details[address][postal_code]".to_string(),
+                postal_code.clone(),
+            ));
+        }
+        if let Some(state) = &config.billing_state {
+            form_params.push((
+                "customer_details[address][state]".to_string(),
+                state.clone(),
+            ));
+        }
+        for (i, (reference, amount)) in lines.iter().enumerate() {
+            form_params.push((format!("line_items[{}][amount]", i), amount.to_string()));
+            form_params.push((format!("line_items[{}][reference]", i), reference.clone()));
+        }
+
+        let calculation: StripeTaxCalculation = self
+            .stripe_tax_request(
+                reqwest::Method::POST,
+                "tax/calculations",
+                Some(&form_params),
+            )
+            .await?;
+        let line_items: StripeTaxLineItems = self
+            .stripe_tax_request(
+                reqwest::Method::GET,
+                &format!("tax/calculations/{}/line_items", calculation.id),
+                None,
+            )
+            .await?;
+
+        Ok(stripe_tax_breakdown(
+            config,
+            currency,
+            calculation,
+            line_items,
+        ))
+    }
+
+    /// Send a Stripe Tax REST request and decode the JSON response
+    async fn stripe_tax_request<T: serde::de::DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        form: Option<&[(String, String)]>,
+    ) -> BillingResult<T> {
+        let mut request = self.stripe.rest_request(method, path);
+        if let Some(form) = form {
+            request = request.form(form);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| BillingError::StripeApi(format!("Failed to call Stripe API: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_body = response.text().await.unwrap_or_default();
+            let error = BillingError::from_stripe_error_body(status.as_u16(), &error_body);
+            tracing::error!(
+                stripe_call = %path,
+                status = %status,
+                error = %error,
+                "Stripe Tax API failed"
+            );
+            return Err(error);
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| BillingError::StripeApi(format!("Invalid Stripe Tax response: {}", e)))
+    }
+
     /// Set tax exempt status for an organization
     pub async fn set_tax_exempt(&self, org_id: Uuid, exempt: bool) -> BillingResult<()> {
         // Update Stripe customer
@@ -391,10 +950,11 @@ impl TaxService {
         end_date: OffsetDateTime,
     ) -> BillingResult<TaxSummary> {
         // Query tax data from billing events
-        let tax_data: Vec<(String, i64, i64, f64)> = sqlx::query_as(
+        let tax_data: Vec<(String, String, i64, i64, f64)> = sqlx::query_as(
             r#"
             SELECT
                 COALESCE(event_data->>'tax_jurisdiction', 'default') as jurisdiction,
+                LOWER(COALESCE(event_data->>'currency', 'usd')) as currency,
                 COALESCE((event_data->>'taxable_amount_cents')::bigint, 0) as taxable_amount,
                 COALESCE((event_data->>'tax_amount_cents')::bigint, 0) as tax_amount,
                 COALESCE((event_data->>'tax_rate_percent')::float, 0) as tax_rate
@@ -413,22 +973,24 @@ impl TaxService {
         .await
         .unwrap_or_default();
 
-        let mut breakdown_map: std::collections::HashMap<String, TaxBreakdown> =
+        let mut breakdown_map: std::collections::HashMap<(String, String), TaxBreakdown> =
             std::collections::HashMap::new();
         let mut total_taxable = 0i64;
         let mut total_tax = 0i64;
 
-        for (jurisdiction, taxable, tax, rate) in tax_data {
+        for (jurisdiction, currency, taxable, tax, rate) in tax_data {
             total_taxable += taxable;
             total_tax += tax;
 
             let entry = breakdown_map
-                .entry(jurisdiction.clone())
+                .entry((jurisdiction.clone(), currency.clone()))
                 .or_insert(TaxBreakdown {
                     jurisdiction,
+                    currency,
                     tax_rate_percent: rate,
                     taxable_amount_cents: 0,
                     tax_amount_cents: 0,
+                    line_items: Vec::new(),
                 });
             entry.taxable_amount_cents += taxable;
             entry.tax_amount_cents += tax;
@@ -491,3 +1053,480 @@ impl TaxService {
         Ok(status)
     }
 }
+
+/// Calculate tax from the manual rate for the org's billing location
+fn calculate_manual(
+    config: &TaxConfig,
+    rates: &HashMap<String, f64>,
+    currency: &str,
+    lines: &[(String, i64)],
+) -> BillingResult<TaxBreakdown> {
+    let country = config
+        .billing_country
+        .as_deref()
+        .ok_or_else(|| BillingError::TaxRateMissing("unknown billing country".to_string()))?;
+
+    let (jurisdiction, rate) = manual_rate(rates, country, config.billing_state.as_deref())
+        .ok_or_else(|| {
+            BillingError::TaxRateMissing(match &config.billing_state {
+                Some(state) => format!("{}-{}", country.to_uppercase(), state.to_uppercase()),
+                None => country.to_uppercase(),
+            })
+        })?;
+
+    Ok(TaxBreakdown::compute_lines(
+        jurisdiction,
+        currency,
+        rate,
+        lines,
+        get_tax_rounding(),
+    ))
+}
+
+/// Stripe Tax calculation (only the fields we use)
+#[derive(Debug, Deserialize)]
+struct StripeTaxCalculation {
+    id: String,
+    tax_amount_exclusive: i64,
+    #[serde(default)]
+    tax_breakdown: Vec<StripeTaxBreakdownEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeTaxBreakdownEntry {
+    tax_rate_details: StripeTaxRateDetails,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeTaxRateDetails {
+    country: Option<String>,
+    state: Option<String>,
+    percentage_decimal: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeTaxLineItems {
+    data: Vec<StripeTaxLineItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeTaxLineItem {
+    amount: i64,
+    amount_tax: i64,
+    reference: Option<String>,
+}
+
+/// Convert a Stripe Tax calculation into our breakdown
+///
+/// The jurisdiction and rate come from the first breakdown entry; Stripe
+/// returns one per applicable tax, so combined taxes report the first rate
+/// while the amounts cover all of them.
+fn stripe_tax_breakdown(
+    config: &TaxConfig,
+    currency: &str,
+    calculation: StripeTaxCalculation,
+    line_items: StripeTaxLineItems,
+) -> TaxBreakdown {
+    let details = calculation
+        .tax_breakdown
+        .first()
+        .map(|b| &b.tax_rate_details);
+    let country = details
+        .and_then(|d| d.country.clone())
+        .or_else(|| config.billing_country.clone())
+        .unwrap_or_default()
+        .to_uppercase();
+    let jurisdiction = match details.and_then(|d| d.state.as_deref()) {
+        Some(state) => format!("{}-{}", country, state.to_uppercase()),
+        None => country,
+    };
+    let tax_rate_percent = details
+        .and_then(|d| d.percentage_decimal.parse().ok())
+        .unwrap_or(0.0);
+
+    let line_items: Vec<TaxLineItem> = line_items
+        .data
+        .into_iter()
+        .map(|line| TaxLineItem {
+            reference: line.reference.unwrap_or_default(),
+            amount_cents: line.amount,
+            tax_amount_cents: line.amount_tax,
+        })
+        .collect();
+
+    TaxBreakdown {
+        jurisdiction,
+        currency: currency.to_lowercase(),
+        tax_rate_percent,
+        taxable_amount_cents: line_items.iter().map(|l| l.amount_cents).sum(),
+        tax_amount_cents: calculation.tax_amount_exclusive,
+        line_items,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_decimals() {
+        assert_eq!(currency_decimals("usd"), 2);
+        assert_eq!(currency_decimals("EUR"), 2);
+        assert_eq!(currency_decimals("jpy"), 0);
+        assert_eq!(currency_decimals("KRW"), 0);
+        assert_eq!(currency_decimals("kwd"), 3);
+    }
+
+    #[test]
+    fn test_usd_tax_rounds_to_cent() {
+        // $10.00 at 8.25% = 82.5 cents -> 83
+        assert_eq!(
+            compute_tax_amount(1000, 8.25, "usd", TaxRounding::HalfUp),
+            83
+        );
+        assert_eq!(
+            compute_tax_amount(1000, 8.25, "usd", TaxRounding::HalfEven),
+            82
+        );
+        // $29.00 at 20% is exact
+        assert_eq!(
+            compute_tax_amount(2900, 20.0, "usd", TaxRounding::HalfUp),
+            580
+        );
+        // 0.07 is not exactly representable as f64; integer math keeps it exact
+        assert_eq!(
+            compute_tax_amount(5000, 7.0, "usd", TaxRounding::HalfUp),
+            350
+        );
+    }
+
+    #[test]
+    fn test_zero_decimal_currency_rounds_to_whole_unit() {
+        // ¥1,234 at 10% = ¥123.4 -> ¥123
+        assert_eq!(
+            compute_tax_amount(1234, 10.0, "jpy", TaxRounding::HalfUp),
+            123
+        );
+        // ¥125 at 10% = ¥12.5 -> ¥13 (half up) / ¥12 (half even)
+        assert_eq!(
+            compute_tax_amount(125, 10.0, "jpy", TaxRounding::HalfUp),
+            13
+        );
+        assert_eq!(
+            compute_tax_amount(125, 10.0, "JPY", TaxRounding::HalfEven),
+            12
+        );
+    }
+
+    #[test]
+    fn test_three_decimal_currency_rounds_to_ten() {
+        // 1.234 KWD at 5% = 61.7 fils -> 60 (multiple of 10)
+        assert_eq!(
+            compute_tax_amount(1234, 5.0, "kwd", TaxRounding::HalfUp),
+            60
+        );
+    }
+
+    #[test]
+    fn test_negative_amounts_round_symmetrically() {
+        assert_eq!(
+            compute_tax_amount(-1000, 8.25, "usd", TaxRounding::HalfUp),
+            -83
+        );
+    }
+
+    #[test]
+    fn test_breakdown_rounds_per_line_like_stripe() {
+        // Three $3.33 lines at 8.25%: 27.47 cents each -> 27 * 3 = 81,
+        // whereas rounding the $9.99 total would give 82
+        let breakdown =
+            TaxBreakdown::compute("US-CA", "USD", 8.25, &[333, 333, 333], TaxRounding::HalfUp);
+        assert_eq!(breakdown.taxable_amount_cents, 999);
+        assert_eq!(breakdown.tax_amount_cents, 81);
+        assert_eq!(breakdown.currency, "usd");
+        assert_eq!(
+            compute_tax_amount(999, 8.25, "usd", TaxRounding::HalfUp),
+            82
+        );
+    }
+
+    #[test]
+    fn test_tax_rounding_from_str() {
+        assert_eq!(
+            "half_up".parse::<TaxRounding>().unwrap(),
+            TaxRounding::HalfUp
+        );
+        assert_eq!(
+            " HALF_EVEN ".parse::<TaxRounding>().unwrap(),
+            TaxRounding::HalfEven
+        );
+        assert!("bankers".parse::<TaxRounding>().is_err());
+    }
+
+    fn config(country: Option<&str>, state: Option<&str>) -> TaxConfig {
+        TaxConfig {
+            org_id: Uuid::new_v4(),
+            tax_exempt: false,
+            tax_ids: Vec::new(),
+            billing_country: country.map(str::to_string),
+            billing_postal_code: None,
+            billing_state: state.map(str::to_string),
+            strategy: TaxStrategy::ManualRates(parse_manual_rates("de=19, US-CA=8.25, US=0")),
+        }
+    }
+
+    fn lines() -> Vec<(String, i64)> {
+        vec![("pro".to_string(), 2900), ("overage".to_string(), 333)]
+    }
+
+    #[test]
+    fn test_parse_manual_rates() {
+        let rates = parse_manual_rates(" DE=19 ,us-ca=8.25,,FR=abc,XX=150,=5");
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates["DE"], 19.0);
+        assert_eq!(rates["US-CA"], 8.25);
+    }
+
+    #[test]
+    fn test_manual_rate_prefers_region() {
+        let rates = parse_manual_rates("US-CA=8.25,US=0");
+        assert_eq!(
+            manual_rate(&rates, "us", Some("ca")),
+            Some(("US-CA".to_string(), 8.25))
+        );
+        assert_eq!(
+            manual_rate(&rates, "US", Some("NY")),
+            Some(("US".to_string(), 0.0))
+        );
+        assert_eq!(
+            manual_rate(&rates, "US", None),
+            Some(("US".to_string(), 0.0))
+        );
+        assert_eq!(manual_rate(&rates, "DE", None), None);
+    }
+
+    #[test]
+    fn test_calculate_manual_returns_line_items() {
+        let config = config(Some("DE"), None);
+        let TaxStrategy::ManualRates(rates) = &config.strategy else {
+            unreachable!()
+        };
+
+        let breakdown = calculate_manual(&config, rates, "EUR", &lines()).unwrap();
+        assert_eq!(breakdown.jurisdiction, "DE");
+        assert_eq!(breakdown.tax_rate_percent, 19.0);
+        assert_eq!(breakdown.taxable_amount_cents, 3233);
+        // 2900 * 19% = 551, 333 * 19% = 63.27 -> 63
+        assert_eq!(
+            breakdown.line_items,
+            vec![
+                TaxLineItem {
+                    reference: "pro".to_string(),
+                    amount_cents: 2900,
+                    tax_amount_cents: 551,
+                },
+                TaxLineItem {
+                    reference: "overage".to_string(),
+                    amount_cents: 333,
+                    tax_amount_cents: 63,
+                },
+            ]
+        );
+        assert_eq!(breakdown.tax_amount_cents, 614);
+    }
+
+    #[test]
+    fn test_calculate_manual_requires_rate_for_location() {
+        for (country, state, missing) in [
+            (Some("FR"), None, "FR"),
+            (Some("ca"), Some("on"), "CA-ON"),
+            (None, None, "unknown billing country"),
+        ] {
+            let config = config(country, state);
+            let TaxStrategy::ManualRates(rates) = &config.strategy else {
+                unreachable!()
+            };
+            match calculate_manual(&config, rates, "usd", &lines()) {
+                Err(BillingError::TaxRateMissing(jurisdiction)) => {
+                    assert_eq!(jurisdiction, missing)
+                }
+                other => panic!("expected TaxRateMissing, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_stripe_tax_breakdown_keeps_line_items() {
+        let calculation: StripeTaxCalculation = serde_json::from_value(serde_json::json!({
+            "id": "taxcalc_123",
+            "tax_amount_exclusive": 266,
+            "tax_breakdown": [{
+                "amount": 266,
+                "tax_rate_details": {
+                    "country": "US",
+                    "state": "CA",
+                    "percentage_decimal": "8.25",
+                    "tax_type": "sales_tax"
+                }
+            }]
+        }))
+        .unwrap();
+        let line_items: StripeTaxLineItems = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [
+                {"amount": 2900, "amount_tax": 239, "reference": "pro"},
+                {"amount": 333, "amount_tax": 27, "reference": "overage"}
+            ]
+        }))
+        .unwrap();
+
+        let breakdown = stripe_tax_breakdown(
+            &config(Some("US"), Some("CA")),
+            "USD",
+            calculation,
+            line_items,
+        );
+        assert_eq!(breakdown.jurisdiction, "US-CA");
+        assert_eq!(breakdown.tax_rate_percent, 8.25);
+        assert_eq!(breakdown.currency, "usd");
+        assert_eq!(breakdown.taxable_amount_cents, 3233);
+        assert_eq!(breakdown.tax_amount_cents, 266);
+        assert_eq!(breakdown.line_items.len(), 2);
+        assert_eq!(breakdown.line_items[1].reference, "overage");
+        assert_eq!(breakdown.line_items[1].tax_amount_cents, 27);
+    }
+
+    fn stored_tax_id(tax_id_type: TaxIdType, value: &str) -> TaxId {
+        // Other has no Stripe type of its own (it maps to eu_vat)
+        let stored_type = match tax_id_type {
+            TaxIdType::Other => "other",
+            t => t.as_stripe_type(),
+        };
+        TaxId {
+            id: Uuid::new_v4(),
+            org_id: Uuid::new_v4(),
+            tax_id_type: stored_type.to_string(),
+            tax_id_value: value.to_string(),
+            stripe_tax_id: None,
+            verification_status: "pending".to_string(),
+            country: None,
+            created_at: OffsetDateTime::now_utc(),
+            updated_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_tax_id_per_type() {
+        let cases = [
+            (
+                TaxIdType::EuVat,
+                " de 123 456 789 ",
+                "DE123456789",
+                "XX123456789",
+            ),
+            (TaxIdType::GbVat, "gb 123 4567 89", "GB123456789", "GB12345"),
+            (TaxIdType::UsEin, "123456789", "12-3456789", "12-34567"),
+            (
+                TaxIdType::AuAbn,
+                "12 345 678 912",
+                "12345678912",
+                "1234567891A",
+            ),
+            (
+                TaxIdType::CaGstHst,
+                "123456789rt0002",
+                "123456789RT0002",
+                "123456789XX0002",
+            ),
+            (
+                TaxIdType::BrCnpj,
+                "01234456543210",
+                "01.234.456/5432-10",
+                "0123445654321",
+            ),
+            (
+                TaxIdType::InGst,
+                "12abcde3456fgzh",
+                "12ABCDE3456FGZH",
+                "12ABCDE3456FGXH",
+            ),
+            (TaxIdType::Other, " abc-123 ", "ABC-123", "abc_123"),
+        ];
+
+        for (tax_id_type, valid, normalized, invalid) in cases {
+            assert_eq!(
+                normalize_tax_id(tax_id_type, valid).unwrap(),
+                normalized,
+                "{:?}",
+                tax_id_type
+            );
+            assert!(
+                TaxService::validate_tax_id(&stored_tax_id(tax_id_type, valid)).is_ok(),
+                "{:?}",
+                tax_id_type
+            );
+            assert!(
+                matches!(
+                    TaxService::validate_tax_id(&stored_tax_id(tax_id_type, invalid)),
+                    Err(BillingError::InvalidTaxId(_))
+                ),
+                "{:?} accepted {}",
+                tax_id_type,
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_tax_id_names_expected_format() {
+        let err = normalize_tax_id(TaxIdType::UsEin, "12-345").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid tax ID: US EIN must be 9 digits (e.g. 12-3456789)"
+        );
+        // Normalizing is idempotent, so stored values still validate
+        for (tax_id_type, value) in [
+            (TaxIdType::GbVat, "GB123456789"),
+            (TaxIdType::GbVat, "GBGD001"),
+            (TaxIdType::EuVat, "EL123456789"),
+            (TaxIdType::UsEin, "12-3456789"),
+            (TaxIdType::BrCnpj, "01.234.456/5432-10"),
+        ] {
+            assert_eq!(normalize_tax_id(tax_id_type, value).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_tax_id_is_rejected() {
+        let types = [
+            TaxIdType::EuVat,
+            TaxIdType::GbVat,
+            TaxIdType::UsEin,
+            TaxIdType::AuAbn,
+            TaxIdType::CaGstHst,
+            TaxIdType::BrCnpj,
+            TaxIdType::InGst,
+            TaxIdType::Other,
+        ];
+        // Multi-byte characters where the formats slice by position
+        for value in ["DÉ123", "É", "12345678ÉRT0002", "１２３４５６７８９"] {
+            for tax_id_type in types {
+                assert!(
+                    matches!(
+                        normalize_tax_id(tax_id_type, value),
+                        Err(BillingError::InvalidTaxId(_))
+                    ),
+                    "{:?} accepted {}",
+                    tax_id_type,
+                    value
+                );
+            }
+        }
+        assert_eq!(
+            normalize_tax_id(TaxIdType::EuVat, "DÉ123")
+                .unwrap_err()
+                .to_string(),
+            format!("Invalid tax ID: {}", tax_id_format(TaxIdType::EuVat))
+        );
+    }
+}
@@ -0,0 +1,504 @@
+//! Payment method enforcement for trials granted without one
+//!
+//! `admin_change_tier` can start a trial for an org that has no payment
+//! method on file; such subscriptions get `trial_payment_required_since`.
+//! Instead of waiting for Stripe's `trial_will_end`, the worker re-checks
+//! these trials hourly and escalates while the payment method is still
+//! missing: first the owner is emailed, later the org is blocked through
+//! `organizations.billing_blocked_at`. Adding a payment method clears the
+//! flag and lifts the block.
+//!
+//! ## Configuration
+//!
+//! - `TRIAL_PAYMENT_REMINDER_DAYS`: days into the trial before the owner is
+//!   reminded (default: 3)
+//! - `TRIAL_PAYMENT_BLOCK_DAYS`: days into the trial before trial access is
+//!   blocked (default: 7, never earlier than the reminder)
+
+use std::future::Future;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use sqlx::PgPool;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::customer::CustomerService;
+use crate::db::{commit_or_rollback, PgTx};
+use crate::error::BillingResult;
+
+/// Block reason stored on orgs blocked for a missing trial payment method
+///
+/// Grace period enforcement leaves blocks with this reason alone, since
+/// they are lifted by adding a payment method rather than by paying.
+pub const TRIAL_PAYMENT_BLOCK_REASON: &str = "Trial requires a payment method";
+
+/// Default days into the trial before the owner is reminded
+const DEFAULT_REMINDER_DAYS: i64 = 3;
+
+/// Default days into the trial before trial access is blocked
+const DEFAULT_BLOCK_DAYS: i64 = 7;
+
+/// When a payment-less trial escalates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrialPaymentSchedule {
+    /// Time into the trial before the owner is emailed
+    pub remind_after: Duration,
+    /// Time into the trial before trial access is blocked
+    pub block_after: Duration,
+}
+
+/// What enforcement does to a payment-less trial
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrialPaymentEscalation {
+    /// Email the owner to add a payment method
+    Remind,
+    /// Block the org until a payment method is added
+    Block,
+}
+
+impl TrialPaymentSchedule {
+    /// Build a schedule, keeping the block no earlier than the reminder
+    pub fn new(remind_after: Duration, block_after: Duration) -> Self {
+        Self {
+            remind_after,
+            block_after: block_after.max(remind_after),
+        }
+    }
+
+    /// The escalation due for a trial still missing a payment method, if any
+    pub fn escalation(
+        &self,
+        required_since: OffsetDateTime,
+        reminded: bool,
+        blocked: bool,
+        now: OffsetDateTime,
+    ) -> Option<TrialPaymentEscalation> {
+        let elapsed = now - required_since;
+        if blocked {
+            None
+        } else if elapsed >= self.block_after {
+            Some(TrialPaymentEscalation::Block)
+        } else if elapsed >= self.remind_after && !reminded {
+            Some(TrialPaymentEscalation::Remind)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get configured escalation schedule for payment-less trials
+pub fn get_trial_payment_schedule() -> TrialPaymentSchedule {
+    static SCHEDULE: OnceLock<TrialPaymentSchedule> = OnceLock::new();
+    *SCHEDULE.get_or_init(|| {
+        let days = |var: &str, default: i64| {
+            std::env::var(var)
+                .ok()
+                .and_then(|v| v.trim().parse::<i64>().ok())
+                .filter(|d| (0..=365).contains(d))
+                .unwrap_or(default)
+        };
+        TrialPaymentSchedule::new(
+            Duration::days(days("TRIAL_PAYMENT_REMINDER_DAYS", DEFAULT_REMINDER_DAYS)),
+            Duration::days(days("TRIAL_PAYMENT_BLOCK_DAYS", DEFAULT_BLOCK_DAYS)),
+        )
+    })
+}
+
+/// What happened to one org during enforcement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrialPaymentAction {
+    /// Owner should be reminded to add a payment method
+    Remind,
+    /// Org was blocked
+    Block,
+    /// A payment method was added; the flag (and any block) was cleared
+    Resolved,
+}
+
+/// One org affected by enforcement
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TrialPaymentOrgAction {
+    pub org_id: Uuid,
+    pub org_name: String,
+    pub action: TrialPaymentAction,
+    /// When trial access is (or was) blocked without a payment method
+    pub block_at: OffsetDateTime,
+}
+
+/// Result of an enforcement run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TrialPaymentEnforcement {
+    pub actions: Vec<TrialPaymentOrgAction>,
+    /// Orgs whose payment method could not be checked or updated
+    pub errors: usize,
+}
+
+impl TrialPaymentEnforcement {
+    /// Orgs whose owner should be reminded
+    pub fn reminders(&self) -> impl Iterator<Item = &TrialPaymentOrgAction> {
+        self.actions
+            .iter()
+            .filter(|a| a.action == TrialPaymentAction::Remind)
+    }
+}
+
+/// Service for escalating trials granted without a payment method
+#[derive(Clone)]
+pub struct TrialPaymentService {
+    pool: PgPool,
+}
+
+impl TrialPaymentService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Flag a subscription whose trial was granted without a payment method
+    pub async fn require_payment_method(&self, stripe_subscription_id: &str) -> BillingResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET trial_payment_required_since = COALESCE(trial_payment_required_since, NOW())
+            WHERE stripe_subscription_id = $1
+            "#,
+        )
+        .bind(stripe_subscription_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Re-check flagged trials against Stripe and escalate the ones still missing a payment method
+    pub async fn enforce(
+        &self,
+        customers: &CustomerService,
+    ) -> BillingResult<TrialPaymentEnforcement> {
+        self.enforce_with(
+            |org_id| async move { customers.has_payment_method(org_id).await },
+            get_trial_payment_schedule(),
+            OffsetDateTime::now_utc(),
+        )
+        .await
+    }
+
+    /// `enforce` with the payment method check, schedule and clock supplied by the caller
+    async fn enforce_with<F, Fut>(
+        &self,
+        has_payment_method: F,
+        schedule: TrialPaymentSchedule,
+        now: OffsetDateTime,
+    ) -> BillingResult<TrialPaymentEnforcement>
+    where
+        F: Fn(Uuid) -> Fut,
+        Fut: Future<Output = BillingResult<bool>>,
+    {
+        let mut enforcement = TrialPaymentEnforcement::default();
+
+        // Blocked trials stay listed after the trial ends so adding a
+        // payment method still lifts the block
+        let flagged: Vec<(Uuid, String, OffsetDateTime, bool, bool, bool)> = sqlx::query_as(
+            r#"
+            SELECT
+                s.org_id,
+                o.name,
+                s.trial_payment_required_since,
+                s.trial_payment_reminded_at IS NOT NULL,
+                o.billing_block_reason IS NOT DISTINCT FROM $1 AND o.billing_blocked_at IS NOT NULL,
+                s.status = 'trialing'
+            FROM subscriptions s
+            JOIN organizations o ON o.id = s.org_id
+            WHERE s.trial_payment_required_since IS NOT NULL
+            "#,
+        )
+        .bind(TRIAL_PAYMENT_BLOCK_REASON)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for (org_id, org_name, required_since, reminded, blocked, trialing) in flagged {
+            let block_at = required_since + schedule.block_after;
+            let has_pm = match has_payment_method(org_id).await {
+                Ok(has_pm) => has_pm,
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to check payment method for trial"
+                    );
+                    enforcement.errors += 1;
+                    continue;
+                }
+            };
+
+            let (action, result) = if has_pm {
+                let mut tx = self.pool.begin().await?;
+                let written = clear_requirement(&mut tx, org_id).await;
+                (
+                    TrialPaymentAction::Resolved,
+                    commit_or_rollback(tx, written).await,
+                )
+            } else if !trialing {
+                continue;
+            } else {
+                match schedule.escalation(required_since, reminded, blocked, now) {
+                    Some(TrialPaymentEscalation::Remind) => {
+                        (TrialPaymentAction::Remind, self.mark_reminded(org_id).await)
+                    }
+                    Some(TrialPaymentEscalation::Block) => {
+                        (TrialPaymentAction::Block, self.block(org_id).await)
+                    }
+                    None => continue,
+                }
+            };
+
+            match result {
+                Ok(true) => {
+                    tracing::info!(
+                        org_id = %org_id,
+                        org_name = %org_name,
+                        action = ?action,
+                        "Trial payment method enforcement"
+                    );
+                    enforcement.actions.push(TrialPaymentOrgAction {
+                        org_id,
+                        org_name,
+                        action,
+                        block_at,
+                    });
+                }
+                // Handled concurrently by someone else
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        action = ?action,
+                        error = %e,
+                        "Failed to enforce trial payment method"
+                    );
+                    enforcement.errors += 1;
+                }
+            }
+        }
+
+        tracing::info!(
+            actions = enforcement.actions.len(),
+            errors = enforcement.errors,
+            "Trial payment method enforcement complete"
+        );
+
+        Ok(enforcement)
+    }
+
+    /// Record the reminder; false if another run already sent it
+    async fn mark_reminded(&self, org_id: Uuid) -> BillingResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE subscriptions
+            SET trial_payment_reminded_at = NOW()
+            WHERE org_id = $1
+              AND trial_payment_required_since IS NOT NULL
+              AND trial_payment_reminded_at IS NULL
+            "#,
+        )
+        .bind(org_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Block the org; false if it was already blocked for any reason
+    async fn block(&self, org_id: Uuid) -> BillingResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE organizations
+            SET billing_blocked_at = NOW(),
+                billing_block_reason = $2
+            WHERE id = $1
+              AND billing_blocked_at IS NULL
+            "#,
+        )
+        .bind(org_id)
+        .bind(TRIAL_PAYMENT_BLOCK_REASON)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+/// Clear the trial's payment method flag and lift a block placed for it
+async fn clear_requirement(tx: &mut PgTx, org_id: Uuid) -> BillingResult<bool> {
+    let cleared = sqlx::query(
+        r#"
+        UPDATE subscriptions
+        SET trial_payment_required_since = NULL,
+            trial_payment_reminded_at = NULL
+        WHERE org_id = $1
+          AND trial_payment_required_since IS NOT NULL
+        "#,
+    )
+    .bind(org_id)
+    .execute(&mut **tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    sqlx::query(
+        r#"
+        UPDATE organizations
+        SET billing_blocked_at = NULL,
+            billing_block_reason = NULL
+        WHERE id = $1
+          AND billing_block_reason = $2
+        "#,
+    )
+    .bind(org_id)
+    .bind(TRIAL_PAYMENT_BLOCK_REASON)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(cleared)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> TrialPaymentSchedule {
+        TrialPaymentSchedule::new(Duration::days(3), Duration::days(7))
+    }
+
+    #[test]
+    fn test_escalation_schedule() {
+        let start = OffsetDateTime::now_utc();
+        let s = schedule();
+
+        assert_eq!(
+            s.escalation(start, false, false, start + Duration::days(1)),
+            None
+        );
+        assert_eq!(
+            s.escalation(start, false, false, start + Duration::days(3)),
+            Some(TrialPaymentEscalation::Remind)
+        );
+        // Reminded once only
+        assert_eq!(
+            s.escalation(start, true, false, start + Duration::days(5)),
+            None
+        );
+        assert_eq!(
+            s.escalation(start, true, false, start + Duration::days(7)),
+            Some(TrialPaymentEscalation::Block)
+        );
+        // A missed reminder doesn't hold back the block
+        assert_eq!(
+            s.escalation(start, false, false, start + Duration::days(8)),
+            Some(TrialPaymentEscalation::Block)
+        );
+        assert_eq!(
+            s.escalation(start, true, true, start + Duration::days(9)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_block_never_precedes_reminder() {
+        let s = TrialPaymentSchedule::new(Duration::days(5), Duration::days(2));
+        assert_eq!(s.block_after, Duration::days(5));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_paymentless_trial_escalates_then_resolves() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let service = TrialPaymentService::new(pool.clone());
+
+        let org_id = Uuid::new_v4();
+        let slug = format!("trial-{}", org_id.simple());
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Trial Org', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(&slug)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let start = OffsetDateTime::now_utc().replace_nanosecond(0).unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (org_id, stripe_subscription_id, status, trial_payment_required_since)
+            VALUES ($1, $2, 'trialing', $3)
+            "#,
+        )
+        .bind(org_id)
+        .bind(format!("sub_{}", org_id.simple()))
+        .bind(start)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let run = |days: i64, has_pm: bool| {
+            service.enforce_with(
+                move |_| async move { Ok(has_pm) },
+                schedule(),
+                start + Duration::days(days),
+            )
+        };
+        let actions_for = |enforcement: TrialPaymentEnforcement| -> Vec<TrialPaymentAction> {
+            enforcement
+                .actions
+                .into_iter()
+                .filter(|a| a.org_id == org_id)
+                .map(|a| a.action)
+                .collect()
+        };
+
+        assert!(actions_for(run(1, false).await.unwrap()).is_empty());
+        assert_eq!(
+            actions_for(run(3, false).await.unwrap()),
+            vec![TrialPaymentAction::Remind]
+        );
+        assert!(actions_for(run(4, false).await.unwrap()).is_empty());
+        assert_eq!(
+            actions_for(run(7, false).await.unwrap()),
+            vec![TrialPaymentAction::Block]
+        );
+        let reason: Option<String> =
+            sqlx::query_scalar("SELECT billing_block_reason FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(reason.as_deref(), Some(TRIAL_PAYMENT_BLOCK_REASON));
+
+        // Adding a payment method lifts the block
+        assert_eq!(
+            actions_for(run(8, true).await.unwrap()),
+            vec![TrialPaymentAction::Resolved]
+        );
+        let blocked: bool = sqlx::query_scalar(
+            "SELECT billing_blocked_at IS NOT NULL FROM organizations WHERE id = $1",
+        )
+        .bind(org_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(!blocked);
+
+        sqlx::query("DELETE FROM subscriptions WHERE org_id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
@@ -46,6 +46,48 @@ pub struct BillingPeriodUsage {
     pub is_over_limit: bool,
 }
 
+/// Bucket size for a usage time series
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Granularity {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// Postgres `date_trunc` unit (weeks start on Monday, all buckets in UTC)
+    fn as_pg_unit(self) -> &'static str {
+        match self {
+            Granularity::Hour => "hour",
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        }
+    }
+
+    /// Shortest possible bucket, used to bound the number of points
+    fn min_len(self) -> Duration {
+        match self {
+            Granularity::Hour => Duration::hours(1),
+            Granularity::Day => Duration::days(1),
+            Granularity::Week => Duration::weeks(1),
+            Granularity::Month => Duration::days(28),
+        }
+    }
+}
+
+/// Most buckets a single time series request may return
+const MAX_TIMESERIES_POINTS: i64 = 5_000;
+
+/// One bucket of a usage time series
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsagePoint {
+    pub bucket_start: OffsetDateTime,
+    pub requests: i64,
+}
+
 /// Usage metering service
 #[derive(Clone)]
 pub struct UsageMeter {
@@ -459,6 +501,70 @@ impl UsageMeter {
             .collect())
     }
 
+    /// Request counts bucketed by `granularity` between `from` and `to`
+    ///
+    /// Reads the hourly rollups in `usage_aggregates`. Every bucket from the
+    /// one containing `from` to the one containing `to` is returned, with
+    /// zero for buckets without usage, so charts need no gap filling.
+    pub async fn usage_timeseries(
+        &self,
+        org_id: Uuid,
+        granularity: Granularity,
+        from: OffsetDateTime,
+        to: OffsetDateTime,
+    ) -> BillingResult<Vec<UsagePoint>> {
+        if from > to {
+            return Err(BillingError::InvalidInput(
+                "Time series start must not be after its end".to_string(),
+            ));
+        }
+        if (to - from) / granularity.min_len() > MAX_TIMESERIES_POINTS as f64 {
+            return Err(BillingError::InvalidInput(format!(
+                "Time series would exceed {} points; use a coarser granularity",
+                MAX_TIMESERIES_POINTS
+            )));
+        }
+
+        let points: Vec<(OffsetDateTime, i64)> = sqlx::query_as(
+            r#"
+            WITH usage AS (
+                SELECT
+                    date_trunc($2, period_hour AT TIME ZONE 'UTC') as bucket,
+                    SUM(total_requests) as requests
+                FROM usage_aggregates
+                WHERE org_id = $1
+                  AND period_hour >= $3
+                  AND period_hour <= $4
+                GROUP BY 1
+            )
+            SELECT
+                b.bucket AT TIME ZONE 'UTC',
+                COALESCE(u.requests, 0)::BIGINT
+            FROM generate_series(
+                date_trunc($2, $3 AT TIME ZONE 'UTC'),
+                date_trunc($2, $4 AT TIME ZONE 'UTC'),
+                ('1 ' || $2)::interval
+            ) AS b(bucket)
+            LEFT JOIN usage u ON u.bucket = b.bucket
+            ORDER BY b.bucket ASC
+            "#,
+        )
+        .bind(org_id)
+        .bind(granularity.as_pg_unit())
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(points
+            .into_iter()
+            .map(|(bucket_start, requests)| UsagePoint {
+                bucket_start,
+                requests,
+            })
+            .collect())
+    }
+
     /// Aggregate usage records into hourly rollups (run periodically)
     pub async fn aggregate_hourly(&self, org_id: Uuid, hour: OffsetDateTime) -> BillingResult<()> {
         let hour_time = time::Time::from_hms(hour.hour(), 0, 0)
@@ -521,6 +627,42 @@ impl UsageMeter {
         Ok(())
     }
 
+    /// Aggregate one org's usage since `since` into hourly rollups (on demand)
+    /// SOC 2 Note: Reads usage_records only; returns the number of usage_aggregates rows written
+    pub async fn aggregate_for_org(
+        &self,
+        org_id: Uuid,
+        since: OffsetDateTime,
+    ) -> BillingResult<u64> {
+        let hours: Vec<(OffsetDateTime,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT date_trunc('hour', period_start) as hour
+            FROM usage_records
+            WHERE org_id = $1
+              AND period_start >= date_trunc('hour', $2::timestamptz)
+            ORDER BY hour
+            "#,
+        )
+        .bind(org_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut written = 0u64;
+        for (hour,) in hours {
+            self.aggregate_hourly(org_id, hour).await?;
+            written += 1;
+        }
+
+        tracing::info!(
+            org_id = %org_id,
+            since = %since,
+            aggregated_count = written,
+            "Completed on-demand usage aggregation"
+        );
+        Ok(written)
+    }
+
     /// Aggregate all recent usage for all orgs (run hourly by background task)
     /// SOC 2 Note: This populates analytics aggregates only, does not modify usage_records audit trail
     pub async fn aggregate_all_recent(&self) -> BillingResult<usize> {
@@ -594,4 +736,69 @@ mod tests {
         assert_eq!(SubscriptionTier::Team.monthly_requests(), 250_000);
         assert_eq!(SubscriptionTier::Enterprise.monthly_requests(), u64::MAX);
     }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_usage_timeseries_fills_empty_buckets() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let meter = UsageMeter::new(pool.clone());
+
+        let org_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO organizations (id, name, slug, subscription_tier) VALUES ($1, 'Usage Org', $2, 'pro')",
+        )
+        .bind(org_id)
+        .bind(format!("usage-{}", org_id.simple()))
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Monday 2026-01-05 00:00 UTC
+        let day = OffsetDateTime::from_unix_timestamp(1_767_571_200).unwrap();
+        for (hour, requests) in [
+            (day + Duration::hours(3), 10i64),
+            (day + Duration::hours(30), 5),
+            (day + Duration::days(2), 7),
+        ] {
+            sqlx::query(
+                "INSERT INTO usage_aggregates (id, org_id, period_hour, total_requests) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(org_id)
+            .bind(hour)
+            .bind(requests)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let daily = meter
+            .usage_timeseries(org_id, Granularity::Day, day, day + Duration::days(4))
+            .await
+            .unwrap();
+        let requests: Vec<i64> = daily.iter().map(|p| p.requests).collect();
+        assert_eq!(requests, vec![10, 5, 7, 0, 0]);
+        assert_eq!(daily[0].bucket_start, day);
+
+        let weekly = meter
+            .usage_timeseries(org_id, Granularity::Week, day, day + Duration::days(4))
+            .await
+            .unwrap();
+        assert_eq!(weekly.len(), 1);
+        assert_eq!(weekly[0].requests, 22);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_granularity_serialization() {
+        assert_eq!(
+            serde_json::from_value::<Granularity>(serde_json::json!("week")).unwrap(),
+            Granularity::Week
+        );
+    }
 }
@@ -3,42 +3,620 @@
 //! Handles Stripe events for subscriptions, invoices, and billing updates.
 //! Also handles instant charges, early payments, and spend cap unpause.
 
+use std::collections::HashMap;
+
 use hmac::{Hmac, Mac};
+use plexmcp_shared::webhook_queue::retry_backoff;
+use plexmcp_shared::SubscriptionTier;
 use sha2::Sha256;
 use sqlx::PgPool;
-use stripe::{Event, EventObject, EventType, Invoice, Subscription, Webhook};
+use stripe::{
+    CheckoutSession, DisputeStatus, Event, EventObject, EventType, Invoice, ListWebhookEndpoints,
+    Subscription, Webhook, WebhookEndpoint, WebhookEndpointStatus,
+};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+use crate::account_status::{get_invoice_grace_config, grace_period_days_for_tier};
+use crate::addons::AddonService;
+use crate::checkout::{mark_first_month_free, BillingInterval};
 use crate::client::StripeClient;
 use crate::email::BillingEmailService;
 use crate::error::{BillingError, BillingResult};
 use crate::events::{ActorType, BillingEventBuilder, BillingEventLogger, BillingEventType};
+use crate::grace_period::GracePeriodService;
 use crate::instant_charge::InstantChargeService;
-use crate::member_suspension::MemberSuspensionService;
+use crate::member_suspension::{get_suspension_grace_period, MemberSuspensionService};
 use crate::overage::OverageService;
+use crate::owners::{get_primary_owner, list_org_owners};
 use crate::spend_cap::SpendCapService;
-use crate::subscriptions::SubscriptionService;
+use crate::subscriptions::{subscription_period, SubscriptionKind, SubscriptionService};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Webhook handling configuration
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Stripe event types that trigger member (seat) reconciliation.
+    /// `invoice.paid` reconciles after a scheduled downgrade is processed;
+    /// `customer.subscription.updated` reconciles as soon as the plan changes.
+    pub member_reconciliation_events: Vec<EventType>,
+    /// Send critical alerts (disputes) to every owner instead of only the primary owner
+    pub notify_all_owners_on_critical: bool,
+    /// Minimum expected total (cents) for an upcoming-invoice email when there
+    /// are no pending overages. Overages always trigger the email.
+    pub upcoming_invoice_min_cents: i64,
+    /// Per-org overrides of `StripeConfig::webhook_tolerance_secs`
+    pub org_timestamp_tolerance_secs: HashMap<Uuid, i64>,
+    /// Clock skew (seconds) above which a warning is logged, even when the
+    /// webhook is still within tolerance
+    pub clock_skew_warning_secs: i64,
+    /// Downgrade the org to free when its Stripe customer is deleted.
+    /// Disable for orgs billed outside Stripe, whose tier must survive a
+    /// customer cleanup.
+    pub downgrade_on_customer_deleted: bool,
+}
+
+/// Minutes after which an event stuck in `processing` may be claimed again
+const PROCESSING_TIMEOUT_MINUTES: i32 = 30;
+
+/// Default minimum upcoming-invoice total that warrants an email ($10.00)
+pub const DEFAULT_UPCOMING_INVOICE_MIN_CENTS: i64 = 1000;
+
+/// Stripe payment attempt from which a failed invoice is treated as final
+const FINAL_PAYMENT_ATTEMPT: i32 = 4;
+
+/// Default webhook timestamp tolerance (5 minutes, matching Stripe's libraries)
+pub const DEFAULT_TIMESTAMP_TOLERANCE_SECS: i64 = 300;
+
+/// Default clock skew that triggers a drift warning
+pub const DEFAULT_CLOCK_SKEW_WARNING_SECS: i64 = 60;
+
+/// Result of comparing a webhook timestamp against our clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSkewCheck {
+    /// Skew within the soft warning threshold
+    Ok,
+    /// Accepted, but skew exceeds the soft warning threshold
+    Warning,
+    /// Skew exceeds the tolerance; the webhook must be rejected
+    Rejected,
+}
+
+/// Observed clock skew in seconds: positive when the webhook was signed in our past
+pub fn clock_skew_secs(now: i64, signed_at: i64) -> i64 {
+    now - signed_at
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            member_reconciliation_events: vec![
+                EventType::InvoicePaid,
+                EventType::CustomerSubscriptionUpdated,
+            ],
+            notify_all_owners_on_critical: false,
+            upcoming_invoice_min_cents: DEFAULT_UPCOMING_INVOICE_MIN_CENTS,
+            org_timestamp_tolerance_secs: HashMap::new(),
+            clock_skew_warning_secs: DEFAULT_CLOCK_SKEW_WARNING_SECS,
+            downgrade_on_customer_deleted: true,
+        }
+    }
+}
+
+impl WebhookConfig {
+    /// Create config from environment variables
+    ///
+    /// `WEBHOOK_MEMBER_RECONCILIATION_EVENTS` is a comma-separated list of Stripe
+    /// event names (e.g. `invoice.paid,customer.subscription.updated`).
+    /// Unknown names are ignored; an unset variable keeps the defaults.
+    ///
+    /// `WEBHOOK_NOTIFY_ALL_OWNERS=true` sends critical alerts to every owner.
+    ///
+    /// `WEBHOOK_UPCOMING_INVOICE_MIN_CENTS` sets the upcoming-invoice email threshold.
+    ///
+    /// `WEBHOOK_CLOCK_SKEW_WARNING_SECS` sets the drift warning threshold;
+    /// `WEBHOOK_ORG_TIMESTAMP_TOLERANCE` holds per-org overrides of the
+    /// timestamp tolerance as `<org_id>=<secs>` pairs separated by commas.
+    ///
+    /// `WEBHOOK_CUSTOMER_DELETED_DOWNGRADE=false` keeps the org's tier when its
+    /// Stripe customer is deleted.
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        if let Ok(events) = std::env::var("WEBHOOK_MEMBER_RECONCILIATION_EVENTS") {
+            config.member_reconciliation_events = parse_event_types(&events);
+        }
+        if let Ok(value) = std::env::var("WEBHOOK_NOTIFY_ALL_OWNERS") {
+            config.notify_all_owners_on_critical = value.trim().eq_ignore_ascii_case("true");
+        }
+        if let Some(min_cents) = std::env::var("WEBHOOK_UPCOMING_INVOICE_MIN_CENTS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+        {
+            config.upcoming_invoice_min_cents = min_cents.max(0);
+        }
+        if let Some(secs) = std::env::var("WEBHOOK_CLOCK_SKEW_WARNING_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+        {
+            config.clock_skew_warning_secs = secs.max(0);
+        }
+        if let Ok(overrides) = std::env::var("WEBHOOK_ORG_TIMESTAMP_TOLERANCE") {
+            config.org_timestamp_tolerance_secs = parse_org_tolerances(&overrides);
+        }
+        if let Ok(value) = std::env::var("WEBHOOK_CUSTOMER_DELETED_DOWNGRADE") {
+            config.downgrade_on_customer_deleted = !value.trim().eq_ignore_ascii_case("false");
+        }
+        config
+    }
+
+    /// Whether the given event type should trigger member reconciliation
+    pub fn reconciles_members_on(&self, event_type: EventType) -> bool {
+        self.member_reconciliation_events.contains(&event_type)
+    }
+
+    /// Timestamp tolerance for an org, falling back to `default_secs`
+    pub fn timestamp_tolerance_for(&self, org_id: Option<Uuid>, default_secs: i64) -> i64 {
+        org_id
+            .and_then(|id| self.org_timestamp_tolerance_secs.get(&id).copied())
+            .unwrap_or(default_secs)
+    }
+
+    /// Classify an observed clock skew against a timestamp tolerance
+    pub fn check_clock_skew(&self, skew_secs: i64, tolerance_secs: i64) -> ClockSkewCheck {
+        let skew = skew_secs.abs();
+        if skew > tolerance_secs {
+            ClockSkewCheck::Rejected
+        } else if skew > self.clock_skew_warning_secs {
+            ClockSkewCheck::Warning
+        } else {
+            ClockSkewCheck::Ok
+        }
+    }
+
+    /// Whether an upcoming invoice warrants a notification email
+    ///
+    /// Pending overages always notify; otherwise the expected total must
+    /// reach `upcoming_invoice_min_cents`.
+    pub fn should_notify_upcoming_invoice(
+        &self,
+        subscription_amount_cents: i64,
+        pending_overage_cents: i64,
+    ) -> bool {
+        if pending_overage_cents > 0 {
+            return true;
+        }
+        let total_expected = subscription_amount_cents + pending_overage_cents;
+        total_expected > 0 && total_expected >= self.upcoming_invoice_min_cents
+    }
+}
+
+/// Parse a comma-separated list of Stripe event names, skipping unknown ones
+fn parse_event_types(list: &str) -> Vec<EventType> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            match serde_json::from_value::<EventType>(serde_json::Value::String(name.to_string())) {
+                Ok(EventType::Unknown) | Err(_) => {
+                    tracing::warn!(event_type = %name, "Ignoring unknown Stripe event type in config");
+                    None
+                }
+                Ok(event_type) => Some(event_type),
+            }
+        })
+        .collect()
+}
+
+/// Parse `<org_id>=<secs>` pairs, skipping malformed entries
+fn parse_org_tolerances(list: &str) -> HashMap<Uuid, i64> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry.split_once('=').and_then(|(org, secs)| {
+                Some((
+                    org.trim().parse::<Uuid>().ok()?,
+                    secs.trim().parse::<i64>().ok()?.max(0),
+                ))
+            });
+            if parsed.is_none() {
+                tracing::warn!(entry = %entry, "Ignoring malformed webhook tolerance override");
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Org id from the event object's metadata, used to pick a tolerance override
+///
+/// Read before the signature is verified, so it only selects the tolerance;
+/// the timestamp itself is covered by the signature.
+fn payload_org_id(payload: &str) -> Option<Uuid> {
+    let value: serde_json::Value = serde_json::from_str(payload).ok()?;
+    value["data"]["object"]["metadata"]["org_id"]
+        .as_str()?
+        .parse()
+        .ok()
+}
+
+/// Check a `Stripe-Signature` header (`t=timestamp,v1=signature,...`) against
+/// the payload, returning the signed timestamp
+///
+/// Only the HMAC is checked; callers decide whether the timestamp is fresh
+/// enough.
+fn verify_signature(payload: &str, signature: &str, webhook_secret: &str) -> BillingResult<i64> {
+    let mut timestamp: Option<i64> = None;
+    let mut v1_signature: Option<String> = None;
+
+    for part in signature.split(',') {
+        let kv: Vec<&str> = part.splitn(2, '=').collect();
+        if kv.len() == 2 {
+            match kv[0] {
+                "t" => timestamp = kv[1].parse().ok(),
+                "v1" => v1_signature = Some(kv[1].to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(|| {
+        tracing::error!("Missing timestamp in signature header");
+        BillingError::WebhookSignatureInvalid
+    })?;
+
+    let v1_signature = v1_signature.ok_or_else(|| {
+        tracing::error!("Missing v1 signature in signature header");
+        BillingError::WebhookSignatureInvalid
+    })?;
+
+    // Compute expected signature
+    // The secret starts with "whsec_" which is a base64-encoded key
+    let secret_key = webhook_secret
+        .strip_prefix("whsec_")
+        .unwrap_or(webhook_secret);
+    let signed_payload = format!("{}.{}", timestamp, payload);
+
+    let mut mac = HmacSha256::new_from_slice(secret_key.as_bytes()).map_err(|_| {
+        tracing::error!("Invalid webhook secret key");
+        BillingError::WebhookSignatureInvalid
+    })?;
+    mac.update(signed_payload.as_bytes());
+    let computed = hex::encode(mac.finalize().into_bytes());
+
+    // Compare signatures
+    if computed != v1_signature {
+        tracing::error!(
+            computed_sig = %computed,
+            received_sig = %v1_signature,
+            "Signature mismatch"
+        );
+        return Err(BillingError::WebhookSignatureInvalid);
+    }
+
+    Ok(timestamp)
+}
+
+/// Signature timestamp (`t=`) from a `Stripe-Signature` header
+fn signature_timestamp(signature: &str) -> Option<i64> {
+    signature
+        .split(',')
+        .find_map(|part| part.strip_prefix("t="))
+        .and_then(|t| t.parse().ok())
+}
+
+/// Whether moving from `previous_tier` to `new_tier` lowers the team member limit
+fn reduces_seat_limit(previous_tier: &str, new_tier: &str) -> bool {
+    match (
+        previous_tier.parse::<SubscriptionTier>(),
+        new_tier.parse::<SubscriptionTier>(),
+    ) {
+        (Ok(previous), Ok(new)) => new.max_team_members() < previous.max_team_members(),
+        _ => false,
+    }
+}
+
+/// Whether a closed dispute ends the usage pause: won, or an inquiry that
+/// closed without becoming a chargeback
+fn dispute_lifts_pause(status: DisputeStatus) -> bool {
+    matches!(status, DisputeStatus::Won | DisputeStatus::WarningClosed)
+}
+
+/// What a completed checkout session paid for, from its `checkout_type` metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CheckoutType {
+    /// Early payment of pending overage charges (`overage_payment`)
+    OveragePayment,
+    /// One-off payment for a tier upgrade plus any pending overages (`upgrade_payment`)
+    UpgradePayment {
+        new_tier: String,
+        billing_interval: BillingInterval,
+        overage_cents: i64,
+    },
+    /// Add-on subscription purchase (`addon`)
+    AddonPurchase { addon_type: String, quantity: u32 },
+    /// Regular subscription checkout (no or unrecognised `checkout_type`)
+    NewSubscription,
+}
+
+impl CheckoutType {
+    /// Parse session metadata; `Err` names a required key that is missing
+    fn from_metadata(metadata: &HashMap<String, String>) -> Result<Self, &'static str> {
+        let non_empty = |key: &'static str| {
+            metadata
+                .get(key)
+                .filter(|v| !v.is_empty())
+                .cloned()
+                .ok_or(key)
+        };
+
+        match metadata.get("checkout_type").map(|s| s.as_str()) {
+            Some("overage_payment") => Ok(CheckoutType::OveragePayment),
+            Some("upgrade_payment") => Ok(CheckoutType::UpgradePayment {
+                new_tier: non_empty("new_tier")?,
+                billing_interval: BillingInterval::from_metadata(metadata),
+                overage_cents: metadata
+                    .get("overage_cents")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0),
+            }),
+            Some("addon") => Ok(CheckoutType::AddonPurchase {
+                addon_type: non_empty("addon_type")?,
+                quantity: metadata
+                    .get("quantity")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(1),
+            }),
+            _ => Ok(CheckoutType::NewSubscription),
+        }
+    }
+}
+
+/// Record a paid checkout session in billing history
+///
+/// Keyed on the session ID, so a re-delivered completion writes nothing.
+/// Returns whether a row was inserted.
+async fn record_checkout_invoice(
+    pool: &PgPool,
+    org_id: Uuid,
+    session_id: &str,
+    amount_cents: i32,
+    description: &str,
+    billing_reason: &str,
+) -> BillingResult<bool> {
+    let result = sqlx::query(
+        r#"
+        INSERT INTO invoices (
+            org_id, stripe_invoice_id, amount_cents, amount_paid_cents,
+            currency, status, description, paid_at, billing_reason
+        )
+        VALUES ($1, $2, $3, $3, 'usd', 'paid', $4, NOW(), $5)
+        ON CONFLICT (stripe_invoice_id) DO NOTHING
+        "#,
+    )
+    .bind(org_id)
+    .bind(session_id)
+    .bind(amount_cents)
+    .bind(description)
+    .bind(billing_reason)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// An add-on bought through checkout, as stored in `subscription_addons`
+struct CheckoutAddon<'a> {
+    org_id: Uuid,
+    /// The org's existing internal subscription
+    subscription_id: Uuid,
+    addon_type: &'a str,
+    stripe_item_id: Option<&'a str>,
+    stripe_price_id: &'a str,
+    quantity: i32,
+    unit_price_cents: i32,
+}
+
+impl CheckoutAddon<'_> {
+    /// Activate the add-on, adding to the quantity of an already active one
+    ///
+    /// Every add-on checkout creates its own Stripe subscription item, so an
+    /// active row already on `stripe_item_id` means the completion was
+    /// re-delivered and its quantity is not added again.
+    async fn record(&self, pool: &PgPool) -> BillingResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO subscription_addons (
+                org_id, subscription_id, addon_type, stripe_item_id, stripe_price_id,
+                status, metadata, quantity, unit_price_cents
+            )
+            VALUES ($1, $2, $3, $4, $5, 'active', '{}'::jsonb, $6, $7)
+            ON CONFLICT (org_id, addon_type)
+            DO UPDATE SET
+                status = 'active',
+                stripe_item_id = EXCLUDED.stripe_item_id,
+                stripe_price_id = EXCLUDED.stripe_price_id,
+                subscription_id = EXCLUDED.subscription_id,
+                quantity = CASE
+                    WHEN subscription_addons.status = 'active'
+                         AND subscription_addons.stripe_item_id = EXCLUDED.stripe_item_id
+                    THEN subscription_addons.quantity
+                    WHEN subscription_addons.status = 'active'
+                    THEN subscription_addons.quantity + EXCLUDED.quantity
+                    ELSE EXCLUDED.quantity
+                END,
+                canceled_at = NULL,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(self.org_id)
+        .bind(self.subscription_id)
+        .bind(self.addon_type)
+        .bind(self.stripe_item_id)
+        .bind(self.stripe_price_id)
+        .bind(self.quantity)
+        .bind(self.unit_price_cents)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Event types with a handler in `process_event_internal`
+const HANDLED_EVENT_TYPES: &[EventType] = &[
+    EventType::CustomerSubscriptionCreated,
+    EventType::CustomerSubscriptionUpdated,
+    EventType::CustomerSubscriptionDeleted,
+    EventType::CustomerSubscriptionTrialWillEnd,
+    EventType::InvoicePaid,
+    EventType::InvoicePaymentFailed,
+    EventType::InvoiceFinalized,
+    EventType::InvoiceUpcoming,
+    EventType::CheckoutSessionCompleted,
+    EventType::ChargeFailed,
+    EventType::ChargeRefunded,
+    EventType::ChargeDisputeCreated,
+    EventType::ChargeDisputeClosed,
+    EventType::PaymentIntentPaymentFailed,
+    EventType::CustomerCreated,
+    EventType::CustomerUpdated,
+    EventType::CustomerDeleted,
+];
+
+/// Fail if no webhook signing secret is configured
+///
+/// Without one every webhook fails signature verification.
+fn check_webhook_secret(secret: &str) -> BillingResult<()> {
+    if secret.trim().is_empty() {
+        return Err(BillingError::Config(
+            "STRIPE_WEBHOOK_SECRET is empty; webhooks cannot be verified".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Handled event types that none of the endpoints' enabled events cover
+///
+/// `enabled_events` are Stripe event names; `*` enables everything.
+fn missing_event_types(enabled_events: &[&str]) -> Vec<EventType> {
+    if enabled_events.contains(&"*") {
+        return Vec::new();
+    }
+    HANDLED_EVENT_TYPES
+        .iter()
+        .copied()
+        .filter(|event_type| !enabled_events.contains(&event_name(*event_type).as_str()))
+        .collect()
+}
+
+/// Stripe's name for an event type (e.g. `invoice.paid`)
+fn event_name(event_type: EventType) -> String {
+    event_type.to_string().trim_matches('"').to_string()
+}
+
+/// What a `customer.deleted` event does to the org it names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CustomerDeletionAction {
+    /// The org still points at the deleted customer: detach it, cancel its
+    /// subscription locally and optionally downgrade
+    Detach { downgrade: bool },
+    /// The org has since been linked to a replacement customer; leave it alone
+    Replaced,
+    /// The org has no customer anymore (already handled)
+    AlreadyDetached,
+}
+
+fn customer_deletion_action(
+    deleted_customer_id: &str,
+    current_customer_id: Option<&str>,
+    downgrade: bool,
+) -> CustomerDeletionAction {
+    match current_customer_id {
+        Some(current) if current == deleted_customer_id => {
+            CustomerDeletionAction::Detach { downgrade }
+        }
+        Some(_) => CustomerDeletionAction::Replaced,
+        None => CustomerDeletionAction::AlreadyDetached,
+    }
+}
+
 /// Webhook handler for Stripe events
 pub struct WebhookHandler {
     stripe: StripeClient,
     pool: PgPool,
     email: BillingEmailService,
     event_logger: BillingEventLogger,
+    config: WebhookConfig,
 }
 
 impl WebhookHandler {
     pub fn new(stripe: StripeClient, pool: PgPool, email: BillingEmailService) -> Self {
+        Self::with_config(stripe, pool, email, WebhookConfig::from_env())
+    }
+
+    /// Create a webhook handler with explicit config
+    pub fn with_config(
+        stripe: StripeClient,
+        pool: PgPool,
+        email: BillingEmailService,
+        config: WebhookConfig,
+    ) -> Self {
         let event_logger = BillingEventLogger::new(pool.clone());
         Self {
             stripe,
             pool,
             email,
             event_logger,
+            config,
+        }
+    }
+
+    /// Startup self-check of the webhook setup
+    ///
+    /// Fails if no signing secret is configured. With a live-mode key, also
+    /// lists the account's enabled webhook endpoints and warns about handled
+    /// event types none of them send; returns those types. Stripe errors
+    /// only log a warning, since the endpoint check is advisory.
+    pub async fn verify_configuration(&self) -> BillingResult<Vec<EventType>> {
+        check_webhook_secret(&self.stripe.config().webhook_secret)?;
+
+        if !self.stripe.is_live_mode() {
+            return Ok(Vec::new());
+        }
+
+        let params = ListWebhookEndpoints {
+            limit: Some(100),
+            ..Default::default()
+        };
+        let endpoints = match WebhookEndpoint::list(self.stripe.inner(), &params).await {
+            Ok(list) => list.data,
+            Err(e) => {
+                tracing::warn!(error = %e, "Could not list Stripe webhook endpoints");
+                return Ok(Vec::new());
+            }
+        };
+
+        let enabled: Vec<&str> = endpoints
+            .iter()
+            .filter(|endpoint| endpoint.status == Some(WebhookEndpointStatus::Enabled))
+            .flat_map(|endpoint| endpoint.enabled_events.iter().flatten())
+            .map(|filter| filter.as_str())
+            .collect();
+
+        if enabled.is_empty() {
+            tracing::warn!("No enabled Stripe webhook endpoints found");
+        }
+
+        let missing = missing_event_types(&enabled);
+        for event_type in &missing {
+            tracing::warn!(
+                event_type = %event_name(*event_type),
+                "Handled Stripe event type is not enabled on any webhook endpoint"
+            );
         }
+
+        Ok(missing)
     }
 
     /// Verify and parse a Stripe webhook event
@@ -57,10 +635,17 @@ impl WebhookHandler {
             "Webhook verify_event - debug info"
         );
 
-        // Try the standard method first
+        let org_id = payload_org_id(payload);
+
+        // Try the standard method first. It applies Stripe's fixed 5-minute tolerance;
+        // deliveries it rejects as stale fall through to manual verification, which
+        // uses the configured `webhook_tolerance_secs`.
         match Webhook::construct_event(payload, signature, webhook_secret) {
             Ok(event) => {
                 tracing::info!("Standard webhook parsing succeeded");
+                if let Some(timestamp) = signature_timestamp(signature) {
+                    self.check_webhook_timestamp(timestamp, event.created, org_id)?;
+                }
                 return Ok(event);
             }
             Err(e) => {
@@ -72,73 +657,7 @@ impl WebhookHandler {
         }
 
         // Manual signature verification for newer Stripe API versions
-        // Parse the signature header: t=timestamp,v1=signature,v0=signature
-        let mut timestamp: Option<i64> = None;
-        let mut v1_signature: Option<String> = None;
-
-        for part in signature.split(',') {
-            let kv: Vec<&str> = part.splitn(2, '=').collect();
-            if kv.len() == 2 {
-                match kv[0] {
-                    "t" => timestamp = kv[1].parse().ok(),
-                    "v1" => v1_signature = Some(kv[1].to_string()),
-                    _ => {}
-                }
-            }
-        }
-
-        let timestamp = timestamp.ok_or_else(|| {
-            tracing::error!("Missing timestamp in signature header");
-            BillingError::WebhookSignatureInvalid
-        })?;
-
-        let v1_signature = v1_signature.ok_or_else(|| {
-            tracing::error!("Missing v1 signature in signature header");
-            BillingError::WebhookSignatureInvalid
-        })?;
-
-        // Check timestamp tolerance (5 minutes)
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map_err(|e| {
-                tracing::error!("System time error: {}", e);
-                BillingError::WebhookSignatureInvalid
-            })?
-            .as_secs() as i64;
-
-        if (now - timestamp).abs() > 300 {
-            tracing::error!(
-                timestamp = timestamp,
-                now = now,
-                diff = (now - timestamp).abs(),
-                "Webhook timestamp too old"
-            );
-            return Err(BillingError::WebhookSignatureInvalid);
-        }
-
-        // Compute expected signature
-        // The secret starts with "whsec_" which is a base64-encoded key
-        let secret_key = webhook_secret
-            .strip_prefix("whsec_")
-            .unwrap_or(webhook_secret);
-        let signed_payload = format!("{}.{}", timestamp, payload);
-
-        let mut mac = HmacSha256::new_from_slice(secret_key.as_bytes()).map_err(|_| {
-            tracing::error!("Invalid webhook secret key");
-            BillingError::WebhookSignatureInvalid
-        })?;
-        mac.update(signed_payload.as_bytes());
-        let computed = hex::encode(mac.finalize().into_bytes());
-
-        // Compare signatures
-        if computed != v1_signature {
-            tracing::error!(
-                computed_sig = %computed,
-                received_sig = %v1_signature,
-                "Signature mismatch"
-            );
-            return Err(BillingError::WebhookSignatureInvalid);
-        }
+        let timestamp = verify_signature(payload, signature, webhook_secret)?;
 
         tracing::info!("Manual signature verification passed");
 
@@ -152,6 +671,8 @@ impl WebhookHandler {
             BillingError::WebhookSignatureInvalid
         })?;
 
+        self.check_webhook_timestamp(timestamp, event.created, org_id)?;
+
         tracing::info!(
             event_type = %event.type_,
             event_id = %event.id,
@@ -161,12 +682,91 @@ impl WebhookHandler {
         Ok(event)
     }
 
+    /// Check the signature timestamp against our clock and record the skew
+    ///
+    /// The signature timestamp is when Stripe sent this delivery, so it tracks
+    /// clock drift; `event.created` is logged alongside as the event age, which
+    /// is large for retried deliveries.
+    fn check_webhook_timestamp(
+        &self,
+        signed_at: i64,
+        event_created: i64,
+        org_id: Option<Uuid>,
+    ) -> BillingResult<()> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let skew = clock_skew_secs(now, signed_at);
+        let event_age = clock_skew_secs(now, event_created);
+        let tolerance = self
+            .config
+            .timestamp_tolerance_for(org_id, self.stripe.config().webhook_tolerance_secs);
+
+        match self.config.check_clock_skew(skew, tolerance) {
+            ClockSkewCheck::Ok => {
+                tracing::debug!(
+                    clock_skew_secs = skew,
+                    event_age_secs = event_age,
+                    "Webhook timestamp within tolerance"
+                );
+                Ok(())
+            }
+            ClockSkewCheck::Warning => {
+                tracing::warn!(
+                    clock_skew_secs = skew,
+                    event_age_secs = event_age,
+                    warning_secs = self.config.clock_skew_warning_secs,
+                    tolerance_secs = tolerance,
+                    org_id = ?org_id,
+                    "Webhook clock skew above warning threshold"
+                );
+                Ok(())
+            }
+            ClockSkewCheck::Rejected => {
+                // A replayed delivery is always signed in our past, usually by far
+                // more than the tolerance; drift can go either way and tends to
+                // sit just past it
+                tracing::warn!(
+                    clock_skew_secs = skew,
+                    signed_in_future = skew < 0,
+                    event_age_secs = event_age,
+                    tolerance_secs = tolerance,
+                    org_id = ?org_id,
+                    "Webhook rejected: signature timestamp outside tolerance (clock drift or replay)"
+                );
+                Err(BillingError::WebhookSignatureInvalid)
+            }
+        }
+    }
+
     /// Handle a verified Stripe event
     ///
     /// SOC 2 CC7.1: Implements atomic idempotency to prevent replay attacks.
     /// Uses INSERT...ON CONFLICT...RETURNING to atomically claim exclusive processing rights.
     /// This prevents race conditions where two concurrent webhooks could both pass an EXISTS check.
     pub async fn handle_event(&self, event: Event) -> BillingResult<()> {
+        self.claim_and_process(event, None).await
+    }
+
+    /// Handle a verified Stripe event, storing its raw body and signature header
+    ///
+    /// The stored delivery can later be re-run with `replay_event`.
+    pub async fn handle_delivery(
+        &self,
+        event: Event,
+        payload: &str,
+        signature: &str,
+    ) -> BillingResult<()> {
+        self.claim_and_process(event, Some((payload, signature)))
+            .await
+    }
+
+    /// Claim the event for exclusive processing, process it and record the result
+    ///
+    /// `raw` is the delivery's body and `Stripe-Signature` header, if known.
+    async fn claim_and_process(
+        &self,
+        event: Event,
+        raw: Option<(&str, &str)>,
+    ) -> BillingResult<()> {
         let event_id = event.id.to_string();
         let event_type_str = event.type_.to_string();
 
@@ -180,27 +780,40 @@ impl WebhookHandler {
         // we have exclusive claim. If it returns None, another process already claimed it.
         //
         // Additionally, we allow re-claiming events that have been stuck in "processing"
-        // for over 30 minutes (timeout recovery).
-        const PROCESSING_TIMEOUT_MINUTES: i32 = 30;
-
-        let claimed: Option<(Uuid,)> = sqlx::query_as(
+        // for over 30 minutes (timeout recovery) and failed events whose retry
+        // backoff has elapsed. Stripe stops redelivering on its own, so failed
+        // events need no dead-letter state; `replay_event` handles the rest.
+        let claimed: Option<(Uuid, i32)> = sqlx::query_as(
             r#"
             INSERT INTO stripe_webhook_events
-                (stripe_event_id, event_type, event_timestamp, processing_result, processing_started_at)
-            VALUES ($1, $2, $3, 'processing', NOW())
+                (stripe_event_id, event_type, event_timestamp, processing_result, processing_started_at,
+                 raw_payload, signature_header, attempt_count)
+            VALUES ($1, $2, $3, 'processing', NOW(), $5, $6, 1)
             ON CONFLICT (stripe_event_id) DO UPDATE SET
                 processing_result = 'processing',
                 processing_started_at = NOW(),
-                error_message = CONCAT('Recovered from stuck state at ', NOW()::TEXT)
-            WHERE stripe_webhook_events.processing_result = 'processing'
-              AND stripe_webhook_events.processing_started_at < NOW() - ($4 || ' minutes')::INTERVAL
-            RETURNING id
+                attempt_count = stripe_webhook_events.attempt_count + 1,
+                next_retry_at = NULL,
+                error_message = CASE
+                    WHEN stripe_webhook_events.processing_result = 'processing'
+                        THEN CONCAT('Recovered from stuck state at ', NOW()::TEXT)
+                    ELSE stripe_webhook_events.error_message
+                END,
+                raw_payload = COALESCE(stripe_webhook_events.raw_payload, EXCLUDED.raw_payload),
+                signature_header = COALESCE(stripe_webhook_events.signature_header, EXCLUDED.signature_header)
+            WHERE (stripe_webhook_events.processing_result = 'processing'
+                   AND stripe_webhook_events.processing_started_at < NOW() - ($4 || ' minutes')::INTERVAL)
+               OR (stripe_webhook_events.processing_result = 'error'
+                   AND COALESCE(stripe_webhook_events.next_retry_at, NOW()) <= NOW())
+            RETURNING id, attempt_count
             "#
         )
         .bind(&event_id)
         .bind(&event_type_str)
         .bind(event_timestamp)
         .bind(PROCESSING_TIMEOUT_MINUTES)
+        .bind(raw.map(|(payload, _)| payload))
+        .bind(raw.map(|(_, signature)| signature))
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| {
@@ -212,7 +825,7 @@ impl WebhookHandler {
             BillingError::Database(e.to_string())
         })?;
 
-        if claimed.is_none() {
+        let Some((_, attempt)) = claimed else {
             // Check if it was already processed successfully or is being actively processed
             let existing_status: Option<(String,)> = sqlx::query_as(
                 "SELECT processing_result FROM stripe_webhook_events WHERE stripe_event_id = $1",
@@ -228,6 +841,18 @@ impl WebhookHandler {
                 Some((status,)) if status == "processing" => {
                     "currently being processed by another worker"
                 }
+                Some((status,)) if status == "error" => {
+                    // Fail the delivery so Stripe redelivers after our backoff
+                    tracing::info!(
+                        event_id = %event_id,
+                        event_type = %event_type_str,
+                        "Failed webhook event redelivered before its retry time"
+                    );
+                    return Err(BillingError::Internal(format!(
+                        "Webhook event {} failed and is waiting for its next retry",
+                        event_id
+                    )));
+                }
                 Some(_) => "exists with another status",
                 None => "unknown (race condition?)",
             };
@@ -239,7 +864,7 @@ impl WebhookHandler {
                 "Duplicate webhook event - atomic idempotency check"
             );
             return Ok(());
-        }
+        };
 
         tracing::info!(
             event_type = %event.type_,
@@ -251,24 +876,30 @@ impl WebhookHandler {
         let result = self.process_event_internal(&event).await;
 
         // Update the event record with processing result
-        let (processing_result, error_message) = match &result {
-            Ok(()) => ("success".to_string(), None),
-            Err(e) => ("error".to_string(), Some(e.to_string())),
+        let (processing_result, error_message, retry_in_secs) = match &result {
+            Ok(()) => ("success".to_string(), None, None),
+            Err(e) => (
+                "error".to_string(),
+                Some(e.to_string()),
+                Some(retry_backoff(attempt).as_secs_f64()),
+            ),
         };
 
         // Update the event record - retry once on failure since this is important for audit
-        let update_result = sqlx::query(
-            r#"
+        let update_sql = r#"
             UPDATE stripe_webhook_events
-            SET processing_result = $1, error_message = $2
+            SET processing_result = $1,
+                error_message = $2,
+                next_retry_at = NOW() + make_interval(secs => $4)
             WHERE stripe_event_id = $3
-            "#,
-        )
-        .bind(&processing_result)
-        .bind(&error_message)
-        .bind(&event_id)
-        .execute(&self.pool)
-        .await;
+            "#;
+        let update_result = sqlx::query(update_sql)
+            .bind(&processing_result)
+            .bind(&error_message)
+            .bind(&event_id)
+            .bind(retry_in_secs)
+            .execute(&self.pool)
+            .await;
 
         if let Err(e) = update_result {
             // Retry once - audit record is important for idempotency
@@ -278,18 +909,13 @@ impl WebhookHandler {
                 "First attempt to update webhook event failed, retrying..."
             );
 
-            if let Err(retry_err) = sqlx::query(
-                r#"
-                UPDATE stripe_webhook_events
-                SET processing_result = $1, error_message = $2
-                WHERE stripe_event_id = $3
-                "#,
-            )
-            .bind(&processing_result)
-            .bind(&error_message)
-            .bind(&event_id)
-            .execute(&self.pool)
-            .await
+            if let Err(retry_err) = sqlx::query(update_sql)
+                .bind(&processing_result)
+                .bind(&error_message)
+                .bind(&event_id)
+                .bind(retry_in_secs)
+                .execute(&self.pool)
+                .await
             {
                 // Log at error level with full context for debugging
                 tracing::error!(
@@ -358,6 +984,9 @@ impl WebhookHandler {
             EventType::ChargeDisputeCreated => {
                 self.handle_charge_dispute_created(event_owned).await?;
             }
+            EventType::ChargeDisputeClosed => {
+                self.handle_charge_dispute_closed(event_owned).await?;
+            }
 
             // Payment intent events
             EventType::PaymentIntentPaymentFailed => {
@@ -436,19 +1065,60 @@ impl WebhookHandler {
         let subscription = self.extract_subscription(event)?;
         let org_id = self.get_org_id_from_metadata(&subscription.metadata)?;
 
+        // Capture the tier before sync so we can detect plan changes
+        let previous_tier: Option<String> =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
         let sub_service = SubscriptionService::new(self.stripe.clone(), self.pool.clone());
-        sub_service
+        let diff = sub_service
             .sync_subscription_to_db(org_id, &subscription)
             .await?;
 
-        // Log billing event
-        if let Err(e) = self
+        // Enforce seat limits promptly when the plan changes to a smaller tier,
+        // rather than waiting for the next invoice.paid
+        if self
+            .config
+            .reconciles_members_on(EventType::CustomerSubscriptionUpdated)
+        {
+            let new_tier = subscription
+                .items
+                .data
+                .first()
+                .and_then(|item| item.price.as_ref())
+                .and_then(|price| self.stripe.config().tier_for_price_id(price.id.as_str()));
+
+            if let (Some(previous_tier), Some(new_tier)) = (previous_tier.as_deref(), new_tier) {
+                if reduces_seat_limit(previous_tier, new_tier) {
+                    tracing::info!(
+                        org_id = %org_id,
+                        from_tier = %previous_tier,
+                        to_tier = %new_tier,
+                        "Subscription tier reduced - reconciling members"
+                    );
+                    self.reconcile_members(org_id, new_tier).await;
+                }
+            }
+        }
+
+        // Log billing event (Stripe sends many updates that change nothing we store,
+        // so only material changes are recorded)
+        if diff.is_empty() {
+            tracing::debug!(
+                org_id = %org_id,
+                subscription_id = %subscription.id,
+                "Subscription updated without material changes - skipping billing event"
+            );
+        } else if let Err(e) = self
             .event_logger
             .log_event(
                 BillingEventBuilder::new(org_id, BillingEventType::SubscriptionUpdated)
                     .data(serde_json::json!({
                         "status": format!("{:?}", subscription.status),
                         "cancel_at_period_end": subscription.cancel_at_period_end,
+                        "changes": diff,
                     }))
                     .stripe_event(&event_id)
                     .stripe_subscription(subscription.id.to_string())
@@ -493,6 +1163,28 @@ impl WebhookHandler {
         let subscription = self.extract_subscription(event)?;
         let org_id = self.get_org_id_from_metadata(&subscription.metadata)?;
 
+        // The addon-only base is removed when its last add-on goes or when its
+        // add-ons move to a paid subscription; neither changes the org's tier
+        let addon_only_price = self.stripe.config().price_ids.addon_only.as_deref();
+        if SubscriptionKind::of(&subscription, addon_only_price) == SubscriptionKind::AddonOnly {
+            sqlx::query(
+                r#"
+                UPDATE subscriptions SET status = 'canceled', updated_at = NOW()
+                WHERE stripe_subscription_id = $1
+                "#,
+            )
+            .bind(subscription.id.as_str())
+            .execute(&self.pool)
+            .await?;
+
+            tracing::info!(
+                org_id = %org_id,
+                subscription_id = %subscription.id,
+                "Add-on only subscription deleted, tier unchanged"
+            );
+            return Ok(());
+        }
+
         // Log billing event
         if let Err(e) = self
             .event_logger
@@ -532,9 +1224,9 @@ impl WebhookHandler {
 
         // Send cancellation confirmation email
         if let Ok(Some((email, org_name))) = self.get_org_owner_email(org_id).await {
-            let end_date = OffsetDateTime::from_unix_timestamp(subscription.current_period_end)
-                .map(|dt| dt.date().to_string())
-                .unwrap_or_else(|_| "soon".to_string());
+            let end_date = subscription_period(&subscription)
+                .map(|(_, end)| end.date().to_string())
+                .unwrap_or_else(|| "soon".to_string());
             if let Err(e) = self
                 .email
                 .send_subscription_cancelled(&email, &org_name, &end_date)
@@ -622,23 +1314,39 @@ impl WebhookHandler {
         // Get org_id from customer
         let org_id = self.get_org_id_from_customer(&invoice.customer).await?;
 
-        // Store invoice record
+        // Store invoice record (zero-amount invoices are still recorded for history)
         self.store_invoice(org_id, &invoice, "paid").await?;
 
         let invoice_id = invoice.id.to_string();
 
-        // Log billing event
-        if let Err(e) = self.event_logger.log_event(
-            BillingEventBuilder::new(org_id, BillingEventType::InvoicePaid)
-                .data(serde_json::json!({
-                    "amount_paid_cents": invoice.amount_paid,
-                    "total_cents": invoice.total,
-                    "billing_reason": invoice.billing_reason.as_ref().map(|r| format!("{:?}", r)),
-                }))
-                .stripe_event(&event_id)
-                .stripe_invoice(&invoice_id)
-                .actor_type(ActorType::Stripe)
-        ).await {
+        // Fully-credited or $0 addon-only base invoices: no money moved, so no
+        // customer-facing payment emails ("paid $0.00" is confusing noise)
+        let zero_amount = is_zero_amount_invoice(&invoice);
+        if zero_amount {
+            tracing::info!(
+                org_id = %org_id,
+                invoice_id = %invoice_id,
+                "Zero-amount invoice paid - recorded for history, skipping payment emails"
+            );
+        }
+
+        // Log billing event
+        if let Err(e) = self
+            .event_logger
+            .log_event(
+                BillingEventBuilder::new(org_id, BillingEventType::InvoicePaid)
+                    .data(serde_json::json!({
+                        "amount_paid_cents": invoice.amount_paid,
+                        "total_cents": invoice.total,
+                        "billing_reason": invoice_billing_reason(&invoice),
+                        "zero_amount": zero_amount,
+                    }))
+                    .stripe_event(&event_id)
+                    .stripe_invoice(&invoice_id)
+                    .actor_type(ActorType::Stripe),
+            )
+            .await
+        {
             tracing::warn!(error = %e, "Failed to log invoice paid event");
         }
 
@@ -666,7 +1374,13 @@ impl WebhookHandler {
             );
 
             // Send confirmation email
-            if let Ok(Some((email, org_name))) = self.get_org_owner_email(org_id).await {
+            if zero_amount {
+                tracing::debug!(
+                    org_id = %org_id,
+                    invoice_id = %invoice_id,
+                    "Skipping pay now confirmation email for zero-amount invoice"
+                );
+            } else if let Ok(Some((email, org_name))) = self.get_org_owner_email(org_id).await {
                 let amount_cents = invoice.amount_paid.unwrap_or(0) as i32;
                 if let Err(e) = self
                     .email
@@ -749,6 +1463,16 @@ impl WebhookHandler {
             tracing::info!(org_id = %org_id, "Organization unpaused after payment");
         }
 
+        // Add-ons removed "at period end" stay active until the paid period is over
+        let addon_service = AddonService::new(self.stripe.clone(), self.pool.clone());
+        if let Err(e) = addon_service.expire_period_end_removals(org_id).await {
+            tracing::warn!(
+                org_id = %org_id,
+                error = %e,
+                "Failed to expire add-ons scheduled for period-end removal"
+            );
+        }
+
         // Check for scheduled downgrades at billing period renewal
         // This invoice payment means a new billing period has started
         // If user scheduled a downgrade, now is the time to process it
@@ -767,44 +1491,8 @@ impl WebhookHandler {
                 );
 
                 // Suspend excess members due to plan downgrade
-                let member_service = MemberSuspensionService::new(self.pool.clone());
-                match member_service
-                    .suspend_excess_members(org_id, &new_tier, "plan_downgrade")
-                    .await
-                {
-                    Ok(result) if result.suspended_count > 0 => {
-                        tracing::info!(
-                            org_id = %org_id,
-                            suspended_count = result.suspended_count,
-                            new_tier = %new_tier,
-                            "Suspended excess members after downgrade"
-                        );
-
-                        // Send notification to suspended members
-                        for member in &result.suspended_members {
-                            if let Err(e) = self
-                                .email
-                                .send_member_suspended(&member.email, &new_tier)
-                                .await
-                            {
-                                tracing::error!(
-                                    error = %e,
-                                    email = %member.email,
-                                    "Failed to send member suspension email"
-                                );
-                            }
-                        }
-                    }
-                    Ok(_) => {
-                        // No excess members to suspend
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            org_id = %org_id,
-                            error = %e,
-                            "Failed to suspend excess members after downgrade"
-                        );
-                    }
+                if self.config.reconciles_members_on(EventType::InvoicePaid) {
+                    self.reconcile_members(org_id, &new_tier).await;
                 }
 
                 // Send email notification about the downgrade
@@ -871,18 +1559,22 @@ impl WebhookHandler {
         .map_err(|e| BillingError::Database(e.to_string()))?;
 
         // Log billing event with retry information
-        if let Err(e) = self.event_logger.log_event(
-            BillingEventBuilder::new(org_id, BillingEventType::InvoiceFailed)
-                .data(serde_json::json!({
-                    "amount_due_cents": invoice.amount_due,
-                    "billing_reason": invoice.billing_reason.as_ref().map(|r| format!("{:?}", r)),
-                    "attempt_count": attempt_count,
-                    "next_attempt": invoice.next_payment_attempt.map(|t| t.to_string()),
-                }))
-                .stripe_event(&event_id)
-                .stripe_invoice(&invoice_id)
-                .actor_type(ActorType::Stripe)
-        ).await {
+        if let Err(e) = self
+            .event_logger
+            .log_event(
+                BillingEventBuilder::new(org_id, BillingEventType::InvoiceFailed)
+                    .data(serde_json::json!({
+                        "amount_due_cents": invoice.amount_due,
+                        "billing_reason": invoice_billing_reason(&invoice),
+                        "attempt_count": attempt_count,
+                        "next_attempt": invoice.next_payment_attempt.map(|t| t.to_string()),
+                    }))
+                    .stripe_event(&event_id)
+                    .stripe_invoice(&invoice_id)
+                    .actor_type(ActorType::Stripe),
+            )
+            .await
+        {
             tracing::warn!(error = %e, "Failed to log invoice payment failed event");
         }
 
@@ -934,7 +1626,7 @@ impl WebhookHandler {
                     }
                 }
                 _ => {
-                    // 4+ attempts - final warning, service may be suspended
+                    // FINAL_PAYMENT_ATTEMPT+ - final warning, service may be suspended
                     tracing::error!(
                         org_id = %org_id,
                         attempt_count = attempt_count,
@@ -948,21 +1640,34 @@ impl WebhookHandler {
                     {
                         tracing::error!(error = %e, "Failed to send final payment warning email");
                     }
+                }
+            }
+        }
 
-                    // Mark org as past_due_locked if too many failures
-                    // (SOC 2: Access control based on payment status)
-                    if let Err(e) = sqlx::query(
-                        "UPDATE organizations SET billing_blocked_at = NOW() WHERE id = $1 AND billing_blocked_at IS NULL"
-                    )
-                    .bind(org_id)
-                    .execute(&self.pool)
-                    .await {
-                        tracing::error!(
-                            org_id = %org_id,
-                            error = %e,
-                            "Failed to block billing for org after repeated payment failures"
-                        );
-                    }
+        // Block after repeated failures (SOC 2: access control based on payment
+        // status), but only once the decline retry grace runs out. The grace
+        // period job does the block, so this never blocks twice.
+        if attempt_count >= FINAL_PAYMENT_ATTEMPT {
+            let grace_days = get_invoice_grace_config().decline_retry_days;
+            match GracePeriodService::new(self.pool.clone())
+                .schedule_block_after_final_decline(&invoice_id, grace_days)
+                .await
+            {
+                Ok(true) => {
+                    tracing::warn!(
+                        org_id = %org_id,
+                        invoice_id = %invoice_id,
+                        grace_days = grace_days,
+                        "Scheduled billing block after repeated payment failures"
+                    );
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to schedule billing block after repeated payment failures"
+                    );
                 }
             }
         }
@@ -1020,7 +1725,10 @@ impl WebhookHandler {
         );
 
         // Send notification email if there are pending overages or significant amount due
-        if total_expected > 0 {
+        if self
+            .config
+            .should_notify_upcoming_invoice(amount_due, pending_overages)
+        {
             if let Ok(Some((email, org_name))) = self.get_org_owner_email(org_id).await {
                 if let Err(e) = self
                     .email
@@ -1073,410 +1781,507 @@ impl WebhookHandler {
             }
         };
 
-        if let Some(metadata) = &session.metadata {
-            if let Some(org_id_str) = metadata.get("org_id") {
-                let org_id = Uuid::parse_str(org_id_str)
-                    .map_err(|e| BillingError::Internal(format!("Invalid org_id: {}", e)))?;
+        let Some(metadata) = &session.metadata else {
+            return Ok(());
+        };
+        let Some(org_id_str) = metadata.get("org_id") else {
+            return Ok(());
+        };
+        let org_id = Uuid::parse_str(org_id_str)
+            .map_err(|e| BillingError::Internal(format!("Invalid org_id: {}", e)))?;
 
-                // Check if this is an overage payment checkout
-                let checkout_type = metadata.get("checkout_type").map(|s| s.as_str());
+        let checkout_type = match CheckoutType::from_metadata(metadata) {
+            Ok(checkout_type) => checkout_type,
+            Err(missing) => {
+                tracing::error!(
+                    org_id = %org_id,
+                    session_id = %session.id,
+                    "Checkout completed but no {} in metadata",
+                    missing
+                );
+                return Ok(());
+            }
+        };
 
-                if checkout_type == Some("overage_payment") {
-                    // Handle overage payment completion
-                    let session_id = session.id.to_string();
-                    let overage_service =
-                        OverageService::new(self.stripe.clone(), self.pool.clone());
+        match checkout_type {
+            CheckoutType::OveragePayment => self.complete_overage_payment(org_id, &session).await,
+            CheckoutType::UpgradePayment {
+                new_tier,
+                billing_interval,
+                overage_cents,
+            } => {
+                self.complete_upgrade_payment(
+                    org_id,
+                    &session,
+                    &new_tier,
+                    billing_interval,
+                    overage_cents,
+                )
+                .await
+            }
+            CheckoutType::AddonPurchase {
+                addon_type,
+                quantity,
+            } => {
+                self.complete_addon_purchase(org_id, &session, &addon_type, quantity)
+                    .await
+            }
+            CheckoutType::NewSubscription => self.complete_new_subscription(org_id, &session).await,
+        }
+    }
 
-                    match overage_service.mark_early_payment_paid(&session_id).await {
-                        Ok(count) if count > 0 => {
-                            tracing::info!(
-                                org_id = %org_id,
-                                session_id = %session_id,
-                                charges_marked_paid = count,
-                                "Overage payment completed via checkout"
-                            );
+    /// Early overage payment: mark the pending charges paid and record an invoice
+    async fn complete_overage_payment(
+        &self,
+        org_id: Uuid,
+        session: &CheckoutSession,
+    ) -> BillingResult<()> {
+        let session_id = session.id.to_string();
+        let overage_service = OverageService::new(self.stripe.clone(), self.pool.clone());
 
-                            // Create invoice record for billing history
-                            let amount_cents = session.amount_total.unwrap_or(0) as i32;
-                            if amount_cents > 0 {
-                                let invoice_result = sqlx::query(
-                                    r#"
-                                    INSERT INTO invoices (
-                                        org_id, stripe_invoice_id, amount_cents, amount_paid_cents,
-                                        currency, status, description, paid_at, billing_reason
-                                    )
-                                    VALUES ($1, $2, $3, $3, 'usd', 'paid', 'Early overage payment', NOW(), 'overage_payment')
-                                    ON CONFLICT (stripe_invoice_id) DO NOTHING
-                                    "#
-                                )
-                                .bind(org_id)
-                                .bind(&session_id)
-                                .bind(amount_cents)
-                                .execute(&self.pool)
-                                .await;
-
-                                match invoice_result {
-                                    Ok(_) => {
-                                        tracing::info!(
-                                            org_id = %org_id,
-                                            amount_cents = amount_cents,
-                                            "Created invoice record for overage payment"
-                                        );
-                                    }
-                                    Err(e) => {
-                                        tracing::error!(
-                                            org_id = %org_id,
-                                            error = %e,
-                                            "Failed to create invoice record for overage payment"
-                                        );
-                                    }
-                                }
-                            }
-                        }
+        match overage_service.mark_early_payment_paid(&session_id).await {
+            Ok(count) if count > 0 => {
+                tracing::info!(
+                    org_id = %org_id,
+                    session_id = %session_id,
+                    charges_marked_paid = count,
+                    "Overage payment completed via checkout"
+                );
+
+                // Create invoice record for billing history
+                let amount_cents = session.amount_total.unwrap_or(0) as i32;
+                if amount_cents > 0 {
+                    let invoice_result = record_checkout_invoice(
+                        &self.pool,
+                        org_id,
+                        &session_id,
+                        amount_cents,
+                        "Early overage payment",
+                        "overage_payment",
+                    )
+                    .await;
+
+                    match invoice_result {
                         Ok(_) => {
-                            tracing::warn!(
+                            tracing::info!(
                                 org_id = %org_id,
-                                session_id = %session_id,
-                                "Overage checkout completed but no charges found to mark as paid"
+                                amount_cents = amount_cents,
+                                "Created invoice record for overage payment"
                             );
                         }
                         Err(e) => {
                             tracing::error!(
                                 org_id = %org_id,
-                                session_id = %session_id,
                                 error = %e,
-                                "Failed to mark overage charges as paid"
+                                "Failed to create invoice record for overage payment"
                             );
                         }
                     }
-
-                    return Ok(());
                 }
+            }
+            Ok(_) => {
+                // Also the case for a re-delivered completion: the charges
+                // were marked paid the first time
+                tracing::warn!(
+                    org_id = %org_id,
+                    session_id = %session_id,
+                    "Overage checkout completed but no charges found to mark as paid"
+                );
+            }
+            Err(e) => {
+                tracing::error!(
+                    org_id = %org_id,
+                    session_id = %session_id,
+                    error = %e,
+                    "Failed to mark overage charges as paid"
+                );
+            }
+        }
 
-                // Handle upgrade payment checkout completion
-                if checkout_type == Some("upgrade_payment") {
-                    let new_tier = metadata.get("new_tier").map(|s| s.as_str()).unwrap_or("");
-                    let billing_interval = metadata
-                        .get("billing_interval")
-                        .map(|s| s.as_str())
-                        .unwrap_or("monthly");
-                    let overage_cents: i64 = metadata
-                        .get("overage_cents")
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(0);
-
-                    if new_tier.is_empty() {
-                        tracing::error!(
-                            org_id = %org_id,
-                            "Upgrade checkout completed but no new_tier in metadata"
-                        );
-                        return Ok(());
-                    }
+        Ok(())
+    }
+
+    /// Upgrade payment: move to the new tier, settle included overages, record an invoice
+    async fn complete_upgrade_payment(
+        &self,
+        org_id: Uuid,
+        session: &CheckoutSession,
+        new_tier: &str,
+        billing_interval: BillingInterval,
+        overage_cents: i64,
+    ) -> BillingResult<()> {
+        tracing::info!(
+            org_id = %org_id,
+            new_tier = %new_tier,
+            billing_interval = billing_interval.as_str(),
+            overage_cents = overage_cents,
+            "Processing upgrade payment checkout completion"
+        );
+
+        // 1. Update the subscription to the new tier
+        let sub_service = SubscriptionService::new(self.stripe.clone(), self.pool.clone());
+        match sub_service
+            .upgrade_subscription_after_payment(org_id, new_tier, billing_interval)
+            .await
+        {
+            Ok(Some(_)) => {
+                tracing::info!(
+                    org_id = %org_id,
+                    new_tier = %new_tier,
+                    "Successfully upgraded subscription after payment"
+                );
+            }
+            Ok(None) => {
+                // Re-delivered completion. Its overages were settled the first
+                // time, and pending ones now belong to a later checkout.
+                tracing::info!(
+                    org_id = %org_id,
+                    session_id = %session.id,
+                    new_tier = %new_tier,
+                    "Upgrade checkout already applied, nothing to do"
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                tracing::error!(
+                    org_id = %org_id,
+                    new_tier = %new_tier,
+                    error = %e,
+                    "Failed to upgrade subscription after payment - MANUAL INTERVENTION REQUIRED"
+                );
+                // Don't return error - payment was successful, just log for manual fix
+            }
+        }
 
+        // 2. Mark overages as paid (if any were included)
+        if overage_cents > 0 {
+            let overage_service = OverageService::new(self.stripe.clone(), self.pool.clone());
+            match overage_service.mark_upgrade_overages_paid(org_id).await {
+                Ok(count) => {
                     tracing::info!(
                         org_id = %org_id,
-                        new_tier = %new_tier,
-                        billing_interval = %billing_interval,
-                        overage_cents = overage_cents,
-                        "Processing upgrade payment checkout completion"
+                        charges_paid = count,
+                        "Marked upgrade overages as paid"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to mark upgrade overages as paid"
                     );
+                }
+            }
+        }
 
-                    // 1. Update the subscription to the new tier
-                    let sub_service =
-                        SubscriptionService::new(self.stripe.clone(), self.pool.clone());
-                    match sub_service
-                        .upgrade_subscription_after_payment(org_id, new_tier, billing_interval)
-                        .await
-                    {
-                        Ok(_) => {
-                            tracing::info!(
-                                org_id = %org_id,
-                                new_tier = %new_tier,
-                                "Successfully upgraded subscription after payment"
-                            );
-                        }
-                        Err(e) => {
-                            tracing::error!(
-                                org_id = %org_id,
-                                new_tier = %new_tier,
-                                error = %e,
-                                "Failed to upgrade subscription after payment - MANUAL INTERVENTION REQUIRED"
-                            );
-                            // Don't return error - payment was successful, just log for manual fix
-                        }
-                    }
+        // 3. Create invoice record for billing history
+        let amount_cents = session.amount_total.unwrap_or(0) as i32;
+        if amount_cents > 0 {
+            let _ = record_checkout_invoice(
+                &self.pool,
+                org_id,
+                session.id.as_str(),
+                amount_cents,
+                &format!("Upgrade to {} plan", new_tier),
+                "upgrade_payment",
+            )
+            .await;
+        }
 
-                    // 2. Mark overages as paid (if any were included)
-                    if overage_cents > 0 {
-                        let overage_service =
-                            OverageService::new(self.stripe.clone(), self.pool.clone());
-                        match overage_service.mark_upgrade_overages_paid(org_id).await {
-                            Ok(count) => {
-                                tracing::info!(
-                                    org_id = %org_id,
-                                    charges_paid = count,
-                                    "Marked upgrade overages as paid"
-                                );
-                            }
-                            Err(e) => {
-                                tracing::error!(
-                                    org_id = %org_id,
-                                    error = %e,
-                                    "Failed to mark upgrade overages as paid"
-                                );
-                            }
-                        }
-                    }
+        Ok(())
+    }
 
-                    // 3. Create invoice record for billing history
-                    let amount_cents = session.amount_total.unwrap_or(0) as i32;
-                    if amount_cents > 0 {
-                        let session_id = session.id.to_string();
-                        let _ = sqlx::query(
-                            r#"
-                            INSERT INTO invoices (
-                                org_id, stripe_invoice_id, amount_cents, amount_paid_cents,
-                                currency, status, description, paid_at, billing_reason
-                            )
-                            VALUES ($1, $2, $3, $3, 'usd', 'paid', $4, NOW(), 'upgrade_payment')
-                            ON CONFLICT (stripe_invoice_id) DO NOTHING
-                            "#,
-                        )
-                        .bind(org_id)
-                        .bind(&session_id)
-                        .bind(amount_cents)
-                        .bind(format!("Upgrade to {} plan", new_tier))
-                        .execute(&self.pool)
-                        .await;
-                    }
+    /// Add-on purchase: record the add-on against the org's existing subscription
+    async fn complete_addon_purchase(
+        &self,
+        org_id: Uuid,
+        session: &CheckoutSession,
+        addon_type: &str,
+        quantity: u32,
+    ) -> BillingResult<()> {
+        tracing::info!(
+            org_id = %org_id,
+            addon_type = %addon_type,
+            quantity = %quantity,
+            "Processing addon checkout completion"
+        );
 
-                    return Ok(());
-                }
+        // Get the EXISTING internal subscription ID first
+        // (Don't sync the new addon subscription - we use the existing one)
+        let internal_sub_id: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM subscriptions WHERE org_id = $1 AND status = 'active' LIMIT 1",
+        )
+        .bind(org_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
-                // Handle addon checkout completion
-                if checkout_type == Some("addon") {
-                    let addon_type = metadata.get("addon_type").map(|s| s.as_str()).unwrap_or("");
-                    let quantity: u32 = metadata
-                        .get("quantity")
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(1);
+        let internal_sub_id = match internal_sub_id {
+            Some((id,)) => id,
+            None => {
+                // Addon purchases require an existing subscription
+                // This is an error condition that should not happen in normal flow
+                tracing::error!(
+                    org_id = %org_id,
+                    addon_type = %addon_type,
+                    session_id = %session.id,
+                    "Cannot process addon checkout: no active subscription found for org. \
+                     Addon purchases require an existing subscription. \
+                     This may indicate a checkout flow bug or data inconsistency."
+                );
+                return Err(BillingError::SubscriptionRequired(
+                    "Addon purchase requires an active subscription".to_string(),
+                ));
+            }
+        };
 
-                    if addon_type.is_empty() {
-                        tracing::error!(
-                            org_id = %org_id,
-                            "Addon checkout completed but no addon_type in metadata"
-                        );
-                        return Ok(());
-                    }
+        // Get the new subscription from the session to extract addon item info
+        if let Some(subscription_id) = &session.subscription {
+            let parsed_sub_id = subscription_id.id().parse().map_err(|e| {
+                tracing::error!("Failed to parse subscription ID: {}", e);
+                BillingError::SubscriptionNotFound(subscription_id.id().to_string())
+            })?;
+            let subscription =
+                stripe::Subscription::retrieve(self.stripe.inner(), &parsed_sub_id, &[]).await?;
 
+            // Note: We intentionally DO NOT sync this new subscription to DB
+            // as the org already has an existing subscription. The addon checkout
+            // creates a separate Stripe subscription which we track via subscription_addons.
+            tracing::info!(
+                org_id = %org_id,
+                new_stripe_subscription_id = %subscription.id,
+                "Addon checkout created new Stripe subscription (not syncing to main subscriptions table)"
+            );
+
+            // Find the addon price ID from config
+            let config_price_id = self.stripe.config().addon_price_id(addon_type);
+
+            // Find the subscription item for this addon
+            // First try to match by config price, then take the last item (addon)
+            let addon_item = subscription
+                .items
+                .data
+                .iter()
+                .find(|item| {
+                    item.price
+                        .as_ref()
+                        .map(|p| {
+                            let item_price = p.id.as_str();
+                            config_price_id
+                                .as_ref()
+                                .map(|pid| item_price == pid)
+                                .unwrap_or(false)
+                        })
+                        .unwrap_or(false)
+                })
+                // If not found by config, take the last item (likely the addon)
+                .or_else(|| subscription.items.data.last());
+
+            let stripe_item_id = addon_item.map(|item| item.id.to_string());
+
+            // Get price ID from the item or fall back to config
+            let price_id = addon_item
+                .and_then(|item| item.price.as_ref())
+                .map(|p| p.id.to_string())
+                .or(config_price_id)
+                .unwrap_or_else(|| format!("unknown_{}", addon_type));
+
+            // Get addon price cents
+            let addon_price_cents = crate::AddonType::from_str(addon_type)
+                .map(|a| a.price_cents())
+                .unwrap_or(0);
+
+            // Create the subscription_addons record
+            let result = CheckoutAddon {
+                org_id,
+                subscription_id: internal_sub_id,
+                addon_type,
+                stripe_item_id: stripe_item_id.as_deref(),
+                stripe_price_id: &price_id,
+                quantity: quantity as i32,
+                unit_price_cents: addon_price_cents,
+            }
+            .record(&self.pool)
+            .await;
+
+            match result {
+                Ok(_) => {
                     tracing::info!(
                         org_id = %org_id,
                         addon_type = %addon_type,
                         quantity = %quantity,
-                        "Processing addon checkout completion"
+                        "Successfully created subscription_addons record after checkout"
                     );
-
-                    // Get the EXISTING internal subscription ID first
-                    // (Don't sync the new addon subscription - we use the existing one)
-                    let internal_sub_id: Option<(Uuid,)> = sqlx::query_as(
-                        "SELECT id FROM subscriptions WHERE org_id = $1 AND status = 'active' LIMIT 1"
-                    )
-                    .bind(org_id)
-                    .fetch_optional(&self.pool)
-                    .await?;
-
-                    let internal_sub_id = match internal_sub_id {
-                        Some((id,)) => id,
-                        None => {
-                            // Addon purchases require an existing subscription
-                            // This is an error condition that should not happen in normal flow
-                            tracing::error!(
-                                org_id = %org_id,
-                                addon_type = %addon_type,
-                                session_id = %session.id,
-                                "Cannot process addon checkout: no active subscription found for org. \
-                                 Addon purchases require an existing subscription. \
-                                 This may indicate a checkout flow bug or data inconsistency."
-                            );
-                            return Err(BillingError::SubscriptionRequired(
-                                "Addon purchase requires an active subscription".to_string(),
-                            ));
-                        }
-                    };
-
-                    // Get the new subscription from the session to extract addon item info
-                    if let Some(subscription_id) = session.subscription {
-                        let parsed_sub_id = subscription_id.id().parse().map_err(|e| {
-                            tracing::error!("Failed to parse subscription ID: {}", e);
-                            BillingError::SubscriptionNotFound(subscription_id.id().to_string())
-                        })?;
-                        let subscription = stripe::Subscription::retrieve(
-                            self.stripe.inner(),
-                            &parsed_sub_id,
-                            &[],
-                        )
-                        .await?;
-
-                        // Note: We intentionally DO NOT sync this new subscription to DB
-                        // as the org already has an existing subscription. The addon checkout
-                        // creates a separate Stripe subscription which we track via subscription_addons.
-                        tracing::info!(
-                            org_id = %org_id,
-                            new_stripe_subscription_id = %subscription.id,
-                            "Addon checkout created new Stripe subscription (not syncing to main subscriptions table)"
-                        );
-
-                        // Find the addon price ID from config
-                        let config_price_id = self.stripe.config().addon_price_id(addon_type);
-
-                        // Find the subscription item for this addon
-                        // First try to match by config price, then take the last item (addon)
-                        let addon_item = subscription
-                            .items
-                            .data
-                            .iter()
-                            .find(|item| {
-                                item.price
-                                    .as_ref()
-                                    .map(|p| {
-                                        let item_price = p.id.as_str();
-                                        config_price_id
-                                            .as_ref()
-                                            .map(|pid| item_price == pid)
-                                            .unwrap_or(false)
-                                    })
-                                    .unwrap_or(false)
-                            })
-                            // If not found by config, take the last item (likely the addon)
-                            .or_else(|| subscription.items.data.last());
-
-                        let stripe_item_id = addon_item.map(|item| item.id.to_string());
-
-                        // Get price ID from the item or fall back to config
-                        let price_id = addon_item
-                            .and_then(|item| item.price.as_ref())
-                            .map(|p| p.id.to_string())
-                            .or(config_price_id)
-                            .unwrap_or_else(|| format!("unknown_{}", addon_type));
-
-                        // Get addon price cents
-                        let addon_price_cents = crate::AddonType::from_str(addon_type)
-                            .map(|a| a.price_cents())
-                            .unwrap_or(0);
-
-                        // Create the subscription_addons record
-                        let result = sqlx::query(
-                            r#"
-                            INSERT INTO subscription_addons (
-                                org_id, subscription_id, addon_type, stripe_item_id, stripe_price_id,
-                                status, metadata, quantity, unit_price_cents
-                            )
-                            VALUES ($1, $2, $3, $4, $5, 'active', '{}'::jsonb, $6, $7)
-                            ON CONFLICT (org_id, addon_type)
-                            DO UPDATE SET
-                                status = 'active',
-                                stripe_item_id = EXCLUDED.stripe_item_id,
-                                stripe_price_id = EXCLUDED.stripe_price_id,
-                                subscription_id = EXCLUDED.subscription_id,
-                                quantity = CASE
-                                    WHEN subscription_addons.status = 'active'
-                                    THEN subscription_addons.quantity + EXCLUDED.quantity
-                                    ELSE EXCLUDED.quantity
-                                END,
-                                canceled_at = NULL,
-                                updated_at = NOW()
-                            "#
-                        )
-                        .bind(org_id)
-                        .bind(internal_sub_id)
-                        .bind(addon_type)
-                        .bind(stripe_item_id.as_ref())
-                        .bind(&price_id)
-                        .bind(quantity as i32)
-                        .bind(addon_price_cents)
-                        .execute(&self.pool)
-                        .await;
-
-                        match result {
-                            Ok(_) => {
-                                tracing::info!(
-                                    org_id = %org_id,
-                                    addon_type = %addon_type,
-                                    quantity = %quantity,
-                                    "Successfully created subscription_addons record after checkout"
-                                );
-                            }
-                            Err(e) => {
-                                tracing::error!(
-                                    org_id = %org_id,
-                                    addon_type = %addon_type,
-                                    error = %e,
-                                    "Failed to create subscription_addons record after checkout"
-                                );
-                            }
-                        }
-                    }
-
-                    return Ok(());
                 }
-
-                // Get the subscription from the session (for subscription checkouts)
-                if let Some(subscription_id) = session.subscription {
-                    let parsed_sub_id = subscription_id.id().parse().map_err(|e| {
-                        tracing::error!("Failed to parse subscription ID: {}", e);
-                        BillingError::SubscriptionNotFound(subscription_id.id().to_string())
-                    })?;
-                    let subscription =
-                        stripe::Subscription::retrieve(self.stripe.inner(), &parsed_sub_id, &[])
-                            .await?;
-
-                    let sub_service =
-                        SubscriptionService::new(self.stripe.clone(), self.pool.clone());
-                    sub_service
-                        .sync_subscription_to_db(org_id, &subscription)
-                        .await?;
-
-                    tracing::info!(
+                Err(e) => {
+                    tracing::error!(
                         org_id = %org_id,
-                        subscription_id = %subscription.id,
-                        "Checkout completed, subscription created"
+                        addon_type = %addon_type,
+                        error = %e,
+                        "Failed to create subscription_addons record after checkout"
                     );
                 }
-
-                // Mark any overages that were included in this upgrade checkout as paid
-                // This handles the case where overages were added as invoice items
-                let overage_service = OverageService::new(self.stripe.clone(), self.pool.clone());
-                match overage_service.mark_upgrade_overages_paid(org_id).await {
-                    Ok(count) if count > 0 => {
-                        tracing::info!(
-                            org_id = %org_id,
-                            overages_marked_paid = count,
-                            "Marked upgrade overages as paid after checkout completion"
-                        );
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            org_id = %org_id,
-                            error = %e,
-                            "Failed to mark upgrade overages as paid"
-                        );
-                    }
-                    _ => {}
-                }
             }
         }
 
         Ok(())
     }
 
-    fn extract_subscription(&self, event: Event) -> BillingResult<Subscription> {
-        match event.data.object {
-            EventObject::Subscription(subscription) => Ok(subscription),
-            _ => Err(BillingError::WebhookEventNotSupported(
-                "Expected Subscription".to_string(),
-            )),
-        }
-    }
+    /// Regular subscription checkout: sync the new subscription and settle included overages
+    async fn complete_new_subscription(
+        &self,
+        org_id: Uuid,
+        session: &CheckoutSession,
+    ) -> BillingResult<()> {
+        // Get the subscription from the session (for subscription checkouts)
+        if let Some(subscription_id) = &session.subscription {
+            let parsed_sub_id = subscription_id.id().parse().map_err(|e| {
+                tracing::error!("Failed to parse subscription ID: {}", e);
+                BillingError::SubscriptionNotFound(subscription_id.id().to_string())
+            })?;
+            let subscription =
+                stripe::Subscription::retrieve(self.stripe.inner(), &parsed_sub_id, &[]).await?;
+
+            let sub_service = SubscriptionService::new(self.stripe.clone(), self.pool.clone());
+            sub_service
+                .sync_subscription_to_db(org_id, &subscription)
+                .await?;
+
+            let first_month_free = session
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("first_month_free"))
+                .is_some_and(|v| v == "true");
+            if first_month_free {
+                mark_first_month_free(&self.pool, org_id, subscription.id.as_str()).await?;
+            }
+
+            tracing::info!(
+                org_id = %org_id,
+                subscription_id = %subscription.id,
+                first_month_free = first_month_free,
+                "Checkout completed, subscription created"
+            );
+        }
+
+        // Mark any overages that were included in this upgrade checkout as paid
+        // This handles the case where overages were added as invoice items
+        let overage_service = OverageService::new(self.stripe.clone(), self.pool.clone());
+        match overage_service.mark_upgrade_overages_paid(org_id).await {
+            Ok(count) if count > 0 => {
+                tracing::info!(
+                    org_id = %org_id,
+                    overages_marked_paid = count,
+                    "Marked upgrade overages as paid after checkout completion"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(
+                    org_id = %org_id,
+                    error = %e,
+                    "Failed to mark upgrade overages as paid"
+                );
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Deal with members beyond the new tier's seat limit and notify them
+    ///
+    /// With a grace period configured, excess members are warned and the
+    /// worker suspends them once it ends; otherwise they are suspended now.
+    /// Failures are logged rather than propagated: the plan change has already
+    /// happened in Stripe, so the webhook should not be retried for this.
+    async fn reconcile_members(&self, org_id: Uuid, new_tier: &str) {
+        let member_service = MemberSuspensionService::new(self.pool.clone());
+        let grace = get_suspension_grace_period();
+        if grace.is_positive() {
+            match member_service
+                .schedule_excess_member_suspension(org_id, new_tier, "plan_downgrade", grace)
+                .await
+            {
+                Ok(Some(pending)) if pending.newly_scheduled => {
+                    for member in &pending.members {
+                        if let Err(e) = self
+                            .email
+                            .send_member_suspension_pending(
+                                &member.email,
+                                new_tier,
+                                pending.suspend_after,
+                            )
+                            .await
+                        {
+                            tracing::error!(
+                                error = %e,
+                                email = %member.email,
+                                "Failed to send pending member suspension email"
+                            );
+                        }
+                    }
+                }
+                Ok(_) => {
+                    // No excess members, or already warned
+                }
+                Err(e) => {
+                    tracing::error!(
+                        org_id = %org_id,
+                        error = %e,
+                        "Failed to schedule suspension of excess members after downgrade"
+                    );
+                }
+            }
+            return;
+        }
+
+        match member_service
+            .suspend_excess_members(org_id, new_tier, "plan_downgrade")
+            .await
+        {
+            Ok(result) if result.suspended_count > 0 => {
+                tracing::info!(
+                    org_id = %org_id,
+                    suspended_count = result.suspended_count,
+                    new_tier = %new_tier,
+                    "Suspended excess members after downgrade"
+                );
+
+                // Send notification to suspended members
+                for member in &result.suspended_members {
+                    if let Err(e) = self
+                        .email
+                        .send_member_suspended(&member.email, new_tier)
+                        .await
+                    {
+                        tracing::error!(
+                            error = %e,
+                            email = %member.email,
+                            "Failed to send member suspension email"
+                        );
+                    }
+                }
+            }
+            Ok(_) => {
+                // No excess members to suspend
+            }
+            Err(e) => {
+                tracing::error!(
+                    org_id = %org_id,
+                    error = %e,
+                    "Failed to suspend excess members after downgrade"
+                );
+            }
+        }
+    }
+
+    fn extract_subscription(&self, event: Event) -> BillingResult<Subscription> {
+        match event.data.object {
+            EventObject::Subscription(subscription) => Ok(subscription),
+            _ => Err(BillingError::WebhookEventNotSupported(
+                "Expected Subscription".to_string(),
+            )),
+        }
+    }
 
     fn extract_invoice(&self, event: Event) -> BillingResult<Invoice> {
         match event.data.object {
@@ -1518,22 +2323,27 @@ impl WebhookHandler {
             .ok_or(BillingError::CustomerNotFound(customer_id))
     }
 
-    /// Get the org owner's email and org name for sending notifications
+    /// Get the primary owner's email and org name for sending notifications
     async fn get_org_owner_email(&self, org_id: Uuid) -> BillingResult<Option<(String, String)>> {
-        let result: Option<(String, String)> = sqlx::query_as(
-            r#"
-            SELECT u.email, o.name
-            FROM users u
-            JOIN organizations o ON o.id = u.org_id
-            WHERE u.org_id = $1 AND u.role = 'owner'
-            LIMIT 1
-            "#,
-        )
-        .bind(org_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        let owner = get_primary_owner(&self.pool, org_id).await?;
+        Ok(owner.map(|o| (o.email, o.org_name)))
+    }
+
+    /// Recipients for critical alerts: every owner if configured, else the primary owner
+    async fn get_critical_alert_recipients(
+        &self,
+        org_id: Uuid,
+    ) -> BillingResult<Vec<(String, String)>> {
+        if !self.config.notify_all_owners_on_critical {
+            return Ok(self
+                .get_org_owner_email(org_id)
+                .await?
+                .into_iter()
+                .collect());
+        }
 
-        Ok(result)
+        let owners = list_org_owners(&self.pool, org_id).await?;
+        Ok(owners.into_iter().map(|o| (o.email, o.org_name)).collect())
     }
 
     /// Store invoice record with line items and grace period tracking
@@ -1561,8 +2371,14 @@ impl WebhookHandler {
             .period_end
             .map(|t| OffsetDateTime::from_unix_timestamp(t).unwrap_or(OffsetDateTime::now_utc()));
 
-        // Calculate grace period (due_date + 30 days)
-        let grace_period_ends_at = due_date.map(|d| d + time::Duration::days(30));
+        // Calculate grace period (due_date + the org tier's configured days)
+        let tier: Option<String> =
+            sqlx::query_scalar("SELECT subscription_tier FROM organizations WHERE id = $1")
+                .bind(org_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let grace_days = grace_period_days_for_tier(tier.as_deref().unwrap_or("free"));
+        let grace_period_ends_at = due_date.map(|d| d + time::Duration::days(grace_days));
 
         // Extract customer and subscription IDs
         let stripe_customer_id = match &invoice.customer {
@@ -1598,6 +2414,7 @@ impl WebhookHandler {
                 paid_at = EXCLUDED.paid_at,
                 invoice_pdf_url = EXCLUDED.invoice_pdf_url,
                 hosted_invoice_url = EXCLUDED.hosted_invoice_url,
+                billing_reason = COALESCE(EXCLUDED.billing_reason, invoices.billing_reason),
                 updated_at = NOW()
             RETURNING id
             "#
@@ -1611,7 +2428,7 @@ impl WebhookHandler {
         .bind(invoice.currency.as_ref().map(|c| c.to_string()).unwrap_or_else(|| "usd".to_string()))
         .bind(status)
         .bind(invoice.description.as_ref())
-        .bind(invoice.billing_reason.as_ref().map(|r| format!("{:?}", r)))
+        .bind(invoice_billing_reason(invoice))
         .bind(period_start)
         .bind(period_end)
         .bind(due_date)
@@ -1641,6 +2458,7 @@ impl WebhookHandler {
             invoice_id = %invoice_id,
             stripe_invoice_id = %invoice.id,
             line_items_count = invoice.lines.as_ref().map(|l| l.data.len()).unwrap_or(0),
+            zero_amount = is_zero_amount_invoice(invoice),
             "Stored invoice with line items"
         );
 
@@ -1927,8 +2745,33 @@ impl WebhookHandler {
         Ok(())
     }
 
+    /// Find the organization billed by a charge, via the charge's customer
+    async fn org_for_charge(&self, charge_id: &str) -> Option<Uuid> {
+        let charge = stripe::Charge::retrieve(
+            self.stripe.inner(),
+            &charge_id.parse().unwrap_or_default(),
+            &[],
+        )
+        .await
+        .ok()?;
+
+        let customer_id = match charge.customer? {
+            stripe::Expandable::Id(id) => id.to_string(),
+            stripe::Expandable::Object(c) => c.id.to_string(),
+        };
+
+        sqlx::query_scalar("SELECT id FROM organizations WHERE stripe_customer_id = $1")
+            .bind(&customer_id)
+            .fetch_optional(&self.pool)
+            .await
+            .ok()
+            .flatten()
+    }
+
     /// Handle charge.dispute.created - customer initiated a chargeback
     /// CRITICAL: Disputes can result in significant financial penalties
+    ///
+    /// New MCP usage for the org is paused until the dispute is resolved.
     async fn handle_charge_dispute_created(&self, event: Event) -> BillingResult<()> {
         let event_id = event.id.to_string();
         let dispute = match event.data.object {
@@ -1941,8 +2784,8 @@ impl WebhookHandler {
         };
 
         let charge_id = match &dispute.charge {
-            stripe::Expandable::Id(id) => Some(id.to_string()),
-            stripe::Expandable::Object(ch) => Some(ch.id.to_string()),
+            stripe::Expandable::Id(id) => id.to_string(),
+            stripe::Expandable::Object(ch) => ch.id.to_string(),
         };
 
         let amount = dispute.amount as i32;
@@ -1955,101 +2798,224 @@ impl WebhookHandler {
         // This is a CRITICAL alert - disputes are serious
         tracing::error!(
             dispute_id = %dispute.id,
-            charge_id = ?charge_id,
+            charge_id = %charge_id,
             amount_cents = amount,
             reason = %reason,
             status = ?dispute.status,
             "CRITICAL: Charge dispute (chargeback) created!"
         );
 
-        // Try to find the customer and org
-        if let Some(charge_id_str) = &charge_id {
-            // Look up the charge to find the customer
-            if let Ok(charge) = stripe::Charge::retrieve(
-                self.stripe.inner(),
-                &charge_id_str.parse().unwrap_or_default(),
-                &[],
+        let Some(id) = self.org_for_charge(&charge_id).await else {
+            tracing::warn!(
+                dispute_id = %dispute.id,
+                charge_id = %charge_id,
+                "No organization found for disputed charge"
+            );
+            return Ok(());
+        };
+
+        // Record the dispute
+        sqlx::query(
+            r#"
+            INSERT INTO billing_disputes (
+                org_id, stripe_dispute_id, stripe_charge_id,
+                amount_cents, reason, status, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, NOW())
+            ON CONFLICT (stripe_dispute_id) DO UPDATE SET
+                status = EXCLUDED.status,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(id)
+        .bind(dispute.id.to_string())
+        .bind(&charge_id)
+        .bind(amount)
+        .bind(&reason)
+        .bind(format!("{:?}", dispute.status))
+        .execute(&self.pool)
+        .await
+        .ok(); // Best effort - don't fail webhook for audit log failure
+
+        // Stop new usage pending resolution
+        let spend_cap_service = SpendCapService::new(self.pool.clone(), self.email.clone());
+        let usage_paused = spend_cap_service.pause_org_for_dispute(id).await?;
+
+        // Notify org owner(s) about the dispute
+        let recipients = self
+            .get_critical_alert_recipients(id)
+            .await
+            .unwrap_or_default();
+        for (email, org_name) in recipients {
+            if let Err(e) = self
+                .email
+                .send_dispute_alert(&email, &org_name, amount, &reason)
+                .await
+            {
+                tracing::error!(error = %e, "Failed to send dispute alert email");
+            }
+        }
+
+        // Log billing events
+        if let Err(e) = self
+            .event_logger
+            .log_event(
+                BillingEventBuilder::new(id, BillingEventType::DisputeCreated)
+                    .data(serde_json::json!({
+                        "amount_cents": amount,
+                        "reason": reason,
+                        "status": format!("{:?}", dispute.status),
+                        "dispute_id": dispute.id.to_string(),
+                        "charge_id": charge_id,
+                        "usage_paused": usage_paused,
+                    }))
+                    .stripe_event(&event_id)
+                    .actor_type(ActorType::Stripe),
             )
             .await
+        {
+            tracing::warn!(error = %e, "Failed to log dispute created event");
+        }
+
+        if usage_paused {
+            if let Err(e) = self
+                .event_logger
+                .log_event(
+                    BillingEventBuilder::new(id, BillingEventType::OrgPaused)
+                        .data(serde_json::json!({
+                            "reason": "dispute",
+                            "dispute_id": dispute.id.to_string(),
+                        }))
+                        .stripe_event(&event_id)
+                        .actor_type(ActorType::Stripe),
+                )
+                .await
             {
-                if let Some(customer) = &charge.customer {
-                    let customer_id = match customer {
-                        stripe::Expandable::Id(id) => id.to_string(),
-                        stripe::Expandable::Object(c) => c.id.to_string(),
-                    };
+                tracing::warn!(error = %e, "Failed to log dispute pause event");
+            }
+        }
 
-                    if let Ok(Some((id,))) = sqlx::query_as::<_, (Uuid,)>(
-                        "SELECT id FROM organizations WHERE stripe_customer_id = $1",
-                    )
-                    .bind(&customer_id)
-                    .fetch_optional(&self.pool)
-                    .await
-                    {
-                        // Record the dispute
-                        sqlx::query(
-                            r#"
-                            INSERT INTO billing_disputes (
-                                org_id, stripe_dispute_id, stripe_charge_id,
-                                amount_cents, reason, status, created_at
-                            )
-                            VALUES ($1, $2, $3, $4, $5, $6, NOW())
-                            ON CONFLICT (stripe_dispute_id) DO UPDATE SET
-                                status = EXCLUDED.status,
-                                updated_at = NOW()
-                            "#,
-                        )
-                        .bind(id)
-                        .bind(dispute.id.to_string())
-                        .bind(charge_id_str)
-                        .bind(amount)
-                        .bind(&reason)
-                        .bind(format!("{:?}", dispute.status))
-                        .execute(&self.pool)
-                        .await
-                        .ok(); // Best effort - don't fail webhook for audit log failure
-
-                        // Notify org owner about the dispute
-                        if let Ok(Some((email, org_name))) = self.get_org_owner_email(id).await {
-                            if let Err(e) = self
-                                .email
-                                .send_dispute_alert(&email, &org_name, amount, &reason)
-                                .await
-                            {
-                                tracing::error!(error = %e, "Failed to send dispute alert email");
-                            }
-                        }
+        tracing::warn!(
+            org_id = %id,
+            dispute_id = %dispute.id,
+            usage_paused = usage_paused,
+            "Dispute recorded for organization"
+        );
 
-                        // Log billing event
-                        if let Err(e) = self
-                            .event_logger
-                            .log_event(
-                                BillingEventBuilder::new(id, BillingEventType::DisputeCreated)
-                                    .data(serde_json::json!({
-                                        "amount_cents": amount,
-                                        "reason": reason,
-                                        "status": format!("{:?}", dispute.status),
-                                        "dispute_id": dispute.id.to_string(),
-                                        "charge_id": charge_id_str,
-                                    }))
-                                    .stripe_event(&event_id)
-                                    .actor_type(ActorType::Stripe),
-                            )
-                            .await
-                        {
-                            tracing::warn!(error = %e, "Failed to log dispute created event");
-                        }
+        Ok(())
+    }
 
-                        tracing::warn!(
-                            org_id = %id,
-                            dispute_id = %dispute.id,
-                            "Dispute recorded for organization"
-                        );
-                    }
+    /// Handle charge.dispute.closed - dispute resolved (won, lost or inquiry closed)
+    ///
+    /// Lifts the dispute pause if the dispute went our way and no other dispute
+    /// for the org is still open. A lost dispute leaves the org paused for
+    /// manual review.
+    async fn handle_charge_dispute_closed(&self, event: Event) -> BillingResult<()> {
+        let event_id = event.id.to_string();
+        let dispute = match event.data.object {
+            EventObject::Dispute(dispute) => dispute,
+            _ => {
+                return Err(BillingError::WebhookEventNotSupported(
+                    "Expected Dispute".to_string(),
+                ))
+            }
+        };
+
+        let dispute_id = dispute.id.to_string();
+        let charge_id = match &dispute.charge {
+            stripe::Expandable::Id(id) => id.to_string(),
+            stripe::Expandable::Object(ch) => ch.id.to_string(),
+        };
+
+        // Prefer the org recorded when the dispute opened
+        let recorded_org: Option<Uuid> =
+            sqlx::query_scalar("SELECT org_id FROM billing_disputes WHERE stripe_dispute_id = $1")
+                .bind(&dispute_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let org_id = match recorded_org {
+            Some(id) => id,
+            None => match self.org_for_charge(&charge_id).await {
+                Some(id) => id,
+                None => {
+                    tracing::warn!(
+                        dispute_id = %dispute_id,
+                        charge_id = %charge_id,
+                        "No organization found for closed dispute"
+                    );
+                    return Ok(());
                 }
+            },
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE billing_disputes SET
+                status = $2,
+                resolution = $3,
+                resolved_at = NOW(),
+                updated_at = NOW()
+            WHERE stripe_dispute_id = $1
+            "#,
+        )
+        .bind(&dispute_id)
+        .bind(format!("{:?}", dispute.status))
+        .bind(dispute.status.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        let usage_resumed = if dispute_lifts_pause(dispute.status) {
+            SpendCapService::new(self.pool.clone(), self.email.clone())
+                .unpause_org_after_dispute(org_id)
+                .await?
+        } else {
+            false
+        };
+
+        if let Err(e) = self
+            .event_logger
+            .log_event(
+                BillingEventBuilder::new(org_id, BillingEventType::DisputeResolved)
+                    .data(serde_json::json!({
+                        "amount_cents": dispute.amount,
+                        "status": dispute.status.as_str(),
+                        "dispute_id": dispute_id,
+                        "charge_id": charge_id,
+                        "usage_resumed": usage_resumed,
+                    }))
+                    .stripe_event(&event_id)
+                    .actor_type(ActorType::Stripe),
+            )
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to log dispute resolved event");
+        }
+
+        if usage_resumed {
+            if let Err(e) = self
+                .event_logger
+                .log_event(
+                    BillingEventBuilder::new(org_id, BillingEventType::OrgUnpaused)
+                        .data(serde_json::json!({
+                            "reason": "dispute_won",
+                            "dispute_id": dispute_id,
+                        }))
+                        .stripe_event(&event_id)
+                        .actor_type(ActorType::Stripe),
+                )
+                .await
+            {
+                tracing::warn!(error = %e, "Failed to log dispute unpause event");
             }
         }
 
-        // TODO: Consider implementing automatic response or admin notification system
+        tracing::info!(
+            org_id = %org_id,
+            dispute_id = %dispute_id,
+            status = %dispute.status.as_str(),
+            usage_resumed = usage_resumed,
+            "Dispute closed"
+        );
 
         Ok(())
     }
@@ -2262,48 +3228,118 @@ impl WebhookHandler {
     async fn handle_customer_deleted(&self, event: Event) -> BillingResult<()> {
         let event_id = event.id.to_string();
         let customer = self.extract_customer(event)?;
+        let customer_id = customer.id.to_string();
+        let metadata_org_id = customer
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("org_id"))
+            .and_then(|id| Uuid::parse_str(id).ok());
 
-        // Find org by customer ID
-        let org_id: Option<(Uuid,)> =
-            sqlx::query_as("SELECT id FROM organizations WHERE stripe_customer_id = $1")
-                .bind(customer.id.to_string())
-                .fetch_optional(&self.pool)
-                .await
-                .map_err(|e| BillingError::Database(e.to_string()))?;
-
-        if let Some((org_id,)) = org_id {
-            // Clear the Stripe customer ID from org (customer was deleted in Stripe)
-            sqlx::query(
-                "UPDATE organizations SET stripe_customer_id = NULL, updated_at = NOW() WHERE id = $1"
-            )
-            .bind(org_id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| BillingError::Database(e.to_string()))?;
+        // Find the org by customer ID, falling back to metadata so we can tell
+        // when the org has already moved to a replacement customer
+        let org: Option<(Uuid, Option<String>, String)> = sqlx::query_as(
+            r#"
+            SELECT id, stripe_customer_id, subscription_tier
+            FROM organizations
+            WHERE stripe_customer_id = $1 OR id = $2
+            ORDER BY (stripe_customer_id = $1) DESC NULLS LAST
+            LIMIT 1
+            "#,
+        )
+        .bind(&customer_id)
+        .bind(metadata_org_id)
+        .fetch_optional(&self.pool)
+        .await?;
 
-            // Log billing event
-            if let Err(e) = self
-                .event_logger
-                .log_event(
-                    BillingEventBuilder::new(org_id, BillingEventType::CustomerDeleted)
-                        .data(serde_json::json!({
-                            "customer_id": customer.id.to_string(),
-                        }))
-                        .stripe_event(&event_id)
-                        .actor_type(ActorType::Stripe),
-                )
-                .await
-            {
-                tracing::warn!(error = %e, "Failed to log customer deleted event");
+        let Some((org_id, current_customer_id, previous_tier)) = org else {
+            return Ok(());
+        };
+
+        let downgrade = match customer_deletion_action(
+            &customer_id,
+            current_customer_id.as_deref(),
+            self.config.downgrade_on_customer_deleted,
+        ) {
+            CustomerDeletionAction::Detach { downgrade } => downgrade,
+            CustomerDeletionAction::Replaced => {
+                tracing::info!(
+                    org_id = %org_id,
+                    customer_id = %customer_id,
+                    "Deleted Stripe customer was already replaced, ignoring"
+                );
+                return Ok(());
             }
+            CustomerDeletionAction::AlreadyDetached => return Ok(()),
+        };
 
-            tracing::warn!(
+        let mut tx = self.pool.begin().await?;
+
+        // Only detach if the org still points at the deleted customer; a
+        // replacement linked since the lookup must not be clobbered
+        let detached = sqlx::query(
+            r#"
+            UPDATE organizations SET
+                stripe_customer_id = NULL,
+                subscription_tier = CASE WHEN $3 THEN 'free' ELSE subscription_tier END,
+                updated_at = NOW()
+            WHERE id = $1 AND stripe_customer_id = $2
+            "#,
+        )
+        .bind(org_id)
+        .bind(&customer_id)
+        .bind(downgrade)
+        .execute(&mut *tx)
+        .await?;
+
+        if detached.rows_affected() == 0 {
+            tracing::info!(
                 org_id = %org_id,
-                customer_id = %customer.id,
-                "Stripe customer deleted - cleared customer ID from organization"
+                customer_id = %customer_id,
+                "Organization relinked during customer deletion, ignoring"
             );
+            return Ok(());
         }
 
+        // The customer's subscriptions are gone in Stripe along with it
+        let canceled = sqlx::query(
+            r#"
+            UPDATE subscriptions SET status = 'canceled', canceled_at = NOW(), updated_at = NOW()
+            WHERE org_id = $1 AND status <> 'canceled'
+            "#,
+        )
+        .bind(org_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        // Log billing event
+        if let Err(e) = self
+            .event_logger
+            .log_event(
+                BillingEventBuilder::new(org_id, BillingEventType::CustomerDeleted)
+                    .data(serde_json::json!({
+                        "customer_id": customer_id,
+                        "previous_tier": previous_tier,
+                        "downgraded": downgrade,
+                        "subscriptions_canceled": canceled.rows_affected(),
+                    }))
+                    .stripe_event(&event_id)
+                    .actor_type(ActorType::Stripe),
+            )
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to log customer deleted event");
+        }
+
+        tracing::warn!(
+            org_id = %org_id,
+            customer_id = %customer_id,
+            previous_tier = %previous_tier,
+            downgraded = downgrade,
+            "Stripe customer deleted - detached from organization"
+        );
+
         Ok(())
     }
 
@@ -2328,7 +3364,7 @@ impl WebhookHandler {
             r#"
             SELECT id, stripe_event_id, event_type, event_timestamp,
                    processing_result, processing_started_at, error_message,
-                   created_at
+                   attempt_count, next_retry_at, created_at
             FROM stripe_webhook_events
             WHERE processing_result IN ('error', 'processing')
             ORDER BY created_at DESC
@@ -2356,7 +3392,7 @@ impl WebhookHandler {
                 r#"
                     SELECT id, stripe_event_id, event_type, event_timestamp,
                            processing_result, processing_started_at, error_message,
-                           created_at
+                           attempt_count, next_retry_at, created_at
                     FROM stripe_webhook_events
                     WHERE processing_result = $1
                     ORDER BY created_at DESC
@@ -2373,7 +3409,7 @@ impl WebhookHandler {
                 r#"
                     SELECT id, stripe_event_id, event_type, event_timestamp,
                            processing_result, processing_started_at, error_message,
-                           created_at
+                           attempt_count, next_retry_at, created_at
                     FROM stripe_webhook_events
                     ORDER BY created_at DESC
                     LIMIT $1 OFFSET $2
@@ -2496,6 +3532,132 @@ impl WebhookHandler {
         })
     }
 
+    /// Replay the stored original delivery of a webhook event
+    ///
+    /// Unlike `replay_webhook`, which re-fetches the event from Stripe, this
+    /// re-runs the exact payload we received. Its signature is re-verified
+    /// (the timestamp is not, it is expected to be old), so deliveries stored
+    /// before a webhook secret rotation can't be replayed this way. The record
+    /// is reset to `processing` before dispatch; an event currently being
+    /// processed is refused.
+    pub async fn replay_event(&self, stripe_event_id: &str) -> BillingResult<WebhookReplayResult> {
+        #[derive(sqlx::FromRow)]
+        struct StoredDelivery {
+            id: Uuid,
+            processing_result: String,
+            error_message: Option<String>,
+            raw_payload: Option<String>,
+            signature_header: Option<String>,
+        }
+
+        let stored: StoredDelivery = sqlx::query_as(
+            r#"
+            SELECT id, processing_result, error_message, raw_payload, signature_header
+            FROM stripe_webhook_events
+            WHERE stripe_event_id = $1
+            "#,
+        )
+        .bind(stripe_event_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            BillingError::NotFound(format!(
+                "Webhook event {} not found in database",
+                stripe_event_id
+            ))
+        })?;
+
+        let (Some(payload), Some(signature)) = (&stored.raw_payload, &stored.signature_header)
+        else {
+            return Err(BillingError::InvalidInput(format!(
+                "No stored payload for webhook event {}; replay it from Stripe instead",
+                stripe_event_id
+            )));
+        };
+
+        verify_signature(payload, signature, &self.stripe.config().webhook_secret)?;
+        let event: Event = serde_json::from_str(payload).map_err(|e| {
+            BillingError::Internal(format!(
+                "Stored webhook payload is not a valid event: {}",
+                e
+            ))
+        })?;
+        if event.id.as_str() != stripe_event_id {
+            return Err(BillingError::Internal(format!(
+                "Stored payload for {} holds event {}",
+                stripe_event_id, event.id
+            )));
+        }
+
+        // Reset to processing, unless another worker is actively handling it
+        let reset = sqlx::query(
+            r#"
+            UPDATE stripe_webhook_events
+            SET processing_result = 'processing',
+                processing_started_at = NOW(),
+                error_message = CONCAT('Replay of stored payload initiated. Previous status: ', $2, '. Previous error: ', COALESCE($3, 'none'))
+            WHERE stripe_event_id = $1
+              AND NOT (
+                  processing_result = 'processing'
+                  AND processing_started_at >= NOW() - ($4 || ' minutes')::INTERVAL
+              )
+            "#,
+        )
+        .bind(stripe_event_id)
+        .bind(&stored.processing_result)
+        .bind(&stored.error_message)
+        .bind(PROCESSING_TIMEOUT_MINUTES)
+        .execute(&self.pool)
+        .await?;
+
+        if reset.rows_affected() == 0 {
+            return Err(BillingError::InvalidInput(format!(
+                "Webhook event {} is currently being processed",
+                stripe_event_id
+            )));
+        }
+
+        let process_result = self.process_event_internal(&event).await;
+
+        let (new_status, new_error) = match &process_result {
+            Ok(()) => ("success".to_string(), None),
+            Err(e) => ("error".to_string(), Some(e.to_string())),
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE stripe_webhook_events
+            SET processing_result = $1,
+                error_message = $2
+            WHERE stripe_event_id = $3
+            "#,
+        )
+        .bind(&new_status)
+        .bind(&new_error)
+        .bind(stripe_event_id)
+        .execute(&self.pool)
+        .await?;
+
+        tracing::info!(
+            stripe_event_id = %stripe_event_id,
+            previous_status = %stored.processing_result,
+            new_status = %new_status,
+            success = process_result.is_ok(),
+            "Stored webhook payload replayed"
+        );
+
+        Ok(WebhookReplayResult {
+            record_id: stored.id,
+            stripe_event_id: stripe_event_id.to_string(),
+            event_type: event_name(event.type_),
+            previous_status: stored.processing_result,
+            previous_error: stored.error_message,
+            new_status,
+            new_error,
+            success: process_result.is_ok(),
+        })
+    }
+
     /// Replay all failed webhooks (with optional limit)
     pub async fn replay_all_failed(
         &self,
@@ -2577,6 +3739,34 @@ impl WebhookHandler {
     }
 }
 
+/// Billing reason stored on `invoices.billing_reason`
+///
+/// Uses Stripe's snake_case values (`subscription_cycle`, `subscription_update`,
+/// `manual`, ...). On-demand overage invoices are Stripe `manual` invoices but
+/// are recorded as `overage_payment`, matching Pay Now checkouts.
+pub fn invoice_billing_reason(invoice: &Invoice) -> Option<String> {
+    if invoice
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("invoice_type"))
+        .is_some_and(|t| t == "overage_invoice_now")
+    {
+        return Some("overage_payment".to_string());
+    }
+
+    invoice.billing_reason.map(|r| r.as_str().to_string())
+}
+
+/// Check whether an invoice moved no money (fully credited, or a $0 addon-only base)
+///
+/// Keyed on `amount_due`: an invoice settled from the customer's credit
+/// balance has a non-zero total but nothing due. Such invoices are still
+/// stored for billing history, but must not trigger customer-facing payment
+/// emails.
+fn is_zero_amount_invoice(invoice: &Invoice) -> bool {
+    invoice.amount_due.unwrap_or(0) == 0
+}
+
 /// Stored webhook event record
 #[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
 pub struct WebhookEventRecord {
@@ -2587,6 +3777,10 @@ pub struct WebhookEventRecord {
     pub processing_result: String,
     pub processing_started_at: Option<OffsetDateTime>,
     pub error_message: Option<String>,
+    /// Processing attempts since first received
+    pub attempt_count: i32,
+    /// When a failed event may next be retried
+    pub next_retry_at: Option<OffsetDateTime>,
     pub created_at: OffsetDateTime,
 }
 
@@ -2602,3 +3796,489 @@ pub struct WebhookReplayResult {
     pub new_error: Option<String>,
     pub success: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::StripeConfig;
+
+    fn invoice_with(total: Option<i64>, amount_due: Option<i64>) -> Invoice {
+        Invoice {
+            total,
+            amount_due,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_zero_amount_invoice_detected() {
+        assert!(is_zero_amount_invoice(&invoice_with(Some(0), Some(0))));
+        // Missing amounts are treated as zero
+        assert!(is_zero_amount_invoice(&invoice_with(None, None)));
+    }
+
+    #[test]
+    fn test_paid_invoice_is_not_zero_amount() {
+        assert!(!is_zero_amount_invoice(&invoice_with(
+            Some(2900),
+            Some(2900)
+        )));
+        // Partly covered by credit: the remainder was still charged
+        assert!(!is_zero_amount_invoice(&invoice_with(
+            Some(2900),
+            Some(900)
+        )));
+    }
+
+    #[test]
+    fn test_default_reconciliation_events() {
+        let config = WebhookConfig::default();
+        assert!(config.reconciles_members_on(EventType::InvoicePaid));
+        assert!(config.reconciles_members_on(EventType::CustomerSubscriptionUpdated));
+        assert!(!config.reconciles_members_on(EventType::CustomerSubscriptionCreated));
+        // Critical alerts go to the primary owner only unless opted in
+        assert!(!config.notify_all_owners_on_critical);
+    }
+
+    #[test]
+    fn test_parse_event_types() {
+        let events =
+            parse_event_types(" customer.subscription.updated , bogus.event,,invoice.paid");
+        assert_eq!(
+            events,
+            vec![
+                EventType::CustomerSubscriptionUpdated,
+                EventType::InvoicePaid
+            ]
+        );
+        assert!(parse_event_types("").is_empty());
+    }
+
+    #[test]
+    fn test_clock_skew_computed_from_signature_timestamp() {
+        assert_eq!(
+            signature_timestamp("t=1700000000,v1=abc,v0=def"),
+            Some(1_700_000_000)
+        );
+        assert_eq!(signature_timestamp("v1=abc"), None);
+        assert_eq!(clock_skew_secs(1_700_000_090, 1_700_000_000), 90);
+        // Our clock behind Stripe's gives a negative skew
+        assert_eq!(clock_skew_secs(1_700_000_000, 1_700_000_030), -30);
+    }
+
+    #[test]
+    fn test_stored_payload_signature_reverifies_regardless_of_age() {
+        let secret = "whsec_test_secret";
+        let payload = r#"{"id":"evt_1","type":"invoice.paid"}"#;
+        // Signed long ago: only the HMAC matters here
+        let timestamp = 1_600_000_000;
+        let mut mac = HmacSha256::new_from_slice(b"test_secret").unwrap();
+        mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+        let signature = format!(
+            "t={},v1={}",
+            timestamp,
+            hex::encode(mac.finalize().into_bytes())
+        );
+
+        assert_eq!(
+            verify_signature(payload, &signature, secret).unwrap(),
+            timestamp
+        );
+        assert!(verify_signature(r#"{"id":"evt_2"}"#, &signature, secret).is_err());
+        assert!(verify_signature(payload, &signature, "whsec_other").is_err());
+        assert!(verify_signature(payload, "t=1600000000", secret).is_err());
+    }
+
+    #[test]
+    fn test_clock_skew_soft_warning() {
+        let config = WebhookConfig::default();
+        let tolerance = StripeConfig::for_tests().webhook_tolerance_secs;
+        assert_eq!(config.check_clock_skew(5, tolerance), ClockSkewCheck::Ok);
+        assert_eq!(
+            config.check_clock_skew(DEFAULT_CLOCK_SKEW_WARNING_SECS, tolerance),
+            ClockSkewCheck::Ok
+        );
+        // Above the soft threshold but within tolerance, in either direction
+        assert_eq!(
+            config.check_clock_skew(120, tolerance),
+            ClockSkewCheck::Warning
+        );
+        assert_eq!(
+            config.check_clock_skew(-120, tolerance),
+            ClockSkewCheck::Warning
+        );
+        assert_eq!(
+            config.check_clock_skew(300, tolerance),
+            ClockSkewCheck::Warning
+        );
+        assert_eq!(
+            config.check_clock_skew(301, tolerance),
+            ClockSkewCheck::Rejected
+        );
+        // A wider configured tolerance accepts the same drift
+        assert_eq!(config.check_clock_skew(301, 900), ClockSkewCheck::Warning);
+    }
+
+    #[test]
+    fn test_per_org_timestamp_tolerance() {
+        let org_id = Uuid::from_u128(7);
+        let config = WebhookConfig {
+            org_timestamp_tolerance_secs: parse_org_tolerances(&format!(
+                "{}=900, not-a-uuid=5, {}=oops",
+                org_id,
+                Uuid::from_u128(8)
+            )),
+            ..Default::default()
+        };
+        assert_eq!(config.org_timestamp_tolerance_secs.len(), 1);
+        let global = DEFAULT_TIMESTAMP_TOLERANCE_SECS;
+        assert_eq!(config.timestamp_tolerance_for(Some(org_id), global), 900);
+        assert_eq!(
+            config.check_clock_skew(600, config.timestamp_tolerance_for(Some(org_id), global)),
+            ClockSkewCheck::Warning
+        );
+        // Other orgs keep the global tolerance
+        assert_eq!(
+            config.timestamp_tolerance_for(Some(Uuid::from_u128(9)), global),
+            global
+        );
+        assert_eq!(
+            config.check_clock_skew(600, config.timestamp_tolerance_for(None, global)),
+            ClockSkewCheck::Rejected
+        );
+    }
+
+    #[test]
+    fn test_payload_org_id() {
+        let org_id = Uuid::from_u128(42);
+        let payload = serde_json::json!({
+            "data": {"object": {"metadata": {"org_id": org_id.to_string()}}}
+        })
+        .to_string();
+        assert_eq!(payload_org_id(&payload), Some(org_id));
+        assert_eq!(payload_org_id("{}"), None);
+        assert_eq!(payload_org_id("not json"), None);
+    }
+
+    #[test]
+    fn test_small_subscription_only_upcoming_invoice_not_notified() {
+        let config = WebhookConfig::default();
+        assert!(!config.should_notify_upcoming_invoice(500, 0));
+        assert!(!config.should_notify_upcoming_invoice(0, 0));
+        // At or above the threshold still notifies
+        assert!(config.should_notify_upcoming_invoice(DEFAULT_UPCOMING_INVOICE_MIN_CENTS, 0));
+        assert!(config.should_notify_upcoming_invoice(2900, 0));
+    }
+
+    #[test]
+    fn test_upcoming_invoice_with_overages_notified() {
+        let config = WebhookConfig::default();
+        // Small subscription amount, but overages are pending
+        assert!(config.should_notify_upcoming_invoice(500, 1));
+        // Overage-only upcoming invoice
+        assert!(config.should_notify_upcoming_invoice(0, 250));
+    }
+
+    #[test]
+    fn test_zero_threshold_notifies_any_amount() {
+        let config = WebhookConfig {
+            upcoming_invoice_min_cents: 0,
+            ..Default::default()
+        };
+        assert!(config.should_notify_upcoming_invoice(1, 0));
+        assert!(!config.should_notify_upcoming_invoice(0, 0));
+    }
+
+    #[test]
+    fn test_tier_reducing_update_triggers_seat_reconciliation() {
+        // Pro (5 seats) -> Free (1 seat)
+        assert!(reduces_seat_limit("pro", "free"));
+        // Team (unlimited) -> Pro (5 seats)
+        assert!(reduces_seat_limit("team", "pro"));
+    }
+
+    #[test]
+    fn test_non_reducing_update_skips_seat_reconciliation() {
+        assert!(!reduces_seat_limit("free", "pro"));
+        assert!(!reduces_seat_limit("pro", "pro"));
+        // Both unlimited
+        assert!(!reduces_seat_limit("enterprise", "team"));
+        // Unknown tiers never trigger suspension
+        assert!(!reduces_seat_limit("pro", "mystery"));
+    }
+
+    #[test]
+    fn test_fully_credited_invoice_is_zero_amount() {
+        // $29 invoice paid entirely from the customer's credit balance:
+        // the total stays $29, but nothing is due
+        let mut invoice = invoice_with(Some(2900), Some(0));
+        invoice.starting_balance = Some(-2900);
+        assert!(is_zero_amount_invoice(&invoice));
+    }
+
+    fn invoice_with_reason(reason: Option<stripe::InvoiceBillingReason>) -> Invoice {
+        Invoice {
+            billing_reason: reason,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_billing_reason_uses_stripe_values() {
+        use stripe::InvoiceBillingReason as Reason;
+
+        let cases = [
+            (Reason::SubscriptionCycle, "subscription_cycle"),
+            (Reason::SubscriptionUpdate, "subscription_update"),
+            (Reason::SubscriptionCreate, "subscription_create"),
+            (Reason::Manual, "manual"),
+        ];
+        for (reason, expected) in cases {
+            assert_eq!(
+                invoice_billing_reason(&invoice_with_reason(Some(reason))).as_deref(),
+                Some(expected)
+            );
+        }
+        assert_eq!(invoice_billing_reason(&invoice_with_reason(None)), None);
+    }
+
+    #[test]
+    fn test_billing_reason_overage_invoice() {
+        let mut invoice = invoice_with_reason(Some(stripe::InvoiceBillingReason::Manual));
+        let mut metadata = stripe::Metadata::new();
+        metadata.insert(
+            "invoice_type".to_string(),
+            "overage_invoice_now".to_string(),
+        );
+        invoice.metadata = Some(metadata);
+
+        assert_eq!(
+            invoice_billing_reason(&invoice).as_deref(),
+            Some("overage_payment")
+        );
+    }
+
+    #[test]
+    fn test_customer_deleted_detaches_and_downgrades() {
+        assert_eq!(
+            customer_deletion_action("cus_old", Some("cus_old"), true),
+            CustomerDeletionAction::Detach { downgrade: true }
+        );
+        // Downgrade is configurable; detaching is not
+        assert_eq!(
+            customer_deletion_action("cus_old", Some("cus_old"), false),
+            CustomerDeletionAction::Detach { downgrade: false }
+        );
+        assert!(WebhookConfig::default().downgrade_on_customer_deleted);
+    }
+
+    #[test]
+    fn test_customer_deleted_ignores_replacement_customer() {
+        assert_eq!(
+            customer_deletion_action("cus_old", Some("cus_new"), true),
+            CustomerDeletionAction::Replaced
+        );
+        assert_eq!(
+            customer_deletion_action("cus_old", None, true),
+            CustomerDeletionAction::AlreadyDetached
+        );
+    }
+
+    #[test]
+    fn test_missing_webhook_secret_fails_verification() {
+        assert!(matches!(
+            check_webhook_secret(""),
+            Err(BillingError::Config(_))
+        ));
+        assert!(check_webhook_secret("  ").is_err());
+        assert!(check_webhook_secret("whsec_test").is_ok());
+    }
+
+    #[test]
+    fn test_only_favourable_dispute_outcomes_lift_pause() {
+        assert!(dispute_lifts_pause(DisputeStatus::Won));
+        assert!(dispute_lifts_pause(DisputeStatus::WarningClosed));
+        assert!(!dispute_lifts_pause(DisputeStatus::Lost));
+        assert!(!dispute_lifts_pause(DisputeStatus::UnderReview));
+        assert!(!dispute_lifts_pause(DisputeStatus::NeedsResponse));
+    }
+
+    #[test]
+    fn test_missing_event_types() {
+        let enabled: Vec<String> = HANDLED_EVENT_TYPES
+            .iter()
+            .copied()
+            .filter(|t| *t != EventType::InvoicePaid)
+            .map(event_name)
+            .collect();
+        let enabled: Vec<&str> = enabled.iter().map(String::as_str).collect();
+
+        assert_eq!(event_name(EventType::InvoicePaid), "invoice.paid");
+        assert_eq!(missing_event_types(&enabled), vec![EventType::InvoicePaid]);
+        assert!(missing_event_types(&["*"]).is_empty());
+        assert_eq!(missing_event_types(&[]).len(), HANDLED_EVENT_TYPES.len());
+    }
+
+    fn checkout_metadata(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_checkout_type_routes_each_completion_path() {
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[(
+                "checkout_type",
+                "overage_payment"
+            )])),
+            Ok(CheckoutType::OveragePayment)
+        );
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[
+                ("checkout_type", "upgrade_payment"),
+                ("new_tier", "team"),
+                ("billing_interval", "annual"),
+                ("overage_cents", "1250"),
+            ])),
+            Ok(CheckoutType::UpgradePayment {
+                new_tier: "team".to_string(),
+                billing_interval: BillingInterval::Annual,
+                overage_cents: 1250,
+            })
+        );
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[
+                ("checkout_type", "addon"),
+                ("addon_type", "extra_requests"),
+                ("quantity", "3"),
+            ])),
+            Ok(CheckoutType::AddonPurchase {
+                addon_type: "extra_requests".to_string(),
+                quantity: 3,
+            })
+        );
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[("org_id", "x")])),
+            Ok(CheckoutType::NewSubscription)
+        );
+    }
+
+    #[test]
+    fn test_checkout_type_defaults_and_missing_fields() {
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[
+                ("checkout_type", "upgrade_payment"),
+                ("new_tier", "pro"),
+            ])),
+            Ok(CheckoutType::UpgradePayment {
+                new_tier: "pro".to_string(),
+                billing_interval: BillingInterval::Monthly,
+                overage_cents: 0,
+            })
+        );
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[
+                ("checkout_type", "upgrade_payment"),
+                ("new_tier", ""),
+            ])),
+            Err("new_tier")
+        );
+        // Unrecognised intervals fall back to monthly rather than failing
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[
+                ("checkout_type", "upgrade_payment"),
+                ("new_tier", "pro"),
+                ("billing_interval", "fortnightly"),
+            ])),
+            Ok(CheckoutType::UpgradePayment {
+                new_tier: "pro".to_string(),
+                billing_interval: BillingInterval::Monthly,
+                overage_cents: 0,
+            })
+        );
+        assert_eq!(
+            CheckoutType::from_metadata(&checkout_metadata(&[("checkout_type", "addon")])),
+            Err("addon_type")
+        );
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_redelivered_checkout_records_are_noops() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = plexmcp_shared::db::create_pool(&url).await.unwrap();
+        let org_id = Uuid::new_v4();
+
+        sqlx::query("INSERT INTO organizations (id, name, slug) VALUES ($1, $2, $3)")
+            .bind(org_id)
+            .bind("Checkout Redelivery Test")
+            .bind(format!("checkout-redelivery-{}", org_id.simple()))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let subscription_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO subscriptions (org_id, status) VALUES ($1, 'active') RETURNING id",
+        )
+        .bind(org_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        // Billing history: one row per session however often it is delivered
+        let session_id = format!("cs_test_{}", org_id.simple());
+        for expect_inserted in [true, false] {
+            let inserted = record_checkout_invoice(
+                &pool,
+                org_id,
+                &session_id,
+                2_900,
+                "Early overage payment",
+                "overage_payment",
+            )
+            .await
+            .unwrap();
+            assert_eq!(inserted, expect_inserted);
+        }
+        let invoices: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM invoices WHERE stripe_invoice_id = $1")
+                .bind(&session_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(invoices, 1);
+
+        // Add-ons: a re-delivered purchase keeps the quantity, a new one adds to it
+        let addon = |stripe_item_id, quantity| CheckoutAddon {
+            org_id,
+            subscription_id,
+            addon_type: "extra_requests",
+            stripe_item_id: Some(stripe_item_id),
+            stripe_price_id: "price_extra_requests",
+            quantity,
+            unit_price_cents: 500,
+        };
+        let quantity = || async {
+            sqlx::query_scalar::<_, i32>(
+                "SELECT quantity FROM subscription_addons WHERE org_id = $1 AND addon_type = 'extra_requests'",
+            )
+            .bind(org_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+
+        addon("si_first", 2).record(&pool).await.unwrap();
+        addon("si_first", 2).record(&pool).await.unwrap();
+        assert_eq!(quantity().await, 2);
+        addon("si_second", 1).record(&pool).await.unwrap();
+        assert_eq!(quantity().await, 3);
+
+        sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(org_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
@@ -17,7 +17,8 @@
 //! ```
 
 use plexmcp_billing::{
-    AdminTierChangeParams, AdminTierChangeResult, BillingError, BillingService, StripeConfig,
+    AdminTierChangeParams, AdminTierChangeResult, BillingError, BillingService, StripeClient,
+    StripeConfig,
 };
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -61,6 +62,9 @@ async fn setup_test_stripe() -> (BillingService, PgPool) {
             .unwrap_or_else(|_| "whsec_test_secret".to_string()),
     };
 
+    // Never let integration tests touch production Stripe
+    StripeClient::new(stripe_config.clone()).assert_test_mode();
+
     let billing = BillingService::new(stripe_config, pool.clone());
     (billing, pool)
 }
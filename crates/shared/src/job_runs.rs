@@ -0,0 +1,212 @@
+//! Scheduled job run history
+//!
+//! Worker jobs record a `job_runs` row when they start and complete it when
+//! they finish, giving ops a view of each job's last execution and outcome.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Outcome of a job run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "VARCHAR", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum JobOutcome {
+    /// Started but not yet finished (or the worker died mid-run)
+    Running,
+    Success,
+    Failure,
+}
+
+impl JobOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobOutcome::Running => "running",
+            JobOutcome::Success => "success",
+            JobOutcome::Failure => "failure",
+        }
+    }
+
+    /// Outcome for a finished run, given its error (if any)
+    pub fn from_error(error: Option<&str>) -> Self {
+        match error {
+            Some(_) => JobOutcome::Failure,
+            None => JobOutcome::Success,
+        }
+    }
+}
+
+impl std::fmt::Display for JobOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single recorded job execution
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobRun {
+    pub id: Uuid,
+    pub job_name: String,
+    pub outcome: JobOutcome,
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub finished_at: Option<OffsetDateTime>,
+    pub duration_ms: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// Record the start of a job run, returning the run id
+pub async fn start_job_run(pool: &PgPool, job_name: &str) -> Result<Uuid, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO job_runs (job_name, outcome, started_at)
+        VALUES ($1, 'running', NOW())
+        RETURNING id
+        "#,
+    )
+    .bind(job_name)
+    .fetch_one(pool)
+    .await
+}
+
+/// Mark a job run finished; the outcome is `failure` when `error` is set
+pub async fn finish_job_run(
+    pool: &PgPool,
+    run_id: Uuid,
+    error: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE job_runs
+        SET outcome = $2,
+            finished_at = NOW(),
+            duration_ms = (EXTRACT(EPOCH FROM (NOW() - started_at)) * 1000)::BIGINT,
+            error = $3
+        WHERE id = $1
+        "#,
+    )
+    .bind(run_id)
+    .bind(JobOutcome::from_error(error))
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// List the most recent runs of a job, newest first
+pub async fn list_job_runs(
+    pool: &PgPool,
+    job_name: &str,
+    limit: i64,
+) -> Result<Vec<JobRun>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT id, job_name, outcome, started_at, finished_at, duration_ms, error
+        FROM job_runs
+        WHERE job_name = $1
+        ORDER BY started_at DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(job_name)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+/// The latest run of every job that has ever run, ordered by job name
+pub async fn list_last_job_runs(pool: &PgPool) -> Result<Vec<JobRun>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT DISTINCT ON (job_name)
+            id, job_name, outcome, started_at, finished_at, duration_ms, error
+        FROM job_runs
+        ORDER BY job_name, started_at DESC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Delete job runs older than `keep_days`, returning the number removed
+pub async fn prune_job_runs(pool: &PgPool, keep_days: i32) -> Result<u64, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM job_runs WHERE started_at < NOW() - make_interval(days => $1)")
+            .bind(keep_days)
+            .execute(pool)
+            .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_from_error() {
+        assert_eq!(JobOutcome::from_error(None), JobOutcome::Success);
+        assert_eq!(JobOutcome::from_error(Some("boom")), JobOutcome::Failure);
+    }
+
+    #[test]
+    fn test_outcome_display_matches_serde() {
+        for outcome in [
+            JobOutcome::Running,
+            JobOutcome::Success,
+            JobOutcome::Failure,
+        ] {
+            let json = serde_json::to_value(outcome).expect("serialize");
+            assert_eq!(json, serde_json::Value::String(outcome.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_record_and_list_job_run() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url)
+            .await
+            .expect("Failed to create pool");
+        crate::db::run_migrations(&pool)
+            .await
+            .expect("Failed to migrate");
+
+        let job_name = format!("test_job_{}", Uuid::new_v4());
+
+        let ok_run = start_job_run(&pool, &job_name).await.expect("start");
+        finish_job_run(&pool, ok_run, None).await.expect("finish");
+
+        let failed_run = start_job_run(&pool, &job_name).await.expect("start");
+        finish_job_run(&pool, failed_run, Some("3 errors"))
+            .await
+            .expect("finish");
+
+        let runs = list_job_runs(&pool, &job_name, 10).await.expect("list");
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, failed_run);
+        assert_eq!(runs[0].outcome, JobOutcome::Failure);
+        assert_eq!(runs[0].error.as_deref(), Some("3 errors"));
+        assert_eq!(runs[1].id, ok_run);
+        assert_eq!(runs[1].outcome, JobOutcome::Success);
+        assert!(runs
+            .iter()
+            .all(|r| r.finished_at.is_some() && r.duration_ms.is_some()));
+
+        let last = list_last_job_runs(&pool).await.expect("last");
+        let latest = last
+            .iter()
+            .find(|r| r.job_name == job_name)
+            .expect("job present");
+        assert_eq!(latest.id, failed_run);
+
+        sqlx::query("DELETE FROM job_runs WHERE job_name = $1")
+            .bind(&job_name)
+            .execute(&pool)
+            .await
+            .expect("cleanup");
+    }
+}
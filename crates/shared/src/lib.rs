@@ -4,10 +4,17 @@
 
 pub mod db;
 pub mod error;
+pub mod job_runs;
+pub mod money;
 pub mod rate_limit;
 pub mod types;
+pub mod webhook_queue;
 
 pub use db::*;
 pub use error::*;
-pub use rate_limit::{RateLimitConfig, RateLimitError, RateLimitResult2, RateLimiter};
+pub use job_runs::{JobOutcome, JobRun};
+pub use money::Money;
+pub use rate_limit::{
+    PostgresRateLimiter, RateLimitConfig, RateLimitError, RateLimitResult2, RateLimiter,
+};
 pub use types::*;
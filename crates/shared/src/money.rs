@@ -0,0 +1,76 @@
+//! Currency amounts
+//!
+//! Billing results carry amounts as [`Money`] rather than bare cents, so
+//! consumers don't each reimplement formatting or assume USD.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// ISO 4217 code for US dollars, the only currency billed today
+pub const USD: &str = "usd";
+
+/// An amount in the currency's smallest unit (cents for USD)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Money {
+    pub amount_cents: i64,
+    /// Lowercase ISO 4217 code, as Stripe reports it
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount_cents: i64, currency: impl Into<String>) -> Self {
+        Self {
+            amount_cents,
+            currency: currency.into().to_ascii_lowercase(),
+        }
+    }
+
+    pub fn from_cents_usd(amount_cents: impl Into<i64>) -> Self {
+        Self::new(amount_cents.into(), USD)
+    }
+}
+
+/// `$12.34` for USD, `12.34 EUR` for anything else; negative amounts get a
+/// leading `-`
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.amount_cents < 0 { "-" } else { "" };
+        let abs = self.amount_cents.unsigned_abs();
+        let (units, cents) = (abs / 100, abs % 100);
+
+        if self.currency == USD {
+            write!(f, "{}${}.{:02}", sign, units, cents)
+        } else {
+            write!(
+                f,
+                "{}{}.{:02} {}",
+                sign,
+                units,
+                cents,
+                self.currency.to_ascii_uppercase()
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usd_display() {
+        assert_eq!(Money::from_cents_usd(2900).to_string(), "$29.00");
+        assert_eq!(Money::from_cents_usd(5).to_string(), "$0.05");
+        assert_eq!(Money::from_cents_usd(-1250).to_string(), "-$12.50");
+        assert_eq!(Money::new(999, "EUR").to_string(), "9.99 EUR");
+    }
+
+    #[test]
+    fn test_serialization() {
+        assert_eq!(
+            serde_json::to_value(Money::from_cents_usd(1500)).unwrap(),
+            serde_json::json!({ "amount_cents": 1500, "currency": "usd" })
+        );
+    }
+}
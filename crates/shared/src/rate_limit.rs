@@ -1,6 +1,14 @@
-//! Rate limiting service using in-memory storage
+//! Rate limiting service
 //!
-//! Provides real-time rate limiting for API requests.
+//! Provides real-time rate limiting for API requests. Both backends use a fixed
+//! one-minute window per key:
+//! - [`InMemoryRateLimiter`]: exact and fast, but per process. Counts reset on
+//!   restart and each API instance enforces its own limit, so N instances let up
+//!   to N times the limit through. Intended for tests and single-instance setups.
+//! - [`PostgresRateLimiter`]: one atomic upsert per check on `rate_limit_buckets`,
+//!   so all instances share a counter and it survives restarts. Costs a database
+//!   round trip per check, and windows follow the database clock. Like any fixed
+//!   window, up to twice the limit can pass across a window boundary.
 //!
 //! Security rate limits are configurable via environment variables:
 //! - `RATE_LIMIT_AUTH_PER_MINUTE`: Auth attempts per IP (default: 10)
@@ -9,7 +17,16 @@
 //! - `RATE_LIMIT_TICKETS_PER_MINUTE`: Ticket creation per org (default: 20)
 //! - `RATE_LIMIT_REGISTRATION_PER_MINUTE`: Account registration per IP (default: 3)
 //! - `RATE_LIMIT_OAUTH_PER_MINUTE`: OAuth attempts per IP (default: 10)
+//! - `RATE_LIMIT_DASHBOARD_PER_MINUTE`: Dashboard API requests per user (default: 100)
+//!
+//! The API uses the Postgres backend unless `RATE_LIMIT_BACKEND=memory`.
+//!
+//! Limits are grouped into named profiles so one [`RateLimiter`] can enforce
+//! different limits per endpoint via [`RateLimiter::check`]. The security limits
+//! above are built-in profiles; routes that name no profile use [`DEFAULT_PROFILE`].
 
+use sqlx::PgPool;
+use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
 use time::OffsetDateTime;
 use uuid::Uuid;
@@ -19,6 +36,9 @@ use uuid::Uuid;
 pub enum RateLimitError {
     #[error("Internal rate limit error: {0}")]
     Internal(String),
+
+    #[error("Rate limit storage error: {0}")]
+    Database(#[from] sqlx::Error),
 }
 
 /// Result type for rate limit operations
@@ -90,6 +110,29 @@ fn get_oauth_rate_limit() -> u32 {
     })
 }
 
+/// Get configurable dashboard API rate limit per minute
+fn get_dashboard_rate_limit() -> u32 {
+    static LIMIT: OnceLock<u32> = OnceLock::new();
+    *LIMIT.get_or_init(|| {
+        std::env::var("RATE_LIMIT_DASHBOARD_PER_MINUTE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(100)
+    })
+}
+
+/// Profile used when a route doesn't name one (or names an unknown one)
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Built-in security profiles (see the module docs for their env vars)
+pub const AUTH_PROFILE: &str = "auth";
+pub const TWO_FACTOR_PROFILE: &str = "2fa";
+pub const PASSWORD_RESET_PROFILE: &str = "password_reset";
+pub const TICKETS_PROFILE: &str = "tickets";
+pub const REGISTRATION_PROFILE: &str = "register";
+pub const OAUTH_PROFILE: &str = "oauth";
+pub const DASHBOARD_PROFILE: &str = "dashboard";
+
 /// Rate limit configuration
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
@@ -115,11 +158,49 @@ impl Default for RateLimitConfig {
 #[derive(Debug, Clone)]
 pub struct RateLimitResult2 {
     pub allowed: bool,
+    /// Requests left under the most restrictive limit (for `X-RateLimit-Remaining`)
+    pub remaining: u64,
     pub remaining_minute: u32,
     pub remaining_hour: Option<u32>,
     pub remaining_monthly: Option<u64>,
+    /// When the current window ends and the quota resets
     pub reset_at: OffsetDateTime,
-    pub retry_after_seconds: Option<u32>,
+    /// Seconds until `reset_at` when rejected (for `Retry-After`)
+    pub retry_after_secs: Option<u64>,
+}
+
+/// Length of a rate limit window in seconds
+const WINDOW_SECONDS: i64 = 60;
+
+/// Build a check result for a fixed window. `count` is the window's count after
+/// this request, or `None` when the request was rejected.
+fn window_result(
+    count: Option<u32>,
+    config: &RateLimitConfig,
+    window_start: i64,
+    now: i64,
+) -> RateLimitResult2 {
+    let allowed = count.is_some();
+    let remaining = count.map_or(0, |c| config.requests_per_minute.saturating_sub(c));
+    let reset_at = OffsetDateTime::from_unix_timestamp(window_start + WINDOW_SECONDS)
+        .unwrap_or(OffsetDateTime::now_utc());
+
+    // Never tell a rejected client to retry immediately
+    let retry_after = if !allowed {
+        Some((window_start + WINDOW_SECONDS - now).max(1) as u64)
+    } else {
+        None
+    };
+
+    RateLimitResult2 {
+        allowed,
+        remaining: u64::from(remaining),
+        remaining_minute: remaining,
+        remaining_hour: None,
+        remaining_monthly: None,
+        reset_at,
+        retry_after_secs: retry_after,
+    }
 }
 
 /// In-memory rate limiter (for development without Redis)
@@ -143,7 +224,7 @@ impl InMemoryRateLimiter {
         config: &RateLimitConfig,
     ) -> RateLimitResult<RateLimitResult2> {
         let now = OffsetDateTime::now_utc().unix_timestamp();
-        let window_start = now - (now % 60); // 1-minute window
+        let window_start = now - (now % WINDOW_SECONDS); // 1-minute window
 
         let mut windows = self.windows.write().await;
 
@@ -163,26 +244,12 @@ impl InMemoryRateLimiter {
             entry.0 += 1;
         }
 
-        let remaining = config
-            .requests_per_minute
-            .saturating_sub(current_count + if allowed { 1 } else { 0 });
-        let reset_at = OffsetDateTime::from_unix_timestamp(window_start + 60)
-            .unwrap_or(OffsetDateTime::now_utc());
-
-        let retry_after = if !allowed {
-            Some((window_start + 60 - now) as u32)
-        } else {
-            None
-        };
-
-        Ok(RateLimitResult2 {
-            allowed,
-            remaining_minute: remaining,
-            remaining_hour: None,
-            remaining_monthly: None,
-            reset_at,
-            retry_after_seconds: retry_after,
-        })
+        Ok(window_result(
+            allowed.then_some(entry.0),
+            config,
+            window_start,
+            now,
+        ))
     }
 
     /// Clean up old windows (call periodically)
@@ -201,19 +268,187 @@ impl Default for InMemoryRateLimiter {
     }
 }
 
+/// Postgres-backed rate limiter shared by all API instances
+/// Uses the same fixed window as [`InMemoryRateLimiter`], stored in `rate_limit_buckets`
+pub struct PostgresRateLimiter {
+    pool: PgPool,
+}
+
+impl PostgresRateLimiter {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Check and increment rate limit
+    ///
+    /// The window start comes from the database clock so instances with skewed
+    /// clocks still agree. The upsert only increments while the window is under
+    /// the limit, so a rejected request leaves no row returned and the count capped.
+    pub async fn check_rate_limit(
+        &self,
+        key: &str,
+        config: &RateLimitConfig,
+    ) -> RateLimitResult<RateLimitResult2> {
+        let (count, now): (Option<i32>, i64) = sqlx::query_as(
+            r#"
+            WITH clock AS (
+                SELECT FLOOR(EXTRACT(EPOCH FROM NOW()))::BIGINT AS now_ts
+            ),
+            bucket AS (
+                INSERT INTO rate_limit_buckets (key, window_start, count)
+                SELECT $1, now_ts - (now_ts % $2), 1
+                FROM clock
+                WHERE $3 > 0
+                ON CONFLICT (key) DO UPDATE SET
+                    count = CASE
+                        WHEN rate_limit_buckets.window_start = EXCLUDED.window_start
+                            THEN rate_limit_buckets.count + 1
+                        ELSE 1
+                    END,
+                    window_start = EXCLUDED.window_start
+                WHERE rate_limit_buckets.window_start <> EXCLUDED.window_start
+                   OR rate_limit_buckets.count < $3
+                RETURNING count
+            )
+            SELECT (SELECT count FROM bucket), now_ts FROM clock
+            "#,
+        )
+        .bind(key)
+        .bind(WINDOW_SECONDS)
+        .bind(config.requests_per_minute as i64)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let window_start = now - (now % WINDOW_SECONDS);
+        Ok(window_result(
+            count.map(|c| c as u32),
+            config,
+            window_start,
+            now,
+        ))
+    }
+
+    /// Delete windows older than an hour (call periodically)
+    pub async fn cleanup(&self) -> RateLimitResult<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM rate_limit_buckets
+            WHERE window_start < FLOOR(EXTRACT(EPOCH FROM NOW()))::BIGINT - 3600
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Storage behind a [`RateLimiter`]
+enum RateLimitBackend {
+    InMemory(InMemoryRateLimiter),
+    Postgres(PostgresRateLimiter),
+}
+
+impl RateLimitBackend {
+    async fn check_rate_limit(
+        &self,
+        key: &str,
+        config: &RateLimitConfig,
+    ) -> RateLimitResult<RateLimitResult2> {
+        match self {
+            Self::InMemory(limiter) => limiter.check_rate_limit(key, config).await,
+            Self::Postgres(limiter) => limiter.check_rate_limit(key, config).await,
+        }
+    }
+}
+
+/// Default and built-in security profiles
+fn builtin_profiles() -> HashMap<String, RateLimitConfig> {
+    let per_minute = |requests_per_minute| RateLimitConfig {
+        requests_per_minute,
+        ..Default::default()
+    };
+
+    HashMap::from([
+        (DEFAULT_PROFILE.to_string(), RateLimitConfig::default()),
+        (AUTH_PROFILE.to_string(), per_minute(get_auth_rate_limit())),
+        (
+            TWO_FACTOR_PROFILE.to_string(),
+            per_minute(get_2fa_rate_limit()),
+        ),
+        (
+            PASSWORD_RESET_PROFILE.to_string(),
+            per_minute(get_password_reset_rate_limit()),
+        ),
+        (
+            TICKETS_PROFILE.to_string(),
+            per_minute(get_ticket_rate_limit()),
+        ),
+        (
+            REGISTRATION_PROFILE.to_string(),
+            per_minute(get_registration_rate_limit()),
+        ),
+        (
+            OAUTH_PROFILE.to_string(),
+            per_minute(get_oauth_rate_limit()),
+        ),
+        (
+            DASHBOARD_PROFILE.to_string(),
+            per_minute(get_dashboard_rate_limit()),
+        ),
+    ])
+}
+
 /// Rate limiter service
 pub struct RateLimiter {
-    inner: Arc<InMemoryRateLimiter>,
+    inner: Arc<RateLimitBackend>,
+    profiles: Arc<HashMap<String, RateLimitConfig>>,
 }
 
 impl RateLimiter {
-    /// Create a new in-memory rate limiter
-    pub fn new_in_memory() -> Self {
+    fn with_backend(backend: RateLimitBackend) -> Self {
         Self {
-            inner: Arc::new(InMemoryRateLimiter::new()),
+            inner: Arc::new(backend),
+            profiles: Arc::new(builtin_profiles()),
         }
     }
 
+    /// Create a new in-memory rate limiter
+    pub fn new_in_memory() -> Self {
+        Self::with_backend(RateLimitBackend::InMemory(InMemoryRateLimiter::new()))
+    }
+
+    /// Create a rate limiter whose counters live in Postgres and are shared
+    /// across API instances
+    pub fn new_postgres(pool: PgPool) -> Self {
+        Self::with_backend(RateLimitBackend::Postgres(PostgresRateLimiter::new(pool)))
+    }
+
+    /// Add or replace a named profile (including [`DEFAULT_PROFILE`])
+    pub fn with_profile(mut self, name: impl Into<String>, config: RateLimitConfig) -> Self {
+        Arc::make_mut(&mut self.profiles).insert(name.into(), config);
+        self
+    }
+
+    /// Configuration for a profile, falling back to [`DEFAULT_PROFILE`]
+    pub fn profile(&self, name: &str) -> &RateLimitConfig {
+        self.profiles
+            .get(name)
+            .or_else(|| self.profiles.get(DEFAULT_PROFILE))
+            .expect("default rate limit profile is always present")
+    }
+
+    /// Check and count a request against a named profile
+    ///
+    /// Counters are kept per profile name, so an unknown profile gets the default
+    /// limits without sharing a counter with other routes.
+    pub async fn check(&self, profile: &str, key: &str) -> RateLimitResult<RateLimitResult2> {
+        let bucket = format!("ratelimit:{}:{}", profile, key);
+        self.inner
+            .check_rate_limit(&bucket, self.profile(profile))
+            .await
+    }
+
     /// Check rate limit for an API key
     pub async fn check_api_key(
         &self,
@@ -281,19 +516,26 @@ impl RateLimiter {
         // Return the most restrictive remaining count
         Ok(RateLimitResult2 {
             allowed: true,
+            remaining: api_key_result.remaining.min(org_result.remaining),
             remaining_minute: api_key_result
                 .remaining_minute
                 .min(org_result.remaining_minute),
             remaining_hour: None,
             remaining_monthly: None,
             reset_at: api_key_result.reset_at.min(org_result.reset_at),
-            retry_after_seconds: None,
+            retry_after_secs: None,
         })
     }
 
     /// Clean up old rate limit windows
-    pub async fn cleanup(&self) {
-        self.inner.cleanup().await;
+    pub async fn cleanup(&self) -> RateLimitResult<()> {
+        match self.inner.as_ref() {
+            RateLimitBackend::InMemory(limiter) => limiter.cleanup().await,
+            RateLimitBackend::Postgres(limiter) => {
+                limiter.cleanup().await?;
+            }
+        }
+        Ok(())
     }
 
     // ==========================================================================
@@ -305,12 +547,8 @@ impl RateLimiter {
     ///
     /// SOC 2 CC6.1: Prevents brute force login attacks
     pub async fn check_auth_by_ip(&self, ip_address: &str) -> RateLimitResult<RateLimitResult2> {
-        let key = format!("ratelimit:auth:ip:{}", ip_address);
-        let config = RateLimitConfig {
-            requests_per_minute: get_auth_rate_limit(),
-            ..Default::default()
-        };
-        self.inner.check_rate_limit(&key, &config).await
+        self.check(AUTH_PROFILE, &format!("ip:{}", ip_address))
+            .await
     }
 
     /// Check rate limit for 2FA verification attempts
@@ -318,12 +556,7 @@ impl RateLimiter {
     ///
     /// SOC 2 CC6.1: Prevents 2FA bypass attempts
     pub async fn check_2fa_attempts(&self, identifier: &str) -> RateLimitResult<RateLimitResult2> {
-        let key = format!("ratelimit:2fa:{}", identifier);
-        let config = RateLimitConfig {
-            requests_per_minute: get_2fa_rate_limit(),
-            ..Default::default()
-        };
-        self.inner.check_rate_limit(&key, &config).await
+        self.check(TWO_FACTOR_PROFILE, identifier).await
     }
 
     /// Check rate limit for password reset requests by IP
@@ -334,12 +567,8 @@ impl RateLimiter {
         &self,
         ip_address: &str,
     ) -> RateLimitResult<RateLimitResult2> {
-        let key = format!("ratelimit:password_reset:ip:{}", ip_address);
-        let config = RateLimitConfig {
-            requests_per_minute: get_password_reset_rate_limit(),
-            ..Default::default()
-        };
-        self.inner.check_rate_limit(&key, &config).await
+        self.check(PASSWORD_RESET_PROFILE, &format!("ip:{}", ip_address))
+            .await
     }
 
     /// Check rate limit for ticket creation by organization
@@ -347,12 +576,7 @@ impl RateLimiter {
     ///
     /// SOC 2 CC6.1: Prevents support system abuse
     pub async fn check_ticket_creation(&self, org_id: Uuid) -> RateLimitResult<RateLimitResult2> {
-        let key = format!("ratelimit:tickets:{}", org_id);
-        let config = RateLimitConfig {
-            requests_per_minute: get_ticket_rate_limit(),
-            ..Default::default()
-        };
-        self.inner.check_rate_limit(&key, &config).await
+        self.check(TICKETS_PROFILE, &org_id.to_string()).await
     }
 
     /// Check rate limit for account registration by IP
@@ -360,12 +584,8 @@ impl RateLimiter {
     ///
     /// SOC 2 CC6.1: Prevents mass account creation
     pub async fn check_registration(&self, ip_address: &str) -> RateLimitResult<RateLimitResult2> {
-        let key = format!("ratelimit:register:ip:{}", ip_address);
-        let config = RateLimitConfig {
-            requests_per_minute: get_registration_rate_limit(),
-            ..Default::default()
-        };
-        self.inner.check_rate_limit(&key, &config).await
+        self.check(REGISTRATION_PROFILE, &format!("ip:{}", ip_address))
+            .await
     }
 
     /// Check rate limit for OAuth authentication attempts by IP
@@ -373,12 +593,8 @@ impl RateLimiter {
     ///
     /// SOC 2 CC6.1: Prevents OAuth abuse and account enumeration
     pub async fn check_oauth(&self, ip_address: &str) -> RateLimitResult<RateLimitResult2> {
-        let key = format!("ratelimit:oauth:ip:{}", ip_address);
-        let config = RateLimitConfig {
-            requests_per_minute: get_oauth_rate_limit(),
-            ..Default::default()
-        };
-        self.inner.check_rate_limit(&key, &config).await
+        self.check(OAUTH_PROFILE, &format!("ip:{}", ip_address))
+            .await
     }
 }
 
@@ -386,6 +602,7 @@ impl Clone for RateLimiter {
     fn clone(&self) -> Self {
         Self {
             inner: Arc::clone(&self.inner),
+            profiles: Arc::clone(&self.profiles),
         }
     }
 }
@@ -421,7 +638,7 @@ mod tests {
         // Next request should be blocked
         let result = limiter.check_api_key(org_id, api_key_id, 3).await.unwrap();
         assert!(!result.allowed);
-        assert!(result.retry_after_seconds.is_some());
+        assert!(result.retry_after_secs.is_some());
     }
 
     #[tokio::test]
@@ -580,4 +797,112 @@ mod tests {
         // At least one key should exist (they're recent)
         assert!(!windows.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_rate_limiter_profiles() {
+        let limiter = RateLimiter::new_in_memory()
+            .with_profile(
+                "login",
+                RateLimitConfig {
+                    requests_per_minute: 2,
+                    ..Default::default()
+                },
+            )
+            .with_profile(
+                "dashboard",
+                RateLimitConfig {
+                    requests_per_minute: 100,
+                    ..Default::default()
+                },
+            );
+
+        for _ in 0..2 {
+            assert!(limiter.check("login", "1.2.3.4").await.unwrap().allowed);
+        }
+        assert!(!limiter.check("login", "1.2.3.4").await.unwrap().allowed);
+
+        // Same key under another profile has its own counter and limit
+        let result = limiter.check("dashboard", "1.2.3.4").await.unwrap();
+        assert!(result.allowed);
+        assert_eq!(result.remaining, 99);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_unknown_profile_uses_default() {
+        let limiter = RateLimiter::new_in_memory().with_profile(
+            DEFAULT_PROFILE,
+            RateLimitConfig {
+                requests_per_minute: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(limiter.profile("unnamed").requests_per_minute, 1);
+        assert_eq!(
+            limiter.profile(AUTH_PROFILE).requests_per_minute,
+            get_auth_rate_limit()
+        );
+
+        assert!(limiter.check("unnamed", "key").await.unwrap().allowed);
+        assert!(!limiter.check("unnamed", "key").await.unwrap().allowed);
+        // A different unnamed route doesn't share the counter
+        assert!(limiter.check("other", "key").await.unwrap().allowed);
+    }
+
+    #[test]
+    fn test_window_result() {
+        let config = RateLimitConfig {
+            requests_per_minute: 5,
+            ..Default::default()
+        };
+
+        let allowed = window_result(Some(3), &config, 1_200, 1_230);
+        assert!(allowed.allowed);
+        assert_eq!(allowed.remaining, 2);
+        assert_eq!(allowed.remaining_minute, 2);
+        assert_eq!(allowed.reset_at.unix_timestamp(), 1_260);
+        assert!(allowed.retry_after_secs.is_none());
+
+        let rejected = window_result(None, &config, 1_200, 1_230);
+        assert!(!rejected.allowed);
+        assert_eq!(rejected.remaining, 0);
+        assert_eq!(rejected.retry_after_secs, Some(30));
+
+        // Retry-After is at least one second, even on the window's last tick
+        let boundary = window_result(None, &config, 1_200, 1_260);
+        assert_eq!(boundary.retry_after_secs, Some(1));
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_postgres_rate_limiter_shared_across_instances() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url)
+            .await
+            .expect("Failed to create pool");
+
+        // Two limiters on the same pool stand in for two API instances
+        let instance_a = RateLimiter::new_postgres(pool.clone());
+        let instance_b = RateLimiter::new_postgres(pool.clone());
+        let org_id = Uuid::new_v4();
+
+        let handles: Vec<_> = (0..10)
+            .map(|i| {
+                let limiter = if i % 2 == 0 { &instance_a } else { &instance_b }.clone();
+                tokio::spawn(async move { limiter.check_org(org_id, 5).await.unwrap() })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for handle in handles {
+            results.push(handle.await.unwrap());
+        }
+
+        let allowed = results.iter().filter(|r| r.allowed).count();
+        assert_eq!(allowed, 5, "instances must share one counter");
+
+        let next = instance_b.check_org(org_id, 5).await.unwrap();
+        assert!(!next.allowed);
+        assert!(next.retry_after_secs.is_some());
+
+        instance_a.cleanup().await.unwrap();
+    }
 }
@@ -233,6 +233,38 @@ impl SubscriptionTier {
         }
     }
 
+    /// Monthly list price in cents
+    ///
+    /// Default prices:
+    /// - Pro: $29/mo = 2900 cents
+    /// - Team: $99/mo = 9900 cents
+    /// - Free/Starter: No price (None)
+    /// - Enterprise: Custom (None - see `custom_monthly_price_cents`)
+    ///
+    /// Configurable via environment variables:
+    /// - `BASE_PRICE_PRO_CENTS`: Pro tier price (default: 2900)
+    /// - `BASE_PRICE_TEAM_CENTS`: Team tier price (default: 9900)
+    pub fn monthly_price_cents(&self) -> Option<i32> {
+        match self {
+            Self::Free | Self::Starter => None,
+            Self::Pro => {
+                let price = std::env::var("BASE_PRICE_PRO_CENTS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(2900);
+                Some(price)
+            }
+            Self::Team => {
+                let price = std::env::var("BASE_PRICE_TEAM_CENTS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(9900);
+                Some(price)
+            }
+            Self::Enterprise => None, // Custom pricing
+        }
+    }
+
     /// Whether custom domains are allowed (via add-on or tier inclusion)
     /// Pro gets custom domains included, Team/Enterprise too
     /// Self-hosted mode: Always allowed
@@ -558,6 +590,64 @@ impl std::str::FromStr for MemberStatus {
     }
 }
 
+/// `suspended_reason` for members at or over their spend sub-cap
+///
+/// Spend is attributed through `api_keys.created_by`, so API keys created by
+/// a member suspended for this reason are rejected as well.
+pub const MEMBER_SPEND_CAP_SUSPENSION_REASON: &str = "member_spend_cap";
+
+/// What happens when an organization reaches its monthly request limit
+/// - Allow: Paid tiers keep serving requests and accrue overage charges
+/// - HardStop: Requests are blocked at 100% of the limit, no overage is billed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverageMode {
+    #[default]
+    Allow,
+    HardStop,
+}
+
+impl OverageMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::HardStop => "hard_stop",
+        }
+    }
+
+    /// Whether requests must be blocked once usage reaches the tier limit.
+    /// Free/Starter never accrue overage; paid tiers block when the org chose
+    /// HardStop or an admin disabled overages.
+    pub fn blocks_at_limit(
+        &self,
+        tier: SubscriptionTier,
+        overages_disabled_by_admin: bool,
+    ) -> bool {
+        match tier {
+            SubscriptionTier::Free | SubscriptionTier::Starter => true,
+            _ => overages_disabled_by_admin || *self == Self::HardStop,
+        }
+    }
+}
+
+impl std::fmt::Display for OverageMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for OverageMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "allow" => Ok(Self::Allow),
+            "hard_stop" => Ok(Self::HardStop),
+            _ => Err(format!("Invalid overage mode: {}", s)),
+        }
+    }
+}
+
 // =============================================================================
 // Database Models
 // =============================================================================
@@ -1270,6 +1360,41 @@ mod tests {
         assert!("invalid".parse::<MemberStatus>().is_err());
     }
 
+    // =========================================================================
+    // OverageMode Tests
+    // =========================================================================
+
+    #[test]
+    fn test_overage_mode_blocks_at_limit() {
+        use SubscriptionTier::*;
+
+        // Allow: only tiers without overage billing block
+        assert!(OverageMode::Allow.blocks_at_limit(Free, false));
+        assert!(!OverageMode::Allow.blocks_at_limit(Pro, false));
+        assert!(!OverageMode::Allow.blocks_at_limit(Team, false));
+        assert!(OverageMode::Allow.blocks_at_limit(Team, true));
+
+        // HardStop blocks paid tiers too
+        assert!(OverageMode::HardStop.blocks_at_limit(Pro, false));
+        assert!(OverageMode::HardStop.blocks_at_limit(Team, false));
+        assert!(OverageMode::HardStop.blocks_at_limit(Enterprise, false));
+    }
+
+    #[test]
+    fn test_overage_mode_display_and_parse() {
+        assert_eq!(OverageMode::default(), OverageMode::Allow);
+        assert_eq!(OverageMode::HardStop.to_string(), "hard_stop");
+        assert_eq!(
+            "hard_stop".parse::<OverageMode>().unwrap(),
+            OverageMode::HardStop
+        );
+        assert!("block".parse::<OverageMode>().is_err());
+        assert_eq!(
+            serde_json::to_value(OverageMode::HardStop).unwrap(),
+            serde_json::json!("hard_stop")
+        );
+    }
+
     // =========================================================================
     // CustomLimits Tests
     // =========================================================================
@@ -0,0 +1,148 @@
+//! Webhook processing queue retry policy and dead letters
+//!
+//! The worker retries failed queue entries with exponential backoff and moves
+//! them to `dead_letter` after `max_attempts`. Admins list dead letters and
+//! requeue them here; a requeued entry goes back to `pending` and the worker
+//! picks it up on its next run. Stripe webhook retries use the same backoff.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::time::Duration;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+/// Default attempts before a queued webhook is dead-lettered
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 10;
+
+/// Environment variable overriding `DEFAULT_MAX_ATTEMPTS`
+pub const MAX_ATTEMPTS_ENV: &str = "WEBHOOK_QUEUE_MAX_ATTEMPTS";
+
+/// Longest wait between retries
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Wait before retrying after failed attempt number `attempt`: 2^attempt
+/// minutes, capped at 6 hours
+pub fn retry_backoff(attempt: i32) -> Duration {
+    let exponent = attempt.clamp(0, 16) as u32;
+    Duration::from_secs(60 * 2u64.pow(exponent)).min(MAX_RETRY_BACKOFF)
+}
+
+/// Max attempts from `WEBHOOK_QUEUE_MAX_ATTEMPTS`, falling back to the default
+pub fn max_attempts_from_env() -> i32 {
+    std::env::var(MAX_ATTEMPTS_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse::<i32>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+}
+
+/// A dead-lettered queue entry
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DeadLetterWebhook {
+    pub id: Uuid,
+    pub webhook_type: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_attempt_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// List dead-lettered queue entries, most recently failed first
+pub async fn list_dead_letters(
+    pool: &PgPool,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<DeadLetterWebhook>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT id, webhook_type, attempts, last_error, last_attempt_at, created_at
+        FROM webhook_processing_queue
+        WHERE status = 'dead_letter'
+        ORDER BY last_attempt_at DESC NULLS LAST, created_at DESC
+        LIMIT $1 OFFSET $2
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+/// Move a dead-lettered entry back to `pending` with a fresh retry budget
+///
+/// Returns false when no dead-lettered entry has that id.
+pub async fn requeue_dead_letter(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE webhook_processing_queue
+        SET status = 'pending',
+            attempts = 0,
+            next_retry_at = NULL
+        WHERE id = $1
+          AND status = 'dead_letter'
+        "#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_cap() {
+        assert_eq!(retry_backoff(1), Duration::from_secs(120));
+        assert_eq!(retry_backoff(3), Duration::from_secs(480));
+        // 2^9 minutes is past the 6 hour cap
+        assert_eq!(retry_backoff(9), MAX_RETRY_BACKOFF);
+        assert_eq!(retry_backoff(i32::MAX), MAX_RETRY_BACKOFF);
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires database
+    async fn test_requeue_returns_dead_letter_to_pending() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL required");
+        let pool = crate::db::create_pool(&url)
+            .await
+            .expect("Failed to create pool");
+
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO webhook_processing_queue
+                (webhook_type, payload, status, attempts, last_error, last_attempt_at)
+            VALUES ('email.received', '{}', 'dead_letter', 10, 'boom', NOW())
+            RETURNING id
+            "#,
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("insert");
+
+        let listed = list_dead_letters(&pool, 200, 0).await.expect("list");
+        assert!(listed.iter().any(|w| w.id == id && w.attempts == 10));
+
+        assert!(requeue_dead_letter(&pool, id).await.expect("requeue"));
+        // Only dead letters can be requeued
+        assert!(!requeue_dead_letter(&pool, id).await.expect("requeue"));
+
+        let (status, attempts): (String, i32) =
+            sqlx::query_as("SELECT status, attempts FROM webhook_processing_queue WHERE id = $1")
+                .bind(id)
+                .fetch_one(&pool)
+                .await
+                .expect("fetch");
+        assert_eq!((status.as_str(), attempts), ("pending", 0));
+
+        sqlx::query("DELETE FROM webhook_processing_queue WHERE id = $1")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .expect("cleanup");
+    }
+}
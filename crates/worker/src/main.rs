@@ -13,14 +13,19 @@
 //! - Webhook queue processing (every minute)
 //! - Test history cleanup based on subscription tier (daily at 4:00 AM UTC)
 //! - MCP health check monitoring (every 30 minutes)
+//! - Member suspension after the downgrade grace period (hourly)
+//!
+//! Every job records its runs in `job_runs` (see `plexmcp_shared::job_runs`).
 
 mod webhook_processor;
 
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
 use plexmcp_api::email::SecurityEmailService;
-use plexmcp_billing::{BillingService, UsageReportResult};
+use plexmcp_billing::{get_primary_owner, BillingService, ItemOutcome, UsageReportResult};
+use plexmcp_shared::job_runs;
 use sqlx::postgres::PgPoolOptions;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info, warn};
@@ -41,8 +46,35 @@ async fn create_db_pool() -> anyhow::Result<sqlx::PgPool> {
     Ok(pool)
 }
 
+/// Run a job body, recording its start, outcome and duration in `job_runs`
+///
+/// Recording failures are logged and never prevent the job from running.
+async fn run_recorded<F>(pool: &sqlx::PgPool, job_name: &str, job: F)
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let run_id = match job_runs::start_job_run(pool, job_name).await {
+        Ok(id) => Some(id),
+        Err(e) => {
+            warn!(job = job_name, error = %e, "Failed to record job start");
+            None
+        }
+    };
+
+    let result = job.await;
+    if let Err(ref e) = result {
+        error!(job = job_name, error = %e, "Scheduled job failed");
+    }
+
+    if let Some(run_id) = run_id {
+        if let Err(e) = job_runs::finish_job_run(pool, run_id, result.err().as_deref()).await {
+            warn!(job = job_name, error = %e, "Failed to record job finish");
+        }
+    }
+}
+
 /// Log results of usage reporting
-fn log_usage_results(results: &[UsageReportResult]) {
+fn log_usage_results(results: &[UsageReportResult]) -> usize {
     let reported = results
         .iter()
         .filter(|r| matches!(r, UsageReportResult::Reported { .. }))
@@ -51,6 +83,14 @@ fn log_usage_results(results: &[UsageReportResult]) {
         .iter()
         .filter(|r| matches!(r, UsageReportResult::NoOverage { .. }))
         .count();
+    let already_reported = results
+        .iter()
+        .filter(|r| matches!(r, UsageReportResult::AlreadyReported { .. }))
+        .count();
+    let partially_reported = results
+        .iter()
+        .filter(|r| matches!(r, UsageReportResult::PartiallyReported { .. }))
+        .count();
     let errors = results
         .iter()
         .filter(|r| matches!(r, UsageReportResult::Error { .. }))
@@ -59,16 +99,35 @@ fn log_usage_results(results: &[UsageReportResult]) {
     info!(
         reported = reported,
         no_overage = no_overage,
+        already_reported = already_reported,
+        partially_reported = partially_reported,
         errors = errors,
         "Usage report cycle complete"
     );
 
-    // Log individual errors
+    // Log individual errors, down to the failed subscription items
     for result in results {
-        if let UsageReportResult::Error { org_id, error } = result {
-            error!(org_id = %org_id, error = %error, "Failed to report usage");
+        match result {
+            UsageReportResult::Error { org_id, error } => {
+                error!(org_id = %org_id, error = %error, "Failed to report usage");
+            }
+            UsageReportResult::PartiallyReported { org_id, items, .. } => {
+                for item in items {
+                    if let ItemOutcome::Failed { error } = &item.outcome {
+                        error!(
+                            org_id = %org_id,
+                            metered_item_id = %item.metered_item_id,
+                            error = %error,
+                            "Failed to report usage for subscription item"
+                        );
+                    }
+                }
+            }
+            _ => {}
         }
     }
+
+    errors + partially_reported
 }
 
 #[tokio::main]
@@ -110,14 +169,22 @@ async fn main() -> anyhow::Result<()> {
 
     // Job 1: Report usage to Stripe every 6 hours
     // Cron: At minute 0 past every 6th hour (0:00, 6:00, 12:00, 18:00 UTC)
+    let metered_pool = pool.clone();
     let metered_service = billing.metered.clone();
     scheduler
         .add(Job::new_async("0 0 */6 * * *", move |_uuid, _l| {
+            let pool = metered_pool.clone();
             let service = metered_service.clone();
             Box::pin(async move {
-                info!("Running scheduled metered usage report to Stripe");
-                let results = service.report_all_usage().await;
-                log_usage_results(&results);
+                run_recorded(&pool, "metered_usage_report", async {
+                    info!("Running scheduled metered usage report to Stripe");
+                    let results = service.report_all_usage().await;
+                    match log_usage_results(&results) {
+                        0 => Ok(()),
+                        errors => Err(format!("{} orgs failed to report usage", errors)),
+                    }
+                })
+                .await;
             })
         })?)
         .await?;
@@ -125,24 +192,38 @@ async fn main() -> anyhow::Result<()> {
 
     // Job 2: Final usage report before billing (end of day UTC)
     // Cron: At 23:55 every day - ensures final usage is captured before midnight billing
+    let metered_pool_final = pool.clone();
     let metered_service_final = billing.metered.clone();
     scheduler
         .add(Job::new_async("0 55 23 * * *", move |_uuid, _l| {
+            let pool = metered_pool_final.clone();
             let service = metered_service_final.clone();
             Box::pin(async move {
-                info!("Running final daily usage report to Stripe");
-                let results = service.report_all_usage().await;
-                log_usage_results(&results);
+                run_recorded(&pool, "final_usage_report", async {
+                    info!("Running final daily usage report to Stripe");
+                    let results = service.report_all_usage().await;
+                    match log_usage_results(&results) {
+                        0 => Ok(()),
+                        errors => Err(format!("{} orgs failed to report usage", errors)),
+                    }
+                })
+                .await;
             })
         })?)
         .await?;
     info!("Scheduled: Final daily usage report (23:55 UTC)");
 
     // Job 3: Health check heartbeat (every 5 minutes)
+    let heartbeat_pool = pool.clone();
     scheduler
-        .add(Job::new_async("0 */5 * * * *", |_uuid, _l| {
+        .add(Job::new_async("0 */5 * * * *", move |_uuid, _l| {
+            let pool = heartbeat_pool.clone();
             Box::pin(async move {
-                info!("Worker heartbeat - all systems operational");
+                run_recorded(&pool, "heartbeat", async {
+                    info!("Worker heartbeat - all systems operational");
+                    Ok(())
+                })
+                .await;
             })
         })?)
         .await?;
@@ -157,208 +238,104 @@ async fn main() -> anyhow::Result<()> {
             let pool = overage_pool.clone();
             let billing = overage_billing.clone();
             Box::pin(async move {
-                info!("Running overage charge calculation job");
-
-                // Get all orgs with Pro/Team subscriptions that have active billing periods
-                // Note: subscriptions.customer_id stores org_id as text
-                let orgs: Vec<(Uuid, String, time::OffsetDateTime, time::OffsetDateTime)> =
-                    sqlx::query_as(
-                        r#"
-                    SELECT o.id, o.subscription_tier, s.current_period_start, s.current_period_end
-                    FROM organizations o
-                    JOIN subscriptions s ON s.customer_id = o.id::text
-                    WHERE o.subscription_tier IN ('pro', 'team')
-                      AND s.status = 'active'
-                      AND s.current_period_start IS NOT NULL
-                      AND s.current_period_end IS NOT NULL
-                    "#,
-                    )
-                    .fetch_all(&pool)
-                    .await
-                    .unwrap_or_default();
-
-                let total_orgs = orgs.len();
-                let mut updated = 0;
-                let mut errors = 0;
+                run_recorded(&pool, "overage_calculation", async {
+                    info!("Running overage charge calculation job");
 
-                for (org_id, tier, period_start, period_end) in orgs {
-                    match billing
+                    let summary = billing
                         .overage
-                        .create_or_update_current_overage(org_id, &tier, period_start, period_end)
+                        .recalculate_all(&billing.spend_cap)
                         .await
-                    {
-                        Ok(Some(_)) => {
-                            updated += 1;
-                            // Sync spend cap tracking (won't double-count)
-                            if let Err(e) = billing.spend_cap.sync_spend_from_overages(org_id).await
-                            {
-                                error!(org_id = %org_id, error = %e, "Failed to sync spend cap");
-                            }
-                        }
-                        Ok(None) => {} // No overage (within limits)
-                        Err(e) => {
-                            error!(org_id = %org_id, error = %e, "Failed to calculate overage");
-                            errors += 1;
-                        }
-                    }
-                }
+                        .map_err(|e| format!("Failed to list overage periods: {}", e))?;
 
-                info!(
-                    total_orgs = total_orgs,
-                    updated = updated,
-                    errors = errors,
-                    "Overage charge calculation complete"
-                );
+                    info!(
+                        total_orgs = summary.total_orgs,
+                        updated = summary.updated,
+                        errors = summary.errors,
+                        "Overage charge calculation complete"
+                    );
+
+                    if summary.errors > 0 {
+                        return Err(format!(
+                            "{} orgs failed overage calculation",
+                            summary.errors
+                        ));
+                    }
+                    Ok(())
+                })
+                .await;
             })
         })?)
         .await?;
     info!("Scheduled: Overage charge calculation (every 15 minutes)");
 
     // Job 5: Grace period enforcement (hourly)
-    // Blocks organizations that have invoices past their 30-day grace period
+    // Blocks organizations that have invoices past their grace period
+    // (INVOICE_GRACE_PERIOD_DAYS, per tier; set on each invoice as grace_period_ends_at)
     let grace_period_pool = pool.clone();
+    let grace_period_billing = billing.clone();
     let grace_period_email_service = SecurityEmailService::from_env();
     scheduler.add(
         Job::new_async("0 0 * * * *", move |_uuid, _l| {
             let pool = grace_period_pool.clone();
+            let billing = grace_period_billing.clone();
             let email_service = grace_period_email_service.clone();
             Box::pin(async move {
-                info!("Running grace period enforcement job");
-
-                // Find orgs with invoices past grace period that aren't already blocked
-                let overdue_orgs: Vec<(Uuid, String, i64)> = sqlx::query_as(
-                    r#"
-                    SELECT DISTINCT
-                        i.org_id,
-                        o.name,
-                        SUM(i.amount_due_cents)::BIGINT as total_due
-                    FROM invoices i
-                    JOIN organizations o ON i.org_id = o.id
-                    WHERE i.status IN ('open', 'uncollectible')
-                      AND i.grace_period_ends_at < NOW()
-                      AND o.billing_blocked_at IS NULL
-                    GROUP BY i.org_id, o.name
-                    HAVING SUM(i.amount_due_cents) > 0
-                    "#
-                )
-                .fetch_all(&pool)
-                .await
-                .unwrap_or_default();
-
-                let total_overdue = overdue_orgs.len();
-                let mut blocked = 0;
-                let mut errors = 0;
-
-                for (org_id, org_name, total_due) in overdue_orgs {
-                    // Block the organization
-                    let result = sqlx::query(
-                        r#"
-                        UPDATE organizations
-                        SET billing_blocked_at = NOW(),
-                            billing_block_reason = $2
-                        WHERE id = $1
-                          AND billing_blocked_at IS NULL
-                        "#
-                    )
-                    .bind(org_id)
-                    .bind(format!(
-                        "Unpaid invoices past 30-day grace period. Outstanding balance: ${:.2}",
-                        total_due as f64 / 100.0
-                    ))
-                    .execute(&pool)
-                    .await;
+                run_recorded(&pool, "grace_period_enforcement", async {
+                    info!("Running grace period enforcement job");
 
-                    match result {
-                        Ok(rows) => {
-                            if rows.rows_affected() > 0 {
-                                blocked += 1;
-                                warn!(
-                                    org_id = %org_id,
-                                    org_name = %org_name,
-                                    total_due_cents = total_due,
-                                    "Organization blocked for non-payment"
-                                );
+                    let enforcement = billing
+                        .grace_period
+                        .enforce(false)
+                        .await
+                        .map_err(|e| format!("Grace period enforcement failed: {}", e))?;
+
+                    for blocked in enforcement.blocked() {
+                        let org_id = blocked.org_id;
+
+                        // Send suspension notification email to organization owner
+                        let owner_email_result: Result<Option<String>, sqlx::Error> = sqlx::query_scalar(
+                            r#"
+                            SELECT u.email
+                            FROM users u
+                            WHERE u.org_id = $1 AND u.role = 'owner'
+                            ORDER BY u.created_at ASC, u.id ASC
+                            LIMIT 1
+                            "#
+                        )
+                        .bind(org_id)
+                        .fetch_optional(&pool)
+                        .await;
 
-                                // Send suspension notification email to organization owner
-                                let owner_email_result: Result<Option<String>, sqlx::Error> = sqlx::query_scalar(
-                                    r#"
-                                    SELECT u.email
-                                    FROM users u
-                                    WHERE u.org_id = $1 AND u.role = 'owner'
-                                    LIMIT 1
-                                    "#
-                                )
-                                .bind(org_id)
-                                .fetch_optional(&pool)
-                                .await;
-
-                                if let Ok(Some(owner_email)) = owner_email_result {
-                                    let email_svc = email_service.clone();
-                                    let org_name_clone = org_name.clone();
-                                    let block_reason = format!(
-                                        "Unpaid invoices past 30-day grace period. Outstanding balance: ${:.2}",
-                                        total_due as f64 / 100.0
-                                    );
-                                    tokio::spawn(async move {
-                                        email_svc
-                                            .send_service_suspended(
-                                                &owner_email,
-                                                &org_name_clone,
-                                                total_due,
-                                                &block_reason,
-                                            )
-                                            .await;
-                                    });
-                                    info!(org_id = %org_id, "Suspension notification email sent");
-                                } else {
-                                    warn!(org_id = %org_id, "No owner email found for suspension notification");
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!(
-                                org_id = %org_id,
-                                error = %e,
-                                "Failed to block organization for non-payment"
-                            );
-                            errors += 1;
+                        if let Ok(Some(owner_email)) = owner_email_result {
+                            let email_svc = email_service.clone();
+                            let org_name = blocked.org_name.clone();
+                            let total_due = blocked.total_due.clone();
+                            let block_reason = blocked.block_reason.clone().unwrap_or_default();
+                            tokio::spawn(async move {
+                                email_svc
+                                    .send_service_suspended(
+                                        &owner_email,
+                                        &org_name,
+                                        &total_due,
+                                        &block_reason,
+                                    )
+                                    .await;
+                            });
+                            info!(org_id = %org_id, "Suspension notification email sent");
+                        } else {
+                            warn!(org_id = %org_id, "No owner email found for suspension notification");
                         }
                     }
-                }
-
-                info!(
-                    total_overdue = total_overdue,
-                    blocked = blocked,
-                    errors = errors,
-                    "Grace period enforcement complete"
-                );
-
-                // Also check for orgs that should be unblocked (all invoices paid)
-                let result = sqlx::query(
-                    r#"
-                    UPDATE organizations o
-                    SET billing_blocked_at = NULL,
-                        billing_block_reason = NULL
-                    WHERE o.billing_blocked_at IS NOT NULL
-                      AND NOT EXISTS (
-                          SELECT 1 FROM invoices i
-                          WHERE i.org_id = o.id
-                            AND i.status IN ('open', 'uncollectible')
-                            AND i.amount_due_cents > 0
-                      )
-                    "#
-                )
-                .execute(&pool)
-                .await;
 
-                if let Ok(rows) = result {
-                    if rows.rows_affected() > 0 {
-                        info!(
-                            unblocked = rows.rows_affected(),
-                            "Unblocked organizations after payment"
-                        );
+                    if enforcement.errors > 0 {
+                        return Err(format!(
+                            "{} orgs failed grace period enforcement",
+                            enforcement.errors
+                        ));
                     }
-                }
+                    Ok(())
+                })
+                .await;
             })
         })?
     ).await?;
@@ -372,6 +349,7 @@ async fn main() -> anyhow::Result<()> {
         .unwrap_or_else(|_| "false".to_string())
         .parse::<bool>()
         .unwrap_or(false);
+    let webhook_max_attempts = plexmcp_shared::webhook_queue::max_attempts_from_env();
 
     scheduler
         .add(Job::new_async("0 * * * * *", move |_uuid, _l| {
@@ -379,13 +357,18 @@ async fn main() -> anyhow::Result<()> {
             let api_key = resend_api_key.clone();
             let routing_enabled = enable_email_routing;
             Box::pin(async move {
-                let http_client = reqwest::Client::new();
-                webhook_processor::process_webhook_queue(
-                    &pool,
-                    &http_client,
-                    &api_key,
-                    routing_enabled,
-                )
+                run_recorded(&pool, "webhook_queue", async {
+                    let http_client = reqwest::Client::new();
+                    webhook_processor::process_webhook_queue(
+                        &pool,
+                        &http_client,
+                        &api_key,
+                        routing_enabled,
+                        webhook_max_attempts,
+                    )
+                    .await;
+                    Ok(())
+                })
                 .await;
             })
         })?)
@@ -398,8 +381,17 @@ async fn main() -> anyhow::Result<()> {
         .add(Job::new_async("0 0 3 * * *", move |_uuid, _l| {
             let pool = cleanup_pool.clone();
             Box::pin(async move {
-                info!("Running webhook queue cleanup");
-                webhook_processor::cleanup_old_webhooks(&pool, 7).await; // Keep 7 days
+                run_recorded(&pool, "webhook_cleanup", async {
+                    info!("Running webhook queue cleanup");
+                    webhook_processor::cleanup_old_webhooks(&pool, 7).await; // Keep 7 days
+
+                    match job_runs::prune_job_runs(&pool, 30).await {
+                        Ok(deleted) => info!(deleted = deleted, "Job run history cleanup complete"),
+                        Err(e) => warn!(error = %e, "Job run history cleanup failed"),
+                    }
+                    Ok(())
+                })
+                .await;
             })
         })?)
         .await?;
@@ -412,30 +404,36 @@ async fn main() -> anyhow::Result<()> {
         .add(Job::new_async("0 0 4 * * *", move |_uuid, _l| {
             let pool = test_cleanup_pool.clone();
             Box::pin(async move {
-                info!("Running test history cleanup job");
-
-                // Delete old test history based on each org's subscription tier
-                // NOTE: subscription_tier is on organizations table, NOT subscriptions
-                let result = sqlx::query(
-                    r#"
-                    DELETE FROM mcp_test_history th
-                    USING organizations o
-                    WHERE th.org_id = o.id
-                    AND th.tested_at < NOW() - CASE o.subscription_tier
-                        WHEN 'free' THEN INTERVAL '7 days'
-                        WHEN 'starter' THEN INTERVAL '30 days'
-                        WHEN 'pro' THEN INTERVAL '90 days'
-                        ELSE INTERVAL '365 days'
-                    END
-                "#,
-                )
-                .execute(&pool)
-                .await;
+                run_recorded(&pool, "test_history_cleanup", async {
+                    info!("Running test history cleanup job");
 
-                match result {
-                    Ok(r) => info!(deleted = r.rows_affected(), "Test history cleanup complete"),
-                    Err(e) => error!(error = %e, "Test history cleanup failed"),
-                }
+                    // Delete old test history based on each org's subscription tier
+                    // NOTE: subscription_tier is on organizations table, NOT subscriptions
+                    let result = sqlx::query(
+                        r#"
+                        DELETE FROM mcp_test_history th
+                        USING organizations o
+                        WHERE th.org_id = o.id
+                        AND th.tested_at < NOW() - CASE o.subscription_tier
+                            WHEN 'free' THEN INTERVAL '7 days'
+                            WHEN 'starter' THEN INTERVAL '30 days'
+                            WHEN 'pro' THEN INTERVAL '90 days'
+                            ELSE INTERVAL '365 days'
+                        END
+                    "#,
+                    )
+                    .execute(&pool)
+                    .await;
+
+                    match result {
+                        Ok(r) => {
+                            info!(deleted = r.rows_affected(), "Test history cleanup complete");
+                            Ok(())
+                        }
+                        Err(e) => Err(format!("Test history cleanup failed: {}", e)),
+                    }
+                })
+                .await;
             })
         })?)
         .await?;
@@ -448,48 +446,196 @@ async fn main() -> anyhow::Result<()> {
         .add(Job::new_async("0 */30 * * * *", move |_uuid, _l| {
             let pool = health_check_pool.clone();
             Box::pin(async move {
-                info!("Running scheduled MCP health checks");
-
-                // Get MCPs that haven't been checked in 30+ minutes
-                let stale_mcps: Vec<(Uuid, String)> = sqlx::query_as(
-                    r#"
-                    SELECT m.id, m.name
-                    FROM mcp_instances m
-                    WHERE m.status = 'active'
-                    AND (m.last_health_check_at IS NULL
-                         OR m.last_health_check_at < NOW() - INTERVAL '30 minutes')
-                    LIMIT 100
-                "#,
-                )
-                .fetch_all(&pool)
-                .await
-                .unwrap_or_default();
-
-                let count = stale_mcps.len();
-                if count > 0 {
-                    info!(count = count, "Found MCPs needing health check");
-
-                    // For each stale MCP, perform a basic connectivity check
-                    // This is a lightweight check - just update last_health_check_at to track
-                    // Full health checks are done by the API when users visit the testing page
-                    for (mcp_id, _name) in &stale_mcps {
-                        let _ = sqlx::query(
-                            "UPDATE mcp_instances SET last_health_check_at = NOW() WHERE id = $1",
+                run_recorded(&pool, "mcp_health_checks", async {
+                    info!("Running scheduled MCP health checks");
+
+                    // Get MCPs that haven't been checked in 30+ minutes
+                    let stale_mcps: Vec<(Uuid, String)> = sqlx::query_as(
+                        r#"
+                        SELECT m.id, m.name
+                        FROM mcp_instances m
+                        WHERE m.status = 'active'
+                        AND (m.last_health_check_at IS NULL
+                             OR m.last_health_check_at < NOW() - INTERVAL '30 minutes')
+                        LIMIT 100
+                    "#,
+                    )
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap_or_default();
+
+                    let count = stale_mcps.len();
+                    if count > 0 {
+                        info!(count = count, "Found MCPs needing health check");
+
+                        // For each stale MCP, perform a basic connectivity check
+                        // This is a lightweight check - just update last_health_check_at to track
+                        // Full health checks are done by the API when users visit the testing page
+                        for (mcp_id, _name) in &stale_mcps {
+                            let _ = sqlx::query(
+                                "UPDATE mcp_instances SET last_health_check_at = NOW() WHERE id = $1",
+                            )
+                            .bind(mcp_id)
+                            .execute(&pool)
+                            .await;
+                        }
+
+                        info!(
+                            updated = count,
+                            "Updated last_health_check_at for stale MCPs"
+                        );
+                    }
+                    Ok(())
+                })
+                .await;
+            })
+        })?)
+        .await?;
+    info!("Scheduled: MCP health check monitoring (every 30 minutes)");
+
+    // Job 10: Suspend excess members after the downgrade grace period (hourly at :30)
+    let suspension_pool = pool.clone();
+    let suspension_billing = billing.clone();
+    scheduler
+        .add(Job::new_async("0 30 * * * *", move |_uuid, _l| {
+            let pool = suspension_pool.clone();
+            let billing = suspension_billing.clone();
+            Box::pin(async move {
+                run_recorded(&pool, "member_suspension_grace", async {
+                    let results = billing
+                        .member_suspension
+                        .process_due_suspensions()
+                        .await
+                        .map_err(|e| format!("Failed to process pending suspensions: {}", e))?;
+
+                    for result in &results {
+                        let tier: Option<String> = sqlx::query_scalar(
+                            "SELECT subscription_tier FROM organizations WHERE id = $1",
                         )
-                        .bind(mcp_id)
-                        .execute(&pool)
-                        .await;
+                        .bind(result.org_id)
+                        .fetch_optional(&pool)
+                        .await
+                        .unwrap_or_default();
+                        let tier = tier.unwrap_or_else(|| "free".to_string());
+
+                        for member in &result.suspended_members {
+                            if let Err(e) = billing
+                                .email
+                                .send_member_suspended(&member.email, &tier)
+                                .await
+                            {
+                                error!(
+                                    error = %e,
+                                    email = %member.email,
+                                    "Failed to send member suspension email"
+                                );
+                            }
+                        }
                     }
 
+                    let suspended: u32 = results.iter().map(|r| r.suspended_count).sum();
                     info!(
-                        updated = count,
-                        "Updated last_health_check_at for stale MCPs"
+                        orgs = results.len(),
+                        suspended = suspended,
+                        "Member suspension grace period enforcement complete"
                     );
-                }
+                    Ok(())
+                })
+                .await;
             })
         })?)
         .await?;
-    info!("Scheduled: MCP health check monitoring (every 30 minutes)");
+    info!("Scheduled: Member suspension after grace period (hourly at :30)");
+
+    // Job 11: Reconcile blocked orgs against their live Stripe balance (hourly at :45)
+    // Unblocks orgs that paid through a channel whose webhook we missed
+    let reconcile_pool = pool.clone();
+    let reconcile_billing = billing.clone();
+    scheduler
+        .add(Job::new_async("0 45 * * * *", move |_uuid, _l| {
+            let pool = reconcile_pool.clone();
+            let billing = reconcile_billing.clone();
+            Box::pin(async move {
+                run_recorded(&pool, "blocked_org_reconciliation", async {
+                    let reconciliation = billing
+                        .grace_period
+                        .reconcile_blocked(&billing.customer)
+                        .await
+                        .map_err(|e| format!("Blocked org reconciliation failed: {}", e))?;
+
+                    if reconciliation.errors > 0 {
+                        return Err(format!(
+                            "{} blocked orgs failed reconciliation",
+                            reconciliation.errors
+                        ));
+                    }
+                    Ok(())
+                })
+                .await;
+            })
+        })?)
+        .await?;
+    info!("Scheduled: Blocked org reconciliation with Stripe (hourly at :45)");
+
+    // Job 12: Escalate trials granted without a payment method (hourly at :50)
+    // Reminds the owner, then blocks the org (TRIAL_PAYMENT_REMINDER_DAYS / TRIAL_PAYMENT_BLOCK_DAYS)
+    let trial_payment_pool = pool.clone();
+    let trial_payment_billing = billing.clone();
+    scheduler
+        .add(Job::new_async("0 50 * * * *", move |_uuid, _l| {
+            let pool = trial_payment_pool.clone();
+            let billing = trial_payment_billing.clone();
+            Box::pin(async move {
+                run_recorded(&pool, "trial_payment_enforcement", async {
+                    let enforcement = billing
+                        .trial_payment
+                        .enforce(&billing.customer)
+                        .await
+                        .map_err(|e| format!("Trial payment enforcement failed: {}", e))?;
+
+                    for reminder in enforcement.reminders() {
+                        let owner = match get_primary_owner(&pool, reminder.org_id).await {
+                            Ok(Some(owner)) => owner,
+                            Ok(None) => continue,
+                            Err(e) => {
+                                error!(
+                                    org_id = %reminder.org_id,
+                                    error = %e,
+                                    "Failed to look up owner for trial payment reminder"
+                                );
+                                continue;
+                            }
+                        };
+                        if let Err(e) = billing
+                            .email
+                            .send_trial_payment_method_required(
+                                &owner.email,
+                                &reminder.org_name,
+                                reminder.block_at,
+                            )
+                            .await
+                        {
+                            error!(
+                                org_id = %reminder.org_id,
+                                error = %e,
+                                "Failed to send trial payment method reminder"
+                            );
+                        }
+                    }
+
+                    if enforcement.errors > 0 {
+                        return Err(format!(
+                            "{} trials failed payment method enforcement",
+                            enforcement.errors
+                        ));
+                    }
+                    Ok(())
+                })
+                .await;
+            })
+        })?)
+        .await?;
+    info!("Scheduled: Trial payment method enforcement (hourly at :50)");
 
     // Start the scheduler
     info!("Starting job scheduler");
@@ -497,7 +643,7 @@ async fn main() -> anyhow::Result<()> {
 
     info!(
         "PlexMCP Worker started successfully with {} scheduled jobs",
-        9
+        12
     );
 
     // Keep the main task running
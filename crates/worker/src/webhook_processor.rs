@@ -1,27 +1,31 @@
-use serde_json::Value;
 ///! Webhook Queue Processor
 ///!
 ///! Processes webhooks from the persistent queue with retry logic.
 ///! This replaces the fire-and-forget pattern with reliable processing.
+use plexmcp_shared::webhook_queue::retry_backoff;
+use serde_json::Value;
 use sqlx::PgPool;
-use std::time::Duration;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 /// Process pending webhooks from the queue
+///
+/// Failed webhooks are retried once their backoff elapses; after
+/// `max_attempts` attempts they are marked `dead_letter` and left for review.
 pub async fn process_webhook_queue(
     pool: &PgPool,
     http_client: &reqwest::Client,
     resend_api_key: &str,
     enable_email_routing: bool,
+    max_attempts: i32,
 ) {
-    // Find webhooks to process (pending or failed with retries remaining)
-    let webhooks: Vec<(Uuid, String, Value, i32, i32)> = match sqlx::query_as(
+    // Find webhooks to process (pending, or failed and due for retry)
+    let webhooks: Vec<(Uuid, String, Value, i32)> = match sqlx::query_as(
         r#"
-        SELECT id, webhook_type, payload, attempts, max_attempts
+        SELECT id, webhook_type, payload, attempts
         FROM webhook_processing_queue
-        WHERE (status = 'pending' OR (status = 'failed' AND attempts < max_attempts))
-          AND (last_attempt_at IS NULL OR last_attempt_at < NOW() - INTERVAL '5 minutes')
+        WHERE status = 'pending'
+           OR (status = 'failed' AND (next_retry_at IS NULL OR next_retry_at <= NOW()))
         ORDER BY created_at ASC
         LIMIT 10
         FOR UPDATE SKIP LOCKED
@@ -43,7 +47,7 @@ pub async fn process_webhook_queue(
 
     info!(count = webhooks.len(), "Processing webhooks from queue");
 
-    for (queue_id, webhook_type, payload, attempts, max_attempts) in webhooks {
+    for (queue_id, webhook_type, payload, attempts) in webhooks {
         // Mark as processing
         if let Err(e) = sqlx::query(
             r#"
@@ -97,18 +101,25 @@ pub async fn process_webhook_queue(
                 let error_msg = e.to_string();
                 let new_attempts = attempts + 1;
 
-                // Determine if this is a permanent failure
-                let status = if new_attempts >= max_attempts {
-                    "failed" // Permanent failure
+                // Dead-letter once out of attempts, otherwise back off before retrying
+                let (status, retry_in) = if new_attempts >= max_attempts {
+                    ("dead_letter", None)
                 } else {
-                    "failed" // Will retry
+                    ("failed", Some(retry_backoff(new_attempts)))
                 };
 
                 if let Err(e) = sqlx::query(
-                    "UPDATE webhook_processing_queue SET status = $1, last_error = $2 WHERE id = $3"
+                    r#"
+                    UPDATE webhook_processing_queue
+                    SET status = $1,
+                        last_error = $2,
+                        next_retry_at = NOW() + make_interval(secs => $3)
+                    WHERE id = $4
+                    "#,
                 )
                 .bind(status)
                 .bind(&error_msg)
+                .bind(retry_in.map(|d| d.as_secs_f64()))
                 .bind(queue_id)
                 .execute(pool)
                 .await
@@ -122,7 +133,7 @@ pub async fn process_webhook_queue(
                         webhook_type = %webhook_type,
                         attempts = new_attempts,
                         error = %error_msg,
-                        "Webhook permanently failed after max retries"
+                        "Webhook dead-lettered after max retries"
                     );
                 } else {
                     warn!(
@@ -130,6 +141,7 @@ pub async fn process_webhook_queue(
                         webhook_type = %webhook_type,
                         attempts = new_attempts,
                         max_attempts = max_attempts,
+                        retry_in_secs = retry_in.map_or(0, |d| d.as_secs()),
                         error = %error_msg,
                         "Webhook processing failed, will retry"
                     );
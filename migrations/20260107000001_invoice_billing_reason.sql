@@ -0,0 +1,52 @@
+-- Invoice Billing Reason: Normalize and backfill invoices.billing_reason
+--
+-- billing_reason was written in two different non-Stripe formats: the webhook
+-- handler stored Rust debug names ("SubscriptionCycle") and the manual invoice
+-- sync stored them lowercased ("subscriptioncycle"). Both now write Stripe's
+-- snake_case values, plus our own 'overage_payment' and 'upgrade_payment'.
+--
+-- This migration rewrites existing rows to the canonical values and fills
+-- missing reasons from the billing event ledger where one was recorded.
+
+-- Normalize existing values (both legacy formats)
+UPDATE invoices
+SET billing_reason = CASE LOWER(billing_reason)
+    WHEN 'automaticpendinginvoiceiteminvoice' THEN 'automatic_pending_invoice_item_invoice'
+    WHEN 'manual' THEN 'manual'
+    WHEN 'quoteaccept' THEN 'quote_accept'
+    WHEN 'subscription' THEN 'subscription'
+    WHEN 'subscriptioncreate' THEN 'subscription_create'
+    WHEN 'subscriptioncycle' THEN 'subscription_cycle'
+    WHEN 'subscriptionthreshold' THEN 'subscription_threshold'
+    WHEN 'subscriptionupdate' THEN 'subscription_update'
+    WHEN 'upcoming' THEN 'upcoming'
+    ELSE billing_reason
+END
+WHERE billing_reason IS NOT NULL
+  AND billing_reason NOT LIKE '%\_%';
+
+-- Backfill from billing events that recorded a billing_reason (best effort)
+UPDATE invoices i
+SET billing_reason = CASE LOWER(e.reason)
+    WHEN 'automaticpendinginvoiceiteminvoice' THEN 'automatic_pending_invoice_item_invoice'
+    WHEN 'quoteaccept' THEN 'quote_accept'
+    WHEN 'subscriptioncreate' THEN 'subscription_create'
+    WHEN 'subscriptioncycle' THEN 'subscription_cycle'
+    WHEN 'subscriptionthreshold' THEN 'subscription_threshold'
+    WHEN 'subscriptionupdate' THEN 'subscription_update'
+    ELSE LOWER(e.reason)
+END
+FROM (
+    SELECT DISTINCT ON (stripe_invoice_id)
+        stripe_invoice_id,
+        event_data->>'billing_reason' AS reason
+    FROM billing_events
+    WHERE stripe_invoice_id IS NOT NULL
+      AND event_data->>'billing_reason' IS NOT NULL
+    ORDER BY stripe_invoice_id, created_at DESC
+) e
+WHERE i.stripe_invoice_id = e.stripe_invoice_id
+  AND i.billing_reason IS NULL;
+
+COMMENT ON COLUMN invoices.billing_reason IS
+    'Stripe billing_reason (subscription_cycle, subscription_update, manual, ...) or overage_payment / upgrade_payment for our own checkouts';
@@ -0,0 +1,27 @@
+-- Job Runs: execution history for scheduled worker jobs
+--
+-- Each worker job records a row when it starts and updates it when it
+-- finishes, so ops can see when every job last ran and whether it succeeded.
+
+CREATE TABLE IF NOT EXISTS job_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_name VARCHAR(100) NOT NULL,
+
+    -- 'running', 'success', 'failure'
+    outcome VARCHAR(20) NOT NULL DEFAULT 'running',
+
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    duration_ms BIGINT,
+
+    -- Error summary for failed runs
+    error TEXT
+);
+
+-- Latest runs per job
+CREATE INDEX IF NOT EXISTS idx_job_runs_job_name
+    ON job_runs(job_name, started_at DESC);
+
+-- Retention cleanup
+CREATE INDEX IF NOT EXISTS idx_job_runs_started_at
+    ON job_runs(started_at);
@@ -0,0 +1,38 @@
+-- Org Entitlement Limits: persisted snapshot of each org's effective limits
+--
+-- Effective limits are derived from the org's tier plus any custom overrides.
+-- The snapshot lets SQL-side consumers (reporting, enforcement queries) read
+-- limits without re-deriving them. When a tier's included limits change, the
+-- snapshot must be refreshed with EntitlementService::recompute_all(tier).
+
+CREATE TABLE IF NOT EXISTS org_entitlement_limits (
+    org_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    tier VARCHAR(50) NOT NULL,
+    max_mcps BIGINT NOT NULL,
+    max_api_keys BIGINT NOT NULL,
+    max_team_members BIGINT NOT NULL,
+    max_requests_monthly BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_org_entitlement_limits_tier
+    ON org_entitlement_limits(tier);
+
+ALTER TABLE org_entitlement_limits ENABLE ROW LEVEL SECURITY;
+ALTER TABLE org_entitlement_limits FORCE ROW LEVEL SECURITY;
+
+-- Only the backend maintains the snapshot
+CREATE POLICY org_entitlement_limits_service_only ON org_entitlement_limits
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY org_entitlement_limits_block_users ON org_entitlement_limits
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+GRANT ALL ON org_entitlement_limits TO service_role;
+
+COMMENT ON TABLE org_entitlement_limits IS 'Derived effective limits per org; refreshed after tier plan changes';
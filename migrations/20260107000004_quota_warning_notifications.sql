@@ -0,0 +1,43 @@
+-- Quota Warning Notifications: one soft-limit warning per org per billing period
+--
+-- When an org crosses the soft request limit (QUOTA_SOFT_LIMIT_PERCENT, 90% by
+-- default), the API emits a billing event and emails the owner. The unique key
+-- keeps that to a single notification per period across API instances.
+
+CREATE TABLE IF NOT EXISTS quota_warning_notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    billing_period_start TIMESTAMPTZ NOT NULL,
+
+    -- Soft limit that was crossed, as a percentage of the monthly limit
+    threshold_percent INTEGER NOT NULL,
+
+    -- Usage when the warning fired
+    requests_used BIGINT NOT NULL,
+    requests_limit BIGINT NOT NULL,
+
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE(org_id, billing_period_start, threshold_percent)
+);
+
+CREATE INDEX IF NOT EXISTS idx_quota_warning_notifications_org_period
+    ON quota_warning_notifications(org_id, billing_period_start);
+
+ALTER TABLE quota_warning_notifications ENABLE ROW LEVEL SECURITY;
+ALTER TABLE quota_warning_notifications FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY quota_warning_notifications_service_only ON quota_warning_notifications
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY quota_warning_notifications_block_users ON quota_warning_notifications
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+GRANT ALL ON quota_warning_notifications TO service_role;
+
+COMMENT ON TABLE quota_warning_notifications IS 'Tracks sent soft quota limit warnings (one per org per billing period)';
@@ -0,0 +1,11 @@
+-- Stripe idempotency generation per organization
+--
+-- Mutating Stripe calls from SubscriptionService send a deterministic
+-- idempotency key derived from the org, operation, tier, the stored
+-- subscription version and this counter. Retries after a network failure
+-- reuse the key so Stripe replays the original response. Stripe also stores
+-- rejected results under a key, so the counter is bumped after a rejection to
+-- let the next attempt run for real.
+
+ALTER TABLE organizations
+    ADD COLUMN IF NOT EXISTS stripe_idempotency_generation INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,24 @@
+-- Member Suspension Grace Period
+--
+-- When a downgrade leaves an org with more active members than the new tier
+-- allows, the excess members are no longer suspended immediately. The org gets
+-- MEMBER_SUSPENSION_GRACE_DAYS (7 by default) to remove or reassign members;
+-- the worker suspends whoever is still over the limit once the window ends.
+
+ALTER TABLE organizations
+    ADD COLUMN IF NOT EXISTS member_suspension_due_at TIMESTAMPTZ;
+
+ALTER TABLE organizations
+    ADD COLUMN IF NOT EXISTS member_suspension_reason TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_organizations_member_suspension_due
+    ON organizations(member_suspension_due_at)
+    WHERE member_suspension_due_at IS NOT NULL;
+
+COMMENT ON COLUMN organizations.member_suspension_due_at IS 'When excess members will be suspended after a downgrade (NULL = none pending)';
+COMMENT ON COLUMN organizations.member_suspension_reason IS 'Suspension reason recorded on members once the grace period ends';
+
+-- Rollback:
+-- DROP INDEX IF EXISTS idx_organizations_member_suspension_due;
+-- ALTER TABLE organizations DROP COLUMN IF EXISTS member_suspension_reason;
+-- ALTER TABLE organizations DROP COLUMN IF EXISTS member_suspension_due_at;
@@ -0,0 +1,32 @@
+-- One active overage charge per billing period
+--
+-- Incremental charges after a Pay Now mean a period can have several
+-- overage_charges rows, but only one of them should be unpaid at a time.
+-- Duplicate active rows inflate the Pay Now total. This partial unique index
+-- enforces the invariant going forward.
+--
+-- The index can't be built while duplicates exist. If any are found it is
+-- skipped with a notice: run OverageService::dedupe_period_charges for the
+-- affected orgs, then re-run the CREATE UNIQUE INDEX below by hand.
+
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1
+        FROM overage_charges
+        WHERE status IN ('pending', 'awaiting_payment')
+          AND (paid_early IS NULL OR paid_early = false)
+        GROUP BY org_id, billing_period_start, billing_period_end, resource_type
+        HAVING COUNT(*) > 1
+    ) THEN
+        RAISE NOTICE 'Duplicate active overage charges found; skipping idx_overage_charges_one_active';
+    ELSE
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_overage_charges_one_active
+            ON overage_charges(org_id, billing_period_start, billing_period_end, resource_type)
+            WHERE status IN ('pending', 'awaiting_payment')
+              AND (paid_early IS NULL OR paid_early = false);
+    END IF;
+END $$;
+
+-- Rollback:
+-- DROP INDEX IF EXISTS idx_overage_charges_one_active;
@@ -0,0 +1,12 @@
+-- Per-organization overage mode
+-- 'allow': paid tiers keep serving past their limit and accrue overage charges
+-- 'hard_stop': requests are blocked at 100% of the tier limit and no overage is billed
+-- Independent of the admin-only overages_disabled override; either one blocks
+
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS overage_mode TEXT NOT NULL DEFAULT 'allow';
+
+ALTER TABLE organizations DROP CONSTRAINT IF EXISTS organizations_overage_mode_check;
+ALTER TABLE organizations ADD CONSTRAINT organizations_overage_mode_check
+    CHECK (overage_mode IN ('allow', 'hard_stop'));
+
+COMMENT ON COLUMN organizations.overage_mode IS 'allow = accrue overage past tier limit, hard_stop = block at tier limit. Set by org owners/admins.';
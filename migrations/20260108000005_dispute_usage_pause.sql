@@ -0,0 +1,12 @@
+-- Pause MCP usage while a chargeback is open
+-- Set by the charge.dispute.created webhook, cleared when the dispute is won
+-- and no other dispute for the org is still open. Kept separate from
+-- spend_caps.is_paused so paying overages doesn't lift a dispute pause.
+
+ALTER TABLE organizations ADD COLUMN IF NOT EXISTS dispute_paused_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_organizations_dispute_paused
+ON organizations (dispute_paused_at)
+WHERE dispute_paused_at IS NOT NULL;
+
+COMMENT ON COLUMN organizations.dispute_paused_at IS 'When set, new MCP requests are blocked pending resolution of an open payment dispute.';
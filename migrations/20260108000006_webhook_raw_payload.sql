@@ -0,0 +1,16 @@
+-- Store the raw webhook body and Stripe-Signature header
+-- Lets an admin replay the exact original delivery after fixing a handler bug,
+-- re-verifying its signature instead of trusting a re-fetched copy.
+-- Rows stored before this migration have NULLs and can only be replayed by
+-- re-fetching the event from Stripe.
+
+ALTER TABLE stripe_webhook_events
+ADD COLUMN IF NOT EXISTS raw_payload TEXT;
+
+ALTER TABLE stripe_webhook_events
+ADD COLUMN IF NOT EXISTS signature_header TEXT;
+
+COMMENT ON COLUMN stripe_webhook_events.raw_payload IS
+    'Exact JSON body of the first delivery, kept for signature-verified replays';
+COMMENT ON COLUMN stripe_webhook_events.signature_header IS
+    'Stripe-Signature header of the delivery stored in raw_payload';
@@ -0,0 +1,34 @@
+-- Exponential backoff and dead-lettering for the webhook processing queue
+-- Failed webhooks were retried every minute; they now wait until next_retry_at,
+-- and after the worker's max attempts land in 'dead_letter' for manual review.
+
+ALTER TABLE webhook_processing_queue
+ADD COLUMN IF NOT EXISTS next_retry_at TIMESTAMPTZ;
+
+ALTER TABLE webhook_processing_queue
+DROP CONSTRAINT IF EXISTS webhook_processing_queue_status_check;
+
+ALTER TABLE webhook_processing_queue
+ADD CONSTRAINT webhook_processing_queue_status_check
+CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'dead_letter'));
+
+ALTER TABLE webhook_processing_queue
+ALTER COLUMN max_attempts SET DEFAULT 10;
+
+-- Rows that already exhausted their retries stay stopped
+UPDATE webhook_processing_queue
+SET status = 'dead_letter'
+WHERE status = 'failed' AND attempts >= max_attempts;
+
+-- Remaining failed rows are due immediately
+UPDATE webhook_processing_queue
+SET next_retry_at = NOW()
+WHERE status = 'failed' AND next_retry_at IS NULL;
+
+DROP INDEX IF EXISTS idx_webhook_queue_retry;
+
+CREATE INDEX IF NOT EXISTS idx_webhook_queue_retry ON webhook_processing_queue(next_retry_at)
+WHERE status = 'failed';
+
+COMMENT ON COLUMN webhook_processing_queue.status IS 'Processing status: pending (not started), processing (in progress), completed (success), failed (awaiting retry), dead_letter (retries exhausted, needs manual review)';
+COMMENT ON COLUMN webhook_processing_queue.next_retry_at IS 'Earliest time a failed webhook is retried (exponential backoff, capped at 6 hours)';
@@ -0,0 +1,21 @@
+-- Retry tracking for Stripe webhook events
+-- A failed event is reprocessed on Stripe's redelivery once next_retry_at
+-- passes, using the webhook queue's backoff (see plexmcp_shared::webhook_queue).
+-- Stripe stops redelivering on its own; events still failing after that are
+-- replayed by an operator.
+
+ALTER TABLE stripe_webhook_events
+ADD COLUMN IF NOT EXISTS attempt_count INT NOT NULL DEFAULT 0;
+
+ALTER TABLE stripe_webhook_events
+ADD COLUMN IF NOT EXISTS next_retry_at TIMESTAMPTZ;
+
+-- Every existing row was attempted once
+UPDATE stripe_webhook_events
+SET attempt_count = 1
+WHERE attempt_count = 0;
+
+COMMENT ON COLUMN stripe_webhook_events.attempt_count IS
+    'Processing attempts since the event was first received';
+COMMENT ON COLUMN stripe_webhook_events.next_retry_at IS
+    'Earliest time a failed event may be processed again (exponential backoff, capped at 6 hours)';
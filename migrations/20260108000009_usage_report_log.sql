@@ -0,0 +1,31 @@
+-- Idempotency log for metered usage reports
+-- A row is claimed before each report to Stripe, so two overlapping report
+-- runs (e.g. the 6-hourly job and the end-of-day job near midnight) cannot
+-- send the same cumulative quantity for a billing period twice.
+
+CREATE TABLE IF NOT EXISTS usage_report_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    period_start TIMESTAMPTZ NOT NULL,
+    reported_quantity_hash VARCHAR(64) NOT NULL,
+    overage_units BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (org_id, period_start, reported_quantity_hash)
+);
+
+ALTER TABLE usage_report_log ENABLE ROW LEVEL SECURITY;
+ALTER TABLE usage_report_log FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY usage_report_log_service_only ON usage_report_log
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY usage_report_log_block_users ON usage_report_log
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON COLUMN usage_report_log.reported_quantity_hash IS
+    'SHA-256 of the metered item ID and the cumulative overage units reported for the period';
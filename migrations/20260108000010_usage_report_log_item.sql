@@ -0,0 +1,10 @@
+-- Track usage reports per subscription item
+-- The quantity hash already covers the item; storing the item ID makes the
+-- ledger queryable per item so a partially reported subscription shows which
+-- items are still outstanding.
+
+ALTER TABLE usage_report_log
+ADD COLUMN IF NOT EXISTS metered_item_id VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_usage_report_log_item
+    ON usage_report_log(metered_item_id, period_start);
@@ -0,0 +1,19 @@
+-- Payment method enforcement for trials granted without one
+-- admin_change_tier flags such trials; the worker reminds the owner and then
+-- blocks the org (billing_block_reason 'Trial requires a payment method')
+-- until a payment method is added.
+
+ALTER TABLE subscriptions
+ADD COLUMN IF NOT EXISTS trial_payment_required_since TIMESTAMPTZ;
+
+ALTER TABLE subscriptions
+ADD COLUMN IF NOT EXISTS trial_payment_reminded_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_subscriptions_trial_payment_required
+    ON subscriptions(trial_payment_required_since)
+    WHERE trial_payment_required_since IS NOT NULL;
+
+COMMENT ON COLUMN subscriptions.trial_payment_required_since IS
+    'When a trial was granted without a payment method; cleared once one is added';
+COMMENT ON COLUMN subscriptions.trial_payment_reminded_at IS
+    'When the owner was reminded to add a payment method for the trial';
@@ -0,0 +1,11 @@
+-- Spend caps expressed as a percentage of the plan's monthly base price
+-- cap_amount_cents keeps the effective cap; SpendCapService recomputes it from
+-- the current base price when the percentage is set, so the cap follows tier
+-- changes. NULL means the cap is an absolute amount.
+
+ALTER TABLE spend_caps
+ADD COLUMN IF NOT EXISTS cap_percent_of_base SMALLINT
+    CHECK (cap_percent_of_base IS NULL OR cap_percent_of_base > 0);
+
+COMMENT ON COLUMN spend_caps.cap_percent_of_base IS
+    'Cap as a percentage of the monthly base price; NULL for an absolute cap';
@@ -0,0 +1,36 @@
+-- Per-member spend sub-caps within an org
+-- SpendCapService attributes the org's overage spend to members by their
+-- share of the period's requests (through the API keys they created). A
+-- member at or over their cap is suspended with reason 'member_spend_cap'
+-- and restored once their attributed spend drops back under it.
+
+CREATE TABLE IF NOT EXISTS member_spend_caps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    cap_amount_cents INTEGER NOT NULL CHECK (cap_amount_cents > 0),
+    current_period_spend_cents INTEGER NOT NULL DEFAULT 0,
+    exceeded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (org_id, user_id)
+);
+
+ALTER TABLE member_spend_caps ENABLE ROW LEVEL SECURITY;
+ALTER TABLE member_spend_caps FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY member_spend_caps_service_only ON member_spend_caps
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY member_spend_caps_block_users ON member_spend_caps
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON COLUMN member_spend_caps.current_period_spend_cents IS
+    'Share of the org''s counted overage spend attributed to this member';
+COMMENT ON COLUMN member_spend_caps.exceeded_at IS
+    'When the member reached the cap and was suspended; NULL while under it';
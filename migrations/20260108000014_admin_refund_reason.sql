@@ -0,0 +1,15 @@
+-- Stripe-native reason code for admin refunds
+-- `reason` keeps the admin's free-text note. Credits are not Stripe refunds and
+-- leave refund_reason NULL. Refunds issued before this column existed were all
+-- sent to Stripe as requested_by_customer.
+
+ALTER TABLE admin_refunds
+ADD COLUMN IF NOT EXISTS refund_reason VARCHAR(30)
+    CHECK (refund_reason IS NULL OR refund_reason IN ('duplicate', 'fraudulent', 'requested_by_customer'));
+
+UPDATE admin_refunds
+SET refund_reason = 'requested_by_customer'
+WHERE refund_type = 'refund' AND refund_reason IS NULL;
+
+COMMENT ON COLUMN admin_refunds.refund_reason IS
+    'Reason code sent to Stripe (duplicate, fraudulent, requested_by_customer); NULL for credits';
@@ -0,0 +1,36 @@
+-- Per-org feature grants independent of tier
+-- EntitlementService unions an org's tier features with its unexpired rows
+-- here (e.g. a beta feature such as 'advanced_routing'). A grant with an
+-- expires_at lapses on its own; revoking deletes the row.
+
+CREATE TABLE IF NOT EXISTS entitlement_overrides (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    feature VARCHAR(64) NOT NULL,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (org_id, feature)
+);
+
+CREATE INDEX IF NOT EXISTS idx_entitlement_overrides_org_id
+    ON entitlement_overrides(org_id);
+
+ALTER TABLE entitlement_overrides ENABLE ROW LEVEL SECURITY;
+ALTER TABLE entitlement_overrides FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY entitlement_overrides_service_only ON entitlement_overrides
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY entitlement_overrides_block_users ON entitlement_overrides
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON COLUMN entitlement_overrides.feature IS
+    'Feature name as used by Entitlement::has_feature, e.g. sso or advanced_routing';
+COMMENT ON COLUMN entitlement_overrides.expires_at IS
+    'When a temporary grant lapses; NULL for a permanent grant';
@@ -0,0 +1,10 @@
+-- First-month-free subscriptions
+-- A 100%-off-once coupon applied at checkout instead of a trial: the customer
+-- is a paying subscriber from day one with a zero first invoice, so trial_start
+-- and trial_end stay NULL. The flag also stops an org redeeming the offer twice.
+
+ALTER TABLE subscriptions
+ADD COLUMN IF NOT EXISTS first_month_free BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN subscriptions.first_month_free IS
+    'First invoice was zeroed by the first-month-free coupon (not a trial)';
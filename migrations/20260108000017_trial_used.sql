@@ -0,0 +1,17 @@
+-- Free trial usage per organization
+-- The subscriptions row is overwritten by each new Stripe subscription, so
+-- trial_start alone loses the history once an org resubscribes. trial_used_at
+-- records the first trial and is never cleared.
+
+ALTER TABLE organizations
+ADD COLUMN IF NOT EXISTS trial_used_at TIMESTAMPTZ;
+
+UPDATE organizations o
+SET trial_used_at = s.trial_start
+FROM subscriptions s
+WHERE s.org_id = o.id
+  AND s.trial_start IS NOT NULL
+  AND o.trial_used_at IS NULL;
+
+COMMENT ON COLUMN organizations.trial_used_at IS
+    'When the organization first started a trial; set once, blocks further trials';
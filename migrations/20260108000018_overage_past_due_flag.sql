@@ -0,0 +1,9 @@
+-- Flag overage accrued while the subscription was past due
+-- Set by the overage calculation under the accumulate and collect policies
+-- (OVERAGE_PAST_DUE_POLICY) so these charges can be reviewed before billing.
+
+ALTER TABLE overage_charges
+ADD COLUMN IF NOT EXISTS accrued_while_past_due BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN overage_charges.accrued_while_past_due IS
+    'Overage accrued while the subscription was past_due; flagged for review';
@@ -0,0 +1,31 @@
+-- One-time billing portal links
+-- PortalService signs short-lived /billing/portal?token=... links. Redeeming a
+-- link records its nonce here, so a copied or replayed URL is rejected. Rows
+-- are only needed until the link expires and are pruned on redemption.
+
+CREATE TABLE IF NOT EXISTS portal_link_redemptions (
+    nonce UUID PRIMARY KEY,
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    redeemed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_portal_link_redemptions_expires_at
+    ON portal_link_redemptions(expires_at);
+
+ALTER TABLE portal_link_redemptions ENABLE ROW LEVEL SECURITY;
+ALTER TABLE portal_link_redemptions FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY portal_link_redemptions_service_only ON portal_link_redemptions
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY portal_link_redemptions_block_users ON portal_link_redemptions
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON COLUMN portal_link_redemptions.nonce IS
+    'Nonce of a redeemed portal link; a second redemption is rejected';
@@ -0,0 +1,16 @@
+-- Per-org instant charge thresholds
+-- InstantChargeService resolves the threshold as: org override, then the
+-- tier's INSTANT_CHARGE_THRESHOLD_CENTS_<TIER>, then the global default.
+-- Each charge records the threshold in effect so history stays explainable.
+
+ALTER TABLE organizations
+ADD COLUMN IF NOT EXISTS instant_charge_threshold_cents INTEGER
+    CHECK (instant_charge_threshold_cents IS NULL OR instant_charge_threshold_cents > 0);
+
+ALTER TABLE instant_charges
+ADD COLUMN IF NOT EXISTS threshold_cents INTEGER;
+
+COMMENT ON COLUMN organizations.instant_charge_threshold_cents IS
+    'Overage (cents) that triggers an instant charge for this org; NULL uses the tier default';
+COMMENT ON COLUMN instant_charges.threshold_cents IS
+    'Instant charge threshold in effect when the charge was created; NULL for older charges';
@@ -0,0 +1,32 @@
+-- Shared rate limit buckets
+-- PostgresRateLimiter keeps one fixed one-minute window per key here so every
+-- API instance counts against the same limit and counts survive restarts.
+-- Each check is a single upsert; stale windows are pruned periodically.
+
+CREATE TABLE IF NOT EXISTS rate_limit_buckets (
+    key TEXT PRIMARY KEY,
+    window_start BIGINT NOT NULL,
+    count INTEGER NOT NULL CHECK (count >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_buckets_window_start
+    ON rate_limit_buckets(window_start);
+
+ALTER TABLE rate_limit_buckets ENABLE ROW LEVEL SECURITY;
+ALTER TABLE rate_limit_buckets FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY rate_limit_buckets_service_only ON rate_limit_buckets
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY rate_limit_buckets_block_users ON rate_limit_buckets
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON COLUMN rate_limit_buckets.window_start IS
+    'Unix timestamp (seconds, database clock) of the current one-minute window';
+COMMENT ON COLUMN rate_limit_buckets.count IS
+    'Requests allowed in the current window; never exceeds the configured limit';
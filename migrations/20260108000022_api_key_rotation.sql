@@ -0,0 +1,27 @@
+-- API key rotation grace window
+-- Rotating a key moves its old hash into previous_key_hash, which keeps
+-- authenticating until previous_key_expires_at so integrations can swap
+-- secrets without downtime. Also allows key creation/rotation in the auth audit log.
+
+ALTER TABLE api_keys
+    ADD COLUMN IF NOT EXISTS previous_key_hash TEXT,
+    ADD COLUMN IF NOT EXISTS previous_key_expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_api_keys_previous_key_hash
+    ON api_keys(previous_key_hash)
+    WHERE previous_key_hash IS NOT NULL;
+
+COMMENT ON COLUMN api_keys.previous_key_hash IS
+    'Hash of the secret replaced by the last rotation; accepted until previous_key_expires_at';
+COMMENT ON COLUMN api_keys.previous_key_expires_at IS
+    'End of the rotation grace window for previous_key_hash';
+
+ALTER TABLE auth_audit_log DROP CONSTRAINT IF EXISTS auth_audit_log_event_type_check;
+ALTER TABLE auth_audit_log ADD CONSTRAINT auth_audit_log_event_type_check CHECK (event_type IN (
+    'login_success', 'login_failed', 'logout',
+    'password_changed', 'password_reset_requested', 'password_reset_completed',
+    '2fa_enabled', '2fa_disabled', '2fa_verified', '2fa_failed',
+    'oauth_login', 'oauth_linked', 'oauth_unlinked',
+    'session_expired', 'account_locked', 'account_unlocked',
+    'api_key_created', 'api_key_rotated'
+));
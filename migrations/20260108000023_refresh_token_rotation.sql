@@ -0,0 +1,63 @@
+-- Refresh token rotation with reuse detection
+-- Every refresh token descended from the same login shares a family_id.
+-- Exchanged refresh tokens are recorded in refresh_token_uses; presenting one
+-- again revokes every session in its family.
+
+ALTER TABLE user_sessions
+    ADD COLUMN IF NOT EXISTS family_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_user_sessions_family_id
+    ON user_sessions(family_id)
+    WHERE revoked_at IS NULL;
+
+COMMENT ON COLUMN user_sessions.family_id IS
+    'Refresh token family (one per login); NULL for sessions created before rotation';
+
+CREATE TABLE IF NOT EXISTS refresh_token_uses (
+    jti TEXT PRIMARY KEY,
+    family_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    used_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_refresh_token_uses_expires_at
+    ON refresh_token_uses(expires_at);
+
+ALTER TABLE refresh_token_uses ENABLE ROW LEVEL SECURITY;
+ALTER TABLE refresh_token_uses FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY refresh_token_uses_service_only ON refresh_token_uses
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY refresh_token_uses_block_users ON refresh_token_uses
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON TABLE refresh_token_uses IS
+    'Refresh token JTIs that have been exchanged; a second exchange means the token leaked';
+COMMENT ON COLUMN refresh_token_uses.expires_at IS
+    'When the refresh token itself expires; the row is useless after that';
+
+-- Prune used refresh tokens together with expired sessions
+CREATE OR REPLACE FUNCTION cleanup_expired_sessions()
+RETURNS INTEGER AS $$
+DECLARE
+    deleted_count INTEGER;
+BEGIN
+    -- Delete sessions expired more than 30 days ago
+    DELETE FROM user_sessions
+    WHERE expires_at < NOW() - INTERVAL '30 days';
+
+    GET DIAGNOSTICS deleted_count = ROW_COUNT;
+
+    DELETE FROM refresh_token_uses
+    WHERE expires_at < NOW();
+
+    RETURN deleted_count;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER;
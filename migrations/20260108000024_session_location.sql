@@ -0,0 +1,13 @@
+-- Session location for the account security page
+-- Approximate location resolved from the session's IP via GeoIP when the
+-- session is created. NULL when the GeoIP database isn't loaded or the IP
+-- is private.
+
+ALTER TABLE user_sessions
+    ADD COLUMN IF NOT EXISTS city TEXT,
+    ADD COLUMN IF NOT EXISTS country_code TEXT;
+
+COMMENT ON COLUMN user_sessions.city IS
+    'Approximate city of ip_address at session creation (GeoIP)';
+COMMENT ON COLUMN user_sessions.country_code IS
+    'ISO 3166-1 alpha-2 country of ip_address at session creation (GeoIP)';
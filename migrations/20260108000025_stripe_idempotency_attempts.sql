@@ -0,0 +1,39 @@
+-- Stable Stripe idempotency attempts
+-- Mutating Stripe calls from SubscriptionService key on the org, operation,
+-- tier and an attempt id stored here before the call is sent. A retry after a
+-- network failure finds the same row and reuses the key, even if a webhook
+-- has changed the subscription row in the meantime. The row is removed once
+-- Stripe has answered (success or rejection), so the next deliberate call
+-- starts a new attempt.
+--
+-- Replaces organizations.stripe_idempotency_generation, whose key also covered
+-- subscriptions.updated_at and changed under a webhook.
+
+CREATE TABLE IF NOT EXISTS stripe_idempotency_attempts (
+    org_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    operation TEXT NOT NULL,
+    tier TEXT NOT NULL,
+    attempt_id UUID NOT NULL DEFAULT gen_random_uuid(),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (org_id, operation, tier)
+);
+
+ALTER TABLE stripe_idempotency_attempts ENABLE ROW LEVEL SECURITY;
+ALTER TABLE stripe_idempotency_attempts FORCE ROW LEVEL SECURITY;
+
+CREATE POLICY stripe_idempotency_attempts_service_only ON stripe_idempotency_attempts
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+CREATE POLICY stripe_idempotency_attempts_block_users ON stripe_idempotency_attempts
+    FOR ALL
+    TO authenticated
+    USING (false);
+
+COMMENT ON TABLE stripe_idempotency_attempts IS
+    'Stripe mutations sent without a confirmed outcome; retries reuse attempt_id in the idempotency key';
+
+ALTER TABLE organizations
+    DROP COLUMN IF EXISTS stripe_idempotency_generation;
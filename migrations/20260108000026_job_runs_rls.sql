@@ -0,0 +1,19 @@
+-- Row level security for job_runs
+-- Job history is only read and written by the worker and admin API through
+-- the service role; users have no access.
+
+ALTER TABLE job_runs ENABLE ROW LEVEL SECURITY;
+ALTER TABLE job_runs FORCE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS job_runs_service_only ON job_runs;
+CREATE POLICY job_runs_service_only ON job_runs
+    FOR ALL
+    TO postgres, service_role
+    USING (true)
+    WITH CHECK (true);
+
+DROP POLICY IF EXISTS job_runs_block_users ON job_runs;
+CREATE POLICY job_runs_block_users ON job_runs
+    FOR ALL
+    TO authenticated
+    USING (false);
@@ -0,0 +1,7 @@
+-- Drop org_entitlement_limits
+-- Effective limits are derived from the org's tier and custom limits each
+-- time an entitlement is computed, so a tier plan change applies to every
+-- org immediately. Nothing read the persisted snapshot, and keeping it in
+-- sync with custom limit edits would need a write at every change site.
+
+DROP TABLE IF EXISTS org_entitlement_limits;